@@ -0,0 +1,209 @@
+use serde::{Deserialize, Serialize};
+use utoipa::ToSchema;
+
+use super::ColorCalibration;
+
+/// 白平衡校准向导的步骤，依次显示纯红、纯绿、纯蓝和纯白
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize, ToSchema)]
+pub enum CalibrationStep {
+    Red,
+    Green,
+    Blue,
+    White,
+}
+
+impl CalibrationStep {
+    /// 该步骤发送到灯带的颜色
+    pub fn color(self) -> [u8; 3] {
+        match self {
+            CalibrationStep::Red => [255, 0, 0],
+            CalibrationStep::Green => [0, 255, 0],
+            CalibrationStep::Blue => [0, 0, 255],
+            CalibrationStep::White => [255, 255, 255],
+        }
+    }
+
+    fn next(self) -> Option<Self> {
+        match self {
+            CalibrationStep::Red => Some(CalibrationStep::Green),
+            CalibrationStep::Green => Some(CalibrationStep::Blue),
+            CalibrationStep::Blue => Some(CalibrationStep::White),
+            CalibrationStep::White => None,
+        }
+    }
+}
+
+/// 白平衡校准向导
+///
+/// 每一步由用户报告看到的颜色，全部完成后根据报告计算建议的RGB校准系数
+#[derive(Debug, Clone)]
+pub struct CalibrationWizard {
+    /// 向导开始时的校准，校准颜色发送时已应用该校准
+    base: ColorCalibration,
+    current: Option<CalibrationStep>,
+    /// 各步骤报告的感知颜色，顺序为红、绿、蓝、白
+    reports: Vec<[u8; 3]>,
+}
+
+impl CalibrationWizard {
+    pub fn new(base: ColorCalibration) -> Self {
+        Self {
+            base,
+            current: Some(CalibrationStep::Red),
+            reports: Vec::with_capacity(4),
+        }
+    }
+
+    /// 当前等待用户报告的步骤，完成后为 None
+    pub fn current_step(&self) -> Option<CalibrationStep> {
+        self.current
+    }
+
+    /// 记录当前步骤的感知颜色并进入下一步，返回下一步（完成时为 None）
+    ///
+    /// 最后一步的报告无法得出建议校准时拒绝该报告，向导停留在当前步骤，可以重新报告
+    pub fn report(&mut self, perceived: [u8; 3]) -> anyhow::Result<Option<CalibrationStep>> {
+        let step = self
+            .current
+            .ok_or_else(|| anyhow::anyhow!("Calibration wizard already finished"))?;
+
+        self.reports.push(perceived);
+        let next = step.next();
+        if next.is_none() && self.calibration_from_reports().is_none() {
+            self.reports.pop();
+            anyhow::bail!("Reported colors do not allow a calibration suggestion");
+        }
+        self.current = next;
+        Ok(next)
+    }
+
+    /// 根据报告计算建议的颜色校准，向导未完成时返回 None
+    pub fn suggested_calibration(&self) -> Option<ColorCalibration> {
+        if self.current.is_some() {
+            return None;
+        }
+        self.calibration_from_reports()
+    }
+
+    /// 每个通道的强度取该原色报告的对应分量与白色报告的对应分量的平均值，
+    /// 偏强的通道按最弱通道的强度衰减（系数不超过 1），其余校准项保持不变。
+    fn calibration_from_reports(&self) -> Option<ColorCalibration> {
+        if self.reports.len() != 4 {
+            return None;
+        }
+
+        let white = self.reports[3];
+        let strengths: [f32; 3] = std::array::from_fn(|channel| {
+            let primary = self.reports[channel][channel] as f32;
+            (primary + white[channel] as f32) / 2.0
+        });
+
+        let weakest = strengths.iter().copied().fold(f32::MAX, f32::min);
+        if weakest <= 0.0 {
+            return None;
+        }
+
+        let current = [self.base.r, self.base.g, self.base.b];
+        let mut suggested: [f32; 3] =
+            std::array::from_fn(|channel| current[channel] * weakest / strengths[channel]);
+
+        // 归一化，使最强的系数为 1，避免整体亮度下降
+        let max = suggested.iter().copied().fold(0.0, f32::max);
+        if max > 0.0 {
+            suggested = suggested.map(|value| (value / max).clamp(0.0, 1.0));
+        }
+
+        Some(ColorCalibration {
+            r: suggested[0],
+            g: suggested[1],
+            b: suggested[2],
+            ..self.base
+        })
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn run_wizard(base: ColorCalibration, reports: [[u8; 3]; 4]) -> CalibrationWizard {
+        let mut wizard = CalibrationWizard::new(base);
+        for report in reports {
+            wizard.report(report).unwrap();
+        }
+        wizard
+    }
+
+    #[test]
+    fn test_wizard_steps_in_order() {
+        let mut wizard = CalibrationWizard::new(ColorCalibration::new());
+        assert_eq!(wizard.current_step(), Some(CalibrationStep::Red));
+        assert_eq!(
+            wizard.report([255, 0, 0]).unwrap(),
+            Some(CalibrationStep::Green)
+        );
+        assert_eq!(
+            wizard.report([0, 255, 0]).unwrap(),
+            Some(CalibrationStep::Blue)
+        );
+        assert!(wizard.suggested_calibration().is_none());
+        assert_eq!(
+            wizard.report([0, 0, 255]).unwrap(),
+            Some(CalibrationStep::White)
+        );
+        assert_eq!(wizard.report([255, 255, 255]).unwrap(), None);
+        assert!(wizard.report([255, 255, 255]).is_err());
+
+        let calibration = wizard.suggested_calibration().unwrap();
+        assert_eq!(
+            [calibration.r, calibration.g, calibration.b],
+            [1.0, 1.0, 1.0]
+        );
+    }
+
+    #[test]
+    fn test_suggestion_attenuates_strong_channels() {
+        // 白色偏蓝，蓝色也显得偏亮
+        let wizard = run_wizard(
+            ColorCalibration::new(),
+            [[200, 0, 0], [0, 200, 0], [0, 0, 255], [200, 200, 255]],
+        );
+        let calibration = wizard.suggested_calibration().unwrap();
+
+        assert_eq!(calibration.r, 1.0);
+        assert_eq!(calibration.g, 1.0);
+        assert!((calibration.b - 200.0 / 255.0).abs() < 1e-4);
+        assert_eq!(calibration.w, 1.0);
+    }
+
+    #[test]
+    fn test_suggestion_builds_on_current_calibration() {
+        let base = ColorCalibration {
+            g: 0.5,
+            ..ColorCalibration::new()
+        };
+        // 报告的颜色已经平衡，建议值保持当前校准
+        let wizard = run_wizard(
+            base,
+            [[255, 0, 0], [0, 255, 0], [0, 0, 255], [255, 255, 255]],
+        );
+        let calibration = wizard.suggested_calibration().unwrap();
+        assert_eq!(
+            [calibration.r, calibration.g, calibration.b],
+            [1.0, 0.5, 1.0]
+        );
+
+        // 某个原色完全看不到时无法给出建议，最后一步的报告被拒绝，向导停留在白色步骤
+        let mut wizard = CalibrationWizard::new(base);
+        for report in [[0, 0, 0], [0, 255, 0], [0, 0, 255]] {
+            wizard.report(report).unwrap();
+        }
+        assert!(wizard.report([0, 255, 255]).is_err());
+        assert_eq!(wizard.current_step(), Some(CalibrationStep::White));
+        assert!(wizard.suggested_calibration().is_none());
+
+        // 重新报告可用的白色后完成
+        assert_eq!(wizard.report([255, 255, 255]).unwrap(), None);
+        assert!(wizard.suggested_calibration().is_some());
+    }
+}
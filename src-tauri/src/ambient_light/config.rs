@@ -1,3 +1,4 @@
+use std::collections::HashMap;
 use std::env::current_dir;
 
 use serde::{Deserialize, Serialize};
@@ -19,6 +20,215 @@ pub enum LedType {
     #[default]
     WS2812B,
     SK6812,
+    /// 3字节RGB灯带，硬件颜色字节固定按RGB排列
+    WS2811,
+}
+
+impl LedType {
+    /// 每个LED在硬件数据中占用的字节数
+    pub fn bytes_per_led(&self) -> usize {
+        match self {
+            LedType::WS2812B | LedType::WS2811 => 3,
+            LedType::SK6812 => 4,
+        }
+    }
+
+    /// 该类型实际使用的颜色顺序：WS2811 固定为RGB，其余类型使用灯带配置的顺序
+    pub fn effective_color_order(&self, configured: ColorOrder) -> ColorOrder {
+        match self {
+            LedType::WS2811 => ColorOrder {
+                rgb: RgbOrder::Rgb,
+                ..configured
+            },
+            LedType::WS2812B | LedType::SK6812 => configured,
+        }
+    }
+}
+
+/// 单个LED覆盖的多个采样区域合并为一个颜色的方式
+#[derive(Clone, Copy, Serialize, Deserialize, Debug, PartialEq, Eq, Default)]
+pub enum ZoneReduction {
+    /// 取所有采样点的平均颜色
+    #[default]
+    Mean,
+    /// 取感知亮度最高的采样点颜色，适合快速运动的画面；所有采样点都低于黑电平时为黑色
+    Max,
+    /// 取出现次数最多的颜色（量化后统计）
+    Dominant,
+}
+
+/// 每个LED采样区域内采样点的排列形状
+#[derive(Clone, Copy, Serialize, Deserialize, Debug, PartialEq, Eq, Default)]
+pub enum SampleShape {
+    /// 沿边缘方向和厚度方向排成正方形网格
+    #[default]
+    Grid,
+    /// 在边缘厚度中线上沿边缘方向排成一行，适合较薄的采样边缘
+    Line,
+    /// 靠近拐角的首尾LED额外沿厚度方向采样形成L形，其余LED与 `Line` 相同
+    LShape,
+}
+
+/// 显示器灯带的走线方向（从屏幕正面看）
+///
+/// 逆时针时该显示器所有灯带的逻辑顺序及其串联顺序整体翻转后再编码输出
+#[derive(Clone, Copy, Serialize, Deserialize, Debug, PartialEq, Eq, Default, utoipa::ToSchema)]
+pub enum DisplayWinding {
+    #[default]
+    Clockwise,
+    CounterClockwise,
+}
+
+/// 显示器启用的边，禁用的边上的灯带既不采样也不占用串联数据中的位置
+///
+/// 适用于只在部分边上安装了灯带的显示器（例如被桌面挡住的底边）
+#[derive(Clone, Copy, Serialize, Deserialize, Debug, PartialEq, Eq, utoipa::ToSchema)]
+pub struct BorderMask {
+    pub top: bool,
+    pub bottom: bool,
+    pub left: bool,
+    pub right: bool,
+}
+
+impl Default for BorderMask {
+    fn default() -> Self {
+        Self {
+            top: true,
+            bottom: true,
+            left: true,
+            right: true,
+        }
+    }
+}
+
+impl BorderMask {
+    /// 判断指定的边是否启用
+    pub fn contains(self, border: Border) -> bool {
+        match border {
+            Border::Top => self.top,
+            Border::Bottom => self.bottom,
+            Border::Left => self.left,
+            Border::Right => self.right,
+        }
+    }
+}
+
+/// 截图位图的像素字节顺序
+///
+/// 大多数截图接口输出 BGRA，部分平台或采集方式输出 RGBA，设置错误时红蓝通道互换
+#[derive(Clone, Copy, Serialize, Deserialize, Debug, PartialEq, Eq, Default, utoipa::ToSchema)]
+pub enum CaptureFormat {
+    #[default]
+    Bgra,
+    Rgba,
+}
+
+impl CaptureFormat {
+    /// 读取一个像素（至少3字节）的 `[r, g, b]`
+    pub fn rgb(self, pixel: &[u8]) -> [u8; 3] {
+        match self {
+            CaptureFormat::Bgra => [pixel[2], pixel[1], pixel[0]],
+            CaptureFormat::Rgba => [pixel[0], pixel[1], pixel[2]],
+        }
+    }
+}
+
+/// RGB三个通道在硬件数据中的排列顺序
+#[derive(Clone, Copy, Serialize, Deserialize, Debug, PartialEq, Eq, Default)]
+pub enum RgbOrder {
+    Rgb,
+    Rbg,
+    #[default]
+    Grb,
+    Gbr,
+    Brg,
+    Bgr,
+}
+
+impl RgbOrder {
+    /// 各输出位置对应的RGB通道下标
+    fn channel_indices(self) -> [usize; 3] {
+        match self {
+            RgbOrder::Rgb => [0, 1, 2],
+            RgbOrder::Rbg => [0, 2, 1],
+            RgbOrder::Grb => [1, 0, 2],
+            RgbOrder::Gbr => [1, 2, 0],
+            RgbOrder::Brg => [2, 0, 1],
+            RgbOrder::Bgr => [2, 1, 0],
+        }
+    }
+
+    /// 将RGB颜色按该顺序排列
+    pub fn arrange(self, rgb: [u8; 3]) -> [u8; 3] {
+        self.channel_indices().map(|channel| rgb[channel])
+    }
+
+    /// 将按该顺序排列的字节还原为RGB
+    pub fn restore(self, bytes: [u8; 3]) -> [u8; 3] {
+        let mut rgb = [0; 3];
+        for (byte, channel) in bytes.into_iter().zip(self.channel_indices()) {
+            rgb[channel] = byte;
+        }
+        rgb
+    }
+}
+
+/// RGBW灯带中白色通道字节的位置
+#[derive(Clone, Copy, Serialize, Deserialize, Debug, PartialEq, Eq, Default)]
+pub enum WhitePosition {
+    /// 白色通道在RGB之前，例如 WRGB
+    Leading,
+    /// 白色通道在RGB之后，例如 GRBW
+    #[default]
+    Trailing,
+}
+
+/// 灯带的颜色字节顺序，RGB子顺序与白色通道位置相互独立
+#[derive(Clone, Copy, Serialize, Deserialize, Debug, PartialEq, Eq, Default)]
+pub struct ColorOrder {
+    #[serde(default)]
+    pub rgb: RgbOrder,
+    /// 仅对 SK6812 等RGBW灯带生效
+    #[serde(default)]
+    pub white: WhitePosition,
+}
+
+impl ColorOrder {
+    /// 按颜色顺序编码单个LED并追加到缓冲区
+    ///
+    /// `white` 为 None 时只写入三个RGB字节（WS2812B）
+    pub fn encode_into(self, rgb: [u8; 3], white: Option<u8>, buffer: &mut Vec<u8>) {
+        let arranged = self.rgb.arrange(rgb);
+        match (white, self.white) {
+            (None, _) => buffer.extend_from_slice(&arranged),
+            (Some(w), WhitePosition::Leading) => {
+                buffer.push(w);
+                buffer.extend_from_slice(&arranged);
+            }
+            (Some(w), WhitePosition::Trailing) => {
+                buffer.extend_from_slice(&arranged);
+                buffer.push(w);
+            }
+        }
+    }
+
+    /// 从单个LED的硬件字节中解码出RGB和白色通道（RGB灯带的白色通道为 None）
+    pub fn decode(self, bytes: &[u8], led_type: LedType) -> ([u8; 3], Option<u8>) {
+        let order = led_type.effective_color_order(self);
+        match (led_type, order.white) {
+            (LedType::WS2812B | LedType::WS2811, _) => {
+                (order.rgb.restore([bytes[0], bytes[1], bytes[2]]), None)
+            }
+            (LedType::SK6812, WhitePosition::Leading) => (
+                order.rgb.restore([bytes[1], bytes[2], bytes[3]]),
+                Some(bytes[0]),
+            ),
+            (LedType::SK6812, WhitePosition::Trailing) => (
+                order.rgb.restore([bytes[0], bytes[1], bytes[2]]),
+                Some(bytes[3]),
+            ),
+        }
+    }
 }
 
 #[derive(Clone, Copy, Serialize, Deserialize, Debug)]
@@ -31,20 +241,88 @@ pub struct LedStripConfig {
     pub led_type: LedType,
     #[serde(default)]
     pub reversed: bool,
+    /// 硬件起始偏移量（以该灯带的LED为单位），为空时紧接上一条灯带
+    #[serde(default)]
+    pub hardware_offset: Option<usize>,
+    /// 多个采样区域合并为LED颜色的方式
+    #[serde(default)]
+    pub zone_reduction: ZoneReduction,
+    /// 硬件数据的颜色字节顺序
+    #[serde(default)]
+    pub color_order: ColorOrder,
+    /// 采样点的排列形状
+    #[serde(default)]
+    pub sample_shape: SampleShape,
+    /// 采样区域距物理屏幕边缘的像素数（与采样深度无关），用于避开贴边的菜单栏、程序坞
+    #[serde(default)]
+    pub sample_inset: usize,
+    /// 边缘起始端（上/下边为左端，左/右边为上端）不被灯带覆盖的百分比，用于没有铺满整条边的灯带
+    #[serde(default)]
+    pub start_trim_percent: f32,
+    /// 边缘末端（上/下边为右端，左/右边为下端）不被灯带覆盖的百分比
+    #[serde(default)]
+    pub end_trim_percent: f32,
+    /// 是否启用；禁用的灯带输出黑色（保留串联偏移），且不发布预览
+    #[serde(default = "default_strip_enabled")]
+    pub enabled: bool,
+    /// 物理串联顺序，为空时与序列号相同；序列号只决定界面中的排列顺序
+    #[serde(default)]
+    pub chain_position: Option<usize>,
+    /// 跨屏灯带展开后的分段所属原灯带的序列号，预览和布局按原序列号报告；未展开的灯带为空
+    #[serde(skip)]
+    pub source_index: Option<usize>,
+    /// 跨屏灯带展开后的分段所属原灯带的显示器ID；未展开的灯带为空
+    #[serde(skip)]
+    pub source_display_id: Option<u32>,
+}
+
+pub(crate) fn default_strip_enabled() -> bool {
+    true
+}
+
+/// 校验边缘首尾裁剪：各自在 0-100 之间，且两端合计小于 100，保证仍有可采样的区域
+pub fn validate_edge_trim(start_trim_percent: f32, end_trim_percent: f32) -> anyhow::Result<()> {
+    let in_range = |v: f32| v.is_finite() && (0.0..100.0).contains(&v);
+    if !in_range(start_trim_percent)
+        || !in_range(end_trim_percent)
+        || start_trim_percent + end_trim_percent >= 100.0
+    {
+        return Err(anyhow::anyhow!(
+            "Edge trim must be within 0-100% and leave part of the edge, got start={}%, end={}%",
+            start_trim_percent,
+            end_trim_percent
+        ));
+    }
+    Ok(())
 }
 
 impl LedStripConfig {
-    /// 计算该灯带的起始位置（基于所有灯带的序列号和长度）
+    /// 对外报告的灯带序列号：跨屏分段使用原灯带的序列号
+    pub fn reported_index(&self) -> usize {
+        self.source_index.unwrap_or(self.index)
+    }
+
+    /// 对外报告的显示器ID：跨屏分段使用原灯带所属的显示器
+    pub fn reported_display_id(&self) -> u32 {
+        self.source_display_id.unwrap_or(self.display_id)
+    }
+
+    /// 串联顺序的排序键：串联位置相同时按序列号排列
+    pub fn chain_order(&self) -> (usize, usize) {
+        (self.chain_position.unwrap_or(self.index), self.index)
+    }
+
+    /// 计算该灯带的起始位置（基于所有灯带的串联顺序和长度）
     pub fn calculate_start_pos(&self, all_strips: &[LedStripConfig]) -> usize {
         let mut start_pos = 0;
 
-        // 按序列号排序所有灯带
+        // 按串联顺序排序所有灯带
         let mut sorted_strips: Vec<_> = all_strips.iter().collect();
-        sorted_strips.sort_by_key(|strip| strip.index);
+        sorted_strips.sort_by_key(|strip| strip.chain_order());
 
         // 计算当前灯带之前的所有LED数量
         for strip in sorted_strips {
-            if strip.index < self.index {
+            if strip.chain_order() < self.chain_order() {
                 start_pos += strip.len;
             } else {
                 break;
@@ -62,6 +340,17 @@ impl LedStripConfig {
             len: 0, // Default to 0 length
             led_type: LedType::WS2812B,
             reversed: false,
+            hardware_offset: None,
+            zone_reduction: ZoneReduction::Mean,
+            color_order: ColorOrder::default(),
+            sample_shape: SampleShape::Grid,
+            sample_inset: 0,
+            start_trim_percent: 0.0,
+            end_trim_percent: 0.0,
+            enabled: true,
+            chain_position: None,
+            source_index: None,
+            source_display_id: None,
         }
     }
 
@@ -73,19 +362,234 @@ impl LedStripConfig {
     }
 }
 
-#[derive(Clone, Copy, Serialize, Deserialize, Debug)]
+/// 跨屏灯带经过的显示器在桌面坐标中的水平范围
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub struct SpanDisplayBounds {
+    pub display_id: u32,
+    pub x: i32,
+    pub width: u32,
+}
+
+impl SpanDisplayBounds {
+    fn left(&self) -> f64 {
+        self.x as f64
+    }
+
+    fn right(&self) -> f64 {
+        self.x as f64 + self.width as f64
+    }
+
+    /// 水平坐标到该显示器的距离，位于显示器内时为0
+    fn distance_to(&self, x: f64) -> f64 {
+        (self.left() - x).max(x - self.right()).max(0.0)
+    }
+}
+
+/// 将跨越多个并排显示器的上/下边灯带拆分为每个显示器上的一段
+///
+/// LED沿所有显示器合并后的水平范围均匀分布（首尾裁剪也相对于合并范围），
+/// 每个LED归入其中心所在的显示器（落在显示器间隙中时归入最近的显示器），
+/// 每段通过首尾裁剪定位到该显示器边缘上对应的区间。
+/// 返回的段按串联顺序排列：正向灯带从最左侧的显示器开始，反向灯带从最右侧开始；
+/// 只有第一段保留硬件起始偏移量，其余段紧接上一段。
+pub fn split_spanning_strip(
+    strip: &LedStripConfig,
+    displays: &[SpanDisplayBounds],
+) -> Vec<LedStripConfig> {
+    let mut displays: Vec<_> = displays.iter().filter(|d| d.width > 0).copied().collect();
+    displays.sort_by_key(|display| display.x);
+
+    if strip.len == 0 || displays.is_empty() {
+        return vec![*strip];
+    }
+
+    let left = displays[0].left();
+    let right = displays
+        .iter()
+        .map(SpanDisplayBounds::right)
+        .fold(f64::MIN, f64::max);
+    let span_width = right - left;
+    let covered_start = left + span_width * strip.start_trim_percent as f64 / 100.0;
+    let covered_width = span_width
+        * (1.0 - (strip.start_trim_percent + strip.end_trim_percent) as f64 / 100.0).max(0.0);
+    let led_width = covered_width / strip.len as f64;
+
+    let owner = |led: usize| {
+        let center = covered_start + (led as f64 + 0.5) * led_width;
+        (0..displays.len())
+            .min_by(|a, b| {
+                displays[*a]
+                    .distance_to(center)
+                    .total_cmp(&displays[*b].distance_to(center))
+            })
+            .unwrap_or(0)
+    };
+
+    let mut segments: Vec<LedStripConfig> = Vec::new();
+    let mut segment_start = 0;
+    while segment_start < strip.len {
+        let display_index = owner(segment_start);
+        let segment_end = (segment_start..strip.len)
+            .find(|led| owner(*led) != display_index)
+            .unwrap_or(strip.len);
+
+        let display = displays[display_index];
+        let width = display.width as f64;
+        let clamp = |x: f64| x.clamp(display.left(), display.right());
+        let segment_left = clamp(covered_start + segment_start as f64 * led_width);
+        let segment_right = clamp(covered_start + segment_end as f64 * led_width);
+
+        segments.push(LedStripConfig {
+            display_id: display.display_id,
+            len: segment_end - segment_start,
+            hardware_offset: None,
+            start_trim_percent: ((segment_left - display.left()) / width * 100.0) as f32,
+            end_trim_percent: ((display.right() - segment_right) / width * 100.0) as f32,
+            ..*strip
+        });
+        segment_start = segment_end;
+    }
+
+    if strip.reversed {
+        segments.reverse();
+    }
+    segments[0].hardware_offset = strip.hardware_offset;
+    segments
+}
+
+/// 展开跨屏灯带，`spans` 为灯带序列号到其经过的显示器范围的映射
+///
+/// 跨屏灯带被替换为各显示器上的分段，分段使用新的序列号（从现有最大序列号之后开始），
+/// 避免与其他灯带冲突；所有灯带按原串联顺序重新编号串联位置，使分段在串联中占据原灯带的位置。
+pub fn expand_spanning_strips(
+    strips: &[LedStripConfig],
+    spans: &HashMap<usize, Vec<SpanDisplayBounds>>,
+) -> Vec<LedStripConfig> {
+    if spans.is_empty() {
+        return strips.to_vec();
+    }
+
+    let mut sorted_strips = strips.to_vec();
+    sorted_strips.sort_by_key(|strip| strip.chain_order());
+
+    let mut next_index = strips
+        .iter()
+        .map(|strip| strip.index + 1)
+        .max()
+        .unwrap_or(0);
+    let mut expanded = Vec::with_capacity(strips.len());
+
+    for strip in sorted_strips {
+        let segments = match spans.get(&strip.index) {
+            Some(displays) if displays.len() > 1 => split_spanning_strip(&strip, displays)
+                .into_iter()
+                .map(|segment| {
+                    next_index += 1;
+                    LedStripConfig {
+                        index: next_index - 1,
+                        source_index: Some(strip.index),
+                        source_display_id: Some(strip.display_id),
+                        ..segment
+                    }
+                })
+                .collect(),
+            _ => vec![strip],
+        };
+
+        for segment in segments {
+            expanded.push(LedStripConfig {
+                chain_position: Some(expanded.len()),
+                ..segment
+            });
+        }
+    }
+
+    expanded
+}
+
+/// 移除位于显示器禁用边上的灯带，`masks` 为显示器系统ID到启用边的映射（未设置时四边都启用）
+///
+/// 剩余灯带保持原串联顺序，被移除的灯带不再占用硬件数据偏移
+pub fn filter_masked_strips(
+    strips: &[LedStripConfig],
+    masks: &HashMap<u32, BorderMask>,
+) -> Vec<LedStripConfig> {
+    strips
+        .iter()
+        .filter(|strip| {
+            masks
+                .get(&strip.display_id)
+                .copied()
+                .unwrap_or_default()
+                .contains(strip.border)
+        })
+        .cloned()
+        .collect()
+}
+
+/// SK6812 白色通道合成策略
+#[derive(Clone, Copy, Serialize, Deserialize, Debug, PartialEq, Default)]
+pub enum WhiteSynthesis {
+    /// 不使用白色通道，始终输出0
+    None,
+    /// 取RGB最小值
+    #[default]
+    Min,
+    /// 按感知亮度（CIE L*）计算，并按色彩饱和度衰减
+    Luminance,
+    /// 按颜色与灯带白点的接近程度计算
+    ColorTemperatureMatched,
+    /// 精确模式：相对灯带白点判断颜色是否接近中性色，只对接近中性的颜色提取白光，
+    /// 饱和颜色完全保留在RGB通道上，避免白光冲淡色彩
+    ///
+    /// 与 `ColorTemperatureMatched` 一样以白点为基准计算可提取的白光，但后者对任何颜色
+    /// 都按白点比例提取（饱和红色仍会叠加白光）；与 `Luminance` 一样按饱和度衰减，
+    /// 但后者以sRGB中性色为基准且线性衰减，暖白点灯带上的暖色会被误判为饱和色。
+    /// 本策略在白点归一化后的白度低于阈值时完全不提取，阈值以上平滑过渡到完全提取
+    Accurate,
+}
+
+#[derive(Clone, Copy, Serialize, Deserialize, Debug, PartialEq)]
 pub struct ColorCalibration {
     pub r: f32,
     pub g: f32,
     pub b: f32,
     #[serde(default = "default_w_value")]
     pub w: f32,
+    /// 白色通道合成策略
+    #[serde(default)]
+    pub white_synthesis: WhiteSynthesis,
+    /// 灯带白色LED的白点（RGB），用于色温匹配策略
+    #[serde(default = "default_white_point")]
+    pub white_point: [u8; 3],
+    /// 感知亮度归一化：按 Rec.709 亮度权重缩放饱和颜色，使不同色相的亮度观感一致
+    #[serde(default)]
+    pub perceptual_normalization: bool,
+    /// 颜色变化死区：与上次输出相比变化小于该值的通道保持上次的值，用于抑制采样抖动导致的闪烁
+    #[serde(default)]
+    pub deadband: u8,
+    /// 高光压缩拐点（0-255）：校准后最亮通道超过该值时整体平滑压缩，中间调不受影响
+    #[serde(default = "default_highlight_knee")]
+    pub highlight_knee: u8,
+    /// 高光压缩强度（0-1）：0 关闭，1 时满亮度被压缩到拐点与 255 的中点
+    ///
+    /// 只柔化接近满白的高光，与硬性功率限制无关
+    #[serde(default)]
+    pub highlight_rolloff: f32,
 }
 
 fn default_w_value() -> f32 {
     1.0
 }
 
+fn default_highlight_knee() -> u8 {
+    200
+}
+
+fn default_white_point() -> [u8; 3] {
+    [255, 255, 255]
+}
+
 impl ColorCalibration {
     pub fn new() -> Self {
         Self {
@@ -93,6 +597,12 @@ impl ColorCalibration {
             g: 1.0,
             b: 1.0,
             w: 1.0,
+            white_synthesis: WhiteSynthesis::default(),
+            white_point: default_white_point(),
+            perceptual_normalization: false,
+            deadband: 0,
+            highlight_knee: default_highlight_knee(),
+            highlight_rolloff: 0.0,
         }
     }
 
@@ -112,6 +622,14 @@ impl ColorCalibration {
             (self.w * 255.0) as u8,
         ]
     }
+
+    /// 校验校准参数：白点不能为全零，否则色温匹配类策略没有可参照的白光
+    pub fn validate(&self) -> anyhow::Result<()> {
+        if self.white_point == [0, 0, 0] {
+            return Err(anyhow::anyhow!("white_point must not be all zero"));
+        }
+        Ok(())
+    }
 }
 
 #[derive(Clone, Serialize, Deserialize, Debug)]
@@ -229,6 +747,22 @@ pub struct SamplePointConfig {
 mod tests {
     use super::*;
 
+    #[test]
+    fn color_calibration_rejects_all_zero_white_point() {
+        let calibration = ColorCalibration {
+            white_synthesis: WhiteSynthesis::ColorTemperatureMatched,
+            white_point: [0, 0, 0],
+            ..ColorCalibration::new()
+        };
+        assert!(calibration.validate().is_err());
+
+        let calibration = ColorCalibration {
+            white_point: [255, 180, 0],
+            ..calibration
+        };
+        assert!(calibration.validate().is_ok());
+    }
+
     #[test]
     fn apply_reversal_reverses_when_enabled() {
         let strip = LedStripConfig {
@@ -238,6 +772,17 @@ mod tests {
             len: 4,
             led_type: LedType::WS2812B,
             reversed: true,
+            hardware_offset: None,
+            zone_reduction: ZoneReduction::Mean,
+            color_order: ColorOrder::default(),
+            sample_shape: SampleShape::Grid,
+            sample_inset: 0,
+            start_trim_percent: 0.0,
+            end_trim_percent: 0.0,
+            enabled: true,
+            chain_position: None,
+            source_index: None,
+            source_display_id: None,
         };
 
         let mut colors = vec![
@@ -262,6 +807,17 @@ mod tests {
             len: 3,
             led_type: LedType::WS2812B,
             reversed: false,
+            hardware_offset: None,
+            zone_reduction: ZoneReduction::Mean,
+            color_order: ColorOrder::default(),
+            sample_shape: SampleShape::Grid,
+            sample_inset: 0,
+            start_trim_percent: 0.0,
+            end_trim_percent: 0.0,
+            enabled: true,
+            chain_position: None,
+            source_index: None,
+            source_display_id: None,
         };
 
         let mut colors = vec![
@@ -276,6 +832,56 @@ mod tests {
         assert_eq!(ordered, vec![[10, 0, 0], [11, 0, 0], [12, 0, 0]]);
     }
 
+    #[test]
+    fn test_color_order_places_white_byte_independently() {
+        let wrgb = ColorOrder {
+            rgb: RgbOrder::Rgb,
+            white: WhitePosition::Leading,
+        };
+        let mut buffer = Vec::new();
+        wrgb.encode_into([10, 20, 30], Some(40), &mut buffer);
+        assert_eq!(buffer, vec![40, 10, 20, 30]);
+        assert_eq!(
+            wrgb.decode(&buffer, LedType::SK6812),
+            ([10, 20, 30], Some(40))
+        );
+
+        // 默认顺序保持GRB/GRBW
+        let mut buffer = Vec::new();
+        ColorOrder::default().encode_into([10, 20, 30], Some(40), &mut buffer);
+        ColorOrder::default().encode_into([10, 20, 30], None, &mut buffer);
+        assert_eq!(buffer, vec![20, 10, 30, 40, 20, 10, 30]);
+
+        // RGB灯带忽略白色通道位置
+        let mut buffer = Vec::new();
+        wrgb.encode_into([10, 20, 30], None, &mut buffer);
+        assert_eq!(buffer, vec![10, 20, 30]);
+        assert_eq!(wrgb.decode(&buffer, LedType::WS2812B), ([10, 20, 30], None));
+
+        // WS2811 固定为RGB，与配置的RGB子顺序无关
+        let ws2811 = LedType::WS2811.effective_color_order(ColorOrder::default());
+        let mut buffer = Vec::new();
+        ws2811.encode_into([10, 20, 30], None, &mut buffer);
+        assert_eq!(buffer, vec![10, 20, 30]);
+        assert_eq!(LedType::WS2811.bytes_per_led(), 3);
+        assert_eq!(
+            ColorOrder::default().decode(&buffer, LedType::WS2811),
+            ([10, 20, 30], None)
+        );
+
+        for order in [
+            RgbOrder::Rgb,
+            RgbOrder::Rbg,
+            RgbOrder::Grb,
+            RgbOrder::Gbr,
+            RgbOrder::Brg,
+            RgbOrder::Bgr,
+        ] {
+            assert_eq!(order.restore(order.arrange([1, 2, 3])), [1, 2, 3]);
+        }
+        assert_eq!(RgbOrder::Brg.arrange([1, 2, 3]), [3, 1, 2]);
+    }
+
     #[test]
     fn test_parse_led_strip_config_group_from_toml() {
         let toml_str = r#"
@@ -430,6 +1036,17 @@ mod tests {
                 len: 38,
                 led_type: LedType::SK6812,
                 reversed: false,
+                hardware_offset: None,
+                zone_reduction: ZoneReduction::Mean,
+                color_order: ColorOrder::default(),
+                sample_shape: SampleShape::Grid,
+                sample_inset: 0,
+                start_trim_percent: 0.0,
+                end_trim_percent: 0.0,
+                enabled: true,
+                chain_position: None,
+                source_index: None,
+                source_display_id: None,
             },
             LedStripConfig {
                 index: 1,
@@ -438,6 +1055,17 @@ mod tests {
                 len: 22,
                 led_type: LedType::WS2812B,
                 reversed: false,
+                hardware_offset: None,
+                zone_reduction: ZoneReduction::Mean,
+                color_order: ColorOrder::default(),
+                sample_shape: SampleShape::Grid,
+                sample_inset: 0,
+                start_trim_percent: 0.0,
+                end_trim_percent: 0.0,
+                enabled: true,
+                chain_position: None,
+                source_index: None,
+                source_display_id: None,
             },
             LedStripConfig {
                 index: 2,
@@ -446,6 +1074,17 @@ mod tests {
                 len: 38,
                 led_type: LedType::WS2812B,
                 reversed: false,
+                hardware_offset: None,
+                zone_reduction: ZoneReduction::Mean,
+                color_order: ColorOrder::default(),
+                sample_shape: SampleShape::Grid,
+                sample_inset: 0,
+                start_trim_percent: 0.0,
+                end_trim_percent: 0.0,
+                enabled: true,
+                chain_position: None,
+                source_index: None,
+                source_display_id: None,
             },
             LedStripConfig {
                 index: 3,
@@ -454,6 +1093,17 @@ mod tests {
                 len: 38,
                 led_type: LedType::SK6812,
                 reversed: false,
+                hardware_offset: None,
+                zone_reduction: ZoneReduction::Mean,
+                color_order: ColorOrder::default(),
+                sample_shape: SampleShape::Grid,
+                sample_inset: 0,
+                start_trim_percent: 0.0,
+                end_trim_percent: 0.0,
+                enabled: true,
+                chain_position: None,
+                source_index: None,
+                source_display_id: None,
             },
         ];
 
@@ -468,6 +1118,185 @@ mod tests {
         assert_eq!(total_leds, 136);
     }
 
+    #[test]
+    fn test_spanning_top_strip_across_two_adjacent_displays() {
+        let displays = [
+            SpanDisplayBounds {
+                display_id: 1,
+                x: 0,
+                width: 1920,
+            },
+            SpanDisplayBounds {
+                display_id: 2,
+                x: 1920,
+                width: 1280,
+            },
+        ];
+        let mut spanning = LedStripConfig::default_for_display(1, 0);
+        spanning.len = 50;
+
+        // 合并宽度3200，每个LED 64像素：前30个落在左屏，后20个落在右屏，接缝处恰好对齐
+        let segments = split_spanning_strip(&spanning, &displays);
+        let layout: Vec<_> = segments
+            .iter()
+            .map(|s| {
+                (
+                    s.display_id,
+                    s.len,
+                    s.start_trim_percent,
+                    s.end_trim_percent,
+                )
+            })
+            .collect();
+        assert_eq!(layout, vec![(1, 30, 0.0, 0.0), (2, 20, 0.0, 0.0)]);
+
+        // 反向灯带从右屏开始串联
+        spanning.reversed = true;
+        spanning.hardware_offset = Some(8);
+        let segments = split_spanning_strip(&spanning, &displays);
+        let layout: Vec<_> = segments
+            .iter()
+            .map(|s| (s.display_id, s.len, s.hardware_offset))
+            .collect();
+        assert_eq!(layout, vec![(2, 20, Some(8)), (1, 30, None)]);
+
+        // 接缝没有对齐时，跨接缝的LED归入中心所在的显示器，左屏一段只覆盖到该段最后一个LED
+        spanning.reversed = false;
+        spanning.len = 4;
+        let uneven = [
+            SpanDisplayBounds {
+                display_id: 1,
+                x: 0,
+                width: 100,
+            },
+            SpanDisplayBounds {
+                display_id: 2,
+                x: 100,
+                width: 200,
+            },
+        ];
+        let segments = split_spanning_strip(&spanning, &uneven);
+        let layout: Vec<_> = segments
+            .iter()
+            .map(|s| {
+                (
+                    s.display_id,
+                    s.len,
+                    s.start_trim_percent,
+                    s.end_trim_percent,
+                )
+            })
+            .collect();
+        assert_eq!(layout, vec![(1, 1, 0.0, 25.0), (2, 3, 0.0, 0.0)]);
+    }
+
+    #[test]
+    fn test_expand_spanning_strips_keeps_chain_layout() {
+        let mut spanning = LedStripConfig::default_for_display(1, 0);
+        spanning.len = 50;
+        let mut right = LedStripConfig::default_for_display(2, 1);
+        right.border = Border::Right;
+        right.len = 10;
+        let strips = vec![right, spanning];
+
+        let spans = HashMap::from([(
+            0,
+            vec![
+                SpanDisplayBounds {
+                    display_id: 2,
+                    x: 1920,
+                    width: 1280,
+                },
+                SpanDisplayBounds {
+                    display_id: 1,
+                    x: 0,
+                    width: 1920,
+                },
+            ],
+        )]);
+        let expanded = expand_spanning_strips(&strips, &spans);
+
+        // 分段使用新序列号并占据原灯带在串联中的位置，其后的灯带偏移量不变
+        let layout: Vec<_> = expanded
+            .iter()
+            .map(|s| {
+                (
+                    s.index,
+                    s.display_id,
+                    s.len,
+                    s.calculate_start_pos(&expanded),
+                )
+            })
+            .collect();
+        assert_eq!(layout, vec![(2, 1, 30, 0), (3, 2, 20, 30), (1, 2, 10, 50)]);
+
+        // 分段对外仍以原灯带的序列号和显示器上报
+        let reported: Vec<_> = expanded
+            .iter()
+            .map(|s| (s.reported_index(), s.reported_display_id()))
+            .collect();
+        assert_eq!(reported, vec![(0, 1), (0, 1), (1, 2)]);
+        assert_eq!(expanded[2].source_index, None);
+
+        // 没有跨屏设置时保持原样
+        let unchanged = expand_spanning_strips(&strips, &HashMap::new());
+        assert_eq!(unchanged.len(), 2);
+        assert_eq!(unchanged[1].calculate_start_pos(&unchanged), 0);
+    }
+
+    #[test]
+    fn test_filter_masked_strips_skips_disabled_bottom() {
+        let borders = [Border::Top, Border::Right, Border::Bottom, Border::Left];
+        let mut strips: Vec<LedStripConfig> = borders
+            .iter()
+            .enumerate()
+            .map(|(index, border)| LedStripConfig {
+                border: *border,
+                len: 10 + index,
+                ..LedStripConfig::default_for_display(1, index)
+            })
+            .collect();
+        // 另一台显示器的底边不受影响
+        strips.push(LedStripConfig {
+            border: Border::Bottom,
+            len: 5,
+            ..LedStripConfig::default_for_display(2, 4)
+        });
+
+        let masks = HashMap::from([(
+            1,
+            BorderMask {
+                bottom: false,
+                ..BorderMask::default()
+            },
+        )]);
+        let filtered = filter_masked_strips(&strips, &masks);
+
+        assert!(!filtered
+            .iter()
+            .any(|s| s.display_id == 1 && s.border == Border::Bottom));
+
+        // 底边不占用偏移，其后的灯带紧接在前一条之后
+        let layout: Vec<_> = filtered
+            .iter()
+            .map(|s| (s.index, s.calculate_start_pos(&filtered)))
+            .collect();
+        assert_eq!(layout, vec![(0, 0), (1, 10), (3, 21), (4, 34)]);
+
+        // 被移除的灯带不生成采样点映射
+        let mut group = LedStripConfigGroup {
+            strips: filtered,
+            mappers: Vec::new(),
+            color_calibration: ColorCalibration::new(),
+        };
+        group.generate_mappers();
+        assert_eq!(group.mappers.len(), 4);
+        assert_eq!(group.mappers[2].start, 21);
+
+        // 未设置的显示器四边都启用
+        assert_eq!(filter_masked_strips(&strips, &HashMap::new()).len(), 5);
+    }
+
     #[tokio::test]
     async fn test_get_default_config() {
         let default_config = LedStripConfigGroup::get_default_config().await.unwrap();
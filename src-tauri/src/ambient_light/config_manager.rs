@@ -5,7 +5,9 @@ use tokio::{sync::OnceCell, task::yield_now};
 
 use crate::ambient_light::{config, LedStripConfigGroup};
 
-use super::{Border, ColorCalibration, LedType, SamplePointMapper};
+use super::{
+    Border, ColorCalibration, ColorOrder, LedType, SamplePointMapper, SampleShape, ZoneReduction,
+};
 
 pub struct ConfigManager {
     config: Arc<RwLock<LedStripConfigGroup>>,
@@ -212,48 +214,6 @@ impl ConfigManager {
         Ok(())
     }
 
-    pub async fn reverse_led_strip_part(
-        &self,
-        display_id: u32,
-        border: Border,
-    ) -> anyhow::Result<()> {
-        let mut config = self.config.write().await;
-
-        // 找到对应的灯带并切换其reversed状态
-        for strip in config.strips.iter_mut() {
-            if strip.display_id == display_id && strip.border == border {
-                strip.reversed = !strip.reversed;
-                log::info!(
-                    "🔄 切换灯带反向状态: 显示器{} {}边 -> reversed={}",
-                    display_id,
-                    match border {
-                        Border::Top => "Top",
-                        Border::Bottom => "Bottom",
-                        Border::Left => "Left",
-                        Border::Right => "Right",
-                    },
-                    strip.reversed
-                );
-                break;
-            }
-        }
-
-        // 重建mappers以反映新的reversed状态
-        Self::rebuild_mappers(&mut config);
-
-        let cloned_config = config.clone();
-
-        drop(config);
-
-        self.update(&cloned_config).await?;
-
-        self.config_update_sender
-            .send(cloned_config)
-            .map_err(|e| anyhow::anyhow!("Failed to send config update: {}", e))?;
-
-        Ok(())
-    }
-
     fn rebuild_mappers(config: &mut LedStripConfigGroup) {
         let mut prev_pos_end = 0;
         let mappers: Vec<SamplePointMapper> = config
@@ -360,6 +320,17 @@ mod tests {
                 len: 30,
                 led_type: LedType::WS2812B,
                 reversed: false,
+                hardware_offset: None,
+                zone_reduction: ZoneReduction::Mean,
+                color_order: ColorOrder::default(),
+                sample_shape: SampleShape::Grid,
+                sample_inset: 0,
+                start_trim_percent: 0.0,
+                end_trim_percent: 0.0,
+                enabled: true,
+                chain_position: None,
+                source_index: None,
+                source_display_id: None,
             };
             strips.push(strip);
             mappers.push(SamplePointMapper {
@@ -1,10 +1,22 @@
 use anyhow::Result;
-use std::sync::Arc;
+use serde::Serialize;
+use std::collections::HashMap;
+use std::path::{Path, PathBuf};
+use std::sync::{
+    atomic::{AtomicU64, Ordering},
+    Arc,
+};
+use std::time::{Duration, SystemTime};
 use tauri::async_runtime::RwLock;
 use tokio::sync::OnceCell;
 
-use crate::ambient_light::{ColorCalibration, LedStripConfigGroupV2, LedStripConfigV2};
+use crate::ambient_light::{
+    BorderMask, CaptureFormat, ColorCalibration, DisplayWinding, LedStripConfigGroupV2,
+    LedStripConfigV2, StripSpan,
+};
 use crate::display::DisplayRegistry;
+use crate::output_sink::SinkFrameLayout;
+use crate::screenshot::SampleCrop;
 
 /// 新版本的配置管理器，支持稳定的显示器ID系统
 pub struct ConfigManagerV2 {
@@ -13,7 +25,61 @@ pub struct ConfigManagerV2 {
     /// 显示器注册管理器
     display_registry: Arc<DisplayRegistry>,
     /// 配置更新通知
-    config_update_sender: tokio::sync::watch::Sender<LedStripConfigGroupV2>,
+    config_update_sender: Arc<tokio::sync::watch::Sender<LedStripConfigGroupV2>>,
+    /// 配置更新通知防抖
+    update_debouncer: Arc<UpdateDebouncer>,
+    /// 与内存配置一致的配置文件内容，用于区分应用自身写入和外部编辑
+    synced_file_content: Arc<RwLock<Option<String>>>,
+    /// 按灯带序列号索引的LED位置，配置变化时重新计算，供每帧采样直接使用
+    led_positions: Arc<RwLock<Arc<HashMap<usize, Vec<f32>>>>>,
+    /// 硬件数据布局，配置变化时重新计算，供网络输出端每帧直接使用
+    sink_frame_layout: Arc<RwLock<Arc<SinkFrameLayout>>>,
+}
+
+/// 按灯带序列号收集配置了位置的灯带的LED位置
+fn collect_led_positions(config: &LedStripConfigGroupV2) -> HashMap<usize, Vec<f32>> {
+    config
+        .strips
+        .iter()
+        .filter_map(|strip| {
+            strip
+                .led_positions
+                .clone()
+                .map(|positions| (strip.index, positions))
+        })
+        .collect()
+}
+
+/// 配置文件轮询间隔
+const CONFIG_FILE_POLL_INTERVAL: Duration = Duration::from_millis(500);
+/// 检测到配置文件变化后等待其稳定的时间，编辑器保存时可能分多次写入
+const CONFIG_FILE_SETTLE_INTERVAL: Duration = Duration::from_millis(300);
+
+/// 读取文件修改时间，文件不存在时返回 None
+async fn file_modified_time(path: &Path) -> Option<SystemTime> {
+    tokio::fs::metadata(path).await.ok()?.modified().ok()
+}
+
+/// 配置更新防抖器：时间窗口内的连续更新只有最后一次会触发通知
+#[derive(Default)]
+struct UpdateDebouncer {
+    generation: AtomicU64,
+}
+
+impl UpdateDebouncer {
+    /// 登记一次更新并等待防抖窗口结束，期间没有更新的更新时返回 true
+    async fn settle(&self, interval: Duration) -> bool {
+        let generation = self.generation.fetch_add(1, Ordering::SeqCst) + 1;
+        if !interval.is_zero() {
+            tokio::time::sleep(interval).await;
+        }
+        self.generation.load(Ordering::SeqCst) == generation
+    }
+
+    /// 取消所有等待中的通知，由调用方立即发送
+    fn cancel_pending(&self) {
+        self.generation.fetch_add(1, Ordering::SeqCst);
+    }
 }
 
 impl ConfigManagerV2 {
@@ -73,10 +139,16 @@ impl ConfigManagerV2 {
             config.color_calibration.w
         );
 
+        let led_positions = Arc::new(collect_led_positions(&config));
+        let sink_frame_layout = Arc::new(SinkFrameLayout::from_strips(&config.strips));
         Self {
             config: Arc::new(RwLock::new(config)),
             display_registry,
-            config_update_sender,
+            config_update_sender: Arc::new(config_update_sender),
+            update_debouncer: Arc::new(UpdateDebouncer::default()),
+            synced_file_content: Arc::new(RwLock::new(None)),
+            led_positions: Arc::new(RwLock::new(led_positions)),
+            sink_frame_layout: Arc::new(RwLock::new(sink_frame_layout)),
         }
     }
 
@@ -131,16 +203,38 @@ impl ConfigManagerV2 {
             new_config.color_calibration.w
         );
 
-        // 保存到文件
+        new_config.validate()?;
+
+        // 保存到文件，先记录写入内容，文件监听据此忽略应用自身的写入
         log::info!("💾 [COLOR_CALIBRATION] Saving config to file...");
-        new_config.write_config().await?;
+        let content = new_config.to_config_file_content().await?;
+        *self.synced_file_content.write().await = Some(content.clone());
+        new_config.write_config_content(content).await?;
+
+        self.apply_config(new_config.clone()).await?;
+
+        log::info!(
+            "✅ [COLOR_CALIBRATION] ConfigManagerV2::update_config completed successfully with color calibration: r={:.3}, g={:.3}, b={:.3}, w={:.3}",
+            new_config.color_calibration.r,
+            new_config.color_calibration.g,
+            new_config.color_calibration.b,
+            new_config.color_calibration.w
+        );
+
+        Ok(())
+    }
 
+    /// 应用已校验的配置：更新内存、显示器注册管理器，并防抖发送更新通知
+    async fn apply_config(&self, new_config: LedStripConfigGroupV2) -> Result<()> {
         // 更新内存中的配置
         log::info!("🧠 [COLOR_CALIBRATION] Updating in-memory config...");
         {
             let mut config = self.config.write().await;
             *config = new_config.clone();
         }
+        *self.led_positions.write().await = Arc::new(collect_led_positions(&new_config));
+        *self.sink_frame_layout.write().await =
+            Arc::new(SinkFrameLayout::from_strips(&new_config.strips));
 
         // 更新显示器注册管理器
         log::info!("📺 [COLOR_CALIBRATION] Updating display registry...");
@@ -148,9 +242,116 @@ impl ConfigManagerV2 {
             .update_config_group(new_config.display_config.clone())
             .await?;
 
+        // 防抖：短时间内的连续更新合并为一次通知，通知时读取最新配置，保证最终状态生效
+        let debounce_ms = crate::user_preferences::UserPreferencesManager::global()
+            .await
+            .get_preferences()
+            .await
+            .config_update
+            .debounce_ms;
+        let config = self.config.clone();
+        let config_update_sender = self.config_update_sender.clone();
+        let display_registry = self.display_registry.clone();
+        let update_debouncer = self.update_debouncer.clone();
+        tokio::spawn(async move {
+            if update_debouncer
+                .settle(Duration::from_millis(debounce_ms))
+                .await
+            {
+                let latest_config = config.read().await.clone();
+                Self::notify_config_changed(latest_config, &config_update_sender, display_registry)
+                    .await;
+            } else {
+                log::debug!("⏭️ Config update coalesced into a later update");
+            }
+        });
+
+        Ok(())
+    }
+
+    /// 立即发送配置更新通知，取代等待中的防抖通知
+    ///
+    /// 用于需要在返回前让更新生效的调用方，例如恢复默认配置后重启采样、应用校准预设后的下一帧
+    pub async fn flush_config_update(&self) {
+        self.update_debouncer.cancel_pending();
+        let latest_config = self.config.read().await.clone();
+        Self::notify_config_changed(
+            latest_config,
+            &self.config_update_sender,
+            self.display_registry.clone(),
+        )
+        .await;
+    }
+
+    /// 启动配置文件监听，外部编辑配置文件后自动校验并热重载
+    ///
+    /// 以轮询修改时间的方式检测变化。应用写入和外部编辑冲突时以最后写入文件的内容为准：
+    /// 应用在外部编辑被检测到之前保存会覆盖该编辑，之后的外部编辑则会被重新加载。
+    pub fn watch_config_file(&'static self) {
+        tokio::spawn(async move {
+            let config_path = LedStripConfigGroupV2::get_config_path();
+            log::info!("👀 Watching config file: {}", config_path.display());
+
+            let mut last_modified = file_modified_time(&config_path).await;
+            let mut interval = tokio::time::interval(CONFIG_FILE_POLL_INTERVAL);
+            loop {
+                interval.tick().await;
+
+                let modified = file_modified_time(&config_path).await;
+                if modified.is_none() || modified == last_modified {
+                    continue;
+                }
+
+                // 防抖：等待文件停止变化后再读取，仍在变化时留到下一轮
+                tokio::time::sleep(CONFIG_FILE_SETTLE_INTERVAL).await;
+                if file_modified_time(&config_path).await != modified {
+                    continue;
+                }
+                last_modified = modified;
+
+                if let Err(e) = self.reload_external_change(&config_path).await {
+                    log::warn!(
+                        "⚠️ Ignoring external config change in {}: {}",
+                        config_path.display(),
+                        e
+                    );
+                }
+            }
+        });
+    }
+
+    /// 重新加载外部修改的配置文件，内容与当前配置一致时不做任何处理
+    async fn reload_external_change(&self, config_path: &Path) -> Result<()> {
+        let content = tokio::fs::read_to_string(config_path).await?;
+        if self.synced_file_content.read().await.as_deref() == Some(content.as_str()) {
+            return Ok(());
+        }
+
+        let new_config = LedStripConfigGroupV2::parse_config_file(&content)?;
+        *self.synced_file_content.write().await = Some(content);
+
+        let current = self.config.read().await.to_config_file_string()?;
+        if new_config.to_config_file_string()? == current {
+            return Ok(());
+        }
+
+        log::info!(
+            "🔁 Reloading externally edited config from {}",
+            config_path.display()
+        );
+        // 不回写文件，保留用户在文件中的注释和格式
+        self.apply_config(new_config).await
+    }
+
+    /// 发送配置更新通知并通过WebSocket广播配置变化
+    async fn notify_config_changed(
+        new_config: LedStripConfigGroupV2,
+        config_update_sender: &tokio::sync::watch::Sender<LedStripConfigGroupV2>,
+        display_registry: Arc<DisplayRegistry>,
+    ) {
         // 发送更新通知
         log::info!("📡 [COLOR_CALIBRATION] Sending config update notification...");
-        if let Err(e) = self.config_update_sender.send(new_config.clone()) {
+        if let Err(e) = config_update_sender.send(new_config.clone()) {
             log::error!(
                 "❌ [COLOR_CALIBRATION] Failed to send config update notification: {}",
                 e
@@ -159,7 +360,7 @@ impl ConfigManagerV2 {
 
         // 通过适配器转换为v1格式并广播配置变化
         log::info!("🔄 [COLOR_CALIBRATION] Converting to v1 format for WebSocket broadcast...");
-        let adapter = crate::ambient_light::PublisherAdapter::new(self.display_registry.clone());
+        let adapter = crate::ambient_light::PublisherAdapter::new(display_registry);
         match adapter.convert_v2_to_v1_config(&new_config).await {
             Ok(v1_config) => {
                 log::info!(
@@ -178,26 +379,35 @@ impl ConfigManagerV2 {
                 );
             }
         }
+    }
 
-        log::info!(
-            "✅ [COLOR_CALIBRATION] ConfigManagerV2::update_config completed successfully with color calibration: r={:.3}, g={:.3}, b={:.3}, w={:.3}",
-            new_config.color_calibration.r,
-            new_config.color_calibration.g,
-            new_config.color_calibration.b,
-            new_config.color_calibration.w
-        );
+    /// 恢复默认配置（灯带、显示器注册信息和颜色校准），先将当前配置文件备份为带时间戳的文件
+    ///
+    /// 返回时默认配置已经生效（不等待防抖通知），返回备份文件路径，配置文件不存在时为空
+    pub async fn reset_to_defaults(&self) -> Result<Option<PathBuf>> {
+        let backup_path =
+            super::backup_config_file(&LedStripConfigGroupV2::get_config_path()).await?;
 
-        Ok(())
+        let config = LedStripConfigGroupV2::get_default_config().await?;
+        self.update_config(config).await?;
+        self.flush_config_update().await;
+
+        log::info!("♻️ LED strip configuration reset to defaults");
+        Ok(backup_path)
     }
 
     /// 重新加载配置
     pub async fn reload_config(&self) -> Result<()> {
         let new_config = LedStripConfigGroupV2::read_config().await?;
+        *self.synced_file_content.write().await = None;
 
         {
             let mut config = self.config.write().await;
             *config = new_config.clone();
         }
+        *self.led_positions.write().await = Arc::new(collect_led_positions(&new_config));
+        *self.sink_frame_layout.write().await =
+            Arc::new(SinkFrameLayout::from_strips(&new_config.strips));
 
         // 更新显示器注册管理器
         self.display_registry
@@ -207,6 +417,18 @@ impl ConfigManagerV2 {
         Ok(())
     }
 
+    /// 获取按灯带序列号配置的LED位置（仅包含配置了位置的灯带）
+    ///
+    /// 在配置变化时预先计算，每帧调用只复制引用
+    pub async fn get_led_positions(&self) -> Arc<HashMap<usize, Vec<f32>>> {
+        self.led_positions.read().await.clone()
+    }
+
+    /// 获取硬件数据布局（各灯带的字节位置和编码），在配置变化时预先计算
+    pub async fn get_sink_frame_layout(&self) -> Arc<SinkFrameLayout> {
+        self.sink_frame_layout.read().await.clone()
+    }
+
     /// 获取显示器注册管理器
     pub fn get_display_registry(&self) -> Arc<DisplayRegistry> {
         self.display_registry.clone()
@@ -255,6 +477,7 @@ impl ConfigManagerV2 {
 
     /// 更新颜色校准
     pub async fn update_color_calibration(&self, calibration: ColorCalibration) -> Result<()> {
+        calibration.validate()?;
         let mut config = self.get_config().await;
 
         // 记录旧的配置值
@@ -280,6 +503,12 @@ impl ConfigManagerV2 {
             && old_calibration.g == calibration.g
             && old_calibration.b == calibration.b
             && old_calibration.w == calibration.w
+            && old_calibration.white_synthesis == calibration.white_synthesis
+            && old_calibration.white_point == calibration.white_point
+            && old_calibration.perceptual_normalization == calibration.perceptual_normalization
+            && old_calibration.deadband == calibration.deadband
+            && old_calibration.highlight_knee == calibration.highlight_knee
+            && old_calibration.highlight_rolloff == calibration.highlight_rolloff
         {
             log::info!("ℹ️ [COLOR_CALIBRATION] No changes detected, skipping update");
             return Ok(());
@@ -312,6 +541,334 @@ impl ConfigManagerV2 {
         }
     }
 
+    /// 获取所有命名的颜色校准预设
+    pub async fn get_calibration_presets(&self) -> HashMap<String, ColorCalibration> {
+        self.config.read().await.calibration_presets.clone()
+    }
+
+    /// 将当前的颜色校准保存为命名预设，同名预设被覆盖
+    pub async fn save_calibration_preset(&self, name: &str) -> Result<()> {
+        let mut config = self.get_config().await;
+
+        if config.save_calibration_preset(name)? {
+            self.update_config(config).await
+        } else {
+            Ok(())
+        }
+    }
+
+    /// 应用命名的颜色校准预设，经 `update_color_calibration` 在下一帧生效；
+    /// 预设不存在时返回 None
+    pub async fn apply_calibration_preset(&self, name: &str) -> Result<Option<ColorCalibration>> {
+        let Some(calibration) = self
+            .config
+            .read()
+            .await
+            .calibration_presets
+            .get(name)
+            .copied()
+        else {
+            return Ok(None);
+        };
+
+        log::info!("🎨 [COLOR_CALIBRATION] Applying calibration preset {name:?}");
+        self.update_color_calibration(calibration).await?;
+        // 不等待防抖窗口，保证预设在下一帧生效
+        self.flush_config_update().await;
+        Ok(Some(calibration))
+    }
+
+    /// 删除命名的颜色校准预设，返回预设是否存在
+    pub async fn delete_calibration_preset(&self, name: &str) -> Result<bool> {
+        let mut config = self.get_config().await;
+
+        if config.delete_calibration_preset(name) {
+            self.update_config(config).await?;
+            Ok(true)
+        } else {
+            Ok(false)
+        }
+    }
+
+    /// 设置显示器的镜像来源，`source_internal_id` 为 None 时取消镜像
+    pub async fn set_mirror_source(
+        &self,
+        target_internal_id: &str,
+        source_internal_id: Option<String>,
+    ) -> Result<()> {
+        let mut config = self.get_config().await;
+
+        if config.set_mirror_source(target_internal_id, source_internal_id)? {
+            self.update_config(config).await
+        } else {
+            Ok(())
+        }
+    }
+
+    /// 设置灯带跨越的显示器，`display_internal_ids` 为 None 时恢复只在所属显示器上采样
+    pub async fn set_strip_span(
+        &self,
+        strip_index: usize,
+        display_internal_ids: Option<Vec<String>>,
+    ) -> Result<()> {
+        let mut config = self.get_config().await;
+
+        if config.set_strip_span(strip_index, display_internal_ids)? {
+            self.update_config(config).await
+        } else {
+            Ok(())
+        }
+    }
+
+    /// 获取所有跨屏灯带设置
+    pub async fn get_strip_spans(&self) -> Vec<StripSpan> {
+        self.config.read().await.strip_spans.clone()
+    }
+
+    /// 设置显示器的采样区域裁剪，`crop` 为 None 时恢复整幅画面采样
+    pub async fn set_display_crop(
+        &self,
+        display_internal_id: &str,
+        crop: Option<SampleCrop>,
+    ) -> Result<()> {
+        let mut config = self.get_config().await;
+
+        if config.set_display_crop(display_internal_id, crop)? {
+            self.update_config(config).await
+        } else {
+            Ok(())
+        }
+    }
+
+    /// 获取系统显示器ID对应的采样区域裁剪
+    pub async fn get_display_crop(&self, display_id: u32) -> Option<SampleCrop> {
+        let internal_id = self
+            .display_registry
+            .get_internal_id_by_display_id(display_id)
+            .await
+            .ok()?;
+        self.config
+            .read()
+            .await
+            .display_crops
+            .get(&internal_id)
+            .copied()
+    }
+
+    /// 设置显示器的缩放比例覆盖，`scale_factor` 为 None 时恢复自动检测
+    pub async fn set_display_scale_factor(
+        &self,
+        display_internal_id: &str,
+        scale_factor: Option<f32>,
+    ) -> Result<()> {
+        let mut config = self.get_config().await;
+
+        if config.set_display_scale_factor(display_internal_id, scale_factor)? {
+            self.update_config(config).await
+        } else {
+            Ok(())
+        }
+    }
+
+    /// 获取系统显示器ID对应的缩放比例覆盖
+    pub async fn get_display_scale_factor(&self, display_id: u32) -> Option<f32> {
+        let internal_id = self
+            .display_registry
+            .get_internal_id_by_display_id(display_id)
+            .await
+            .ok()?;
+        self.config
+            .read()
+            .await
+            .display_scale_factors
+            .get(&internal_id)
+            .copied()
+    }
+
+    /// 设置显示器的采集帧率覆盖，`frame_rate` 为 None 时恢复按刷新率采集
+    pub async fn set_display_frame_rate(
+        &self,
+        display_internal_id: &str,
+        frame_rate: Option<f32>,
+    ) -> Result<()> {
+        let mut config = self.get_config().await;
+
+        if config.set_display_frame_rate(display_internal_id, frame_rate)? {
+            self.update_config(config).await
+        } else {
+            Ok(())
+        }
+    }
+
+    /// 获取系统显示器ID对应的采集帧率覆盖
+    pub async fn get_display_frame_rate(&self, display_id: u32) -> Option<f32> {
+        let internal_id = self
+            .display_registry
+            .get_internal_id_by_display_id(display_id)
+            .await
+            .ok()?;
+        self.config
+            .read()
+            .await
+            .display_frame_rates
+            .get(&internal_id)
+            .copied()
+    }
+
+    /// 设置显示器的灯带走线方向
+    pub async fn set_display_winding(
+        &self,
+        display_internal_id: &str,
+        winding: DisplayWinding,
+    ) -> Result<()> {
+        let mut config = self.get_config().await;
+
+        if config.set_display_winding(display_internal_id, winding) {
+            self.update_config(config).await
+        } else {
+            Ok(())
+        }
+    }
+
+    /// 获取系统显示器ID对应的灯带走线方向
+    pub async fn get_display_winding(&self, display_id: u32) -> DisplayWinding {
+        let Ok(internal_id) = self
+            .display_registry
+            .get_internal_id_by_display_id(display_id)
+            .await
+        else {
+            return DisplayWinding::default();
+        };
+        self.config
+            .read()
+            .await
+            .display_windings
+            .get(&internal_id)
+            .copied()
+            .unwrap_or_default()
+    }
+
+    /// 设置显示器截图的像素字节顺序
+    pub async fn set_display_capture_format(
+        &self,
+        display_internal_id: &str,
+        capture_format: CaptureFormat,
+    ) -> Result<()> {
+        let mut config = self.get_config().await;
+
+        if config.set_display_capture_format(display_internal_id, capture_format) {
+            self.update_config(config).await
+        } else {
+            Ok(())
+        }
+    }
+
+    /// 获取系统显示器ID对应的截图像素字节顺序
+    pub async fn get_display_capture_format(&self, display_id: u32) -> CaptureFormat {
+        let Ok(internal_id) = self
+            .display_registry
+            .get_internal_id_by_display_id(display_id)
+            .await
+        else {
+            return CaptureFormat::default();
+        };
+        self.config
+            .read()
+            .await
+            .display_capture_formats
+            .get(&internal_id)
+            .copied()
+            .unwrap_or_default()
+    }
+
+    /// 设置显示器启用的边
+    pub async fn set_display_border_mask(
+        &self,
+        display_internal_id: &str,
+        mask: BorderMask,
+    ) -> Result<()> {
+        let mut config = self.get_config().await;
+
+        if config.set_display_border_mask(display_internal_id, mask) {
+            self.update_config(config).await
+        } else {
+            Ok(())
+        }
+    }
+
+    /// 获取系统显示器ID对应的启用边
+    pub async fn get_display_border_mask(&self, display_id: u32) -> BorderMask {
+        let Ok(internal_id) = self
+            .display_registry
+            .get_internal_id_by_display_id(display_id)
+            .await
+        else {
+            return BorderMask::default();
+        };
+        self.config.read().await.border_mask(&internal_id)
+    }
+
+    /// 将指定序列号的灯带手动绑定到已注册的显示器
+    ///
+    /// 绑定按显示器内部ID保存，显示器断开后绑定保留，重新连接时继续生效
+    pub async fn bind_strips_to_display(
+        &self,
+        display_internal_id: &str,
+        strip_indices: &[usize],
+    ) -> Result<()> {
+        if self
+            .display_registry
+            .find_display_by_internal_id(display_internal_id)
+            .await
+            .is_none()
+        {
+            return Err(anyhow::anyhow!(
+                "Display {} is not registered",
+                display_internal_id
+            ));
+        }
+
+        let mut config = self.get_config().await;
+
+        if config.bind_strips_to_display(display_internal_id, strip_indices)? {
+            self.update_config(config).await
+        } else {
+            Ok(())
+        }
+    }
+
+    /// 获取所有已注册显示器（含当前未连接的）及绑定到各显示器的灯带
+    pub async fn get_display_bindings(&self) -> Vec<DisplayBinding> {
+        let strips = self.config.read().await.strips.clone();
+        let mut bindings = Vec::new();
+
+        for display in self.display_registry.get_all_displays().await {
+            let system_id = self
+                .display_registry
+                .get_display_id_by_internal_id(&display.internal_id)
+                .await
+                .ok();
+            let mut strip_indices: Vec<usize> = strips
+                .iter()
+                .filter(|strip| strip.display_internal_id == display.internal_id)
+                .map(|strip| strip.index)
+                .collect();
+            strip_indices.sort_unstable();
+
+            bindings.push(DisplayBinding {
+                internal_id: display.internal_id,
+                name: display.name,
+                manufacturer: display.manufacturer,
+                model: display.model,
+                connected: system_id.is_some(),
+                system_id,
+                strip_indices,
+            });
+        }
+
+        bindings
+    }
+
     /// 获取指定显示器的LED灯带
     pub async fn get_strips_for_display(&self, display_internal_id: &str) -> Vec<LedStripConfigV2> {
         let config = self.config.read().await;
@@ -361,6 +918,22 @@ impl ConfigManagerV2 {
     }
 }
 
+/// 已注册显示器及其绑定的灯带
+#[derive(Debug, Clone, Serialize)]
+pub struct DisplayBinding {
+    /// 稳定的显示器内部ID
+    pub internal_id: String,
+    pub name: String,
+    pub manufacturer: Option<String>,
+    pub model: Option<String>,
+    /// 显示器当前是否已连接
+    pub connected: bool,
+    /// 当前的系统显示器ID，未连接时为空
+    pub system_id: Option<u32>,
+    /// 绑定到该显示器的灯带序列号
+    pub strip_indices: Vec<usize>,
+}
+
 /// 配置统计信息
 #[derive(Debug, Clone)]
 pub struct ConfigStats {
@@ -383,6 +956,17 @@ impl From<LedStripConfigGroupV2> for crate::ambient_light::LedStripConfigGroup {
                 len: strip.len,
                 led_type: strip.led_type,
                 reversed: strip.reversed,
+                hardware_offset: strip.hardware_offset,
+                zone_reduction: strip.zone_reduction,
+                color_order: strip.color_order,
+                sample_shape: strip.sample_shape,
+                sample_inset: strip.sample_inset,
+                start_trim_percent: strip.start_trim_percent,
+                end_trim_percent: strip.end_trim_percent,
+                enabled: strip.enabled,
+                chain_position: strip.chain_position,
+                source_index: None,
+                source_display_id: None,
             })
             .collect();
 
@@ -396,3 +980,50 @@ impl From<LedStripConfigGroupV2> for crate::ambient_light::LedStripConfigGroup {
         config
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[tokio::test]
+    async fn test_update_debouncer_only_last_update_settles() {
+        let debouncer = Arc::new(UpdateDebouncer::default());
+        let interval = Duration::from_millis(50);
+
+        let mut handles = Vec::new();
+        for _ in 0..3 {
+            let debouncer = debouncer.clone();
+            handles.push(tokio::spawn(
+                async move { debouncer.settle(interval).await },
+            ));
+            tokio::time::sleep(Duration::from_millis(5)).await;
+        }
+
+        let mut settled = Vec::new();
+        for handle in handles {
+            settled.push(handle.await.unwrap());
+        }
+        assert_eq!(settled, vec![false, false, true]);
+
+        // 窗口结束后的更新会再次触发通知
+        assert!(debouncer.settle(interval).await);
+        // 间隔为0时立即通知
+        assert!(debouncer.settle(Duration::ZERO).await);
+    }
+
+    #[tokio::test]
+    async fn test_update_debouncer_cancel_pending() {
+        let debouncer = Arc::new(UpdateDebouncer::default());
+        let interval = Duration::from_millis(50);
+
+        let pending = {
+            let debouncer = debouncer.clone();
+            tokio::spawn(async move { debouncer.settle(interval).await })
+        };
+        tokio::time::sleep(Duration::from_millis(5)).await;
+
+        // 立即发送通知后，等待中的防抖通知不再重复发送
+        debouncer.cancel_pending();
+        assert!(!pending.await.unwrap());
+    }
+}
@@ -1,15 +1,82 @@
 use dirs::config_dir;
 use serde::{Deserialize, Serialize};
+use std::collections::HashMap;
 use std::env::current_dir;
-use std::path::PathBuf;
+use std::path::{Path, PathBuf};
 use std::time::SystemTime;
 
 use crate::display::DisplayConfigGroup;
+use crate::screenshot::SampleCrop;
 
-use super::{Border, ColorCalibration, LedType, SamplePointMapper};
+use super::{
+    Border, BorderMask, CaptureFormat, ColorCalibration, ColorOrder, DisplayWinding, LedType,
+    SamplePointMapper, SampleShape, ZoneReduction,
+};
 
 const CONFIG_FILE_NAME_V2: &str = "cc.ivanli.ambient_light/config_v2.toml";
 
+/// 写入配置文件时附加的说明注释，方便手动编辑
+const CONFIG_FILE_HEADER: &str = "\
+# Ambient Light 灯带与颜色校准配置 (config_v2)
+#
+# 此文件可以手动编辑，应用运行时会自动检测修改并热重载。
+# 修改会先经过校验，校验失败时保留当前配置并在日志中给出原因。
+# 应用保存配置时只更新变化的值，自行添加的注释会保留。
+#
+# [color_calibration]  颜色校准，r/g/b/w 为各通道增益 (0.0-1.0)
+# [[strips]]           LED灯带：index 序列号、border 所在边缘、len LED数量、
+#                      display_internal_id 所属显示器、reversed 是否反向
+# [display_config]     显示器注册信息，由应用自动维护，一般无需修改
+# led_positions        可选，每个LED在边缘上的位置 (0.0-1.0)，数量须与 len 一致
+# display_crops        显示器采样区域裁剪 (x/y/width/height 均为 0.0-1.0)
+# display_scale_factors 显示器缩放比例覆盖 (0.5-4.0)
+# display_windings    显示器灯带走线方向 (Clockwise / CounterClockwise)
+# display_capture_formats 显示器截图像素字节顺序 (Bgra / Rgba)，红蓝颜色互换时修改
+# display_frame_rates  显示器采集帧率覆盖 (1-240 Hz)，未设置时按显示器刷新率采集
+# display_border_masks 显示器启用的边 (top/bottom/left/right)，禁用边上的灯带不采样也不占用串联位置
+# [[strip_spans]]      跨屏灯带：strip_index 灯带序列号、display_internal_ids 跨越的并排显示器
+# [calibration_presets.<名称>] 命名的颜色校准预设，字段与 [color_calibration] 相同
+
+";
+
+/// 缩放比例覆盖允许的最小值
+pub const MIN_SCALE_FACTOR_OVERRIDE: f32 = 0.5;
+/// 缩放比例覆盖允许的最大值
+pub const MAX_SCALE_FACTOR_OVERRIDE: f32 = 4.0;
+/// 显示器采集帧率允许的最小值（Hz）
+pub const MIN_DISPLAY_FRAME_RATE: f32 = 1.0;
+/// 显示器采集帧率允许的最大值（Hz）
+pub const MAX_DISPLAY_FRAME_RATE: f32 = 240.0;
+/// 颜色校准预设名称的最大长度（字符数）
+pub const MAX_CALIBRATION_PRESET_NAME_LEN: usize = 64;
+
+/// 跨屏灯带：一条上/下边灯带连续经过多个并排的显示器，沿它们合并后的边缘采样
+#[derive(Clone, Serialize, Deserialize, Debug, PartialEq, utoipa::ToSchema)]
+pub struct StripSpan {
+    /// 灯带序列号
+    pub strip_index: usize,
+    /// 灯带经过的显示器内部ID（至少两个，顺序无关，按显示器的水平位置排列）
+    pub display_internal_ids: Vec<String>,
+}
+
+impl StripSpan {
+    /// 校验至少跨越两个不同的显示器
+    ///
+    /// 灯带被删除或移到左/右边后跨屏设置保留但不生效，因此这里不检查灯带本身
+    pub fn validate(&self) -> anyhow::Result<()> {
+        let mut unique_ids = self.display_internal_ids.clone();
+        unique_ids.sort();
+        unique_ids.dedup();
+        if unique_ids.len() != self.display_internal_ids.len() || unique_ids.len() < 2 {
+            return Err(anyhow::anyhow!(
+                "Strip {}: a span needs at least two distinct displays",
+                self.strip_index
+            ));
+        }
+        Ok(())
+    }
+}
+
 /// 新版本的LED灯带配置，使用稳定的显示器内部ID
 #[derive(Clone, Serialize, Deserialize, Debug)]
 pub struct LedStripConfigV2 {
@@ -22,20 +89,56 @@ pub struct LedStripConfigV2 {
     pub led_type: LedType,
     #[serde(default)]
     pub reversed: bool,
+    /// 硬件起始偏移量（以该灯带的LED为单位），为空时紧接上一条灯带
+    #[serde(default)]
+    pub hardware_offset: Option<usize>,
+    /// 每个LED沿边缘的归一化位置（0-1，按采样方向：上/下边从左到右，左/右边从上到下），
+    /// 用于LED间距不均匀的灯带；为空时均匀分布
+    #[serde(default)]
+    pub led_positions: Option<Vec<f32>>,
+    /// 多个采样区域合并为LED颜色的方式
+    #[serde(default)]
+    pub zone_reduction: ZoneReduction,
+    /// 硬件数据的颜色字节顺序
+    #[serde(default)]
+    pub color_order: ColorOrder,
+    /// 采样点的排列形状
+    #[serde(default)]
+    pub sample_shape: SampleShape,
+    /// 采样区域距物理屏幕边缘的像素数（与采样深度无关），用于避开贴边的菜单栏、程序坞
+    #[serde(default)]
+    pub sample_inset: usize,
+    /// 边缘起始端（上/下边为左端，左/右边为上端）不被灯带覆盖的百分比，用于没有铺满整条边的灯带
+    #[serde(default)]
+    pub start_trim_percent: f32,
+    /// 边缘末端（上/下边为右端，左/右边为下端）不被灯带覆盖的百分比
+    #[serde(default)]
+    pub end_trim_percent: f32,
+    /// 是否启用；禁用的灯带输出黑色（保留串联偏移），且不发布预览
+    #[serde(default = "super::config::default_strip_enabled")]
+    pub enabled: bool,
+    /// 物理串联顺序，为空时与序列号相同；序列号只决定界面中的排列顺序
+    #[serde(default)]
+    pub chain_position: Option<usize>,
 }
 
 impl LedStripConfigV2 {
-    /// 计算该灯带的起始位置（基于所有灯带的序列号和长度）
+    /// 串联顺序的排序键：串联位置相同时按序列号排列
+    pub fn chain_order(&self) -> (usize, usize) {
+        (self.chain_position.unwrap_or(self.index), self.index)
+    }
+
+    /// 计算该灯带的起始位置（基于所有灯带的串联顺序和长度）
     pub fn calculate_start_pos(&self, all_strips: &[LedStripConfigV2]) -> usize {
         let mut start_pos = 0;
 
-        // 按序列号排序所有灯带
+        // 按串联顺序排序所有灯带
         let mut sorted_strips: Vec<_> = all_strips.iter().collect();
-        sorted_strips.sort_by_key(|strip| strip.index);
+        sorted_strips.sort_by_key(|strip| strip.chain_order());
 
         // 计算当前灯带之前的所有LED数量
         for strip in sorted_strips {
-            if strip.index < self.index {
+            if strip.chain_order() < self.chain_order() {
                 start_pos += strip.len;
             } else {
                 break;
@@ -53,7 +156,41 @@ impl LedStripConfigV2 {
             len: 0, // Default to 0 length
             led_type: LedType::WS2812B,
             reversed: false,
+            hardware_offset: None,
+            led_positions: None,
+            zone_reduction: ZoneReduction::Mean,
+            color_order: ColorOrder::default(),
+            sample_shape: SampleShape::Grid,
+            sample_inset: 0,
+            start_trim_percent: 0.0,
+            end_trim_percent: 0.0,
+            enabled: true,
+            chain_position: None,
+        }
+    }
+
+    /// 校验LED位置：数量必须等于灯带长度，且每个位置都在 0 到 1 之间
+    pub fn validate_led_positions(&self) -> anyhow::Result<()> {
+        let Some(positions) = &self.led_positions else {
+            return Ok(());
+        };
+
+        if positions.len() != self.len {
+            return Err(anyhow::anyhow!(
+                "Strip {} has {} LED positions but {} LEDs",
+                self.index,
+                positions.len(),
+                self.len
+            ));
+        }
+        if let Some(position) = positions.iter().find(|p| !(0.0..=1.0).contains(*p)) {
+            return Err(anyhow::anyhow!(
+                "Strip {} has LED position {} outside 0-1",
+                self.index,
+                position
+            ));
         }
+        Ok(())
     }
 }
 
@@ -71,6 +208,36 @@ pub struct LedStripConfigGroupV2 {
     pub mappers: Vec<SamplePointMapper>,
     /// 颜色校准配置
     pub color_calibration: ColorCalibration,
+    /// 镜像来源：目标显示器内部ID -> 来源显示器内部ID
+    /// 目标显示器的灯带将使用来源显示器的画面采样颜色
+    #[serde(default)]
+    pub mirror_sources: HashMap<String, String>,
+    /// 采样区域裁剪：显示器内部ID -> 归一化裁剪区域
+    /// 设置后该显示器的边缘采样以裁剪区域的边缘为准
+    #[serde(default)]
+    pub display_crops: HashMap<String, SampleCrop>,
+    /// 缩放比例覆盖：显示器内部ID -> 手动设置的缩放比例
+    /// 用于自动检测的缩放比例与实际帧缓冲区不符的显示器
+    #[serde(default)]
+    pub display_scale_factors: HashMap<String, f32>,
+    /// 灯带走线方向：显示器内部ID -> 方向，未设置时为顺时针
+    #[serde(default)]
+    pub display_windings: HashMap<String, DisplayWinding>,
+    /// 截图像素字节顺序：显示器内部ID -> 格式，未设置时为 BGRA
+    #[serde(default)]
+    pub display_capture_formats: HashMap<String, CaptureFormat>,
+    /// 采集帧率覆盖：显示器内部ID -> 帧率（Hz），未设置时按显示器刷新率采集
+    #[serde(default)]
+    pub display_frame_rates: HashMap<String, f32>,
+    /// 启用的边：显示器内部ID -> 启用的边，未设置时四边都启用
+    #[serde(default)]
+    pub display_border_masks: HashMap<String, BorderMask>,
+    /// 跨屏灯带，未列出的灯带只在所属显示器上采样
+    #[serde(default)]
+    pub strip_spans: Vec<StripSpan>,
+    /// 命名的颜色校准预设：名称 -> 校准，应用时替换当前的颜色校准
+    #[serde(default)]
+    pub calibration_presets: HashMap<String, ColorCalibration>,
     /// 配置创建时间
     pub created_at: SystemTime,
     /// 最后更新时间
@@ -87,6 +254,15 @@ impl LedStripConfigGroupV2 {
             strips: Vec::new(),
             mappers: Vec::new(),
             color_calibration: ColorCalibration::new(),
+            mirror_sources: HashMap::new(),
+            display_crops: HashMap::new(),
+            display_scale_factors: HashMap::new(),
+            display_windings: HashMap::new(),
+            display_capture_formats: HashMap::new(),
+            display_frame_rates: HashMap::new(),
+            display_border_masks: HashMap::new(),
+            strip_spans: Vec::new(),
+            calibration_presets: HashMap::new(),
             created_at: now,
             updated_at: now,
         }
@@ -133,10 +309,352 @@ impl LedStripConfigGroupV2 {
         }
     }
 
+    /// 设置显示器的镜像来源，返回配置是否发生变化
+    ///
+    /// 不允许镜像自身，也不允许链式镜像（来源本身是镜像目标，或目标已是其他显示器的来源）。
+    pub fn set_mirror_source(
+        &mut self,
+        target_internal_id: &str,
+        source_internal_id: Option<String>,
+    ) -> anyhow::Result<bool> {
+        let Some(source) = source_internal_id else {
+            return Ok(self.mirror_sources.remove(target_internal_id).is_some());
+        };
+
+        if source == target_internal_id {
+            return Err(anyhow::anyhow!("显示器不能镜像自身"));
+        }
+        if self.mirror_sources.contains_key(&source) {
+            return Err(anyhow::anyhow!(
+                "来源显示器 {} 本身正在镜像其他显示器",
+                source
+            ));
+        }
+        if self
+            .mirror_sources
+            .values()
+            .any(|s| s == target_internal_id)
+        {
+            return Err(anyhow::anyhow!(
+                "显示器 {} 是其他显示器的镜像来源，不能再镜像其他显示器",
+                target_internal_id
+            ));
+        }
+
+        let previous = self
+            .mirror_sources
+            .insert(target_internal_id.to_string(), source.clone());
+        Ok(previous.as_ref() != Some(&source))
+    }
+
+    /// 设置显示器的采样区域裁剪，`crop` 为 None 时恢复整幅画面采样，返回配置是否发生变化
+    pub fn set_display_crop(
+        &mut self,
+        display_internal_id: &str,
+        crop: Option<SampleCrop>,
+    ) -> anyhow::Result<bool> {
+        let Some(crop) = crop else {
+            return Ok(self.display_crops.remove(display_internal_id).is_some());
+        };
+
+        crop.validate()?;
+        let previous = self
+            .display_crops
+            .insert(display_internal_id.to_string(), crop);
+        Ok(previous != Some(crop))
+    }
+
+    /// 设置显示器的缩放比例覆盖，`scale_factor` 为 None 时恢复自动检测，返回配置是否发生变化
+    pub fn set_display_scale_factor(
+        &mut self,
+        display_internal_id: &str,
+        scale_factor: Option<f32>,
+    ) -> anyhow::Result<bool> {
+        let Some(scale_factor) = scale_factor else {
+            return Ok(self
+                .display_scale_factors
+                .remove(display_internal_id)
+                .is_some());
+        };
+
+        if !scale_factor.is_finite()
+            || !(MIN_SCALE_FACTOR_OVERRIDE..=MAX_SCALE_FACTOR_OVERRIDE).contains(&scale_factor)
+        {
+            return Err(anyhow::anyhow!(
+                "Scale factor must be between {MIN_SCALE_FACTOR_OVERRIDE} and {MAX_SCALE_FACTOR_OVERRIDE}, got {scale_factor}"
+            ));
+        }
+        let previous = self
+            .display_scale_factors
+            .insert(display_internal_id.to_string(), scale_factor);
+        Ok(previous != Some(scale_factor))
+    }
+
+    /// 设置显示器的采集帧率覆盖，`frame_rate` 为 None 时恢复按刷新率采集，返回配置是否发生变化
+    pub fn set_display_frame_rate(
+        &mut self,
+        display_internal_id: &str,
+        frame_rate: Option<f32>,
+    ) -> anyhow::Result<bool> {
+        let Some(frame_rate) = frame_rate else {
+            return Ok(self
+                .display_frame_rates
+                .remove(display_internal_id)
+                .is_some());
+        };
+
+        validate_display_frame_rate(frame_rate)?;
+        let previous = self
+            .display_frame_rates
+            .insert(display_internal_id.to_string(), frame_rate);
+        Ok(previous != Some(frame_rate))
+    }
+
+    /// 将指定序列号的灯带绑定到显示器，返回配置是否发生变化
+    ///
+    /// 任一序列号不存在时不做任何修改并返回错误
+    pub fn bind_strips_to_display(
+        &mut self,
+        display_internal_id: &str,
+        strip_indices: &[usize],
+    ) -> anyhow::Result<bool> {
+        if let Some(missing) = strip_indices
+            .iter()
+            .find(|index| !self.strips.iter().any(|strip| strip.index == **index))
+        {
+            return Err(anyhow::anyhow!("Strip {} not found", missing));
+        }
+
+        let mut changed = false;
+        for strip in self
+            .strips
+            .iter_mut()
+            .filter(|strip| strip_indices.contains(&strip.index))
+        {
+            if strip.display_internal_id != display_internal_id {
+                strip.display_internal_id = display_internal_id.to_string();
+                changed = true;
+            }
+        }
+        Ok(changed)
+    }
+
+    /// 设置显示器的灯带走线方向，顺时针为默认值不单独保存，返回配置是否发生变化
+    pub fn set_display_winding(
+        &mut self,
+        display_internal_id: &str,
+        winding: DisplayWinding,
+    ) -> bool {
+        let previous = if winding == DisplayWinding::default() {
+            self.display_windings.remove(display_internal_id)
+        } else {
+            self.display_windings
+                .insert(display_internal_id.to_string(), winding)
+        };
+        previous.unwrap_or_default() != winding
+    }
+
+    /// 设置显示器截图的像素字节顺序，BGRA 为默认值不单独保存，返回配置是否发生变化
+    pub fn set_display_capture_format(
+        &mut self,
+        display_internal_id: &str,
+        capture_format: CaptureFormat,
+    ) -> bool {
+        let previous = if capture_format == CaptureFormat::default() {
+            self.display_capture_formats.remove(display_internal_id)
+        } else {
+            self.display_capture_formats
+                .insert(display_internal_id.to_string(), capture_format)
+        };
+        previous.unwrap_or_default() != capture_format
+    }
+
+    /// 设置显示器启用的边，四边都启用为默认值不单独保存，返回配置是否发生变化
+    pub fn set_display_border_mask(&mut self, display_internal_id: &str, mask: BorderMask) -> bool {
+        let previous = if mask == BorderMask::default() {
+            self.display_border_masks.remove(display_internal_id)
+        } else {
+            self.display_border_masks
+                .insert(display_internal_id.to_string(), mask)
+        };
+        previous.unwrap_or_default() != mask
+    }
+
+    /// 获取显示器启用的边
+    pub fn border_mask(&self, display_internal_id: &str) -> BorderMask {
+        self.display_border_masks
+            .get(display_internal_id)
+            .copied()
+            .unwrap_or_default()
+    }
+
+    /// 获取位于所属显示器启用边上的灯带
+    pub fn active_strips(&self) -> Vec<LedStripConfigV2> {
+        self.strips
+            .iter()
+            .filter(|strip| {
+                self.border_mask(&strip.display_internal_id)
+                    .contains(strip.border)
+            })
+            .cloned()
+            .collect()
+    }
+
+    /// 设置灯带跨越的显示器，`display_internal_ids` 为 None 时恢复只在所属显示器上采样，
+    /// 返回配置是否发生变化
+    pub fn set_strip_span(
+        &mut self,
+        strip_index: usize,
+        display_internal_ids: Option<Vec<String>>,
+    ) -> anyhow::Result<bool> {
+        let Some(display_internal_ids) = display_internal_ids else {
+            let previous_len = self.strip_spans.len();
+            self.strip_spans
+                .retain(|span| span.strip_index != strip_index);
+            return Ok(self.strip_spans.len() != previous_len);
+        };
+
+        let span = StripSpan {
+            strip_index,
+            display_internal_ids,
+        };
+        span.validate()?;
+
+        let strip = self
+            .strips
+            .iter()
+            .find(|strip| strip.index == strip_index)
+            .ok_or_else(|| anyhow::anyhow!("Strip {} not found", strip_index))?;
+        if !matches!(strip.border, Border::Top | Border::Bottom) {
+            return Err(anyhow::anyhow!(
+                "Strip {}: only top and bottom strips can span displays, got {:?}",
+                strip_index,
+                strip.border
+            ));
+        }
+        if !span
+            .display_internal_ids
+            .contains(&strip.display_internal_id)
+        {
+            return Err(anyhow::anyhow!(
+                "Strip {}: span must include the strip's own display {}",
+                strip_index,
+                strip.display_internal_id
+            ));
+        }
+
+        match self
+            .strip_spans
+            .iter_mut()
+            .find(|existing| existing.strip_index == strip_index)
+        {
+            Some(existing) if *existing == span => Ok(false),
+            Some(existing) => {
+                *existing = span;
+                Ok(true)
+            }
+            None => {
+                self.strip_spans.push(span);
+                Ok(true)
+            }
+        }
+    }
+
+    /// 将当前的颜色校准保存为命名预设，同名预设被覆盖，返回配置是否发生变化
+    pub fn save_calibration_preset(&mut self, name: &str) -> anyhow::Result<bool> {
+        validate_calibration_preset_name(name)?;
+        let previous = self
+            .calibration_presets
+            .insert(name.to_string(), self.color_calibration);
+        Ok(previous != Some(self.color_calibration))
+    }
+
+    /// 删除命名的颜色校准预设，返回预设是否存在
+    pub fn delete_calibration_preset(&mut self, name: &str) -> bool {
+        self.calibration_presets.remove(name).is_some()
+    }
+
+    /// 校验整个配置：LED位置、采样区域裁剪、缩放比例覆盖、跨屏灯带、颜色校准和校准预设
+    pub fn validate(&self) -> anyhow::Result<()> {
+        for strip in &self.strips {
+            strip.validate_led_positions()?;
+            super::validate_edge_trim(strip.start_trim_percent, strip.end_trim_percent)
+                .map_err(|e| anyhow::anyhow!("Strip {}: {}", strip.index, e))?;
+        }
+        for (display_internal_id, crop) in &self.display_crops {
+            crop.validate()
+                .map_err(|e| anyhow::anyhow!("Display {}: {}", display_internal_id, e))?;
+        }
+        for (display_internal_id, scale_factor) in &self.display_scale_factors {
+            if !scale_factor.is_finite()
+                || !(MIN_SCALE_FACTOR_OVERRIDE..=MAX_SCALE_FACTOR_OVERRIDE).contains(scale_factor)
+            {
+                return Err(anyhow::anyhow!(
+                    "Display {display_internal_id}: scale factor must be between {MIN_SCALE_FACTOR_OVERRIDE} and {MAX_SCALE_FACTOR_OVERRIDE}, got {scale_factor}"
+                ));
+            }
+        }
+        for (display_internal_id, frame_rate) in &self.display_frame_rates {
+            validate_display_frame_rate(*frame_rate)
+                .map_err(|e| anyhow::anyhow!("Display {}: {}", display_internal_id, e))?;
+        }
+        for span in &self.strip_spans {
+            span.validate()?;
+        }
+        self.color_calibration.validate()?;
+        for (name, calibration) in &self.calibration_presets {
+            validate_calibration_preset_name(name)?;
+            calibration
+                .validate()
+                .map_err(|e| anyhow::anyhow!("Calibration preset {}: {}", name, e))?;
+        }
+        Ok(())
+    }
+
+    /// 生成写入配置文件的内容（带说明注释的 TOML）
+    pub fn to_config_file_string(&self) -> anyhow::Result<String> {
+        Ok(format!(
+            "{}{}",
+            CONFIG_FILE_HEADER,
+            toml::to_string_pretty(self)?
+        ))
+    }
+
+    /// 将当前配置合并进已有的配置文件内容，保留用户添加的注释和格式；
+    /// 已有内容无法解析时使用全新生成的内容
+    pub fn merge_into_config_file(&self, existing: &str) -> anyhow::Result<String> {
+        let content = self.to_config_file_string()?;
+        let Ok(mut document) = existing.parse::<toml_edit::Document>() else {
+            return Ok(content);
+        };
+        let mut fresh = content.parse::<toml_edit::Document>()?;
+        merge_toml_table(
+            document.as_table_mut(),
+            std::mem::take(fresh.as_table_mut()),
+        );
+        Ok(document.to_string())
+    }
+
+    /// 生成要写入配置文件的内容，配置文件已存在时保留其中的注释
+    pub async fn to_config_file_content(&self) -> anyhow::Result<String> {
+        match tokio::fs::read_to_string(Self::get_config_path()).await {
+            Ok(existing) => self.merge_into_config_file(&existing),
+            Err(_) => self.to_config_file_string(),
+        }
+    }
+
+    /// 解析并校验配置文件内容，用于热重载外部修改
+    pub fn parse_config_file(content: &str) -> anyhow::Result<Self> {
+        let mut config: Self = toml::from_str(content)?;
+        config.validate()?;
+        config.generate_mappers();
+        Ok(config)
+    }
+
     /// 获取配置文件路径
     /// 优先使用环境变量 AMBIENT_LIGHT_CONFIG_PATH 指定的路径
     /// 如果未设置环境变量，则使用默认的全局配置路径
-    fn get_config_path() -> PathBuf {
+    pub fn get_config_path() -> PathBuf {
         if let Ok(custom_path) = std::env::var("AMBIENT_LIGHT_CONFIG_PATH") {
             PathBuf::from(custom_path)
         } else {
@@ -195,6 +713,12 @@ impl LedStripConfigGroupV2 {
 
     /// 写入配置文件
     pub async fn write_config(&self) -> anyhow::Result<()> {
+        let content = self.to_config_file_content().await?;
+        self.write_config_content(content).await
+    }
+
+    /// 将已生成的内容写入配置文件
+    pub async fn write_config_content(&self, content: String) -> anyhow::Result<()> {
         let config_path = Self::get_config_path();
 
         log::info!(
@@ -214,8 +738,6 @@ impl LedStripConfigGroupV2 {
             tokio::fs::create_dir_all(parent).await?;
         }
 
-        let content = toml::to_string_pretty(self)?;
-
         match tokio::fs::write(&config_path, content).await {
             Ok(_) => {
                 log::info!(
@@ -298,3 +820,389 @@ impl Default for LedStripConfigGroupV2 {
         Self::new()
     }
 }
+
+/// 校验颜色校准预设名称：去掉首尾空白后非空，且不超过 `MAX_CALIBRATION_PRESET_NAME_LEN` 个字符
+pub fn validate_calibration_preset_name(name: &str) -> anyhow::Result<()> {
+    if name.trim().is_empty() || name.trim() != name {
+        anyhow::bail!("calibration preset name must be non-empty without surrounding whitespace, got {name:?}");
+    }
+    if name.chars().count() > MAX_CALIBRATION_PRESET_NAME_LEN {
+        anyhow::bail!(
+            "calibration preset name must be at most {MAX_CALIBRATION_PRESET_NAME_LEN} characters"
+        );
+    }
+    Ok(())
+}
+
+/// 校验显示器采集帧率覆盖，须在 `MIN_DISPLAY_FRAME_RATE` 到 `MAX_DISPLAY_FRAME_RATE` Hz 之间
+pub fn validate_display_frame_rate(frame_rate: f32) -> anyhow::Result<()> {
+    if !frame_rate.is_finite()
+        || !(MIN_DISPLAY_FRAME_RATE..=MAX_DISPLAY_FRAME_RATE).contains(&frame_rate)
+    {
+        anyhow::bail!(
+            "frame rate must be between {MIN_DISPLAY_FRAME_RATE} and {MAX_DISPLAY_FRAME_RATE} Hz, got {frame_rate}"
+        );
+    }
+    Ok(())
+}
+
+/// 生成带时间戳的备份文件路径，与原文件位于同一目录，例如 `config_v2.backup-20240102-030405.toml`
+pub fn timestamped_backup_path(path: &Path, timestamp: chrono::DateTime<chrono::Local>) -> PathBuf {
+    let stem = path
+        .file_stem()
+        .map(|stem| stem.to_string_lossy().into_owned())
+        .unwrap_or_default();
+    let mut file_name = format!("{stem}.backup-{}", timestamp.format("%Y%m%d-%H%M%S"));
+    if let Some(extension) = path.extension() {
+        file_name.push('.');
+        file_name.push_str(&extension.to_string_lossy());
+    }
+    path.with_file_name(file_name)
+}
+
+/// 将配置文件复制为带时间戳的备份，文件不存在时不备份并返回 None
+pub async fn backup_config_file(path: &Path) -> anyhow::Result<Option<PathBuf>> {
+    if !path.exists() {
+        return Ok(None);
+    }
+
+    let backup_path = timestamped_backup_path(path, chrono::Local::now());
+    tokio::fs::copy(path, &backup_path).await?;
+    log::info!(
+        "🗄️ Backed up {} to {}",
+        path.display(),
+        backup_path.display()
+    );
+    Ok(Some(backup_path))
+}
+
+/// 将新生成的 TOML 表合并进已有的表：更新值时保留原有注释，删除已不存在的键
+fn merge_toml_table(existing: &mut toml_edit::Table, new: toml_edit::Table) {
+    let stale_keys: Vec<String> = existing
+        .iter()
+        .map(|(key, _)| key.to_string())
+        .filter(|key| !new.contains_key(key))
+        .collect();
+    for key in stale_keys {
+        existing.remove(&key);
+    }
+
+    for (key, item) in new {
+        match existing.get_mut(&key) {
+            Some(current) => merge_toml_item(current, item),
+            None => {
+                existing.insert(&key, item);
+            }
+        }
+    }
+}
+
+fn merge_toml_item(existing: &mut toml_edit::Item, new: toml_edit::Item) {
+    use toml_edit::Item;
+
+    match (existing, new) {
+        (Item::Table(current), Item::Table(new)) => merge_toml_table(current, new),
+        (Item::ArrayOfTables(current), Item::ArrayOfTables(new)) => {
+            let new_len = new.len();
+            for (index, table) in new.into_iter().enumerate() {
+                match current.get_mut(index) {
+                    Some(current_table) => merge_toml_table(current_table, table),
+                    None => current.push(table),
+                }
+            }
+            while current.len() > new_len {
+                current.remove(current.len() - 1);
+            }
+        }
+        (Item::Value(current), Item::Value(mut new)) => {
+            *new.decor_mut() = current.decor().clone();
+            *current = new;
+        }
+        (current, new) => *current = new,
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use chrono::TimeZone;
+
+    #[test]
+    fn test_set_mirror_source() {
+        let mut config = LedStripConfigGroupV2::new();
+
+        assert!(config
+            .set_mirror_source("secondary", Some("primary".to_string()))
+            .unwrap());
+        assert_eq!(config.mirror_sources["secondary"], "primary");
+
+        // 重复设置相同来源不视为变化
+        assert!(!config
+            .set_mirror_source("secondary", Some("primary".to_string()))
+            .unwrap());
+
+        // 禁止镜像自身和链式镜像
+        assert!(config
+            .set_mirror_source("primary", Some("primary".to_string()))
+            .is_err());
+        assert!(config
+            .set_mirror_source("third", Some("secondary".to_string()))
+            .is_err());
+        assert!(config
+            .set_mirror_source("primary", Some("third".to_string()))
+            .is_err());
+
+        assert!(config.set_mirror_source("secondary", None).unwrap());
+        assert!(!config.set_mirror_source("secondary", None).unwrap());
+        assert!(config.mirror_sources.is_empty());
+    }
+
+    #[test]
+    fn test_set_display_crop() {
+        let mut config = LedStripConfigGroupV2::new();
+        let crop = SampleCrop {
+            x: 0.1,
+            y: 0.05,
+            width: 0.8,
+            height: 0.9,
+        };
+
+        assert!(config.set_display_crop("projector", Some(crop)).unwrap());
+        assert!(!config.set_display_crop("projector", Some(crop)).unwrap());
+        assert_eq!(config.display_crops["projector"], crop);
+
+        // 超出画面的裁剪区域被拒绝，且不影响已有配置
+        let invalid = SampleCrop { x: 0.5, ..crop };
+        assert!(config.set_display_crop("projector", Some(invalid)).is_err());
+        assert_eq!(config.display_crops["projector"], crop);
+
+        assert!(config.set_display_crop("projector", None).unwrap());
+        assert!(!config.set_display_crop("projector", None).unwrap());
+    }
+
+    #[test]
+    fn test_set_display_frame_rate() {
+        let mut config = LedStripConfigGroupV2::new();
+
+        assert!(config
+            .set_display_frame_rate("secondary", Some(60.0))
+            .unwrap());
+        assert!(!config
+            .set_display_frame_rate("secondary", Some(60.0))
+            .unwrap());
+        assert_eq!(config.display_frame_rates["secondary"], 60.0);
+        assert!(config.validate().is_ok());
+
+        assert!(config
+            .set_display_frame_rate("secondary", Some(0.0))
+            .is_err());
+        assert!(config
+            .set_display_frame_rate("secondary", Some(500.0))
+            .is_err());
+        assert!(config
+            .set_display_frame_rate("secondary", Some(f32::NAN))
+            .is_err());
+        assert_eq!(config.display_frame_rates["secondary"], 60.0);
+
+        assert!(config.set_display_frame_rate("secondary", None).unwrap());
+        assert!(!config.set_display_frame_rate("secondary", None).unwrap());
+    }
+
+    #[test]
+    fn test_set_display_scale_factor() {
+        let mut config = LedStripConfigGroupV2::new();
+
+        assert!(config
+            .set_display_scale_factor("retina", Some(2.0))
+            .unwrap());
+        assert!(!config
+            .set_display_scale_factor("retina", Some(2.0))
+            .unwrap());
+        assert_eq!(config.display_scale_factors["retina"], 2.0);
+
+        assert!(config
+            .set_display_scale_factor("retina", Some(0.0))
+            .is_err());
+        assert!(config
+            .set_display_scale_factor("retina", Some(f32::NAN))
+            .is_err());
+        assert_eq!(config.display_scale_factors["retina"], 2.0);
+
+        assert!(config.set_display_scale_factor("retina", None).unwrap());
+        assert!(!config.set_display_scale_factor("retina", None).unwrap());
+    }
+
+    #[test]
+    fn test_bind_strips_to_display() {
+        let mut config = LedStripConfigGroupV2::new();
+        for index in 0..3 {
+            config.strips.push(LedStripConfigV2::default_for_display(
+                "primary".to_string(),
+                index,
+            ));
+        }
+
+        assert!(config.bind_strips_to_display("secondary", &[0, 2]).unwrap());
+        let bound: Vec<&str> = config
+            .strips
+            .iter()
+            .map(|strip| strip.display_internal_id.as_str())
+            .collect();
+        assert_eq!(bound, ["secondary", "primary", "secondary"]);
+
+        // 重复绑定不视为变化
+        assert!(!config.bind_strips_to_display("secondary", &[0, 2]).unwrap());
+
+        // 不存在的灯带导致整个绑定失败
+        assert!(config.bind_strips_to_display("primary", &[0, 7]).is_err());
+        assert_eq!(config.strips[0].display_internal_id, "secondary");
+    }
+
+    #[test]
+    fn test_set_strip_span() {
+        let mut config = LedStripConfigGroupV2::new();
+        for index in 0..2 {
+            config.strips.push(LedStripConfigV2::default_for_display(
+                "left".to_string(),
+                index,
+            ));
+        }
+        config.strips[1].border = Border::Left;
+        let both = || Some(vec!["left".to_string(), "right".to_string()]);
+
+        assert!(config.set_strip_span(0, both()).unwrap());
+        assert!(!config.set_strip_span(0, both()).unwrap());
+
+        // 左/右边灯带、不存在的灯带、只有一个显示器或不含所属显示器的设置被拒绝
+        assert!(config.set_strip_span(1, both()).is_err());
+        assert!(config.set_strip_span(7, both()).is_err());
+        assert!(config
+            .set_strip_span(0, Some(vec!["left".to_string(), "left".to_string()]))
+            .is_err());
+        assert!(config
+            .set_strip_span(0, Some(vec!["right".to_string(), "third".to_string()]))
+            .is_err());
+        assert_eq!(config.strip_spans.len(), 1);
+
+        assert!(config.set_strip_span(0, None).unwrap());
+        assert!(!config.set_strip_span(0, None).unwrap());
+        assert!(config.strip_spans.is_empty());
+    }
+
+    #[test]
+    fn test_config_file_round_trip_and_validation() {
+        let mut config = LedStripConfigGroupV2::new();
+        config.strips.push(LedStripConfigV2::default_for_display(
+            "primary".to_string(),
+            0,
+        ));
+
+        let content = config.to_config_file_string().unwrap();
+        assert!(content.starts_with('#'));
+        let parsed = LedStripConfigGroupV2::parse_config_file(&content).unwrap();
+        assert_eq!(parsed.strips.len(), 1);
+        assert_eq!(parsed.mappers.len(), 1);
+
+        // 手动编辑出的非法值在应用前被拒绝
+        config
+            .display_scale_factors
+            .insert("primary".to_string(), 10.0);
+        let content = config.to_config_file_string().unwrap();
+        assert!(LedStripConfigGroupV2::parse_config_file(&content).is_err());
+
+        assert!(LedStripConfigGroupV2::parse_config_file("strips = 'broken'").is_err());
+    }
+
+    #[test]
+    fn test_merge_into_config_file_keeps_user_comments() {
+        let mut config = LedStripConfigGroupV2::new();
+        config.strips.push(LedStripConfigV2::default_for_display(
+            "primary".to_string(),
+            0,
+        ));
+        config.strips.push(LedStripConfigV2::default_for_display(
+            "primary".to_string(),
+            1,
+        ));
+
+        let content = config.to_config_file_string().unwrap();
+        let edited = content
+            .replacen(
+                "\n[color_calibration]",
+                "\n# 夜间偏暖\n[color_calibration]",
+                1,
+            )
+            .replacen("\nr = ", "\n# 红色通道\nr = ", 1);
+
+        config.color_calibration.r = 0.5;
+        config.strips.pop();
+        let merged = config.merge_into_config_file(&edited).unwrap();
+
+        assert!(merged.contains("# 夜间偏暖\n[color_calibration]"));
+        assert!(merged.contains("# 红色通道\nr = 0.5"));
+        let parsed = LedStripConfigGroupV2::parse_config_file(&merged).unwrap();
+        assert_eq!(parsed.color_calibration.r, 0.5);
+        assert_eq!(parsed.strips.len(), 1);
+
+        // 无法解析的旧内容直接被新内容替换
+        let merged = config.merge_into_config_file("strips = [").unwrap();
+        assert_eq!(merged, config.to_config_file_string().unwrap());
+    }
+
+    #[test]
+    fn test_calibration_preset_round_trip() {
+        let mut config = LedStripConfigGroupV2::new();
+        config.color_calibration.r = 0.8;
+        config.color_calibration.b = 0.6;
+        let evening = config.color_calibration;
+
+        assert!(config.save_calibration_preset("evening").unwrap());
+        assert!(!config.save_calibration_preset("evening").unwrap());
+        assert!(config.save_calibration_preset(" ").is_err());
+        assert!(config.save_calibration_preset(&"x".repeat(65)).is_err());
+
+        // 预设随配置文件保存，修改当前校准不影响已保存的预设
+        config.color_calibration = ColorCalibration::new();
+        let content = config.to_config_file_string().unwrap();
+        let parsed = LedStripConfigGroupV2::parse_config_file(&content).unwrap();
+        assert_eq!(parsed.calibration_presets["evening"], evening);
+        assert_eq!(parsed.color_calibration, ColorCalibration::new());
+
+        assert!(config.delete_calibration_preset("evening"));
+        assert!(!config.delete_calibration_preset("evening"));
+    }
+
+    #[test]
+    fn test_timestamped_backup_path() {
+        let timestamp = chrono::Local
+            .with_ymd_and_hms(2024, 1, 2, 3, 4, 5)
+            .single()
+            .unwrap();
+        assert_eq!(
+            timestamped_backup_path(Path::new("/tmp/ambient/config_v2.toml"), timestamp),
+            PathBuf::from("/tmp/ambient/config_v2.backup-20240102-030405.toml")
+        );
+        assert_eq!(
+            timestamped_backup_path(Path::new("settings"), timestamp),
+            PathBuf::from("settings.backup-20240102-030405")
+        );
+    }
+
+    #[test]
+    fn test_validate_led_positions() {
+        let mut strip = LedStripConfigV2::default_for_display("primary".to_string(), 0);
+        strip.len = 3;
+        assert!(strip.validate_led_positions().is_ok());
+
+        strip.led_positions = Some(vec![0.05, 0.5, 0.95]);
+        assert!(strip.validate_led_positions().is_ok());
+
+        // 数量与灯带长度不一致
+        strip.led_positions = Some(vec![0.1, 0.9]);
+        assert!(strip.validate_led_positions().is_err());
+
+        // 超出 0-1 范围
+        strip.led_positions = Some(vec![0.1, 0.5, 1.5]);
+        assert!(strip.validate_led_positions().is_err());
+    }
+}
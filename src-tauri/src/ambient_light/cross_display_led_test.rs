@@ -3,7 +3,7 @@
 //! 这个测试模块验证跨显示器串联LED灯带的全局位置计算逻辑是否正确。
 //! 测试场景基于实际配置文件中的数据。
 
-use crate::ambient_light::config::{LedStripConfig, LedStripConfigGroup, Border, LedType};
+use crate::ambient_light::config::{LedStripConfig, LedStripConfigGroup, Border, ColorOrder, LedType, SampleShape, ZoneReduction};
 
 #[cfg(test)]
 mod tests {
@@ -21,6 +21,17 @@ mod tests {
                 len: 38,
                 led_type: LedType::SK6812,
                 reversed: false,
+                hardware_offset: None,
+                zone_reduction: ZoneReduction::Mean,
+                color_order: ColorOrder::default(),
+                sample_shape: SampleShape::Grid,
+                sample_inset: 0,
+                start_trim_percent: 0.0,
+                end_trim_percent: 0.0,
+                enabled: true,
+                chain_position: None,
+                source_index: None,
+                source_display_id: None,
             },
             LedStripConfig {
                 index: 1,
@@ -29,6 +40,17 @@ mod tests {
                 len: 22,
                 led_type: LedType::WS2812B,
                 reversed: false,
+                hardware_offset: None,
+                zone_reduction: ZoneReduction::Mean,
+                color_order: ColorOrder::default(),
+                sample_shape: SampleShape::Grid,
+                sample_inset: 0,
+                start_trim_percent: 0.0,
+                end_trim_percent: 0.0,
+                enabled: true,
+                chain_position: None,
+                source_index: None,
+                source_display_id: None,
             },
             LedStripConfig {
                 index: 2,
@@ -37,6 +59,17 @@ mod tests {
                 len: 38,
                 led_type: LedType::WS2812B,
                 reversed: false,
+                hardware_offset: None,
+                zone_reduction: ZoneReduction::Mean,
+                color_order: ColorOrder::default(),
+                sample_shape: SampleShape::Grid,
+                sample_inset: 0,
+                start_trim_percent: 0.0,
+                end_trim_percent: 0.0,
+                enabled: true,
+                chain_position: None,
+                source_index: None,
+                source_display_id: None,
             },
             // 显示器1的灯带 (序列号3，继续串联)
             LedStripConfig {
@@ -46,6 +79,17 @@ mod tests {
                 len: 38,
                 led_type: LedType::SK6812,
                 reversed: false,
+                hardware_offset: None,
+                zone_reduction: ZoneReduction::Mean,
+                color_order: ColorOrder::default(),
+                sample_shape: SampleShape::Grid,
+                sample_inset: 0,
+                start_trim_percent: 0.0,
+                end_trim_percent: 0.0,
+                enabled: true,
+                chain_position: None,
+                source_index: None,
+                source_display_id: None,
             },
         ]
     }
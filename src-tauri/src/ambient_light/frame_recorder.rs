@@ -0,0 +1,506 @@
+use std::{
+    fs::File,
+    io::{BufRead, BufReader, BufWriter, Write},
+    path::{Path, PathBuf},
+    time::{Duration, Instant},
+};
+
+use serde::{Deserialize, Serialize};
+use tokio::{sync::mpsc, task::JoinHandle};
+
+use crate::led_color::LedColor;
+
+use super::{ColorCalibration, DisplayWinding, LedStripConfig};
+
+/// 录制文件目录（相对于系统配置目录）
+const RECORDINGS_DIR_NAME: &str = "cc.ivanli.ambient_light/recordings";
+/// 录制名称的最大长度（字节）
+const MAX_RECORDING_NAME_LEN: usize = 255;
+/// 单个录制文件的最大大小，超过后不再写入新帧
+pub const MAX_RECORDING_BYTES: u64 = 256 * 1024 * 1024;
+/// 单次录制的最长时长，超过后不再写入新帧
+pub const MAX_RECORDING_DURATION: Duration = Duration::from_secs(30 * 60);
+/// 等待写入的帧数上限，写入跟不上时丢弃新帧而不是阻塞采样循环
+const RECORDING_QUEUE_CAPACITY: usize = 256;
+
+/// 校验录制名称：只允许单个文件名，不能包含路径分隔符、`..` 或绝对路径
+pub fn validate_recording_name(name: &str) -> anyhow::Result<()> {
+    let is_bare_file_name = !name.is_empty()
+        && name.len() <= MAX_RECORDING_NAME_LEN
+        && name != "."
+        && name != ".."
+        && !name.contains(['/', '\\'])
+        && !name.chars().any(char::is_control)
+        && Path::new(name).file_name() == Some(std::ffi::OsStr::new(name));
+    if !is_bare_file_name {
+        anyhow::bail!(
+            "Invalid recording name {name:?}: must be a file name without path separators"
+        );
+    }
+    Ok(())
+}
+
+/// 将录制名称解析为配置目录下录制文件目录中的路径
+pub fn recording_path(name: &str) -> anyhow::Result<PathBuf> {
+    let config_dir = dirs::config_dir()
+        .ok_or_else(|| anyhow::anyhow!("Could not determine config directory"))?;
+    resolve_recording_path(&config_dir.join(RECORDINGS_DIR_NAME), name)
+}
+
+/// 将录制名称解析为 `dir` 下的路径，规范化后确认仍位于 `dir` 内（包括已存在的符号链接）
+fn resolve_recording_path(dir: &Path, name: &str) -> anyhow::Result<PathBuf> {
+    validate_recording_name(name)?;
+    std::fs::create_dir_all(dir)?;
+    let dir = dir.canonicalize()?;
+
+    // 已存在的文件或符号链接（包括失效的链接）必须能规范化，避免写入链接指向的目录外文件
+    let path = dir.join(name);
+    let resolved = if path.symlink_metadata().is_ok() {
+        path.canonicalize()?
+    } else {
+        path
+    };
+    if resolved.parent() != Some(dir.as_path()) {
+        anyhow::bail!("Recording {name:?} resolves outside of the recordings directory");
+    }
+    Ok(resolved)
+}
+
+/// 录制文件中单个显示器的灯带布局
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct RecordedDisplay {
+    pub display_id: u32,
+    /// 该显示器的灯带（顺序与展平颜色中的顺序一致）
+    pub strips: Vec<LedStripConfig>,
+    /// 该显示器第一个LED在整条灯带中的偏移量
+    pub start_led_offset: usize,
+    /// 该显示器的灯带走线方向
+    #[serde(default)]
+    pub winding: DisplayWinding,
+}
+
+impl RecordedDisplay {
+    pub fn led_count(&self) -> usize {
+        self.strips.iter().map(|strip| strip.len).sum()
+    }
+}
+
+/// 展平颜色帧的布局信息，作为录制文件的头部
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct FrameLayout {
+    /// 按展平顺序排列的显示器
+    pub displays: Vec<RecordedDisplay>,
+    /// 全部灯带配置，用于计算字节偏移量
+    pub all_strips: Vec<LedStripConfig>,
+    pub color_calibration: ColorCalibration,
+}
+
+impl FrameLayout {
+    /// 将展平的RGB帧按显示器拆分为LED颜色
+    pub fn split_frame(&self, colors: &[u8]) -> Vec<(&RecordedDisplay, Vec<LedColor>)> {
+        let mut offset = 0;
+
+        self.displays
+            .iter()
+            .map(|display| {
+                let end = (offset + display.led_count() * 3).min(colors.len());
+                let start = offset.min(end);
+                offset += display.led_count() * 3;
+
+                let display_colors = colors[start..end]
+                    .chunks_exact(3)
+                    .map(|rgb| LedColor::new(rgb[0], rgb[1], rgb[2]))
+                    .collect();
+                (display, display_colors)
+            })
+            .collect()
+    }
+}
+
+/// 录制的一帧展平RGB颜色数据
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct RecordedFrame {
+    /// 相对于录制开始的时间（毫秒）
+    pub timestamp_ms: u64,
+    /// 十六进制编码的RGB数据
+    pub colors: String,
+}
+
+impl RecordedFrame {
+    pub fn colors(&self) -> anyhow::Result<Vec<u8>> {
+        Ok(hex::decode(&self.colors)?)
+    }
+}
+
+/// 发送给录制写入任务的消息
+enum RecorderMessage {
+    Layout(Box<FrameLayout>),
+    Frame { timestamp_ms: u64, colors: Vec<u8> },
+}
+
+/// 颜色帧录制器，以 JSON Lines 格式写入：首行为布局，其余每行一帧
+///
+/// 文件写入在独立的阻塞任务中进行，`record` 只把帧放入队列，不会阻塞采样循环。
+/// 录制超过大小或时长上限后不再写入新帧。
+pub struct FrameRecorder {
+    path: PathBuf,
+    sender: mpsc::Sender<RecorderMessage>,
+    writer_task: JoinHandle<anyhow::Result<usize>>,
+    started_at: Instant,
+    max_duration: Duration,
+    layout_sent: bool,
+    limit_reached: bool,
+}
+
+impl FrameRecorder {
+    pub async fn create(path: impl AsRef<Path>) -> anyhow::Result<Self> {
+        Self::create_with_limits(path, MAX_RECORDING_BYTES, MAX_RECORDING_DURATION).await
+    }
+
+    async fn create_with_limits(
+        path: impl AsRef<Path>,
+        max_bytes: u64,
+        max_duration: Duration,
+    ) -> anyhow::Result<Self> {
+        let path = path.as_ref().to_path_buf();
+        let file = {
+            let path = path.clone();
+            tokio::task::spawn_blocking(move || -> anyhow::Result<File> {
+                if let Some(parent) = path.parent() {
+                    std::fs::create_dir_all(parent)?;
+                }
+                Ok(File::create(&path)?)
+            })
+            .await??
+        };
+
+        let (sender, receiver) = mpsc::channel(RECORDING_QUEUE_CAPACITY);
+        let writer_task = tokio::task::spawn_blocking(move || {
+            write_recording(BufWriter::new(file), receiver, max_bytes)
+        });
+
+        Ok(Self {
+            path,
+            sender,
+            writer_task,
+            started_at: Instant::now(),
+            max_duration,
+            layout_sent: false,
+            limit_reached: false,
+        })
+    }
+
+    pub fn path(&self) -> &Path {
+        &self.path
+    }
+
+    /// 将一帧颜色数据放入写入队列，首帧前写入布局信息
+    ///
+    /// 队列已满时丢弃该帧并返回错误，不会等待写入完成
+    pub fn record(&mut self, layout: &FrameLayout, colors: &[u8]) -> anyhow::Result<()> {
+        if self.limit_reached {
+            return Ok(());
+        }
+
+        let elapsed = self.started_at.elapsed();
+        if elapsed > self.max_duration {
+            self.stop_at_limit();
+            return Ok(());
+        }
+
+        if !self.layout_sent {
+            self.enqueue(RecorderMessage::Layout(Box::new(layout.clone())))?;
+            self.layout_sent = true;
+        }
+        self.enqueue(RecorderMessage::Frame {
+            timestamp_ms: elapsed.as_millis() as u64,
+            colors: colors.to_vec(),
+        })
+    }
+
+    fn enqueue(&mut self, message: RecorderMessage) -> anyhow::Result<()> {
+        match self.sender.try_send(message) {
+            Ok(()) => Ok(()),
+            Err(mpsc::error::TrySendError::Full(_)) => {
+                anyhow::bail!("Recording queue is full, dropping frame")
+            }
+            // 写入任务在文件达到大小上限后关闭队列
+            Err(mpsc::error::TrySendError::Closed(_)) => {
+                self.stop_at_limit();
+                Ok(())
+            }
+        }
+    }
+
+    fn stop_at_limit(&mut self) {
+        self.limit_reached = true;
+        log::info!(
+            "⏹️ 颜色帧录制已达到大小或时长上限，不再写入新帧: {}",
+            self.path.display()
+        );
+    }
+
+    /// 结束录制，等待队列中的帧写入完成后返回录制的帧数
+    pub async fn finish(self) -> anyhow::Result<usize> {
+        drop(self.sender);
+        self.writer_task.await?
+    }
+}
+
+/// 录制写入任务：依次写入队列中的布局与帧，文件将超过 `max_bytes` 时关闭队列并停止写入
+fn write_recording(
+    mut writer: BufWriter<File>,
+    mut receiver: mpsc::Receiver<RecorderMessage>,
+    max_bytes: u64,
+) -> anyhow::Result<usize> {
+    let mut written_bytes = 0u64;
+    let mut frame_count = 0;
+
+    while let Some(message) = receiver.blocking_recv() {
+        let (mut line, is_frame) = match message {
+            RecorderMessage::Layout(layout) => (serde_json::to_vec(&layout)?, false),
+            RecorderMessage::Frame {
+                timestamp_ms,
+                colors,
+            } => {
+                let frame = RecordedFrame {
+                    timestamp_ms,
+                    colors: hex::encode(colors),
+                };
+                (serde_json::to_vec(&frame)?, true)
+            }
+        };
+        line.push(b'\n');
+
+        if written_bytes + line.len() as u64 > max_bytes {
+            receiver.close();
+            break;
+        }
+        writer.write_all(&line)?;
+        written_bytes += line.len() as u64;
+        if is_frame {
+            frame_count += 1;
+        }
+    }
+
+    writer.flush()?;
+    Ok(frame_count)
+}
+
+/// 读取录制文件
+pub fn read_recording(path: impl AsRef<Path>) -> anyhow::Result<(FrameLayout, Vec<RecordedFrame>)> {
+    let reader = BufReader::new(File::open(path.as_ref())?);
+    let mut lines = reader.lines();
+
+    let layout_line = lines
+        .next()
+        .ok_or_else(|| anyhow::anyhow!("Recording is empty"))??;
+    let layout: FrameLayout = serde_json::from_str(&layout_line)?;
+
+    let mut frames = Vec::new();
+    for line in lines {
+        let line = line?;
+        if line.trim().is_empty() {
+            continue;
+        }
+        frames.push(serde_json::from_str(&line)?);
+    }
+
+    Ok((layout, frames))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::ambient_light::{Border, ColorOrder, LedType, SampleShape, ZoneReduction};
+
+    fn strip(index: usize, display_id: u32, len: usize) -> LedStripConfig {
+        LedStripConfig {
+            index,
+            border: Border::Top,
+            display_id,
+            len,
+            led_type: LedType::WS2812B,
+            reversed: false,
+            hardware_offset: None,
+            zone_reduction: ZoneReduction::Mean,
+            color_order: ColorOrder::default(),
+            sample_shape: SampleShape::Grid,
+            sample_inset: 0,
+            start_trim_percent: 0.0,
+            end_trim_percent: 0.0,
+            enabled: true,
+            chain_position: None,
+            source_index: None,
+            source_display_id: None,
+        }
+    }
+
+    fn test_layout() -> FrameLayout {
+        let strips = vec![strip(0, 1, 2), strip(1, 2, 1)];
+        FrameLayout {
+            displays: vec![
+                RecordedDisplay {
+                    display_id: 1,
+                    strips: vec![strips[0]],
+                    start_led_offset: 0,
+                    winding: DisplayWinding::Clockwise,
+                },
+                RecordedDisplay {
+                    display_id: 2,
+                    strips: vec![strips[1]],
+                    start_led_offset: 2,
+                    winding: DisplayWinding::Clockwise,
+                },
+            ],
+            all_strips: strips,
+            color_calibration: ColorCalibration::new(),
+        }
+    }
+
+    #[tokio::test]
+    async fn test_record_and_read_round_trip() {
+        let path = std::env::temp_dir().join(format!(
+            "ambient_light_frames_{}.jsonl",
+            uuid::Uuid::new_v4()
+        ));
+        let layout = test_layout();
+
+        let mut recorder = FrameRecorder::create(&path).await.unwrap();
+        recorder
+            .record(&layout, &[1, 2, 3, 4, 5, 6, 7, 8, 9])
+            .unwrap();
+        recorder
+            .record(&layout, &[9, 8, 7, 6, 5, 4, 3, 2, 1])
+            .unwrap();
+        assert_eq!(recorder.finish().await.unwrap(), 2);
+
+        let (read_layout, frames) = read_recording(&path).unwrap();
+        std::fs::remove_file(&path).ok();
+
+        assert_eq!(read_layout.displays.len(), 2);
+        assert_eq!(read_layout.displays[1].display_id, 2);
+        assert_eq!(read_layout.displays[1].start_led_offset, 2);
+        assert_eq!(read_layout.all_strips.len(), layout.all_strips.len());
+        assert_eq!(frames.len(), 2);
+        assert!(frames[0].timestamp_ms <= frames[1].timestamp_ms);
+        assert_eq!(frames[1].colors().unwrap(), vec![9, 8, 7, 6, 5, 4, 3, 2, 1]);
+    }
+
+    #[tokio::test]
+    async fn test_recording_stops_at_size_limit() {
+        let path = std::env::temp_dir().join(format!(
+            "ambient_light_frames_{}.jsonl",
+            uuid::Uuid::new_v4()
+        ));
+        let layout = test_layout();
+        let layout_len = serde_json::to_vec(&layout).unwrap().len() as u64 + 1;
+        let frame_len = serde_json::to_vec(&RecordedFrame {
+            timestamp_ms: 0,
+            colors: hex::encode([0u8; 9]),
+        })
+        .unwrap()
+        .len() as u64
+            + 1;
+
+        // 只够写入布局和一帧（时间戳位数可能增加，留出少量余量）
+        let mut recorder = FrameRecorder::create_with_limits(
+            &path,
+            layout_len + frame_len + 4,
+            MAX_RECORDING_DURATION,
+        )
+        .await
+        .unwrap();
+        for _ in 0..5 {
+            recorder.record(&layout, &[0; 9]).unwrap();
+        }
+        assert_eq!(recorder.finish().await.unwrap(), 1);
+
+        let (_, frames) = read_recording(&path).unwrap();
+        std::fs::remove_file(&path).ok();
+        assert_eq!(frames.len(), 1);
+    }
+
+    #[tokio::test]
+    async fn test_recording_stops_after_max_duration() {
+        let path = std::env::temp_dir().join(format!(
+            "ambient_light_frames_{}.jsonl",
+            uuid::Uuid::new_v4()
+        ));
+        let layout = test_layout();
+
+        let mut recorder =
+            FrameRecorder::create_with_limits(&path, MAX_RECORDING_BYTES, Duration::ZERO)
+                .await
+                .unwrap();
+        std::thread::sleep(Duration::from_millis(2));
+        recorder.record(&layout, &[0; 9]).unwrap();
+        assert_eq!(recorder.finish().await.unwrap(), 0);
+        std::fs::remove_file(&path).ok();
+    }
+
+    #[test]
+    fn test_recording_name_must_be_bare_file_name() {
+        assert!(validate_recording_name("session-1.jsonl").is_ok());
+        for name in [
+            "",
+            ".",
+            "..",
+            "../x",
+            "/etc/passwd",
+            "a/b",
+            "a\\b",
+            "bad\nname",
+        ] {
+            assert!(
+                validate_recording_name(name).is_err(),
+                "{name:?} should be rejected"
+            );
+        }
+    }
+
+    #[test]
+    fn test_resolve_recording_path_stays_inside_directory() {
+        let dir =
+            std::env::temp_dir().join(format!("ambient_light_recordings_{}", uuid::Uuid::new_v4()));
+
+        let path = resolve_recording_path(&dir, "session.jsonl").unwrap();
+        assert_eq!(path, dir.canonicalize().unwrap().join("session.jsonl"));
+        assert!(resolve_recording_path(&dir, "../session.jsonl").is_err());
+
+        // 指向目录外的符号链接同样拒绝
+        #[cfg(unix)]
+        {
+            let outside = std::env::temp_dir()
+                .join(format!("ambient_light_outside_{}", uuid::Uuid::new_v4()));
+            std::fs::write(&outside, b"").unwrap();
+            std::os::unix::fs::symlink(&outside, dir.join("link.jsonl")).unwrap();
+            assert!(resolve_recording_path(&dir, "link.jsonl").is_err());
+            std::fs::remove_file(&outside).ok();
+            // 失效的链接在创建文件时会写到目录外，同样拒绝
+            assert!(resolve_recording_path(&dir, "link.jsonl").is_err());
+        }
+
+        std::fs::remove_dir_all(&dir).ok();
+    }
+
+    fn rgb(colors: &[LedColor]) -> Vec<[u8; 3]> {
+        colors.iter().map(|color| color.get_rgb()).collect()
+    }
+
+    #[test]
+    fn test_split_frame_by_display() {
+        let layout = test_layout();
+        let split = layout.split_frame(&[1, 2, 3, 4, 5, 6, 7, 8, 9]);
+
+        assert_eq!(split.len(), 2);
+        assert_eq!(split[0].0.display_id, 1);
+        assert_eq!(rgb(&split[0].1), vec![[1, 2, 3], [4, 5, 6]]);
+        assert_eq!(rgb(&split[1].1), vec![[7, 8, 9]]);
+
+        // 帧数据不足时缺失的显示器得到空颜色
+        let split = layout.split_frame(&[1, 2, 3]);
+        assert_eq!(rgb(&split[0].1), vec![[1, 2, 3]]);
+        assert!(split[1].1.is_empty());
+    }
+}
@@ -1,16 +1,22 @@
+mod calibration_wizard;
 mod config;
 mod config_manager;
 mod config_manager_v2;
 mod config_v2;
+mod frame_recorder;
+mod preview_render;
 mod publisher;
 mod publisher_adapter;
 
 #[cfg(test)]
 mod publisher_test;
 
+pub use calibration_wizard::*;
 pub use config::*;
 pub use config_manager::*;
 pub use config_manager_v2::*;
 pub use config_v2::*;
+pub use frame_recorder::{recording_path, validate_recording_name};
+pub use preview_render::*;
 pub use publisher::*;
 pub use publisher_adapter::*;
@@ -0,0 +1,289 @@
+use std::io::Cursor;
+
+use image::{ImageFormat, Rgba, RgbaImage};
+
+use crate::led_color::LedColor;
+
+use super::{frame_recorder::RecordedDisplay, Border};
+
+/// 预览图默认宽度
+pub const DEFAULT_PREVIEW_RENDER_WIDTH: u32 = 960;
+/// 预览图默认高度
+pub const DEFAULT_PREVIEW_RENDER_HEIGHT: u32 = 540;
+/// 预览图允许的最小边长
+pub const MIN_PREVIEW_RENDER_SIZE: u32 = 64;
+/// 预览图允许的最大宽度
+pub const MAX_PREVIEW_RENDER_WIDTH: u32 = 3840;
+/// 预览图允许的最大高度
+pub const MAX_PREVIEW_RENDER_HEIGHT: u32 = 2160;
+
+const BACKGROUND_COLOR: Rgba<u8> = Rgba([16, 16, 20, 255]);
+const BEZEL_COLOR: Rgba<u8> = Rgba([64, 64, 72, 255]);
+const SCREEN_COLOR: Rgba<u8> = Rgba([28, 28, 34, 255]);
+
+/// 校验预览图尺寸
+pub fn validate_preview_render_size(width: u32, height: u32) -> anyhow::Result<()> {
+    if !(MIN_PREVIEW_RENDER_SIZE..=MAX_PREVIEW_RENDER_WIDTH).contains(&width)
+        || !(MIN_PREVIEW_RENDER_SIZE..=MAX_PREVIEW_RENDER_HEIGHT).contains(&height)
+    {
+        anyhow::bail!(
+            "Preview size must be between {MIN_PREVIEW_RENDER_SIZE}x{MIN_PREVIEW_RENDER_SIZE} and {MAX_PREVIEW_RENDER_WIDTH}x{MAX_PREVIEW_RENDER_HEIGHT}, got {width}x{height}"
+        );
+    }
+    Ok(())
+}
+
+/// 屏幕上的矩形区域（像素）
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+struct Rect {
+    x: u32,
+    y: u32,
+    width: u32,
+    height: u32,
+}
+
+impl Rect {
+    /// 向内收缩 `inset` 像素，不足时收缩为空
+    fn inset(&self, inset: u32) -> Self {
+        let inset_x = inset.min(self.width / 2);
+        let inset_y = inset.min(self.height / 2);
+        Self {
+            x: self.x + inset_x,
+            y: self.y + inset_y,
+            width: self.width - inset_x * 2,
+            height: self.height - inset_y * 2,
+        }
+    }
+
+    fn fill(&self, image: &mut RgbaImage, color: Rgba<u8>) {
+        for y in self.y..(self.y + self.height).min(image.height()) {
+            for x in self.x..(self.x + self.width).min(image.width()) {
+                image.put_pixel(x, y, color);
+            }
+        }
+    }
+}
+
+/// 绘制氛围光预览：每个显示器画为一个带边框的屏幕，四周按灯带配置画出当前的LED颜色
+///
+/// 多个显示器按展平顺序从左到右排列。同一边上的灯带按配置顺序首尾相接，
+/// LED顺序与采样顺序一致：上下边从左到右，左右边从上到下。
+///
+/// 展平颜色中反向灯带的颜色已调整为物理顺序，绘制前还原为采样顺序；
+/// 走线方向只在硬件编码时应用，展平颜色不受其影响。
+pub fn render_ambient_preview(
+    displays: &[(&RecordedDisplay, Vec<LedColor>)],
+    width: u32,
+    height: u32,
+) -> RgbaImage {
+    let mut image = RgbaImage::from_pixel(width, height, BACKGROUND_COLOR);
+    if displays.is_empty() {
+        return image;
+    }
+
+    let cell_width = width / displays.len() as u32;
+    for (i, (display, colors)) in displays.iter().enumerate() {
+        let cell = Rect {
+            x: cell_width * i as u32,
+            y: 0,
+            width: cell_width,
+            height,
+        };
+        render_display(&mut image, cell, display, colors);
+    }
+    image
+}
+
+fn render_display(
+    image: &mut RgbaImage,
+    cell: Rect,
+    display: &RecordedDisplay,
+    colors: &[LedColor],
+) {
+    let short_side = cell.width.min(cell.height);
+    let padding = (short_side / 16).max(1);
+    let band = (short_side / 20).max(2);
+
+    // 在可用区域内居中放置 16:9 的灯带外框
+    let available = cell.inset(padding);
+    let outer_width = available.width.min(available.height * 16 / 9);
+    let outer_height = (outer_width * 9 / 16).min(available.height);
+    let outer = Rect {
+        x: available.x + (available.width - outer_width) / 2,
+        y: available.y + (available.height - outer_height) / 2,
+        width: outer_width,
+        height: outer_height,
+    };
+
+    let monitor = outer.inset(band + band / 2);
+    monitor.fill(image, BEZEL_COLOR);
+    monitor.inset((band / 3).max(1)).fill(image, SCREEN_COLOR);
+
+    // 每条灯带在展平颜色中的起始位置
+    let mut strip_colors = Vec::with_capacity(display.strips.len());
+    let mut offset = 0;
+    for strip in &display.strips {
+        strip_colors.push((strip, offset));
+        offset += strip.len;
+    }
+
+    for border in [Border::Top, Border::Bottom, Border::Left, Border::Right] {
+        let leds: Vec<LedColor> = strip_colors
+            .iter()
+            .filter(|(strip, _)| strip.border == border)
+            .flat_map(|(strip, offset)| {
+                let mut strip_colors: Vec<LedColor> = (*offset..*offset + strip.len)
+                    .map(|index| colors.get(index).copied().unwrap_or_else(LedColor::default))
+                    .collect();
+                strip.apply_reversal(&mut strip_colors);
+                strip_colors
+            })
+            .collect();
+        if leds.is_empty() {
+            continue;
+        }
+
+        // 四角留空，灯带沿边排列
+        let (span_start, span_len) = match border {
+            Border::Top | Border::Bottom => (outer.x + band, outer.width.saturating_sub(band * 2)),
+            Border::Left | Border::Right => (outer.y + band, outer.height.saturating_sub(band * 2)),
+        };
+        for (k, color) in leds.iter().enumerate() {
+            let start = span_start + (k as u64 * span_len as u64 / leds.len() as u64) as u32;
+            let end = span_start + ((k + 1) as u64 * span_len as u64 / leds.len() as u64) as u32;
+            // 足够宽时在LED之间留出1像素间隔
+            let len = if end - start >= 4 {
+                end - start - 1
+            } else {
+                end - start
+            };
+            let rect = match border {
+                Border::Top => Rect {
+                    x: start,
+                    y: outer.y,
+                    width: len,
+                    height: band,
+                },
+                Border::Bottom => Rect {
+                    x: start,
+                    y: outer.y + outer.height - band,
+                    width: len,
+                    height: band,
+                },
+                Border::Left => Rect {
+                    x: outer.x,
+                    y: start,
+                    width: band,
+                    height: len,
+                },
+                Border::Right => Rect {
+                    x: outer.x + outer.width - band,
+                    y: start,
+                    width: band,
+                    height: len,
+                },
+            };
+            let [r, g, b] = color.get_rgb();
+            rect.fill(image, Rgba([r, g, b, 255]));
+        }
+    }
+}
+
+/// 将预览图编码为 PNG
+pub fn encode_preview_png(image: &RgbaImage) -> anyhow::Result<Vec<u8>> {
+    let mut png = Vec::new();
+    image.write_to(&mut Cursor::new(&mut png), ImageFormat::Png)?;
+    Ok(png)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::ambient_light::{DisplayWinding, LedStripConfig};
+
+    fn strip(index: usize, border: Border, len: usize) -> LedStripConfig {
+        LedStripConfig {
+            index,
+            border,
+            len,
+            ..LedStripConfig::default()
+        }
+    }
+
+    #[test]
+    fn test_render_ambient_preview_draws_strip_colors_around_bezel() {
+        let display = RecordedDisplay {
+            display_id: 1,
+            strips: vec![strip(0, Border::Top, 2), strip(1, Border::Left, 1)],
+            start_led_offset: 0,
+            winding: Default::default(),
+        };
+        let colors = vec![
+            LedColor::new(255, 0, 0),
+            LedColor::new(0, 255, 0),
+            LedColor::new(0, 0, 255),
+        ];
+
+        let image = render_ambient_preview(&[(&display, colors)], 320, 180);
+        assert_eq!(image.dimensions(), (320, 180));
+
+        let pixels: Vec<Rgba<u8>> = image.pixels().copied().collect();
+        for color in [[255, 0, 0, 255], [0, 255, 0, 255], [0, 0, 255, 255]] {
+            assert!(pixels.contains(&Rgba(color)), "missing LED color {color:?}");
+        }
+        assert!(pixels.contains(&SCREEN_COLOR));
+        // 上边从左到右依次为第一、第二颗LED
+        let first_lit = pixels
+            .iter()
+            .find(|pixel| **pixel == Rgba([255, 0, 0, 255]) || **pixel == Rgba([0, 255, 0, 255]));
+        assert_eq!(first_lit, Some(&Rgba([255, 0, 0, 255])));
+
+        let png = encode_preview_png(&image).unwrap();
+        assert_eq!(&png[1..4], b"PNG");
+    }
+
+    #[test]
+    fn test_render_ambient_preview_draws_reversed_strip_in_sampling_order() {
+        let red = LedColor::new(255, 0, 0);
+        let green = LedColor::new(0, 255, 0);
+        let first_lit = |reversed: bool, winding: DisplayWinding, colors: Vec<LedColor>| {
+            let display = RecordedDisplay {
+                display_id: 1,
+                strips: vec![LedStripConfig {
+                    reversed,
+                    ..strip(0, Border::Top, 2)
+                }],
+                start_led_offset: 0,
+                winding,
+            };
+            let image = render_ambient_preview(&[(&display, colors)], 320, 180);
+            image
+                .pixels()
+                .copied()
+                .find(|pixel| *pixel == Rgba([255, 0, 0, 255]) || *pixel == Rgba([0, 255, 0, 255]))
+        };
+
+        // 反向灯带的物理起点在右侧，展平颜色 [绿, 红] 绘制为从左到右的红、绿
+        assert_eq!(
+            first_lit(true, DisplayWinding::Clockwise, vec![green, red]),
+            Some(Rgba([255, 0, 0, 255]))
+        );
+        // 走线方向不改变展平颜色的顺序
+        assert_eq!(
+            first_lit(false, DisplayWinding::CounterClockwise, vec![red, green]),
+            Some(Rgba([255, 0, 0, 255]))
+        );
+    }
+
+    #[test]
+    fn test_validate_preview_render_size() {
+        assert!(validate_preview_render_size(
+            DEFAULT_PREVIEW_RENDER_WIDTH,
+            DEFAULT_PREVIEW_RENDER_HEIGHT
+        )
+        .is_ok());
+        assert!(validate_preview_render_size(MIN_PREVIEW_RENDER_SIZE - 1, 540).is_err());
+        assert!(validate_preview_render_size(960, MAX_PREVIEW_RENDER_HEIGHT + 1).is_err());
+        assert!(validate_preview_render_size(MAX_PREVIEW_RENDER_WIDTH + 1, 540).is_err());
+    }
+}
@@ -1,4 +1,10 @@
-use std::{sync::Arc, time::Duration};
+use std::{
+    borrow::Cow,
+    collections::{BTreeSet, HashMap},
+    path::PathBuf,
+    sync::Arc,
+    time::Duration,
+};
 
 use paris::warn;
 use tauri::async_runtime::RwLock;
@@ -16,13 +22,642 @@ use crate::{
     led_status_manager::LedStatusManager,
     screenshot::{LedSamplePoints, Screenshot},
     screenshot_manager::ScreenshotManager,
+    user_preferences::{CaptureFailurePreferences, UserPreferencesManager},
+};
+
+use crate::display::{DisplayConfig, DisplayRegistry};
+
+use crate::ambient_light::config_v2::{LedStripConfigGroupV2, LedStripConfigV2};
+
+use super::{
+    encode_preview_png, expand_spanning_strips, filter_masked_strips,
+    frame_recorder::{read_recording, FrameLayout, FrameRecorder, RecordedDisplay},
+    render_ambient_preview, validate_preview_render_size, BorderMask, ColorCalibration,
+    DisplayWinding, LedStripConfig, LedStripConfigGroup, LedType, SamplePointMapper,
+    SpanDisplayBounds,
 };
 
-use crate::display::DisplayRegistry;
+/// 手动纯色模式的发送间隔（5Hz）
+const MANUAL_COLOR_SEND_INTERVAL: Duration = Duration::from_millis(200);
+/// 截图采集失败期间回退颜色的刷新间隔
+const CAPTURE_FAILURE_FRAME_INTERVAL: Duration = Duration::from_millis(100);
+/// 判定截图采集失败的最短等待时间（毫秒）
+pub const MIN_CAPTURE_STALL_TIMEOUT_MS: u64 = 100;
+/// 检查显示器是否仍在系统显示器列表中的间隔
+const DISPLAY_AVAILABILITY_CHECK_INTERVAL: Duration = Duration::from_secs(2);
+/// 采样结果持续全黑超过该时长视为显示器已休眠（节能模式下画面为纯黑）
+const BLACK_FRAME_SLEEP_THRESHOLD: Duration = Duration::from_secs(3);
+/// 显示器休眠后渐变到黑色的时长
+const DISPLAY_SLEEP_FADE_DURATION: Duration = Duration::from_secs(2);
+/// 自适应采样质量的最低等级
+const MAX_ADAPTIVE_QUALITY_LEVEL: u8 = 3;
+/// 单帧处理时间滑动平均中新一帧的权重
+const FRAME_TIME_SMOOTHING: f64 = 0.2;
+/// 处理时间超出预算后，至少间隔这么多帧才再次降低质量
+const ADAPTIVE_QUALITY_DEGRADE_FRAMES: u32 = 10;
+/// 处理时间持续低于预算的该比例时才逐级恢复质量
+const ADAPTIVE_QUALITY_RESTORE_HEADROOM: f64 = 0.5;
+/// 恢复一级质量前需要保持余量的帧数（约 2 秒），避免在两个等级间反复切换
+const ADAPTIVE_QUALITY_RESTORE_FRAMES: u32 = 60;
+
+/// 截图采集失败（长时间没有新画面或截图通道关闭）时灯带的表现
+#[derive(
+    Debug,
+    Clone,
+    Copy,
+    Default,
+    PartialEq,
+    Eq,
+    serde::Serialize,
+    serde::Deserialize,
+    utoipa::ToSchema,
+)]
+pub enum CaptureFailurePolicy {
+    /// 保持最后一帧颜色
+    #[default]
+    Hold,
+    /// 在 `fade_ms` 内渐变到黑色
+    FadeToBlack,
+    /// 显示诊断颜色，提示采集异常
+    DiagnosticColor,
+}
+
+/// 多显示器颜色合并为整帧的时机
+#[derive(
+    Debug,
+    Clone,
+    Copy,
+    Default,
+    PartialEq,
+    Eq,
+    serde::Serialize,
+    serde::Deserialize,
+    utoipa::ToSchema,
+)]
+pub enum FrameSyncMode {
+    /// 所有显示器都上报过颜色后才开始发送
+    #[default]
+    Synchronized,
+    /// 任一显示器更新即发送整帧，其余显示器沿用上次的颜色（尚未上报的为黑色），
+    /// 避免单个慢速或停止输出的显示器阻塞整帧
+    Immediate,
+}
+
+/// 采样得到的颜色数量与灯带配置的LED总数不一致时的处理方式
+///
+/// 实时编辑灯带长度时，采样结果可能短暂地与新的 `len` 不同步
+#[derive(
+    Debug,
+    Clone,
+    Copy,
+    Default,
+    PartialEq,
+    Eq,
+    serde::Serialize,
+    serde::Deserialize,
+    utoipa::ToSchema,
+)]
+pub enum ColorCountPolicy {
+    /// 多余的颜色丢弃，不足的LED填充黑色
+    #[default]
+    Truncate,
+    /// 多余的颜色丢弃，不足的LED从头循环使用颜色
+    Wrap,
+    /// 沿灯带线性插值，把颜色拉伸或压缩到LED总数
+    Resample,
+}
+
+/// 呼吸效果允许的最高频率（Hz），限制闪烁速度
+pub const MAX_BREATHING_FREQUENCY_HZ: f64 = 3.0;
+
+/// 单屏灯带配置模式下活跃灯带的呼吸效果参数
+///
+/// 对光敏感的用户可以降低频率或缩小亮度范围
+#[derive(Debug, Clone, Copy, PartialEq, serde::Serialize, serde::Deserialize, utoipa::ToSchema)]
+pub struct BreathingConfig {
+    /// 呼吸频率（每秒完整周期数）
+    pub frequency_hz: f64,
+    /// 最低亮度（0-1）
+    pub min_brightness: f32,
+    /// 最高亮度（0-1）
+    pub max_brightness: f32,
+}
 
-use crate::ambient_light::config_v2::LedStripConfigGroupV2;
+impl Default for BreathingConfig {
+    /// 每2秒一个周期，亮度在30%到100%之间变化
+    fn default() -> Self {
+        Self {
+            frequency_hz: 0.5,
+            min_brightness: 0.3,
+            max_brightness: 1.0,
+        }
+    }
+}
 
-use super::{ColorCalibration, LedStripConfig, LedStripConfigGroup, LedType, SamplePointMapper};
+impl BreathingConfig {
+    /// 校验频率与亮度范围
+    pub fn validate(&self) -> anyhow::Result<()> {
+        if !(self.frequency_hz > 0.0 && self.frequency_hz <= MAX_BREATHING_FREQUENCY_HZ) {
+            return Err(anyhow::anyhow!(
+                "Breathing frequency must be within (0, {MAX_BREATHING_FREQUENCY_HZ}] Hz, got {}",
+                self.frequency_hz
+            ));
+        }
+        if !(0.0 <= self.min_brightness
+            && self.min_brightness <= self.max_brightness
+            && self.max_brightness <= 1.0)
+        {
+            return Err(anyhow::anyhow!(
+                "Breathing brightness must satisfy 0 <= min <= max <= 1, got min {}, max {}",
+                self.min_brightness,
+                self.max_brightness
+            ));
+        }
+        Ok(())
+    }
+
+    /// 经过 `time_seconds` 秒时的亮度系数
+    pub fn brightness_at(&self, time_seconds: f64) -> f32 {
+        let wave =
+            (time_seconds * self.frequency_hz * 2.0 * std::f64::consts::PI).sin() * 0.5 + 0.5;
+        self.min_brightness + (self.max_brightness - self.min_brightness) * wave as f32
+    }
+}
+
+/// 灯带颜色的采样方式
+#[derive(
+    Debug,
+    Clone,
+    Copy,
+    Default,
+    PartialEq,
+    Eq,
+    serde::Serialize,
+    serde::Deserialize,
+    utoipa::ToSchema,
+)]
+pub enum SamplingStrategy {
+    /// 按灯带所在边缘逐个LED采样
+    #[default]
+    Edges,
+    /// 整屏平均色填充该显示器的所有LED（背光效果）
+    ScreenAverage,
+}
+
+/// 显示器休眠（关闭或进入节能模式）时灯带的表现
+#[derive(
+    Debug,
+    Clone,
+    Copy,
+    Default,
+    PartialEq,
+    Eq,
+    serde::Serialize,
+    serde::Deserialize,
+    utoipa::ToSchema,
+)]
+pub enum DisplaySleepPolicy {
+    /// 不检测休眠，照常输出采样结果（通常为黑色）
+    Ignore,
+    /// 保持休眠前最后一帧颜色
+    #[default]
+    Hold,
+    /// 从休眠前最后一帧渐变到黑色
+    FadeToBlack,
+}
+
+/// 检测单个显示器是否休眠：系统不再报告该显示器，或（启用全黑检测时）采样结果持续全黑
+#[derive(Debug, Default)]
+struct DisplaySleepDetector {
+    /// 采样结果开始全黑的时间
+    black_since: Option<tokio::time::Instant>,
+    /// 判定为休眠的时间
+    asleep_since: Option<tokio::time::Instant>,
+    /// 最后一帧非全黑的颜色，休眠时据此保持或渐变
+    awake_colors: Vec<LedColor>,
+}
+
+impl DisplaySleepDetector {
+    /// 记录一帧采样结果，返回已休眠的时长，未休眠时返回 None
+    ///
+    /// `detect_black_frames` 为 false 时只根据显示器是否仍被系统报告判断，
+    /// 避免暗场景被误判为休眠而冻结灯带
+    fn update(
+        &mut self,
+        available: bool,
+        colors: &[LedColor],
+        now: tokio::time::Instant,
+        detect_black_frames: bool,
+    ) -> Option<Duration> {
+        let black = colors.iter().all(|color| color.get_rgb() == [0, 0, 0]);
+        if black {
+            self.black_since.get_or_insert(now);
+        } else {
+            self.black_since = None;
+            self.awake_colors = colors.to_vec();
+        }
+
+        let black_too_long = detect_black_frames
+            && self
+                .black_since
+                .is_some_and(|since| now.duration_since(since) >= BLACK_FRAME_SLEEP_THRESHOLD);
+        if available && !black_too_long {
+            self.asleep_since = None;
+            return None;
+        }
+
+        let since = *self.asleep_since.get_or_insert(now);
+        Some(now.duration_since(since))
+    }
+}
+
+/// 单个显示器当前的自适应采样质量，用于状态统计
+#[derive(Debug, Clone, Copy, PartialEq, serde::Serialize, serde::Deserialize)]
+pub struct AdaptiveQualityState {
+    /// 质量等级，0 为配置的完整质量，越大采样越稀疏
+    pub level: u8,
+    /// 当前实际使用的每个LED采样点数
+    pub sample_points_per_led: usize,
+    /// 当前是否使用双线性采样
+    pub bilinear_sampling: bool,
+    /// 单帧处理（采样、编码和发送）时间的滑动平均（毫秒）
+    pub frame_time_ms: f64,
+    /// 单帧处理时间预算（毫秒），即正常采集帧间隔
+    pub budget_ms: f64,
+}
+
+/// 单条灯带在硬件数据中的布局，用于排查颜色显示在错误LED上的接线或偏移量问题
+#[derive(Debug, Clone, PartialEq, serde::Serialize, utoipa::ToSchema)]
+pub struct StripLayoutEntry {
+    /// 灯带序列号
+    pub index: usize,
+    /// 串联顺序中的位置（从0开始）
+    pub chain_position: usize,
+    /// 所属显示器的系统ID
+    pub display_id: u32,
+    #[schema(value_type = String)]
+    pub border: Border,
+    #[schema(value_type = String)]
+    pub led_type: LedType,
+    /// LED数量
+    pub len: usize,
+    /// 在完整硬件数据中的起始字节偏移量
+    pub byte_offset: usize,
+    /// 占用的字节数
+    pub byte_len: usize,
+    /// 起始位置是否来自显式配置的硬件偏移量
+    pub explicit_offset: bool,
+}
+
+/// 将灯带布局格式化为便于在日志中阅读的表格
+pub fn format_strip_layout(layout: &[StripLayoutEntry]) -> String {
+    let mut table = format!(
+        "{:>5} {:>5} {:>10} {:<6} {:<7} {:>5} {:>11} {:>6}",
+        "chain", "index", "display", "border", "type", "len", "byte_offset", "bytes"
+    );
+    for entry in layout {
+        // 枚举的 Debug 输出不支持宽度对齐，先转换为字符串
+        let border = format!("{:?}", entry.border);
+        let led_type = format!("{:?}", entry.led_type);
+        table.push_str(&format!(
+            "\n{:>5} {:>5} {:>10} {:<6} {:<7} {:>5} {:>11} {:>6}{}",
+            entry.chain_position,
+            entry.index,
+            entry.display_id,
+            border,
+            led_type,
+            entry.len,
+            entry.byte_offset,
+            entry.byte_len,
+            if entry.explicit_offset {
+                " (explicit)"
+            } else {
+                ""
+            }
+        ));
+    }
+    table
+}
+
+/// 按单帧处理时间自动调整采样质量：持续超出预算时逐级降低采样密度，余量恢复后逐级恢复
+///
+/// 等级 1 关闭双线性采样，之后每级将每个LED的采样网格边长减半（至少 1x1）
+#[derive(Debug, Default)]
+struct AdaptiveQualityController {
+    level: u8,
+    /// 单帧处理时间的指数滑动平均
+    average_frame_time: Option<Duration>,
+    /// 距上次调整等级的帧数
+    frames_since_change: u32,
+}
+
+impl AdaptiveQualityController {
+    /// 记录一帧的处理时间，按需调整质量等级
+    fn record(&mut self, frame_time: Duration, budget: Duration) {
+        let average = match self.average_frame_time {
+            Some(average) => {
+                average.mul_f64(1.0 - FRAME_TIME_SMOOTHING)
+                    + frame_time.mul_f64(FRAME_TIME_SMOOTHING)
+            }
+            None => frame_time,
+        };
+        self.average_frame_time = Some(average);
+        self.frames_since_change = self.frames_since_change.saturating_add(1);
+
+        if average > budget
+            && self.level < MAX_ADAPTIVE_QUALITY_LEVEL
+            && self.frames_since_change >= ADAPTIVE_QUALITY_DEGRADE_FRAMES
+        {
+            self.level += 1;
+            self.frames_since_change = 0;
+            log::info!(
+                "Frame time {average:?} exceeds budget {budget:?}, lowering sampling quality to level {}",
+                self.level
+            );
+        } else if average < budget.mul_f64(ADAPTIVE_QUALITY_RESTORE_HEADROOM)
+            && self.level > 0
+            && self.frames_since_change >= ADAPTIVE_QUALITY_RESTORE_FRAMES
+        {
+            self.level -= 1;
+            self.frames_since_change = 0;
+            log::info!(
+                "Frame time {average:?} back within budget {budget:?}, restoring sampling quality to level {}",
+                self.level
+            );
+        }
+    }
+
+    /// 当前等级下实际使用的（每个LED采样点数，是否双线性采样）
+    fn sampling(&self, sample_points_per_led: usize, bilinear_sampling: bool) -> (usize, bool) {
+        if self.level == 0 {
+            return (sample_points_per_led, bilinear_sampling);
+        }
+        let side = (sample_points_per_led as f64).sqrt() as usize;
+        let side = (side >> (self.level - 1)).max(1);
+        (side * side, false)
+    }
+
+    fn state(
+        &self,
+        sample_points_per_led: usize,
+        bilinear_sampling: bool,
+        budget: Duration,
+    ) -> AdaptiveQualityState {
+        let (sample_points_per_led, bilinear_sampling) =
+            self.sampling(sample_points_per_led, bilinear_sampling);
+        AdaptiveQualityState {
+            level: self.level,
+            sample_points_per_led,
+            bilinear_sampling,
+            frame_time_ms: self.average_frame_time.unwrap_or_default().as_secs_f64() * 1000.0,
+            budget_ms: budget.as_secs_f64() * 1000.0,
+        }
+    }
+}
+
+/// 按 `policy` 计算显示器休眠 `asleep_for` 时长后应输出的颜色，`None` 表示保持（不发送新数据）
+///
+/// `asleep_for` 为 None（未休眠）或策略为 Ignore 时原样输出 `colors`
+fn display_sleep_colors(
+    policy: DisplaySleepPolicy,
+    awake_colors: &[LedColor],
+    colors: Vec<LedColor>,
+    asleep_for: Option<Duration>,
+) -> Option<Vec<LedColor>> {
+    let Some(asleep_for) = asleep_for else {
+        return Some(colors);
+    };
+    match policy {
+        DisplaySleepPolicy::Ignore => Some(colors),
+        DisplaySleepPolicy::Hold => None,
+        DisplaySleepPolicy::FadeToBlack => Some(faded_colors(
+            awake_colors,
+            colors.len(),
+            DISPLAY_SLEEP_FADE_DURATION,
+            asleep_for,
+        )),
+    }
+}
+
+/// 从 `last_colors` 线性渐变到黑色，经过 `elapsed` 后的颜色；长度不足 `led_count` 时补黑色
+fn faded_colors(
+    last_colors: &[LedColor],
+    led_count: usize,
+    fade: Duration,
+    elapsed: Duration,
+) -> Vec<LedColor> {
+    let remaining = if fade.is_zero() {
+        0.0
+    } else {
+        1.0 - elapsed.as_millis() as f32 / fade.as_millis() as f32
+    }
+    .clamp(0.0, 1.0);
+
+    (0..led_count)
+        .map(|i| {
+            let [r, g, b] = last_colors
+                .get(i)
+                .map_or([0; 3], LedColor::get_rgb)
+                .map(|value| (value as f32 * remaining).round() as u8);
+            LedColor::new(r, g, b)
+        })
+        .collect()
+}
+
+/// 已经警告过的颜色数量不匹配（颜色数量, LED总数），同一组合在一份配置内只警告一次
+static REPORTED_COLOR_COUNT_MISMATCHES: std::sync::Mutex<BTreeSet<(usize, usize)>> =
+    std::sync::Mutex::new(BTreeSet::new());
+
+/// 配置变更后清空已警告的组合，使新配置下再次出现的不匹配重新输出警告
+fn reset_color_count_mismatch_reports() {
+    if let Ok(mut reported) = REPORTED_COLOR_COUNT_MISMATCHES.lock() {
+        reported.clear();
+    }
+}
+
+/// 按 `policy` 将颜色数量调整为 `expected`，数量一致时不复制
+fn fit_color_count(
+    colors: &[LedColor],
+    expected: usize,
+    policy: ColorCountPolicy,
+) -> Cow<'_, [LedColor]> {
+    if colors.len() == expected {
+        return Cow::Borrowed(colors);
+    }
+
+    if let Ok(mut reported) = REPORTED_COLOR_COUNT_MISMATCHES.lock() {
+        if reported.insert((colors.len(), expected)) {
+            log::warn!(
+                "Sampled {} colors for {} LEDs, applying {:?} policy",
+                colors.len(),
+                expected,
+                policy
+            );
+        }
+    }
+
+    if colors.is_empty() {
+        return Cow::Owned(vec![LedColor::default(); expected]);
+    }
+
+    let fitted = match policy {
+        ColorCountPolicy::Truncate => colors
+            .iter()
+            .copied()
+            .chain(std::iter::repeat(LedColor::default()))
+            .take(expected)
+            .collect(),
+        ColorCountPolicy::Wrap => colors.iter().copied().cycle().take(expected).collect(),
+        ColorCountPolicy::Resample => {
+            let last = colors.len() - 1;
+            (0..expected)
+                .map(|index| {
+                    let position = ((index as f64 + 0.5) * colors.len() as f64 / expected as f64
+                        - 0.5)
+                        .clamp(0.0, last as f64);
+                    let lower = position.floor() as usize;
+                    let upper = (lower + 1).min(last);
+                    let weight = position - lower as f64;
+                    let [r, g, b] = [0, 1, 2].map(|channel| {
+                        let from = colors[lower].get_rgb()[channel] as f64;
+                        let to = colors[upper].get_rgb()[channel] as f64;
+                        (from + (to - from) * weight).round() as u8
+                    });
+                    LedColor::new(r, g, b)
+                })
+                .collect()
+        }
+    };
+    Cow::Owned(fitted)
+}
+
+/// 将各显示器的颜色按显示器顺序合并为整帧
+///
+/// 各显示器按自己的帧率上报颜色，本次没有更新的显示器沿用上次上报的颜色
+struct FrameAssembler {
+    /// 每个显示器的颜色字节数，用于填充尚未上报的显示器
+    display_lens: Vec<usize>,
+    colors: Vec<Option<Vec<u8>>>,
+}
+
+impl FrameAssembler {
+    fn new(display_lens: Vec<usize>) -> Self {
+        let colors = vec![None; display_lens.len()];
+        Self {
+            display_lens,
+            colors,
+        }
+    }
+
+    /// 记录第 `index` 个显示器的新颜色，返回需要发送的整帧
+    fn update(&mut self, index: usize, colors: Vec<u8>, mode: FrameSyncMode) -> Option<Vec<u8>> {
+        self.colors[index] = Some(colors);
+
+        if mode == FrameSyncMode::Synchronized && self.colors.iter().any(Option::is_none) {
+            return None;
+        }
+
+        Some(
+            self.colors
+                .iter()
+                .zip(&self.display_lens)
+                .flat_map(|(colors, &len)| match colors {
+                    Some(colors) => colors.clone(),
+                    None => vec![0; len],
+                })
+                .collect(),
+        )
+    }
+}
+
+/// 校验截图采集失败回退配置
+pub fn validate_capture_failure(preferences: &CaptureFailurePreferences) -> anyhow::Result<()> {
+    if preferences.stall_timeout_ms < MIN_CAPTURE_STALL_TIMEOUT_MS {
+        anyhow::bail!(
+            "stall timeout must be at least {MIN_CAPTURE_STALL_TIMEOUT_MS} ms, got {}",
+            preferences.stall_timeout_ms
+        );
+    }
+    Ok(())
+}
+
+/// 计算采集失败 `stalled_for` 时长后应输出的颜色，`None` 表示保持最后一帧（不发送新数据）
+///
+/// `last_colors` 为最后一帧成功采样的颜色，长度不足 `led_count` 时补黑色
+pub fn capture_failure_colors(
+    preferences: &CaptureFailurePreferences,
+    last_colors: &[LedColor],
+    led_count: usize,
+    stalled_for: Duration,
+) -> Option<Vec<LedColor>> {
+    match preferences.policy {
+        CaptureFailurePolicy::Hold => None,
+        CaptureFailurePolicy::FadeToBlack => Some(faded_colors(
+            last_colors,
+            led_count,
+            Duration::from_millis(preferences.fade_ms),
+            stalled_for,
+        )),
+        CaptureFailurePolicy::DiagnosticColor => {
+            let [r, g, b] = preferences.diagnostic_color;
+            Some(vec![LedColor::new(r, g, b); led_count])
+        }
+    }
+}
+
+/// 等待下一帧截图的结果
+#[derive(Debug, PartialEq, Eq)]
+enum ScreenshotWait {
+    Frame,
+    /// 超时未收到新画面
+    Stalled,
+    /// 截图通道已关闭
+    Closed,
+}
+
+/// 收到新画面时输出的颜色：冻结且已有上一帧颜色时返回上一帧，`sample` 不会被执行；
+/// 否则等待 `sample` 对新画面采样，返回 None 表示本帧不发送
+async fn frame_colors(
+    frozen: bool,
+    last_colors: &[LedColor],
+    sample: impl std::future::Future<Output = Option<Vec<LedColor>>>,
+) -> Option<Vec<LedColor>> {
+    if frozen && !last_colors.is_empty() {
+        return Some(last_colors.to_vec());
+    }
+    sample.await
+}
+
+/// 等待一帧使用 `calibration` 编码的颜色，超时返回 false
+///
+/// `encoded_rx` 需在应用校准之前订阅，订阅前已编码的帧不计入
+pub async fn wait_for_calibration_frame(
+    encoded_rx: &mut watch::Receiver<Option<ColorCalibration>>,
+    calibration: ColorCalibration,
+    timeout: Duration,
+) -> bool {
+    tokio::time::timeout(timeout, async {
+        while encoded_rx.changed().await.is_ok() {
+            if *encoded_rx.borrow_and_update() == Some(calibration) {
+                return true;
+            }
+        }
+        false
+    })
+    .await
+    .unwrap_or(false)
+}
+
+async fn wait_for_screenshot<T>(
+    screenshot_rx: &mut watch::Receiver<T>,
+    timeout: Duration,
+) -> ScreenshotWait {
+    match tokio::time::timeout(timeout, screenshot_rx.changed()).await {
+        Ok(Ok(())) => ScreenshotWait::Frame,
+        Ok(Err(_)) => ScreenshotWait::Closed,
+        Err(_) => ScreenshotWait::Stalled,
+    }
+}
 
 #[derive(Debug, Clone, serde::Serialize, serde::Deserialize)]
 pub struct BorderColors {
@@ -43,6 +678,20 @@ pub struct LedColorsPublisher {
     #[allow(clippy::type_complexity)]
     single_display_config_data: Arc<RwLock<Option<(Vec<LedStripConfig>, BorderColors)>>>,
     active_strip_for_breathing: Arc<RwLock<Option<(u32, String)>>>, // (display_id, border)
+    breathing: Arc<RwLock<BreathingConfig>>,                        // 活跃灯带呼吸效果参数
+    display_colors: Arc<RwLock<HashMap<u32, Vec<u8>>>>,             // 每个显示器最新一帧的RGB数据
+    preview_calibration: Arc<RwLock<Option<ColorCalibration>>>,     // 预览中的颜色校准（不持久化）
+    frame_recorder: Arc<RwLock<Option<FrameRecorder>>>,             // 颜色帧录制器（调试用）
+    manual_color: Arc<RwLock<Option<[u8; 3]>>>,                     // 手动纯色（清除前持续发送）
+    manual_color_version: Arc<RwLock<usize>>,                       // 手动纯色发送任务版本
+    /// 冻结时重发上一帧颜色，不再采样
+    frozen: Arc<RwLock<bool>>,
+    /// 每编码一帧颜色都会发布该帧使用的颜色校准
+    encoded_calibration: Arc<watch::Sender<Option<ColorCalibration>>>,
+    /// 当前运行配置中各灯带的布局（诊断用）
+    strip_layout: Arc<RwLock<Vec<StripLayoutEntry>>>,
+    /// 当前运行配置中展平颜色帧的布局，用于绘制预览图
+    frame_layout: Arc<RwLock<Option<FrameLayout>>>,
 }
 
 impl LedColorsPublisher {
@@ -64,6 +713,16 @@ impl LedColorsPublisher {
                     single_display_config_mode: Arc::new(RwLock::new(false)),
                     single_display_config_data: Arc::new(RwLock::new(None)),
                     active_strip_for_breathing: Arc::new(RwLock::new(None)),
+                    breathing: Arc::new(RwLock::new(BreathingConfig::default())),
+                    display_colors: Arc::new(RwLock::new(HashMap::new())),
+                    preview_calibration: Arc::new(RwLock::new(None)),
+                    frame_recorder: Arc::new(RwLock::new(None)),
+                    manual_color: Arc::new(RwLock::new(None)),
+                    manual_color_version: Arc::new(RwLock::new(0)),
+                    frozen: Arc::new(RwLock::new(false)),
+                    encoded_calibration: Arc::new(watch::channel(None).0),
+                    strip_layout: Arc::new(RwLock::new(Vec::new())),
+                    frame_layout: Arc::new(RwLock::new(None)),
                 }
             })
             .await
@@ -73,6 +732,7 @@ impl LedColorsPublisher {
     async fn start_one_display_colors_fetcher(
         &self,
         display_id: u32,
+        source_display_id: u32, // 采样画面来源显示器（镜像模式下与 display_id 不同）
         _sample_points: Vec<LedSamplePoints>, // 不再使用旧的采样点，改用LED配置
         _bound_scale_factor: f32,
         mappers: Vec<SamplePointMapper>,
@@ -80,23 +740,58 @@ impl LedColorsPublisher {
         strips: Vec<LedStripConfig>,
         color_calibration: ColorCalibration,
         start_led_offset: usize,
+        winding: DisplayWinding,
         all_strips: Vec<LedStripConfig>, // 新增：全部灯带配置，用于正确计算字节偏移
     ) {
         let internal_tasks_version = self.inner_tasks_version.clone();
+        let frozen = self.frozen.clone();
         let screenshot_manager = ScreenshotManager::global().await;
 
-        let screenshot_rx = screenshot_manager.subscribe_by_display_id(display_id).await;
+        let screenshot_rx = screenshot_manager
+            .subscribe_by_display_id(source_display_id)
+            .await;
 
         if let Err(err) = screenshot_rx {
             log::error!("{err}");
             return;
         }
         let mut screenshot_rx = screenshot_rx.unwrap();
+        // 采样画面来源显示器的采集帧间隔，作为单帧处理的时间预算
+        let frame_budget = screenshot_manager
+            .get_frame_interval(source_display_id)
+            .await;
 
-        log::info!("Starting fetcher for display #{display_id}");
+        if source_display_id == display_id {
+            log::info!("Starting fetcher for display #{display_id}");
+        } else {
+            log::info!(
+                "Starting fetcher for display #{display_id} (mirroring display #{source_display_id})"
+            );
+        }
 
         tokio::spawn(async move {
             let init_version = *internal_tasks_version.read().await;
+            // 偏好设置只在变化时重新读取，避免每帧获取偏好设置锁
+            let mut preferences = UserPreferencesManager::global().await.snapshot();
+
+            // 只处理属于当前显示器的LED灯带配置
+            let current_display_strips: Vec<LedStripConfig> = strips
+                .iter()
+                .filter(|strip| strip.display_id == display_id)
+                .cloned()
+                .collect();
+            let led_count: usize = current_display_strips.iter().map(|strip| strip.len).sum();
+
+            // 最后一帧成功采样的颜色及采集停滞的起始时间，用于采集失败时的回退
+            let mut last_colors: Vec<LedColor> = Vec::new();
+            let mut stalled_since: Option<tokio::time::Instant> = None;
+            // 显示器休眠检测：定期确认系统仍报告该显示器，并跟踪持续全黑的画面
+            let mut sleep_detector = DisplaySleepDetector::default();
+            let mut display_available = true;
+            let mut availability_checked_at: Option<tokio::time::Instant> = None;
+            let mut display_asleep = false;
+            // 单帧处理超出采集帧间隔时自动降低采样质量，避免积压
+            let mut adaptive_quality = AdaptiveQualityController::default();
 
             loop {
                 // Check if the inner task version changed FIRST
@@ -106,29 +801,159 @@ impl LedColorsPublisher {
                     break;
                 }
 
-                if let Err(err) = screenshot_rx.changed().await {
-                    log::error!("Screenshot channel closed for display #{display_id}: {err:?}");
-                    break;
-                }
-
-                let screenshot = screenshot_rx.borrow().clone();
-
-                // 使用新的采样函数替换旧的采样逻辑
-                // 只处理属于当前显示器的LED灯带配置
-                let current_display_strips: Vec<LedStripConfig> = strips
-                    .iter()
-                    .filter(|strip| strip.display_id == display_id)
-                    .cloned()
-                    .collect();
+                let capture_failure = preferences.current().capture_failure.clone();
+                let wait_timeout = if stalled_since.is_some() {
+                    CAPTURE_FAILURE_FRAME_INTERVAL
+                } else {
+                    Duration::from_millis(capture_failure.stall_timeout_ms)
+                };
 
-                let mut colors_by_strips = screenshot
-                    .get_colors_by_led_configs(&current_display_strips)
-                    .await;
+                let frozen = *frozen.read().await;
+                let mut channel_closed = false;
+                // 本帧采样开始的时间及截图配置的采样质量，仅对新采样的画面统计处理时间
+                let mut frame_started: Option<(std::time::Instant, usize, bool)> = None;
+                let wait = wait_for_screenshot(&mut screenshot_rx, wait_timeout).await;
+                let capture_paused = wait == ScreenshotWait::Stalled
+                    && ScreenshotManager::global()
+                        .await
+                        .is_capture_paused(source_display_id)
+                        .await;
+                let colors = match wait {
+                    ScreenshotWait::Frame => {
+                        if stalled_since.take().is_some() {
+                            log::info!("Screen capture recovered for display #{display_id}");
+                        }
 
-                Self::apply_reversal_to_colors(&current_display_strips, &mut colors_by_strips);
+                        // 冻结时不对新画面采样，直接重发上一帧
+                        let sampled = frame_colors(frozen, &last_colors, async {
+                            let screenshot = screenshot_rx.borrow().clone();
+                            frame_started = Some((
+                                std::time::Instant::now(),
+                                screenshot.sample_points_per_led,
+                                screenshot.bilinear_sampling,
+                            ));
+
+                            let colors: Vec<LedColor> =
+                                match preferences.current().sampling.sampling_strategy {
+                                    SamplingStrategy::Edges => {
+                                        let (sample_points_per_led, bilinear_sampling) =
+                                            adaptive_quality.sampling(
+                                                screenshot.sample_points_per_led,
+                                                screenshot.bilinear_sampling,
+                                            );
+                                        let screenshot = screenshot
+                                            .with_sample_points_per_led(sample_points_per_led)
+                                            .with_bilinear_sampling(bilinear_sampling);
+
+                                        // 使用新的采样函数替换旧的采样逻辑
+                                        let mut colors_by_strips = screenshot
+                                            .get_colors_by_led_configs(&current_display_strips)
+                                            .await;
+
+                                        Self::apply_reversal_to_colors(
+                                            &current_display_strips,
+                                            &mut colors_by_strips,
+                                        );
+
+                                        // 将二维颜色数组展平为一维数组，保持与旧API的兼容性
+                                        colors_by_strips.iter().flatten().copied().collect()
+                                    }
+                                    SamplingStrategy::ScreenAverage => {
+                                        vec![screenshot.get_average_color(); led_count]
+                                    }
+                                };
+
+                            let sampling_preferences = &preferences.current().sampling;
+                            let sleep_policy = sampling_preferences.display_sleep_policy;
+                            let detect_black_frames =
+                                sampling_preferences.detect_sleep_from_black_frames;
+                            let asleep_for = if sleep_policy == DisplaySleepPolicy::Ignore {
+                                None
+                            } else {
+                                let now = tokio::time::Instant::now();
+                                if availability_checked_at.is_none_or(|checked_at| {
+                                    now.duration_since(checked_at)
+                                        >= DISPLAY_AVAILABILITY_CHECK_INTERVAL
+                                }) {
+                                    display_available =
+                                        Self::is_display_available(source_display_id).await;
+                                    availability_checked_at = Some(now);
+                                }
+                                sleep_detector.update(
+                                    display_available,
+                                    &colors,
+                                    now,
+                                    detect_black_frames,
+                                )
+                            };
+                            if asleep_for.is_some() != display_asleep {
+                                display_asleep = asleep_for.is_some();
+                                log::info!(
+                                    "Display #{display_id} {}, display sleep policy: {sleep_policy:?}",
+                                    if display_asleep { "is asleep" } else { "woke up" }
+                                );
+                            }
 
-                // 将二维颜色数组展平为一维数组，保持与旧API的兼容性
-                let colors: Vec<LedColor> = colors_by_strips.iter().flatten().copied().collect();
+                            display_sleep_colors(
+                                sleep_policy,
+                                &sleep_detector.awake_colors,
+                                colors,
+                                asleep_for,
+                            )
+                        })
+                        .await;
+                        match sampled {
+                            Some(colors) => {
+                                last_colors = colors.clone();
+                                colors
+                            }
+                            None => continue,
+                        }
+                    }
+                    ScreenshotWait::Stalled if capture_paused => {
+                        // 全屏采集配置有意暂停了该显示器，不属于采集失败，保持最后一帧
+                        if stalled_since.take().is_some() {
+                            log::info!("Screen capture paused for display #{display_id}");
+                        }
+                        continue;
+                    }
+                    ScreenshotWait::Stalled if frozen && !last_colors.is_empty() => {
+                        // 冻结期间画面没有变化也持续重发上一帧
+                        last_colors.clone()
+                    }
+                    ScreenshotWait::Stalled => {
+                        let since = *stalled_since.get_or_insert_with(|| {
+                            log::warn!(
+                                "No screenshot for display #{display_id} within {wait_timeout:?}, capture failure policy: {:?}",
+                                capture_failure.policy
+                            );
+                            tokio::time::Instant::now()
+                        });
+                        match capture_failure_colors(
+                            &capture_failure,
+                            &last_colors,
+                            led_count,
+                            since.elapsed(),
+                        ) {
+                            Some(colors) => colors,
+                            None => continue,
+                        }
+                    }
+                    ScreenshotWait::Closed => {
+                        log::error!("Screenshot channel closed for display #{display_id}");
+                        // 通道关闭后不会再有新画面，直接输出回退策略的最终状态
+                        channel_closed = true;
+                        match capture_failure_colors(
+                            &capture_failure,
+                            &last_colors,
+                            led_count,
+                            Duration::MAX,
+                        ) {
+                            Some(colors) => colors,
+                            None => break,
+                        }
+                    }
+                };
 
                 let colors_copy = colors.clone();
 
@@ -155,6 +980,7 @@ impl LedColorsPublisher {
                         &strips,
                         &color_calibration,
                         start_led_offset,
+                        winding,
                         &all_strips,
                     )
                     .await
@@ -172,6 +998,21 @@ impl LedColorsPublisher {
                     // 移除频繁的debug日志，只在模式切换时记录
                 }
 
+                if let Some((started, sample_points_per_led, bilinear_sampling)) = frame_started {
+                    adaptive_quality.record(started.elapsed(), frame_budget);
+                    LedStatusManager::global()
+                        .await
+                        .update_adaptive_quality(
+                            display_id,
+                            adaptive_quality.state(
+                                sample_points_per_led,
+                                bilinear_sampling,
+                                frame_budget,
+                            ),
+                        )
+                        .await;
+                }
+
                 match display_colors_tx.send((
                     display_id,
                     colors_copy
@@ -186,10 +1027,22 @@ impl LedColorsPublisher {
                         warn!("Failed to send display_colors: {}", err);
                     }
                 };
+
+                if channel_closed {
+                    break;
+                }
             }
         });
     }
 
+    /// 系统是否仍报告该显示器，无法查询时视为可用
+    async fn is_display_available(display_id: u32) -> bool {
+        match tokio::task::spawn_blocking(display_info::DisplayInfo::all).await {
+            Ok(Ok(displays)) => displays.iter().any(|display| display.id == display_id),
+            _ => true,
+        }
+    }
+
     fn apply_reversal_to_colors(strips: &[LedStripConfig], colors_by_strips: &mut [Vec<LedColor>]) {
         if strips.len() != colors_by_strips.len() {
             log::warn!(
@@ -208,26 +1061,46 @@ impl LedColorsPublisher {
         &self,
         display_ids: Vec<u32>,
         mappers: Vec<SamplePointMapper>,
+        frame_layout: FrameLayout,
         mut display_colors_rx: broadcast::Receiver<(u32, Vec<u8>)>,
     ) {
         let sorted_colors_tx = self.sorted_colors_tx.clone();
         let colors_tx = self.colors_tx.clone();
+        let display_colors = self.display_colors.clone();
+        let frame_recorder = self.frame_recorder.clone();
 
         tokio::spawn(async move {
-            // 检查当前模式，只有在非颜色校准模式下才设置为环境光
+            // 清除旧配置下的显示器颜色，避免返回过期数据
+            display_colors.write().await.clear();
+
+            // 检查当前模式，只有在非颜色校准、非手动纯色模式下才设置为环境光
             let sender = LedDataSender::global().await;
             let current_mode = sender.get_mode().await;
-            if current_mode != DataSendMode::ColorCalibration {
+            if !matches!(
+                current_mode,
+                DataSendMode::ColorCalibration | DataSendMode::ManualColor
+            ) {
                 sender.set_mode(DataSendMode::AmbientLight).await;
                 log::info!("✅ 氛围光工作器启动，设置LED数据发送模式为: AmbientLight");
             } else {
-                log::info!("🎨 保持颜色校准模式，氛围光工作器跳过模式切换");
+                log::info!("🎨 保持{current_mode}模式，氛围光工作器跳过模式切换");
             }
 
             let sorted_colors_tx = sorted_colors_tx.write().await;
             let colors_tx = colors_tx.write().await;
 
-            let mut all_colors: Vec<Option<Vec<u8>>> = vec![None; display_ids.len()];
+            let display_lens = display_ids
+                .iter()
+                .map(|id| {
+                    frame_layout
+                        .displays
+                        .iter()
+                        .find(|display| display.display_id == *id)
+                        .map_or(0, |display| display.led_count() * 3)
+                })
+                .collect();
+            let mut frame_assembler = FrameAssembler::new(display_lens);
+            let mut preferences = UserPreferencesManager::global().await.snapshot();
             let mut _start: tokio::time::Instant = tokio::time::Instant::now();
 
             loop {
@@ -253,14 +1126,20 @@ impl LedColorsPublisher {
                     continue;
                 }
 
-                all_colors[index.unwrap()] = Some(colors);
+                display_colors
+                    .write()
+                    .await
+                    .insert(display_id, colors.clone());
 
-                if all_colors.iter().all(|color| color.is_some()) {
-                    let flatten_colors = all_colors
-                        .clone()
-                        .into_iter()
-                        .flat_map(|c| c.unwrap())
-                        .collect::<Vec<_>>();
+                let sync_mode = preferences.current().sampling.frame_sync_mode;
+                if let Some(flatten_colors) =
+                    frame_assembler.update(index.unwrap(), colors, sync_mode)
+                {
+                    if let Some(recorder) = frame_recorder.write().await.as_mut() {
+                        if let Err(err) = recorder.record(&frame_layout, &flatten_colors) {
+                            warn!("Failed to record color frame: {}", err);
+                        }
+                    }
 
                     match colors_tx.send(flatten_colors.clone()) {
                         Ok(_) => {}
@@ -380,22 +1259,158 @@ impl LedColorsPublisher {
                 len: s.len,
                 led_type: s.led_type,
                 reversed: s.reversed,
+                hardware_offset: s.hardware_offset,
+                zone_reduction: s.zone_reduction,
+                color_order: s.color_order,
+                sample_shape: s.sample_shape,
+                sample_inset: s.sample_inset,
+                start_trim_percent: s.start_trim_percent,
+                end_trim_percent: s.end_trim_percent,
+                enabled: s.enabled,
+                chain_position: s.chain_position,
+                source_index: None,
+                source_display_id: None,
             });
         }
 
+        // 将镜像来源从内部ID映射为系统ID
+        let mut mirror_sources = HashMap::new();
+        for (target, source) in v2_config.mirror_sources.iter() {
+            match (
+                display_registry.get_display_id_by_internal_id(target).await,
+                display_registry.get_display_id_by_internal_id(source).await,
+            ) {
+                (Ok(target_id), Ok(source_id)) => {
+                    mirror_sources.insert(target_id, source_id);
+                }
+                _ => {
+                    log::warn!("⚠️ 镜像配置 {target} <- {source} 中的显示器未连接，忽略");
+                }
+            }
+        }
+
+        // 将灯带走线方向从内部ID映射为系统ID
+        let mut windings = HashMap::new();
+        for (internal_id, winding) in v2_config.display_windings.iter() {
+            if let Ok(display_id) = display_registry
+                .get_display_id_by_internal_id(internal_id)
+                .await
+            {
+                windings.insert(display_id, *winding);
+            }
+        }
+
+        // 移除位于显示器禁用边上的灯带，使其不采样也不占用串联数据中的位置
+        let mut border_masks = HashMap::new();
+        for (internal_id, mask) in v2_config.display_border_masks.iter() {
+            if let Ok(display_id) = display_registry
+                .get_display_id_by_internal_id(internal_id)
+                .await
+            {
+                border_masks.insert(display_id, *mask);
+            }
+        }
+        v1_group.strips = filter_masked_strips(&v1_group.strips, &border_masks);
+
+        // 跨屏灯带按显示器当前的桌面布局拆分为各显示器上的分段
+        let spans = Self::resolve_strip_spans(&v2_config, &display_registry).await;
+        if !spans.is_empty() {
+            v1_group.strips = expand_spanning_strips(&v1_group.strips, &spans);
+            log::info!("🖥️ 展开了 {} 条跨屏灯带", spans.len());
+        }
+
         // 生成 mapper（保持与 v1 逻辑一致）
         v1_group.generate_mappers();
 
         // 走现有 v1 处理管线
-        self.handle_config_change(v1_group).await;
+        self.handle_config_change(v1_group, mirror_sources, windings, border_masks)
+            .await;
+    }
+
+    /// 解析跨屏灯带经过的显示器在桌面中的水平范围，返回灯带序列号到显示器范围的映射
+    ///
+    /// 灯带不存在、不在上/下边或经过的显示器当前连接不足两个时，该灯带只在所属显示器上采样
+    async fn resolve_strip_spans(
+        v2_config: &LedStripConfigGroupV2,
+        display_registry: &DisplayRegistry,
+    ) -> HashMap<usize, Vec<SpanDisplayBounds>> {
+        if v2_config.strip_spans.is_empty() {
+            return HashMap::new();
+        }
+
+        let displays = match tokio::task::spawn_blocking(display_info::DisplayInfo::all).await {
+            Ok(Ok(displays)) => displays,
+            _ => {
+                log::warn!("⚠️ 无法获取显示器布局，跨屏灯带暂按所属显示器采样");
+                return HashMap::new();
+            }
+        };
+
+        let mut spans = HashMap::new();
+        for span in &v2_config.strip_spans {
+            let Some(strip) = v2_config
+                .strips
+                .iter()
+                .find(|strip| strip.index == span.strip_index)
+            else {
+                continue;
+            };
+            if !matches!(strip.border, Border::Top | Border::Bottom) {
+                log::warn!(
+                    "⚠️ 灯带 {} 位于 {:?} 边，只有上/下边灯带可以跨屏，忽略跨屏设置",
+                    strip.index,
+                    strip.border
+                );
+                continue;
+            }
+
+            let mut bounds = Vec::new();
+            for internal_id in &span.display_internal_ids {
+                let Ok(display_id) = display_registry
+                    .get_display_id_by_internal_id(internal_id)
+                    .await
+                else {
+                    continue;
+                };
+                if let Some(display) = displays.iter().find(|display| display.id == display_id) {
+                    bounds.push(SpanDisplayBounds {
+                        display_id,
+                        x: display.x,
+                        width: display.width,
+                    });
+                }
+            }
+
+            if bounds.len() < 2 {
+                log::warn!(
+                    "⚠️ 灯带 {} 跨越的显示器当前连接不足两个，暂按所属显示器采样",
+                    strip.index
+                );
+                continue;
+            }
+            spans.insert(span.strip_index, bounds);
+        }
+        spans
     }
 
-    async fn handle_config_change(&self, mut original_configs: LedStripConfigGroup) {
-        // Sort strips by index to ensure correct order
-        original_configs.strips.sort_by_key(|s| s.index);
+    /// `mirror_sources` 为目标显示器系统ID到来源显示器系统ID的映射，
+    /// `windings` 为显示器系统ID到灯带走线方向的映射（未设置的显示器为顺时针），
+    /// `border_masks` 为显示器系统ID到启用边的映射（未设置的显示器四边都启用）
+    async fn handle_config_change(
+        &self,
+        mut original_configs: LedStripConfigGroup,
+        mirror_sources: HashMap<u32, u32>,
+        windings: HashMap<u32, DisplayWinding>,
+        border_masks: HashMap<u32, BorderMask>,
+    ) {
+        reset_color_count_mismatch_reports();
+        crate::led_data_processor::reset_spanning_strip_preview().await;
+
+        // Sort strips by physical chain order
+        original_configs.strips.sort_by_key(|s| s.chain_order());
 
         let inner_tasks_version = self.inner_tasks_version.clone();
-        let configs = Self::get_colors_configs(&original_configs).await;
+        let configs = Self::get_colors_configs(&original_configs, &border_masks).await;
 
         if let Err(err) = configs {
             warn!("Failed to get configs: {}", err);
@@ -422,12 +1437,17 @@ impl LedColorsPublisher {
         }
         let updated_configs = updated_configs.unwrap();
 
+        // 记录各灯带的偏移量和长度，便于从日志排查颜色错位问题
+        let strip_layout = Self::strip_layout(&updated_configs.strips);
+        log::info!("LED strip layout:\n{}", format_strip_layout(&strip_layout));
+        *self.strip_layout.write().await = strip_layout;
+
         let (display_colors_tx, display_colors_rx) = broadcast::channel::<(u32, Vec<u8>)>(8);
 
         // Calculate start offsets for each display using updated configs
-        // 按序列号排序灯带，确保正确的串联顺序
+        // 按串联顺序排序灯带
         let mut sorted_strips = updated_configs.strips.clone();
-        sorted_strips.sort_by_key(|strip| strip.index);
+        sorted_strips.sort_by_key(|strip| strip.chain_order());
 
         let mut display_start_offsets = std::collections::HashMap::new();
         let mut cumulative_led_offset = 0;
@@ -442,6 +1462,8 @@ impl LedColorsPublisher {
 
         log::info!("计算的显示器起始偏移量: {display_start_offsets:?}");
 
+        let mut recorded_displays = Vec::new();
+
         for sample_point_group in configs.sample_point_groups.clone() {
             let display_id = sample_point_group.display_id;
             let sample_points = sample_point_group.points;
@@ -456,9 +1478,23 @@ impl LedColorsPublisher {
                 .collect();
 
             let start_led_offset = *display_start_offsets.get(&display_id).unwrap_or(&0);
+            let winding = windings.get(&display_id).copied().unwrap_or_default();
+
+            recorded_displays.push(RecordedDisplay {
+                display_id,
+                strips: display_strips.clone(),
+                start_led_offset,
+                winding,
+            });
+
+            let source_display_id = mirror_sources
+                .get(&display_id)
+                .copied()
+                .unwrap_or(display_id);
 
             self.start_one_display_colors_fetcher(
                 display_id,
+                source_display_id,
                 sample_points,
                 bound_scale_factor,
                 sample_point_group.mappers,
@@ -466,15 +1502,24 @@ impl LedColorsPublisher {
                 display_strips,
                 updated_configs.color_calibration,
                 start_led_offset,
+                winding,
                 updated_configs.strips.clone(), // 传入全部灯带配置
             )
             .await;
         }
 
+        let frame_layout = FrameLayout {
+            displays: recorded_displays,
+            all_strips: updated_configs.strips.clone(),
+            color_calibration: updated_configs.color_calibration,
+        };
+        *self.frame_layout.write().await = Some(frame_layout.clone());
+
         let display_ids = configs.sample_point_groups;
         self.start_all_colors_worker(
             display_ids.iter().map(|c| c.display_id).collect(),
             configs.mappers,
+            frame_layout,
             display_colors_rx,
         );
     }
@@ -494,6 +1539,7 @@ impl LedColorsPublisher {
             DataSendMode::ColorCalibration => "ColorCalibration",
             DataSendMode::TestEffect => "TestEffect",
             DataSendMode::StripConfig => "StripConfig",
+            DataSendMode::ManualColor => "ManualColor",
             _ => "AmbientLight",
         };
 
@@ -571,7 +1617,9 @@ impl LedColorsPublisher {
                 }
 
                 // 发送校准颜色
-                if let Err(e) = Self::send_single_calibration_color(r, g, b).await {
+                if let Err(e) =
+                    Self::send_single_color([r, g, b], DataSendMode::ColorCalibration).await
+                {
                     log::error!("❌ Failed to send calibration color: {}", e);
 
                     // 如果是模式冲突错误，停止任务
@@ -591,20 +1639,21 @@ impl LedColorsPublisher {
         Ok(())
     }
 
-    /// 发送单次校准颜色（内部方法）
+    /// 发送单次纯色到所有LED（内部方法）
     ///
-    /// 用于持续发送任务中的实际颜色发送
+    /// 用于校准和手动纯色持续发送任务中的实际颜色发送，颜色经过校准和颜色顺序编码
     ///
     /// # 参数
-    /// * `r` - 红色分量 (0-255)
-    /// * `g` - 绿色分量 (0-255)
-    /// * `b` - 蓝色分量 (0-255)
-    async fn send_single_calibration_color(r: u8, g: u8, b: u8) -> anyhow::Result<()> {
+    /// * `color` - RGB颜色 (0-255)
+    /// * `mode` - 发送数据所属的模式
+    async fn send_single_color(color: [u8; 3], mode: DataSendMode) -> anyhow::Result<()> {
+        let [r, g, b] = color;
         // 获取配置
         let config_manager_v2 = crate::ambient_light::ConfigManagerV2::global().await;
         let configs_v2 = config_manager_v2.get_config().await;
         let display_registry = config_manager_v2.get_display_registry();
-        let strips = &configs_v2.strips;
+        // 与氛围光模式一致，禁用边上的灯带不占用串联数据中的位置
+        let strips = &configs_v2.active_strips();
 
         if strips.is_empty() {
             return Err(anyhow::anyhow!("No LED strips configured"));
@@ -623,75 +1672,130 @@ impl LedColorsPublisher {
             strips,
             &display_registry,
             Some(&configs_v2.color_calibration),
-            crate::led_data_sender::DataSendMode::ColorCalibration,
-            0, // 校准模式偏移量为0
+            mode,
+            0, // 纯色模式偏移量为0
         )
         .await?;
 
         // 发送到硬件
         let sender = LedDataSender::global().await;
         sender
-            .send_complete_led_data(0, hardware_data, "ColorCalibration")
+            .send_complete_led_data(0, hardware_data, &mode.to_string())
             .await?;
 
-        log::debug!("✅ Calibration color sent successfully");
+        log::debug!("✅ {mode} color sent successfully");
         Ok(())
     }
 
-    /// 计算指定LED位置对应的字节偏移量
+    /// 按发送时使用的偏移量计算生成全部灯带的布局，按串联顺序排列
+    pub fn strip_layout(all_strips: &[LedStripConfig]) -> Vec<StripLayoutEntry> {
+        let offsets = Self::calculate_strip_byte_offsets(all_strips);
+        let mut sorted_strips: Vec<_> = all_strips.iter().collect();
+        sorted_strips.sort_by_key(|strip| strip.chain_order());
+
+        let mut layout: Vec<StripLayoutEntry> = Vec::with_capacity(sorted_strips.len());
+        let mut previous_source = None;
+        for strip in sorted_strips {
+            let byte_len = strip.len * strip.led_type.bytes_per_led();
+            // 跨屏灯带的分段在串联中相邻，合并为原灯带的一项
+            if strip.source_index.is_some() && strip.source_index == previous_source {
+                if let Some(entry) = layout.last_mut() {
+                    entry.len += strip.len;
+                    entry.byte_len += byte_len;
+                    continue;
+                }
+            }
+            previous_source = strip.source_index;
+
+            layout.push(StripLayoutEntry {
+                index: strip.reported_index(),
+                chain_position: layout.len(),
+                display_id: strip.reported_display_id(),
+                border: strip.border,
+                led_type: strip.led_type,
+                len: strip.len,
+                byte_offset: offsets.get(&strip.index).copied().unwrap_or_default(),
+                byte_len,
+                explicit_offset: strip.hardware_offset.is_some(),
+            });
+        }
+        layout
+    }
+
+    /// 获取当前运行配置中各灯带的布局
+    pub async fn get_strip_layout(&self) -> Vec<StripLayoutEntry> {
+        self.strip_layout.read().await.clone()
+    }
+
+    /// 计算每条灯带在硬件数据中的起始字节偏移量（键为灯带序列号）
     ///
+    /// 配置了 `hardware_offset` 的灯带从该位置开始，其余灯带按串联顺序紧接上一条灯带之后。
     /// 考虑不同LED类型的字节数差异：
     /// - WS2812B: 3字节/LED (GRB)
     /// - SK6812: 4字节/LED (GRBW)
-    ///
-    /// # 参数
-    /// * `target_led_offset` - 目标LED的位置偏移量
-    /// * `all_strips` - 所有灯带配置（按index排序）
-    ///
-    /// # 返回值
-    /// 返回对应的字节偏移量
-    fn calculate_byte_offset_for_led_position(
-        target_led_offset: usize,
-        all_strips: &[LedStripConfig],
-    ) -> anyhow::Result<usize> {
-        // 按序列号排序灯带，确保正确的串联顺序
+    fn calculate_strip_byte_offsets(all_strips: &[LedStripConfig]) -> HashMap<usize, usize> {
+        // 按串联顺序排序灯带
         let mut sorted_strips: Vec<_> = all_strips.iter().collect();
-        sorted_strips.sort_by_key(|strip| strip.index);
+        sorted_strips.sort_by_key(|strip| strip.chain_order());
 
-        let mut cumulative_led_count = 0;
-        let mut cumulative_byte_count = 0;
+        let mut offsets = HashMap::with_capacity(sorted_strips.len());
+        let mut next_byte_offset = 0;
 
         for strip in sorted_strips {
-            // 如果目标LED位置在当前灯带范围内
-            if target_led_offset < cumulative_led_count + strip.len {
-                // 计算在当前灯带内的偏移量
-                let offset_in_strip = target_led_offset - cumulative_led_count;
-                let bytes_per_led = match strip.led_type {
-                    crate::ambient_light::config::LedType::WS2812B => 3,
-                    crate::ambient_light::config::LedType::SK6812 => 4,
-                };
-                return Ok(cumulative_byte_count + offset_in_strip * bytes_per_led);
-            }
-
-            // 累加当前灯带的LED数量和字节数
-            cumulative_led_count += strip.len;
-            let bytes_per_led = match strip.led_type {
-                crate::ambient_light::config::LedType::WS2812B => 3,
-                crate::ambient_light::config::LedType::SK6812 => 4,
-            };
-            cumulative_byte_count += strip.len * bytes_per_led;
+            let bytes_per_led = strip.led_type.bytes_per_led();
+            let byte_offset = strip
+                .hardware_offset
+                .map(|offset| offset * bytes_per_led)
+                .unwrap_or(next_byte_offset);
+
+            offsets.insert(strip.index, byte_offset);
+            next_byte_offset = byte_offset + strip.len * bytes_per_led;
         }
 
-        // 如果目标LED位置等于总LED数量，返回总字节数（用于下一个显示器的起始位置）
-        if target_led_offset == cumulative_led_count {
-            Ok(cumulative_byte_count)
-        } else {
-            Err(anyhow::anyhow!(
-                "LED偏移量 {} 超出范围，总LED数量: {}",
-                target_led_offset,
-                cumulative_led_count
-            ))
+        offsets
+    }
+
+    /// 将按串联顺序编码的硬件数据拆分为 (字节偏移量, 数据) 段，相邻的灯带合并为一段
+    fn split_hardware_data_by_strip(
+        hardware_data: &[u8],
+        strips: &[LedStripConfig],
+        all_strips: &[LedStripConfig],
+    ) -> anyhow::Result<Vec<(usize, Vec<u8>)>> {
+        let strip_offsets = Self::calculate_strip_byte_offsets(all_strips);
+
+        let mut sorted_strips: Vec<_> = strips.iter().collect();
+        sorted_strips.sort_by_key(|strip| strip.chain_order());
+
+        let mut segments: Vec<(usize, Vec<u8>)> = Vec::new();
+        let mut data_cursor = 0;
+
+        for strip in sorted_strips {
+            let strip_bytes = strip.len * strip.led_type.bytes_per_led();
+            let data = hardware_data
+                .get(data_cursor..data_cursor + strip_bytes)
+                .ok_or_else(|| {
+                    anyhow::anyhow!(
+                        "灯带 {} 的硬件数据不足: 需要 {} 字节，剩余 {} 字节",
+                        strip.index,
+                        strip_bytes,
+                        hardware_data.len().saturating_sub(data_cursor)
+                    )
+                })?;
+            data_cursor += strip_bytes;
+
+            let byte_offset = *strip_offsets
+                .get(&strip.index)
+                .ok_or_else(|| anyhow::anyhow!("灯带 {} 不在灯带配置中", strip.index))?;
+
+            match segments.last_mut() {
+                Some((start, bytes)) if *start + bytes.len() == byte_offset => {
+                    bytes.extend_from_slice(data);
+                }
+                _ => segments.push((byte_offset, data.to_vec())),
+            }
         }
+
+        Ok(segments)
     }
 
     /// Get updated configs with proper display IDs assigned
@@ -700,49 +1804,47 @@ impl LedColorsPublisher {
     ) -> anyhow::Result<LedStripConfigGroup> {
         let displays = display_info::DisplayInfo::all()
             .map_err(|e| anyhow::anyhow!("Failed to get displays: {}", e))?;
+        let strip_display_ids = Self::strip_display_ids(&displays).await;
 
-        // Create a mutable copy of configs with proper display IDs
         let mut updated_configs = configs.clone();
-        for strip in updated_configs.strips.iter_mut() {
-            if strip.display_id == 0 {
-                // Assign display ID based on strip index
-                let display_index = strip.index / 4;
-                if display_index < displays.len() {
-                    // TEMPORARY FIX: Reverse display order to match UI layout
-                    // This fixes the issue where display detection order doesn't match UI order
-                    let corrected_display_index = if displays.len() == 2 {
-                        1 - display_index // Swap 0->1, 1->0 for 2 displays
-                    } else {
-                        display_index // Keep original for other cases
-                    };
-
-                    if corrected_display_index < displays.len() {
-                        strip.display_id = displays[corrected_display_index].id;
-                        log::info!(
-                            "Assigned display ID {} to strip {} (original_index={}, corrected_index={})",
-                            strip.display_id,
-                            strip.index,
-                            display_index,
-                            corrected_display_index
-                        );
-                    }
-                }
-            }
-        }
+        assign_display_ids(&mut updated_configs, &strip_display_ids);
 
         Ok(updated_configs)
     }
 
+    /// 按V2配置中每条灯带所属的显示器，获取灯带序号到当前系统显示器ID的映射
+    async fn strip_display_ids(displays: &[display_info::DisplayInfo]) -> HashMap<usize, u32> {
+        let system_ids: Vec<u32> = displays.iter().map(|d| d.id).collect();
+        let config_manager = crate::ambient_light::ConfigManagerV2::global().await;
+        let strips = config_manager.get_config().await.strips;
+        let registered = config_manager
+            .get_display_registry()
+            .get_all_displays()
+            .await;
+
+        resolve_strip_display_ids(&strips, &registered, &system_ids)
+    }
+
     pub async fn send_colors_by_display(
         colors: Vec<LedColor>,
         _mappers: Vec<SamplePointMapper>, // 保留参数但不使用，避免破坏API
         strips: &[LedStripConfig],
         color_calibration: &ColorCalibration,
         start_led_offset: usize,
+        winding: DisplayWinding,
         all_strips: &[LedStripConfig], // 新增：全部灯带配置，用于正确计算字节偏移
     ) -> anyhow::Result<()> {
         // 将一维颜色数组转换为二维数组，按灯带分组
-        let led_colors_2d = Self::convert_1d_to_2d_colors(&colors, strips)?;
+        let color_count_policy = UserPreferencesManager::global()
+            .await
+            .get_color_count_policy()
+            .await;
+        let led_colors_2d = Self::convert_1d_to_2d_colors(&colors, strips, color_count_policy)?;
+
+        // 预览校准优先于已保存的校准，便于在实时画面上对比效果
+        let publisher = Self::global().await;
+        let preview_calibration = publisher.get_preview_calibration().await;
+        let color_calibration = preview_calibration.as_ref().unwrap_or(color_calibration);
 
         // 使用新的LED数据处理器
         let hardware_data = crate::led_data_processor::LedDataProcessor::process_and_publish(
@@ -751,20 +1853,25 @@ impl LedColorsPublisher {
             Some(color_calibration),
             crate::led_data_sender::DataSendMode::AmbientLight,
             start_led_offset,
+            winding,
         )
         .await?;
+        publisher
+            .encoded_calibration
+            .send_replace(Some(*color_calibration));
 
         // 发送到硬件
         let sender = LedDataSender::global().await;
 
-        // 正确计算字节偏移量：需要考虑不同LED类型的字节数差异
-        // 不能简单地用LED数量乘以3，因为SK6812是4字节/LED，WS2812B是3字节/LED
-        let byte_offset =
-            Self::calculate_byte_offset_for_led_position(start_led_offset, all_strips)?;
+        // 按灯带拆分发送：每条灯带使用显式的硬件偏移量或紧接上一条灯带的偏移量
+        // 字节偏移量需要考虑不同LED类型的字节数差异（SK6812是4字节/LED，WS2812B是3字节/LED）
+        let segments = Self::split_hardware_data_by_strip(&hardware_data, strips, all_strips)?;
 
-        sender
-            .send_complete_led_data(byte_offset as u16, hardware_data, "AmbientLight")
-            .await?;
+        for (byte_offset, data) in segments {
+            sender
+                .send_complete_led_data(byte_offset as u16, data, "AmbientLight")
+                .await?;
+        }
 
         Ok(())
     }
@@ -774,16 +1881,21 @@ impl LedColorsPublisher {
     /// # 参数
     /// * `colors` - 一维颜色数组，包含所有LED的颜色
     /// * `strips` - LED灯带配置数组
+    /// * `policy` - 颜色数量与LED总数不一致时的处理方式
     ///
     /// # 返回值
     /// 返回二维颜色数组，外层按strips排序，内层为每个LED的颜色
     fn convert_1d_to_2d_colors(
         colors: &[LedColor],
         strips: &[LedStripConfig],
+        policy: ColorCountPolicy,
     ) -> anyhow::Result<Vec<Vec<LedColor>>> {
-        // 按序列号排序灯带，确保正确的串联顺序
+        let led_count = strips.iter().map(|strip| strip.len).sum();
+        let colors = fit_color_count(colors, led_count, policy);
+
+        // 按串联顺序排序灯带
         let mut sorted_strips: Vec<_> = strips.iter().enumerate().collect();
-        sorted_strips.sort_by_key(|(_, strip)| strip.index);
+        sorted_strips.sort_by_key(|(_, strip)| strip.chain_order());
 
         log::debug!(
             "排序后的灯带顺序: {:?}",
@@ -807,35 +1919,12 @@ impl LedColorsPublisher {
                 color_offset
             );
 
-            // 检查颜色数据是否足够
-            if color_offset + strip_len > colors.len() {
-                log::warn!(
-                    "灯带 {} 颜色范围 {}..{} 超出可用颜色数量 ({})",
-                    original_index,
-                    color_offset,
-                    color_offset + strip_len,
-                    colors.len()
-                );
-                // 用黑色填充不足的部分
-                let available_colors = colors.len().saturating_sub(color_offset);
-                let mut strip_colors = Vec::with_capacity(strip_len);
-
-                // 添加可用的颜色
-                for i in 0..available_colors {
-                    strip_colors.push(colors[color_offset + i]);
-                }
-
-                // 用黑色填充剩余部分
-                for _ in available_colors..strip_len {
-                    strip_colors.push(LedColor::new(0, 0, 0));
-                }
-
-                led_colors_2d[original_index] = strip_colors;
-                color_offset += strip_len;
+            // 长度为0的灯带保持空数组，不消耗颜色
+            if strip_len == 0 {
                 continue;
             }
 
-            // 提取这个灯带的颜色
+            // 提取这个灯带的颜色（颜色数量已调整为LED总数）
             let strip_colors: Vec<LedColor> =
                 colors[color_offset..color_offset + strip_len].to_vec();
             led_colors_2d[original_index] = strip_colors;
@@ -848,8 +1937,10 @@ impl LedColorsPublisher {
     pub async fn clone_sorted_colors_receiver(&self) -> watch::Receiver<Vec<u8>> {
         self.sorted_colors_rx.read().await.clone()
     }
+    /// 生成各显示器的采样点配置，位于 `border_masks` 中禁用边上的灯带不生成采样点
     pub async fn get_colors_configs(
         configs: &LedStripConfigGroup,
+        border_masks: &HashMap<u32, BorderMask>,
     ) -> anyhow::Result<AllColorConfig> {
         // Get actual display information and assign IDs if needed
         let displays = display_info::DisplayInfo::all().map_err(|e| {
@@ -857,49 +1948,34 @@ impl LedColorsPublisher {
             anyhow::anyhow!("Failed to get display info: {}", e)
         })?;
 
-        // Create a mutable copy of configs with proper display IDs
+        let strip_display_ids = Self::strip_display_ids(&displays).await;
         let mut updated_configs = configs.clone();
-        for strip in updated_configs.strips.iter_mut() {
-            if strip.display_id == 0 {
-                // Assign display ID based on strip index
-                let display_index = strip.index / 4;
-                if display_index < displays.len() {
-                    // TEMPORARY FIX: Reverse display order to match UI layout
-                    // This fixes the issue where display detection order doesn't match UI order
-                    let corrected_display_index = if displays.len() == 2 {
-                        1 - display_index // Swap 0->1, 1->0 for 2 displays
-                    } else {
-                        display_index // Keep original for other cases
-                    };
-
-                    if corrected_display_index < displays.len() {
-                        strip.display_id = displays[corrected_display_index].id;
-                        log::info!(
-                            "get_colors_configs - Assigned display ID {} to strip {} (original_index={}, corrected_index={})",
-                            strip.display_id,
-                            strip.index,
-                            display_index,
-                            corrected_display_index
-                        );
-                    }
-                }
-            }
-        }
+        assign_display_ids(&mut updated_configs, &strip_display_ids);
 
         let mappers = updated_configs.mappers.clone();
+        let led_positions = crate::ambient_light::ConfigManagerV2::global()
+            .await
+            .get_led_positions()
+            .await;
 
         let mut colors_configs = Vec::new();
 
         for display_info in displays {
             let display_id = display_info.id;
+            let border_mask = border_masks.get(&display_id).copied().unwrap_or_default();
 
             let mut led_strip_configs: Vec<_> = updated_configs
                 .strips
                 .iter()
-                .filter(|c| c.display_id == display_id)
+                .filter(|c| c.display_id == display_id && border_mask.contains(c.border))
                 .cloned()
                 .collect();
 
+            if led_strip_configs.is_empty() && border_mask != BorderMask::default() {
+                log::debug!("Display {display_id} has no LED strips on its enabled borders");
+                continue;
+            }
+
             if led_strip_configs.is_empty() {
                 warn!(
                     "No LED strip config for display_id: {}, using default.",
@@ -911,8 +1987,8 @@ impl LedColorsPublisher {
                 ));
             }
 
-            // 按序列号排序，确保与send_colors_by_display中的顺序一致
-            led_strip_configs.sort_by_key(|strip| strip.index);
+            // 按串联顺序排序，确保与send_colors_by_display中的顺序一致
+            led_strip_configs.sort_by_key(|strip| strip.chain_order());
 
             // Create a dummy screenshot object to calculate sample points
             let dummy_screenshot = Screenshot::new(
@@ -923,7 +1999,8 @@ impl LedColorsPublisher {
                 Arc::new(vec![]),
                 display_info.scale_factor,
                 display_info.scale_factor,
-            );
+            )
+            .with_led_positions(led_positions.clone());
 
             let points: Vec<_> = led_strip_configs
                 .iter()
@@ -939,7 +2016,9 @@ impl LedColorsPublisher {
                 .mappers
                 .iter()
                 .zip(&updated_configs.strips)
-                .filter(|(_, strip)| strip.display_id == display_id)
+                .filter(|(_, strip)| {
+                    strip.display_id == display_id && border_mask.contains(strip.border)
+                })
                 .map(|(mapper, _)| mapper.clone())
                 .collect();
 
@@ -963,6 +2042,34 @@ impl LedColorsPublisher {
         self.colors_rx.read().await.clone()
     }
 
+    /// 用当前的LED颜色和灯带配置绘制氛围光预览图，返回 PNG 数据
+    pub async fn render_preview_png(&self, width: u32, height: u32) -> anyhow::Result<Vec<u8>> {
+        validate_preview_render_size(width, height)?;
+
+        let colors = self.colors_rx.read().await.borrow().clone();
+        let frame_layout = self.frame_layout.read().await.clone();
+
+        // 绘制和 PNG 编码是CPU密集的同步操作，不占用异步运行时的工作线程
+        tokio::task::spawn_blocking(move || {
+            let displays = frame_layout
+                .as_ref()
+                .map(|layout| layout.split_frame(&colors))
+                .unwrap_or_default();
+            encode_preview_png(&render_ambient_preview(&displays, width, height))
+        })
+        .await?
+    }
+
+    /// 获取指定显示器最新一帧采样颜色的平均值
+    ///
+    /// 尚未产生该显示器的帧时返回 None
+    pub async fn get_display_average_color(&self, display_id: u32) -> Option<LedColor> {
+        let display_colors = self.display_colors.read().await;
+        display_colors
+            .get(&display_id)
+            .and_then(|colors| average_rgb_bytes(colors))
+    }
+
     /// Enable test mode - this will set the data send mode to TestEffect
     pub async fn enable_test_mode(&self) {
         let sender = LedDataSender::global().await;
@@ -974,17 +2081,7 @@ impl LedColorsPublisher {
     /// Disable test mode - this will resume normal LED data publishing
     pub async fn disable_test_mode(&self) {
         let sender = LedDataSender::global().await;
-
-        // Check if ambient light is enabled to determine the correct mode to restore
-        let ambient_light_state_manager =
-            crate::ambient_light_state::AmbientLightStateManager::global().await;
-        let ambient_light_enabled = ambient_light_state_manager.is_enabled().await;
-
-        let restore_mode = if ambient_light_enabled {
-            DataSendMode::AmbientLight
-        } else {
-            DataSendMode::None
-        };
+        let restore_mode = self.idle_mode().await;
 
         sender.set_mode(restore_mode).await;
 
@@ -996,6 +2093,13 @@ impl LedColorsPublisher {
     pub async fn restart_ambient_light_publisher(&self) -> anyhow::Result<()> {
         log::info!("🔄 重新启动环境光发布器...");
 
+        // 手动纯色优先于环境光，恢复为手动纯色模式
+        let sender = LedDataSender::global().await;
+        if self.get_manual_color().await.is_some() {
+            sender.set_mode(DataSendMode::ManualColor).await;
+            log::info!("🎨 手动纯色已设置，恢复LED数据发送模式为: ManualColor");
+        }
+
         // 检查环境光是否启用
         let ambient_light_state_manager =
             crate::ambient_light_state::AmbientLightStateManager::global().await;
@@ -1006,14 +2110,16 @@ impl LedColorsPublisher {
             return Ok(());
         }
 
-        // 检查当前模式，只有在非颜色校准模式下才设置为环境光
-        let sender = LedDataSender::global().await;
+        // 检查当前模式，只有在非颜色校准、非手动纯色模式下才设置为环境光
         let current_mode = sender.get_mode().await;
-        if current_mode != DataSendMode::ColorCalibration {
+        if !matches!(
+            current_mode,
+            DataSendMode::ColorCalibration | DataSendMode::ManualColor
+        ) {
             sender.set_mode(DataSendMode::AmbientLight).await;
             log::info!("✅ 设置LED数据发送模式为: AmbientLight");
         } else {
-            log::info!("🎨 保持颜色校准模式，跳过模式切换");
+            log::info!("🎨 保持{current_mode}模式，跳过模式切换");
         }
 
         // 重新启动氛围光处理任务 - 使用ConfigManagerV2保持一致性
@@ -1027,13 +2133,169 @@ impl LedColorsPublisher {
             self.handle_config_change_v2(v2_config, display_registry)
                 .await;
         } else {
-            log::warn!("⚠️ 当前LED配置为空，无法重新启动氛围光处理");
+            // 停止按旧配置运行的任务（例如配置被重置后）
+            let mut version = self.inner_tasks_version.write().await;
+            *version = version.overflowing_add(1).0;
+            log::warn!("⚠️ 当前LED配置为空，已停止氛围光处理任务");
         }
 
         log::info!("✅ 环境光发布器重启完成");
         Ok(())
     }
 
+    /// 设置手动纯色并切换到手动纯色模式
+    ///
+    /// 与一次性的颜色校准不同，手动纯色会以固定频率持续发送，直到调用
+    /// [`Self::clear_manual_color`] 清除。期间其他模式（如测试效果）临时接管时暂停发送，
+    /// 结束后恢复。
+    pub async fn set_manual_color(&self, color: [u8; 3]) -> anyhow::Result<()> {
+        let [r, g, b] = color;
+        log::info!("🎨 Setting manual color: RGB({r}, {g}, {b})");
+
+        let was_active = self.manual_color.write().await.replace(color).is_some();
+
+        let sender = LedDataSender::global().await;
+        sender.set_mode(DataSendMode::ManualColor).await;
+
+        if !was_active {
+            self.start_manual_color_task().await;
+        }
+
+        Self::persist_send_mode(DataSendMode::ManualColor, Some(color)).await;
+
+        // 立即发送一次，避免等待下一个发送周期
+        Self::send_single_color(color, DataSendMode::ManualColor).await
+    }
+
+    /// 清除手动纯色，根据环境光状态恢复发送模式
+    pub async fn clear_manual_color(&self) {
+        if self.manual_color.write().await.take().is_none() {
+            return;
+        }
+
+        {
+            let mut version = self.manual_color_version.write().await;
+            *version = version.overflowing_add(1).0;
+        }
+
+        let restore_mode = self.idle_mode().await;
+        Self::persist_send_mode(restore_mode, None).await;
+
+        let sender = LedDataSender::global().await;
+        if sender.get_mode().await == DataSendMode::ManualColor {
+            sender.set_mode(restore_mode).await;
+            log::info!("✅ 手动纯色已清除，恢复LED数据发送模式为: {restore_mode}");
+        } else {
+            log::info!("✅ 手动纯色已清除");
+        }
+    }
+
+    /// 恢复上次退出前持久化的发送模式（目前仅手动纯色需要恢复）
+    ///
+    /// 环境光启用状态由 `AmbientLightStateManager` 单独持久化，应在发布器启动后调用
+    pub async fn restore_persisted_send_mode(&self) -> anyhow::Result<()> {
+        let preferences_manager = crate::user_preferences::UserPreferencesManager::global().await;
+        let send_mode = preferences_manager.get_send_mode().await;
+
+        match (send_mode.last_mode, send_mode.manual_color) {
+            (DataSendMode::ManualColor, Some(color)) => {
+                log::info!("♻️ 恢复上次的手动纯色: {color:?}");
+                self.set_manual_color(color).await
+            }
+            _ => Ok(()),
+        }
+    }
+
+    /// 持久化当前的常驻发送模式，失败时只记录警告
+    async fn persist_send_mode(mode: DataSendMode, manual_color: Option<[u8; 3]>) {
+        let preferences_manager = crate::user_preferences::UserPreferencesManager::global().await;
+        if let Err(e) = preferences_manager
+            .update_send_mode(mode, manual_color)
+            .await
+        {
+            log::warn!("⚠️ Failed to persist send mode: {e}");
+        }
+    }
+
+    /// 获取当前的手动纯色，未设置时返回 None
+    pub async fn get_manual_color(&self) -> Option<[u8; 3]> {
+        *self.manual_color.read().await
+    }
+
+    /// 没有临时模式（测试效果、定位色等）时应处于的发送模式
+    ///
+    /// 手动纯色优先，其次根据环境光是否启用决定
+    async fn idle_mode(&self) -> DataSendMode {
+        if self.get_manual_color().await.is_some() {
+            return DataSendMode::ManualColor;
+        }
+
+        let ambient_light_state_manager =
+            crate::ambient_light_state::AmbientLightStateManager::global().await;
+        if ambient_light_state_manager.is_enabled().await {
+            DataSendMode::AmbientLight
+        } else {
+            DataSendMode::None
+        }
+    }
+
+    /// 启动手动纯色持续发送任务
+    ///
+    /// 使用独立的任务版本号，灯带配置变化重启氛围光任务时不会中断手动纯色
+    async fn start_manual_color_task(&self) {
+        let current_version = *self.manual_color_version.read().await;
+        let manual_color_version = self.manual_color_version.clone();
+        let manual_color = self.manual_color.clone();
+
+        tokio::spawn(async move {
+            let mut interval = tokio::time::interval(MANUAL_COLOR_SEND_INTERVAL);
+            let sender = LedDataSender::global().await;
+
+            loop {
+                interval.tick().await;
+
+                if *manual_color_version.read().await != current_version {
+                    log::info!("🛑 Manual color task stopped (version changed)");
+                    break;
+                }
+
+                let Some(color) = *manual_color.read().await else {
+                    break;
+                };
+
+                // 其他模式临时接管时暂停发送
+                if sender.get_mode().await != DataSendMode::ManualColor {
+                    continue;
+                }
+
+                if let Err(e) = Self::send_single_color(color, DataSendMode::ManualColor).await {
+                    log::warn!("❌ Failed to send manual color: {e}");
+                }
+            }
+        });
+
+        log::info!("✅ Manual color continuous sending task started");
+    }
+
+    /// 冻结或恢复氛围光颜色
+    ///
+    /// 冻结期间各显示器的采样任务不再处理新画面，而是持续重发最后一帧颜色；
+    /// 发送模式保持不变，解除冻结后从下一帧画面起恢复实时采样。
+    pub async fn set_frozen(&self, frozen: bool) {
+        let was_frozen = std::mem::replace(&mut *self.frozen.write().await, frozen);
+        if was_frozen != frozen {
+            log::info!(
+                "🧊 Ambient light colors {}",
+                if frozen { "frozen" } else { "unfrozen" }
+            );
+        }
+    }
+
+    /// 氛围光颜色是否已冻结
+    pub async fn is_frozen(&self) -> bool {
+        *self.frozen.read().await
+    }
+
     /// Check if test mode is currently active
     pub async fn is_test_mode_active(&self) -> bool {
         let sender = LedDataSender::global().await;
@@ -1145,17 +2407,7 @@ impl LedColorsPublisher {
 
         // 恢复LED数据发送模式，根据环境光状态决定
         let sender = crate::led_data_sender::LedDataSender::global().await;
-
-        // Check if ambient light is enabled to determine the correct mode to restore
-        let ambient_light_state_manager =
-            crate::ambient_light_state::AmbientLightStateManager::global().await;
-        let ambient_light_enabled = ambient_light_state_manager.is_enabled().await;
-
-        let restore_mode = if ambient_light_enabled {
-            crate::led_data_sender::DataSendMode::AmbientLight
-        } else {
-            crate::led_data_sender::DataSendMode::None
-        };
+        let restore_mode = self.idle_mode().await;
 
         sender.set_mode(restore_mode).await;
         log::info!("✅ 恢复LED数据发送模式为: {restore_mode:?}");
@@ -1178,23 +2430,169 @@ impl LedColorsPublisher {
         Ok(())
     }
 
-    /// 设置活跃灯带用于呼吸效果
+    /// 设置活跃灯带用于呼吸效果，`breathing` 为呼吸频率与亮度范围
     pub async fn set_active_strip_for_breathing(
         &self,
         display_id: u32,
         border: Option<String>,
+        breathing: BreathingConfig,
     ) -> anyhow::Result<()> {
+        breathing.validate()?;
+
         log::info!("🫁 设置活跃灯带用于呼吸效果");
         log::info!("   - 显示器ID: {display_id}");
         log::info!("   - 边框: {border:?}");
+        log::info!("   - 呼吸参数: {breathing:?}");
+
+        {
+            let mut active_strip = self.active_strip_for_breathing.write().await;
+            *active_strip = border.map(|b| (display_id, b));
+        }
+        *self.breathing.write().await = breathing;
+
+        log::info!("✅ 活跃灯带状态已更新");
+        Ok(())
+    }
+
+    /// 设置预览颜色校准
+    ///
+    /// 环境光模式下使用该校准编码硬件数据，但不修改已保存的配置
+    pub async fn set_preview_calibration(&self, calibration: ColorCalibration) {
+        let mut preview = self.preview_calibration.write().await;
+        *preview = Some(calibration);
+
+        log::info!(
+            "🎨 预览颜色校准已设置: r={:.3}, g={:.3}, b={:.3}, w={:.3}",
+            calibration.r,
+            calibration.g,
+            calibration.b,
+            calibration.w
+        );
+    }
+
+    /// 清除预览颜色校准，恢复使用已保存的校准
+    pub async fn clear_preview_calibration(&self) {
+        let mut preview = self.preview_calibration.write().await;
+        *preview = None;
+
+        log::info!("🎨 预览颜色校准已清除");
+    }
+
+    /// 获取当前预览颜色校准
+    pub async fn get_preview_calibration(&self) -> Option<ColorCalibration> {
+        *self.preview_calibration.read().await
+    }
+
+    /// 订阅每帧编码所用的颜色校准，配合 `wait_for_calibration_frame` 确认校准已生效
+    pub fn subscribe_encoded_calibration(&self) -> watch::Receiver<Option<ColorCalibration>> {
+        self.encoded_calibration.subscribe()
+    }
+
+    /// 开始录制展平后的颜色帧到文件（已有录制会先结束）
+    pub async fn record_frames(&self, path: PathBuf) -> anyhow::Result<()> {
+        let recorder = FrameRecorder::create(&path).await?;
+        let previous = self.frame_recorder.write().await.replace(recorder);
+
+        if let Some(previous) = previous {
+            let frame_count = previous.finish().await?;
+            log::info!("⏹️ 已结束之前的颜色帧录制，共 {frame_count} 帧");
+        }
+
+        log::info!("⏺️ 开始录制颜色帧: {}", path.display());
+        Ok(())
+    }
+
+    /// 停止录制颜色帧，返回录制的帧数
+    pub async fn stop_recording(&self) -> anyhow::Result<usize> {
+        let recorder = self
+            .frame_recorder
+            .write()
+            .await
+            .take()
+            .ok_or_else(|| anyhow::anyhow!("No frame recording in progress"))?;
+
+        let path = recorder.path().to_path_buf();
+        let frame_count = recorder.finish().await?;
+        log::info!("⏹️ 颜色帧录制完成: {} ({frame_count} 帧)", path.display());
+        Ok(frame_count)
+    }
+
+    /// 回放录制的颜色帧，按原始时间间隔送入发送管线，回放结束后恢复环境光
+    ///
+    /// 返回录制文件中的帧数，回放在后台进行
+    pub async fn replay_frames(&self, path: PathBuf) -> anyhow::Result<usize> {
+        let (layout, frames) = {
+            let path = path.clone();
+            tokio::task::spawn_blocking(move || read_recording(path)).await??
+        };
+        let frame_count = frames.len();
+
+        // 停止实时采样任务，避免与回放数据冲突
+        let replay_version = {
+            let mut version = self.inner_tasks_version.write().await;
+            *version = version.overflowing_add(1).0;
+            *version
+        };
+        tokio::time::sleep(Duration::from_millis(100)).await;
+
+        LedDataSender::global()
+            .await
+            .set_mode(DataSendMode::AmbientLight)
+            .await;
+
+        log::info!("▶️ 开始回放颜色帧: {} ({frame_count} 帧)", path.display());
+
+        let inner_tasks_version = self.inner_tasks_version.clone();
+        tokio::spawn(async move {
+            let started_at = tokio::time::Instant::now();
+
+            for frame in frames {
+                tokio::time::sleep_until(started_at + Duration::from_millis(frame.timestamp_ms))
+                    .await;
+
+                if *inner_tasks_version.read().await != replay_version {
+                    log::info!("🛑 颜色帧回放已中断（任务版本变化）");
+                    return;
+                }
+
+                let colors = match frame.colors() {
+                    Ok(colors) => colors,
+                    Err(err) => {
+                        warn!("Skipping invalid recorded frame: {}", err);
+                        continue;
+                    }
+                };
+
+                for (display, display_colors) in layout.split_frame(&colors) {
+                    if let Err(err) = Self::send_colors_by_display(
+                        display_colors,
+                        vec![],
+                        &display.strips,
+                        &layout.color_calibration,
+                        display.start_led_offset,
+                        display.winding,
+                        &layout.all_strips,
+                    )
+                    .await
+                    {
+                        warn!(
+                            "Failed to replay colors for display #{}: {}",
+                            display.display_id, err
+                        );
+                    }
+                }
+            }
 
-        {
-            let mut active_strip = self.active_strip_for_breathing.write().await;
-            *active_strip = border.map(|b| (display_id, b));
-        }
+            log::info!("✅ 颜色帧回放完成");
+            if let Err(err) = Self::global().await.restart_ambient_light_publisher().await {
+                warn!(
+                    "Failed to restart ambient light publisher after replay: {}",
+                    err
+                );
+            }
+        });
 
-        log::info!("✅ 活跃灯带状态已更新");
-        Ok(())
+        Ok(frame_count)
     }
 
     /// 启动单屏配置模式的30Hz发布任务
@@ -1276,6 +2674,7 @@ impl LedColorsPublisher {
             let active_strip_guard = self.active_strip_for_breathing.read().await;
             active_strip_guard.clone()
         };
+        let breathing = *self.breathing.read().await;
 
         // 4. 生成RGB格式预览数据
         let rgb_preview_buffer = self.generate_rgb_colors_for_preview(
@@ -1283,6 +2682,7 @@ impl LedColorsPublisher {
             &all_configs,
             &edge_colors,
             active_strip,
+            breathing,
         )?;
 
         // 5. 发布RGB预览数据到前端
@@ -1392,7 +2792,7 @@ impl LedColorsPublisher {
     ) -> anyhow::Result<Vec<u8>> {
         // 简化实现，专门用于测试，不包含呼吸效果
         let mut sorted_strips = config_group.strips.clone();
-        sorted_strips.sort_by_key(|s| s.index);
+        sorted_strips.sort_by_key(|s| s.chain_order());
 
         let mut buffer = Vec::new();
 
@@ -1421,6 +2821,7 @@ impl LedColorsPublisher {
                         buffer.push(rgb[0]); // R
                         buffer.push(rgb[2]); // B
                     }
+                    LedType::WS2811 => buffer.extend_from_slice(&rgb),
                     LedType::SK6812 => {
                         buffer.push(rgb[1]); // G
                         buffer.push(rgb[0]); // R
@@ -1441,10 +2842,11 @@ impl LedColorsPublisher {
         all_configs: &LedStripConfigGroup,
         edge_colors: &std::collections::HashMap<Border, [LedColor; 2]>,
         active_strip: Option<(u32, String)>, // (display_id, border)
+        breathing: BreathingConfig,
     ) -> anyhow::Result<Vec<u8>> {
-        // 按序列号排序所有灯带
+        // 按串联顺序排序所有灯带
         let mut all_sorted_strips = all_configs.strips.clone();
-        all_sorted_strips.sort_by_key(|s| s.index);
+        all_sorted_strips.sort_by_key(|s| s.chain_order());
 
         // 计算总LED数量
         let total_leds: usize = all_sorted_strips.iter().map(|s| s.len).sum();
@@ -1455,17 +2857,14 @@ impl LedColorsPublisher {
         let current_display_strips: std::collections::HashSet<usize> =
             config_group.strips.iter().map(|s| s.index).collect();
 
-        // 简单的正弦函数呼吸效果 - 1Hz频率
+        // 正弦呼吸效果，频率与亮度范围由 `breathing` 决定
         let time_ms = std::time::SystemTime::now()
             .duration_since(std::time::UNIX_EPOCH)
             .unwrap_or_default()
             .as_millis();
 
         let time_seconds = time_ms as f64 / 1000.0;
-
-        // 1Hz正弦波，范围从0.3到1.0 (30%到100%亮度)
-        let breathing_factor = (time_seconds * std::f64::consts::PI).sin() * 0.5 + 0.5; // 0到1
-        let breathing_brightness = (0.3 + 0.7 * breathing_factor) as f32; // 30%到100%
+        let breathing_brightness = breathing.brightness_at(time_seconds);
 
         // 定义填充颜色：如果有活跃灯带则用白色填充，否则用黑色（保持原有行为）
         let fill_rgb = if active_strip.is_some() {
@@ -1548,16 +2947,16 @@ impl LedColorsPublisher {
         rgb_buffer: &[u8],
         all_configs: &LedStripConfigGroup,
     ) -> anyhow::Result<(Vec<u8>, u16)> {
-        // 按序列号排序所有灯带
+        // 按串联顺序排序所有灯带
         let mut all_sorted_strips = all_configs.strips.clone();
-        all_sorted_strips.sort_by_key(|s| s.index);
+        all_sorted_strips.sort_by_key(|s| s.chain_order());
 
         // 计算总字节数
         let total_bytes: usize = all_sorted_strips
             .iter()
             .map(|s| {
                 let bytes_per_led = match s.led_type {
-                    LedType::WS2812B => 3,
+                    LedType::WS2812B | LedType::WS2811 => 3,
                     LedType::SK6812 => 4,
                 };
                 s.len * bytes_per_led
@@ -1584,6 +2983,10 @@ impl LedColorsPublisher {
                             hardware_buffer.push(r); // R
                             hardware_buffer.push(b); // B
                         }
+                        LedType::WS2811 => {
+                            // RGB格式
+                            hardware_buffer.extend_from_slice(&[r, g, b]);
+                        }
                         LedType::SK6812 => {
                             // GRBW格式
                             hardware_buffer.push(g); // G
@@ -1612,10 +3015,11 @@ impl LedColorsPublisher {
         all_configs: &LedStripConfigGroup,
         edge_colors: &std::collections::HashMap<Border, [LedColor; 2]>,
         active_strip: Option<(u32, String)>, // (display_id, border)
+        breathing: BreathingConfig,
     ) -> anyhow::Result<(Vec<u8>, u16)> {
-        // 按序列号排序所有灯带
+        // 按串联顺序排序所有灯带
         let mut all_sorted_strips = all_configs.strips.clone();
-        all_sorted_strips.sort_by_key(|s| s.index);
+        all_sorted_strips.sort_by_key(|s| s.chain_order());
 
         // 计算总LED数量和总字节数
         let total_leds: usize = all_sorted_strips.iter().map(|s| s.len).sum();
@@ -1623,7 +3027,7 @@ impl LedColorsPublisher {
             .iter()
             .map(|s| {
                 let bytes_per_led = match s.led_type {
-                    LedType::WS2812B => 3,
+                    LedType::WS2812B | LedType::WS2811 => 3,
                     LedType::SK6812 => 4,
                 };
                 s.len * bytes_per_led
@@ -1634,17 +3038,14 @@ impl LedColorsPublisher {
         let current_display_strips: std::collections::HashSet<usize> =
             config_group.strips.iter().map(|s| s.index).collect();
 
-        // 简单的正弦函数呼吸效果 - 1Hz频率
+        // 正弦呼吸效果，频率与亮度范围由 `breathing` 决定
         let time_ms = std::time::SystemTime::now()
             .duration_since(std::time::UNIX_EPOCH)
             .unwrap_or_default()
             .as_millis();
 
         let time_seconds = time_ms as f64 / 1000.0;
-
-        // 1Hz正弦波，范围从0.3到1.0 (30%到100%亮度)
-        let breathing_factor = (time_seconds * std::f64::consts::PI).sin() * 0.5 + 0.5; // 0到1
-        let breathing_brightness = (0.3 + 0.7 * breathing_factor) as f32; // 30%到100%
+        let breathing_brightness = breathing.brightness_at(time_seconds);
 
         // 定义填充颜色：如果有活跃灯带则用白色填充，否则用黑色（保持原有行为）
         let (fill_rgb, fill_w) = if active_strip.is_some() {
@@ -1657,6 +3058,11 @@ impl LedColorsPublisher {
 
         // 遍历所有灯带，按序列号顺序生成完整的LED数据流
         for strip in &all_sorted_strips {
+            // 长度为0的灯带不产生数据
+            if strip.len == 0 {
+                continue;
+            }
+
             let is_current_display = current_display_strips.contains(&strip.index);
 
             if is_current_display {
@@ -1721,6 +3127,10 @@ impl LedColorsPublisher {
                             buffer.push(rgb[0]); // R
                             buffer.push(rgb[2]); // B
                         }
+                        LedType::WS2811 => {
+                            // RGB格式
+                            buffer.extend_from_slice(&rgb);
+                        }
                         LedType::SK6812 => {
                             // GRBW格式
                             buffer.push(rgb[1]); // G
@@ -1759,6 +3169,10 @@ impl LedColorsPublisher {
                             buffer.push(fill_rgb[0]); // R
                             buffer.push(fill_rgb[2]); // B
                         }
+                        LedType::WS2811 => {
+                            // RGB格式
+                            buffer.extend_from_slice(&fill_rgb);
+                        }
                         LedType::SK6812 => {
                             // GRBW格式
                             if active_strip.is_some() {
@@ -1800,6 +3214,76 @@ impl LedColorsPublisher {
     }
 }
 
+/// 解析每条灯带所在显示器的当前系统ID，返回灯带序号到显示器ID的映射
+///
+/// 灯带通过 `display_internal_id` 显式关联显示器，每个显示器的灯带数量不限；
+/// 显示器经注册表记录的 `last_system_id` 解析，未注册或当前未连接的显示器上的灯带不在结果中。
+fn resolve_strip_display_ids(
+    strips: &[LedStripConfigV2],
+    registered: &[DisplayConfig],
+    system_ids: &[u32],
+) -> HashMap<usize, u32> {
+    strips
+        .iter()
+        .filter_map(|strip| {
+            let system_id = registered
+                .iter()
+                .find(|display| display.internal_id == strip.display_internal_id)?
+                .last_system_id?;
+            system_ids
+                .contains(&system_id)
+                .then_some((strip.index, system_id))
+        })
+        .collect()
+}
+
+/// 为未指定显示器的灯带分配显示器ID
+///
+/// 按 `strip_display_ids`（灯带序号到显示器ID）分配，映射中没有的灯带保持未分配。
+fn assign_display_ids(configs: &mut LedStripConfigGroup, strip_display_ids: &HashMap<usize, u32>) {
+    for strip in configs.strips.iter_mut() {
+        if strip.display_id != 0 {
+            continue;
+        }
+
+        if let Some(display_id) = strip_display_ids.get(&strip.index) {
+            strip.display_id = *display_id;
+            log::info!(
+                "Assigned display ID {} to strip {}",
+                strip.display_id,
+                strip.index
+            );
+        } else {
+            log::warn!(
+                "No connected display found for strip {}, leaving it unassigned",
+                strip.index
+            );
+        }
+    }
+}
+
+/// 计算RGB字节序列的平均颜色，数据为空时返回 None
+fn average_rgb_bytes(colors: &[u8]) -> Option<LedColor> {
+    let count = colors.len() / 3;
+    if count == 0 {
+        return None;
+    }
+
+    let mut sum = [0u64; 3];
+    for rgb in colors.chunks_exact(3) {
+        sum[0] += rgb[0] as u64;
+        sum[1] += rgb[1] as u64;
+        sum[2] += rgb[2] as u64;
+    }
+
+    let count = count as u64;
+    Some(LedColor::new(
+        (sum[0] / count) as u8,
+        (sum[1] / count) as u8,
+        (sum[2] / count) as u8,
+    ))
+}
+
 #[derive(Debug, Clone)]
 pub struct AllColorConfig {
     pub sample_point_groups: Vec<DisplaySamplePointGroup>,
@@ -1817,7 +3301,9 @@ pub struct DisplaySamplePointGroup {
 
 #[cfg(test)]
 mod tests {
-    use crate::ambient_light::config::{Border, ColorCalibration, LedStripConfig, LedType};
+    use crate::ambient_light::config::{
+        Border, ColorCalibration, ColorOrder, LedStripConfig, LedType, SampleShape, ZoneReduction,
+    };
     use crate::led_color::LedColor;
     use std::sync::Arc;
     use tokio::sync::Mutex;
@@ -1870,7 +3356,7 @@ mod tests {
 
             let led_type = strip.led_type;
             let bytes_per_led = match led_type {
-                LedType::WS2812B => 3,
+                LedType::WS2812B | LedType::WS2811 => 3,
                 LedType::SK6812 => 4,
             };
             let mut buffer = Vec::<u8>::with_capacity(strip_len * bytes_per_led);
@@ -1887,6 +3373,15 @@ mod tests {
                             ((col[2] as f32 * cal[2] as f32 / 255.0) as u8), // B
                         ]
                     }
+                    LedType::WS2811 => {
+                        let cal = color_calibration.to_bytes();
+                        let col = colors[color_index].as_bytes();
+                        vec![
+                            ((col[0] as f32 * cal[0] as f32 / 255.0) as u8), // R
+                            ((col[1] as f32 * cal[1] as f32 / 255.0) as u8), // G
+                            ((col[2] as f32 * cal[2] as f32 / 255.0) as u8), // B
+                        ]
+                    }
                     LedType::SK6812 => {
                         let cal = color_calibration.to_bytes_rgbw();
                         let col = colors[color_index].as_bytes();
@@ -1924,6 +3419,17 @@ mod tests {
                 len: 2,
                 led_type: LedType::WS2812B,
                 reversed: false,
+                hardware_offset: None,
+                zone_reduction: ZoneReduction::Mean,
+                color_order: ColorOrder::default(),
+                sample_shape: SampleShape::Grid,
+                sample_inset: 0,
+                start_trim_percent: 0.0,
+                end_trim_percent: 0.0,
+                enabled: true,
+                chain_position: None,
+                source_index: None,
+                source_display_id: None,
             },
             LedStripConfig {
                 index: 1,
@@ -1932,6 +3438,17 @@ mod tests {
                 len: 3,
                 led_type: LedType::WS2812B,
                 reversed: true,
+                hardware_offset: None,
+                zone_reduction: ZoneReduction::Mean,
+                color_order: ColorOrder::default(),
+                sample_shape: SampleShape::Grid,
+                sample_inset: 0,
+                start_trim_percent: 0.0,
+                end_trim_percent: 0.0,
+                enabled: true,
+                chain_position: None,
+                source_index: None,
+                source_display_id: None,
             },
         ];
 
@@ -1964,6 +3481,17 @@ mod tests {
             len: 1,
             led_type: LedType::WS2812B,
             reversed: false,
+            hardware_offset: None,
+            zone_reduction: ZoneReduction::Mean,
+            color_order: ColorOrder::default(),
+            sample_shape: SampleShape::Grid,
+            sample_inset: 0,
+            start_trim_percent: 0.0,
+            end_trim_percent: 0.0,
+            enabled: true,
+            chain_position: None,
+            source_index: None,
+            source_display_id: None,
         }];
         let mut calibration = ColorCalibration::new();
         calibration.r = 0.5; // Halve the red channel
@@ -1991,6 +3519,17 @@ mod tests {
             len: 1,
             led_type: LedType::SK6812,
             reversed: false,
+            hardware_offset: None,
+            zone_reduction: ZoneReduction::Mean,
+            color_order: ColorOrder::default(),
+            sample_shape: SampleShape::Grid,
+            sample_inset: 0,
+            start_trim_percent: 0.0,
+            end_trim_percent: 0.0,
+            enabled: true,
+            chain_position: None,
+            source_index: None,
+            source_display_id: None,
         }];
         let mut calibration = ColorCalibration::new();
         calibration.w = 0.8; // Set white channel to 80%
@@ -2040,6 +3579,726 @@ mod tests {
         assert_eq!(sent_data[1].0, 33);
     }
 
+    #[test]
+    fn test_average_rgb_bytes() {
+        assert!(super::average_rgb_bytes(&[]).is_none());
+
+        let average = super::average_rgb_bytes(&[255, 0, 0, 0, 0, 255, 0, 255, 0]).unwrap();
+        assert_eq!(average.get_rgb(), [85, 85, 85]);
+    }
+
+    fn strip_group(strip_count: usize) -> super::LedStripConfigGroup {
+        super::LedStripConfigGroup {
+            strips: (0..strip_count)
+                .map(|index| LedStripConfig {
+                    index,
+                    ..Default::default()
+                })
+                .collect(),
+            mappers: vec![],
+            color_calibration: ColorCalibration::new(),
+        }
+    }
+
+    fn registered_display(system_id: u32) -> crate::display::DisplayConfig {
+        let mut display = crate::display::DisplayConfig::new(
+            format!("显示器 {system_id}"),
+            1920,
+            1080,
+            1.0,
+            false,
+        );
+        display.last_system_id = Some(system_id);
+        display
+    }
+
+    fn assigned_ids(group: &super::LedStripConfigGroup) -> Vec<u32> {
+        group.strips.iter().map(|s| s.display_id).collect()
+    }
+
+    fn v2_strips(
+        displays: &[(&crate::display::DisplayConfig, usize)],
+    ) -> Vec<super::LedStripConfigV2> {
+        displays
+            .iter()
+            .flat_map(|(display, count)| std::iter::repeat_n(display.internal_id.clone(), *count))
+            .enumerate()
+            .map(|(index, internal_id)| {
+                super::LedStripConfigV2::default_for_display(internal_id, index)
+            })
+            .collect()
+    }
+
+    #[test]
+    fn test_assign_display_ids_groups_uneven_strip_counts() {
+        // 显示器2只有3条灯带，显示器1有5条，不再按每4条一个显示器分组
+        let first = registered_display(2);
+        let second = registered_display(1);
+        let strips = v2_strips(&[(&first, 3), (&second, 5)]);
+        let strip_display_ids =
+            super::resolve_strip_display_ids(&strips, &[first, second], &[1, 2]);
+
+        let mut group = strip_group(8);
+        super::assign_display_ids(&mut group, &strip_display_ids);
+        assert_eq!(assigned_ids(&group), vec![2, 2, 2, 1, 1, 1, 1, 1]);
+    }
+
+    #[test]
+    fn test_assign_display_ids_keeps_explicit_and_skips_disconnected() {
+        let connected = registered_display(2);
+        let disconnected = registered_display(5);
+        let strips = v2_strips(&[(&connected, 2), (&disconnected, 2)]);
+        let strip_display_ids =
+            super::resolve_strip_display_ids(&strips, &[connected, disconnected], &[2]);
+
+        let mut group = strip_group(4);
+        group.strips[1].display_id = 9;
+        super::assign_display_ids(&mut group, &strip_display_ids);
+        // 已指定的显示器ID保持不变，未连接显示器上的灯带保持未分配
+        assert_eq!(assigned_ids(&group), vec![2, 9, 0, 0]);
+    }
+
+    #[test]
+    fn test_chain_position_overrides_index_for_concatenation() {
+        // 界面顺序：0 在前；物理串联顺序：1 在前
+        let strips = vec![
+            LedStripConfig {
+                index: 0,
+                len: 2,
+                chain_position: Some(1),
+                ..Default::default()
+            },
+            LedStripConfig {
+                index: 1,
+                len: 3,
+                chain_position: Some(0),
+                ..Default::default()
+            },
+        ];
+
+        let offsets = super::LedColorsPublisher::calculate_strip_byte_offsets(&strips);
+        assert_eq!(offsets[&1], 0);
+        assert_eq!(offsets[&0], 9);
+
+        // 一维颜色按串联顺序排列：前3个属于灯带1，后2个属于灯带0
+        let colors: Vec<LedColor> = (0..5).map(|i| LedColor::new(i, 0, 0)).collect();
+        let colors_2d = super::LedColorsPublisher::convert_1d_to_2d_colors(
+            &colors,
+            &strips,
+            super::ColorCountPolicy::default(),
+        )
+        .unwrap();
+        let reds = |strip: &[LedColor]| strip.iter().map(|c| c.get_rgb()[0]).collect::<Vec<_>>();
+        assert_eq!(reds(&colors_2d[0]), vec![3, 4]);
+        assert_eq!(reds(&colors_2d[1]), vec![0, 1, 2]);
+
+        // 未设置串联位置时与序列号相同
+        let default_chain = LedStripConfig {
+            index: 2,
+            ..Default::default()
+        };
+        assert_eq!(default_chain.chain_order(), (2, 2));
+        assert_eq!(default_chain.calculate_start_pos(&strips), 5);
+        assert_eq!(strips[0].calculate_start_pos(&strips), 3);
+        assert_eq!(strips[1].calculate_start_pos(&strips), 0);
+    }
+
+    #[test]
+    fn test_strip_byte_offsets_mix_explicit_and_derived() {
+        let strips = vec![
+            LedStripConfig {
+                index: 0,
+                len: 10,
+                ..Default::default()
+            },
+            // 显式偏移：从第 20 个 SK6812 LED 开始
+            LedStripConfig {
+                index: 1,
+                len: 5,
+                led_type: LedType::SK6812,
+                hardware_offset: Some(20),
+                ..Default::default()
+            },
+            // 紧接显式偏移的灯带之后
+            LedStripConfig {
+                index: 2,
+                len: 4,
+                ..Default::default()
+            },
+            LedStripConfig {
+                index: 3,
+                len: 2,
+                hardware_offset: Some(0),
+                ..Default::default()
+            },
+        ];
+
+        let offsets = super::LedColorsPublisher::calculate_strip_byte_offsets(&strips);
+        assert_eq!(offsets[&0], 0);
+        assert_eq!(offsets[&1], 80);
+        assert_eq!(offsets[&2], 100);
+        assert_eq!(offsets[&3], 0);
+
+        // 诊断布局与发送时的偏移量一致
+        let layout = super::LedColorsPublisher::strip_layout(&strips);
+        for entry in &layout {
+            assert_eq!(entry.byte_offset, offsets[&entry.index]);
+        }
+        let table = super::format_strip_layout(&layout);
+        assert_eq!(table.lines().count(), strips.len() + 1);
+        assert!(table.contains("(explicit)"));
+
+        // 连续的灯带合并为一段，显式偏移的灯带单独成段
+        let hardware_data: Vec<u8> = (0..(10 * 3 + 5 * 4 + 4 * 3) as u8).collect();
+        let segments = super::LedColorsPublisher::split_hardware_data_by_strip(
+            &hardware_data,
+            &strips[..3],
+            &strips,
+        )
+        .unwrap();
+        assert_eq!(segments.len(), 2);
+        assert_eq!(segments[0], (0, hardware_data[..30].to_vec()));
+        assert_eq!(segments[1], (80, hardware_data[30..].to_vec()));
+
+        // 数据不足时返回错误
+        assert!(super::LedColorsPublisher::split_hardware_data_by_strip(
+            &hardware_data[..10],
+            &strips[..1],
+            &strips,
+        )
+        .is_err());
+    }
+
+    #[test]
+    fn test_strip_layout_merges_spanning_segments() {
+        // 原灯带 0 跨两台显示器展开为分段 2、3，其后是灯带 1
+        let segment = |index: usize, display_id: u32, len: usize| LedStripConfig {
+            index,
+            display_id,
+            len,
+            chain_position: Some(0),
+            source_index: Some(0),
+            source_display_id: Some(1),
+            ..Default::default()
+        };
+        let strips = vec![
+            segment(2, 1, 30),
+            segment(3, 2, 20),
+            LedStripConfig {
+                index: 1,
+                display_id: 2,
+                len: 10,
+                chain_position: Some(1),
+                ..Default::default()
+            },
+        ];
+
+        let layout = super::LedColorsPublisher::strip_layout(&strips);
+        let entries: Vec<_> = layout
+            .iter()
+            .map(|e| {
+                (
+                    e.index,
+                    e.chain_position,
+                    e.display_id,
+                    e.len,
+                    e.byte_offset,
+                )
+            })
+            .collect();
+        assert_eq!(entries, vec![(0, 0, 1, 50, 0), (1, 1, 2, 10, 150)]);
+        assert_eq!(layout[0].byte_len, 150);
+    }
+
+    #[test]
+    fn test_convert_1d_to_2d_colors_skips_zero_length_strips() {
+        let strip = |index: usize, len: usize| LedStripConfig {
+            index,
+            len,
+            ..Default::default()
+        };
+        let colors: Vec<LedColor> = (0..5).map(|i| LedColor::new(i, 0, 0)).collect();
+        let strips = [
+            strip(0, 0),
+            strip(1, 2),
+            strip(2, 0),
+            strip(3, 3),
+            strip(4, 0),
+        ];
+
+        let colors_2d = super::LedColorsPublisher::convert_1d_to_2d_colors(
+            &colors,
+            &strips,
+            super::ColorCountPolicy::Truncate,
+        )
+        .unwrap()
+        .into_iter()
+        .map(|strip| strip.iter().map(|c| c.get_rgb()[0]).collect::<Vec<_>>())
+        .collect::<Vec<_>>();
+
+        assert_eq!(
+            colors_2d,
+            vec![vec![], vec![0, 1], vec![], vec![2, 3, 4], vec![]]
+        );
+    }
+
+    #[test]
+    fn test_convert_1d_to_2d_colors_count_mismatch_policies() {
+        use super::ColorCountPolicy;
+
+        let strip = |index: usize, len: usize| LedStripConfig {
+            index,
+            len,
+            ..Default::default()
+        };
+        let colors = |values: &[u8]| -> Vec<LedColor> {
+            values.iter().map(|&v| LedColor::new(v, 0, 0)).collect()
+        };
+        let convert = |colors: &[LedColor], strips: &[LedStripConfig], policy| {
+            super::LedColorsPublisher::convert_1d_to_2d_colors(colors, strips, policy)
+                .unwrap()
+                .into_iter()
+                .map(|strip| strip.iter().map(|c| c.get_rgb()[0]).collect::<Vec<_>>())
+                .collect::<Vec<_>>()
+        };
+
+        // 6 个LED只有 3 个颜色
+        let strips = [strip(0, 2), strip(1, 4)];
+        let short = colors(&[10, 20, 30]);
+        assert_eq!(
+            convert(&short, &strips, ColorCountPolicy::Truncate),
+            vec![vec![10, 20], vec![30, 0, 0, 0]]
+        );
+        assert_eq!(
+            convert(&short, &strips, ColorCountPolicy::Wrap),
+            vec![vec![10, 20], vec![30, 10, 20, 30]]
+        );
+        assert_eq!(
+            convert(&short, &strips, ColorCountPolicy::Resample),
+            vec![vec![10, 13], vec![18, 23, 28, 30]]
+        );
+
+        // 3 个LED有 6 个颜色
+        let strips = [strip(0, 1), strip(1, 2)];
+        let long = colors(&[0, 10, 20, 30, 40, 50]);
+        assert_eq!(
+            convert(&long, &strips, ColorCountPolicy::Truncate),
+            vec![vec![0], vec![10, 20]]
+        );
+        assert_eq!(
+            convert(&long, &strips, ColorCountPolicy::Wrap),
+            vec![vec![0], vec![10, 20]]
+        );
+        assert_eq!(
+            convert(&long, &strips, ColorCountPolicy::Resample),
+            vec![vec![5], vec![25, 45]]
+        );
+    }
+
+    #[test]
+    fn test_breathing_frequency_controls_cycle_speed() {
+        use super::BreathingConfig;
+
+        // 统计4秒内亮度向上穿过中值的次数，即完整呼吸周期数
+        let cycles = |breathing: BreathingConfig| {
+            let midpoint = (breathing.min_brightness + breathing.max_brightness) / 2.0;
+            (1..400)
+                .filter(|i| {
+                    let previous = breathing.brightness_at((i - 1) as f64 * 0.01);
+                    let current = breathing.brightness_at(*i as f64 * 0.01);
+                    previous < midpoint && current >= midpoint
+                })
+                .count()
+        };
+
+        let fast = BreathingConfig {
+            frequency_hz: 1.0,
+            ..BreathingConfig::default()
+        };
+        let slow = BreathingConfig {
+            frequency_hz: 0.5,
+            ..BreathingConfig::default()
+        };
+        assert!(cycles(slow) < cycles(fast));
+
+        // 亮度保持在配置范围内
+        let gentle = BreathingConfig {
+            frequency_hz: 0.5,
+            min_brightness: 0.6,
+            max_brightness: 0.8,
+        };
+        assert!(gentle.validate().is_ok());
+        assert!((0..400)
+            .map(|i| gentle.brightness_at(i as f64 * 0.01))
+            .all(|brightness| (0.6..=0.8).contains(&brightness)));
+
+        assert!(BreathingConfig {
+            frequency_hz: 0.0,
+            ..BreathingConfig::default()
+        }
+        .validate()
+        .is_err());
+        assert!(BreathingConfig {
+            min_brightness: 0.9,
+            max_brightness: 0.5,
+            ..BreathingConfig::default()
+        }
+        .validate()
+        .is_err());
+    }
+
+    #[tokio::test]
+    async fn test_breathing_buffer_skips_zero_length_strips() {
+        let strip = |index: usize, border: Border, len: usize, display_id: u32| LedStripConfig {
+            index,
+            border,
+            len,
+            display_id,
+            ..Default::default()
+        };
+        let group = |strips: Vec<LedStripConfig>| super::LedStripConfigGroup {
+            strips,
+            mappers: Vec::new(),
+            color_calibration: ColorCalibration::new(),
+        };
+        let edge_colors = std::collections::HashMap::from([
+            (
+                Border::Top,
+                [LedColor::new(255, 0, 0), LedColor::new(0, 255, 0)],
+            ),
+            (
+                Border::Bottom,
+                [LedColor::new(0, 0, 255), LedColor::new(255, 255, 255)],
+            ),
+        ]);
+        let publisher = super::LedColorsPublisher::global().await;
+        let build = |current: &super::LedStripConfigGroup, all: &super::LedStripConfigGroup| {
+            publisher
+                .map_edge_colors_to_led_buffer_with_breathing(
+                    current,
+                    all,
+                    &edge_colors,
+                    None,
+                    super::BreathingConfig::default(),
+                )
+                .unwrap()
+                .0
+        };
+
+        let current = group(vec![
+            strip(0, Border::Top, 3, 1),
+            strip(2, Border::Bottom, 2, 1),
+        ]);
+        let all = group(vec![
+            strip(0, Border::Top, 3, 1),
+            strip(1, Border::Left, 4, 2),
+            strip(2, Border::Bottom, 2, 1),
+        ]);
+        let expected = build(&current, &all);
+
+        let current_with_empty = group(vec![
+            strip(0, Border::Top, 3, 1),
+            strip(3, Border::Right, 0, 1),
+            strip(2, Border::Bottom, 2, 1),
+        ]);
+        let all_with_empty = group(vec![
+            strip(0, Border::Top, 3, 1),
+            strip(1, Border::Left, 4, 2),
+            strip(3, Border::Right, 0, 1),
+            strip(2, Border::Bottom, 2, 1),
+            strip(4, Border::Top, 0, 2),
+        ]);
+
+        assert_eq!(expected.len(), (3 + 4 + 2) * 3);
+        assert_eq!(build(&current_with_empty, &all_with_empty), expected);
+    }
+
+    #[tokio::test]
+    async fn test_stalled_screenshot_channel_engages_fallback() {
+        use super::{
+            capture_failure_colors, wait_for_screenshot, CaptureFailurePolicy, ScreenshotWait,
+        };
+        use crate::user_preferences::CaptureFailurePreferences;
+        use std::time::Duration;
+
+        let timeout = Duration::from_millis(20);
+        let (screenshot_tx, mut screenshot_rx) = tokio::sync::watch::channel(0u32);
+
+        screenshot_tx.send(1).unwrap();
+        assert_eq!(
+            wait_for_screenshot(&mut screenshot_rx, timeout).await,
+            ScreenshotWait::Frame
+        );
+        // 截图通道没有新画面时超时，判定为采集停滞
+        assert_eq!(
+            wait_for_screenshot(&mut screenshot_rx, timeout).await,
+            ScreenshotWait::Stalled
+        );
+
+        let last_colors = vec![LedColor::new(200, 100, 0); 2];
+        let fallback = |policy, stalled_for| {
+            let preferences = CaptureFailurePreferences {
+                policy,
+                fade_ms: 1000,
+                diagnostic_color: [0, 0, 255],
+                ..Default::default()
+            };
+            capture_failure_colors(&preferences, &last_colors, 3, stalled_for)
+                .map(|colors| colors.iter().map(LedColor::get_rgb).collect::<Vec<_>>())
+        };
+
+        assert_eq!(
+            fallback(CaptureFailurePolicy::Hold, Duration::from_secs(10)),
+            None
+        );
+        assert_eq!(
+            fallback(
+                CaptureFailurePolicy::FadeToBlack,
+                Duration::from_millis(500)
+            ),
+            Some(vec![[100, 50, 0], [100, 50, 0], [0, 0, 0]])
+        );
+        assert_eq!(
+            fallback(CaptureFailurePolicy::FadeToBlack, Duration::MAX),
+            Some(vec![[0, 0, 0]; 3])
+        );
+        assert_eq!(
+            fallback(CaptureFailurePolicy::DiagnosticColor, Duration::ZERO),
+            Some(vec![[0, 0, 255]; 3])
+        );
+
+        drop(screenshot_tx);
+        assert_eq!(
+            wait_for_screenshot(&mut screenshot_rx, timeout).await,
+            ScreenshotWait::Closed
+        );
+    }
+
+    #[test]
+    fn test_unavailable_display_triggers_sleep_policy() {
+        use super::{display_sleep_colors, DisplaySleepDetector, DisplaySleepPolicy};
+        use std::time::Duration;
+
+        let start = tokio::time::Instant::now();
+        let lit = vec![LedColor::new(200, 100, 0); 2];
+        let mut detector = DisplaySleepDetector::default();
+
+        // 显示器在线且画面有颜色，不判定为休眠
+        assert_eq!(detector.update(true, &lit, start, true), None);
+
+        // 系统不再报告该显示器，立即判定为休眠
+        let black = vec![LedColor::new(0, 0, 0); 2];
+        assert_eq!(
+            detector.update(false, &black, start, true),
+            Some(Duration::ZERO)
+        );
+        let asleep_for = detector.update(false, &black, start + Duration::from_secs(1), true);
+        assert_eq!(asleep_for, Some(Duration::from_secs(1)));
+
+        let output = |policy| {
+            display_sleep_colors(policy, &detector.awake_colors, black.clone(), asleep_for)
+                .map(|colors| colors.iter().map(LedColor::get_rgb).collect::<Vec<_>>())
+        };
+        assert_eq!(output(DisplaySleepPolicy::Hold), None);
+        assert_eq!(
+            output(DisplaySleepPolicy::FadeToBlack),
+            Some(vec![[100, 50, 0]; 2])
+        );
+        assert_eq!(output(DisplaySleepPolicy::Ignore), Some(vec![[0, 0, 0]; 2]));
+
+        // 显示器恢复后照常输出
+        assert_eq!(
+            detector.update(true, &lit, start + Duration::from_secs(2), true),
+            None
+        );
+
+        // 在线但持续全黑超过阈值：默认不启用全黑检测，暗场景照常输出
+        let black_start = start + Duration::from_secs(3);
+        let black_end = black_start + super::BLACK_FRAME_SLEEP_THRESHOLD;
+        assert_eq!(detector.update(true, &black, black_start, false), None);
+        assert_eq!(detector.update(true, &black, black_end, false), None);
+
+        // 启用全黑检测后同样判定为休眠
+        assert!(detector.update(true, &black, black_end, true).is_some());
+    }
+
+    #[test]
+    fn test_adaptive_quality_degrades_over_budget_and_restores() {
+        use super::{AdaptiveQualityController, MAX_ADAPTIVE_QUALITY_LEVEL};
+        use std::time::Duration;
+
+        let budget = Duration::from_millis(33);
+        let mut controller = AdaptiveQualityController::default();
+        assert_eq!(controller.sampling(16, true), (16, true));
+
+        // 持续超出预算时逐级降低：先关闭双线性采样，再减半采样网格边长
+        let mut levels = Vec::new();
+        for _ in 0..100 {
+            controller.record(Duration::from_millis(50), budget);
+            if levels.last() != Some(&controller.level) {
+                levels.push(controller.level);
+            }
+        }
+        assert_eq!(levels, vec![0, 1, 2, 3]);
+        assert_eq!(controller.level, MAX_ADAPTIVE_QUALITY_LEVEL);
+        assert_eq!(controller.sampling(16, true), (1, false));
+
+        // 短暂回落到预算内但没有足够余量时保持当前等级
+        for _ in 0..100 {
+            controller.record(Duration::from_millis(25), budget);
+        }
+        assert_eq!(controller.level, MAX_ADAPTIVE_QUALITY_LEVEL);
+
+        // 余量充足时逐级恢复到完整质量
+        for _ in 0..1000 {
+            controller.record(Duration::from_millis(5), budget);
+        }
+        assert_eq!(controller.level, 0);
+
+        let state = controller.state(16, true, budget);
+        assert_eq!(
+            (state.sample_points_per_led, state.bilinear_sampling),
+            (16, true)
+        );
+        assert!(state.frame_time_ms < state.budget_ms);
+    }
+
+    #[tokio::test]
+    async fn test_wait_for_calibration_frame_skips_stale_frames() {
+        let (tx, _) = watch::channel(None);
+        let old = ColorCalibration::new();
+        let mut new = ColorCalibration::new();
+        new.r = 0.5;
+
+        // 订阅前已编码的帧不算，旧校准的帧继续等待
+        tx.send_replace(Some(new));
+        let mut rx = tx.subscribe();
+        let waiter = tokio::spawn(async move {
+            wait_for_calibration_frame(&mut rx, new, Duration::from_secs(1)).await
+        });
+        tokio::time::sleep(Duration::from_millis(10)).await;
+        tx.send_replace(Some(old));
+        tokio::time::sleep(Duration::from_millis(10)).await;
+        assert!(!waiter.is_finished());
+        tx.send_replace(Some(new));
+        assert!(waiter.await.unwrap());
+
+        // 没有新帧时超时
+        let mut rx = tx.subscribe();
+        assert!(!wait_for_calibration_frame(&mut rx, new, Duration::from_millis(20)).await);
+    }
+
+    #[tokio::test]
+    async fn test_frozen_frame_ignores_new_screenshots() {
+        use super::{frame_colors, wait_for_screenshot, ScreenshotWait};
+        use std::sync::atomic::{AtomicUsize, Ordering};
+        use std::time::Duration;
+
+        let timeout = Duration::from_millis(20);
+        let (screenshot_tx, mut screenshot_rx) = tokio::sync::watch::channel([255, 0, 0]);
+        let samples = AtomicUsize::new(0);
+        let sample = |frame: [u8; 3]| {
+            let samples = &samples;
+            async move {
+                samples.fetch_add(1, Ordering::SeqCst);
+                let [r, g, b] = frame;
+                Some(vec![LedColor::new(r, g, b)])
+            }
+        };
+
+        // 未冻结时对画面采样
+        let frame = *screenshot_rx.borrow();
+        let mut last_colors = frame_colors(false, &[], sample(frame)).await.unwrap();
+        assert_eq!(samples.load(Ordering::SeqCst), 1);
+
+        // 冻结后新画面照常到达，但不再采样，输出始终是冻结前的一帧
+        for frame in [[0, 0, 255], [0, 255, 0]] {
+            screenshot_tx.send(frame).unwrap();
+            assert_eq!(
+                wait_for_screenshot(&mut screenshot_rx, timeout).await,
+                ScreenshotWait::Frame
+            );
+            let frame = *screenshot_rx.borrow();
+            last_colors = frame_colors(true, &last_colors, sample(frame))
+                .await
+                .unwrap();
+            assert_eq!(
+                last_colors
+                    .iter()
+                    .map(LedColor::get_rgb)
+                    .collect::<Vec<_>>(),
+                vec![[255, 0, 0]]
+            );
+        }
+        assert_eq!(samples.load(Ordering::SeqCst), 1);
+
+        // 解除冻结后立即使用最新画面
+        let frame = *screenshot_rx.borrow();
+        let colors = frame_colors(false, &last_colors, sample(frame))
+            .await
+            .unwrap();
+        assert_eq!(
+            colors.iter().map(LedColor::get_rgb).collect::<Vec<_>>(),
+            vec![[0, 255, 0]]
+        );
+        assert_eq!(samples.load(Ordering::SeqCst), 2);
+
+        // 尚无可重发的帧时即使冻结也需要采样
+        assert!(frame_colors(true, &[], sample([1, 2, 3])).await.is_some());
+        assert_eq!(samples.load(Ordering::SeqCst), 3);
+    }
+
+    #[test]
+    fn test_capture_failure_validation() {
+        use crate::user_preferences::CaptureFailurePreferences;
+
+        assert!(super::validate_capture_failure(&CaptureFailurePreferences::default()).is_ok());
+        assert!(super::validate_capture_failure(&CaptureFailurePreferences {
+            stall_timeout_ms: 10,
+            ..Default::default()
+        })
+        .is_err());
+    }
+
+    #[test]
+    fn test_frame_assembler_with_displays_updating_at_different_rates() {
+        use super::{FrameAssembler, FrameSyncMode};
+
+        // 显示器 A 有 1 颗灯珠且频繁更新，显示器 B 有 2 颗灯珠且更新较慢
+        let mut synchronized = FrameAssembler::new(vec![3, 6]);
+        assert_eq!(
+            synchronized.update(0, vec![10, 10, 10], FrameSyncMode::Synchronized),
+            None
+        );
+        assert_eq!(
+            synchronized.update(0, vec![20, 20, 20], FrameSyncMode::Synchronized),
+            None
+        );
+        assert_eq!(
+            synchronized.update(1, vec![1, 2, 3, 4, 5, 6], FrameSyncMode::Synchronized),
+            Some(vec![20, 20, 20, 1, 2, 3, 4, 5, 6])
+        );
+
+        let mut immediate = FrameAssembler::new(vec![3, 6]);
+        assert_eq!(
+            immediate.update(0, vec![10, 10, 10], FrameSyncMode::Immediate),
+            Some(vec![10, 10, 10, 0, 0, 0, 0, 0, 0])
+        );
+        assert_eq!(
+            immediate.update(0, vec![20, 20, 20], FrameSyncMode::Immediate),
+            Some(vec![20, 20, 20, 0, 0, 0, 0, 0, 0])
+        );
+        assert_eq!(
+            immediate.update(1, vec![1, 2, 3, 4, 5, 6], FrameSyncMode::Immediate),
+            Some(vec![20, 20, 20, 1, 2, 3, 4, 5, 6])
+        );
+        // B 不再更新时，A 的新颜色仍立即发送并沿用 B 上次的颜色
+        assert_eq!(
+            immediate.update(0, vec![30, 30, 30], FrameSyncMode::Immediate),
+            Some(vec![30, 30, 30, 1, 2, 3, 4, 5, 6])
+        );
+    }
+
     // Helper function to provide a default LedStripConfig
     impl Default for LedStripConfig {
         fn default() -> Self {
@@ -2050,6 +4309,17 @@ mod tests {
                 len: 0,
                 led_type: LedType::WS2812B,
                 reversed: false,
+                hardware_offset: None,
+                zone_reduction: ZoneReduction::Mean,
+                color_order: ColorOrder::default(),
+                sample_shape: SampleShape::Grid,
+                sample_inset: 0,
+                start_trim_percent: 0.0,
+                end_trim_percent: 0.0,
+                enabled: true,
+                chain_position: None,
+                source_index: None,
+                source_display_id: None,
             }
         }
     }
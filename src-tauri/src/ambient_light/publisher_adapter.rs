@@ -90,25 +90,25 @@ impl PublisherAdapter {
         // 转换LED灯带配置
         let mut v1_strips = Vec::new();
         for v2_strip in &v2_config.strips {
-            // 若条目中的 internal_id 在配置里不存在，基于 strip.index 回退到某个有效显示器
+            // 位于显示器禁用边上的灯带不参与采样，也不占用串联数据中的位置
+            if !v2_config
+                .border_mask(&v2_strip.display_internal_id)
+                .contains(v2_strip.border)
+            {
+                continue;
+            }
+
+            // 若条目中的 internal_id 在配置里不存在，回退到第一个显示器
+            // （每个显示器的灯带数量不固定，无法从 strip.index 推断所属显示器）
             let mut target_internal_id = v2_strip.display_internal_id.clone();
             if v2_config
                 .display_config
                 .find_by_internal_id(&target_internal_id)
                 .is_none()
             {
-                let display_index = v2_strip.index / 4; // 每4个灯带对应一个显示器（Top/Right/Bottom/Left）
-                if let Some(disp) = v2_config.display_config.displays.get(display_index) {
-                    log::warn!(
-                        "⚠️ 条目 {} 内部ID '{}' 未在配置中找到，按索引回退为显示器 '{}'",
-                        v2_strip.index,
-                        target_internal_id,
-                        disp.internal_id
-                    );
-                    target_internal_id = disp.internal_id.clone();
-                } else if let Some(first) = v2_config.display_config.displays.first() {
+                if let Some(first) = v2_config.display_config.displays.first() {
                     log::warn!(
-                        "⚠️ 条目 {} 内部ID '{}' 未在配置中找到，且索引回退越界，使用第一个显示器 '{}'",
+                        "⚠️ 条目 {} 内部ID '{}' 未在配置中找到，使用第一个显示器 '{}'",
                         v2_strip.index,
                         target_internal_id,
                         first.internal_id
@@ -169,6 +169,17 @@ impl PublisherAdapter {
                 len: v2_strip.len,
                 led_type: v2_strip.led_type,
                 reversed: v2_strip.reversed,
+                hardware_offset: v2_strip.hardware_offset,
+                zone_reduction: v2_strip.zone_reduction,
+                color_order: v2_strip.color_order,
+                sample_shape: v2_strip.sample_shape,
+                sample_inset: v2_strip.sample_inset,
+                start_trim_percent: v2_strip.start_trim_percent,
+                end_trim_percent: v2_strip.end_trim_percent,
+                enabled: v2_strip.enabled,
+                chain_position: v2_strip.chain_position,
+                source_index: None,
+                source_display_id: None,
             };
 
             v1_strips.push(v1_strip);
@@ -258,6 +269,16 @@ impl PublisherAdapter {
                 len: v1_strip.len,
                 led_type: v1_strip.led_type,
                 reversed: v1_strip.reversed,
+                hardware_offset: v1_strip.hardware_offset,
+                led_positions: None,
+                zone_reduction: v1_strip.zone_reduction,
+                color_order: v1_strip.color_order,
+                sample_shape: v1_strip.sample_shape,
+                sample_inset: v1_strip.sample_inset,
+                start_trim_percent: v1_strip.start_trim_percent,
+                end_trim_percent: v1_strip.end_trim_percent,
+                enabled: v1_strip.enabled,
+                chain_position: v1_strip.chain_position,
             };
 
             v2_strips.push(v2_strip);
@@ -276,6 +297,15 @@ impl PublisherAdapter {
             color_calibration: v1_config.color_calibration,
             display_config,
             mappers: Vec::new(),
+            mirror_sources: std::collections::HashMap::new(),
+            display_crops: std::collections::HashMap::new(),
+            display_scale_factors: std::collections::HashMap::new(),
+            display_windings: std::collections::HashMap::new(),
+            display_capture_formats: std::collections::HashMap::new(),
+            display_frame_rates: std::collections::HashMap::new(),
+            display_border_masks: std::collections::HashMap::new(),
+            strip_spans: Vec::new(),
+            calibration_presets: std::collections::HashMap::new(),
             created_at: std::time::SystemTime::now(),
             updated_at: std::time::SystemTime::now(),
         };
@@ -351,6 +381,12 @@ impl PublisherAdapter {
             || (v2_cal.g - v1_cal.g).abs() > 0.001
             || (v2_cal.b - v1_cal.b).abs() > 0.001
             || (v2_cal.w - v1_cal.w).abs() > 0.001
+            || v2_cal.white_synthesis != v1_cal.white_synthesis
+            || v2_cal.white_point != v1_cal.white_point
+            || v2_cal.perceptual_normalization != v1_cal.perceptual_normalization
+            || v2_cal.deadband != v1_cal.deadband
+            || v2_cal.highlight_knee != v1_cal.highlight_knee
+            || (v2_cal.highlight_rolloff - v1_cal.highlight_rolloff).abs() > 0.001
         {
             log::error!("❌ 颜色校准不匹配");
             return Ok(false);
@@ -430,6 +466,16 @@ mod tests {
             len: 30,
             led_type: crate::ambient_light::LedType::WS2812B,
             reversed: false,
+            hardware_offset: None,
+            led_positions: None,
+            zone_reduction: crate::ambient_light::ZoneReduction::Mean,
+            color_order: crate::ambient_light::ColorOrder::default(),
+            sample_shape: crate::ambient_light::SampleShape::Grid,
+            sample_inset: 0,
+            start_trim_percent: 0.0,
+            end_trim_percent: 0.0,
+            enabled: true,
+            chain_position: None,
         };
         v2_config.strips.push(strip);
 
@@ -49,6 +49,17 @@ mod tests {
                 len: 4, // 使用小数量便于验证
                 led_type: LedType::WS2812B,
                 reversed: false,
+                hardware_offset: None,
+                zone_reduction: ZoneReduction::Mean,
+                color_order: ColorOrder::default(),
+                sample_shape: SampleShape::Grid,
+                sample_inset: 0,
+                start_trim_percent: 0.0,
+                end_trim_percent: 0.0,
+                enabled: true,
+                chain_position: None,
+                source_index: None,
+                source_display_id: None,
             },
             LedStripConfig {
                 index: 1,
@@ -57,6 +68,17 @@ mod tests {
                 len: 3,
                 led_type: LedType::SK6812,
                 reversed: false,
+                hardware_offset: None,
+                zone_reduction: ZoneReduction::Mean,
+                color_order: ColorOrder::default(),
+                sample_shape: SampleShape::Grid,
+                sample_inset: 0,
+                start_trim_percent: 0.0,
+                end_trim_percent: 0.0,
+                enabled: true,
+                chain_position: None,
+                source_index: None,
+                source_display_id: None,
             },
             LedStripConfig {
                 index: 2,
@@ -65,6 +87,17 @@ mod tests {
                 len: 2,
                 led_type: LedType::WS2812B,
                 reversed: false,
+                hardware_offset: None,
+                zone_reduction: ZoneReduction::Mean,
+                color_order: ColorOrder::default(),
+                sample_shape: SampleShape::Grid,
+                sample_inset: 0,
+                start_trim_percent: 0.0,
+                end_trim_percent: 0.0,
+                enabled: true,
+                chain_position: None,
+                source_index: None,
+                source_display_id: None,
             },
         ];
 
@@ -384,6 +417,17 @@ mod tests {
                 len: 3, // 使用小数量便于验证
                 led_type: LedType::SK6812,
                 reversed: false,
+                hardware_offset: None,
+                zone_reduction: ZoneReduction::Mean,
+                color_order: ColorOrder::default(),
+                sample_shape: SampleShape::Grid,
+                sample_inset: 0,
+                start_trim_percent: 0.0,
+                end_trim_percent: 0.0,
+                enabled: true,
+                chain_position: None,
+                source_index: None,
+                source_display_id: None,
             },
             LedStripConfig {
                 index: 1,
@@ -392,6 +436,17 @@ mod tests {
                 len: 2,
                 led_type: LedType::WS2812B,
                 reversed: false,
+                hardware_offset: None,
+                zone_reduction: ZoneReduction::Mean,
+                color_order: ColorOrder::default(),
+                sample_shape: SampleShape::Grid,
+                sample_inset: 0,
+                start_trim_percent: 0.0,
+                end_trim_percent: 0.0,
+                enabled: true,
+                chain_position: None,
+                source_index: None,
+                source_display_id: None,
             },
             LedStripConfig {
                 index: 2,
@@ -400,6 +455,17 @@ mod tests {
                 len: 3,
                 led_type: LedType::WS2812B,
                 reversed: false,
+                hardware_offset: None,
+                zone_reduction: ZoneReduction::Mean,
+                color_order: ColorOrder::default(),
+                sample_shape: SampleShape::Grid,
+                sample_inset: 0,
+                start_trim_percent: 0.0,
+                end_trim_percent: 0.0,
+                enabled: true,
+                chain_position: None,
+                source_index: None,
+                source_display_id: None,
             },
             // 显示器1的灯带 (序列号3，继续串联)
             LedStripConfig {
@@ -409,6 +475,17 @@ mod tests {
                 len: 4,
                 led_type: LedType::SK6812,
                 reversed: false,
+                hardware_offset: None,
+                zone_reduction: ZoneReduction::Mean,
+                color_order: ColorOrder::default(),
+                sample_shape: SampleShape::Grid,
+                sample_inset: 0,
+                start_trim_percent: 0.0,
+                end_trim_percent: 0.0,
+                enabled: true,
+                chain_position: None,
+                source_index: None,
+                source_display_id: None,
             },
         ];
 
@@ -617,7 +694,7 @@ mod tests {
 
         for strip in &sorted_strips {
             let bytes_per_led = match strip.led_type {
-                LedType::WS2812B => 3,
+                LedType::WS2812B | LedType::WS2811 => 3,
                 LedType::SK6812 => 4,
             };
             let strip_bytes = strip.len * bytes_per_led;
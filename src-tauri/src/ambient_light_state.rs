@@ -102,23 +102,30 @@ impl AmbientLightStateManager {
 
     /// Set ambient light enabled state
     pub async fn set_enabled(&self, enabled: bool) -> anyhow::Result<()> {
-        {
+        let old_state = {
             let mut state = self.state.write().await;
+            let old_state = state.clone();
             state.enabled = enabled;
-        }
+            old_state
+        };
 
         // Save to file
         let current_state = self.get_state().await;
         current_state.write_config().await?;
 
-        // Set data send mode
-        let led_data_sender = LedDataSender::global().await;
-        let new_mode = if enabled {
-            DataSendMode::AmbientLight
+        // Set data send mode, unless a manual color is overriding the strips
+        let publisher = crate::ambient_light::LedColorsPublisher::global().await;
+        if publisher.get_manual_color().await.is_some() {
+            info!("Manual color is active, keeping ManualColor data send mode");
         } else {
-            DataSendMode::None
-        };
-        led_data_sender.set_mode(new_mode).await;
+            let led_data_sender = LedDataSender::global().await;
+            let new_mode = if enabled {
+                DataSendMode::AmbientLight
+            } else {
+                DataSendMode::None
+            };
+            led_data_sender.set_mode(new_mode).await;
+        }
 
         info!(
             "Ambient light state changed to: {}",
@@ -129,6 +136,15 @@ impl AmbientLightStateManager {
         let current_state = self.get_state().await;
         crate::websocket_events::publish_ambient_light_state_changed(&current_state).await;
 
+        if old_state.enabled != current_state.enabled {
+            crate::webhook::notify(
+                crate::webhook::WebhookEvent::AmbientLightToggled,
+                &old_state,
+                &current_state,
+            )
+            .await;
+        }
+
         Ok(())
     }
 
@@ -0,0 +1,219 @@
+use std::net::{IpAddr, SocketAddr};
+use std::sync::atomic::{AtomicU8, Ordering};
+
+use tokio::net::UdpSocket;
+use tokio::sync::Mutex;
+
+/// Art-Net默认UDP端口
+pub const ARTNET_DEFAULT_PORT: u16 = 6454;
+/// ArtDmx数据包头长度
+pub const ARTNET_HEADER_LEN: usize = 18;
+/// 单个universe的最大通道数
+pub const ARTNET_MAX_CHANNELS: usize = 512;
+/// 15位端口地址的最大值
+pub const ARTNET_MAX_UNIVERSE: u16 = 0x7FFF;
+
+const ARTNET_ID: &[u8; 8] = b"Art-Net\0";
+/// OpDmx操作码（小端）
+const ARTNET_OP_DMX: u16 = 0x5000;
+/// 协议版本14（大端）
+const ARTNET_PROTOCOL_VERSION: u16 = 14;
+
+/// 解析Art-Net目标地址，支持 `IP` 或 `IP:端口`，省略端口时使用6454
+pub fn parse_artnet_target(target: &str) -> anyhow::Result<SocketAddr> {
+    let target = target.trim();
+    if let Ok(addr) = target.parse::<SocketAddr>() {
+        return Ok(addr);
+    }
+    let ip: IpAddr = target.parse().map_err(|_| {
+        anyhow::anyhow!("Invalid Art-Net target '{target}': expected an IP address or IP:port")
+    })?;
+    Ok(SocketAddr::new(ip, ARTNET_DEFAULT_PORT))
+}
+
+/// 每个universe实际使用的通道数，取像素字节数的整数倍，保证像素不跨universe
+pub fn channels_per_universe(rgbw: bool) -> usize {
+    if rgbw {
+        ARTNET_MAX_CHANNELS
+    } else {
+        ARTNET_MAX_CHANNELS - ARTNET_MAX_CHANNELS % 3
+    }
+}
+
+/// 构建ArtDmx数据包，数据长度为奇数时补一个0（协议要求长度为偶数）
+pub fn build_artdmx_packet(universe: u16, sequence: u8, data: &[u8]) -> Vec<u8> {
+    let len = (data.len() + data.len() % 2).clamp(2, ARTNET_MAX_CHANNELS);
+
+    let mut packet = Vec::with_capacity(ARTNET_HEADER_LEN + len);
+    packet.extend_from_slice(ARTNET_ID);
+    packet.extend_from_slice(&ARTNET_OP_DMX.to_le_bytes());
+    packet.extend_from_slice(&ARTNET_PROTOCOL_VERSION.to_be_bytes());
+    packet.push(sequence);
+    packet.push(0); // Physical
+    packet.push((universe & 0xFF) as u8); // SubUni
+    packet.push(((universe >> 8) & 0x7F) as u8); // Net
+    packet.extend_from_slice(&(len as u16).to_be_bytes());
+    packet.extend_from_slice(&data[..data.len().min(len)]);
+    packet.resize(ARTNET_HEADER_LEN + len, 0);
+    packet
+}
+
+/// Art-Net输出，将LED数据按universe依次映射发送到单个目标
+///
+/// ArtDmx总是从universe的第一个通道开始，而LED数据可能按显示器分段发送，
+/// 因此在本地保留整帧数据，每次只重发被更新的universe。
+#[derive(Debug)]
+pub struct ArtNetSender {
+    socket: UdpSocket,
+    target: SocketAddr,
+    start_universe: u16,
+    rgbw: bool,
+    frame: Mutex<Vec<u8>>,
+    /// 上一帧的序列号（1-255循环，0表示未使用序列号）
+    sequence: AtomicU8,
+}
+
+impl ArtNetSender {
+    pub async fn new(target: SocketAddr, start_universe: u16, rgbw: bool) -> anyhow::Result<Self> {
+        if start_universe > ARTNET_MAX_UNIVERSE {
+            anyhow::bail!(
+                "Art-Net universe {start_universe} exceeds the maximum of {ARTNET_MAX_UNIVERSE}"
+            );
+        }
+        let socket = crate::output_sink::bind_udp_socket(target).await?;
+        Ok(Self {
+            socket,
+            target,
+            start_universe,
+            rgbw,
+            frame: Mutex::new(Vec::new()),
+            sequence: AtomicU8::new(0),
+        })
+    }
+
+    pub fn target(&self) -> SocketAddr {
+        self.target
+    }
+
+    fn next_sequence(&self) -> u8 {
+        let previous = self
+            .sequence
+            .fetch_update(Ordering::Relaxed, Ordering::Relaxed, |sequence| {
+                Some(sequence % 255 + 1)
+            })
+            .unwrap_or(0);
+        previous % 255 + 1
+    }
+
+    /// 更新帧内从 `start_offset` 开始的数据，并发送受影响的universe，返回发送的数据包数量
+    pub async fn send_frame(&self, start_offset: usize, data: &[u8]) -> anyhow::Result<usize> {
+        let packets = {
+            let mut frame = self.frame.lock().await;
+            update_frame_packets(
+                &mut frame,
+                start_offset,
+                data,
+                self.start_universe,
+                self.next_sequence(),
+                self.rgbw,
+            )
+        };
+
+        for packet in &packets {
+            self.socket.send_to(packet, self.target).await?;
+        }
+        Ok(packets.len())
+    }
+}
+
+/// 将数据写入本地帧缓冲，并为受影响的universe构建ArtDmx数据包
+fn update_frame_packets(
+    frame: &mut Vec<u8>,
+    start_offset: usize,
+    data: &[u8],
+    start_universe: u16,
+    sequence: u8,
+    rgbw: bool,
+) -> Vec<Vec<u8>> {
+    if data.is_empty() {
+        return Vec::new();
+    }
+
+    let end = start_offset + data.len();
+    if frame.len() < end {
+        frame.resize(end, 0);
+    }
+    frame[start_offset..end].copy_from_slice(data);
+
+    let channels = channels_per_universe(rgbw);
+    let first = start_offset / channels;
+    let last = (end - 1) / channels;
+
+    (first..=last)
+        .filter_map(|index| {
+            let universe = start_universe as usize + index;
+            if universe > ARTNET_MAX_UNIVERSE as usize {
+                return None;
+            }
+            let chunk = &frame[index * channels..((index + 1) * channels).min(frame.len())];
+            Some(build_artdmx_packet(universe as u16, sequence, chunk))
+        })
+        .collect()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_artdmx_packet_header() {
+        let packet = build_artdmx_packet(0x0123, 7, &[1, 2, 3]);
+
+        assert_eq!(&packet[..8], b"Art-Net\0");
+        assert_eq!(&packet[8..10], &[0x00, 0x50]);
+        assert_eq!(&packet[10..12], &[0, 14]);
+        assert_eq!(packet[12], 7);
+        assert_eq!(&packet[14..16], &[0x23, 0x01]);
+        // 奇数长度补齐为偶数
+        assert_eq!(&packet[16..18], &[0, 4]);
+        assert_eq!(&packet[18..], &[1, 2, 3, 0]);
+    }
+
+    #[test]
+    fn test_partial_update_resends_only_touched_universes() {
+        let mut frame = Vec::new();
+
+        // 第一段：两个完整universe加一部分
+        let first: Vec<u8> = (0..1200).map(|i| i as u8).collect();
+        let packets = update_frame_packets(&mut frame, 0, &first, 1, 1, false);
+        assert_eq!(packets.len(), 3);
+        assert_eq!(
+            packets.iter().map(|p| p[14]).collect::<Vec<_>>(),
+            vec![1, 2, 3]
+        );
+        assert_eq!(&packets[0][18..], &first[..510]);
+
+        // 第二段从偏移1200开始，只更新第3个universe，且保留之前的数据
+        let packets = update_frame_packets(&mut frame, 1200, &[9, 9, 9], 1, 2, false);
+        assert_eq!(packets.len(), 1);
+        assert_eq!(packets[0][14], 3);
+        let payload = &packets[0][18..];
+        assert_eq!(&payload[..180], &first[1020..1200]);
+        assert_eq!(&payload[180..183], &[9, 9, 9]);
+    }
+
+    #[test]
+    fn test_channels_per_universe_keeps_pixels_whole() {
+        assert_eq!(channels_per_universe(false), 510);
+        assert_eq!(channels_per_universe(true), 512);
+    }
+
+    #[test]
+    fn test_parse_artnet_target() {
+        assert_eq!(
+            parse_artnet_target("10.0.0.2").unwrap(),
+            "10.0.0.2:6454".parse().unwrap()
+        );
+        assert!(parse_artnet_target("controller.local").is_err());
+    }
+}
@@ -0,0 +1,128 @@
+use std::borrow::Cow;
+
+use serde::{Deserialize, Serialize};
+
+type Matrix = [[f32; 3]; 3];
+
+/// Machado 等（2009）在线性RGB空间下的二色视觉模拟矩阵（严重程度 1.0）：红色盲
+const PROTANOPIA: Matrix = [
+    [0.152286, 1.052583, -0.204868],
+    [0.114503, 0.786281, 0.099216],
+    [-0.003882, -0.048116, 1.051998],
+];
+/// 绿色盲模拟矩阵
+const DEUTERANOPIA: Matrix = [
+    [0.367322, 0.860646, -0.227968],
+    [0.280085, 0.672501, 0.047413],
+    [-0.011820, 0.042940, 0.968881],
+];
+/// 蓝色盲模拟矩阵
+const TRITANOPIA: Matrix = [
+    [1.255528, -0.076749, -0.178779],
+    [-0.078411, 0.930809, 0.147602],
+    [0.004733, 0.691367, 0.303900],
+];
+
+/// 色觉缺陷模拟类型，只作用于前端预览，不影响发送到硬件的数据
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Serialize, Deserialize, utoipa::ToSchema)]
+pub enum ColorVisionSimulation {
+    /// 不模拟
+    #[default]
+    None,
+    /// 红色盲
+    Protanopia,
+    /// 绿色盲
+    Deuteranopia,
+    /// 蓝色盲
+    Tritanopia,
+}
+
+/// sRGB 通道值转换为线性光强（0-1）
+fn srgb_to_linear(value: u8) -> f32 {
+    let v = value as f32 / 255.0;
+    if v <= 0.04045 {
+        v / 12.92
+    } else {
+        ((v + 0.055) / 1.055).powf(2.4)
+    }
+}
+
+/// 线性光强转换回 sRGB 通道值，超出 0-1 的结果被截断
+fn linear_to_srgb(value: f32) -> u8 {
+    let v = value.clamp(0.0, 1.0);
+    let v = if v <= 0.003_130_8 {
+        v * 12.92
+    } else {
+        1.055 * v.powf(1.0 / 2.4) - 0.055
+    };
+    (v * 255.0).round() as u8
+}
+
+impl ColorVisionSimulation {
+    /// 该类型对应的模拟矩阵，`None` 不需要转换
+    fn matrix(&self) -> Option<&'static Matrix> {
+        match self {
+            ColorVisionSimulation::None => None,
+            ColorVisionSimulation::Protanopia => Some(&PROTANOPIA),
+            ColorVisionSimulation::Deuteranopia => Some(&DEUTERANOPIA),
+            ColorVisionSimulation::Tritanopia => Some(&TRITANOPIA),
+        }
+    }
+
+    /// 对RGB预览数据（每个LED 3字节）应用模拟，`None` 时原样返回
+    pub fn apply<'a>(&self, rgb: &'a [u8]) -> Cow<'a, [u8]> {
+        let Some(matrix) = self.matrix() else {
+            return Cow::Borrowed(rgb);
+        };
+
+        let mut data = rgb.to_vec();
+        for pixel in data.chunks_exact_mut(3) {
+            let linear = [
+                srgb_to_linear(pixel[0]),
+                srgb_to_linear(pixel[1]),
+                srgb_to_linear(pixel[2]),
+            ];
+            for (channel, row) in pixel.iter_mut().zip(matrix) {
+                *channel =
+                    linear_to_srgb(row[0] * linear[0] + row[1] * linear[1] + row[2] * linear[2]);
+            }
+        }
+        Cow::Owned(data)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_none_leaves_preview_untouched() {
+        let rgb = [255, 0, 0, 0, 255, 0];
+        assert!(matches!(
+            ColorVisionSimulation::None.apply(&rgb),
+            Cow::Borrowed(_)
+        ));
+    }
+
+    #[test]
+    fn test_simulation_keeps_neutrals_and_confuses_red_green() {
+        for simulation in [
+            ColorVisionSimulation::Protanopia,
+            ColorVisionSimulation::Deuteranopia,
+            ColorVisionSimulation::Tritanopia,
+        ] {
+            let grays = [0, 0, 0, 128, 128, 128, 255, 255, 255];
+            let simulated = simulation.apply(&grays);
+            for (a, b) in simulated.iter().zip(grays) {
+                assert!(a.abs_diff(b) <= 1, "{simulation:?} shifted a neutral color");
+            }
+        }
+
+        // 绿色盲下红色和绿色都变成黄褐色调
+        let simulated = ColorVisionSimulation::Deuteranopia.apply(&[255, 0, 0, 0, 255, 0]);
+        let (red, green) = (&simulated[..3], &simulated[3..]);
+        assert!(red[0].abs_diff(red[1]) < 30);
+        assert!(green[0].abs_diff(green[1]) < 30);
+        assert!(red[2] < 10 && green[2] < 70);
+    }
+}
@@ -0,0 +1,227 @@
+use std::net::{IpAddr, SocketAddr};
+use std::sync::atomic::{AtomicU8, Ordering};
+
+use tokio::net::UdpSocket;
+
+/// DDP默认UDP端口
+pub const DDP_DEFAULT_PORT: u16 = 4048;
+/// DDP数据包头长度（不含时间码）
+pub const DDP_HEADER_LEN: usize = 10;
+/// 单个DDP数据包最大数据长度，480个RGB或360个RGBW像素，保证不拆分像素
+pub const DDP_MAX_DATA_LEN: usize = 1440;
+
+/// 协议版本1
+const DDP_FLAGS_VERSION_1: u8 = 0x40;
+/// 推送标志：接收端收到后立即显示整帧
+const DDP_FLAGS_PUSH: u8 = 0x01;
+/// 目标ID：默认输出设备
+const DDP_ID_DISPLAY: u8 = 0x01;
+/// 数据类型：RGB，每通道8位
+const DDP_TYPE_RGB24: u8 = 0x0B;
+/// 数据类型：RGBW，每通道8位
+const DDP_TYPE_RGBW32: u8 = 0x1B;
+
+/// 解析DDP目标地址，支持 `IP` 或 `IP:端口`，省略端口时使用4048
+pub fn parse_ddp_target(target: &str) -> anyhow::Result<SocketAddr> {
+    let target = target.trim();
+    if let Ok(addr) = target.parse::<SocketAddr>() {
+        return Ok(addr);
+    }
+    let ip: IpAddr = target.parse().map_err(|_| {
+        anyhow::anyhow!("Invalid DDP target '{target}': expected an IP address or IP:port")
+    })?;
+    Ok(SocketAddr::new(ip, DDP_DEFAULT_PORT))
+}
+
+/// 构建一段数据的DDP数据包
+///
+/// 数据按 `DDP_MAX_DATA_LEN` 拆分，每包携带自身在帧内的字节偏移（加上 `start_offset`）
+/// 和数据长度；`push` 为真时（这段数据到达整帧末尾）只有最后一包设置推送标志。
+pub fn build_ddp_packets(
+    data: &[u8],
+    start_offset: u32,
+    sequence: u8,
+    rgbw: bool,
+    push: bool,
+) -> Vec<Vec<u8>> {
+    let data_type = if rgbw {
+        DDP_TYPE_RGBW32
+    } else {
+        DDP_TYPE_RGB24
+    };
+    let chunk_count = data.len().div_ceil(DDP_MAX_DATA_LEN);
+
+    data.chunks(DDP_MAX_DATA_LEN)
+        .enumerate()
+        .map(|(index, chunk)| {
+            let mut flags = DDP_FLAGS_VERSION_1;
+            if push && index + 1 == chunk_count {
+                flags |= DDP_FLAGS_PUSH;
+            }
+            let offset = start_offset + (index * DDP_MAX_DATA_LEN) as u32;
+
+            let mut packet = Vec::with_capacity(DDP_HEADER_LEN + chunk.len());
+            packet.push(flags);
+            packet.push(sequence & 0x0F);
+            packet.push(data_type);
+            packet.push(DDP_ID_DISPLAY);
+            packet.extend_from_slice(&offset.to_be_bytes());
+            packet.extend_from_slice(&(chunk.len() as u16).to_be_bytes());
+            packet.extend_from_slice(chunk);
+            packet
+        })
+        .collect()
+}
+
+/// DDP（Distributed Display Protocol）输出，向单个目标发送LED数据
+#[derive(Debug)]
+pub struct DdpSender {
+    socket: UdpSocket,
+    target: SocketAddr,
+    rgbw: bool,
+    /// 当前帧的序列号（1-15循环），整帧推送后前进
+    sequence: AtomicU8,
+}
+
+impl DdpSender {
+    pub async fn new(target: SocketAddr, rgbw: bool) -> anyhow::Result<Self> {
+        let socket = crate::output_sink::bind_udp_socket(target).await?;
+        Ok(Self {
+            socket,
+            target,
+            rgbw,
+            sequence: AtomicU8::new(1),
+        })
+    }
+
+    pub fn target(&self) -> SocketAddr {
+        self.target
+    }
+
+    fn current_sequence(&self) -> u8 {
+        self.sequence.load(Ordering::Relaxed)
+    }
+
+    fn advance_sequence(&self) {
+        let _ = self
+            .sequence
+            .fetch_update(Ordering::Relaxed, Ordering::Relaxed, |sequence| {
+                Some(sequence % 15 + 1)
+            });
+    }
+
+    /// 发送一帧中从 `start_offset` 开始的一段数据，返回发送的数据包数量
+    ///
+    /// 各显示器的数据分段发送，只有到达整帧末尾（`frame_len`）的一段设置推送标志，
+    /// 之后的数据使用新的序列号；`frame_len` 为0（布局未知）时每段都推送
+    pub async fn send_frame(
+        &self,
+        start_offset: u32,
+        data: &[u8],
+        frame_len: usize,
+    ) -> anyhow::Result<usize> {
+        let push = start_offset as usize + data.len() >= frame_len;
+        let packets =
+            build_ddp_packets(data, start_offset, self.current_sequence(), self.rgbw, push);
+        for packet in &packets {
+            self.socket.send_to(packet, self.target).await?;
+        }
+        if push {
+            self.advance_sequence();
+        }
+        Ok(packets.len())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_single_packet_header() {
+        let packets = build_ddp_packets(&[1, 2, 3, 4, 5, 6], 0, 3, false, true);
+        assert_eq!(packets.len(), 1);
+        assert_eq!(
+            packets[0],
+            vec![0x41, 3, 0x0B, 0x01, 0, 0, 0, 0, 0, 6, 1, 2, 3, 4, 5, 6]
+        );
+    }
+
+    #[test]
+    fn test_multi_packet_frame() {
+        let data: Vec<u8> = (0..3000).map(|i| i as u8).collect();
+        let packets = build_ddp_packets(&data, 12, 15, true, true);
+
+        assert_eq!(packets.len(), 3);
+        let offsets: Vec<u32> = packets
+            .iter()
+            .map(|p| u32::from_be_bytes([p[4], p[5], p[6], p[7]]))
+            .collect();
+        assert_eq!(offsets, vec![12, 12 + 1440, 12 + 2880]);
+        let lengths: Vec<u16> = packets
+            .iter()
+            .map(|p| u16::from_be_bytes([p[8], p[9]]))
+            .collect();
+        assert_eq!(lengths, vec![1440, 1440, 120]);
+
+        // 只有最后一包推送
+        assert_eq!(
+            packets.iter().map(|p| p[0]).collect::<Vec<_>>(),
+            vec![0x40, 0x40, 0x41]
+        );
+        assert!(packets.iter().all(|p| p[1] == 15 && p[2] == 0x1B));
+
+        let payload: Vec<u8> = packets
+            .iter()
+            .flat_map(|p| p[DDP_HEADER_LEN..].to_vec())
+            .collect();
+        assert_eq!(payload, data);
+
+        // 未到达整帧末尾的一段不推送
+        let packets = build_ddp_packets(&data, 0, 1, true, false);
+        assert!(packets.iter().all(|p| p[0] == 0x40));
+    }
+
+    #[tokio::test]
+    async fn test_sequence_cycles_and_skips_zero() {
+        let sender = DdpSender::new("127.0.0.1:4048".parse().unwrap(), false)
+            .await
+            .unwrap();
+        let sequences: Vec<u8> = (0..17)
+            .map(|_| {
+                let sequence = sender.current_sequence();
+                sender.advance_sequence();
+                sequence
+            })
+            .collect();
+        assert_eq!(&sequences[..3], &[1, 2, 3]);
+        assert_eq!(&sequences[14..], &[15, 1, 2]);
+    }
+
+    #[tokio::test]
+    async fn test_sequence_advances_only_after_full_frame() {
+        let sender = DdpSender::new("127.0.0.1:4048".parse().unwrap(), false)
+            .await
+            .unwrap();
+
+        // 第一个显示器的数据段未到达整帧末尾，不推送也不前进序列号
+        sender.send_frame(0, &[0; 6], 12).await.unwrap();
+        assert_eq!(sender.current_sequence(), 1);
+
+        sender.send_frame(6, &[0; 6], 12).await.unwrap();
+        assert_eq!(sender.current_sequence(), 2);
+    }
+
+    #[test]
+    fn test_parse_ddp_target() {
+        assert_eq!(
+            parse_ddp_target("192.168.1.50").unwrap(),
+            "192.168.1.50:4048".parse().unwrap()
+        );
+        assert_eq!(
+            parse_ddp_target(" 192.168.1.50:5000 ").unwrap(),
+            "192.168.1.50:5000".parse().unwrap()
+        );
+        assert!(parse_ddp_target("wled.local").is_err());
+    }
+}
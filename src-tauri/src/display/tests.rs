@@ -226,6 +226,16 @@ mod tests {
             len: 30,
             led_type: crate::ambient_light::LedType::WS2812B,
             reversed: false,
+            hardware_offset: None,
+            led_positions: None,
+            zone_reduction: crate::ambient_light::ZoneReduction::Mean,
+            color_order: crate::ambient_light::ColorOrder::default(),
+            sample_shape: crate::ambient_light::SampleShape::Grid,
+            sample_inset: 0,
+            start_trim_percent: 0.0,
+            end_trim_percent: 0.0,
+            enabled: true,
+            chain_position: None,
         };
 
         assert_eq!(strip.index, 0);
@@ -254,6 +264,16 @@ mod tests {
             len: 30,
             led_type: crate::ambient_light::LedType::WS2812B,
             reversed: false,
+            hardware_offset: None,
+            led_positions: None,
+            zone_reduction: crate::ambient_light::ZoneReduction::Mean,
+            color_order: crate::ambient_light::ColorOrder::default(),
+            sample_shape: crate::ambient_light::SampleShape::Grid,
+            sample_inset: 0,
+            start_trim_percent: 0.0,
+            end_trim_percent: 0.0,
+            enabled: true,
+            chain_position: None,
         };
         config.strips.push(strip);
 
@@ -1,3 +1,4 @@
+use std::collections::VecDeque;
 use std::time::{Duration, Instant};
 
 /// 时间窗口结构
@@ -193,6 +194,67 @@ impl Default for FrequencyCalculator {
     }
 }
 
+/// 滑动窗口帧率统计
+///
+/// 同时提供瞬时帧率（最近两帧的间隔）和窗口内的平均帧率，平均值不会随单帧抖动跳变
+#[derive(Debug, Clone)]
+pub struct RollingFps {
+    /// 滑动窗口大小
+    window: Duration,
+    /// 窗口内每帧的时间戳（从旧到新）
+    timestamps: VecDeque<Instant>,
+}
+
+impl RollingFps {
+    /// 创建指定窗口大小的帧率统计
+    pub fn new(window: Duration) -> Self {
+        Self {
+            window,
+            timestamps: VecDeque::new(),
+        }
+    }
+
+    /// 记录一帧
+    pub fn record(&mut self, now: Instant) {
+        self.timestamps.push_back(now);
+        self.evict(now);
+    }
+
+    /// 瞬时帧率：最近两帧间隔的倒数，窗口内少于两帧时为 0
+    pub fn instantaneous(&mut self, now: Instant) -> f64 {
+        self.evict(now);
+        let mut latest = self.timestamps.iter().rev();
+        match (latest.next(), latest.next()) {
+            (Some(last), Some(previous)) if last > previous => {
+                1.0 / last.duration_since(*previous).as_secs_f64()
+            }
+            _ => 0.0,
+        }
+    }
+
+    /// 窗口内的平均帧率：窗口内帧间隔的平均值的倒数，窗口内少于两帧时为 0
+    pub fn smoothed(&mut self, now: Instant) -> f64 {
+        self.evict(now);
+        match (self.timestamps.front(), self.timestamps.back()) {
+            (Some(first), Some(last)) if last > first => {
+                (self.timestamps.len() - 1) as f64 / last.duration_since(*first).as_secs_f64()
+            }
+            _ => 0.0,
+        }
+    }
+
+    /// 移除窗口之外的帧
+    fn evict(&mut self, now: Instant) {
+        while let Some(oldest) = self.timestamps.front() {
+            if now.duration_since(*oldest) > self.window {
+                self.timestamps.pop_front();
+            } else {
+                break;
+            }
+        }
+    }
+}
+
 #[cfg(test)]
 mod tests {
     use super::*;
@@ -230,6 +292,34 @@ mod tests {
         assert!(frequency > 0.0);
     }
 
+    #[test]
+    fn test_rolling_fps_smooths_jitter_over_window() {
+        let start = Instant::now();
+        let mut fps = RollingFps::new(Duration::from_secs(2));
+
+        // 间隔在 10ms 和 30ms 之间交替，平均 20ms（50 FPS）
+        let mut now = start;
+        for i in 0..60 {
+            now += Duration::from_millis(if i % 2 == 0 { 10 } else { 30 });
+            fps.record(now);
+        }
+        let instantaneous = fps.instantaneous(now);
+        assert!((instantaneous - 1000.0 / 30.0).abs() < 0.01);
+        assert!((fps.smoothed(now) - 50.0).abs() < 1.0);
+
+        // 超出窗口的旧帧不再参与平均：之后以 100ms 间隔（10 FPS）持续 3 秒
+        for _ in 0..30 {
+            now += Duration::from_millis(100);
+            fps.record(now);
+        }
+        assert!((fps.smoothed(now) - 10.0).abs() < 0.01);
+        assert!((fps.instantaneous(now) - 10.0).abs() < 0.01);
+
+        // 停止发送超过窗口后瞬时和平均帧率都归零
+        assert_eq!(fps.instantaneous(now + Duration::from_secs(3)), 0.0);
+        assert_eq!(fps.smoothed(now + Duration::from_secs(3)), 0.0);
+    }
+
     #[test]
     fn test_frequency_calculator_reset() {
         let mut calc = FrequencyCalculator::new();
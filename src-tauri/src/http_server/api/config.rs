@@ -1,19 +1,48 @@
 use axum::{
+    extract::Path,
     http::StatusCode,
     response::Json,
-    routing::{get, post, put},
+    routing::{delete, get, post, put},
     Router,
 };
-use serde::Deserialize;
+use serde::{Deserialize, Serialize};
+use std::collections::HashMap;
 use utoipa::ToSchema;
 
 use crate::{
-    ambient_light::{self, Border, ColorCalibration, LedStripConfigGroupV2, LedType},
-    http_server::{ApiResponse, AppState},
+    ambient_light::{
+        self, Border, CaptureFailurePolicy, ColorCalibration, ColorCountPolicy, DisplaySleepPolicy,
+        FrameSyncMode, LedStripConfigGroupV2, LedStripConfigV2, LedType, SamplingStrategy,
+    },
+    http_server::{ApiError, ApiErrorResponse, ApiResponse, AppState},
     language_manager::LanguageManager,
+    led_data_processor::{AutoBrightnessMode, CalibrationCurve, LedDataProcessor},
+    led_data_sender::LedDataSender,
+    output_sink::OutputSinkConfig,
+    preview_scaling::PreviewFilter,
+    screenshot::AveragingColorSpace,
+    screenshot_manager::{CaptureBackend, ScreenshotManager},
     user_preferences::{UIPreferences, UserPreferences, UserPreferencesManager, WindowPreferences},
+    webhook::WebhookEvent,
 };
 
+/// 恢复默认配置请求
+#[derive(Deserialize, ToSchema)]
+pub struct ResetConfigRequest {
+    /// 确认清除全部配置，必须为 true，防止误操作
+    #[serde(default)]
+    pub confirm: bool,
+}
+
+/// 恢复默认配置结果
+#[derive(Serialize, ToSchema)]
+pub struct ResetConfigResult {
+    /// 原灯带配置的备份文件路径，原配置文件不存在时为空
+    pub config_backup: Option<String>,
+    /// 原用户偏好设置的备份文件路径，原文件不存在时为空
+    pub preferences_backup: Option<String>,
+}
+
 /// LED灯带长度更新请求
 #[derive(Deserialize, ToSchema)]
 pub struct UpdateLedStripLenRequest {
@@ -36,6 +65,17 @@ pub struct UpdateLedStripTypeRequest {
     pub led_type: LedType,
 }
 
+/// LED灯带启用状态更新请求
+#[derive(Deserialize, ToSchema)]
+pub struct UpdateLedStripEnabledRequest {
+    /// 显示器ID
+    pub display_id: u32,
+    /// 边框
+    pub border: Border,
+    /// 是否启用（禁用的灯带输出黑色）
+    pub enabled: bool,
+}
+
 /// LED灯带反转请求
 #[derive(Deserialize, ToSchema)]
 pub struct ReverseLedStripRequest {
@@ -66,6 +106,232 @@ pub struct UpdateUserPreferencesRequest {
     pub preferences: UserPreferences,
 }
 
+/// 采样点数量更新请求
+#[derive(Deserialize, ToSchema)]
+pub struct UpdateSamplePointsPerLedRequest {
+    /// 每个LED的采样点数量（1到64之间的完全平方数）
+    pub points: usize,
+}
+
+/// 黑电平阈值更新请求
+#[derive(Deserialize, ToSchema)]
+pub struct UpdateBlackLevelRequest {
+    /// 感知亮度低于该值（0-255）的像素不参与颜色平均，0 表示关闭
+    pub black_level: u8,
+}
+
+/// LED颜色插值强度更新请求
+#[derive(Deserialize, ToSchema)]
+pub struct UpdateInterpolationStrengthRequest {
+    /// 相邻LED之间的颜色插值强度（0-1），0 表示关闭
+    pub strength: f32,
+}
+
+/// 采样平均颜色空间更新请求
+#[derive(Deserialize, ToSchema)]
+pub struct UpdateAveragingColorSpaceRequest {
+    /// 采样点取平均时使用的颜色空间（gamma/linear）
+    pub color_space: AveragingColorSpace,
+}
+
+/// 双线性采样开关更新请求
+#[derive(Deserialize, ToSchema)]
+pub struct UpdateBilinearSamplingRequest {
+    /// 是否按子像素位置双线性插值读取像素
+    pub enabled: bool,
+}
+
+/// 纵向渐变采样开关更新请求
+#[derive(Deserialize, ToSchema)]
+pub struct UpdateVerticalGradientSamplingRequest {
+    /// 左右边的LED是否覆盖相邻LED的纵向窗口采样
+    pub enabled: bool,
+}
+
+/// 多显示器帧同步模式更新请求
+#[derive(Deserialize, ToSchema)]
+pub struct UpdateFrameSyncModeRequest {
+    /// Synchronized：等待所有显示器上报后发送；Immediate：任一显示器更新即发送
+    pub mode: FrameSyncMode,
+}
+
+/// 显示器休眠处理方式更新请求
+#[derive(Deserialize, ToSchema)]
+pub struct UpdateDisplaySleepPolicyRequest {
+    /// Ignore：照常输出；Hold：保持休眠前的颜色；FadeToBlack：渐变到黑色
+    pub policy: DisplaySleepPolicy,
+    /// 是否将持续全黑的画面也视为休眠，省略时保持不变；默认关闭，
+    /// 仅在系统不再报告该显示器时触发休眠处理，避免暗场景冻结灯带
+    #[serde(default)]
+    pub detect_sleep_from_black_frames: Option<bool>,
+}
+
+/// 采样方式更新请求
+#[derive(Deserialize, ToSchema)]
+pub struct UpdateSamplingStrategyRequest {
+    /// Edges：按边缘逐个LED采样；ScreenAverage：整屏平均色填充所有LED
+    pub strategy: SamplingStrategy,
+}
+
+/// 颜色数量不匹配处理方式更新请求
+#[derive(Deserialize, ToSchema)]
+pub struct UpdateColorCountPolicyRequest {
+    /// Truncate：截断/补黑；Wrap：循环使用颜色；Resample：插值拉伸到LED总数
+    pub policy: ColorCountPolicy,
+}
+
+/// 采集后端选择请求
+#[derive(Deserialize, ToSchema)]
+pub struct UpdateCaptureBackendRequest {
+    /// WindowList：CGWindowListCreateImage；DisplayImage：CGDisplayCreateImage
+    pub backend: CaptureBackend,
+}
+
+/// 采集后端及其可用性
+#[derive(Serialize, ToSchema)]
+pub struct CaptureBackendInfo {
+    pub backend: CaptureBackend,
+    /// 后端说明
+    pub description: String,
+    /// 在当前系统上试采集是否成功（例如缺少屏幕录制权限时不可用）
+    pub available: bool,
+}
+
+/// 可用采集后端列表及当前选择
+#[derive(Serialize, ToSchema)]
+pub struct CaptureBackendsStatus {
+    /// 当前选择的后端，采集失败时自动回退到默认后端
+    pub selected: CaptureBackend,
+    /// 各显示器最近一帧实际使用的后端（按显示器ID索引），选择的后端失败回退时与 `selected` 不同
+    pub active: HashMap<u32, CaptureBackend>,
+    pub backends: Vec<CaptureBackendInfo>,
+}
+
+/// 全屏采集配置更新请求
+#[derive(Deserialize, ToSchema)]
+pub struct UpdateFullscreenCaptureRequest {
+    /// 是否在全屏应用运行时切换到全屏采集配置
+    pub enabled: bool,
+    /// 全屏应用运行时只采集主显示器
+    pub primary_display_only: bool,
+    /// 全屏应用运行时的采集帧间隔（毫秒）
+    pub frame_interval_ms: u64,
+}
+
+/// 全屏采集配置及当前检测状态
+#[derive(Serialize, ToSchema)]
+pub struct FullscreenCaptureStatus {
+    /// 是否在全屏应用运行时切换到全屏采集配置
+    pub enabled: bool,
+    /// 全屏应用运行时只采集主显示器
+    pub primary_display_only: bool,
+    /// 全屏应用运行时的采集帧间隔（毫秒）
+    pub frame_interval_ms: u64,
+    /// 当前检测到的全屏应用所在显示器，没有全屏应用或未启用时为空
+    pub fullscreen_display_id: Option<u32>,
+}
+
+/// 自动亮度设置
+#[derive(Serialize, Deserialize, ToSchema)]
+pub struct AutoBrightnessSettings {
+    /// 是否根据屏幕整体亮度自动调整输出
+    pub enabled: bool,
+    /// 调整方向（反向：画面越亮输出越暗）
+    pub mode: AutoBrightnessMode,
+    /// 调整强度（0-1）
+    pub strength: f32,
+}
+
+/// 光标跟随设置
+#[derive(Serialize, Deserialize, ToSchema)]
+pub struct CursorFocusSettings {
+    /// 是否突出鼠标光标所在显示器的灯带
+    pub enabled: bool,
+    /// 调整强度（0-1），光标所在显示器提升亮度，其他显示器调暗
+    pub strength: f32,
+}
+
+/// 调色板量化设置
+#[derive(Serialize, Deserialize, ToSchema)]
+pub struct PaletteQuantizationSettings {
+    /// 是否将颜色映射到调色板中最近的颜色
+    pub enabled: bool,
+    /// 调色板RGB颜色列表（1-256 个）
+    pub palette: Vec<[u8; 3]>,
+}
+
+/// 定时色温偏移设置
+#[derive(Serialize, Deserialize, ToSchema)]
+pub struct ColorTemperatureShiftSettings {
+    /// 是否按时间在日间与夜间色温之间切换
+    pub enabled: bool,
+    /// 日间色温（K，1000-10000）
+    pub day_kelvin: u32,
+    /// 夜间色温（K，1000-10000）
+    pub night_kelvin: u32,
+    /// 日出时间（本地时间午夜起的分钟数）
+    pub sunrise_minute: u32,
+    /// 日落时间（本地时间午夜起的分钟数）
+    pub sunset_minute: u32,
+    /// 日出/日落后的过渡时长（分钟）
+    pub transition_minutes: u32,
+}
+
+/// 截图采集失败回退设置
+#[derive(Serialize, Deserialize, ToSchema)]
+pub struct CaptureFailureSettings {
+    /// 采集失败时的表现：保持、渐变到黑色或显示诊断颜色
+    pub policy: CaptureFailurePolicy,
+    /// 超过该时间（毫秒）没有新画面视为采集失败，最小 100
+    pub stall_timeout_ms: u64,
+    /// 渐变到黑色的时长（毫秒）
+    pub fade_ms: u64,
+    /// 诊断颜色（RGB）
+    pub diagnostic_color: [u8; 3],
+}
+
+/// LED数据输出设置
+#[derive(Serialize, Deserialize, ToSchema)]
+pub struct OutputSettings {
+    /// 输出端列表，每帧数据同时发送到所有输出端；为空时不输出
+    pub sinks: Vec<OutputSinkConfig>,
+    /// 发送UDP数据使用的本地网卡地址，为空时由系统选择；
+    /// 已发现的本应用协议设备在重启后使用新地址
+    #[serde(default)]
+    #[schema(value_type = Option<String>)]
+    pub local_bind_address: Option<std::net::IpAddr>,
+    /// 设备重新上线后从全黑渐亮到目标亮度的时长（毫秒），0 表示立即全亮；
+    /// 为空时保持当前设置
+    #[serde(default)]
+    pub reconnect_fade_ms: Option<u64>,
+}
+
+/// 显示器预览缩放设置
+#[derive(Serialize, Deserialize, ToSchema)]
+pub struct PreviewScalingSettings {
+    /// 中间尺寸宽度，为 0 时关闭两步缩放
+    pub intermediate_width: u32,
+    /// 中间尺寸高度，为 0 时关闭两步缩放
+    pub intermediate_height: u32,
+    /// 缩小到中间尺寸使用的滤波器
+    pub intermediate_filter: PreviewFilter,
+    /// 缩放到目标尺寸使用的滤波器
+    pub final_filter: PreviewFilter,
+}
+
+/// 状态变化Webhook设置
+#[derive(Serialize, Deserialize, ToSchema)]
+pub struct WebhookSettings {
+    /// 接收POST请求的 `http://` 地址，为空时关闭
+    pub url: String,
+    /// 触发请求的事件
+    pub events: Vec<WebhookEvent>,
+    /// 单次请求超时（毫秒）
+    pub timeout_ms: u64,
+    /// 请求失败后的重试次数
+    pub max_retries: u32,
+}
+
 /// 窗口偏好设置更新请求
 #[derive(Deserialize, ToSchema)]
 pub struct UpdateWindowPreferencesRequest {
@@ -87,6 +353,34 @@ pub struct UpdateGlobalColorCalibrationRequest {
     pub calibration: ColorCalibration,
 }
 
+/// 保存颜色校准预设请求
+#[derive(Deserialize, ToSchema)]
+pub struct SaveCalibrationPresetRequest {
+    /// 预设名称，同名预设被覆盖
+    pub name: String,
+}
+
+/// 等待校准生效的默认超时时间（毫秒）
+const DEFAULT_CALIBRATION_CONFIRM_TIMEOUT_MS: u64 = 2_000;
+/// 等待校准生效的最大超时时间（毫秒）
+const MAX_CALIBRATION_CONFIRM_TIMEOUT_MS: u64 = 10_000;
+
+/// 应用并确认全局颜色校准请求
+#[derive(Deserialize, ToSchema)]
+pub struct ConfirmGlobalColorCalibrationRequest {
+    /// 颜色校准设置
+    pub calibration: ColorCalibration,
+    /// 等待使用新校准的第一帧的超时时间（毫秒），默认 2000，最大 10000
+    pub timeout_ms: Option<u64>,
+}
+
+/// 应用并确认全局颜色校准结果
+#[derive(Serialize, ToSchema)]
+pub struct ConfirmGlobalColorCalibrationResponse {
+    /// 从应用校准到第一帧使用新校准完成编码的耗时（毫秒）
+    pub latency_ms: u64,
+}
+
 /// 语言设置更新请求
 #[derive(Deserialize, ToSchema)]
 pub struct UpdateLanguageRequest {
@@ -122,13 +416,13 @@ pub async fn get_led_strip_configs_v2(
     request_body = LedStripConfigGroupV2,
     responses(
         (status = 200, description = "更新LED灯带配置成功 (v2 语义)", body = ApiResponse<String>),
-        (status = 500, description = "更新失败", body = ApiResponse<String>),
+        (status = 500, description = "更新失败", body = ApiError),
     ),
     tag = "config"
 )]
 pub async fn update_led_strip_configs_v2(
     Json(v2_config): Json<LedStripConfigGroupV2>,
-) -> Result<Json<ApiResponse<String>>, StatusCode> {
+) -> Result<Json<ApiResponse<String>>, ApiErrorResponse> {
     let config_manager_v2 = ambient_light::ConfigManagerV2::global().await;
     match config_manager_v2.update_config(v2_config).await {
         Ok(_) => Ok(Json(ApiResponse::success(
@@ -136,11 +430,79 @@ pub async fn update_led_strip_configs_v2(
         ))),
         Err(e) => {
             log::error!("Failed to update LED strip configs: {e}");
-            Err(StatusCode::INTERNAL_SERVER_ERROR)
+            Err(config_update_failed(&e))
         }
     }
 }
 
+/// 通过显示器注册管理器获取显示器的内部ID
+async fn resolve_display_internal_id(
+    config_manager_v2: &ambient_light::ConfigManagerV2,
+    display_id: u32,
+) -> Result<String, ApiErrorResponse> {
+    config_manager_v2
+        .get_display_registry()
+        .get_internal_id_by_display_id(display_id)
+        .await
+        .map_err(|e| {
+            log::warn!("Failed to get internal ID for display {display_id}: {e}");
+            ApiError::response(
+                StatusCode::NOT_FOUND,
+                ApiError::DISPLAY_NOT_FOUND,
+                &format!("Display {display_id} not found: {e}"),
+            )
+        })
+}
+
+/// 查找指定显示器某一边框上的灯带
+fn find_strip_mut<'a>(
+    config: &'a mut LedStripConfigGroupV2,
+    internal_id: &str,
+    display_id: u32,
+    border: Border,
+) -> Result<&'a mut LedStripConfigV2, ApiErrorResponse> {
+    config
+        .strips
+        .iter_mut()
+        .find(|strip| strip.display_internal_id == internal_id && strip.border == border)
+        .ok_or_else(|| {
+            log::warn!("LED strip not found for display {display_id} border {border:?}");
+            ApiError::response(
+                StatusCode::NOT_FOUND,
+                ApiError::STRIP_NOT_FOUND,
+                &format!("No LED strip on the {border:?} border of display {display_id}"),
+            )
+        })
+}
+
+/// 配置保存失败时的错误响应
+fn config_update_failed(error: impl std::fmt::Display) -> ApiErrorResponse {
+    ApiError::response(
+        StatusCode::INTERNAL_SERVER_ERROR,
+        ApiError::CONFIG_UPDATE_FAILED,
+        &error.to_string(),
+    )
+}
+
+/// 请求参数校验失败时的错误响应
+fn invalid_request(error: impl std::fmt::Display) -> ApiErrorResponse {
+    ApiError::response(
+        StatusCode::BAD_REQUEST,
+        ApiError::INVALID_REQUEST,
+        &error.to_string(),
+    )
+}
+
+/// 颜色校准预设不存在时的错误响应
+fn preset_not_found(name: &str) -> ApiErrorResponse {
+    log::warn!("Calibration preset {name:?} not found");
+    ApiError::response(
+        StatusCode::NOT_FOUND,
+        ApiError::PRESET_NOT_FOUND,
+        &format!("Calibration preset {name:?} not found"),
+    )
+}
+
 /// 更新LED灯带长度
 #[utoipa::path(
     put,
@@ -148,54 +510,29 @@ pub async fn update_led_strip_configs_v2(
     request_body = UpdateLedStripLenRequest,
     responses(
         (status = 200, description = "更新LED灯带长度成功", body = ApiResponse<String>),
-        (status = 500, description = "更新失败", body = ApiResponse<String>),
+        (status = 404, description = "显示器（DISPLAY_NOT_FOUND）或灯带（STRIP_NOT_FOUND）不存在", body = ApiError),
+        (status = 500, description = "更新失败（CONFIG_UPDATE_FAILED）", body = ApiError),
     ),
     tag = "config"
 )]
 pub async fn update_led_strip_length(
     Json(request): Json<UpdateLedStripLenRequest>,
-) -> Result<Json<ApiResponse<String>>, StatusCode> {
+) -> Result<Json<ApiResponse<String>>, ApiErrorResponse> {
     let config_manager_v2 = ambient_light::ConfigManagerV2::global().await;
 
     // 获取当前配置
     let mut v2_config = config_manager_v2.get_config().await;
 
-    // 通过显示器注册管理器获取内部ID
-    let display_registry = config_manager_v2.get_display_registry();
-    let internal_id = match display_registry
-        .get_internal_id_by_display_id(request.display_id)
-        .await
-    {
-        Ok(id) => id,
-        Err(e) => {
-            log::error!(
-                "Failed to get internal ID for display {}: {}",
-                request.display_id,
-                e
-            );
-            return Err(StatusCode::INTERNAL_SERVER_ERROR);
-        }
-    };
+    let internal_id = resolve_display_internal_id(config_manager_v2, request.display_id).await?;
 
     // 查找并更新对应的灯带
-    let mut found = false;
-    for strip in &mut v2_config.strips {
-        if strip.display_internal_id == internal_id && strip.border == request.border {
-            let new_len = (strip.len as i32 + request.delta_len as i32).max(0) as usize;
-            strip.len = new_len;
-            found = true;
-            break;
-        }
-    }
-
-    if !found {
-        log::error!(
-            "LED strip not found for display {} border {:?}",
-            request.display_id,
-            request.border
-        );
-        return Err(StatusCode::INTERNAL_SERVER_ERROR);
-    }
+    let strip = find_strip_mut(
+        &mut v2_config,
+        &internal_id,
+        request.display_id,
+        request.border,
+    )?;
+    strip.len = (strip.len as i32 + request.delta_len as i32).max(0) as usize;
 
     // 重新生成mappers
     v2_config.generate_mappers();
@@ -207,7 +544,46 @@ pub async fn update_led_strip_length(
         ))),
         Err(e) => {
             log::error!("Failed to update LED strip length: {e}");
-            Err(StatusCode::INTERNAL_SERVER_ERROR)
+            Err(config_update_failed(&e))
+        }
+    }
+}
+
+/// 启用或禁用LED灯带，便于只接入部分灯带时逐步安装调试
+#[utoipa::path(
+    put,
+    path = "/api/v1/config/led-strips/enabled",
+    request_body = UpdateLedStripEnabledRequest,
+    responses(
+        (status = 200, description = "更新LED灯带启用状态成功", body = ApiResponse<String>),
+        (status = 404, description = "显示器（DISPLAY_NOT_FOUND）或灯带（STRIP_NOT_FOUND）不存在", body = ApiError),
+        (status = 500, description = "更新失败（CONFIG_UPDATE_FAILED）", body = ApiError),
+    ),
+    tag = "config"
+)]
+pub async fn update_led_strip_enabled(
+    Json(request): Json<UpdateLedStripEnabledRequest>,
+) -> Result<Json<ApiResponse<String>>, ApiErrorResponse> {
+    let config_manager_v2 = ambient_light::ConfigManagerV2::global().await;
+    let mut v2_config = config_manager_v2.get_config().await;
+
+    let internal_id = resolve_display_internal_id(config_manager_v2, request.display_id).await?;
+
+    let strip = find_strip_mut(
+        &mut v2_config,
+        &internal_id,
+        request.display_id,
+        request.border,
+    )?;
+    strip.enabled = request.enabled;
+
+    match config_manager_v2.update_config(v2_config).await {
+        Ok(_) => Ok(Json(ApiResponse::success(
+            "LED strip enabled state updated successfully".to_string(),
+        ))),
+        Err(e) => {
+            log::error!("Failed to update LED strip enabled state: {e}");
+            Err(config_update_failed(&e))
         }
     }
 }
@@ -219,20 +595,31 @@ pub async fn update_led_strip_length(
     request_body = ReverseLedStripRequest,
     responses(
         (status = 200, description = "反转LED灯带成功", body = ApiResponse<String>),
-        (status = 404, description = "未找到指定的LED灯带", body = ApiResponse<String>),
-        (status = 500, description = "反转失败", body = ApiResponse<String>),
+        (status = 404, description = "显示器（DISPLAY_NOT_FOUND）或灯带（STRIP_NOT_FOUND）不存在", body = ApiError),
+        (status = 500, description = "反转失败（CONFIG_UPDATE_FAILED）", body = ApiError),
     ),
     tag = "config"
 )]
 pub async fn reverse_led_strip(
     Json(request): Json<ReverseLedStripRequest>,
-) -> Result<Json<ApiResponse<String>>, StatusCode> {
-    let config_manager = ambient_light::ConfigManager::global().await;
+) -> Result<Json<ApiResponse<String>>, ApiErrorResponse> {
+    let config_manager_v2 = ambient_light::ConfigManagerV2::global().await;
+    let mut v2_config = config_manager_v2.get_config().await;
 
-    match config_manager
-        .reverse_led_strip_part(request.display_id, request.border)
-        .await
-    {
+    let internal_id = resolve_display_internal_id(config_manager_v2, request.display_id).await?;
+
+    let strip = find_strip_mut(
+        &mut v2_config,
+        &internal_id,
+        request.display_id,
+        request.border,
+    )?;
+    strip.reversed = !strip.reversed;
+
+    // 重新生成mappers以反映新的反向状态
+    v2_config.generate_mappers();
+
+    match config_manager_v2.update_config(v2_config).await {
         Ok(_) => {
             log::info!(
                 "LED strip reversed successfully: display_id={}, border={:?}",
@@ -245,7 +632,7 @@ pub async fn reverse_led_strip(
         }
         Err(e) => {
             log::error!("Failed to reverse LED strip: {e}");
-            Err(StatusCode::INTERNAL_SERVER_ERROR)
+            Err(config_update_failed(&e))
         }
     }
 }
@@ -273,13 +660,13 @@ pub async fn get_user_preferences() -> Result<Json<ApiResponse<UserPreferences>>
     request_body = UpdateThemeRequest,
     responses(
         (status = 200, description = "更新主题成功", body = ApiResponse<String>),
-        (status = 500, description = "更新失败", body = ApiResponse<String>),
+        (status = 500, description = "更新失败（CONFIG_UPDATE_FAILED）", body = ApiError),
     ),
     tag = "config"
 )]
 pub async fn update_theme(
     Json(request): Json<UpdateThemeRequest>,
-) -> Result<Json<ApiResponse<String>>, StatusCode> {
+) -> Result<Json<ApiResponse<String>>, ApiErrorResponse> {
     let preferences_manager = UserPreferencesManager::global().await;
     match preferences_manager.update_theme(request.theme).await {
         Ok(_) => Ok(Json(ApiResponse::success(
@@ -287,7 +674,7 @@ pub async fn update_theme(
         ))),
         Err(e) => {
             log::error!("Failed to update theme: {e}");
-            Err(StatusCode::INTERNAL_SERVER_ERROR)
+            Err(config_update_failed(&e))
         }
     }
 }
@@ -299,53 +686,29 @@ pub async fn update_theme(
     request_body = UpdateLedStripTypeRequest,
     responses(
         (status = 200, description = "更新LED灯带类型成功", body = ApiResponse<String>),
-        (status = 500, description = "更新失败", body = ApiResponse<String>),
+        (status = 404, description = "显示器（DISPLAY_NOT_FOUND）或灯带（STRIP_NOT_FOUND）不存在", body = ApiError),
+        (status = 500, description = "更新失败（CONFIG_UPDATE_FAILED）", body = ApiError),
     ),
     tag = "config"
 )]
 pub async fn update_led_strip_type(
     Json(request): Json<UpdateLedStripTypeRequest>,
-) -> Result<Json<ApiResponse<String>>, StatusCode> {
+) -> Result<Json<ApiResponse<String>>, ApiErrorResponse> {
     let config_manager_v2 = ambient_light::ConfigManagerV2::global().await;
 
     // 获取当前配置
     let mut v2_config = config_manager_v2.get_config().await;
 
-    // 通过显示器注册管理器获取内部ID
-    let display_registry = config_manager_v2.get_display_registry();
-    let internal_id = match display_registry
-        .get_internal_id_by_display_id(request.display_id)
-        .await
-    {
-        Ok(id) => id,
-        Err(e) => {
-            log::error!(
-                "Failed to get internal ID for display {}: {}",
-                request.display_id,
-                e
-            );
-            return Err(StatusCode::INTERNAL_SERVER_ERROR);
-        }
-    };
+    let internal_id = resolve_display_internal_id(config_manager_v2, request.display_id).await?;
 
     // 查找并更新对应的灯带
-    let mut found = false;
-    for strip in &mut v2_config.strips {
-        if strip.display_internal_id == internal_id && strip.border == request.border {
-            strip.led_type = request.led_type;
-            found = true;
-            break;
-        }
-    }
-
-    if !found {
-        log::error!(
-            "LED strip not found for display {} border {:?}",
-            request.display_id,
-            request.border
-        );
-        return Err(StatusCode::INTERNAL_SERVER_ERROR);
-    }
+    find_strip_mut(
+        &mut v2_config,
+        &internal_id,
+        request.display_id,
+        request.border,
+    )?
+    .led_type = request.led_type;
 
     // 重新生成mappers
     v2_config.generate_mappers();
@@ -357,7 +720,7 @@ pub async fn update_led_strip_type(
         ))),
         Err(e) => {
             log::error!("Failed to update LED strip type: {e}");
-            Err(StatusCode::INTERNAL_SERVER_ERROR)
+            Err(config_update_failed(&e))
         }
     }
 }
@@ -414,13 +777,13 @@ pub async fn get_current_language() -> Result<Json<ApiResponse<String>>, StatusC
     request_body = UpdateLanguageRequest,
     responses(
         (status = 200, description = "设置语言成功", body = ApiResponse<String>),
-        (status = 500, description = "设置失败", body = ApiResponse<String>),
+        (status = 500, description = "设置失败（CONFIG_UPDATE_FAILED）", body = ApiError),
     ),
     tag = "config"
 )]
 pub async fn set_current_language(
     Json(request): Json<UpdateLanguageRequest>,
-) -> Result<Json<ApiResponse<String>>, StatusCode> {
+) -> Result<Json<ApiResponse<String>>, ApiErrorResponse> {
     let language_manager = LanguageManager::global().await;
 
     match language_manager
@@ -435,7 +798,7 @@ pub async fn set_current_language(
         }
         Err(e) => {
             log::error!("Failed to set language: {e}");
-            Err(StatusCode::INTERNAL_SERVER_ERROR)
+            Err(config_update_failed(&e))
         }
     }
 }
@@ -477,13 +840,19 @@ pub async fn get_view_scale() -> Result<Json<ApiResponse<f64>>, StatusCode> {
     request_body = UpdateViewScaleRequest,
     responses(
         (status = 200, description = "更新视图缩放成功", body = ApiResponse<String>),
-        (status = 500, description = "更新失败", body = ApiResponse<String>),
+        (status = 400, description = "视图缩放超出范围（INVALID_REQUEST）", body = ApiError),
+        (status = 500, description = "更新失败（CONFIG_UPDATE_FAILED）", body = ApiError),
     ),
     tag = "config"
 )]
 pub async fn update_view_scale(
     Json(request): Json<UpdateViewScaleRequest>,
-) -> Result<Json<ApiResponse<String>>, StatusCode> {
+) -> Result<Json<ApiResponse<String>>, ApiErrorResponse> {
+    if let Err(e) = crate::user_preferences::validate_view_scale(request.scale) {
+        log::warn!("Invalid view scale: {e}");
+        return Err(invalid_request(&e));
+    }
+
     let preferences_manager = UserPreferencesManager::global().await;
     match preferences_manager.update_view_scale(request.scale).await {
         Ok(_) => Ok(Json(ApiResponse::success(
@@ -491,147 +860,1667 @@ pub async fn update_view_scale(
         ))),
         Err(e) => {
             log::error!("Failed to update view scale: {e}");
-            Err(StatusCode::INTERNAL_SERVER_ERROR)
+            Err(config_update_failed(&e))
         }
     }
 }
 
-/// 更新全局颜色校准
+/// 获取每个LED的采样点数量
+#[utoipa::path(
+    get,
+    path = "/api/v1/config/sample-points-per-led",
+    responses(
+        (status = 200, description = "获取采样点数量成功", body = ApiResponse<usize>),
+    ),
+    tag = "config"
+)]
+pub async fn get_sample_points_per_led() -> Result<Json<ApiResponse<usize>>, StatusCode> {
+    let preferences_manager = UserPreferencesManager::global().await;
+    let points = preferences_manager.get_sample_points_per_led().await;
+    Ok(Json(ApiResponse::success(points)))
+}
+
+/// 更新每个LED的采样点数量
 #[utoipa::path(
     put,
-    path = "/api/v1/config/global-color-calibration",
-    request_body = UpdateGlobalColorCalibrationRequest,
+    path = "/api/v1/config/sample-points-per-led",
+    request_body = UpdateSamplePointsPerLedRequest,
     responses(
-        (status = 200, description = "更新全局颜色校准成功", body = ApiResponse<String>),
-        (status = 500, description = "更新失败", body = ApiResponse<String>),
+        (status = 200, description = "更新采样点数量成功", body = ApiResponse<String>),
+        (status = 400, description = "采样点数量无效（INVALID_REQUEST）", body = ApiError),
+        (status = 500, description = "更新失败（CONFIG_UPDATE_FAILED）", body = ApiError),
     ),
     tag = "config"
 )]
-pub async fn update_global_color_calibration(
-    Json(request): Json<UpdateGlobalColorCalibrationRequest>,
-) -> Result<Json<ApiResponse<String>>, StatusCode> {
-    log::info!(
-        "🎨 [COLOR_CALIBRATION] HTTP API request to update color calibration: r={:.3}, g={:.3}, b={:.3}, w={:.3}",
-        request.calibration.r,
-        request.calibration.g,
-        request.calibration.b,
-        request.calibration.w
-    );
+pub async fn update_sample_points_per_led(
+    Json(request): Json<UpdateSamplePointsPerLedRequest>,
+) -> Result<Json<ApiResponse<String>>, ApiErrorResponse> {
+    if let Err(e) = crate::screenshot::validate_sample_points_per_led(request.points) {
+        log::warn!("Invalid sample points per LED: {e}");
+        return Err(invalid_request(&e));
+    }
 
-    let config_manager_v2 = ambient_light::ConfigManagerV2::global().await;
-    match config_manager_v2
-        .update_color_calibration(request.calibration)
+    let preferences_manager = UserPreferencesManager::global().await;
+    match preferences_manager
+        .update_sample_points_per_led(request.points)
         .await
     {
-        Ok(_) => {
-            log::info!(
-                "✅ [COLOR_CALIBRATION] HTTP API successfully updated color calibration: r={:.3}, g={:.3}, b={:.3}, w={:.3}",
-                request.calibration.r,
-                request.calibration.g,
-                request.calibration.b,
-                request.calibration.w
-            );
-            Ok(Json(ApiResponse::success(
-                "Global color calibration updated successfully".to_string(),
-            )))
-        }
+        Ok(_) => Ok(Json(ApiResponse::success(
+            "Sample points per LED updated successfully".to_string(),
+        ))),
         Err(e) => {
-            log::error!(
-                "❌ [COLOR_CALIBRATION] HTTP API failed to update color calibration: r={:.3}, g={:.3}, b={:.3}, w={:.3}, error: {}",
-                request.calibration.r,
-                request.calibration.g,
-                request.calibration.b,
-                request.calibration.w,
-                e
-            );
-            Err(StatusCode::INTERNAL_SERVER_ERROR)
+            log::error!("Failed to update sample points per LED: {e}");
+            Err(config_update_failed(&e))
         }
     }
 }
 
-/// 更新用户偏好设置
+/// 获取采样黑电平阈值
+#[utoipa::path(
+    get,
+    path = "/api/v1/config/black-level",
+    responses(
+        (status = 200, description = "获取黑电平阈值成功", body = ApiResponse<u8>),
+    ),
+    tag = "config"
+)]
+pub async fn get_black_level() -> Result<Json<ApiResponse<u8>>, StatusCode> {
+    let preferences_manager = UserPreferencesManager::global().await;
+    let black_level = preferences_manager.get_black_level().await;
+    Ok(Json(ApiResponse::success(black_level)))
+}
+
+/// 更新采样黑电平阈值
 #[utoipa::path(
     put,
-    path = "/api/v1/config/user-preferences",
-    request_body = UpdateUserPreferencesRequest,
+    path = "/api/v1/config/black-level",
+    request_body = UpdateBlackLevelRequest,
     responses(
-        (status = 200, description = "更新用户偏好设置成功", body = ApiResponse<String>),
-        (status = 500, description = "更新失败", body = ApiResponse<String>),
+        (status = 200, description = "更新黑电平阈值成功", body = ApiResponse<String>),
+        (status = 500, description = "更新失败（CONFIG_UPDATE_FAILED）", body = ApiError),
     ),
     tag = "config"
 )]
-pub async fn update_user_preferences(
-    Json(request): Json<UpdateUserPreferencesRequest>,
-) -> Result<Json<ApiResponse<String>>, StatusCode> {
+pub async fn update_black_level(
+    Json(request): Json<UpdateBlackLevelRequest>,
+) -> Result<Json<ApiResponse<String>>, ApiErrorResponse> {
     let preferences_manager = UserPreferencesManager::global().await;
     match preferences_manager
-        .update_preferences(request.preferences)
+        .update_black_level(request.black_level)
         .await
     {
         Ok(_) => Ok(Json(ApiResponse::success(
-            "User preferences updated successfully".to_string(),
+            "Black level updated successfully".to_string(),
         ))),
         Err(e) => {
-            log::error!("Failed to update user preferences: {e}");
-            Err(StatusCode::INTERNAL_SERVER_ERROR)
+            log::error!("Failed to update black level: {e}");
+            Err(config_update_failed(&e))
         }
     }
 }
 
-/// 更新窗口偏好设置
+/// 获取相邻LED之间的颜色插值强度
+#[utoipa::path(
+    get,
+    path = "/api/v1/config/interpolation-strength",
+    responses(
+        (status = 200, description = "获取插值强度成功", body = ApiResponse<f32>),
+    ),
+    tag = "config"
+)]
+pub async fn get_interpolation_strength() -> Result<Json<ApiResponse<f32>>, StatusCode> {
+    let preferences_manager = UserPreferencesManager::global().await;
+    let strength = preferences_manager.get_interpolation_strength().await;
+    Ok(Json(ApiResponse::success(strength)))
+}
+
+/// 更新相邻LED之间的颜色插值强度
 #[utoipa::path(
     put,
-    path = "/api/v1/config/window-preferences",
-    request_body = UpdateWindowPreferencesRequest,
+    path = "/api/v1/config/interpolation-strength",
+    request_body = UpdateInterpolationStrengthRequest,
     responses(
-        (status = 200, description = "更新窗口偏好设置成功", body = ApiResponse<String>),
-        (status = 500, description = "更新失败", body = ApiResponse<String>),
+        (status = 200, description = "更新插值强度成功", body = ApiResponse<String>),
+        (status = 400, description = "插值强度无效（INVALID_REQUEST）", body = ApiError),
+        (status = 500, description = "更新失败（CONFIG_UPDATE_FAILED）", body = ApiError),
     ),
     tag = "config"
 )]
-pub async fn update_window_preferences(
-    Json(request): Json<UpdateWindowPreferencesRequest>,
-) -> Result<Json<ApiResponse<String>>, StatusCode> {
+pub async fn update_interpolation_strength(
+    Json(request): Json<UpdateInterpolationStrengthRequest>,
+) -> Result<Json<ApiResponse<String>>, ApiErrorResponse> {
+    if let Err(e) = crate::screenshot::validate_interpolation_strength(request.strength) {
+        log::warn!("Invalid interpolation strength: {e}");
+        return Err(invalid_request(&e));
+    }
+
     let preferences_manager = UserPreferencesManager::global().await;
     match preferences_manager
-        .update_window_preferences(request.window_prefs)
+        .update_interpolation_strength(request.strength)
         .await
     {
         Ok(_) => Ok(Json(ApiResponse::success(
-            "Window preferences updated successfully".to_string(),
+            "Interpolation strength updated successfully".to_string(),
         ))),
         Err(e) => {
-            log::error!("Failed to update window preferences: {e}");
-            Err(StatusCode::INTERNAL_SERVER_ERROR)
+            log::error!("Failed to update interpolation strength: {e}");
+            Err(config_update_failed(&e))
         }
     }
 }
 
-/// 更新UI偏好设置
+/// 获取采样点取平均时使用的颜色空间
+#[utoipa::path(
+    get,
+    path = "/api/v1/config/averaging-color-space",
+    responses(
+        (status = 200, description = "获取平均颜色空间成功", body = ApiResponse<AveragingColorSpace>),
+    ),
+    tag = "config"
+)]
+pub async fn get_averaging_color_space(
+) -> Result<Json<ApiResponse<AveragingColorSpace>>, StatusCode> {
+    let preferences_manager = UserPreferencesManager::global().await;
+    let color_space = preferences_manager.get_averaging_color_space().await;
+    Ok(Json(ApiResponse::success(color_space)))
+}
+
+/// 更新采样点取平均时使用的颜色空间，影响所有显示器的采样
 #[utoipa::path(
     put,
-    path = "/api/v1/config/ui-preferences",
-    request_body = UpdateUIPreferencesRequest,
+    path = "/api/v1/config/averaging-color-space",
+    request_body = UpdateAveragingColorSpaceRequest,
     responses(
-        (status = 200, description = "更新UI偏好设置成功", body = ApiResponse<String>),
-        (status = 500, description = "更新失败", body = ApiResponse<String>),
+        (status = 200, description = "更新平均颜色空间成功", body = ApiResponse<String>),
+        (status = 500, description = "更新失败（CONFIG_UPDATE_FAILED）", body = ApiError),
     ),
     tag = "config"
 )]
-pub async fn update_ui_preferences(
-    Json(request): Json<UpdateUIPreferencesRequest>,
-) -> Result<Json<ApiResponse<String>>, StatusCode> {
+pub async fn update_averaging_color_space(
+    Json(request): Json<UpdateAveragingColorSpaceRequest>,
+) -> Result<Json<ApiResponse<String>>, ApiErrorResponse> {
     let preferences_manager = UserPreferencesManager::global().await;
     match preferences_manager
-        .update_ui_preferences(request.ui_prefs)
+        .update_averaging_color_space(request.color_space)
         .await
     {
         Ok(_) => Ok(Json(ApiResponse::success(
-            "UI preferences updated successfully".to_string(),
+            "Averaging color space updated successfully".to_string(),
         ))),
         Err(e) => {
-            log::error!("Failed to update UI preferences: {e}");
-            Err(StatusCode::INTERNAL_SERVER_ERROR)
+            log::error!("Failed to update averaging color space: {e}");
+            Err(config_update_failed(&e))
+        }
+    }
+}
+
+/// 获取是否启用双线性子像素采样
+#[utoipa::path(
+    get,
+    path = "/api/v1/config/bilinear-sampling",
+    responses(
+        (status = 200, description = "获取双线性采样开关成功", body = ApiResponse<bool>),
+    ),
+    tag = "config"
+)]
+pub async fn get_bilinear_sampling() -> Result<Json<ApiResponse<bool>>, StatusCode> {
+    let preferences_manager = UserPreferencesManager::global().await;
+    let enabled = preferences_manager.get_bilinear_sampling().await;
+    Ok(Json(ApiResponse::success(enabled)))
+}
+
+/// 启用或关闭双线性子像素采样，开启后采样更平滑但开销更大
+#[utoipa::path(
+    put,
+    path = "/api/v1/config/bilinear-sampling",
+    request_body = UpdateBilinearSamplingRequest,
+    responses(
+        (status = 200, description = "更新双线性采样开关成功", body = ApiResponse<String>),
+        (status = 500, description = "更新失败（CONFIG_UPDATE_FAILED）", body = ApiError),
+    ),
+    tag = "config"
+)]
+pub async fn update_bilinear_sampling(
+    Json(request): Json<UpdateBilinearSamplingRequest>,
+) -> Result<Json<ApiResponse<String>>, ApiErrorResponse> {
+    let preferences_manager = UserPreferencesManager::global().await;
+    match preferences_manager
+        .update_bilinear_sampling(request.enabled)
+        .await
+    {
+        Ok(_) => Ok(Json(ApiResponse::success(
+            "Bilinear sampling updated successfully".to_string(),
+        ))),
+        Err(e) => {
+            log::error!("Failed to update bilinear sampling: {e}");
+            Err(config_update_failed(&e))
+        }
+    }
+}
+
+/// 获取左右边是否启用纵向渐变采样
+#[utoipa::path(
+    get,
+    path = "/api/v1/config/vertical-gradient-sampling",
+    responses(
+        (status = 200, description = "获取纵向渐变采样开关成功", body = ApiResponse<bool>),
+    ),
+    tag = "config"
+)]
+pub async fn get_vertical_gradient_sampling() -> Result<Json<ApiResponse<bool>>, StatusCode> {
+    let preferences_manager = UserPreferencesManager::global().await;
+    let enabled = preferences_manager.get_vertical_gradient_sampling().await;
+    Ok(Json(ApiResponse::success(enabled)))
+}
+
+/// 启用或关闭纵向渐变采样，开启后左右边灯带沿长度方向呈现与画面一致的平滑渐变
+#[utoipa::path(
+    put,
+    path = "/api/v1/config/vertical-gradient-sampling",
+    request_body = UpdateVerticalGradientSamplingRequest,
+    responses(
+        (status = 200, description = "更新纵向渐变采样开关成功", body = ApiResponse<String>),
+        (status = 500, description = "更新失败（CONFIG_UPDATE_FAILED）", body = ApiError),
+    ),
+    tag = "config"
+)]
+pub async fn update_vertical_gradient_sampling(
+    Json(request): Json<UpdateVerticalGradientSamplingRequest>,
+) -> Result<Json<ApiResponse<String>>, ApiErrorResponse> {
+    let preferences_manager = UserPreferencesManager::global().await;
+    match preferences_manager
+        .update_vertical_gradient_sampling(request.enabled)
+        .await
+    {
+        Ok(_) => Ok(Json(ApiResponse::success(
+            "Vertical gradient sampling updated successfully".to_string(),
+        ))),
+        Err(e) => {
+            log::error!("Failed to update vertical gradient sampling: {e}");
+            Err(config_update_failed(&e))
+        }
+    }
+}
+
+/// 获取多显示器帧同步模式
+#[utoipa::path(
+    get,
+    path = "/api/v1/config/frame-sync-mode",
+    responses(
+        (status = 200, description = "获取帧同步模式成功", body = ApiResponse<FrameSyncMode>),
+    ),
+    tag = "config"
+)]
+pub async fn get_frame_sync_mode() -> Result<Json<ApiResponse<FrameSyncMode>>, StatusCode> {
+    let preferences_manager = UserPreferencesManager::global().await;
+    let mode = preferences_manager.get_frame_sync_mode().await;
+    Ok(Json(ApiResponse::success(mode)))
+}
+
+/// 更新多显示器帧同步模式，Immediate 模式下慢速显示器不会阻塞其他显示器的输出
+#[utoipa::path(
+    put,
+    path = "/api/v1/config/frame-sync-mode",
+    request_body = UpdateFrameSyncModeRequest,
+    responses(
+        (status = 200, description = "更新帧同步模式成功", body = ApiResponse<String>),
+        (status = 500, description = "更新失败（CONFIG_UPDATE_FAILED）", body = ApiError),
+    ),
+    tag = "config"
+)]
+pub async fn update_frame_sync_mode(
+    Json(request): Json<UpdateFrameSyncModeRequest>,
+) -> Result<Json<ApiResponse<String>>, ApiErrorResponse> {
+    let preferences_manager = UserPreferencesManager::global().await;
+    match preferences_manager
+        .update_frame_sync_mode(request.mode)
+        .await
+    {
+        Ok(_) => Ok(Json(ApiResponse::success(
+            "Frame sync mode updated successfully".to_string(),
+        ))),
+        Err(e) => {
+            log::error!("Failed to update frame sync mode: {e}");
+            Err(config_update_failed(&e))
+        }
+    }
+}
+
+/// 获取灯带颜色的采样方式
+#[utoipa::path(
+    get,
+    path = "/api/v1/config/sampling-strategy",
+    responses(
+        (status = 200, description = "获取采样方式成功", body = ApiResponse<SamplingStrategy>),
+    ),
+    tag = "config"
+)]
+pub async fn get_sampling_strategy() -> Result<Json<ApiResponse<SamplingStrategy>>, StatusCode> {
+    let preferences_manager = UserPreferencesManager::global().await;
+    let strategy = preferences_manager.get_sampling_strategy().await;
+    Ok(Json(ApiResponse::success(strategy)))
+}
+
+/// 更新灯带颜色的采样方式，ScreenAverage 时每个显示器的灯带整体显示整屏平均色
+#[utoipa::path(
+    put,
+    path = "/api/v1/config/sampling-strategy",
+    request_body = UpdateSamplingStrategyRequest,
+    responses(
+        (status = 200, description = "更新采样方式成功", body = ApiResponse<String>),
+        (status = 500, description = "更新失败（CONFIG_UPDATE_FAILED）", body = ApiError),
+    ),
+    tag = "config"
+)]
+pub async fn update_sampling_strategy(
+    Json(request): Json<UpdateSamplingStrategyRequest>,
+) -> Result<Json<ApiResponse<String>>, ApiErrorResponse> {
+    let preferences_manager = UserPreferencesManager::global().await;
+    match preferences_manager
+        .update_sampling_strategy(request.strategy)
+        .await
+    {
+        Ok(_) => Ok(Json(ApiResponse::success(
+            "Sampling strategy updated successfully".to_string(),
+        ))),
+        Err(e) => {
+            log::error!("Failed to update sampling strategy: {e}");
+            Err(config_update_failed(&e))
+        }
+    }
+}
+
+/// 获取显示器休眠（关闭或节能模式）时灯带的处理方式
+#[utoipa::path(
+    get,
+    path = "/api/v1/config/display-sleep-policy",
+    responses(
+        (status = 200, description = "获取显示器休眠处理方式成功", body = ApiResponse<DisplaySleepPolicy>),
+    ),
+    tag = "config"
+)]
+pub async fn get_display_sleep_policy() -> Result<Json<ApiResponse<DisplaySleepPolicy>>, StatusCode>
+{
+    let preferences_manager = UserPreferencesManager::global().await;
+    let policy = preferences_manager.get_display_sleep_policy().await;
+    Ok(Json(ApiResponse::success(policy)))
+}
+
+/// 更新显示器休眠时灯带的处理方式，避免显示器休眠后画面变黑导致灯带熄灭
+#[utoipa::path(
+    put,
+    path = "/api/v1/config/display-sleep-policy",
+    request_body = UpdateDisplaySleepPolicyRequest,
+    responses(
+        (status = 200, description = "更新显示器休眠处理方式成功", body = ApiResponse<String>),
+        (status = 500, description = "更新失败（CONFIG_UPDATE_FAILED）", body = ApiError),
+    ),
+    tag = "config"
+)]
+pub async fn update_display_sleep_policy(
+    Json(request): Json<UpdateDisplaySleepPolicyRequest>,
+) -> Result<Json<ApiResponse<String>>, ApiErrorResponse> {
+    let preferences_manager = UserPreferencesManager::global().await;
+    match preferences_manager
+        .update_display_sleep_policy(request.policy, request.detect_sleep_from_black_frames)
+        .await
+    {
+        Ok(_) => Ok(Json(ApiResponse::success(
+            "Display sleep policy updated successfully".to_string(),
+        ))),
+        Err(e) => {
+            log::error!("Failed to update display sleep policy: {e}");
+            Err(config_update_failed(&e))
+        }
+    }
+}
+
+/// 获取采样颜色数量与LED总数不一致时的处理方式
+#[utoipa::path(
+    get,
+    path = "/api/v1/config/color-count-policy",
+    responses(
+        (status = 200, description = "获取颜色数量处理方式成功", body = ApiResponse<ColorCountPolicy>),
+    ),
+    tag = "config"
+)]
+pub async fn get_color_count_policy() -> Result<Json<ApiResponse<ColorCountPolicy>>, StatusCode> {
+    let preferences_manager = UserPreferencesManager::global().await;
+    let policy = preferences_manager.get_color_count_policy().await;
+    Ok(Json(ApiResponse::success(policy)))
+}
+
+/// 更新采样颜色数量与LED总数不一致时的处理方式
+#[utoipa::path(
+    put,
+    path = "/api/v1/config/color-count-policy",
+    request_body = UpdateColorCountPolicyRequest,
+    responses(
+        (status = 200, description = "更新颜色数量处理方式成功", body = ApiResponse<String>),
+        (status = 500, description = "更新失败（CONFIG_UPDATE_FAILED）", body = ApiError),
+    ),
+    tag = "config"
+)]
+pub async fn update_color_count_policy(
+    Json(request): Json<UpdateColorCountPolicyRequest>,
+) -> Result<Json<ApiResponse<String>>, ApiErrorResponse> {
+    let preferences_manager = UserPreferencesManager::global().await;
+    match preferences_manager
+        .update_color_count_policy(request.policy)
+        .await
+    {
+        Ok(_) => Ok(Json(ApiResponse::success(
+            "Color count policy updated successfully".to_string(),
+        ))),
+        Err(e) => {
+            log::error!("Failed to update color count policy: {e}");
+            Err(config_update_failed(&e))
+        }
+    }
+}
+
+/// 列出屏幕采集后端及其可用性，以及当前选择的后端
+#[utoipa::path(
+    get,
+    path = "/api/v1/config/capture-backends",
+    responses(
+        (status = 200, description = "获取采集后端列表成功", body = ApiResponse<CaptureBackendsStatus>),
+    ),
+    tag = "config"
+)]
+pub async fn get_capture_backends() -> Result<Json<ApiResponse<CaptureBackendsStatus>>, StatusCode>
+{
+    let selected = UserPreferencesManager::global()
+        .await
+        .get_capture_backend()
+        .await;
+
+    let screenshot_manager = ScreenshotManager::global().await;
+    let mut backends = Vec::with_capacity(CaptureBackend::ALL.len());
+    for backend in CaptureBackend::ALL {
+        backends.push(CaptureBackendInfo {
+            backend,
+            description: backend.description().to_string(),
+            available: screenshot_manager
+                .is_capture_backend_available(backend)
+                .await,
+        });
+    }
+
+    Ok(Json(ApiResponse::success(CaptureBackendsStatus {
+        selected,
+        active: screenshot_manager.get_active_capture_backends().await,
+        backends,
+    })))
+}
+
+/// 选择屏幕采集后端，无需重新编译即可绕过特定后端的问题
+#[utoipa::path(
+    put,
+    path = "/api/v1/config/capture-backend",
+    request_body = UpdateCaptureBackendRequest,
+    responses(
+        (status = 200, description = "选择采集后端成功", body = ApiResponse<String>),
+        (status = 500, description = "更新失败（CONFIG_UPDATE_FAILED）", body = ApiError),
+    ),
+    tag = "config"
+)]
+pub async fn update_capture_backend(
+    Json(request): Json<UpdateCaptureBackendRequest>,
+) -> Result<Json<ApiResponse<String>>, ApiErrorResponse> {
+    if !ScreenshotManager::global()
+        .await
+        .is_capture_backend_available(request.backend)
+        .await
+    {
+        log::warn!(
+            "Capture backend {:?} is not available, frames will fall back to {:?}",
+            request.backend,
+            CaptureBackend::default()
+        );
+    }
+
+    match UserPreferencesManager::global()
+        .await
+        .update_capture_backend(request.backend)
+        .await
+    {
+        Ok(_) => Ok(Json(ApiResponse::success(
+            "Capture backend updated successfully".to_string(),
+        ))),
+        Err(e) => {
+            log::error!("Failed to update capture backend: {e}");
+            Err(config_update_failed(&e))
+        }
+    }
+}
+
+/// 获取全屏采集配置及当前全屏检测状态
+#[utoipa::path(
+    get,
+    path = "/api/v1/config/fullscreen-capture",
+    responses(
+        (status = 200, description = "获取全屏采集配置成功", body = ApiResponse<FullscreenCaptureStatus>),
+    ),
+    tag = "config"
+)]
+pub async fn get_fullscreen_capture(
+) -> Result<Json<ApiResponse<FullscreenCaptureStatus>>, StatusCode> {
+    let profile = UserPreferencesManager::global()
+        .await
+        .get_fullscreen_capture()
+        .await;
+    let fullscreen_display_id = crate::screenshot_manager::ScreenshotManager::global()
+        .await
+        .get_fullscreen_display()
+        .await;
+
+    Ok(Json(ApiResponse::success(FullscreenCaptureStatus {
+        enabled: profile.enabled,
+        primary_display_only: profile.primary_display_only,
+        frame_interval_ms: profile.frame_interval_ms,
+        fullscreen_display_id,
+    })))
+}
+
+/// 更新全屏采集配置
+#[utoipa::path(
+    put,
+    path = "/api/v1/config/fullscreen-capture",
+    request_body = UpdateFullscreenCaptureRequest,
+    responses(
+        (status = 200, description = "更新全屏采集配置成功", body = ApiResponse<String>),
+        (status = 400, description = "帧间隔超出范围（INVALID_REQUEST）", body = ApiError),
+        (status = 500, description = "更新失败（CONFIG_UPDATE_FAILED）", body = ApiError),
+    ),
+    tag = "config"
+)]
+pub async fn update_fullscreen_capture(
+    Json(request): Json<UpdateFullscreenCaptureRequest>,
+) -> Result<Json<ApiResponse<String>>, ApiErrorResponse> {
+    if let Err(e) =
+        crate::screenshot_manager::validate_fullscreen_frame_interval(request.frame_interval_ms)
+    {
+        log::warn!("Invalid fullscreen capture profile: {e}");
+        return Err(invalid_request(&e));
+    }
+
+    let profile = crate::user_preferences::FullscreenCapturePreferences {
+        enabled: request.enabled,
+        primary_display_only: request.primary_display_only,
+        frame_interval_ms: request.frame_interval_ms,
+    };
+    match UserPreferencesManager::global()
+        .await
+        .update_fullscreen_capture(profile)
+        .await
+    {
+        Ok(_) => Ok(Json(ApiResponse::success(
+            "Fullscreen capture profile updated successfully".to_string(),
+        ))),
+        Err(e) => {
+            log::error!("Failed to update fullscreen capture profile: {e}");
+            Err(config_update_failed(&e))
+        }
+    }
+}
+
+/// 获取自动亮度设置
+#[utoipa::path(
+    get,
+    path = "/api/v1/config/auto-brightness",
+    responses(
+        (status = 200, description = "获取自动亮度设置成功", body = ApiResponse<AutoBrightnessSettings>),
+    ),
+    tag = "config"
+)]
+pub async fn get_auto_brightness() -> Result<Json<ApiResponse<AutoBrightnessSettings>>, StatusCode>
+{
+    let settings = UserPreferencesManager::global()
+        .await
+        .get_auto_brightness()
+        .await;
+
+    Ok(Json(ApiResponse::success(AutoBrightnessSettings {
+        enabled: settings.enabled,
+        mode: settings.mode,
+        strength: settings.strength,
+    })))
+}
+
+/// 更新自动亮度设置
+#[utoipa::path(
+    put,
+    path = "/api/v1/config/auto-brightness",
+    request_body = AutoBrightnessSettings,
+    responses(
+        (status = 200, description = "更新自动亮度设置成功", body = ApiResponse<String>),
+        (status = 400, description = "强度超出范围（INVALID_REQUEST）", body = ApiError),
+        (status = 500, description = "更新失败（CONFIG_UPDATE_FAILED）", body = ApiError),
+    ),
+    tag = "config"
+)]
+pub async fn update_auto_brightness(
+    Json(request): Json<AutoBrightnessSettings>,
+) -> Result<Json<ApiResponse<String>>, ApiErrorResponse> {
+    if let Err(e) = crate::led_data_processor::validate_auto_brightness_strength(request.strength) {
+        log::warn!("Invalid auto brightness settings: {e}");
+        return Err(invalid_request(&e));
+    }
+
+    let settings = crate::user_preferences::AutoBrightnessPreferences {
+        enabled: request.enabled,
+        mode: request.mode,
+        strength: request.strength,
+    };
+    match UserPreferencesManager::global()
+        .await
+        .update_auto_brightness(settings)
+        .await
+    {
+        Ok(_) => Ok(Json(ApiResponse::success(
+            "Auto brightness updated successfully".to_string(),
+        ))),
+        Err(e) => {
+            log::error!("Failed to update auto brightness: {e}");
+            Err(config_update_failed(&e))
+        }
+    }
+}
+
+/// 获取光标跟随设置
+#[utoipa::path(
+    get,
+    path = "/api/v1/config/cursor-focus",
+    responses(
+        (status = 200, description = "获取光标跟随设置成功", body = ApiResponse<CursorFocusSettings>),
+    ),
+    tag = "config"
+)]
+pub async fn get_cursor_focus() -> Result<Json<ApiResponse<CursorFocusSettings>>, StatusCode> {
+    let settings = UserPreferencesManager::global()
+        .await
+        .get_cursor_focus()
+        .await;
+
+    Ok(Json(ApiResponse::success(CursorFocusSettings {
+        enabled: settings.enabled,
+        strength: settings.strength,
+    })))
+}
+
+/// 更新光标跟随设置
+#[utoipa::path(
+    put,
+    path = "/api/v1/config/cursor-focus",
+    request_body = CursorFocusSettings,
+    responses(
+        (status = 200, description = "更新光标跟随设置成功", body = ApiResponse<String>),
+        (status = 400, description = "强度超出范围（INVALID_REQUEST）", body = ApiError),
+        (status = 500, description = "更新失败（CONFIG_UPDATE_FAILED）", body = ApiError),
+    ),
+    tag = "config"
+)]
+pub async fn update_cursor_focus(
+    Json(request): Json<CursorFocusSettings>,
+) -> Result<Json<ApiResponse<String>>, ApiErrorResponse> {
+    if let Err(e) = crate::led_data_processor::validate_cursor_focus_strength(request.strength) {
+        log::warn!("Invalid cursor focus settings: {e}");
+        return Err(invalid_request(&e));
+    }
+
+    let settings = crate::user_preferences::CursorFocusPreferences {
+        enabled: request.enabled,
+        strength: request.strength,
+    };
+    match UserPreferencesManager::global()
+        .await
+        .update_cursor_focus(settings)
+        .await
+    {
+        Ok(_) => Ok(Json(ApiResponse::success(
+            "Cursor focus updated successfully".to_string(),
+        ))),
+        Err(e) => {
+            log::error!("Failed to update cursor focus: {e}");
+            Err(config_update_failed(&e))
+        }
+    }
+}
+
+/// 获取定时色温偏移设置
+#[utoipa::path(
+    get,
+    path = "/api/v1/config/color-temperature-shift",
+    responses(
+        (status = 200, description = "获取定时色温偏移设置成功", body = ApiResponse<ColorTemperatureShiftSettings>),
+    ),
+    tag = "config"
+)]
+pub async fn get_color_temperature_shift(
+) -> Result<Json<ApiResponse<ColorTemperatureShiftSettings>>, StatusCode> {
+    let settings = UserPreferencesManager::global()
+        .await
+        .get_color_temperature_shift()
+        .await;
+
+    Ok(Json(ApiResponse::success(ColorTemperatureShiftSettings {
+        enabled: settings.enabled,
+        day_kelvin: settings.day_kelvin,
+        night_kelvin: settings.night_kelvin,
+        sunrise_minute: settings.sunrise_minute,
+        sunset_minute: settings.sunset_minute,
+        transition_minutes: settings.transition_minutes,
+    })))
+}
+
+/// 更新定时色温偏移设置
+#[utoipa::path(
+    put,
+    path = "/api/v1/config/color-temperature-shift",
+    request_body = ColorTemperatureShiftSettings,
+    responses(
+        (status = 200, description = "更新定时色温偏移设置成功", body = ApiResponse<String>),
+        (status = 400, description = "色温或时间超出范围（INVALID_REQUEST）", body = ApiError),
+        (status = 500, description = "更新失败（CONFIG_UPDATE_FAILED）", body = ApiError),
+    ),
+    tag = "config"
+)]
+pub async fn update_color_temperature_shift(
+    Json(request): Json<ColorTemperatureShiftSettings>,
+) -> Result<Json<ApiResponse<String>>, ApiErrorResponse> {
+    let settings = crate::user_preferences::ColorTemperatureShiftPreferences {
+        enabled: request.enabled,
+        day_kelvin: request.day_kelvin,
+        night_kelvin: request.night_kelvin,
+        sunrise_minute: request.sunrise_minute,
+        sunset_minute: request.sunset_minute,
+        transition_minutes: request.transition_minutes,
+    };
+    if let Err(e) = crate::led_data_processor::validate_color_temperature_shift(&settings) {
+        log::warn!("Invalid color temperature shift settings: {e}");
+        return Err(invalid_request(&e));
+    }
+
+    match UserPreferencesManager::global()
+        .await
+        .update_color_temperature_shift(settings)
+        .await
+    {
+        Ok(_) => Ok(Json(ApiResponse::success(
+            "Color temperature shift updated successfully".to_string(),
+        ))),
+        Err(e) => {
+            log::error!("Failed to update color temperature shift: {e}");
+            Err(config_update_failed(&e))
+        }
+    }
+}
+
+/// 获取调色板量化设置
+#[utoipa::path(
+    get,
+    path = "/api/v1/config/palette-quantization",
+    responses(
+        (status = 200, description = "获取调色板量化设置成功", body = ApiResponse<PaletteQuantizationSettings>),
+    ),
+    tag = "config"
+)]
+pub async fn get_palette_quantization(
+) -> Result<Json<ApiResponse<PaletteQuantizationSettings>>, StatusCode> {
+    let settings = UserPreferencesManager::global()
+        .await
+        .get_palette_quantization()
+        .await;
+
+    Ok(Json(ApiResponse::success(PaletteQuantizationSettings {
+        enabled: settings.enabled,
+        palette: settings.palette,
+    })))
+}
+
+/// 更新调色板量化设置，同时作用于硬件输出和预览
+#[utoipa::path(
+    put,
+    path = "/api/v1/config/palette-quantization",
+    request_body = PaletteQuantizationSettings,
+    responses(
+        (status = 200, description = "更新调色板量化设置成功", body = ApiResponse<String>),
+        (status = 400, description = "调色板为空或颜色过多（INVALID_REQUEST）", body = ApiError),
+        (status = 500, description = "更新失败（CONFIG_UPDATE_FAILED）", body = ApiError),
+    ),
+    tag = "config"
+)]
+pub async fn update_palette_quantization(
+    Json(request): Json<PaletteQuantizationSettings>,
+) -> Result<Json<ApiResponse<String>>, ApiErrorResponse> {
+    if let Err(e) = crate::led_data_processor::validate_palette(&request.palette) {
+        log::warn!("Invalid palette quantization settings: {e}");
+        return Err(invalid_request(&e));
+    }
+
+    let settings = crate::user_preferences::PaletteQuantizationPreferences {
+        enabled: request.enabled,
+        palette: request.palette,
+    };
+    match UserPreferencesManager::global()
+        .await
+        .update_palette_quantization(settings)
+        .await
+    {
+        Ok(_) => Ok(Json(ApiResponse::success(
+            "Palette quantization updated successfully".to_string(),
+        ))),
+        Err(e) => {
+            log::error!("Failed to update palette quantization: {e}");
+            Err(config_update_failed(&e))
+        }
+    }
+}
+
+/// 获取截图采集失败回退设置
+#[utoipa::path(
+    get,
+    path = "/api/v1/config/capture-failure",
+    responses(
+        (status = 200, description = "获取采集失败回退设置成功", body = ApiResponse<CaptureFailureSettings>),
+    ),
+    tag = "config"
+)]
+pub async fn get_capture_failure() -> Result<Json<ApiResponse<CaptureFailureSettings>>, StatusCode>
+{
+    let settings = UserPreferencesManager::global()
+        .await
+        .get_capture_failure()
+        .await;
+
+    Ok(Json(ApiResponse::success(CaptureFailureSettings {
+        policy: settings.policy,
+        stall_timeout_ms: settings.stall_timeout_ms,
+        fade_ms: settings.fade_ms,
+        diagnostic_color: settings.diagnostic_color,
+    })))
+}
+
+/// 更新截图采集失败回退设置
+#[utoipa::path(
+    put,
+    path = "/api/v1/config/capture-failure",
+    request_body = CaptureFailureSettings,
+    responses(
+        (status = 200, description = "更新采集失败回退设置成功", body = ApiResponse<String>),
+        (status = 400, description = "设置无效（INVALID_REQUEST）", body = ApiError),
+        (status = 500, description = "更新失败（CONFIG_UPDATE_FAILED）", body = ApiError),
+    ),
+    tag = "config"
+)]
+pub async fn update_capture_failure(
+    Json(request): Json<CaptureFailureSettings>,
+) -> Result<Json<ApiResponse<String>>, ApiErrorResponse> {
+    let settings = crate::user_preferences::CaptureFailurePreferences {
+        policy: request.policy,
+        stall_timeout_ms: request.stall_timeout_ms,
+        fade_ms: request.fade_ms,
+        diagnostic_color: request.diagnostic_color,
+    };
+    if let Err(e) = crate::ambient_light::validate_capture_failure(&settings) {
+        log::warn!("Invalid capture failure settings: {e}");
+        return Err(invalid_request(&e));
+    }
+
+    match UserPreferencesManager::global()
+        .await
+        .update_capture_failure(settings)
+        .await
+    {
+        Ok(_) => Ok(Json(ApiResponse::success(
+            "Capture failure settings updated successfully".to_string(),
+        ))),
+        Err(e) => {
+            log::error!("Failed to update capture failure settings: {e}");
+            Err(config_update_failed(&e))
+        }
+    }
+}
+
+/// 校验、保存并应用输出端配置
+async fn apply_output(
+    output: crate::user_preferences::OutputPreferences,
+) -> Result<(), ApiErrorResponse> {
+    if let Err(e) = crate::output_sink::validate_output_sinks(&output.sinks)
+        .and_then(|_| crate::output_sink::validate_local_bind_address(output.local_bind_address))
+        .and_then(|_| crate::led_data_sender::validate_reconnect_fade_ms(output.reconnect_fade_ms))
+    {
+        log::warn!("Invalid output settings: {e}");
+        return Err(invalid_request(&e));
+    }
+
+    if let Err(e) = UserPreferencesManager::global()
+        .await
+        .update_output(output.clone())
+        .await
+    {
+        log::error!("Failed to update output settings: {e}");
+        return Err(config_update_failed(&e));
+    }
+
+    // 创建失败的输出端被跳过，其余输出端照常生效
+    if let Err(e) = LedDataSender::global()
+        .await
+        .configure_output(&output)
+        .await
+    {
+        log::error!("Failed to apply output settings: {e}");
+        return Err(config_update_failed(&e));
+    }
+    Ok(())
+}
+
+/// 获取LED数据输出设置
+#[utoipa::path(
+    get,
+    path = "/api/v1/config/output",
+    responses(
+        (status = 200, description = "获取输出设置成功", body = ApiResponse<OutputSettings>),
+    ),
+    tag = "config"
+)]
+pub async fn get_output() -> Result<Json<ApiResponse<OutputSettings>>, StatusCode> {
+    let output = UserPreferencesManager::global().await.get_output().await;
+
+    Ok(Json(ApiResponse::success(OutputSettings {
+        sinks: output.sinks,
+        local_bind_address: output.local_bind_address,
+        reconnect_fade_ms: Some(output.reconnect_fade_ms),
+    })))
+}
+
+/// 替换全部LED数据输出端，立即生效
+#[utoipa::path(
+    put,
+    path = "/api/v1/config/output",
+    request_body = OutputSettings,
+    responses(
+        (status = 200, description = "更新输出设置成功", body = ApiResponse<String>),
+        (status = 400, description = "输出端配置无效（INVALID_REQUEST）", body = ApiError),
+        (status = 500, description = "更新失败（CONFIG_UPDATE_FAILED）", body = ApiError),
+    ),
+    tag = "config"
+)]
+pub async fn update_output(
+    Json(request): Json<OutputSettings>,
+) -> Result<Json<ApiResponse<String>>, ApiErrorResponse> {
+    let current = UserPreferencesManager::global().await.get_output().await;
+    apply_output(crate::user_preferences::OutputPreferences {
+        sinks: request.sinks,
+        local_bind_address: request.local_bind_address,
+        reconnect_fade_ms: request
+            .reconnect_fade_ms
+            .unwrap_or(current.reconnect_fade_ms),
+    })
+    .await?;
+
+    Ok(Json(ApiResponse::success(
+        "Output settings updated successfully".to_string(),
+    )))
+}
+
+/// 获取LED数据输出端列表
+#[utoipa::path(
+    get,
+    path = "/api/v1/config/output/sinks",
+    responses(
+        (status = 200, description = "获取输出端列表成功", body = ApiResponse<Vec<OutputSinkConfig>>),
+    ),
+    tag = "config"
+)]
+pub async fn list_output_sinks() -> Result<Json<ApiResponse<Vec<OutputSinkConfig>>>, StatusCode> {
+    let output = UserPreferencesManager::global().await.get_output().await;
+    Ok(Json(ApiResponse::success(output.sinks)))
+}
+
+/// 添加LED数据输出端，未指定ID时自动生成
+#[utoipa::path(
+    post,
+    path = "/api/v1/config/output/sinks",
+    request_body = OutputSinkConfig,
+    responses(
+        (status = 200, description = "添加输出端成功", body = ApiResponse<OutputSinkConfig>),
+        (status = 400, description = "输出端配置无效（INVALID_REQUEST）", body = ApiError),
+        (status = 500, description = "添加失败（CONFIG_UPDATE_FAILED）", body = ApiError),
+    ),
+    tag = "config"
+)]
+pub async fn add_output_sink(
+    Json(mut sink): Json<OutputSinkConfig>,
+) -> Result<Json<ApiResponse<OutputSinkConfig>>, ApiErrorResponse> {
+    if sink.id.is_empty() {
+        sink.id = uuid::Uuid::new_v4().simple().to_string();
+    }
+
+    let mut output = UserPreferencesManager::global().await.get_output().await;
+    output.sinks.push(sink.clone());
+    apply_output(output).await?;
+
+    Ok(Json(ApiResponse::success(sink)))
+}
+
+/// 删除LED数据输出端
+#[utoipa::path(
+    delete,
+    path = "/api/v1/config/output/sinks/{sink_id}",
+    params(
+        ("sink_id" = String, Path, description = "输出端ID")
+    ),
+    responses(
+        (status = 200, description = "删除输出端成功", body = ApiResponse<String>),
+        (status = 404, description = "输出端不存在（SINK_NOT_FOUND）", body = ApiError),
+        (status = 500, description = "删除失败（CONFIG_UPDATE_FAILED）", body = ApiError),
+    ),
+    tag = "config"
+)]
+pub async fn remove_output_sink(
+    Path(sink_id): Path<String>,
+) -> Result<Json<ApiResponse<String>>, ApiErrorResponse> {
+    let mut output = UserPreferencesManager::global().await.get_output().await;
+    let sink_count = output.sinks.len();
+    output.sinks.retain(|sink| sink.id != sink_id);
+    if output.sinks.len() == sink_count {
+        return Err(ApiError::response(
+            StatusCode::NOT_FOUND,
+            ApiError::SINK_NOT_FOUND,
+            &format!("Output sink {sink_id} not found"),
+        ));
+    }
+    apply_output(output).await?;
+
+    Ok(Json(ApiResponse::success(format!(
+        "Output sink {sink_id} removed"
+    ))))
+}
+
+/// 获取显示器预览缩放设置
+#[utoipa::path(
+    get,
+    path = "/api/v1/config/preview-scaling",
+    responses(
+        (status = 200, description = "获取预览缩放设置成功", body = ApiResponse<PreviewScalingSettings>),
+    ),
+    tag = "config"
+)]
+pub async fn get_preview_scaling() -> Result<Json<ApiResponse<PreviewScalingSettings>>, StatusCode>
+{
+    let preview_scaling = UserPreferencesManager::global()
+        .await
+        .get_preview_scaling()
+        .await;
+
+    Ok(Json(ApiResponse::success(PreviewScalingSettings {
+        intermediate_width: preview_scaling.intermediate_width,
+        intermediate_height: preview_scaling.intermediate_height,
+        intermediate_filter: preview_scaling.intermediate_filter,
+        final_filter: preview_scaling.final_filter,
+    })))
+}
+
+/// 更新显示器预览缩放设置
+///
+/// 中间尺寸越大、滤波器越平滑，预览画质越好，CPU 开销也越大
+#[utoipa::path(
+    put,
+    path = "/api/v1/config/preview-scaling",
+    request_body = PreviewScalingSettings,
+    responses(
+        (status = 200, description = "更新预览缩放设置成功", body = ApiResponse<String>),
+        (status = 400, description = "中间尺寸超出范围（INVALID_REQUEST）", body = ApiError),
+        (status = 500, description = "更新失败（CONFIG_UPDATE_FAILED）", body = ApiError),
+    ),
+    tag = "config"
+)]
+pub async fn update_preview_scaling(
+    Json(request): Json<PreviewScalingSettings>,
+) -> Result<Json<ApiResponse<String>>, ApiErrorResponse> {
+    let preview_scaling = crate::user_preferences::PreviewScalingPreferences {
+        intermediate_width: request.intermediate_width,
+        intermediate_height: request.intermediate_height,
+        intermediate_filter: request.intermediate_filter,
+        final_filter: request.final_filter,
+    };
+    if let Err(e) = crate::preview_scaling::validate_preview_scaling(&preview_scaling) {
+        log::warn!("Invalid preview scaling settings: {e}");
+        return Err(invalid_request(&e));
+    }
+
+    match UserPreferencesManager::global()
+        .await
+        .update_preview_scaling(preview_scaling)
+        .await
+    {
+        Ok(_) => Ok(Json(ApiResponse::success(
+            "Preview scaling updated successfully".to_string(),
+        ))),
+        Err(e) => {
+            log::error!("Failed to update preview scaling: {e}");
+            Err(config_update_failed(&e))
+        }
+    }
+}
+
+/// 获取状态变化Webhook设置
+#[utoipa::path(
+    get,
+    path = "/api/v1/config/webhook",
+    responses(
+        (status = 200, description = "获取Webhook设置成功", body = ApiResponse<WebhookSettings>),
+    ),
+    tag = "config"
+)]
+pub async fn get_webhook() -> Result<Json<ApiResponse<WebhookSettings>>, StatusCode> {
+    let webhook = UserPreferencesManager::global().await.get_webhook().await;
+
+    Ok(Json(ApiResponse::success(WebhookSettings {
+        url: webhook.url,
+        events: webhook.events,
+        timeout_ms: webhook.timeout_ms,
+        max_retries: webhook.max_retries,
+    })))
+}
+
+/// 更新状态变化Webhook设置
+///
+/// 氛围光开关或发送模式变化时向配置的地址POST JSON，包含变化前后的状态；
+/// 请求在后台发送，失败只记录日志
+#[utoipa::path(
+    put,
+    path = "/api/v1/config/webhook",
+    request_body = WebhookSettings,
+    responses(
+        (status = 200, description = "更新Webhook设置成功", body = ApiResponse<String>),
+        (status = 400, description = "地址、超时或重试次数无效（INVALID_REQUEST）", body = ApiError),
+        (status = 500, description = "更新失败（CONFIG_UPDATE_FAILED）", body = ApiError),
+    ),
+    tag = "config"
+)]
+pub async fn update_webhook(
+    Json(request): Json<WebhookSettings>,
+) -> Result<Json<ApiResponse<String>>, ApiErrorResponse> {
+    let webhook = crate::user_preferences::WebhookPreferences {
+        url: request.url.trim().to_string(),
+        events: request.events,
+        timeout_ms: request.timeout_ms,
+        max_retries: request.max_retries,
+    };
+    if let Err(e) = crate::webhook::validate_webhook(&webhook) {
+        log::warn!("Invalid webhook settings: {e}");
+        return Err(invalid_request(&e));
+    }
+
+    match UserPreferencesManager::global()
+        .await
+        .update_webhook(webhook)
+        .await
+    {
+        Ok(_) => Ok(Json(ApiResponse::success(
+            "Webhook updated successfully".to_string(),
+        ))),
+        Err(e) => {
+            log::error!("Failed to update webhook: {e}");
+            Err(config_update_failed(&e))
+        }
+    }
+}
+
+/// 更新全局颜色校准
+#[utoipa::path(
+    put,
+    path = "/api/v1/config/global-color-calibration",
+    request_body = UpdateGlobalColorCalibrationRequest,
+    responses(
+        (status = 200, description = "更新全局颜色校准成功", body = ApiResponse<String>),
+        (status = 400, description = "颜色校准参数无效（INVALID_REQUEST）", body = ApiError),
+        (status = 500, description = "更新失败（CONFIG_UPDATE_FAILED）", body = ApiError),
+    ),
+    tag = "config"
+)]
+pub async fn update_global_color_calibration(
+    Json(request): Json<UpdateGlobalColorCalibrationRequest>,
+) -> Result<Json<ApiResponse<String>>, ApiErrorResponse> {
+    log::info!(
+        "🎨 [COLOR_CALIBRATION] HTTP API request to update color calibration: r={:.3}, g={:.3}, b={:.3}, w={:.3}",
+        request.calibration.r,
+        request.calibration.g,
+        request.calibration.b,
+        request.calibration.w
+    );
+
+    if let Err(e) = request.calibration.validate() {
+        log::warn!("❌ [COLOR_CALIBRATION] Invalid color calibration: {e}");
+        return Err(invalid_request(&e));
+    }
+
+    let config_manager_v2 = ambient_light::ConfigManagerV2::global().await;
+    match config_manager_v2
+        .update_color_calibration(request.calibration)
+        .await
+    {
+        Ok(_) => {
+            log::info!(
+                "✅ [COLOR_CALIBRATION] HTTP API successfully updated color calibration: r={:.3}, g={:.3}, b={:.3}, w={:.3}",
+                request.calibration.r,
+                request.calibration.g,
+                request.calibration.b,
+                request.calibration.w
+            );
+            // 已保存的校准生效后，预览校准不再需要
+            ambient_light::LedColorsPublisher::global()
+                .await
+                .clear_preview_calibration()
+                .await;
+            Ok(Json(ApiResponse::success(
+                "Global color calibration updated successfully".to_string(),
+            )))
+        }
+        Err(e) => {
+            log::error!(
+                "❌ [COLOR_CALIBRATION] HTTP API failed to update color calibration: r={:.3}, g={:.3}, b={:.3}, w={:.3}, error: {}",
+                request.calibration.r,
+                request.calibration.g,
+                request.calibration.b,
+                request.calibration.w,
+                e
+            );
+            Err(config_update_failed(&e))
+        }
+    }
+}
+
+/// 应用全局颜色校准，并等待至少一帧使用新校准完成编码后再返回
+///
+/// 便于基于截图的校准工具获得确定的时序；超时仍未产生新帧（例如氛围光未运行）时返回 504
+#[utoipa::path(
+    put,
+    path = "/api/v1/config/global-color-calibration/confirm",
+    request_body = ConfirmGlobalColorCalibrationRequest,
+    responses(
+        (status = 200, description = "校准已应用并在新帧中生效", body = ApiResponse<ConfirmGlobalColorCalibrationResponse>),
+        (status = 400, description = "超时时间或颜色校准参数无效（INVALID_REQUEST）", body = ApiError),
+        (status = 500, description = "更新失败（CONFIG_UPDATE_FAILED）", body = ApiError),
+        (status = 504, description = "超时时间内没有使用新校准的帧（CALIBRATION_CONFIRM_TIMEOUT）", body = ApiError),
+    ),
+    tag = "config"
+)]
+pub async fn confirm_global_color_calibration(
+    Json(request): Json<ConfirmGlobalColorCalibrationRequest>,
+) -> Result<Json<ApiResponse<ConfirmGlobalColorCalibrationResponse>>, ApiErrorResponse> {
+    let timeout_ms = request
+        .timeout_ms
+        .unwrap_or(DEFAULT_CALIBRATION_CONFIRM_TIMEOUT_MS);
+    if timeout_ms == 0 || timeout_ms > MAX_CALIBRATION_CONFIRM_TIMEOUT_MS {
+        let message = format!(
+            "Invalid calibration confirm timeout {timeout_ms} ms, expected 1-{MAX_CALIBRATION_CONFIRM_TIMEOUT_MS}"
+        );
+        log::warn!("{message}");
+        return Err(invalid_request(message));
+    }
+    if let Err(e) = request.calibration.validate() {
+        log::warn!("❌ [COLOR_CALIBRATION] Invalid color calibration: {e}");
+        return Err(invalid_request(&e));
+    }
+
+    // 先订阅再应用，避免错过应用后的第一帧
+    let publisher = ambient_light::LedColorsPublisher::global().await;
+    let mut encoded_rx = publisher.subscribe_encoded_calibration();
+    let started_at = std::time::Instant::now();
+
+    if let Err(e) = ambient_light::ConfigManagerV2::global()
+        .await
+        .update_color_calibration(request.calibration)
+        .await
+    {
+        log::error!("❌ [COLOR_CALIBRATION] Failed to apply color calibration: {e}");
+        return Err(config_update_failed(&e));
+    }
+    publisher.clear_preview_calibration().await;
+
+    if ambient_light::wait_for_calibration_frame(
+        &mut encoded_rx,
+        request.calibration,
+        std::time::Duration::from_millis(timeout_ms),
+    )
+    .await
+    {
+        Ok(Json(ApiResponse::success(
+            ConfirmGlobalColorCalibrationResponse {
+                latency_ms: started_at.elapsed().as_millis() as u64,
+            },
+        )))
+    } else {
+        let message = format!("No frame encoded with the new calibration within {timeout_ms} ms");
+        log::warn!("⚠️ [COLOR_CALIBRATION] {message}");
+        Err(ApiError::response(
+            StatusCode::GATEWAY_TIMEOUT,
+            ApiError::CALIBRATION_CONFIRM_TIMEOUT,
+            &message,
+        ))
+    }
+}
+
+/// 获取当前生效的颜色校准曲线
+///
+/// 返回 LED 数据处理器对每个通道 0..=255 输入的输出映射，预览校准优先于已保存的校准
+#[utoipa::path(
+    get,
+    path = "/api/v1/config/calibration-curve",
+    responses(
+        (status = 200, description = "获取校准曲线成功", body = ApiResponse<CalibrationCurve>),
+    ),
+    tag = "config"
+)]
+pub async fn get_calibration_curve() -> Result<Json<ApiResponse<CalibrationCurve>>, StatusCode> {
+    let preview_calibration = ambient_light::LedColorsPublisher::global()
+        .await
+        .get_preview_calibration()
+        .await;
+    let calibration = match preview_calibration {
+        Some(calibration) => calibration,
+        None => {
+            ambient_light::ConfigManagerV2::global()
+                .await
+                .get_config()
+                .await
+                .color_calibration
+        }
+    };
+
+    Ok(Json(ApiResponse::success(
+        LedDataProcessor::calibration_curve(&calibration),
+    )))
+}
+
+/// 获取所有命名的颜色校准预设
+#[utoipa::path(
+    get,
+    path = "/api/v1/config/calibration-presets",
+    responses(
+        (status = 200, description = "获取校准预设成功（按名称索引）", body = ApiResponse<HashMap<String, ColorCalibration>>),
+    ),
+    tag = "config"
+)]
+pub async fn get_calibration_presets(
+) -> Result<Json<ApiResponse<HashMap<String, ColorCalibration>>>, StatusCode> {
+    let presets = ambient_light::ConfigManagerV2::global()
+        .await
+        .get_calibration_presets()
+        .await;
+    Ok(Json(ApiResponse::success(presets)))
+}
+
+/// 将当前的颜色校准保存为命名预设
+#[utoipa::path(
+    post,
+    path = "/api/v1/config/calibration-presets",
+    request_body = SaveCalibrationPresetRequest,
+    responses(
+        (status = 200, description = "保存校准预设成功", body = ApiResponse<String>),
+        (status = 400, description = "预设名称无效（INVALID_REQUEST）", body = ApiError),
+        (status = 500, description = "保存失败（CONFIG_UPDATE_FAILED）", body = ApiError),
+    ),
+    tag = "config"
+)]
+pub async fn save_calibration_preset(
+    Json(request): Json<SaveCalibrationPresetRequest>,
+) -> Result<Json<ApiResponse<String>>, ApiErrorResponse> {
+    if let Err(e) = ambient_light::validate_calibration_preset_name(&request.name) {
+        log::warn!("Invalid calibration preset name: {e}");
+        return Err(invalid_request(&e));
+    }
+
+    match ambient_light::ConfigManagerV2::global()
+        .await
+        .save_calibration_preset(&request.name)
+        .await
+    {
+        Ok(_) => Ok(Json(ApiResponse::success(format!(
+            "Calibration preset {} saved",
+            request.name
+        )))),
+        Err(e) => {
+            log::error!("Failed to save calibration preset {:?}: {e}", request.name);
+            Err(config_update_failed(&e))
+        }
+    }
+}
+
+/// 应用命名的颜色校准预设，替换当前的颜色校准并在下一帧生效，返回应用的校准
+#[utoipa::path(
+    post,
+    path = "/api/v1/config/calibration-presets/{name}/apply",
+    params(
+        ("name" = String, Path, description = "预设名称")
+    ),
+    responses(
+        (status = 200, description = "应用校准预设成功", body = ApiResponse<ColorCalibration>),
+        (status = 404, description = "预设不存在（PRESET_NOT_FOUND）", body = ApiError),
+        (status = 500, description = "应用失败（CONFIG_UPDATE_FAILED）", body = ApiError),
+    ),
+    tag = "config"
+)]
+pub async fn apply_calibration_preset(
+    Path(name): Path<String>,
+) -> Result<Json<ApiResponse<ColorCalibration>>, ApiErrorResponse> {
+    match ambient_light::ConfigManagerV2::global()
+        .await
+        .apply_calibration_preset(&name)
+        .await
+    {
+        Ok(Some(calibration)) => {
+            // 已保存的校准生效后，预览校准不再需要
+            ambient_light::LedColorsPublisher::global()
+                .await
+                .clear_preview_calibration()
+                .await;
+            Ok(Json(ApiResponse::success(calibration)))
+        }
+        Ok(None) => Err(preset_not_found(&name)),
+        Err(e) => {
+            log::error!("Failed to apply calibration preset {name:?}: {e}");
+            Err(config_update_failed(&e))
+        }
+    }
+}
+
+/// 删除命名的颜色校准预设
+#[utoipa::path(
+    delete,
+    path = "/api/v1/config/calibration-presets/{name}",
+    params(
+        ("name" = String, Path, description = "预设名称")
+    ),
+    responses(
+        (status = 200, description = "删除校准预设成功", body = ApiResponse<String>),
+        (status = 404, description = "预设不存在（PRESET_NOT_FOUND）", body = ApiError),
+        (status = 500, description = "删除失败（CONFIG_UPDATE_FAILED）", body = ApiError),
+    ),
+    tag = "config"
+)]
+pub async fn delete_calibration_preset(
+    Path(name): Path<String>,
+) -> Result<Json<ApiResponse<String>>, ApiErrorResponse> {
+    match ambient_light::ConfigManagerV2::global()
+        .await
+        .delete_calibration_preset(&name)
+        .await
+    {
+        Ok(true) => Ok(Json(ApiResponse::success(format!(
+            "Calibration preset {name} deleted"
+        )))),
+        Ok(false) => Err(preset_not_found(&name)),
+        Err(e) => {
+            log::error!("Failed to delete calibration preset {name:?}: {e}");
+            Err(config_update_failed(&e))
+        }
+    }
+}
+
+/// 恢复出厂设置：将灯带配置、颜色校准和用户偏好设置恢复为默认值
+///
+/// 原配置文件先备份为带时间戳的文件，恢复后重新生成映射、广播配置变化并重启环境光发布器
+#[utoipa::path(
+    post,
+    path = "/api/v1/config/reset",
+    request_body = ResetConfigRequest,
+    responses(
+        (status = 200, description = "已恢复默认配置，返回备份文件路径", body = ApiResponse<ResetConfigResult>),
+        (status = 400, description = "未确认恢复操作（INVALID_REQUEST）", body = ApiError),
+        (status = 500, description = "恢复失败（CONFIG_UPDATE_FAILED）", body = ApiError),
+    ),
+    tag = "config"
+)]
+pub async fn reset_config(
+    Json(request): Json<ResetConfigRequest>,
+) -> Result<Json<ApiResponse<ResetConfigResult>>, ApiErrorResponse> {
+    if !request.confirm {
+        log::warn!("Refusing to reset configuration without confirmation");
+        return Err(invalid_request(
+            "Resetting the configuration requires confirm = true",
+        ));
+    }
+
+    let config_backup = ambient_light::ConfigManagerV2::global()
+        .await
+        .reset_to_defaults()
+        .await
+        .map_err(|e| {
+            log::error!("Failed to reset LED strip configuration: {e}");
+            config_update_failed(&e)
+        })?;
+
+    let preferences_backup = UserPreferencesManager::global()
+        .await
+        .reset_to_defaults()
+        .await
+        .map_err(|e| {
+            log::error!("Failed to reset user preferences: {e}");
+            config_update_failed(&e)
+        })?;
+
+    // 默认配置已在 reset_to_defaults 返回前生效，此时重启采样使用的是新配置
+    if let Err(e) = ambient_light::LedColorsPublisher::global()
+        .await
+        .restart_ambient_light_publisher()
+        .await
+    {
+        log::error!("Failed to restart ambient light publisher after reset: {e}");
+        return Err(config_update_failed(&e));
+    }
+
+    Ok(Json(ApiResponse::success(ResetConfigResult {
+        config_backup: config_backup.map(|path| path.display().to_string()),
+        preferences_backup: preferences_backup.map(|path| path.display().to_string()),
+    })))
+}
+
+/// 更新用户偏好设置
+#[utoipa::path(
+    put,
+    path = "/api/v1/config/user-preferences",
+    request_body = UpdateUserPreferencesRequest,
+    responses(
+        (status = 200, description = "更新用户偏好设置成功", body = ApiResponse<String>),
+        (status = 400, description = "偏好设置取值无效（INVALID_REQUEST）", body = ApiError),
+        (status = 500, description = "更新失败（CONFIG_UPDATE_FAILED）", body = ApiError),
+    ),
+    tag = "config"
+)]
+pub async fn update_user_preferences(
+    Json(request): Json<UpdateUserPreferencesRequest>,
+) -> Result<Json<ApiResponse<String>>, ApiErrorResponse> {
+    if let Err(e) = request.preferences.validate() {
+        log::warn!("Invalid user preferences: {e}");
+        return Err(invalid_request(&e));
+    }
+
+    let preferences_manager = UserPreferencesManager::global().await;
+    match preferences_manager
+        .update_preferences(request.preferences)
+        .await
+    {
+        Ok(_) => Ok(Json(ApiResponse::success(
+            "User preferences updated successfully".to_string(),
+        ))),
+        Err(e) => {
+            log::error!("Failed to update user preferences: {e}");
+            Err(config_update_failed(&e))
+        }
+    }
+}
+
+/// 更新窗口偏好设置
+#[utoipa::path(
+    put,
+    path = "/api/v1/config/window-preferences",
+    request_body = UpdateWindowPreferencesRequest,
+    responses(
+        (status = 200, description = "更新窗口偏好设置成功", body = ApiResponse<String>),
+        (status = 400, description = "窗口尺寸或位置无效（INVALID_REQUEST）", body = ApiError),
+        (status = 500, description = "更新失败（CONFIG_UPDATE_FAILED）", body = ApiError),
+    ),
+    tag = "config"
+)]
+pub async fn update_window_preferences(
+    Json(request): Json<UpdateWindowPreferencesRequest>,
+) -> Result<Json<ApiResponse<String>>, ApiErrorResponse> {
+    if let Err(e) = request.window_prefs.validate() {
+        log::warn!("Invalid window preferences: {e}");
+        return Err(invalid_request(&e));
+    }
+
+    let preferences_manager = UserPreferencesManager::global().await;
+    match preferences_manager
+        .update_window_preferences(request.window_prefs)
+        .await
+    {
+        Ok(_) => Ok(Json(ApiResponse::success(
+            "Window preferences updated successfully".to_string(),
+        ))),
+        Err(e) => {
+            log::error!("Failed to update window preferences: {e}");
+            Err(config_update_failed(&e))
+        }
+    }
+}
+
+/// 更新UI偏好设置
+#[utoipa::path(
+    put,
+    path = "/api/v1/config/ui-preferences",
+    request_body = UpdateUIPreferencesRequest,
+    responses(
+        (status = 200, description = "更新UI偏好设置成功", body = ApiResponse<String>),
+        (status = 400, description = "视图缩放超出范围（INVALID_REQUEST）", body = ApiError),
+        (status = 500, description = "更新失败（CONFIG_UPDATE_FAILED）", body = ApiError),
+    ),
+    tag = "config"
+)]
+pub async fn update_ui_preferences(
+    Json(request): Json<UpdateUIPreferencesRequest>,
+) -> Result<Json<ApiResponse<String>>, ApiErrorResponse> {
+    if let Err(e) = crate::user_preferences::validate_view_scale(request.ui_prefs.view_scale) {
+        log::warn!("Invalid view scale: {e}");
+        return Err(invalid_request(&e));
+    }
+
+    let preferences_manager = UserPreferencesManager::global().await;
+    match preferences_manager
+        .update_ui_preferences(request.ui_prefs)
+        .await
+    {
+        Ok(_) => Ok(Json(ApiResponse::success(
+            "UI preferences updated successfully".to_string(),
+        ))),
+        Err(e) => {
+            log::error!("Failed to update UI preferences: {e}");
+            Err(config_update_failed(&e))
         }
     }
 }
@@ -645,6 +2534,8 @@ pub fn create_routes() -> Router<AppState> {
         .route("/led-strips/length", put(update_led_strip_length))
         .route("/led-strips/type", put(update_led_strip_type))
         .route("/led-strips/reverse", put(reverse_led_strip))
+        .route("/led-strips/enabled", put(update_led_strip_enabled))
+        .route("/reset", post(reset_config))
         .route("/user-preferences", get(get_user_preferences))
         .route("/user-preferences", put(update_user_preferences))
         .route("/window-preferences", put(update_window_preferences))
@@ -653,10 +2544,101 @@ pub fn create_routes() -> Router<AppState> {
         .route("/theme", put(update_theme))
         .route("/view-scale", get(get_view_scale))
         .route("/view-scale", put(update_view_scale))
+        .route(
+            "/sample-points-per-led",
+            get(get_sample_points_per_led).put(update_sample_points_per_led),
+        )
+        .route("/black-level", get(get_black_level).put(update_black_level))
+        .route(
+            "/interpolation-strength",
+            get(get_interpolation_strength).put(update_interpolation_strength),
+        )
+        .route(
+            "/averaging-color-space",
+            get(get_averaging_color_space).put(update_averaging_color_space),
+        )
+        .route(
+            "/bilinear-sampling",
+            get(get_bilinear_sampling).put(update_bilinear_sampling),
+        )
+        .route(
+            "/vertical-gradient-sampling",
+            get(get_vertical_gradient_sampling).put(update_vertical_gradient_sampling),
+        )
+        .route(
+            "/frame-sync-mode",
+            get(get_frame_sync_mode).put(update_frame_sync_mode),
+        )
+        .route(
+            "/color-count-policy",
+            get(get_color_count_policy).put(update_color_count_policy),
+        )
+        .route(
+            "/sampling-strategy",
+            get(get_sampling_strategy).put(update_sampling_strategy),
+        )
+        .route(
+            "/display-sleep-policy",
+            get(get_display_sleep_policy).put(update_display_sleep_policy),
+        )
+        .route(
+            "/fullscreen-capture",
+            get(get_fullscreen_capture).put(update_fullscreen_capture),
+        )
+        .route("/capture-backends", get(get_capture_backends))
+        .route("/capture-backend", put(update_capture_backend))
+        .route(
+            "/auto-brightness",
+            get(get_auto_brightness).put(update_auto_brightness),
+        )
+        .route(
+            "/cursor-focus",
+            get(get_cursor_focus).put(update_cursor_focus),
+        )
+        .route(
+            "/color-temperature-shift",
+            get(get_color_temperature_shift).put(update_color_temperature_shift),
+        )
+        .route(
+            "/palette-quantization",
+            get(get_palette_quantization).put(update_palette_quantization),
+        )
+        .route(
+            "/capture-failure",
+            get(get_capture_failure).put(update_capture_failure),
+        )
+        .route("/output", get(get_output).put(update_output))
+        .route(
+            "/output/sinks",
+            get(list_output_sinks).post(add_output_sink),
+        )
+        .route("/output/sinks/:sink_id", delete(remove_output_sink))
+        .route(
+            "/preview-scaling",
+            get(get_preview_scaling).put(update_preview_scaling),
+        )
+        .route("/webhook", get(get_webhook).put(update_webhook))
         .route(
             "/global-color-calibration",
             put(update_global_color_calibration),
         )
+        .route(
+            "/global-color-calibration/confirm",
+            put(confirm_global_color_calibration),
+        )
+        .route("/calibration-curve", get(get_calibration_curve))
+        .route(
+            "/calibration-presets",
+            get(get_calibration_presets).post(save_calibration_preset),
+        )
+        .route(
+            "/calibration-presets/:name",
+            delete(delete_calibration_preset),
+        )
+        .route(
+            "/calibration-presets/:name/apply",
+            post(apply_calibration_preset),
+        )
         .route(
             "/night-mode-theme-enabled",
             get(get_night_mode_theme_enabled),
@@ -669,3 +2651,43 @@ pub fn create_routes() -> Router<AppState> {
 }
 
 // 已移除 v2 路由构建函数，统一使用 v1 路径 + v2 语义的 create_routes()
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_find_strip_mut_reports_missing_strip() {
+        let mut config = LedStripConfigGroupV2::new();
+        config.strips.push(LedStripConfigV2 {
+            index: 0,
+            border: Border::Top,
+            display_internal_id: "display-a".to_string(),
+            len: 30,
+            led_type: LedType::WS2812B,
+            reversed: false,
+            hardware_offset: None,
+            led_positions: None,
+            zone_reduction: crate::ambient_light::ZoneReduction::Mean,
+            color_order: crate::ambient_light::ColorOrder::default(),
+            sample_shape: crate::ambient_light::SampleShape::Grid,
+            sample_inset: 0,
+            start_trim_percent: 0.0,
+            end_trim_percent: 0.0,
+            enabled: true,
+            chain_position: None,
+        });
+
+        assert!(find_strip_mut(&mut config, "display-a", 1, Border::Top).is_ok());
+
+        let (status, Json(error)) =
+            find_strip_mut(&mut config, "display-a", 1, Border::Bottom).unwrap_err();
+        assert_eq!(status, StatusCode::NOT_FOUND);
+        assert_eq!(error.code, ApiError::STRIP_NOT_FOUND);
+
+        let (status, Json(error)) =
+            find_strip_mut(&mut config, "display-b", 2, Border::Top).unwrap_err();
+        assert_eq!(status, StatusCode::NOT_FOUND);
+        assert_eq!(error.code, ApiError::STRIP_NOT_FOUND);
+    }
+}
@@ -1,19 +1,34 @@
 use axum::{
+    extract::{Path, Query},
     http::StatusCode,
     response::Json,
-    routing::{get, put},
+    routing::{get, post, put},
     Router,
 };
-use serde::Deserialize;
+use serde::{Deserialize, Serialize};
+use std::time::Duration;
 use utoipa::ToSchema;
 
 use crate::{
     ambient_light_state::{AmbientLightState, AmbientLightStateManager},
     auto_start::AutoStartManager,
     http_server::{ApiResponse, AppState},
-    rpc::{BoardInfo, UdpRpc},
+    led_data_sender::DEFAULT_PROTOCOL_VERSION,
+    led_test_effects::LedTestEffectManager,
+    logger::{self, LogEntry},
+    rpc::{
+        BoardConfig, BoardConfigGroup, BoardConfigManager, BoardHealth, BoardInfo,
+        BoardSelfCheckReport, UdpRpc,
+    },
 };
 
+/// 设备板健康检查等待回应的超时时间
+const BOARD_HEALTH_TIMEOUT: Duration = Duration::from_secs(1);
+
+fn default_protocol_version() -> u8 {
+    DEFAULT_PROTOCOL_VERSION
+}
+
 /// 自动启动设置请求
 #[derive(Deserialize, ToSchema)]
 pub struct SetAutoStartRequest {
@@ -28,6 +43,53 @@ pub struct SetAmbientLightStateRequest {
     pub enabled: bool,
 }
 
+/// 设备板覆盖配置设置请求
+#[derive(Deserialize, ToSchema)]
+pub struct SetBoardConfigRequest {
+    /// 设备全名（mDNS fullname）
+    pub fullname: String,
+    /// UDP端口覆盖（为空时使用设备公布的端口）
+    #[serde(default)]
+    pub port: Option<u16>,
+    /// LED数据包协议版本（默认2，即0x02帧格式）
+    #[serde(default = "default_protocol_version")]
+    pub protocol_version: u8,
+    /// 设备上线后的预热时间（毫秒），期间丢弃LED数据，默认0（不预热）
+    #[serde(default)]
+    pub warmup_ms: u64,
+    /// 最小发送间隔（毫秒），用于处理不了高帧率的设备，默认0（不限制）
+    #[serde(default)]
+    pub min_send_interval_ms: u64,
+    /// 数据包命令字节（为空时使用协议默认值，版本2为0x02），用于兼容社区固件
+    #[serde(default)]
+    pub command_byte: Option<u8>,
+    /// 命令字节之后的通道/灯带选择字节（为空时不插入）
+    #[serde(default)]
+    pub channel: Option<u8>,
+    /// 接在该设备上的灯带序列号，为空时视为该设备驱动全部灯带
+    #[serde(default)]
+    pub strips: Vec<usize>,
+}
+
+/// 日志查询参数
+#[derive(Deserialize, ToSchema)]
+pub struct LogsQuery {
+    /// 返回最近的日志条数，默认200
+    #[serde(default = "default_log_limit")]
+    pub limit: usize,
+}
+
+fn default_log_limit() -> usize {
+    200
+}
+
+/// 日志捕获级别
+#[derive(Serialize, Deserialize, ToSchema)]
+pub struct LogLevelRequest {
+    /// 日志级别：off/error/warn/info/debug/trace
+    pub level: String,
+}
+
 /// 获取设备板列表
 #[utoipa::path(
     get,
@@ -52,6 +114,237 @@ pub async fn get_boards() -> Result<Json<ApiResponse<Vec<BoardInfo>>>, StatusCod
     }
 }
 
+/// 让指定设备板的灯带闪烁识别图案，数秒后自动熄灭
+#[utoipa::path(
+    post,
+    path = "/api/v1/device/boards/{address}/identify",
+    params(
+        ("address" = String, Path, description = "设备板地址（IP:端口）")
+    ),
+    responses(
+        (status = 200, description = "识别闪烁已启动", body = ApiResponse<String>),
+        (status = 400, description = "设备板地址无效", body = ApiResponse<String>),
+        (status = 500, description = "启动失败", body = ApiResponse<String>),
+    ),
+    tag = "device"
+)]
+pub async fn identify_board(
+    Path(address): Path<String>,
+) -> Result<Json<ApiResponse<String>>, StatusCode> {
+    let target_addr = match address.parse() {
+        Ok(addr) => addr,
+        Err(e) => {
+            log::warn!("Invalid board address {address}: {e}");
+            return Err(StatusCode::BAD_REQUEST);
+        }
+    };
+
+    let effect_manager = LedTestEffectManager::global().await;
+    match effect_manager.identify_board(target_addr).await {
+        Ok(_) => Ok(Json(ApiResponse::success(
+            "Board identify started".to_string(),
+        ))),
+        Err(e) => {
+            log::error!("Failed to identify board {address}: {e}");
+            Err(StatusCode::INTERNAL_SERVER_ERROR)
+        }
+    }
+}
+
+/// 检查指定设备板是否正常工作：发送一个不改变LED的测试数据包并测量 ping 往返延迟
+///
+/// 与 `/health` 只表示应用本身在运行不同，此接口用于确认控制器能收到数据
+#[utoipa::path(
+    get,
+    path = "/api/v1/device/boards/{address}/health",
+    params(
+        ("address" = String, Path, description = "设备板地址（IP:端口）")
+    ),
+    responses(
+        (status = 200, description = "健康检查完成（结果见 healthy 字段）", body = ApiResponse<BoardHealth>),
+        (status = 400, description = "设备板地址无效", body = ApiResponse<String>),
+        (status = 404, description = "设备板不存在", body = ApiResponse<String>),
+        (status = 500, description = "健康检查失败", body = ApiResponse<String>),
+    ),
+    tag = "device"
+)]
+pub async fn get_board_health(
+    Path(address): Path<String>,
+) -> Result<Json<ApiResponse<BoardHealth>>, StatusCode> {
+    let target_addr = match address.parse() {
+        Ok(addr) => addr,
+        Err(e) => {
+            log::warn!("Invalid board address {address}: {e}");
+            return Err(StatusCode::BAD_REQUEST);
+        }
+    };
+
+    let udp_rpc = UdpRpc::global().await.as_ref().map_err(|e| {
+        log::error!("Failed to get UDP RPC: {e}");
+        StatusCode::INTERNAL_SERVER_ERROR
+    })?;
+
+    match udp_rpc
+        .check_board_health(target_addr, BOARD_HEALTH_TIMEOUT)
+        .await
+    {
+        Ok(Some(health)) => Ok(Json(ApiResponse::success(health))),
+        Ok(None) => {
+            log::warn!("Board {address} not found");
+            Err(StatusCode::NOT_FOUND)
+        }
+        Err(e) => {
+            log::error!("Failed to check health of board {address}: {e}");
+            Err(StatusCode::INTERNAL_SERVER_ERROR)
+        }
+    }
+}
+
+/// 获取启动时设备板连通性自检的结果，用于界面显示设置健康状态
+///
+/// 自检在应用启动后异步执行，完成前返回空
+#[utoipa::path(
+    get,
+    path = "/api/v1/device/boards/self-check",
+    responses(
+        (status = 200, description = "获取自检结果成功（未完成时为空）", body = ApiResponse<Option<BoardSelfCheckReport>>),
+        (status = 500, description = "获取自检结果失败", body = ApiResponse<String>),
+    ),
+    tag = "device"
+)]
+pub async fn get_board_self_check(
+) -> Result<Json<ApiResponse<Option<BoardSelfCheckReport>>>, StatusCode> {
+    let udp_rpc = UdpRpc::global().await.as_ref().map_err(|e| {
+        log::error!("Failed to get UDP RPC: {e}");
+        StatusCode::INTERNAL_SERVER_ERROR
+    })?;
+
+    Ok(Json(ApiResponse::success(
+        udp_rpc.get_self_check_report().await,
+    )))
+}
+
+/// 获取最近捕获的日志（含级别和时间戳），用于导出问题日志
+#[utoipa::path(
+    get,
+    path = "/api/v1/device/logs",
+    params(
+        ("limit" = Option<usize>, Query, description = "返回最近的日志条数，默认200")
+    ),
+    responses(
+        (status = 200, description = "获取日志成功", body = ApiResponse<Vec<LogEntry>>),
+    ),
+    tag = "device"
+)]
+pub async fn get_logs(Query(query): Query<LogsQuery>) -> Json<ApiResponse<Vec<LogEntry>>> {
+    Json(ApiResponse::success(logger::recent_logs(query.limit)))
+}
+
+/// 获取当前的日志捕获级别
+#[utoipa::path(
+    get,
+    path = "/api/v1/device/logs/level",
+    responses(
+        (status = 200, description = "获取日志级别成功", body = ApiResponse<LogLevelRequest>),
+    ),
+    tag = "device"
+)]
+pub async fn get_log_level() -> Json<ApiResponse<LogLevelRequest>> {
+    Json(ApiResponse::success(LogLevelRequest {
+        level: logger::get_level().to_string().to_lowercase(),
+    }))
+}
+
+/// 在运行时设置日志捕获级别
+#[utoipa::path(
+    put,
+    path = "/api/v1/device/logs/level",
+    request_body = LogLevelRequest,
+    responses(
+        (status = 200, description = "设置日志级别成功", body = ApiResponse<String>),
+        (status = 400, description = "日志级别无效", body = ApiResponse<String>),
+    ),
+    tag = "device"
+)]
+pub async fn set_log_level(
+    Json(request): Json<LogLevelRequest>,
+) -> Result<Json<ApiResponse<String>>, StatusCode> {
+    match logger::set_level(&request.level) {
+        Ok(level) => Ok(Json(ApiResponse::success(format!(
+            "Log level set to {}",
+            level.to_string().to_lowercase()
+        )))),
+        Err(e) => {
+            log::warn!("Failed to set log level: {e}");
+            Err(StatusCode::BAD_REQUEST)
+        }
+    }
+}
+
+/// 获取设备板覆盖配置
+#[utoipa::path(
+    get,
+    path = "/api/v1/device/board-configs",
+    responses(
+        (status = 200, description = "获取设备板覆盖配置成功", body = ApiResponse<BoardConfigGroup>),
+    ),
+    tag = "device"
+)]
+pub async fn get_board_configs() -> Result<Json<ApiResponse<BoardConfigGroup>>, StatusCode> {
+    let config_manager = BoardConfigManager::global().await;
+    let config = config_manager.get_config().await;
+    Ok(Json(ApiResponse::success(config)))
+}
+
+/// 设置设备板覆盖配置（UDP端口、协议版本、预热时间、最小发送间隔、数据包头、所接灯带）
+#[utoipa::path(
+    put,
+    path = "/api/v1/device/board-config",
+    request_body = SetBoardConfigRequest,
+    responses(
+        (status = 200, description = "设置设备板覆盖配置成功", body = ApiResponse<String>),
+        (status = 400, description = "配置无效", body = ApiResponse<String>),
+        (status = 500, description = "设置失败", body = ApiResponse<String>),
+    ),
+    tag = "device"
+)]
+pub async fn set_board_config(
+    Json(request): Json<SetBoardConfigRequest>,
+) -> Result<Json<ApiResponse<String>>, StatusCode> {
+    let config = BoardConfig {
+        port: request.port,
+        protocol_version: request.protocol_version,
+        warmup_ms: request.warmup_ms,
+        min_send_interval_ms: request.min_send_interval_ms,
+        command_byte: request.command_byte,
+        channel: request.channel,
+        strips: request.strips,
+    };
+
+    if let Err(e) = config.validate() {
+        log::warn!("Invalid board config for {}: {e}", request.fullname);
+        return Err(StatusCode::BAD_REQUEST);
+    }
+
+    let udp_rpc = match UdpRpc::global().await {
+        Ok(udp_rpc) => udp_rpc,
+        Err(e) => {
+            log::error!("Failed to get UDP RPC: {e}");
+            return Err(StatusCode::INTERNAL_SERVER_ERROR);
+        }
+    };
+
+    match udp_rpc.apply_board_config(&request.fullname, config).await {
+        Ok(_) => Ok(Json(ApiResponse::success(
+            "Board config updated successfully".to_string(),
+        ))),
+        Err(e) => {
+            log::error!("Failed to update board config: {e}");
+            Err(StatusCode::INTERNAL_SERVER_ERROR)
+        }
+    }
+}
+
 /// 获取自动启动状态
 #[utoipa::path(
     get,
@@ -149,8 +442,15 @@ pub async fn set_ambient_light_state(
 pub fn create_routes() -> Router<AppState> {
     Router::new()
         .route("/boards", get(get_boards))
+        .route("/boards/:address/identify", post(identify_board))
+        .route("/boards/self-check", get(get_board_self_check))
+        .route("/boards/:address/health", get(get_board_health))
+        .route("/board-configs", get(get_board_configs))
+        .route("/board-config", put(set_board_config))
         .route("/auto-start", get(get_auto_start_status))
         .route("/auto-start", put(set_auto_start_status))
         .route("/ambient-light-state", get(get_ambient_light_state))
         .route("/ambient-light-state", put(set_ambient_light_state))
+        .route("/logs", get(get_logs))
+        .route("/logs/level", get(get_log_level).put(set_log_level))
 }
@@ -2,17 +2,22 @@ use axum::{
     extract::{Path, Query},
     http::StatusCode,
     response::Json,
-    routing::get,
+    routing::{get, post, put},
     Router,
 };
-use serde::Deserialize;
+use serde::{Deserialize, Serialize};
+use std::collections::BTreeMap;
 use utoipa::ToSchema;
 
 use crate::{
-    ambient_light::LedStripConfig,
+    ambient_light::{
+        BorderMask, CaptureFormat, ConfigManagerV2, DisplayBinding, DisplayWinding,
+        LedColorsPublisher, LedStripConfig, PublisherAdapter, StripSpan,
+    },
     display::{DisplayConfig, DisplayManager, DisplayState},
     http_server::{ApiResponse, AppState},
     led_color::LedColor,
+    screenshot::{LedSampleRegion, SampleCrop},
     DisplayInfoWrapper, ScreenshotManager,
 };
 
@@ -23,6 +28,82 @@ pub struct DisplayColorsQuery {
     pub led_configs: Option<String>,
 }
 
+/// 显示器镜像来源设置请求
+#[derive(Deserialize, ToSchema)]
+pub struct SetMirrorSourceRequest {
+    /// 来源显示器ID（为空时取消镜像，恢复采样自身画面）
+    pub source_display_id: Option<u32>,
+}
+
+/// 显示器缩放比例覆盖设置请求
+#[derive(Deserialize, ToSchema)]
+pub struct SetScaleFactorOverrideRequest {
+    /// 手动缩放比例（0.5-4），采样时代替自动检测的值
+    pub scale_factor: f32,
+}
+
+/// 显示器采集帧率设置请求
+#[derive(Deserialize, ToSchema)]
+pub struct SetDisplayFrameRateRequest {
+    /// 采集帧率（1-240 Hz），代替按显示器刷新率采集
+    pub frame_rate: f32,
+}
+
+/// 显示器采集帧率
+#[derive(Serialize, ToSchema)]
+pub struct DisplayFrameRate {
+    /// 配置的帧率覆盖（按显示器刷新率采集时为空）
+    pub frame_rate: Option<f32>,
+    /// 实际使用的采集帧率（Hz）
+    pub effective_frame_rate: f32,
+}
+
+/// 显示器当前使用的采样区域
+#[derive(Serialize, ToSchema)]
+pub struct DisplaySampleRegions {
+    /// 截图宽度（像素）
+    pub width: u32,
+    /// 截图高度（像素）
+    pub height: u32,
+    /// 按灯带序列号索引，每个LED一个采样区域（索引0为灯带物理起点）
+    pub strips: BTreeMap<usize, Vec<LedSampleRegion>>,
+}
+
+/// 灯带绑定请求
+#[derive(Deserialize, ToSchema)]
+pub struct BindStripsRequest {
+    /// 绑定到该显示器的灯带序列号
+    pub strip_indices: Vec<usize>,
+}
+
+/// 跨屏灯带设置请求
+#[derive(Deserialize, ToSchema)]
+pub struct SetStripSpanRequest {
+    /// 灯带经过的显示器内部ID（至少两个，须包含灯带所属的显示器；为空时取消跨屏）
+    pub display_internal_ids: Option<Vec<String>>,
+}
+
+/// 显示器灯带走线方向设置请求
+#[derive(Deserialize, ToSchema)]
+pub struct SetDisplayWindingRequest {
+    /// 走线方向（从屏幕正面看）
+    pub winding: DisplayWinding,
+}
+
+/// 显示器启用边设置请求
+#[derive(Deserialize, ToSchema)]
+pub struct SetDisplayBorderMaskRequest {
+    /// 启用的边，禁用边上的灯带不采样也不占用串联数据中的位置
+    pub border_mask: BorderMask,
+}
+
+/// 显示器截图像素字节顺序设置请求
+#[derive(Deserialize, ToSchema)]
+pub struct SetDisplayCaptureFormatRequest {
+    /// 像素字节顺序（BGRA / RGBA）
+    pub capture_format: CaptureFormat,
+}
+
 /// 获取所有显示器状态
 #[utoipa::path(
     get,
@@ -86,6 +167,124 @@ pub async fn get_display_configs() -> Result<Json<ApiResponse<Vec<DisplayConfig>
     Ok(Json(ApiResponse::success(configs)))
 }
 
+/// 获取所有已注册显示器（含当前未连接的）的稳定ID及绑定的灯带
+#[utoipa::path(
+    get,
+    path = "/api/v1/display/bindings",
+    responses(
+        (status = 200, description = "获取显示器绑定成功", body = ApiResponse<Vec<DisplayBinding>>),
+    ),
+    tag = "display"
+)]
+pub async fn get_display_bindings() -> Result<Json<ApiResponse<Vec<DisplayBinding>>>, StatusCode> {
+    let bindings = ConfigManagerV2::global().await.get_display_bindings().await;
+    Ok(Json(ApiResponse::success(bindings)))
+}
+
+/// 将一组灯带手动绑定到指定显示器（按稳定的内部ID），显示器断开后绑定保留
+#[utoipa::path(
+    put,
+    path = "/api/v1/display/bindings/{internal_id}",
+    params(
+        ("internal_id" = String, Path, description = "显示器内部ID")
+    ),
+    request_body = BindStripsRequest,
+    responses(
+        (status = 200, description = "绑定灯带成功", body = ApiResponse<String>),
+        (status = 400, description = "灯带不存在", body = ApiResponse<String>),
+        (status = 404, description = "显示器未注册", body = ApiResponse<String>),
+    ),
+    tag = "display"
+)]
+pub async fn bind_strips_to_display(
+    Path(internal_id): Path<String>,
+    Json(request): Json<BindStripsRequest>,
+) -> Result<Json<ApiResponse<String>>, StatusCode> {
+    let config_manager = ConfigManagerV2::global().await;
+    if config_manager
+        .get_display_registry()
+        .find_display_by_internal_id(&internal_id)
+        .await
+        .is_none()
+    {
+        log::warn!("Display {internal_id} is not registered");
+        return Err(StatusCode::NOT_FOUND);
+    }
+
+    match config_manager
+        .bind_strips_to_display(&internal_id, &request.strip_indices)
+        .await
+    {
+        Ok(_) => Ok(Json(ApiResponse::success(
+            "Strips bound successfully".to_string(),
+        ))),
+        Err(e) => {
+            log::warn!("Failed to bind strips to display {internal_id}: {e}");
+            Err(StatusCode::BAD_REQUEST)
+        }
+    }
+}
+
+/// 获取所有跨屏灯带设置
+#[utoipa::path(
+    get,
+    path = "/api/v1/display/spans",
+    responses(
+        (status = 200, description = "获取跨屏灯带成功", body = ApiResponse<Vec<StripSpan>>),
+    ),
+    tag = "display"
+)]
+pub async fn get_strip_spans() -> Result<Json<ApiResponse<Vec<StripSpan>>>, StatusCode> {
+    let spans = ConfigManagerV2::global().await.get_strip_spans().await;
+    Ok(Json(ApiResponse::success(spans)))
+}
+
+/// 设置上/下边灯带跨越的并排显示器，灯带沿这些显示器合并后的边缘采样
+#[utoipa::path(
+    put,
+    path = "/api/v1/display/spans/{strip_index}",
+    params(
+        ("strip_index" = usize, Path, description = "灯带序列号")
+    ),
+    request_body = SetStripSpanRequest,
+    responses(
+        (status = 200, description = "设置跨屏灯带成功", body = ApiResponse<String>),
+        (status = 400, description = "灯带不存在、不在上/下边或显示器不足两个", body = ApiResponse<String>),
+        (status = 404, description = "显示器未注册", body = ApiResponse<String>),
+    ),
+    tag = "display"
+)]
+pub async fn set_strip_span(
+    Path(strip_index): Path<usize>,
+    Json(request): Json<SetStripSpanRequest>,
+) -> Result<Json<ApiResponse<String>>, StatusCode> {
+    let config_manager = ConfigManagerV2::global().await;
+    let registry = config_manager.get_display_registry();
+    for internal_id in request.display_internal_ids.iter().flatten() {
+        if registry
+            .find_display_by_internal_id(internal_id)
+            .await
+            .is_none()
+        {
+            log::warn!("Display {internal_id} is not registered");
+            return Err(StatusCode::NOT_FOUND);
+        }
+    }
+
+    match config_manager
+        .set_strip_span(strip_index, request.display_internal_ids)
+        .await
+    {
+        Ok(_) => Ok(Json(ApiResponse::success(
+            "Strip span updated successfully".to_string(),
+        ))),
+        Err(e) => {
+            log::warn!("Failed to set span for strip {strip_index}: {e}");
+            Err(StatusCode::BAD_REQUEST)
+        }
+    }
+}
+
 /// 获取指定显示器的颜色
 #[utoipa::path(
     get,
@@ -147,11 +346,777 @@ pub async fn get_display_colors(
     }
 }
 
+/// 获取各显示器灯带当前使用的采样区域（截图像素坐标），供预览叠加显示
+#[utoipa::path(
+    get,
+    path = "/api/v1/display/sample-regions",
+    responses(
+        (status = 200, description = "获取采样区域成功（按显示器ID和灯带序列号索引）", body = ApiResponse<BTreeMap<u32, DisplaySampleRegions>>),
+        (status = 500, description = "读取灯带配置失败", body = ApiResponse<String>),
+    ),
+    tag = "display"
+)]
+pub async fn get_sample_regions(
+) -> Result<Json<ApiResponse<BTreeMap<u32, DisplaySampleRegions>>>, StatusCode> {
+    let config_manager = ConfigManagerV2::global().await;
+    let v2_config = config_manager.get_config().await;
+    let adapter = PublisherAdapter::new(config_manager.get_display_registry());
+    let strips = match adapter.convert_v2_to_v1_config(&v2_config).await {
+        Ok(config) => config.strips,
+        Err(e) => {
+            log::error!("Failed to resolve LED strip configs for sample regions: {e}");
+            return Err(StatusCode::INTERNAL_SERVER_ERROR);
+        }
+    };
+
+    let screenshot_manager = ScreenshotManager::global().await;
+    let channels = screenshot_manager.channels.read().await;
+
+    let mut result = BTreeMap::new();
+    for (display_id, rx) in channels.iter() {
+        // 使用最新一帧截图，采样参数（旋转、裁剪、每LED采样点数等）与实际采样一致
+        let screenshot = rx.read().await.borrow().clone();
+
+        let strips = strips
+            .iter()
+            .filter(|strip| strip.display_id == *display_id)
+            .map(|strip| {
+                let mut regions = screenshot.get_sample_regions(strip);
+                if strip.reversed {
+                    regions.reverse();
+                }
+                (strip.index, regions)
+            })
+            .collect();
+
+        result.insert(
+            *display_id,
+            DisplaySampleRegions {
+                width: screenshot.width,
+                height: screenshot.height,
+                strips,
+            },
+        );
+    }
+
+    Ok(Json(ApiResponse::success(result)))
+}
+
+/// 用指定显示器的最新一帧截图立即采样一次，返回各灯带的颜色（按灯带序列号索引，索引0为灯带物理起点）
+///
+/// 不经过持续运行的采样管线，也不发送到硬件，便于用已知的画面内容验证采样结果
+#[utoipa::path(
+    post,
+    path = "/api/v1/display/{display_id}/sample-now",
+    params(
+        ("display_id" = u32, Path, description = "显示器ID")
+    ),
+    responses(
+        (status = 200, description = "采样成功", body = ApiResponse<BTreeMap<usize, Vec<LedColor>>>),
+        (status = 404, description = "显示器未找到", body = ApiResponse<String>),
+        (status = 500, description = "读取灯带配置失败", body = ApiResponse<String>),
+        (status = 503, description = "尚未采集到截图", body = ApiResponse<String>),
+    ),
+    tag = "display"
+)]
+pub async fn sample_display_now(
+    Path(display_id): Path<u32>,
+) -> Result<Json<ApiResponse<BTreeMap<usize, Vec<LedColor>>>>, StatusCode> {
+    let screenshot_manager = ScreenshotManager::global().await;
+    let channels = screenshot_manager.channels.read().await;
+    let Some(rx) = channels.get(&display_id) else {
+        return Err(StatusCode::NOT_FOUND);
+    };
+    let screenshot = rx.read().await.borrow().clone();
+    drop(channels);
+
+    // 采集任务启动前通道中只有空白占位截图
+    if screenshot.bytes.is_empty() || screenshot.width == 0 || screenshot.height == 0 {
+        log::debug!("No screenshot for display {display_id} yet");
+        return Err(StatusCode::SERVICE_UNAVAILABLE);
+    }
+
+    let config_manager = ConfigManagerV2::global().await;
+    let v2_config = config_manager.get_config().await;
+    let adapter = PublisherAdapter::new(config_manager.get_display_registry());
+    let strips: Vec<LedStripConfig> = match adapter.convert_v2_to_v1_config(&v2_config).await {
+        Ok(config) => config
+            .strips
+            .into_iter()
+            .filter(|strip| strip.display_id == display_id)
+            .collect(),
+        Err(e) => {
+            log::error!("Failed to resolve LED strip configs for display {display_id}: {e}");
+            return Err(StatusCode::INTERNAL_SERVER_ERROR);
+        }
+    };
+
+    let colors = screenshot.get_colors_by_led_configs(&strips).await;
+    let result = strips
+        .iter()
+        .zip(colors)
+        .map(|(strip, mut strip_colors)| {
+            strip.apply_reversal(&mut strip_colors);
+            (strip.index, strip_colors)
+        })
+        .collect();
+
+    Ok(Json(ApiResponse::success(result)))
+}
+
+/// 获取指定显示器最新一帧采样颜色的平均值
+#[utoipa::path(
+    get,
+    path = "/api/v1/display/{display_id}/average-color",
+    params(
+        ("display_id" = u32, Path, description = "显示器ID")
+    ),
+    responses(
+        (status = 200, description = "获取显示器平均颜色成功", body = ApiResponse<LedColor>),
+        (status = 503, description = "尚未产生采样帧", body = ApiResponse<String>),
+    ),
+    tag = "display"
+)]
+pub async fn get_display_average_color(
+    Path(display_id): Path<u32>,
+) -> Result<Json<ApiResponse<LedColor>>, StatusCode> {
+    let publisher = LedColorsPublisher::global().await;
+    match publisher.get_display_average_color(display_id).await {
+        Some(color) => Ok(Json(ApiResponse::success(color))),
+        None => {
+            log::debug!("No sampled frame for display {display_id} yet");
+            Err(StatusCode::SERVICE_UNAVAILABLE)
+        }
+    }
+}
+
+/// 获取显示器的镜像来源
+#[utoipa::path(
+    get,
+    path = "/api/v1/display/{display_id}/mirror-source",
+    params(
+        ("display_id" = u32, Path, description = "显示器ID")
+    ),
+    responses(
+        (status = 200, description = "获取镜像来源成功（未镜像时为空）", body = ApiResponse<Option<u32>>),
+        (status = 404, description = "显示器不存在", body = ApiResponse<String>),
+    ),
+    tag = "display"
+)]
+pub async fn get_display_mirror_source(
+    Path(display_id): Path<u32>,
+) -> Result<Json<ApiResponse<Option<u32>>>, StatusCode> {
+    let config_manager = ConfigManagerV2::global().await;
+    let registry = config_manager.get_display_registry();
+
+    let internal_id = registry
+        .get_internal_id_by_display_id(display_id)
+        .await
+        .map_err(|e| {
+            log::warn!("Display {display_id} not found: {e}");
+            StatusCode::NOT_FOUND
+        })?;
+
+    let config = config_manager.get_config().await;
+    let source_display_id = match config.mirror_sources.get(&internal_id) {
+        Some(source) => registry.get_display_id_by_internal_id(source).await.ok(),
+        None => None,
+    };
+
+    Ok(Json(ApiResponse::success(source_display_id)))
+}
+
+/// 设置显示器的镜像来源，使其灯带使用来源显示器的画面颜色
+#[utoipa::path(
+    put,
+    path = "/api/v1/display/{display_id}/mirror-source",
+    params(
+        ("display_id" = u32, Path, description = "显示器ID")
+    ),
+    request_body = SetMirrorSourceRequest,
+    responses(
+        (status = 200, description = "设置镜像来源成功", body = ApiResponse<String>),
+        (status = 400, description = "镜像配置无效", body = ApiResponse<String>),
+        (status = 404, description = "显示器不存在", body = ApiResponse<String>),
+    ),
+    tag = "display"
+)]
+pub async fn set_display_mirror_source(
+    Path(display_id): Path<u32>,
+    Json(request): Json<SetMirrorSourceRequest>,
+) -> Result<Json<ApiResponse<String>>, StatusCode> {
+    let config_manager = ConfigManagerV2::global().await;
+    let registry = config_manager.get_display_registry();
+
+    let target_internal_id = registry
+        .get_internal_id_by_display_id(display_id)
+        .await
+        .map_err(|e| {
+            log::warn!("Display {display_id} not found: {e}");
+            StatusCode::NOT_FOUND
+        })?;
+
+    let source_internal_id = match request.source_display_id {
+        Some(source_display_id) => Some(
+            registry
+                .get_internal_id_by_display_id(source_display_id)
+                .await
+                .map_err(|e| {
+                    log::warn!("Mirror source display {source_display_id} not found: {e}");
+                    StatusCode::NOT_FOUND
+                })?,
+        ),
+        None => None,
+    };
+
+    match config_manager
+        .set_mirror_source(&target_internal_id, source_internal_id)
+        .await
+    {
+        Ok(_) => Ok(Json(ApiResponse::success(
+            "Mirror source updated successfully".to_string(),
+        ))),
+        Err(e) => {
+            log::warn!("Failed to set mirror source for display {display_id}: {e}");
+            Err(StatusCode::BAD_REQUEST)
+        }
+    }
+}
+
+/// 将系统显示器ID转换为内部ID，显示器不存在时返回 404
+async fn display_internal_id(display_id: u32) -> Result<String, StatusCode> {
+    ConfigManagerV2::global()
+        .await
+        .get_display_registry()
+        .get_internal_id_by_display_id(display_id)
+        .await
+        .map_err(|e| {
+            log::warn!("Display {display_id} not found: {e}");
+            StatusCode::NOT_FOUND
+        })
+}
+
+/// 获取显示器的采样区域裁剪
+#[utoipa::path(
+    get,
+    path = "/api/v1/display/{display_id}/crop",
+    params(
+        ("display_id" = u32, Path, description = "显示器ID")
+    ),
+    responses(
+        (status = 200, description = "获取裁剪区域成功（未裁剪时为空）", body = ApiResponse<Option<SampleCrop>>),
+        (status = 404, description = "显示器不存在", body = ApiResponse<String>),
+    ),
+    tag = "display"
+)]
+pub async fn get_display_crop(
+    Path(display_id): Path<u32>,
+) -> Result<Json<ApiResponse<Option<SampleCrop>>>, StatusCode> {
+    display_internal_id(display_id).await?;
+
+    let crop = ConfigManagerV2::global()
+        .await
+        .get_display_crop(display_id)
+        .await;
+    Ok(Json(ApiResponse::success(crop)))
+}
+
+/// 设置显示器的采样区域裁剪（归一化坐标），边缘采样将以裁剪区域的边缘为准
+#[utoipa::path(
+    put,
+    path = "/api/v1/display/{display_id}/crop",
+    params(
+        ("display_id" = u32, Path, description = "显示器ID")
+    ),
+    request_body = SampleCrop,
+    responses(
+        (status = 200, description = "设置裁剪区域成功", body = ApiResponse<String>),
+        (status = 400, description = "裁剪区域无效", body = ApiResponse<String>),
+        (status = 404, description = "显示器不存在", body = ApiResponse<String>),
+    ),
+    tag = "display"
+)]
+pub async fn set_display_crop(
+    Path(display_id): Path<u32>,
+    Json(crop): Json<SampleCrop>,
+) -> Result<Json<ApiResponse<String>>, StatusCode> {
+    let internal_id = display_internal_id(display_id).await?;
+
+    match ConfigManagerV2::global()
+        .await
+        .set_display_crop(&internal_id, Some(crop))
+        .await
+    {
+        Ok(_) => Ok(Json(ApiResponse::success(
+            "Display crop updated successfully".to_string(),
+        ))),
+        Err(e) => {
+            log::warn!("Failed to set crop for display {display_id}: {e}");
+            Err(StatusCode::BAD_REQUEST)
+        }
+    }
+}
+
+/// 清除显示器的采样区域裁剪，恢复整幅画面采样
+#[utoipa::path(
+    delete,
+    path = "/api/v1/display/{display_id}/crop",
+    params(
+        ("display_id" = u32, Path, description = "显示器ID")
+    ),
+    responses(
+        (status = 200, description = "清除裁剪区域成功", body = ApiResponse<String>),
+        (status = 404, description = "显示器不存在", body = ApiResponse<String>),
+        (status = 500, description = "保存配置失败", body = ApiResponse<String>),
+    ),
+    tag = "display"
+)]
+pub async fn clear_display_crop(
+    Path(display_id): Path<u32>,
+) -> Result<Json<ApiResponse<String>>, StatusCode> {
+    let internal_id = display_internal_id(display_id).await?;
+
+    match ConfigManagerV2::global()
+        .await
+        .set_display_crop(&internal_id, None)
+        .await
+    {
+        Ok(_) => Ok(Json(ApiResponse::success(
+            "Display crop cleared successfully".to_string(),
+        ))),
+        Err(e) => {
+            log::error!("Failed to clear crop for display {display_id}: {e}");
+            Err(StatusCode::INTERNAL_SERVER_ERROR)
+        }
+    }
+}
+
+/// 获取显示器的缩放比例覆盖
+#[utoipa::path(
+    get,
+    path = "/api/v1/display/{display_id}/scale-factor",
+    params(
+        ("display_id" = u32, Path, description = "显示器ID")
+    ),
+    responses(
+        (status = 200, description = "获取缩放比例覆盖成功（使用自动检测值时为空）", body = ApiResponse<Option<f32>>),
+        (status = 404, description = "显示器不存在", body = ApiResponse<String>),
+    ),
+    tag = "display"
+)]
+pub async fn get_display_scale_factor(
+    Path(display_id): Path<u32>,
+) -> Result<Json<ApiResponse<Option<f32>>>, StatusCode> {
+    display_internal_id(display_id).await?;
+
+    let scale_factor = ConfigManagerV2::global()
+        .await
+        .get_display_scale_factor(display_id)
+        .await;
+    Ok(Json(ApiResponse::success(scale_factor)))
+}
+
+/// 设置显示器的缩放比例覆盖，用于自动检测值与实际帧缓冲区不符导致采样位置错误的显示器
+#[utoipa::path(
+    put,
+    path = "/api/v1/display/{display_id}/scale-factor",
+    params(
+        ("display_id" = u32, Path, description = "显示器ID")
+    ),
+    request_body = SetScaleFactorOverrideRequest,
+    responses(
+        (status = 200, description = "设置缩放比例覆盖成功", body = ApiResponse<String>),
+        (status = 400, description = "缩放比例无效", body = ApiResponse<String>),
+        (status = 404, description = "显示器不存在", body = ApiResponse<String>),
+    ),
+    tag = "display"
+)]
+pub async fn set_display_scale_factor(
+    Path(display_id): Path<u32>,
+    Json(request): Json<SetScaleFactorOverrideRequest>,
+) -> Result<Json<ApiResponse<String>>, StatusCode> {
+    let internal_id = display_internal_id(display_id).await?;
+
+    match ConfigManagerV2::global()
+        .await
+        .set_display_scale_factor(&internal_id, Some(request.scale_factor))
+        .await
+    {
+        Ok(_) => Ok(Json(ApiResponse::success(
+            "Display scale factor override updated successfully".to_string(),
+        ))),
+        Err(e) => {
+            log::warn!("Failed to set scale factor for display {display_id}: {e}");
+            Err(StatusCode::BAD_REQUEST)
+        }
+    }
+}
+
+/// 清除显示器的缩放比例覆盖，恢复自动检测
+#[utoipa::path(
+    delete,
+    path = "/api/v1/display/{display_id}/scale-factor",
+    params(
+        ("display_id" = u32, Path, description = "显示器ID")
+    ),
+    responses(
+        (status = 200, description = "清除缩放比例覆盖成功", body = ApiResponse<String>),
+        (status = 404, description = "显示器不存在", body = ApiResponse<String>),
+        (status = 500, description = "保存配置失败", body = ApiResponse<String>),
+    ),
+    tag = "display"
+)]
+pub async fn clear_display_scale_factor(
+    Path(display_id): Path<u32>,
+) -> Result<Json<ApiResponse<String>>, StatusCode> {
+    let internal_id = display_internal_id(display_id).await?;
+
+    match ConfigManagerV2::global()
+        .await
+        .set_display_scale_factor(&internal_id, None)
+        .await
+    {
+        Ok(_) => Ok(Json(ApiResponse::success(
+            "Display scale factor override cleared successfully".to_string(),
+        ))),
+        Err(e) => {
+            log::error!("Failed to clear scale factor for display {display_id}: {e}");
+            Err(StatusCode::INTERNAL_SERVER_ERROR)
+        }
+    }
+}
+
+/// 获取显示器的采集帧率，未设置覆盖时按显示器刷新率采集
+#[utoipa::path(
+    get,
+    path = "/api/v1/display/{display_id}/frame-rate",
+    params(
+        ("display_id" = u32, Path, description = "显示器ID")
+    ),
+    responses(
+        (status = 200, description = "获取采集帧率成功", body = ApiResponse<DisplayFrameRate>),
+        (status = 404, description = "显示器不存在", body = ApiResponse<String>),
+    ),
+    tag = "display"
+)]
+pub async fn get_display_frame_rate(
+    Path(display_id): Path<u32>,
+) -> Result<Json<ApiResponse<DisplayFrameRate>>, StatusCode> {
+    display_internal_id(display_id).await?;
+
+    let frame_rate = ConfigManagerV2::global()
+        .await
+        .get_display_frame_rate(display_id)
+        .await;
+    let frame_interval = ScreenshotManager::global()
+        .await
+        .get_frame_interval(display_id)
+        .await;
+    Ok(Json(ApiResponse::success(DisplayFrameRate {
+        frame_rate,
+        effective_frame_rate: (1.0 / frame_interval.as_secs_f64()) as f32,
+    })))
+}
+
+/// 设置显示器的采集帧率，各显示器按各自的帧率独立采集
+#[utoipa::path(
+    put,
+    path = "/api/v1/display/{display_id}/frame-rate",
+    params(
+        ("display_id" = u32, Path, description = "显示器ID")
+    ),
+    request_body = SetDisplayFrameRateRequest,
+    responses(
+        (status = 200, description = "设置采集帧率成功", body = ApiResponse<String>),
+        (status = 400, description = "采集帧率无效", body = ApiResponse<String>),
+        (status = 404, description = "显示器不存在", body = ApiResponse<String>),
+    ),
+    tag = "display"
+)]
+pub async fn set_display_frame_rate(
+    Path(display_id): Path<u32>,
+    Json(request): Json<SetDisplayFrameRateRequest>,
+) -> Result<Json<ApiResponse<String>>, StatusCode> {
+    let internal_id = display_internal_id(display_id).await?;
+
+    match ConfigManagerV2::global()
+        .await
+        .set_display_frame_rate(&internal_id, Some(request.frame_rate))
+        .await
+    {
+        Ok(_) => Ok(Json(ApiResponse::success(
+            "Display frame rate updated successfully".to_string(),
+        ))),
+        Err(e) => {
+            log::warn!("Failed to set frame rate for display {display_id}: {e}");
+            Err(StatusCode::BAD_REQUEST)
+        }
+    }
+}
+
+/// 清除显示器的采集帧率覆盖，恢复按显示器刷新率采集
+#[utoipa::path(
+    delete,
+    path = "/api/v1/display/{display_id}/frame-rate",
+    params(
+        ("display_id" = u32, Path, description = "显示器ID")
+    ),
+    responses(
+        (status = 200, description = "清除采集帧率覆盖成功", body = ApiResponse<String>),
+        (status = 404, description = "显示器不存在", body = ApiResponse<String>),
+        (status = 500, description = "保存配置失败", body = ApiResponse<String>),
+    ),
+    tag = "display"
+)]
+pub async fn clear_display_frame_rate(
+    Path(display_id): Path<u32>,
+) -> Result<Json<ApiResponse<String>>, StatusCode> {
+    let internal_id = display_internal_id(display_id).await?;
+
+    match ConfigManagerV2::global()
+        .await
+        .set_display_frame_rate(&internal_id, None)
+        .await
+    {
+        Ok(_) => Ok(Json(ApiResponse::success(
+            "Display frame rate override cleared successfully".to_string(),
+        ))),
+        Err(e) => {
+            log::error!("Failed to clear frame rate for display {display_id}: {e}");
+            Err(StatusCode::INTERNAL_SERVER_ERROR)
+        }
+    }
+}
+
+/// 获取显示器的灯带走线方向
+#[utoipa::path(
+    get,
+    path = "/api/v1/display/{display_id}/winding",
+    params(
+        ("display_id" = u32, Path, description = "显示器ID")
+    ),
+    responses(
+        (status = 200, description = "获取走线方向成功", body = ApiResponse<DisplayWinding>),
+        (status = 404, description = "显示器不存在", body = ApiResponse<String>),
+    ),
+    tag = "display"
+)]
+pub async fn get_display_winding(
+    Path(display_id): Path<u32>,
+) -> Result<Json<ApiResponse<DisplayWinding>>, StatusCode> {
+    display_internal_id(display_id).await?;
+
+    let winding = ConfigManagerV2::global()
+        .await
+        .get_display_winding(display_id)
+        .await;
+    Ok(Json(ApiResponse::success(winding)))
+}
+
+/// 设置显示器的灯带走线方向，逆时针时该显示器所有灯带的顺序整体翻转后输出
+#[utoipa::path(
+    put,
+    path = "/api/v1/display/{display_id}/winding",
+    params(
+        ("display_id" = u32, Path, description = "显示器ID")
+    ),
+    request_body = SetDisplayWindingRequest,
+    responses(
+        (status = 200, description = "设置走线方向成功", body = ApiResponse<String>),
+        (status = 404, description = "显示器不存在", body = ApiResponse<String>),
+        (status = 500, description = "保存配置失败", body = ApiResponse<String>),
+    ),
+    tag = "display"
+)]
+pub async fn set_display_winding(
+    Path(display_id): Path<u32>,
+    Json(request): Json<SetDisplayWindingRequest>,
+) -> Result<Json<ApiResponse<String>>, StatusCode> {
+    let internal_id = display_internal_id(display_id).await?;
+
+    match ConfigManagerV2::global()
+        .await
+        .set_display_winding(&internal_id, request.winding)
+        .await
+    {
+        Ok(_) => Ok(Json(ApiResponse::success(
+            "Display winding updated successfully".to_string(),
+        ))),
+        Err(e) => {
+            log::error!("Failed to set winding for display {display_id}: {e}");
+            Err(StatusCode::INTERNAL_SERVER_ERROR)
+        }
+    }
+}
+
+/// 获取显示器启用的边
+#[utoipa::path(
+    get,
+    path = "/api/v1/display/{display_id}/borders",
+    params(
+        ("display_id" = u32, Path, description = "显示器ID")
+    ),
+    responses(
+        (status = 200, description = "获取启用的边成功", body = ApiResponse<BorderMask>),
+        (status = 404, description = "显示器不存在", body = ApiResponse<String>),
+    ),
+    tag = "display"
+)]
+pub async fn get_display_border_mask(
+    Path(display_id): Path<u32>,
+) -> Result<Json<ApiResponse<BorderMask>>, StatusCode> {
+    display_internal_id(display_id).await?;
+
+    let border_mask = ConfigManagerV2::global()
+        .await
+        .get_display_border_mask(display_id)
+        .await;
+    Ok(Json(ApiResponse::success(border_mask)))
+}
+
+/// 设置显示器启用的边，适用于只在部分边上安装了灯带的显示器
+#[utoipa::path(
+    put,
+    path = "/api/v1/display/{display_id}/borders",
+    params(
+        ("display_id" = u32, Path, description = "显示器ID")
+    ),
+    request_body = SetDisplayBorderMaskRequest,
+    responses(
+        (status = 200, description = "设置启用的边成功", body = ApiResponse<String>),
+        (status = 404, description = "显示器不存在", body = ApiResponse<String>),
+        (status = 500, description = "保存配置失败", body = ApiResponse<String>),
+    ),
+    tag = "display"
+)]
+pub async fn set_display_border_mask(
+    Path(display_id): Path<u32>,
+    Json(request): Json<SetDisplayBorderMaskRequest>,
+) -> Result<Json<ApiResponse<String>>, StatusCode> {
+    let internal_id = display_internal_id(display_id).await?;
+
+    match ConfigManagerV2::global()
+        .await
+        .set_display_border_mask(&internal_id, request.border_mask)
+        .await
+    {
+        Ok(_) => Ok(Json(ApiResponse::success(
+            "Display border mask updated successfully".to_string(),
+        ))),
+        Err(e) => {
+            log::error!("Failed to set border mask for display {display_id}: {e}");
+            Err(StatusCode::INTERNAL_SERVER_ERROR)
+        }
+    }
+}
+
+/// 获取显示器截图的像素字节顺序
+#[utoipa::path(
+    get,
+    path = "/api/v1/display/{display_id}/capture-format",
+    params(
+        ("display_id" = u32, Path, description = "显示器ID")
+    ),
+    responses(
+        (status = 200, description = "获取像素字节顺序成功", body = ApiResponse<CaptureFormat>),
+        (status = 404, description = "显示器不存在", body = ApiResponse<String>),
+    ),
+    tag = "display"
+)]
+pub async fn get_display_capture_format(
+    Path(display_id): Path<u32>,
+) -> Result<Json<ApiResponse<CaptureFormat>>, StatusCode> {
+    display_internal_id(display_id).await?;
+
+    let capture_format = ConfigManagerV2::global()
+        .await
+        .get_display_capture_format(display_id)
+        .await;
+    Ok(Json(ApiResponse::success(capture_format)))
+}
+
+/// 设置显示器截图的像素字节顺序，用于修正采集方式不同导致的红蓝通道互换
+#[utoipa::path(
+    put,
+    path = "/api/v1/display/{display_id}/capture-format",
+    params(
+        ("display_id" = u32, Path, description = "显示器ID")
+    ),
+    request_body = SetDisplayCaptureFormatRequest,
+    responses(
+        (status = 200, description = "设置像素字节顺序成功", body = ApiResponse<String>),
+        (status = 404, description = "显示器不存在", body = ApiResponse<String>),
+        (status = 500, description = "保存配置失败", body = ApiResponse<String>),
+    ),
+    tag = "display"
+)]
+pub async fn set_display_capture_format(
+    Path(display_id): Path<u32>,
+    Json(request): Json<SetDisplayCaptureFormatRequest>,
+) -> Result<Json<ApiResponse<String>>, StatusCode> {
+    let internal_id = display_internal_id(display_id).await?;
+
+    match ConfigManagerV2::global()
+        .await
+        .set_display_capture_format(&internal_id, request.capture_format)
+        .await
+    {
+        Ok(_) => Ok(Json(ApiResponse::success(
+            "Display capture format updated successfully".to_string(),
+        ))),
+        Err(e) => {
+            log::error!("Failed to set capture format for display {display_id}: {e}");
+            Err(StatusCode::INTERNAL_SERVER_ERROR)
+        }
+    }
+}
+
 /// 创建显示器相关路由
 pub fn create_routes() -> Router<AppState> {
     Router::new()
         .route("/", get(get_displays))
         .route("/info", get(list_display_info))
         .route("/configs", get(get_display_configs))
+        .route("/bindings", get(get_display_bindings))
+        .route("/bindings/:internal_id", put(bind_strips_to_display))
+        .route("/spans", get(get_strip_spans))
+        .route("/spans/:strip_index", put(set_strip_span))
+        .route("/sample-regions", get(get_sample_regions))
         .route("/:display_id/colors", get(get_display_colors))
+        .route("/:display_id/average-color", get(get_display_average_color))
+        .route("/:display_id/sample-now", post(sample_display_now))
+        .route(
+            "/:display_id/mirror-source",
+            get(get_display_mirror_source).put(set_display_mirror_source),
+        )
+        .route(
+            "/:display_id/crop",
+            get(get_display_crop)
+                .put(set_display_crop)
+                .delete(clear_display_crop),
+        )
+        .route(
+            "/:display_id/scale-factor",
+            get(get_display_scale_factor)
+                .put(set_display_scale_factor)
+                .delete(clear_display_scale_factor),
+        )
+        .route(
+            "/:display_id/frame-rate",
+            get(get_display_frame_rate)
+                .put(set_display_frame_rate)
+                .delete(clear_display_frame_rate),
+        )
+        .route(
+            "/:display_id/winding",
+            get(get_display_winding).put(set_display_winding),
+        )
+        .route(
+            "/:display_id/borders",
+            get(get_display_border_mask).put(set_display_border_mask),
+        )
+        .route(
+            "/:display_id/capture-format",
+            get(get_display_capture_format).put(set_display_capture_format),
+        )
 }
@@ -1,4 +1,5 @@
 use axum::{
+    extract::State,
     http::StatusCode,
     response::Json,
     routing::{get, post},
@@ -7,7 +8,7 @@ use axum::{
 use serde::{Deserialize, Serialize};
 use utoipa::ToSchema;
 
-use crate::http_server::{ApiResponse, AppState};
+use crate::http_server::{websocket::WebSocketClientStats, ApiResponse, AppState};
 
 /// 应用版本信息
 #[derive(Serialize, ToSchema)]
@@ -68,6 +69,22 @@ pub async fn get_system_info() -> Result<Json<ApiResponse<SystemInfo>>, StatusCo
     Ok(Json(ApiResponse::success(system_info)))
 }
 
+/// 获取WebSocket客户端统计（含慢客户端被丢弃的颜色帧数量）
+#[utoipa::path(
+    get,
+    path = "/api/v1/info/websocket-clients",
+    responses(
+        (status = 200, description = "WebSocket客户端统计", body = ApiResponse<Vec<WebSocketClientStats>>),
+    ),
+    tag = "info"
+)]
+pub async fn get_websocket_clients(
+    State(state): State<AppState>,
+) -> Result<Json<ApiResponse<Vec<WebSocketClientStats>>>, StatusCode> {
+    let stats = state.websocket_manager.get_client_stats().await;
+    Ok(Json(ApiResponse::success(stats)))
+}
+
 /// 页面信息报告请求
 #[derive(Deserialize, ToSchema)]
 pub struct ReportPageRequest {
@@ -224,6 +241,7 @@ pub fn create_routes() -> Router<AppState> {
     Router::new()
         .route("/version", get(get_app_version))
         .route("/system", get(get_system_info))
+        .route("/websocket-clients", get(get_websocket_clients))
         .route("/current-page", post(report_current_page))
         .route("/report-page", post(report_page))
         .route("/navigate", post(navigate_to_page))
@@ -1,20 +1,47 @@
 use axum::{
-    http::StatusCode,
-    response::Json,
-    routing::{get, post, put},
+    extract::Query,
+    http::{header, StatusCode},
+    response::{IntoResponse, Json},
+    routing::{delete, get, post, put},
     Router,
 };
-use serde::Deserialize;
+use serde::{Deserialize, Serialize};
+use std::collections::HashMap;
+use tokio::sync::Mutex;
 use utoipa::ToSchema;
 
 use crate::{
-    ambient_light::{self, BorderColors, LedStripConfig},
+    ambient_light::{
+        self, BorderColors, CalibrationStep, CalibrationWizard, ColorCalibration, LedStripConfig,
+        StripLayoutEntry,
+    },
+    color_vision::ColorVisionSimulation,
     http_server::{ApiResponse, AppState},
     led_data_sender::{DataSendMode, LedDataSender},
     led_preview_state::{LedPreviewState, LedPreviewStateManager},
     led_status_manager::{LedStatusManager, LedStatusStats},
+    led_test_effects::LengthDetectionStatus,
 };
 
+/// 氛围光预览图查询参数
+#[derive(Deserialize, ToSchema)]
+pub struct PreviewImageQuery {
+    /// 图片宽度（64-3840），默认960
+    #[serde(default = "default_preview_width")]
+    pub width: u32,
+    /// 图片高度（64-2160），默认540
+    #[serde(default = "default_preview_height")]
+    pub height: u32,
+}
+
+fn default_preview_width() -> u32 {
+    ambient_light::DEFAULT_PREVIEW_RENDER_WIDTH
+}
+
+fn default_preview_height() -> u32 {
+    ambient_light::DEFAULT_PREVIEW_RENDER_HEIGHT
+}
+
 /// LED颜色发送请求
 #[derive(Deserialize, ToSchema)]
 pub struct SendColorsRequest {
@@ -35,6 +62,63 @@ pub struct SendCalibrationColorRequest {
     pub b: u8,
 }
 
+/// 手动纯色设置请求
+#[derive(Deserialize, ToSchema)]
+pub struct SetManualColorRequest {
+    /// 红色分量 (0-255)
+    pub r: u8,
+    /// 绿色分量 (0-255)
+    pub g: u8,
+    /// 蓝色分量 (0-255)
+    pub b: u8,
+}
+
+/// 预览颜色校准请求
+#[derive(Deserialize, ToSchema)]
+pub struct SetPreviewCalibrationRequest {
+    /// 待预览的颜色校准（不会持久化）
+    pub calibration: ColorCalibration,
+}
+
+/// 校准向导感知颜色报告请求
+#[derive(Deserialize, ToSchema)]
+pub struct CalibrationWizardReportRequest {
+    /// 用户看到的红色分量 (0-255)
+    pub r: u8,
+    /// 用户看到的绿色分量 (0-255)
+    pub g: u8,
+    /// 用户看到的蓝色分量 (0-255)
+    pub b: u8,
+}
+
+/// 校准向导状态
+#[derive(Serialize, ToSchema)]
+pub struct CalibrationWizardStatus {
+    /// 当前等待报告的步骤，向导完成时为空
+    pub step: Option<CalibrationStep>,
+    /// 当前步骤发送到灯带的颜色
+    pub color: Option<[u8; 3]>,
+    /// 向导完成后建议的颜色校准（未保存，需确认后再应用）
+    #[schema(value_type = Option<Object>)]
+    pub suggested_calibration: Option<ColorCalibration>,
+}
+
+/// 进行中的白平衡校准向导
+struct ActiveCalibrationWizard {
+    wizard: CalibrationWizard,
+    /// 向导开始前的LED数据发送模式，向导结束或取消后恢复
+    previous_mode: DataSendMode,
+}
+
+static CALIBRATION_WIZARD: Mutex<Option<ActiveCalibrationWizard>> = Mutex::const_new(None);
+
+/// 颜色帧录制/回放请求
+#[derive(Deserialize, ToSchema)]
+pub struct FrameRecordingRequest {
+    /// 录制名称（配置目录下 recordings 目录中的文件名，不能包含路径）
+    pub name: String,
+}
+
 /// 测试颜色发送请求
 #[derive(Deserialize, ToSchema)]
 pub struct SendTestColorsRequest {
@@ -62,6 +146,12 @@ pub struct BreathingStripRequest {
     pub display_id: u32,
     /// 边框（可选）
     pub border: Option<String>,
+    /// 呼吸频率（Hz），默认0.5（每2秒一个周期）
+    pub frequency_hz: Option<f64>,
+    /// 最低亮度（0-1），默认0.3
+    pub min_brightness: Option<f32>,
+    /// 最高亮度（0-1），默认1.0
+    pub max_brightness: Option<f32>,
 }
 
 /// LED测试效果请求
@@ -80,12 +170,26 @@ pub struct StartLedTestEffectRequest {
     /// 目标板地址
     #[serde(alias = "boardAddress")]
     pub board_address: String,
-    /// 效果配置
+    /// 效果配置，`params` 为各效果的可选参数（分组大小与颜色、呼吸颜色与亮度范围）
+    #[serde(alias = "effectConfig")]
+    pub effect_config: serde_json::Value,
+    /// 更新间隔（毫秒）
+    #[serde(alias = "updateIntervalMs")]
+    pub update_interval_ms: u32,
+}
+
+/// 在所有设备上启动LED测试效果请求
+#[derive(Deserialize, ToSchema)]
+pub struct StartAllBoardsTestEffectRequest {
+    /// 效果配置；已配置灯带时各设备的LED数量由其所接灯带推导，否则 `led_count` 为每个设备的LED数量
     #[serde(alias = "effectConfig")]
     pub effect_config: serde_json::Value,
     /// 更新间隔（毫秒）
     #[serde(alias = "updateIntervalMs")]
     pub update_interval_ms: u32,
+    /// 按设备地址（IP:端口）单独指定的更新间隔（毫秒），未指定的设备使用 `update_interval_ms`
+    #[serde(default, alias = "boardUpdateIntervalsMs")]
+    pub board_update_intervals_ms: HashMap<String, u32>,
 }
 
 /// 停止LED测试效果请求
@@ -103,11 +207,61 @@ pub struct StopLedTestEffectRequest {
     pub led_type: String,
 }
 
+/// 启动灯带长度检测请求
+#[derive(Deserialize, ToSchema)]
+pub struct StartLengthDetectionRequest {
+    /// 目标板地址
+    pub board_address: String,
+    /// 效果配置，led_count 为检测的最大LED数量，效果类型固定为单灯扫描
+    pub effect_config: serde_json::Value,
+    /// 亮灯前进一个LED的间隔（毫秒），必须大于0
+    pub step_interval_ms: u32,
+    /// 是否自动前进，关闭时仅通过步进接口移动亮灯
+    #[serde(default = "default_auto_advance")]
+    pub auto_advance: bool,
+}
+
+fn default_auto_advance() -> bool {
+    true
+}
+
+/// 灯带长度检测步进请求
+#[derive(Deserialize, ToSchema)]
+pub struct AdvanceLengthDetectionRequest {
+    /// 目标板地址
+    pub board_address: String,
+    /// 步进的LED数量，负数表示后退
+    #[serde(default)]
+    pub steps: i32,
+    /// 切换自动前进，为空时保持不变
+    #[serde(default)]
+    pub auto_advance: Option<bool>,
+}
+
+/// 灯带长度检测标记拐角请求
+#[derive(Deserialize, ToSchema)]
+pub struct MarkLengthDetectionCornerRequest {
+    /// 目标板地址
+    pub board_address: String,
+}
+
+/// 应用灯带长度检测结果请求
+#[derive(Deserialize, ToSchema)]
+pub struct ApplyLengthDetectionRequest {
+    /// 目标板地址
+    pub board_address: String,
+    /// 按串联顺序排列的灯带序列号，依次对应检测到的各段长度
+    pub strip_indices: Vec<usize>,
+}
+
 /// 数据发送模式设置请求
 #[derive(Deserialize, ToSchema)]
 pub struct SetDataSendModeRequest {
     /// 数据发送模式
     pub mode: DataSendMode,
+    /// 模拟发送：执行完整的处理流程但不发送UDP数据，为空时保持不变
+    #[serde(default)]
+    pub dry_run: Option<bool>,
 }
 
 /// 发送LED颜色数据
@@ -265,8 +419,15 @@ pub async fn set_data_send_mode(
     Json(request): Json<SetDataSendModeRequest>,
 ) -> Result<Json<ApiResponse<String>>, StatusCode> {
     let sender = LedDataSender::global().await;
+    if let Some(dry_run) = request.dry_run {
+        sender.set_dry_run(dry_run).await;
+    }
     sender.set_mode(request.mode).await;
-    log::info!("LED data send mode set to: {}", request.mode);
+    log::info!(
+        "LED data send mode set to: {} (dry run: {})",
+        request.mode,
+        sender.is_dry_run().await
+    );
     Ok(Json(ApiResponse::success(
         "Mode set successfully".to_string(),
     )))
@@ -416,6 +577,7 @@ pub async fn restart_ambient_light_publisher() -> Result<Json<ApiResponse<String
     request_body = BreathingStripRequest,
     responses(
         (status = 200, description = "设置呼吸效果成功", body = ApiResponse<String>),
+        (status = 400, description = "呼吸参数无效", body = ApiResponse<String>),
         (status = 500, description = "设置失败", body = ApiResponse<String>),
     ),
     tag = "led"
@@ -427,8 +589,19 @@ pub async fn set_active_strip_breathing(
     let display_id = request.display_id;
     let border = request.border.clone();
 
+    let defaults = ambient_light::BreathingConfig::default();
+    let breathing = ambient_light::BreathingConfig {
+        frequency_hz: request.frequency_hz.unwrap_or(defaults.frequency_hz),
+        min_brightness: request.min_brightness.unwrap_or(defaults.min_brightness),
+        max_brightness: request.max_brightness.unwrap_or(defaults.max_brightness),
+    };
+    if let Err(e) = breathing.validate() {
+        log::warn!("Invalid breathing parameters: {e}");
+        return Err(StatusCode::BAD_REQUEST);
+    }
+
     match publisher
-        .set_active_strip_for_breathing(display_id, request.border)
+        .set_active_strip_for_breathing(display_id, request.border, breathing)
         .await
     {
         Ok(_) => {
@@ -453,6 +626,7 @@ pub async fn set_active_strip_breathing(
     request_body = StartLedTestEffectRequest,
     responses(
         (status = 200, description = "启动测试效果成功", body = ApiResponse<String>),
+        (status = 400, description = "效果配置无效", body = ApiResponse<String>),
         (status = 500, description = "启动失败", body = ApiResponse<String>),
     ),
     tag = "led"
@@ -471,6 +645,10 @@ pub async fn start_led_test_effect(
             log::error!("Failed to parse effect config: {e}");
             StatusCode::BAD_REQUEST
         })?;
+    if let Err(e) = crate::led_test_effects::validate_test_effect_params(&config.params) {
+        log::warn!("Invalid test effect params: {e}");
+        return Err(StatusCode::BAD_REQUEST);
+    }
 
     // 获取测试效果管理器并启动效果
     let manager = crate::led_test_effects::LedTestEffectManager::global().await;
@@ -493,6 +671,69 @@ pub async fn start_led_test_effect(
     }
 }
 
+/// 在所有设备上同时启动LED测试效果，彩虹按设备顺序连续衔接
+#[utoipa::path(
+    post,
+    path = "/api/v1/led/test-all",
+    request_body = StartAllBoardsTestEffectRequest,
+    responses(
+        (status = 200, description = "启动测试效果成功，返回按串联顺序排列的设备地址", body = ApiResponse<Vec<String>>),
+        (status = 400, description = "效果配置无效", body = ApiResponse<String>),
+        (status = 500, description = "启动失败", body = ApiResponse<String>),
+    ),
+    tag = "led"
+)]
+pub async fn start_all_boards_test_effect(
+    Json(request): Json<StartAllBoardsTestEffectRequest>,
+) -> Result<Json<ApiResponse<Vec<String>>>, StatusCode> {
+    let config: crate::led_test_effects::TestEffectConfig =
+        serde_json::from_value(request.effect_config).map_err(|e| {
+            log::error!("Failed to parse effect config: {e}");
+            StatusCode::BAD_REQUEST
+        })?;
+    if let Err(e) = crate::led_test_effects::validate_test_effect_params(&config.params) {
+        log::warn!("Invalid test effect params: {e}");
+        return Err(StatusCode::BAD_REQUEST);
+    }
+
+    let manager = crate::led_test_effects::LedTestEffectManager::global().await;
+    match manager
+        .start_test_effect_on_all_boards(
+            config,
+            request.update_interval_ms,
+            &request.board_update_intervals_ms,
+        )
+        .await
+    {
+        Ok(boards) => Ok(Json(ApiResponse::success(boards))),
+        Err(e) => {
+            log::error!("Failed to start LED test effect on all boards: {e}");
+            Err(StatusCode::INTERNAL_SERVER_ERROR)
+        }
+    }
+}
+
+/// 停止在所有设备上同时运行的LED测试效果
+#[utoipa::path(
+    post,
+    path = "/api/v1/led/test-all/stop",
+    responses(
+        (status = 200, description = "停止测试效果成功，返回已停止的设备地址", body = ApiResponse<Vec<String>>),
+        (status = 500, description = "停止失败", body = ApiResponse<String>),
+    ),
+    tag = "led"
+)]
+pub async fn stop_all_boards_test_effect() -> Result<Json<ApiResponse<Vec<String>>>, StatusCode> {
+    let manager = crate::led_test_effects::LedTestEffectManager::global().await;
+    match manager.stop_all_boards_test_effect().await {
+        Ok(boards) => Ok(Json(ApiResponse::success(boards))),
+        Err(e) => {
+            log::error!("Failed to stop LED test effect on all boards: {e}");
+            Err(StatusCode::INTERNAL_SERVER_ERROR)
+        }
+    }
+}
+
 /// 停止LED测试效果
 #[utoipa::path(
     post,
@@ -526,6 +767,177 @@ pub async fn stop_led_test_effect(
     }
 }
 
+/// 启动灯带长度检测
+#[utoipa::path(
+    post,
+    path = "/api/v1/led/length-detection/start",
+    request_body = StartLengthDetectionRequest,
+    responses(
+        (status = 200, description = "启动长度检测成功", body = ApiResponse<LengthDetectionStatus>),
+        (status = 400, description = "请求参数无效"),
+        (status = 500, description = "启动失败"),
+    ),
+    tag = "led"
+)]
+pub async fn start_length_detection(
+    Json(request): Json<StartLengthDetectionRequest>,
+) -> Result<Json<ApiResponse<LengthDetectionStatus>>, StatusCode> {
+    let config: crate::led_test_effects::TestEffectConfig =
+        serde_json::from_value(request.effect_config).map_err(|e| {
+            log::error!("Failed to parse effect config: {e}");
+            StatusCode::BAD_REQUEST
+        })?;
+    if let Err(e) = crate::led_test_effects::validate_test_effect_params(&config.params) {
+        log::warn!("Invalid test effect params: {e}");
+        return Err(StatusCode::BAD_REQUEST);
+    }
+    if config.led_count == 0 {
+        return Err(StatusCode::BAD_REQUEST);
+    }
+    if request.step_interval_ms == 0 {
+        log::warn!("Invalid length detection step interval: 0 ms");
+        return Err(StatusCode::BAD_REQUEST);
+    }
+
+    let manager = crate::led_test_effects::LedTestEffectManager::global().await;
+    match manager
+        .start_length_detection(
+            request.board_address,
+            config,
+            request.step_interval_ms,
+            request.auto_advance,
+        )
+        .await
+    {
+        Ok(status) => Ok(Json(ApiResponse::success(status))),
+        Err(e) => {
+            log::error!("Failed to start LED length detection: {e}");
+            Err(StatusCode::INTERNAL_SERVER_ERROR)
+        }
+    }
+}
+
+/// 步进灯带长度检测的亮灯位置
+#[utoipa::path(
+    post,
+    path = "/api/v1/led/length-detection/advance",
+    request_body = AdvanceLengthDetectionRequest,
+    responses(
+        (status = 200, description = "步进成功", body = ApiResponse<LengthDetectionStatus>),
+        (status = 404, description = "未在进行长度检测"),
+    ),
+    tag = "led"
+)]
+pub async fn advance_length_detection(
+    Json(request): Json<AdvanceLengthDetectionRequest>,
+) -> Result<Json<ApiResponse<LengthDetectionStatus>>, StatusCode> {
+    let manager = crate::led_test_effects::LedTestEffectManager::global().await;
+    match manager
+        .advance_length_detection(&request.board_address, request.steps, request.auto_advance)
+        .await
+    {
+        Ok(status) => Ok(Json(ApiResponse::success(status))),
+        Err(e) => {
+            log::warn!("Failed to advance LED length detection: {e}");
+            Err(StatusCode::NOT_FOUND)
+        }
+    }
+}
+
+/// 标记亮灯已到达拐角
+#[utoipa::path(
+    post,
+    path = "/api/v1/led/length-detection/mark",
+    request_body = MarkLengthDetectionCornerRequest,
+    responses(
+        (status = 200, description = "标记成功", body = ApiResponse<LengthDetectionStatus>),
+        (status = 400, description = "未在进行长度检测或标记位置无效"),
+    ),
+    tag = "led"
+)]
+pub async fn mark_length_detection_corner(
+    Json(request): Json<MarkLengthDetectionCornerRequest>,
+) -> Result<Json<ApiResponse<LengthDetectionStatus>>, StatusCode> {
+    let manager = crate::led_test_effects::LedTestEffectManager::global().await;
+    match manager
+        .mark_length_detection_corner(&request.board_address)
+        .await
+    {
+        Ok(status) => Ok(Json(ApiResponse::success(status))),
+        Err(e) => {
+            log::warn!("Failed to mark LED length detection corner: {e}");
+            Err(StatusCode::BAD_REQUEST)
+        }
+    }
+}
+
+/// 结束灯带长度检测并将各段长度写入灯带配置
+#[utoipa::path(
+    post,
+    path = "/api/v1/led/length-detection/apply",
+    request_body = ApplyLengthDetectionRequest,
+    responses(
+        (status = 200, description = "应用检测结果成功", body = ApiResponse<Vec<usize>>),
+        (status = 400, description = "未在进行长度检测或标记的段数不足"),
+        (status = 404, description = "未找到指定的LED灯带"),
+        (status = 500, description = "保存配置失败"),
+    ),
+    tag = "led"
+)]
+pub async fn apply_length_detection(
+    Json(request): Json<ApplyLengthDetectionRequest>,
+) -> Result<Json<ApiResponse<Vec<usize>>>, StatusCode> {
+    let manager = crate::led_test_effects::LedTestEffectManager::global().await;
+    let segment_lengths = manager
+        .get_length_detection_status(&request.board_address)
+        .await
+        .ok_or(StatusCode::BAD_REQUEST)?
+        .segment_lengths;
+
+    if segment_lengths.len() < request.strip_indices.len() {
+        log::warn!(
+            "Only {} segments marked for {} strips",
+            segment_lengths.len(),
+            request.strip_indices.len()
+        );
+        return Err(StatusCode::BAD_REQUEST);
+    }
+
+    let config_manager_v2 = ambient_light::ConfigManagerV2::global().await;
+    let mut v2_config = config_manager_v2.get_config().await;
+
+    for (strip_index, len) in request.strip_indices.iter().zip(&segment_lengths) {
+        let Some(strip) = v2_config
+            .strips
+            .iter_mut()
+            .find(|strip| strip.index == *strip_index)
+        else {
+            log::error!("LED strip not found for index {strip_index}");
+            return Err(StatusCode::NOT_FOUND);
+        };
+        strip.len = *len;
+    }
+
+    // 重新生成mappers
+    v2_config.generate_mappers();
+
+    if let Err(e) = config_manager_v2.update_config(v2_config).await {
+        log::error!("Failed to save detected LED strip lengths: {e}");
+        return Err(StatusCode::INTERNAL_SERVER_ERROR);
+    }
+
+    let applied = segment_lengths[..request.strip_indices.len()].to_vec();
+    if let Err(e) = manager
+        .finish_length_detection(&request.board_address)
+        .await
+    {
+        log::warn!("Failed to stop LED length detection: {e}");
+    }
+
+    log::info!("📏 Applied detected LED strip lengths: {applied:?}");
+    Ok(Json(ApiResponse::success(applied)))
+}
+
 /// 测试单屏配置模式
 #[utoipa::path(
     post,
@@ -617,6 +1029,508 @@ pub async fn set_led_preview_state(
     }
 }
 
+/// 预览色觉模拟设置请求
+#[derive(Deserialize, ToSchema)]
+pub struct SetPreviewColorVisionRequest {
+    /// 模拟的色觉缺陷类型，`None` 表示关闭
+    pub simulation: ColorVisionSimulation,
+}
+
+/// 获取预览色觉模拟类型
+#[utoipa::path(
+    get,
+    path = "/api/v1/led/preview-color-vision",
+    responses(
+        (status = 200, description = "获取预览色觉模拟类型成功", body = ApiResponse<ColorVisionSimulation>),
+    ),
+    tag = "led"
+)]
+pub async fn get_preview_color_vision(
+) -> Result<Json<ApiResponse<ColorVisionSimulation>>, StatusCode> {
+    let state_manager = LedPreviewStateManager::global().await;
+    Ok(Json(ApiResponse::success(
+        state_manager.get_color_vision().await,
+    )))
+}
+
+/// 设置预览色觉模拟类型
+///
+/// 只转换通过WebSocket发布的预览颜色，发送到硬件的数据不受影响
+#[utoipa::path(
+    put,
+    path = "/api/v1/led/preview-color-vision",
+    request_body = SetPreviewColorVisionRequest,
+    responses(
+        (status = 200, description = "设置预览色觉模拟类型成功", body = ApiResponse<String>),
+        (status = 500, description = "设置失败", body = ApiResponse<String>),
+    ),
+    tag = "led"
+)]
+pub async fn set_preview_color_vision(
+    Json(request): Json<SetPreviewColorVisionRequest>,
+) -> Result<Json<ApiResponse<String>>, StatusCode> {
+    let state_manager = LedPreviewStateManager::global().await;
+    match state_manager.set_color_vision(request.simulation).await {
+        Ok(_) => Ok(Json(ApiResponse::success(
+            "Preview color vision simulation set successfully".to_string(),
+        ))),
+        Err(e) => {
+            log::error!("Failed to set preview color vision simulation: {e}");
+            Err(StatusCode::INTERNAL_SERVER_ERROR)
+        }
+    }
+}
+
+/// 获取当前预览颜色校准
+#[utoipa::path(
+    get,
+    path = "/api/v1/led/preview-calibration",
+    responses(
+        (status = 200, description = "获取预览颜色校准成功", body = ApiResponse<Option<ColorCalibration>>),
+    ),
+    tag = "led"
+)]
+pub async fn get_preview_calibration(
+) -> Result<Json<ApiResponse<Option<ColorCalibration>>>, StatusCode> {
+    let publisher = ambient_light::LedColorsPublisher::global().await;
+    let calibration = publisher.get_preview_calibration().await;
+    Ok(Json(ApiResponse::success(calibration)))
+}
+
+/// 设置预览颜色校准（在环境光模式下实时生效，不修改已保存配置）
+#[utoipa::path(
+    put,
+    path = "/api/v1/led/preview-calibration",
+    request_body = SetPreviewCalibrationRequest,
+    responses(
+        (status = 200, description = "设置预览颜色校准成功", body = ApiResponse<String>),
+        (status = 400, description = "颜色校准参数无效", body = ApiResponse<String>),
+    ),
+    tag = "led"
+)]
+pub async fn set_preview_calibration(
+    Json(request): Json<SetPreviewCalibrationRequest>,
+) -> Result<Json<ApiResponse<String>>, StatusCode> {
+    if let Err(e) = request.calibration.validate() {
+        log::warn!("Invalid preview calibration: {e}");
+        return Err(StatusCode::BAD_REQUEST);
+    }
+    let publisher = ambient_light::LedColorsPublisher::global().await;
+    publisher.set_preview_calibration(request.calibration).await;
+    Ok(Json(ApiResponse::success(
+        "Preview calibration set successfully".to_string(),
+    )))
+}
+
+/// 清除预览颜色校准，恢复使用已保存的校准
+#[utoipa::path(
+    delete,
+    path = "/api/v1/led/preview-calibration",
+    responses(
+        (status = 200, description = "清除预览颜色校准成功", body = ApiResponse<String>),
+    ),
+    tag = "led"
+)]
+pub async fn clear_preview_calibration() -> Result<Json<ApiResponse<String>>, StatusCode> {
+    let publisher = ambient_light::LedColorsPublisher::global().await;
+    publisher.clear_preview_calibration().await;
+    Ok(Json(ApiResponse::success(
+        "Preview calibration cleared successfully".to_string(),
+    )))
+}
+
+/// 获取当前手动纯色，未设置时返回 null
+#[utoipa::path(
+    get,
+    path = "/api/v1/led/manual-color",
+    responses(
+        (status = 200, description = "获取手动纯色成功", body = ApiResponse<Option<Vec<u8>>>),
+    ),
+    tag = "led"
+)]
+pub async fn get_manual_color() -> Result<Json<ApiResponse<Option<[u8; 3]>>>, StatusCode> {
+    let publisher = ambient_light::LedColorsPublisher::global().await;
+    let color = publisher.get_manual_color().await;
+    Ok(Json(ApiResponse::success(color)))
+}
+
+/// 设置手动纯色（持续发送，直到清除）
+#[utoipa::path(
+    put,
+    path = "/api/v1/led/manual-color",
+    request_body = SetManualColorRequest,
+    responses(
+        (status = 200, description = "设置手动纯色成功", body = ApiResponse<String>),
+        (status = 500, description = "发送失败", body = ApiResponse<String>),
+    ),
+    tag = "led"
+)]
+pub async fn set_manual_color(
+    Json(request): Json<SetManualColorRequest>,
+) -> Result<Json<ApiResponse<String>>, StatusCode> {
+    let publisher = ambient_light::LedColorsPublisher::global().await;
+    match publisher
+        .set_manual_color([request.r, request.g, request.b])
+        .await
+    {
+        Ok(_) => Ok(Json(ApiResponse::success(
+            "Manual color set successfully".to_string(),
+        ))),
+        Err(e) => {
+            log::error!("❌ Failed to send manual color: {e}");
+            Err(StatusCode::INTERNAL_SERVER_ERROR)
+        }
+    }
+}
+
+/// 清除手动纯色，恢复氛围光或停止发送
+#[utoipa::path(
+    delete,
+    path = "/api/v1/led/manual-color",
+    responses(
+        (status = 200, description = "清除手动纯色成功", body = ApiResponse<String>),
+    ),
+    tag = "led"
+)]
+pub async fn clear_manual_color() -> Result<Json<ApiResponse<String>>, StatusCode> {
+    let publisher = ambient_light::LedColorsPublisher::global().await;
+    publisher.clear_manual_color().await;
+    Ok(Json(ApiResponse::success(
+        "Manual color cleared successfully".to_string(),
+    )))
+}
+
+/// 获取各灯带在硬件数据中的偏移量和长度（按串联顺序），同时以表格形式写入日志
+///
+/// 用于排查颜色显示在错误LED上的问题，数据与实际发送时的偏移量计算一致
+#[utoipa::path(
+    get,
+    path = "/api/v1/led/strip-layout",
+    responses(
+        (status = 200, description = "获取灯带布局成功", body = ApiResponse<Vec<StripLayoutEntry>>),
+    ),
+    tag = "led"
+)]
+pub async fn get_strip_layout() -> Result<Json<ApiResponse<Vec<StripLayoutEntry>>>, StatusCode> {
+    let publisher = ambient_light::LedColorsPublisher::global().await;
+    let layout = publisher.get_strip_layout().await;
+    log::info!(
+        "LED strip layout:\n{}",
+        ambient_light::format_strip_layout(&layout)
+    );
+    Ok(Json(ApiResponse::success(layout)))
+}
+
+/// 绘制氛围光预览图：显示器边框及四周按灯带配置排列的当前LED颜色，返回 PNG 图片
+///
+/// 用于文档和远程配置时分享当前的灯效状态
+#[utoipa::path(
+    get,
+    path = "/api/v1/led/preview.png",
+    params(
+        ("width" = Option<u32>, Query, description = "图片宽度（64-3840），默认960"),
+        ("height" = Option<u32>, Query, description = "图片高度（64-2160），默认540")
+    ),
+    responses(
+        (status = 200, description = "绘制预览图成功", content_type = "image/png", body = Vec<u8>),
+        (status = 400, description = "图片尺寸无效", body = ApiResponse<String>),
+        (status = 500, description = "绘制预览图失败", body = ApiResponse<String>),
+    ),
+    tag = "led"
+)]
+pub async fn get_preview_image(
+    Query(query): Query<PreviewImageQuery>,
+) -> Result<impl IntoResponse, StatusCode> {
+    if let Err(e) = ambient_light::validate_preview_render_size(query.width, query.height) {
+        log::warn!("Invalid preview image size: {e}");
+        return Err(StatusCode::BAD_REQUEST);
+    }
+
+    let publisher = ambient_light::LedColorsPublisher::global().await;
+    match publisher
+        .render_preview_png(query.width, query.height)
+        .await
+    {
+        Ok(png) => Ok(([(header::CONTENT_TYPE, "image/png")], png)),
+        Err(e) => {
+            log::error!("Failed to render preview image: {e}");
+            Err(StatusCode::INTERNAL_SERVER_ERROR)
+        }
+    }
+}
+
+/// 获取氛围光颜色是否已冻结
+#[utoipa::path(
+    get,
+    path = "/api/v1/led/freeze",
+    responses(
+        (status = 200, description = "获取冻结状态成功", body = ApiResponse<bool>),
+    ),
+    tag = "led"
+)]
+pub async fn get_freeze() -> Result<Json<ApiResponse<bool>>, StatusCode> {
+    let publisher = ambient_light::LedColorsPublisher::global().await;
+    Ok(Json(ApiResponse::success(publisher.is_frozen().await)))
+}
+
+/// 冻结氛围光颜色，持续重发当前颜色而不采样新画面（不改变发送模式）
+#[utoipa::path(
+    post,
+    path = "/api/v1/led/freeze",
+    responses(
+        (status = 200, description = "冻结成功", body = ApiResponse<String>),
+    ),
+    tag = "led"
+)]
+pub async fn freeze_colors() -> Result<Json<ApiResponse<String>>, StatusCode> {
+    let publisher = ambient_light::LedColorsPublisher::global().await;
+    publisher.set_frozen(true).await;
+    Ok(Json(ApiResponse::success(
+        "Ambient light colors frozen".to_string(),
+    )))
+}
+
+/// 解除冻结，立即恢复实时采样
+#[utoipa::path(
+    delete,
+    path = "/api/v1/led/freeze",
+    responses(
+        (status = 200, description = "解除冻结成功", body = ApiResponse<String>),
+    ),
+    tag = "led"
+)]
+pub async fn unfreeze_colors() -> Result<Json<ApiResponse<String>>, StatusCode> {
+    let publisher = ambient_light::LedColorsPublisher::global().await;
+    publisher.set_frozen(false).await;
+    Ok(Json(ApiResponse::success(
+        "Ambient light colors unfrozen".to_string(),
+    )))
+}
+
+/// 显示校准向导当前步骤的颜色并返回向导状态
+async fn show_calibration_wizard_step(
+    wizard: &CalibrationWizard,
+) -> Result<CalibrationWizardStatus, StatusCode> {
+    let step = wizard.current_step();
+    if let Some(step) = step {
+        let [r, g, b] = step.color();
+        if let Err(e) = ambient_light::LedColorsPublisher::send_calibration_color(r, g, b).await {
+            log::error!("❌ Failed to send calibration wizard color: {e}");
+            return Err(StatusCode::INTERNAL_SERVER_ERROR);
+        }
+    }
+
+    Ok(CalibrationWizardStatus {
+        step,
+        color: step.map(CalibrationStep::color),
+        suggested_calibration: wizard.suggested_calibration(),
+    })
+}
+
+/// 开始白平衡校准向导，依次显示纯红、纯绿、纯蓝和纯白
+#[utoipa::path(
+    post,
+    path = "/api/v1/led/calibration-wizard/start",
+    responses(
+        (status = 200, description = "校准向导已开始", body = ApiResponse<CalibrationWizardStatus>),
+        (status = 500, description = "发送校准颜色失败", body = ApiResponse<String>),
+    ),
+    tag = "led"
+)]
+pub async fn start_calibration_wizard(
+) -> Result<Json<ApiResponse<CalibrationWizardStatus>>, StatusCode> {
+    let config_manager = ambient_light::ConfigManagerV2::global().await;
+    let calibration = config_manager.get_config().await.color_calibration;
+
+    let mut wizard_slot = CALIBRATION_WIZARD.lock().await;
+    // 重新开始时保留最初的模式，而不是向导自身设置的校准模式
+    let previous_mode = match wizard_slot.take() {
+        Some(active) => active.previous_mode,
+        None => LedDataSender::global().await.get_mode().await,
+    };
+    let active = wizard_slot.insert(ActiveCalibrationWizard {
+        wizard: CalibrationWizard::new(calibration),
+        previous_mode,
+    });
+    log::info!("🎨 Calibration wizard started (previous mode: {previous_mode})");
+
+    let status = show_calibration_wizard_step(&active.wizard).await?;
+    Ok(Json(ApiResponse::success(status)))
+}
+
+/// 校准向导结束后恢复开始前的LED数据发送模式
+async fn restore_mode_after_calibration_wizard(
+    previous_mode: DataSendMode,
+) -> Result<(), StatusCode> {
+    LedDataSender::global().await.set_mode(previous_mode).await;
+    if previous_mode == DataSendMode::AmbientLight {
+        // 校准颜色发送时停止了氛围光任务，需要重新启动
+        if let Err(e) = ambient_light::LedColorsPublisher::global()
+            .await
+            .restart_ambient_light_publisher()
+            .await
+        {
+            log::error!("❌ Failed to restart ambient light after calibration wizard: {e}");
+            return Err(StatusCode::INTERNAL_SERVER_ERROR);
+        }
+    }
+    log::info!("🎨 Calibration wizard ended, data send mode restored to: {previous_mode}");
+    Ok(())
+}
+
+/// 报告当前步骤看到的颜色并进入下一步，完成后返回建议的颜色校准
+#[utoipa::path(
+    post,
+    path = "/api/v1/led/calibration-wizard/report",
+    request_body = CalibrationWizardReportRequest,
+    responses(
+        (status = 200, description = "报告已记录", body = ApiResponse<CalibrationWizardStatus>),
+        (status = 409, description = "没有进行中的校准向导", body = ApiResponse<String>),
+        (status = 422, description = "无法根据报告计算校准，向导停留在当前步骤，可重新报告", body = ApiResponse<String>),
+        (status = 500, description = "发送校准颜色失败", body = ApiResponse<String>),
+    ),
+    tag = "led"
+)]
+pub async fn report_calibration_wizard_color(
+    Json(request): Json<CalibrationWizardReportRequest>,
+) -> Result<Json<ApiResponse<CalibrationWizardStatus>>, StatusCode> {
+    let mut wizard_slot = CALIBRATION_WIZARD.lock().await;
+    let active = wizard_slot.as_mut().ok_or(StatusCode::CONFLICT)?;
+
+    // 报告无法得出建议时保留向导状态，用户可以重新报告当前步骤
+    if let Err(e) = active.wizard.report([request.r, request.g, request.b]) {
+        log::warn!("Calibration wizard report rejected: {e}");
+        return Err(StatusCode::UNPROCESSABLE_ENTITY);
+    }
+
+    let status = show_calibration_wizard_step(&active.wizard).await?;
+    if let Some(calibration) = status.suggested_calibration {
+        log::info!(
+            "✅ Calibration wizard finished: r={:.3}, g={:.3}, b={:.3}",
+            calibration.r,
+            calibration.g,
+            calibration.b
+        );
+        let previous_mode = active.previous_mode;
+        wizard_slot.take();
+        restore_mode_after_calibration_wizard(previous_mode).await?;
+    }
+
+    Ok(Json(ApiResponse::success(status)))
+}
+
+/// 取消进行中的白平衡校准向导，恢复开始前的LED数据发送模式
+#[utoipa::path(
+    post,
+    path = "/api/v1/led/calibration-wizard/cancel",
+    responses(
+        (status = 200, description = "校准向导已取消", body = ApiResponse<String>),
+        (status = 409, description = "没有进行中的校准向导", body = ApiResponse<String>),
+        (status = 500, description = "恢复发送模式失败", body = ApiResponse<String>),
+    ),
+    tag = "led"
+)]
+pub async fn cancel_calibration_wizard() -> Result<Json<ApiResponse<String>>, StatusCode> {
+    let active = CALIBRATION_WIZARD
+        .lock()
+        .await
+        .take()
+        .ok_or(StatusCode::CONFLICT)?;
+
+    restore_mode_after_calibration_wizard(active.previous_mode).await?;
+    Ok(Json(ApiResponse::success(
+        "Calibration wizard cancelled".to_string(),
+    )))
+}
+
+/// 将请求中的录制名称解析为录制目录中的路径，名称无效或指向目录外时返回 400
+fn recording_path_from_name(name: &str) -> Result<std::path::PathBuf, StatusCode> {
+    if let Err(e) = ambient_light::validate_recording_name(name) {
+        log::warn!("Rejected frame recording request: {e}");
+        return Err(StatusCode::BAD_REQUEST);
+    }
+    ambient_light::recording_path(name).map_err(|e| {
+        log::warn!("Rejected frame recording request: {e}");
+        StatusCode::BAD_REQUEST
+    })
+}
+
+/// 开始录制颜色帧到配置目录下 recordings 目录中的文件
+///
+/// 录制文件超过 256 MiB 或录制超过 30 分钟后不再写入新帧
+#[utoipa::path(
+    post,
+    path = "/api/v1/led/frame-recording/start",
+    request_body = FrameRecordingRequest,
+    responses(
+        (status = 200, description = "开始录制成功", body = ApiResponse<String>),
+        (status = 400, description = "录制名称无效", body = ApiResponse<String>),
+        (status = 500, description = "无法创建录制文件", body = ApiResponse<String>),
+    ),
+    tag = "led"
+)]
+pub async fn start_frame_recording(
+    Json(request): Json<FrameRecordingRequest>,
+) -> Result<Json<ApiResponse<String>>, StatusCode> {
+    let path = recording_path_from_name(&request.name)?;
+    let publisher = ambient_light::LedColorsPublisher::global().await;
+    match publisher.record_frames(path).await {
+        Ok(_) => Ok(Json(ApiResponse::success(
+            "Frame recording started".to_string(),
+        ))),
+        Err(e) => {
+            log::error!("Failed to start frame recording: {e}");
+            Err(StatusCode::INTERNAL_SERVER_ERROR)
+        }
+    }
+}
+
+/// 停止录制颜色帧，返回录制的帧数
+#[utoipa::path(
+    post,
+    path = "/api/v1/led/frame-recording/stop",
+    responses(
+        (status = 200, description = "停止录制成功", body = ApiResponse<usize>),
+        (status = 409, description = "当前没有进行中的录制", body = ApiResponse<String>),
+    ),
+    tag = "led"
+)]
+pub async fn stop_frame_recording() -> Result<Json<ApiResponse<usize>>, StatusCode> {
+    let publisher = ambient_light::LedColorsPublisher::global().await;
+    match publisher.stop_recording().await {
+        Ok(frame_count) => Ok(Json(ApiResponse::success(frame_count))),
+        Err(e) => {
+            log::warn!("Failed to stop frame recording: {e}");
+            Err(StatusCode::CONFLICT)
+        }
+    }
+}
+
+/// 回放录制的颜色帧，返回帧数
+#[utoipa::path(
+    post,
+    path = "/api/v1/led/frame-replay",
+    request_body = FrameRecordingRequest,
+    responses(
+        (status = 200, description = "开始回放成功", body = ApiResponse<usize>),
+        (status = 400, description = "录制名称或录制文件无效", body = ApiResponse<String>),
+    ),
+    tag = "led"
+)]
+pub async fn replay_frames(
+    Json(request): Json<FrameRecordingRequest>,
+) -> Result<Json<ApiResponse<usize>>, StatusCode> {
+    let path = recording_path_from_name(&request.name)?;
+    let publisher = ambient_light::LedColorsPublisher::global().await;
+    match publisher.replay_frames(path).await {
+        Ok(frame_count) => Ok(Json(ApiResponse::success(frame_count))),
+        Err(e) => {
+            log::error!("Failed to replay frames: {e}");
+            Err(StatusCode::BAD_REQUEST)
+        }
+    }
+}
+
 /// 创建LED控制相关路由
 pub fn create_routes() -> Router<AppState> {
     Router::new()
@@ -624,6 +1538,15 @@ pub fn create_routes() -> Router<AppState> {
         .route("/current-colors", get(get_current_led_colors))
         .route("/colors", post(send_colors))
         .route("/calibration-color", post(send_calibration_color))
+        .route("/calibration-wizard/start", post(start_calibration_wizard))
+        .route(
+            "/calibration-wizard/report",
+            post(report_calibration_wizard_color),
+        )
+        .route(
+            "/calibration-wizard/cancel",
+            post(cancel_calibration_wizard),
+        )
         .route("/test-colors", post(send_test_colors_to_board))
         .route("/mode", get(get_data_send_mode))
         .route("/mode", put(set_data_send_mode))
@@ -648,6 +1571,12 @@ pub fn create_routes() -> Router<AppState> {
         )
         .route("/start-test-effect", post(start_led_test_effect))
         .route("/stop-test-effect", post(stop_led_test_effect))
+        .route("/test-all", post(start_all_boards_test_effect))
+        .route("/test-all/stop", post(stop_all_boards_test_effect))
+        .route("/length-detection/start", post(start_length_detection))
+        .route("/length-detection/advance", post(advance_length_detection))
+        .route("/length-detection/mark", post(mark_length_detection_corner))
+        .route("/length-detection/apply", post(apply_length_detection))
         .route(
             "/test-single-display-config",
             post(test_single_display_config),
@@ -655,4 +1584,46 @@ pub fn create_routes() -> Router<AppState> {
         .route("/test-data-sender", post(test_led_data_sender))
         .route("/preview-state", get(get_led_preview_state))
         .route("/preview-state", put(set_led_preview_state))
+        .route("/preview-color-vision", get(get_preview_color_vision))
+        .route("/preview-color-vision", put(set_preview_color_vision))
+        .route("/preview-calibration", get(get_preview_calibration))
+        .route("/preview-calibration", put(set_preview_calibration))
+        .route("/preview-calibration", delete(clear_preview_calibration))
+        .route("/manual-color", get(get_manual_color))
+        .route("/manual-color", put(set_manual_color))
+        .route("/manual-color", delete(clear_manual_color))
+        .route("/strip-layout", get(get_strip_layout))
+        .route("/preview.png", get(get_preview_image))
+        .route("/freeze", get(get_freeze))
+        .route("/freeze", post(freeze_colors))
+        .route("/freeze", delete(unfreeze_colors))
+        .route("/frame-recording/start", post(start_frame_recording))
+        .route("/frame-recording/stop", post(stop_frame_recording))
+        .route("/frame-replay", post(replay_frames))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[tokio::test]
+    async fn test_frame_recording_rejects_paths_outside_recordings_dir() {
+        for name in ["../x", "/etc/passwd", "a/b"] {
+            let request = || {
+                Json(FrameRecordingRequest {
+                    name: name.to_string(),
+                })
+            };
+            assert_eq!(
+                start_frame_recording(request()).await.err(),
+                Some(StatusCode::BAD_REQUEST),
+                "recording {name:?} should be rejected"
+            );
+            assert_eq!(
+                replay_frames(request()).await.err(),
+                Some(StatusCode::BAD_REQUEST),
+                "replay {name:?} should be rejected"
+            );
+        }
+    }
 }
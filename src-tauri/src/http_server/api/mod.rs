@@ -5,3 +5,4 @@ pub mod general;
 pub mod health;
 pub mod info;
 pub mod led;
+pub mod profile;
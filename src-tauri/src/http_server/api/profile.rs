@@ -0,0 +1,251 @@
+use axum::{
+    extract::Path,
+    http::StatusCode,
+    response::Json,
+    routing::{delete, get, post, put},
+    Router,
+};
+use serde::Deserialize;
+use std::collections::BTreeMap;
+use utoipa::ToSchema;
+
+use crate::{
+    http_server::{ApiResponse, AppState},
+    profile_manager::{
+        validate_profile_name, Profile, ProfileActivation, ProfileManager, ProfileSchedule,
+    },
+};
+
+/// 保存情景模式请求
+#[derive(Deserialize, ToSchema)]
+pub struct SaveProfileRequest {
+    /// 情景模式名称，同名情景模式被覆盖
+    pub name: String,
+}
+
+/// 获取所有情景模式
+#[utoipa::path(
+    get,
+    path = "/api/v1/profile/profiles",
+    responses(
+        (status = 200, description = "获取情景模式成功（按名称索引）", body = ApiResponse<BTreeMap<String, Profile>>),
+    ),
+    tag = "profile"
+)]
+pub async fn get_profiles() -> Result<Json<ApiResponse<BTreeMap<String, Profile>>>, StatusCode> {
+    let profiles = ProfileManager::global().await.get_profiles().await;
+    Ok(Json(ApiResponse::success(profiles)))
+}
+
+/// 将当前的颜色校准和环境光开关状态保存为命名的情景模式
+#[utoipa::path(
+    post,
+    path = "/api/v1/profile/profiles",
+    request_body = SaveProfileRequest,
+    responses(
+        (status = 200, description = "保存情景模式成功", body = ApiResponse<Profile>),
+        (status = 400, description = "情景模式名称无效", body = ApiResponse<String>),
+        (status = 500, description = "保存失败", body = ApiResponse<String>),
+    ),
+    tag = "profile"
+)]
+pub async fn save_profile(
+    Json(request): Json<SaveProfileRequest>,
+) -> Result<Json<ApiResponse<Profile>>, StatusCode> {
+    if let Err(e) = validate_profile_name(&request.name) {
+        log::warn!("Invalid profile name: {e}");
+        return Err(StatusCode::BAD_REQUEST);
+    }
+
+    match ProfileManager::global()
+        .await
+        .save_current_profile(&request.name)
+        .await
+    {
+        Ok(profile) => Ok(Json(ApiResponse::success(profile))),
+        Err(e) => {
+            log::error!("Failed to save profile {:?}: {e}", request.name);
+            Err(StatusCode::INTERNAL_SERVER_ERROR)
+        }
+    }
+}
+
+/// 删除情景模式，引用它的定时规则保留，触发时跳过
+#[utoipa::path(
+    delete,
+    path = "/api/v1/profile/profiles/{name}",
+    params(
+        ("name" = String, Path, description = "情景模式名称")
+    ),
+    responses(
+        (status = 200, description = "删除情景模式成功", body = ApiResponse<String>),
+        (status = 404, description = "情景模式不存在", body = ApiResponse<String>),
+        (status = 500, description = "删除失败", body = ApiResponse<String>),
+    ),
+    tag = "profile"
+)]
+pub async fn delete_profile(
+    Path(name): Path<String>,
+) -> Result<Json<ApiResponse<String>>, StatusCode> {
+    match ProfileManager::global().await.delete_profile(&name).await {
+        Ok(true) => Ok(Json(ApiResponse::success(format!(
+            "Profile {name} deleted"
+        )))),
+        Ok(false) => Err(StatusCode::NOT_FOUND),
+        Err(e) => {
+            log::error!("Failed to delete profile {name:?}: {e}");
+            Err(StatusCode::INTERNAL_SERVER_ERROR)
+        }
+    }
+}
+
+/// 立即激活情景模式，与定时规则触发时走同一路径并广播 `ProfileActivated` 事件
+#[utoipa::path(
+    post,
+    path = "/api/v1/profile/profiles/{name}/activate",
+    params(
+        ("name" = String, Path, description = "情景模式名称")
+    ),
+    responses(
+        (status = 200, description = "激活情景模式成功", body = ApiResponse<ProfileActivation>),
+        (status = 404, description = "情景模式不存在", body = ApiResponse<String>),
+        (status = 500, description = "激活失败", body = ApiResponse<String>),
+    ),
+    tag = "profile"
+)]
+pub async fn activate_profile(
+    Path(name): Path<String>,
+) -> Result<Json<ApiResponse<ProfileActivation>>, StatusCode> {
+    match ProfileManager::global()
+        .await
+        .activate_profile(&name, None)
+        .await
+    {
+        Ok(Some(activation)) => Ok(Json(ApiResponse::success(activation))),
+        Ok(None) => Err(StatusCode::NOT_FOUND),
+        Err(e) => {
+            log::error!("Failed to activate profile {name:?}: {e}");
+            Err(StatusCode::INTERNAL_SERVER_ERROR)
+        }
+    }
+}
+
+/// 获取所有定时规则
+#[utoipa::path(
+    get,
+    path = "/api/v1/profile/schedules",
+    responses(
+        (status = 200, description = "获取定时规则成功", body = ApiResponse<Vec<ProfileSchedule>>),
+    ),
+    tag = "profile"
+)]
+pub async fn get_schedules() -> Result<Json<ApiResponse<Vec<ProfileSchedule>>>, StatusCode> {
+    let schedules = ProfileManager::global().await.get_schedules().await;
+    Ok(Json(ApiResponse::success(schedules)))
+}
+
+/// 创建定时规则，返回分配了ID的规则
+#[utoipa::path(
+    post,
+    path = "/api/v1/profile/schedules",
+    request_body = ProfileSchedule,
+    responses(
+        (status = 200, description = "创建定时规则成功", body = ApiResponse<ProfileSchedule>),
+        (status = 400, description = "规则无效或情景模式不存在", body = ApiResponse<String>),
+        (status = 500, description = "保存失败", body = ApiResponse<String>),
+    ),
+    tag = "profile"
+)]
+pub async fn create_schedule(
+    Json(schedule): Json<ProfileSchedule>,
+) -> Result<Json<ApiResponse<ProfileSchedule>>, StatusCode> {
+    let manager = ProfileManager::global().await;
+    if let Err(e) = manager.validate_schedule(&schedule).await {
+        log::warn!("Invalid profile schedule: {e}");
+        return Err(StatusCode::BAD_REQUEST);
+    }
+
+    match manager.create_schedule(schedule).await {
+        Ok(schedule) => Ok(Json(ApiResponse::success(schedule))),
+        Err(e) => {
+            log::error!("Failed to create profile schedule: {e}");
+            Err(StatusCode::INTERNAL_SERVER_ERROR)
+        }
+    }
+}
+
+/// 更新定时规则
+#[utoipa::path(
+    put,
+    path = "/api/v1/profile/schedules/{id}",
+    params(
+        ("id" = u32, Path, description = "规则ID")
+    ),
+    request_body = ProfileSchedule,
+    responses(
+        (status = 200, description = "更新定时规则成功", body = ApiResponse<ProfileSchedule>),
+        (status = 400, description = "规则无效或情景模式不存在", body = ApiResponse<String>),
+        (status = 404, description = "规则不存在", body = ApiResponse<String>),
+        (status = 500, description = "保存失败", body = ApiResponse<String>),
+    ),
+    tag = "profile"
+)]
+pub async fn update_schedule(
+    Path(id): Path<u32>,
+    Json(schedule): Json<ProfileSchedule>,
+) -> Result<Json<ApiResponse<ProfileSchedule>>, StatusCode> {
+    let manager = ProfileManager::global().await;
+    if let Err(e) = manager.validate_schedule(&schedule).await {
+        log::warn!("Invalid profile schedule: {e}");
+        return Err(StatusCode::BAD_REQUEST);
+    }
+
+    match manager.update_schedule(id, schedule).await {
+        Ok(Some(schedule)) => Ok(Json(ApiResponse::success(schedule))),
+        Ok(None) => Err(StatusCode::NOT_FOUND),
+        Err(e) => {
+            log::error!("Failed to update profile schedule {id}: {e}");
+            Err(StatusCode::INTERNAL_SERVER_ERROR)
+        }
+    }
+}
+
+/// 删除定时规则
+#[utoipa::path(
+    delete,
+    path = "/api/v1/profile/schedules/{id}",
+    params(
+        ("id" = u32, Path, description = "规则ID")
+    ),
+    responses(
+        (status = 200, description = "删除定时规则成功", body = ApiResponse<String>),
+        (status = 404, description = "规则不存在", body = ApiResponse<String>),
+        (status = 500, description = "删除失败", body = ApiResponse<String>),
+    ),
+    tag = "profile"
+)]
+pub async fn delete_schedule(Path(id): Path<u32>) -> Result<Json<ApiResponse<String>>, StatusCode> {
+    match ProfileManager::global().await.delete_schedule(id).await {
+        Ok(true) => Ok(Json(ApiResponse::success(format!(
+            "Profile schedule {id} deleted"
+        )))),
+        Ok(false) => Err(StatusCode::NOT_FOUND),
+        Err(e) => {
+            log::error!("Failed to delete profile schedule {id}: {e}");
+            Err(StatusCode::INTERNAL_SERVER_ERROR)
+        }
+    }
+}
+
+/// 创建情景模式相关路由
+pub fn create_routes() -> Router<AppState> {
+    Router::new()
+        .route("/profiles", get(get_profiles).post(save_profile))
+        .route("/profiles/:name", delete(delete_profile))
+        .route("/profiles/:name/activate", post(activate_profile))
+        .route("/schedules", get(get_schedules).post(create_schedule))
+        .route(
+            "/schedules/:id",
+            put(update_schedule).delete(delete_schedule),
+        )
+}
@@ -0,0 +1,199 @@
+use std::net::SocketAddr;
+
+use axum::{
+    extract::{ConnectInfo, Request, State},
+    http::{header, HeaderValue, Method, StatusCode},
+    middleware::Next,
+    response::{IntoResponse, Json, Response},
+};
+
+use super::ApiResponse;
+
+/// HTTP 与 WebSocket 服务器的令牌鉴权配置
+#[derive(Debug, Clone, Default)]
+pub struct AuthConfig {
+    /// 访问令牌，为空时不鉴权
+    pub token: Option<String>,
+    /// 来自回环地址的请求免鉴权
+    pub exempt_loopback: bool,
+}
+
+impl AuthConfig {
+    /// 是否配置了访问令牌
+    pub fn is_enabled(&self) -> bool {
+        self.token.as_deref().is_some_and(|token| !token.is_empty())
+    }
+
+    /// 校验请求是否已授权
+    ///
+    /// 令牌可以通过 `Authorization: Bearer <token>` 请求头或 `token` 查询参数提供
+    /// （浏览器的 WebSocket API 无法设置请求头）。对端地址未知时不视为回环地址。
+    ///
+    /// 参数为原始字符串，以便 axum 与 tungstenite（使用不同版本的 `http` 类型）共用
+    pub fn is_authorized(
+        &self,
+        peer: Option<SocketAddr>,
+        authorization: Option<&str>,
+        query: Option<&str>,
+    ) -> bool {
+        let Some(expected) = self.token.as_deref().filter(|token| !token.is_empty()) else {
+            return true;
+        };
+
+        if self.exempt_loopback && peer.is_some_and(|peer| peer.ip().is_loopback()) {
+            return true;
+        }
+
+        let bearer = authorization
+            .and_then(|value| value.strip_prefix("Bearer "))
+            .map(str::trim);
+        let query_token = query.and_then(|query| {
+            query
+                .split('&')
+                .find_map(|pair| pair.strip_prefix("token="))
+        });
+
+        [bearer, query_token]
+            .into_iter()
+            .flatten()
+            .any(|provided| constant_time_eq(provided.as_bytes(), expected.as_bytes()))
+    }
+}
+
+/// 常量时间比较，避免通过响应时间推测令牌
+fn constant_time_eq(a: &[u8], b: &[u8]) -> bool {
+    if a.len() != b.len() {
+        return false;
+    }
+    a.iter().zip(b).fold(0u8, |diff, (x, y)| diff | (x ^ y)) == 0
+}
+
+/// axum 鉴权中间件，未授权的请求返回 401
+///
+/// CORS 预检请求（OPTIONS）不携带令牌，直接放行
+pub async fn require_token(
+    State(auth): State<AuthConfig>,
+    request: Request,
+    next: Next,
+) -> Response {
+    if request.method() == Method::OPTIONS {
+        return next.run(request).await;
+    }
+
+    let peer = request
+        .extensions()
+        .get::<ConnectInfo<SocketAddr>>()
+        .map(|ConnectInfo(addr)| *addr);
+
+    let authorization = request
+        .headers()
+        .get(header::AUTHORIZATION)
+        .and_then(|value| value.to_str().ok());
+    if auth.is_authorized(peer, authorization, request.uri().query()) {
+        return next.run(request).await;
+    }
+
+    log::warn!(
+        "🔒 Rejected unauthorized request {} {} from {:?}",
+        request.method(),
+        request.uri().path(),
+        peer
+    );
+    let mut response = (
+        StatusCode::UNAUTHORIZED,
+        Json(ApiResponse::<String>::error("Unauthorized".to_string())),
+    )
+        .into_response();
+    response
+        .headers_mut()
+        .insert(header::WWW_AUTHENTICATE, HeaderValue::from_static("Bearer"));
+    response
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use axum::{body::Body, middleware, routing::get, Router};
+    use tower::ServiceExt;
+
+    fn app(auth: AuthConfig) -> Router {
+        Router::new()
+            .route("/ping", get(|| async { "pong" }))
+            .layer(middleware::from_fn_with_state(auth, require_token))
+    }
+
+    fn request(uri: &str, token: Option<&str>, peer: Option<&str>) -> Request {
+        let mut builder = Request::builder().uri(uri);
+        if let Some(token) = token {
+            builder = builder.header(header::AUTHORIZATION, format!("Bearer {token}"));
+        }
+        let mut request = builder.body(Body::empty()).unwrap();
+        if let Some(peer) = peer {
+            let addr: SocketAddr = peer.parse().unwrap();
+            request.extensions_mut().insert(ConnectInfo(addr));
+        }
+        request
+    }
+
+    async fn status(auth: &AuthConfig, request: Request) -> StatusCode {
+        app(auth.clone()).oneshot(request).await.unwrap().status()
+    }
+
+    #[tokio::test]
+    async fn test_token_required_when_configured() {
+        let auth = AuthConfig {
+            token: Some("secret".to_string()),
+            exempt_loopback: false,
+        };
+        let lan = Some("192.168.1.20:50000");
+
+        assert_eq!(
+            status(&auth, request("/ping", None, lan)).await,
+            StatusCode::UNAUTHORIZED
+        );
+        assert_eq!(
+            status(&auth, request("/ping", Some("wrong"), lan)).await,
+            StatusCode::UNAUTHORIZED
+        );
+        assert_eq!(
+            status(&auth, request("/ping", Some("secret"), lan)).await,
+            StatusCode::OK
+        );
+        assert_eq!(
+            status(&auth, request("/ping?token=secret", None, lan)).await,
+            StatusCode::OK
+        );
+        // 未开启回环豁免时本机请求同样需要令牌
+        assert_eq!(
+            status(&auth, request("/ping", None, Some("127.0.0.1:50000"))).await,
+            StatusCode::UNAUTHORIZED
+        );
+    }
+
+    #[tokio::test]
+    async fn test_loopback_exemption_and_disabled_auth() {
+        let auth = AuthConfig {
+            token: Some("secret".to_string()),
+            exempt_loopback: true,
+        };
+        assert_eq!(
+            status(&auth, request("/ping", None, Some("127.0.0.1:50000"))).await,
+            StatusCode::OK
+        );
+        assert_eq!(
+            status(&auth, request("/ping", None, Some("192.168.1.20:50000"))).await,
+            StatusCode::UNAUTHORIZED
+        );
+        // 对端地址未知时不豁免
+        assert_eq!(
+            status(&auth, request("/ping", None, None)).await,
+            StatusCode::UNAUTHORIZED
+        );
+
+        // 未配置令牌时不鉴权
+        assert_eq!(
+            status(&AuthConfig::default(), request("/ping", None, None)).await,
+            StatusCode::OK
+        );
+    }
+}
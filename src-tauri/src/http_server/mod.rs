@@ -1,13 +1,22 @@
-use axum::{http::Method, routing::get, Router};
+use axum::{
+    http::{Method, StatusCode},
+    middleware,
+    routing::get,
+    Json, Router,
+};
 use serde::Serialize;
+use std::net::{IpAddr, SocketAddr};
 use tower_http::cors::{Any, CorsLayer};
 use tower_http::services::ServeDir;
 use utoipa::{OpenApi, ToSchema};
 use utoipa_swagger_ui::SwaggerUi;
 
 pub mod api;
+pub mod auth;
 pub mod websocket;
 
+use auth::AuthConfig;
+
 /// HTTP服务器配置
 #[derive(Debug, Clone)]
 pub struct ServerConfig {
@@ -16,6 +25,7 @@ pub struct ServerConfig {
     pub enable_cors: bool,
     pub serve_static_files: bool,
     pub static_files_path: Option<String>,
+    pub auth: AuthConfig,
 }
 
 impl Default for ServerConfig {
@@ -26,10 +36,67 @@ impl Default for ServerConfig {
             enable_cors: true,
             serve_static_files: false,
             static_files_path: None,
+            auth: AuthConfig::default(),
+        }
+    }
+}
+
+impl ServerConfig {
+    /// 解析并校验监听地址
+    pub fn bind_addr(&self) -> anyhow::Result<SocketAddr> {
+        resolve_bind_addr(&self.host, self.port)
+    }
+}
+
+/// WebSocket服务器（屏幕流）配置
+#[derive(Debug, Clone)]
+pub struct WebSocketServerConfig {
+    pub host: String,
+    pub port: u16,
+    pub auth: AuthConfig,
+}
+
+impl Default for WebSocketServerConfig {
+    fn default() -> Self {
+        Self {
+            host: "127.0.0.1".to_string(),
+            port: 24102,
+            auth: AuthConfig::default(),
         }
     }
 }
 
+impl WebSocketServerConfig {
+    /// 解析并校验监听地址
+    pub fn bind_addr(&self) -> anyhow::Result<SocketAddr> {
+        resolve_bind_addr(&self.host, self.port)
+    }
+}
+
+/// 将监听主机和端口解析为套接字地址
+///
+/// 主机必须是IP地址（如 `127.0.0.1`、`0.0.0.0`、`::1`），不解析主机名；端口不能为0
+pub fn resolve_bind_addr(host: &str, port: u16) -> anyhow::Result<SocketAddr> {
+    let ip: IpAddr = host.trim().parse().map_err(|_| {
+        anyhow::anyhow!(
+            "Invalid bind address '{host}': expected an IP address such as 127.0.0.1 or 0.0.0.0"
+        )
+    })?;
+    if port == 0 {
+        anyhow::bail!("Invalid bind port for {host}: port must not be 0");
+    }
+    Ok(SocketAddr::new(ip, port))
+}
+
+/// 未配置访问令牌却监听非回环地址时发出警告：局域网内任何人都可以控制灯光和读取屏幕画面
+pub fn warn_if_exposed(name: &str, addr: SocketAddr, auth: &AuthConfig) {
+    if !addr.ip().is_loopback() && !auth.is_enabled() {
+        log::warn!(
+            "⚠️ {name} is listening on non-loopback address {addr} without an auth token; anyone who can reach this address can control the lights and read screen captures"
+        );
+    }
+}
+
 /// 应用状态，包含所有共享资源
 #[derive(Clone)]
 pub struct AppState {
@@ -70,13 +137,36 @@ pub struct ApiError {
     pub message: String,
 }
 
+/// 带HTTP状态码的错误响应，响应体为 [`ApiError`]
+pub type ApiErrorResponse = (StatusCode, Json<ApiError>);
+
 impl ApiError {
+    /// 指定的显示器不存在或未连接
+    pub const DISPLAY_NOT_FOUND: &'static str = "DISPLAY_NOT_FOUND";
+    /// 指定显示器的该边框上没有灯带
+    pub const STRIP_NOT_FOUND: &'static str = "STRIP_NOT_FOUND";
+    /// 配置保存或应用失败
+    pub const CONFIG_UPDATE_FAILED: &'static str = "CONFIG_UPDATE_FAILED";
+    /// 请求参数校验失败
+    pub const INVALID_REQUEST: &'static str = "INVALID_REQUEST";
+    /// 指定的颜色校准预设不存在
+    pub const PRESET_NOT_FOUND: &'static str = "PRESET_NOT_FOUND";
+    /// 指定的输出端不存在
+    pub const SINK_NOT_FOUND: &'static str = "SINK_NOT_FOUND";
+    /// 超时时间内没有使用新颜色校准的帧
+    pub const CALIBRATION_CONFIRM_TIMEOUT: &'static str = "CALIBRATION_CONFIRM_TIMEOUT";
+
     pub fn new(code: &str, message: &str) -> Self {
         Self {
             code: code.to_string(),
             message: message.to_string(),
         }
     }
+
+    /// 构造带状态码的错误响应
+    pub fn response(status: StatusCode, code: &str, message: &str) -> ApiErrorResponse {
+        (status, Json(Self::new(code, message)))
+    }
 }
 
 /// OpenAPI文档定义
@@ -88,6 +178,7 @@ impl ApiError {
         api::general::ping,
         api::info::get_app_version,
         api::info::get_system_info,
+        api::info::get_websocket_clients,
         api::info::report_current_page,
         api::info::report_page,
         api::info::navigate_to_page,
@@ -107,13 +198,40 @@ impl ApiError {
         api::led::set_active_strip_breathing,
         api::led::start_led_test_effect,
         api::led::stop_led_test_effect,
+        api::led::start_all_boards_test_effect,
+        api::led::stop_all_boards_test_effect,
+        api::led::start_length_detection,
+        api::led::advance_length_detection,
+        api::led::mark_length_detection_corner,
+        api::led::apply_length_detection,
         api::led::test_single_display_config,
         api::led::test_led_data_sender,
+        api::led::get_preview_color_vision,
+        api::led::set_preview_color_vision,
+        api::led::get_preview_calibration,
+        api::led::set_preview_calibration,
+        api::led::clear_preview_calibration,
+        api::led::get_manual_color,
+        api::led::set_manual_color,
+        api::led::clear_manual_color,
+        api::led::get_strip_layout,
+        api::led::get_preview_image,
+        api::led::get_freeze,
+        api::led::freeze_colors,
+        api::led::unfreeze_colors,
+        api::led::start_calibration_wizard,
+        api::led::report_calibration_wizard_color,
+        api::led::cancel_calibration_wizard,
+        api::led::start_frame_recording,
+        api::led::stop_frame_recording,
+        api::led::replay_frames,
         // v1 接口直接使用 v2 语义
         api::config::get_led_strip_configs_v2,
         api::config::update_led_strip_configs_v2,
         api::config::update_led_strip_length,
         api::config::update_led_strip_type,
+        api::config::update_led_strip_enabled,
+        api::config::reset_config,
         api::config::get_user_preferences,
         api::config::update_user_preferences,
         api::config::update_window_preferences,
@@ -122,16 +240,105 @@ impl ApiError {
         api::config::update_theme,
         api::config::get_view_scale,
         api::config::update_view_scale,
+        api::config::get_sample_points_per_led,
+        api::config::update_sample_points_per_led,
+        api::config::get_black_level,
+        api::config::update_black_level,
+        api::config::get_interpolation_strength,
+        api::config::update_interpolation_strength,
+        api::config::get_averaging_color_space,
+        api::config::update_averaging_color_space,
+        api::config::get_bilinear_sampling,
+        api::config::update_bilinear_sampling,
+        api::config::get_vertical_gradient_sampling,
+        api::config::update_vertical_gradient_sampling,
+        api::config::get_frame_sync_mode,
+        api::config::update_frame_sync_mode,
+        api::config::get_sampling_strategy,
+        api::config::update_sampling_strategy,
+        api::config::get_display_sleep_policy,
+        api::config::update_display_sleep_policy,
+        api::config::get_color_count_policy,
+        api::config::update_color_count_policy,
+        api::config::get_fullscreen_capture,
+        api::config::update_fullscreen_capture,
+        api::config::get_capture_backends,
+        api::config::update_capture_backend,
+        api::config::get_auto_brightness,
+        api::config::update_auto_brightness,
+        api::config::get_cursor_focus,
+        api::config::update_cursor_focus,
+        api::config::get_color_temperature_shift,
+        api::config::update_color_temperature_shift,
+        api::config::get_palette_quantization,
+        api::config::update_palette_quantization,
+        api::config::get_capture_failure,
+        api::config::update_capture_failure,
+        api::config::get_output,
+        api::config::update_output,
+        api::config::list_output_sinks,
+        api::config::add_output_sink,
+        api::config::remove_output_sink,
+        api::config::get_preview_scaling,
+        api::config::update_preview_scaling,
+        api::config::get_webhook,
+        api::config::update_webhook,
+        api::config::confirm_global_color_calibration,
+        api::config::get_calibration_curve,
+        api::config::get_calibration_presets,
+        api::config::save_calibration_preset,
+        api::config::apply_calibration_preset,
+        api::config::delete_calibration_preset,
         api::config::get_night_mode_theme_enabled,
         api::config::get_night_mode_theme,
         api::config::get_current_language,
         api::display::get_displays,
         api::display::list_display_info,
+        api::display::get_display_bindings,
+        api::display::bind_strips_to_display,
+        api::display::get_strip_spans,
+        api::display::set_strip_span,
+        api::display::get_sample_regions,
         api::display::get_display_colors,
+        api::display::get_display_average_color,
+        api::display::sample_display_now,
+        api::display::get_display_mirror_source,
+        api::display::set_display_mirror_source,
+        api::display::get_display_crop,
+        api::display::set_display_crop,
+        api::display::clear_display_crop,
+        api::display::get_display_scale_factor,
+        api::display::set_display_scale_factor,
+        api::display::clear_display_scale_factor,
+        api::display::get_display_frame_rate,
+        api::display::set_display_frame_rate,
+        api::display::clear_display_frame_rate,
+        api::display::get_display_winding,
+        api::display::set_display_winding,
+        api::display::get_display_border_mask,
+        api::display::set_display_border_mask,
+        api::display::get_display_capture_format,
+        api::display::set_display_capture_format,
         api::device::get_boards,
+        api::device::identify_board,
+        api::device::get_board_health,
+        api::device::get_board_self_check,
+        api::device::get_logs,
+        api::device::get_log_level,
+        api::device::set_log_level,
+        api::device::get_board_configs,
+        api::device::set_board_config,
         api::device::get_auto_start_status,
         api::device::set_auto_start_status,
         api::device::get_ambient_light_state,
+        api::profile::get_profiles,
+        api::profile::save_profile,
+        api::profile::delete_profile,
+        api::profile::activate_profile,
+        api::profile::get_schedules,
+        api::profile::create_schedule,
+        api::profile::update_schedule,
+        api::profile::delete_schedule,
     ),
     components(
         schemas(
@@ -149,6 +356,7 @@ impl ApiError {
         (name = "led", description = "LED控制相关API"),
         (name = "display", description = "显示器相关API"),
         (name = "device", description = "设备管理相关API"),
+        (name = "profile", description = "情景模式与定时激活相关API"),
     ),
     info(
         title = "Ambient Light Control API",
@@ -212,6 +420,10 @@ pub async fn create_server(config: ServerConfig) -> Result<Router, anyhow::Error
 
     let app = app
         // 中间件
+        .layer(middleware::from_fn_with_state(
+            config.auth.clone(),
+            auth::require_token,
+        ))
         .layer(tower_http::trace::TraceLayer::new_for_http())
         .layer(cors)
         .with_state(app_state);
@@ -234,19 +446,50 @@ fn create_api_routes() -> Router<AppState> {
         .nest("/display", api::display::create_routes())
         // 设备管理
         .nest("/device", api::device::create_routes())
+        // 情景模式与定时激活
+        .nest("/profile", api::profile::create_routes())
 }
 
 /// 启动HTTP服务器
 pub async fn start_server(config: ServerConfig) -> Result<(), anyhow::Error> {
     let app = create_server(config.clone()).await?;
 
-    let addr = format!("{}:{}", config.host, config.port);
-    let listener = tokio::net::TcpListener::bind(&addr).await?;
+    let addr = config.bind_addr()?;
+    warn_if_exposed("HTTP server", addr, &config.auth);
+    let listener = tokio::net::TcpListener::bind(addr)
+        .await
+        .map_err(|e| anyhow::anyhow!("Failed to bind HTTP server to {addr}: {e}"))?;
 
     log::info!("🚀 HTTP服务器启动在 http://{addr}");
     log::info!("📚 API文档地址: http://{addr}/swagger-ui");
 
-    axum::serve(listener, app).await?;
+    // 鉴权中间件需要对端地址来判断回环豁免
+    axum::serve(
+        listener,
+        app.into_make_service_with_connect_info::<SocketAddr>(),
+    )
+    .await?;
 
     Ok(())
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_resolve_bind_addr() {
+        assert_eq!(
+            resolve_bind_addr("0.0.0.0", 24101).unwrap(),
+            "0.0.0.0:24101".parse().unwrap()
+        );
+        assert_eq!(
+            resolve_bind_addr("::1", 24102).unwrap(),
+            "[::1]:24102".parse().unwrap()
+        );
+
+        assert!(resolve_bind_addr("localhost", 24101).is_err());
+        assert!(resolve_bind_addr("192.168.1", 24101).is_err());
+        assert!(resolve_bind_addr("127.0.0.1", 0).is_err());
+    }
+}
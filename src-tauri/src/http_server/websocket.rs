@@ -8,15 +8,21 @@ use axum::{
 use futures::{sink::SinkExt, stream::StreamExt};
 use serde::{Deserialize, Serialize};
 use std::{
-    collections::{HashMap, HashSet},
+    collections::{BTreeSet, HashMap, HashSet, VecDeque},
     sync::{
         atomic::{AtomicU64, Ordering},
         Arc,
     },
 };
-use tokio::sync::{broadcast, RwLock};
-
-use crate::http_server::AppState;
+use tokio::sync::{broadcast, Mutex, Notify, RwLock};
+
+use crate::{
+    ambient_light::LedColorsPublisher,
+    ambient_light_state::AmbientLightStateManager,
+    http_server::AppState,
+    led_data_sender::{DataSendMode, LedDataSender},
+    rpc::{BoardMessageChannels, DisplaySetting, DisplaySettingRequest},
+};
 
 /// WebSocket消息类型
 #[derive(Debug, Clone, Serialize, Deserialize)]
@@ -28,18 +34,28 @@ pub enum WsMessage {
     LedSortedColorsChanged { data: LedSortedColorsChangedData },
     /// LED灯带颜色变化（按灯带分组）
     LedStripColorsChanged { data: LedStripColorsChangedData },
+    /// LED灯带低分辨率预览颜色变化（按灯带分组，按订阅的分辨率上限降采样）
+    LedStripPreviewColorsChanged {
+        data: LedStripPreviewColorsChangedData,
+    },
     /// LED状态变化
     LedStatusChanged { data: serde_json::Value },
     /// 配置变化
     ConfigChanged { data: serde_json::Value },
     /// 设备列表变化
     BoardsChanged { data: serde_json::Value },
+    /// 设备板启动自检完成
+    BoardSelfCheckCompleted { data: serde_json::Value },
     /// 显示器状态变化
     DisplaysChanged { data: serde_json::Value },
     /// 环境光状态变化
     AmbientLightStateChanged { data: serde_json::Value },
+    /// 情景模式被激活（手动或定时规则触发）
+    ProfileActivated { data: serde_json::Value },
     /// LED预览状态变化
     LedPreviewStateChanged { data: serde_json::Value },
+    /// LED数据发送模式变化
+    DataSendModeChanged { data: DataSendModeChangedData },
     /// 导航事件
     Navigate { data: NavigateData },
     /// 订阅事件
@@ -48,12 +64,124 @@ pub enum WsMessage {
     Unsubscribe { data: Vec<String> },
     /// 订阅确认
     SubscriptionConfirmed { data: Vec<String> },
+    /// 客户端命令，`data` 为 [`WsCommand`]，执行结果以 `CommandAck` 回复给该客户端
+    Command {
+        /// 客户端生成的请求ID，原样带回确认消息
+        #[serde(default)]
+        id: Option<String>,
+        data: serde_json::Value,
+    },
+    /// 命令执行结果
+    CommandAck { data: WsCommandAck },
     /// 心跳
     Ping,
     /// 心跳响应
     Pong,
 }
 
+/// 客户端通过WebSocket发送的命令，与对应的HTTP接口使用相同的管理器
+///
+/// 示例：`{"type":"Command","id":"1","data":{"command":"SetManualColor","r":255,"g":0,"b":0}}`
+#[derive(Debug, Clone, PartialEq, Deserialize)]
+#[serde(tag = "command", deny_unknown_fields)]
+pub enum WsCommand {
+    /// 启用或禁用环境光（同 `PUT /api/v1/device/ambient-light-state`）
+    SetAmbientLightEnabled { enabled: bool },
+    /// 设置LED数据发送模式（同 `PUT /api/v1/led/mode`）
+    SetDataSendMode { mode: DataSendMode },
+    /// 设置手动纯色（同 `PUT /api/v1/led/manual-color`）
+    SetManualColor { r: u8, g: u8, b: u8 },
+    /// 清除手动纯色（同 `DELETE /api/v1/led/manual-color`）
+    ClearManualColor,
+    /// 通过DDC/CI设置显示器亮度，与设备板旋钮调节走同一通道
+    SetDisplayBrightness {
+        display_index: usize,
+        brightness: u8,
+    },
+}
+
+impl WsCommand {
+    /// 解析命令，未知命令或参数错误时返回错误说明
+    pub fn parse(data: serde_json::Value) -> Result<Self, String> {
+        serde_json::from_value(data).map_err(|e| format!("Invalid command: {e}"))
+    }
+
+    /// 执行命令
+    pub async fn execute(self) -> anyhow::Result<()> {
+        match self {
+            WsCommand::SetAmbientLightEnabled { enabled } => {
+                AmbientLightStateManager::global()
+                    .await
+                    .set_enabled(enabled)
+                    .await
+            }
+            WsCommand::SetDataSendMode { mode } => {
+                LedDataSender::global().await.set_mode(mode).await;
+                Ok(())
+            }
+            WsCommand::SetManualColor { r, g, b } => {
+                LedColorsPublisher::global()
+                    .await
+                    .set_manual_color([r, g, b])
+                    .await
+            }
+            WsCommand::ClearManualColor => {
+                LedColorsPublisher::global()
+                    .await
+                    .clear_manual_color()
+                    .await;
+                Ok(())
+            }
+            WsCommand::SetDisplayBrightness {
+                display_index,
+                brightness,
+            } => {
+                let channels = BoardMessageChannels::global().await;
+                channels
+                    .display_setting_request_sender
+                    .send(DisplaySettingRequest {
+                        display_index,
+                        setting: DisplaySetting::Brightness(brightness),
+                    })
+                    .map(|_| ())
+                    .map_err(|e| anyhow::anyhow!("Failed to request display brightness: {e}"))
+            }
+        }
+    }
+}
+
+/// 命令执行结果
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct WsCommandAck {
+    /// 对应命令的请求ID
+    pub id: Option<String>,
+    pub success: bool,
+    /// 失败原因（命令无法解析或执行失败）
+    pub error: Option<String>,
+}
+
+impl WsCommandAck {
+    /// 解析并执行命令，返回确认消息
+    pub async fn handle(id: Option<String>, data: serde_json::Value) -> Self {
+        let result = match WsCommand::parse(data) {
+            Ok(command) => {
+                log::info!("收到WebSocket命令: {command:?}");
+                command.execute().await.map_err(|e| e.to_string())
+            }
+            Err(e) => Err(e),
+        };
+        if let Err(e) = &result {
+            log::warn!("WebSocket命令执行失败: {e}");
+        }
+
+        Self {
+            id,
+            success: result.is_ok(),
+            error: result.err(),
+        }
+    }
+}
+
 /// LED颜色变化数据
 #[derive(Debug, Clone, Serialize, Deserialize)]
 pub struct LedColorsChangedData {
@@ -86,12 +214,144 @@ pub struct LedStripColorsChangedData {
     pub mode: crate::led_data_sender::DataSendMode,
 }
 
+/// 低分辨率预览事件的订阅前缀，完整订阅名为 `LedStripPreviewColorsChanged:max_<N>`
+pub const LED_STRIP_PREVIEW_EVENT: &str = "LedStripPreviewColorsChanged";
+/// 低分辨率预览每条灯带允许订阅的最大颜色数
+pub const MAX_PREVIEW_COLORS_PER_STRIP: usize = 1024;
+
+/// 解析低分辨率预览订阅名中的分辨率上限，格式不符或超出范围时返回 None
+pub fn parse_preview_resolution_cap(event_type: &str) -> Option<usize> {
+    event_type
+        .strip_prefix(LED_STRIP_PREVIEW_EVENT)?
+        .strip_prefix(":max_")?
+        .parse()
+        .ok()
+        .filter(|cap| (1..=MAX_PREVIEW_COLORS_PER_STRIP).contains(cap))
+}
+
+/// LED灯带低分辨率预览颜色数据（按灯带分组）
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct LedStripPreviewColorsChangedData {
+    /// 显示器ID
+    pub display_id: u32,
+    /// 边框位置 ("Top", "Bottom", "Left", "Right")
+    pub border: String,
+    /// 灯带索引
+    pub strip_index: usize,
+    /// 灯带实际的LED数量
+    pub led_count: usize,
+    /// 订阅的分辨率上限（每条灯带最多的颜色数）
+    pub max_colors: usize,
+    /// 降采样后的颜色数据（RGB字节数组），颜色数为 min(led_count, max_colors)，
+    /// 每个颜色是灯带上连续一段LED的平均值
+    pub colors: Vec<u8>,
+    /// 数据发送模式
+    pub mode: crate::led_data_sender::DataSendMode,
+}
+
+/// LED数据发送模式变化数据
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct DataSendModeChangedData {
+    /// 变化前的发送模式
+    pub previous_mode: crate::led_data_sender::DataSendMode,
+    /// 当前发送模式
+    pub mode: crate::led_data_sender::DataSendMode,
+}
+
 /// 导航数据
 #[derive(Debug, Clone, Serialize, Deserialize)]
 pub struct NavigateData {
     pub path: String,
 }
 
+impl WsMessage {
+    /// 逐帧推送的颜色消息的合并键（事件类型 + 偏移/灯带），其他消息返回 None
+    ///
+    /// 合并键相同的两帧中，新帧可以替换旧帧而不丢失信息
+    fn frame_key(&self) -> Option<(&'static str, usize)> {
+        match self {
+            WsMessage::LedColorsChanged { .. } => Some(("LedColorsChanged", 0)),
+            WsMessage::LedSortedColorsChanged { data } => {
+                Some(("LedSortedColorsChanged", data.led_offset))
+            }
+            WsMessage::LedStripColorsChanged { data } => {
+                Some(("LedStripColorsChanged", data.strip_index))
+            }
+            WsMessage::LedStripPreviewColorsChanged { data } => {
+                Some(("LedStripPreviewColorsChanged", data.strip_index))
+            }
+            _ => None,
+        }
+    }
+}
+
+/// 单个客户端待发送消息的积压上限，超过后颜色帧按"最新优先"合并
+const MAX_PENDING_MESSAGES: usize = 32;
+
+/// 单个客户端的发送队列
+///
+/// 广播消息先由转发任务放入该队列，再由发送任务写入socket，慢客户端只会让自己的队列积压，
+/// 不会阻塞发布方或影响其他客户端。合并策略：
+/// * 积压未达到 [`MAX_PENDING_MESSAGES`] 时所有消息按顺序排队；
+/// * 达到上限后，新的颜色帧替换队列中合并键相同的旧帧（保留旧帧的位置）；
+///   没有同键旧帧时丢弃队列中最旧的颜色帧，再把新帧放到队尾；
+/// * 其他事件（状态、配置变化、订阅确认等）从不丢弃。
+#[derive(Default)]
+struct ClientOutbox {
+    queue: VecDeque<WsMessage>,
+}
+
+impl ClientOutbox {
+    /// 放入一条消息，返回因合并而丢弃的颜色帧数量
+    fn push(&mut self, message: WsMessage) -> u64 {
+        let Some(key) = message.frame_key() else {
+            self.queue.push_back(message);
+            return 0;
+        };
+
+        if self.queue.len() < MAX_PENDING_MESSAGES {
+            self.queue.push_back(message);
+            return 0;
+        }
+
+        if let Some(queued) = self
+            .queue
+            .iter_mut()
+            .find(|queued| queued.frame_key() == Some(key))
+        {
+            *queued = message;
+            return 1;
+        }
+
+        let dropped = match self
+            .queue
+            .iter()
+            .position(|queued| queued.frame_key().is_some())
+        {
+            Some(index) => {
+                self.queue.remove(index);
+                1
+            }
+            None => 0,
+        };
+        self.queue.push_back(message);
+        dropped
+    }
+
+    fn pop(&mut self) -> Option<WsMessage> {
+        self.queue.pop_front()
+    }
+}
+
+/// 单个WebSocket客户端的统计信息
+#[derive(Debug, Clone, Serialize, utoipa::ToSchema)]
+pub struct WebSocketClientStats {
+    /// 连接ID
+    pub connection_id: ConnectionId,
+    /// 因客户端过慢而被合并丢弃的颜色帧数量
+    pub dropped_frames: u64,
+}
+
 /// 连接ID类型
 pub type ConnectionId = u64;
 
@@ -110,6 +370,8 @@ pub struct WebSocketManager {
     subscriptions: Arc<RwLock<HashMap<ConnectionId, HashSet<String>>>>,
     /// 连接ID计数器
     connection_counter: Arc<AtomicU64>,
+    /// 每个连接被丢弃的颜色帧数量
+    dropped_frames: Arc<RwLock<HashMap<ConnectionId, u64>>>,
 }
 
 impl WebSocketManager {
@@ -121,6 +383,7 @@ impl WebSocketManager {
             sender,
             subscriptions: Arc::new(RwLock::new(HashMap::new())),
             connection_counter: Arc::new(AtomicU64::new(0)),
+            dropped_frames: Arc::new(RwLock::new(HashMap::new())),
         }
     }
 
@@ -138,6 +401,7 @@ impl WebSocketManager {
 
     /// 移除连接
     pub async fn remove_connection(&self, connection_id: ConnectionId) {
+        self.dropped_frames.write().await.remove(&connection_id);
         let mut subscriptions = self.subscriptions.write().await;
         if let Some(removed_subscriptions) = subscriptions.remove(&connection_id) {
             log::debug!(
@@ -201,6 +465,16 @@ impl WebSocketManager {
         }
     }
 
+    /// 当前所有连接订阅的低分辨率预览分辨率上限
+    pub async fn preview_resolution_caps(&self) -> BTreeSet<usize> {
+        let subscriptions = self.subscriptions.read().await;
+        subscriptions
+            .values()
+            .flatten()
+            .filter_map(|event_type| parse_preview_resolution_cap(event_type))
+            .collect()
+    }
+
     /// 获取接收器
     pub fn subscribe(&self) -> broadcast::Receiver<WsMessage> {
         self.sender.subscribe()
@@ -221,11 +495,37 @@ impl WebSocketManager {
         subscriptions.len()
     }
 
+    /// 累加连接被丢弃的颜色帧数量
+    async fn record_dropped_frames(&self, connection_id: ConnectionId, count: u64) {
+        *self
+            .dropped_frames
+            .write()
+            .await
+            .entry(connection_id)
+            .or_default() += count;
+    }
+
+    /// 获取所有连接的统计信息（用于监控慢客户端）
+    pub async fn get_client_stats(&self) -> Vec<WebSocketClientStats> {
+        let subscriptions = self.subscriptions.read().await;
+        let dropped_frames = self.dropped_frames.read().await;
+        let mut stats: Vec<_> = subscriptions
+            .keys()
+            .map(|&connection_id| WebSocketClientStats {
+                connection_id,
+                dropped_frames: dropped_frames.get(&connection_id).copied().unwrap_or(0),
+            })
+            .collect();
+        stats.sort_by_key(|stats| stats.connection_id);
+        stats
+    }
+
     /// 清理所有连接（用于关闭时清理）
     pub async fn clear_all_connections(&self) {
         let mut subscriptions = self.subscriptions.write().await;
         let count = subscriptions.len();
         subscriptions.clear();
+        self.dropped_frames.write().await.clear();
         log::info!("🔌 Cleared all {count} connections");
     }
 }
@@ -282,8 +582,14 @@ async fn handle_socket(socket: WebSocket, state: AppState) {
         }
     }
 
+    // 该客户端的发送队列：广播消息与命令确认都经由此队列发送
+    let outbox = Arc::new(Mutex::new(ClientOutbox::default()));
+    let outbox_notify = Arc::new(Notify::new());
+
     // 处理客户端消息的任务
     let ws_manager_for_recv = ws_manager.clone();
+    let recv_outbox = outbox.clone();
+    let recv_notify = outbox_notify.clone();
     let mut recv_task = tokio::spawn(async move {
         while let Some(Ok(msg)) = receiver.next().await {
             match msg {
@@ -309,6 +615,14 @@ async fn handle_socket(socket: WebSocket, state: AppState) {
                                     .unsubscribe_events(connection_id, event_types)
                                     .await;
                             }
+                            WsMessage::Command { id, data } => {
+                                let ack = WsCommandAck::handle(id, data).await;
+                                recv_outbox
+                                    .lock()
+                                    .await
+                                    .push(WsMessage::CommandAck { data: ack });
+                                recv_notify.notify_one();
+                            }
                             _ => {
                                 // 处理其他客户端消息
                                 log::debug!("收到WebSocket消息: {ws_msg:?}");
@@ -334,26 +648,21 @@ async fn handle_socket(socket: WebSocket, state: AppState) {
         ws_manager_for_recv.remove_connection(connection_id).await;
     });
 
-    // 广播消息给客户端的任务
-    let ws_manager_for_send = ws_manager.clone();
-    let mut send_task = tokio::spawn(async move {
-        // 实现从ws_receiver接收广播消息并发送给客户端
+    // 将广播消息转入该客户端发送队列的任务（不等待socket，避免广播通道滞后）
+    let ws_manager_for_forward = ws_manager.clone();
+    let forward_outbox = outbox.clone();
+    let forward_notify = outbox_notify.clone();
+    let forward_task = tokio::spawn(async move {
         loop {
             match ws_receiver.recv().await {
                 Ok(msg) => {
-                    let text = match serde_json::to_string(&msg) {
-                        Ok(text) => text,
-                        Err(e) => {
-                            log::error!("序列化WebSocket消息失败: {e}");
-                            continue;
-                        }
-                    };
-
-                    if sender.send(Message::Text(text)).await.is_err() {
-                        log::debug!("WebSocket发送消息失败，连接可能已断开");
-                        break;
+                    let dropped = forward_outbox.lock().await.push(msg);
+                    if dropped > 0 {
+                        ws_manager_for_forward
+                            .record_dropped_frames(connection_id, dropped)
+                            .await;
                     }
-                    // 移除成功发送的日志，减少输出
+                    forward_notify.notify_one();
                 }
                 Err(broadcast::error::RecvError::Closed) => {
                     log::debug!("WebSocket广播通道已关闭");
@@ -361,11 +670,40 @@ async fn handle_socket(socket: WebSocket, state: AppState) {
                 }
                 Err(broadcast::error::RecvError::Lagged(skipped)) => {
                     log::warn!("WebSocket接收器滞后，跳过了 {} 条消息", skipped);
+                    ws_manager_for_forward
+                        .record_dropped_frames(connection_id, skipped)
+                        .await;
                     // 继续处理，不要断开连接
                     continue;
                 }
             }
         }
+    });
+
+    // 将发送队列中的消息写入客户端的任务
+    let ws_manager_for_send = ws_manager.clone();
+    let mut send_task = tokio::spawn(async move {
+        loop {
+            let next = outbox.lock().await.pop();
+            let Some(msg) = next else {
+                outbox_notify.notified().await;
+                continue;
+            };
+
+            let text = match serde_json::to_string(&msg) {
+                Ok(text) => text,
+                Err(e) => {
+                    log::error!("序列化WebSocket消息失败: {e}");
+                    continue;
+                }
+            };
+
+            if sender.send(Message::Text(text)).await.is_err() {
+                log::debug!("WebSocket发送消息失败，连接可能已断开");
+                break;
+            }
+            // 移除成功发送的日志，减少输出
+        }
         // 发送任务结束时也清理连接
         ws_manager_for_send.remove_connection(connection_id).await;
     });
@@ -379,8 +717,148 @@ async fn handle_socket(socket: WebSocket, state: AppState) {
             recv_task.abort();
         }
     }
+    forward_task.abort();
 
     // 确保连接被清理（双重保险）
     ws_manager.remove_connection(connection_id).await;
     log::debug!("WebSocket连接已断开，连接ID: {connection_id}");
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn sorted_frame(led_offset: usize, value: u8) -> WsMessage {
+        WsMessage::LedSortedColorsChanged {
+            data: LedSortedColorsChangedData {
+                sorted_colors: vec![value; 3],
+                mode: crate::led_data_sender::DataSendMode::AmbientLight,
+                led_offset,
+                timestamp: chrono::Utc::now(),
+            },
+        }
+    }
+
+    fn frame_value(message: &WsMessage) -> Option<(usize, u8)> {
+        match message {
+            WsMessage::LedSortedColorsChanged { data } => {
+                Some((data.led_offset, data.sorted_colors[0]))
+            }
+            _ => None,
+        }
+    }
+
+    #[test]
+    fn test_outbox_queues_in_order_below_threshold() {
+        let mut outbox = ClientOutbox::default();
+        for value in 0..MAX_PENDING_MESSAGES as u8 {
+            assert_eq!(outbox.push(sorted_frame(0, value)), 0);
+        }
+        assert_eq!(outbox.queue.len(), MAX_PENDING_MESSAGES);
+        assert_eq!(frame_value(&outbox.pop().unwrap()), Some((0, 0)));
+    }
+
+    #[test]
+    fn test_outbox_latest_frame_wins_when_backlogged() {
+        let mut outbox = ClientOutbox::default();
+        outbox.push(sorted_frame(10, 0));
+        for _ in 1..MAX_PENDING_MESSAGES {
+            outbox.push(WsMessage::Pong);
+        }
+
+        // 同键的新帧替换队列中的旧帧
+        assert_eq!(outbox.push(sorted_frame(10, 1)), 1);
+        assert_eq!(outbox.queue.len(), MAX_PENDING_MESSAGES);
+        assert_eq!(frame_value(&outbox.queue[0]), Some((10, 1)));
+
+        // 不同键的新帧挤掉最旧的帧
+        assert_eq!(outbox.push(sorted_frame(20, 2)), 1);
+        assert_eq!(outbox.queue.len(), MAX_PENDING_MESSAGES);
+        assert_eq!(frame_value(outbox.queue.back().unwrap()), Some((20, 2)));
+        assert!(outbox
+            .queue
+            .iter()
+            .all(|msg| frame_value(msg) != Some((10, 1))));
+
+        // 其他事件从不丢弃
+        assert_eq!(outbox.push(WsMessage::Ping), 0);
+        assert_eq!(outbox.queue.len(), MAX_PENDING_MESSAGES + 1);
+    }
+
+    #[test]
+    fn test_parse_ws_commands() {
+        let message: WsMessage = serde_json::from_str(
+            r#"{"type":"Command","id":"1","data":{"command":"SetManualColor","r":255,"g":0,"b":10}}"#,
+        )
+        .unwrap();
+        let WsMessage::Command { id, data } = message else {
+            panic!("expected a command message");
+        };
+        assert_eq!(id.as_deref(), Some("1"));
+        assert_eq!(
+            WsCommand::parse(data),
+            Ok(WsCommand::SetManualColor {
+                r: 255,
+                g: 0,
+                b: 10
+            })
+        );
+
+        assert_eq!(
+            WsCommand::parse(serde_json::json!({"command": "ClearManualColor"})),
+            Ok(WsCommand::ClearManualColor)
+        );
+        assert_eq!(
+            WsCommand::parse(serde_json::json!({"command": "SetDataSendMode", "mode": "None"})),
+            Ok(WsCommand::SetDataSendMode {
+                mode: DataSendMode::None
+            })
+        );
+
+        // 未知命令、缺少参数或多余参数都被拒绝
+        assert!(WsCommand::parse(serde_json::json!({"command": "Reboot"})).is_err());
+        assert!(
+            WsCommand::parse(serde_json::json!({"command": "SetAmbientLightEnabled"})).is_err()
+        );
+        assert!(WsCommand::parse(
+            serde_json::json!({"command": "SetAmbientLightEnabled", "enabled": true, "extra": 1})
+        )
+        .is_err());
+    }
+
+    #[tokio::test]
+    async fn test_unknown_command_acks_with_error() {
+        let ack = WsCommandAck::handle(
+            Some("42".to_string()),
+            serde_json::json!({"command": "SelfDestruct"}),
+        )
+        .await;
+        assert_eq!(ack.id.as_deref(), Some("42"));
+        assert!(!ack.success);
+        assert!(ack.error.unwrap().contains("SelfDestruct"));
+    }
+
+    #[test]
+    fn test_parse_preview_resolution_cap() {
+        assert_eq!(
+            parse_preview_resolution_cap("LedStripPreviewColorsChanged:max_16"),
+            Some(16)
+        );
+        assert_eq!(
+            parse_preview_resolution_cap("LedStripPreviewColorsChanged"),
+            None
+        );
+        assert_eq!(
+            parse_preview_resolution_cap("LedStripPreviewColorsChanged:max_0"),
+            None
+        );
+        assert_eq!(
+            parse_preview_resolution_cap("LedStripPreviewColorsChanged:max_4096"),
+            None
+        );
+        assert_eq!(
+            parse_preview_resolution_cap("LedStripColorsChanged:display_1"),
+            None
+        );
+    }
+}
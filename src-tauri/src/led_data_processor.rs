@@ -1,14 +1,171 @@
+use std::collections::{BTreeMap, BTreeSet};
+use std::time::Duration;
+
 use anyhow::Result;
 use log::{debug, warn};
+use serde::{Deserialize, Serialize};
+use tokio::sync::Mutex;
+use tokio::time::Instant;
+use utoipa::ToSchema;
 
 use crate::{
-    ambient_light::{Border, ColorCalibration, LedStripConfig, LedStripConfigV2, LedType},
+    ambient_light::{
+        Border, ColorCalibration, ColorOrder, DisplayWinding, LedStripConfig, LedStripConfigV2,
+        LedType, WhiteSynthesis,
+    },
     display::DisplayRegistry,
     led_color::LedColor,
     led_data_sender::DataSendMode,
+    user_preferences::ColorTemperatureShiftPreferences,
     websocket_events::WebSocketEventPublisher,
 };
 
+/// 感知亮度归一化时纯色的目标亮度（三原色 Rec.709 亮度的平均值）
+const PERCEPTUAL_REFERENCE_LUMINANCE: f32 = (0.2126 + 0.7152 + 0.0722) / 3.0;
+
+/// 精确白色合成的白度阈值（相对白点的最小/最大通道比），低于该值的饱和颜色不提取白光
+const ACCURATE_WHITENESS_THRESHOLD: f32 = 0.5;
+
+/// 自动亮度的最低输出比例，避免画面亮度极端时灯带完全熄灭
+const AUTO_BRIGHTNESS_MIN_SCALE: f32 = 0.2;
+/// 自动亮度向目标比例靠近的时间常数（指数平滑），与帧率无关，约1秒内完成大部分过渡
+const AUTO_BRIGHTNESS_TIME_CONSTANT: Duration = Duration::from_millis(400);
+
+/// 光标所在显示器在强度为1时的最大亮度提升比例
+const CURSOR_FOCUS_MAX_BOOST: f32 = 0.3;
+/// 光标跟随每帧向目标比例靠近的系数（指数平滑），切换显示器时约半秒内完成过渡
+const CURSOR_FOCUS_SMOOTHING: f32 = 0.15;
+
+/// 色温偏移允许的色温范围（K）
+pub const MIN_SHIFT_KELVIN: u32 = 1000;
+pub const MAX_SHIFT_KELVIN: u32 = 10000;
+/// 色温偏移的参考色温（K），该色温下不改变颜色
+const REFERENCE_KELVIN: f32 = 6500.0;
+/// 一天的分钟数
+const MINUTES_PER_DAY: u32 = 24 * 60;
+
+/// 各显示器平滑后的自动亮度输出比例及其更新时间，未启用时清空
+static AUTO_BRIGHTNESS_SCALES: Mutex<BTreeMap<Option<u32>, (f32, Instant)>> =
+    Mutex::const_new(BTreeMap::new());
+
+/// 光标跟随模式下各显示器平滑后的输出比例
+static CURSOR_FOCUS_SCALES: Mutex<BTreeMap<u32, f32>> = Mutex::const_new(BTreeMap::new());
+
+/// 颜色死区保持的上次输出，按LED偏移量（即每个显示器的数据段）区分
+static DEADBAND_HELD_COLORS: Mutex<BTreeMap<usize, Vec<[u8; 3]>>> =
+    Mutex::const_new(BTreeMap::new());
+
+/// 跨屏灯带各分段最近一帧的预览颜色，按原灯带序列号、分段序列号索引；
+/// 分段分属不同显示器、分别处理，合并后按原灯带发布
+static SPANNING_STRIP_PREVIEW: Mutex<BTreeMap<usize, BTreeMap<usize, Vec<u8>>>> =
+    Mutex::const_new(BTreeMap::new());
+
+/// 配置变更后清空跨屏灯带的分段预览，避免旧分段混入新配置的预览
+pub async fn reset_spanning_strip_preview() {
+    SPANNING_STRIP_PREVIEW.lock().await.clear();
+}
+
+/// 自动亮度随屏幕整体亮度调整输出的方向
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Serialize, Deserialize, ToSchema)]
+pub enum AutoBrightnessMode {
+    /// 画面越亮输出越暗（避免亮场景刺眼，暗场景过暗）
+    #[default]
+    Inverse,
+    /// 画面越亮输出越亮
+    Proportional,
+}
+
+/// 校验自动亮度强度（0-1）
+pub fn validate_auto_brightness_strength(strength: f32) -> anyhow::Result<()> {
+    if !(0.0..=1.0).contains(&strength) {
+        anyhow::bail!("auto brightness strength must be between 0 and 1, got {strength}");
+    }
+    Ok(())
+}
+
+/// 校验光标跟随强度（0-1）
+pub fn validate_cursor_focus_strength(strength: f32) -> anyhow::Result<()> {
+    if !(0.0..=1.0).contains(&strength) {
+        anyhow::bail!("cursor focus strength must be between 0 and 1, got {strength}");
+    }
+    Ok(())
+}
+
+/// 校验定时色温偏移设置：色温在允许范围内，日出/日落为一天内的分钟数且不相同，
+/// 过渡时长不超过白天或夜晚的时长
+pub fn validate_color_temperature_shift(
+    settings: &ColorTemperatureShiftPreferences,
+) -> anyhow::Result<()> {
+    for kelvin in [settings.day_kelvin, settings.night_kelvin] {
+        if !(MIN_SHIFT_KELVIN..=MAX_SHIFT_KELVIN).contains(&kelvin) {
+            anyhow::bail!(
+                "color temperature must be between {MIN_SHIFT_KELVIN}K and {MAX_SHIFT_KELVIN}K, got {kelvin}K"
+            );
+        }
+    }
+    for minute in [settings.sunrise_minute, settings.sunset_minute] {
+        if minute >= MINUTES_PER_DAY {
+            anyhow::bail!("sunrise/sunset must be minutes since midnight (0-1439), got {minute}");
+        }
+    }
+    if settings.sunrise_minute == settings.sunset_minute {
+        anyhow::bail!("sunrise and sunset must differ");
+    }
+    let day_length =
+        (settings.sunset_minute + MINUTES_PER_DAY - settings.sunrise_minute) % MINUTES_PER_DAY;
+    let shortest_period = day_length.min(MINUTES_PER_DAY - day_length);
+    if settings.transition_minutes > shortest_period {
+        anyhow::bail!(
+            "transition must not exceed {shortest_period} minutes, got {}",
+            settings.transition_minutes
+        );
+    }
+    Ok(())
+}
+
+/// 调色板量化允许的最大颜色数
+pub const MAX_PALETTE_SIZE: usize = 256;
+
+/// 默认的量化调色板（经典 16 色）
+pub const DEFAULT_QUANTIZATION_PALETTE: [[u8; 3]; 16] = [
+    [0, 0, 0],
+    [128, 0, 0],
+    [0, 128, 0],
+    [128, 128, 0],
+    [0, 0, 128],
+    [128, 0, 128],
+    [0, 128, 128],
+    [192, 192, 192],
+    [128, 128, 128],
+    [255, 0, 0],
+    [0, 255, 0],
+    [255, 255, 0],
+    [0, 0, 255],
+    [255, 0, 255],
+    [0, 255, 255],
+    [255, 255, 255],
+];
+
+/// 校验量化调色板（1 到 [`MAX_PALETTE_SIZE`] 个颜色）
+pub fn validate_palette(palette: &[[u8; 3]]) -> anyhow::Result<()> {
+    if palette.is_empty() || palette.len() > MAX_PALETTE_SIZE {
+        anyhow::bail!(
+            "palette must contain between 1 and {MAX_PALETTE_SIZE} colors, got {}",
+            palette.len()
+        );
+    }
+    Ok(())
+}
+
+/// 颜色校准的传递曲线，每个通道包含 256 个输入值对应的输出值
+#[derive(Debug, Clone, PartialEq, Serialize, ToSchema)]
+pub struct CalibrationCurve {
+    pub r: Vec<u8>,
+    pub g: Vec<u8>,
+    pub b: Vec<u8>,
+    pub w: Vec<u8>,
+}
+
 /// LED数据处理器
 ///
 /// 负责统一处理所有模式的LED数据：
@@ -26,6 +183,7 @@ impl LedDataProcessor {
     /// * `color_calibration` - 颜色校准配置（None时使用当前配置）
     /// * `mode` - 当前数据发送模式
     /// * `start_led_offset` - LED偏移量（必填）
+    /// * `winding` - 显示器灯带走线方向（仅影响硬件输出）
     ///
     /// # 返回值
     /// 返回硬件编码后的数据，可直接发送给LED硬件
@@ -35,6 +193,7 @@ impl LedDataProcessor {
         color_calibration: Option<&ColorCalibration>,
         _mode: DataSendMode,
         start_led_offset: usize,
+        winding: DisplayWinding,
     ) -> Result<Vec<u8>> {
         // 1. 获取颜色校准配置
         let calibration = match color_calibration {
@@ -42,6 +201,9 @@ impl LedDataProcessor {
             None => Self::get_current_color_calibration().await?,
         };
 
+        // 1.1. 调色板量化（在预览之前，预览与硬件输出保持一致）
+        let led_colors = Self::apply_palette_quantization(led_colors).await;
+
         // 2. 转换为预览数据（一维RGB字节数组，无校准）
         let preview_rgb_bytes = Self::colors_2d_to_rgb_bytes(&led_colors);
 
@@ -52,18 +214,33 @@ impl LedDataProcessor {
             .publish_led_sorted_colors_changed(&preview_rgb_bytes, start_led_offset)
             .await;
 
-        // 记录数据发送事件到频率计算器
+        // 记录数据发送事件到频率计算器（按所属显示器分别统计）
         let status_manager = crate::led_status_manager::LedStatusManager::global().await;
-        if let Err(e) = status_manager.record_data_send_event().await {
+        let display_id = strips.first().map(|strip| strip.display_id);
+        if let Err(e) = status_manager.record_data_send_event(display_id).await {
             log::warn!("Failed to record data send event: {e}");
         }
 
         // 3.1. 按灯带分组发布（替代旧的 LedColorsChanged 事件）
         Self::publish_led_strip_colors(&led_colors, strips, websocket_publisher).await;
 
-        // 4. 硬件编码（应用颜色校准）
+        // 3.2. 颜色死区，抑制采样抖动导致的微小闪烁
+        let led_colors =
+            Self::apply_deadband_for_offset(led_colors, start_led_offset, calibration.deadband)
+                .await;
+
+        // 4. 自动亮度（仅影响硬件输出，预览保持屏幕原始颜色）
+        let led_colors = Self::apply_auto_brightness(led_colors, display_id).await;
+
+        // 4.1. 定时色温偏移（仅影响硬件输出）
+        let led_colors = Self::apply_color_temperature_shift(led_colors).await;
+
+        // 4.2. 光标跟随：突出光标所在显示器的灯带（仅影响硬件输出）
+        let led_colors = Self::apply_cursor_focus(led_colors, display_id).await;
+
+        // 5. 硬件编码（按走线方向排列，应用颜色校准）
         let hardware_data =
-            Self::encode_for_hardware(led_colors, strips, &calibration, start_led_offset)?;
+            Self::encode_for_hardware(led_colors, strips, &calibration, start_led_offset, winding)?;
 
         Ok(hardware_data)
     }
@@ -106,7 +283,7 @@ impl LedDataProcessor {
 
         // 记录数据发送事件到频率计算器
         let status_manager = crate::led_status_manager::LedStatusManager::global().await;
-        if let Err(e) = status_manager.record_data_send_event().await {
+        if let Err(e) = status_manager.record_data_send_event(None).await {
             log::warn!("Failed to record data send event: {e}");
         }
 
@@ -132,6 +309,7 @@ impl LedDataProcessor {
     /// * `rgb_colors` - 一维测试效果RGB数据
     /// * `led_type` - 强制指定的LED类型
     /// * `led_count` - LED数量
+    /// * `color_order` - 硬件颜色字节顺序
     /// * `mode` - 当前数据发送模式
     ///
     /// # 返回值
@@ -140,6 +318,7 @@ impl LedDataProcessor {
         rgb_colors: Vec<LedColor>,
         led_type: LedType,
         led_count: usize,
+        color_order: ColorOrder,
         mode: DataSendMode,
     ) -> Result<Vec<u8>> {
         debug!(
@@ -162,14 +341,15 @@ impl LedDataProcessor {
 
         // 记录数据发送事件到频率计算器
         let status_manager = crate::led_status_manager::LedStatusManager::global().await;
-        if let Err(e) = status_manager.record_data_send_event().await {
+        if let Err(e) = status_manager.record_data_send_event(None).await {
             log::warn!("Failed to record data send event: {e}");
         }
 
         debug!("✅ Test LED preview data published successfully");
 
         // 3. 测试模式编码（无校准）
-        let hardware_data = Self::encode_for_test_mode(rgb_colors, led_type, led_count)?;
+        let hardware_data =
+            Self::encode_for_test_mode(rgb_colors, led_type, led_count, color_order)?;
 
         debug!(
             "🧪 Test mode encoding completed: {} bytes",
@@ -206,6 +386,44 @@ impl LedDataProcessor {
             .collect()
     }
 
+    /// 按走线方向排列显示器的颜色
+    ///
+    /// 逆时针时将所有灯带按序列号串联后的颜色整体反转，再按各灯带长度重新切分，
+    /// 相当于同时反转灯带的串联顺序和每条灯带内部的顺序。缺少的颜色以黑色补齐。
+    fn apply_winding(
+        led_colors: Vec<Vec<LedColor>>,
+        strips: &[LedStripConfig],
+        winding: DisplayWinding,
+    ) -> Vec<Vec<LedColor>> {
+        if winding == DisplayWinding::Clockwise {
+            return led_colors;
+        }
+
+        let mut sorted_strips: Vec<_> = strips.iter().enumerate().collect();
+        sorted_strips.sort_by_key(|(_, strip)| strip.chain_order());
+
+        let mut reversed = sorted_strips
+            .iter()
+            .flat_map(|(strip_index, strip)| {
+                let strip_colors = led_colors.get(*strip_index).map_or(&[][..], Vec::as_slice);
+                (0..strip.len).map(move |i| {
+                    strip_colors
+                        .get(i)
+                        .copied()
+                        .unwrap_or_else(|| LedColor::new(0, 0, 0))
+                })
+            })
+            .collect::<Vec<_>>()
+            .into_iter()
+            .rev();
+
+        let mut wound = vec![Vec::new(); strips.len()];
+        for (strip_index, strip) in sorted_strips {
+            wound[strip_index] = reversed.by_ref().take(strip.len).collect();
+        }
+        wound
+    }
+
     /// 核心方法：硬件编码（从 send_colors_by_display 移动过来）
     ///
     /// 将二维颜色数组按strips配置编码为硬件数据，应用颜色校准
@@ -215,24 +433,29 @@ impl LedDataProcessor {
     /// * `strips` - LED配置数组
     /// * `color_calibration` - 颜色校准配置
     /// * `start_led_offset` - LED偏移量
+    /// * `winding` - 显示器灯带走线方向，逆时针时整体翻转该显示器的颜色顺序
     ///
     /// # 返回值
-    /// 返回硬件编码后的数据（GRB/GRBW格式）
+    /// 返回硬件编码后的数据（按灯带颜色顺序排列，默认GRB/GRBW）
     fn encode_for_hardware(
         led_colors: Vec<Vec<LedColor>>,
         strips: &[LedStripConfig],
         color_calibration: &ColorCalibration,
         start_led_offset: usize,
+        winding: DisplayWinding,
     ) -> Result<Vec<u8>> {
         debug!(
-            "🔧 Encoding for hardware: {} strips, offset: {}",
+            "🔧 Encoding for hardware: {} strips, offset: {}, winding: {:?}",
             strips.len(),
-            start_led_offset
+            start_led_offset,
+            winding
         );
 
-        // 按序列号排序灯带，确保正确的串联顺序
+        let led_colors = Self::apply_winding(led_colors, strips, winding);
+
+        // 按串联顺序排序灯带
         let mut sorted_strips: Vec<_> = strips.iter().enumerate().collect();
-        sorted_strips.sort_by_key(|(_, strip)| strip.index);
+        sorted_strips.sort_by_key(|(_, strip)| strip.chain_order());
 
         debug!(
             "排序后的灯带顺序: {:?}",
@@ -247,7 +470,7 @@ impl LedDataProcessor {
             .iter()
             .map(|(_, strip)| {
                 let bytes_per_led = match strip.led_type {
-                    LedType::WS2812B => 3,
+                    LedType::WS2812B | LedType::WS2811 => 3,
                     LedType::SK6812 => 4,
                 };
                 strip.len * bytes_per_led
@@ -265,6 +488,21 @@ impl LedDataProcessor {
                 strip_index, strip.border, strip_len, strip.led_type
             );
 
+            // 长度为0的灯带（例如配置过程中）不占用任何字节
+            if strip_len == 0 {
+                continue;
+            }
+
+            // 禁用的灯带输出黑色，保留其在串联数据中的位置
+            if !strip.enabled {
+                complete_led_data.resize(
+                    complete_led_data.len() + strip_len * strip.led_type.bytes_per_led(),
+                    0,
+                );
+                total_leds += strip_len;
+                continue;
+            }
+
             // 检查二维数组索引是否有效
             if strip_index >= led_colors.len() {
                 warn!(
@@ -275,7 +513,9 @@ impl LedDataProcessor {
                 // 添加黑色作为后备
                 for _ in 0..strip_len {
                     match strip.led_type {
-                        LedType::WS2812B => complete_led_data.extend_from_slice(&[0, 0, 0]),
+                        LedType::WS2812B | LedType::WS2811 => {
+                            complete_led_data.extend_from_slice(&[0, 0, 0])
+                        }
                         LedType::SK6812 => complete_led_data.extend_from_slice(&[0, 0, 0, 0]),
                     }
                 }
@@ -289,38 +529,21 @@ impl LedDataProcessor {
             for i in 0..strip_len {
                 if i < strip_colors.len() {
                     let color = strip_colors[i];
-                    let rgb = color.get_rgb();
-
                     // 应用颜色校准
-                    let calibrated_r = (rgb[0] as f32 * color_calibration.r) as u8;
-                    let calibrated_g = (rgb[1] as f32 * color_calibration.g) as u8;
-                    let calibrated_b = (rgb[2] as f32 * color_calibration.b) as u8;
-
-                    match strip.led_type {
-                        LedType::WS2812B => {
-                            // GRB格式
-                            complete_led_data.extend_from_slice(&[
-                                calibrated_g, // G (Green)
-                                calibrated_r, // R (Red)
-                                calibrated_b, // B (Blue)
-                            ]);
-                        }
+                    let calibrated = Self::calibrate_rgb(color.get_rgb(), color_calibration);
+                    // SK6812的W通道单独校准
+                    let calibrated_w = match strip.led_type {
+                        LedType::WS2812B | LedType::WS2811 => None,
                         LedType::SK6812 => {
-                            // GRBW格式，W通道单独校准
-                            let w_channel = Self::calculate_white_channel(
-                                calibrated_r,
-                                calibrated_g,
-                                calibrated_b,
-                            );
-                            let calibrated_w = (w_channel as f32 * color_calibration.w) as u8;
-                            complete_led_data.extend_from_slice(&[
-                                calibrated_g, // G (Green)
-                                calibrated_r, // R (Red)
-                                calibrated_b, // B (Blue)
-                                calibrated_w, // W (White)
-                            ]);
+                            Some(Self::calibrate_white(calibrated, color_calibration))
                         }
-                    }
+                    };
+
+                    // 按灯带配置的颜色顺序写入（默认GRB/GRBW，WS2811 固定RGB）
+                    strip
+                        .led_type
+                        .effective_color_order(strip.color_order)
+                        .encode_into(calibrated, calibrated_w, &mut complete_led_data);
                 } else {
                     warn!(
                         "LED索引 {} 超出灯带颜色数组范围 ({})",
@@ -329,7 +552,9 @@ impl LedDataProcessor {
                     );
                     // 添加黑色作为后备
                     match strip.led_type {
-                        LedType::WS2812B => complete_led_data.extend_from_slice(&[0, 0, 0]),
+                        LedType::WS2812B | LedType::WS2811 => {
+                            complete_led_data.extend_from_slice(&[0, 0, 0])
+                        }
                         LedType::SK6812 => complete_led_data.extend_from_slice(&[0, 0, 0, 0]),
                     }
                 }
@@ -355,19 +580,21 @@ impl LedDataProcessor {
     /// * `rgb_colors` - 一维颜色数组
     /// * `led_type` - 强制指定的LED类型
     /// * `led_count` - LED数量
+    /// * `color_order` - 硬件颜色字节顺序
     ///
     /// # 返回值
-    /// 返回硬件编码后的数据（GRB/GRBW格式，无校准）
+    /// 返回硬件编码后的数据（按颜色顺序排列，无校准）
     fn encode_for_test_mode(
         rgb_colors: Vec<LedColor>,
         led_type: LedType,
         led_count: usize,
+        color_order: ColorOrder,
     ) -> Result<Vec<u8>> {
         debug!("🧪 Encoding for test mode: type={led_type:?}, count={led_count}");
 
         // 预分配缓冲区大小，减少内存重分配
         let bytes_per_led = match led_type {
-            LedType::WS2812B => 3,
+            LedType::WS2812B | LedType::WS2811 => 3,
             LedType::SK6812 => 4,
         };
         let mut buffer = Vec::with_capacity(led_count * bytes_per_led);
@@ -377,25 +604,14 @@ impl LedDataProcessor {
             let color = rgb_colors.get(i).unwrap_or(&default_color);
             let rgb = color.get_rgb();
 
-            match led_type {
-                LedType::WS2812B => {
-                    // GRB格式，无校准
-                    buffer.extend_from_slice(&[
-                        rgb[1], // G (Green)
-                        rgb[0], // R (Red)
-                        rgb[2], // B (Blue)
-                    ]);
-                }
-                LedType::SK6812 => {
-                    // GRBW格式，无校准，W通道为0
-                    buffer.extend_from_slice(&[
-                        rgb[1], // G (Green)
-                        rgb[0], // R (Red)
-                        rgb[2], // B (Blue)
-                        0,      // W (White) - 测试模式不使用白色通道
-                    ]);
-                }
-            }
+            // 无校准，测试模式不使用白色通道
+            let white = match led_type {
+                LedType::WS2812B | LedType::WS2811 => None,
+                LedType::SK6812 => Some(0),
+            };
+            led_type
+                .effective_color_order(color_order)
+                .encode_into(rgb, white, &mut buffer);
         }
 
         debug!(
@@ -418,7 +634,7 @@ impl LedDataProcessor {
     /// * `start_led_offset` - LED偏移量
     ///
     /// # 返回值
-    /// 返回硬件编码后的数据（GRB/GRBW格式）
+    /// 返回硬件编码后的数据（按灯带颜色顺序排列，默认GRB/GRBW）
     fn encode_for_hardware_v2(
         led_colors: Vec<Vec<LedColor>>,
         strips: &[LedStripConfigV2],
@@ -444,42 +660,34 @@ impl LedDataProcessor {
                 strip.index, strip.len, strip.led_type, strip.display_internal_id
             );
 
+            // 禁用的灯带输出黑色，保留其在串联数据中的位置
+            if !strip.enabled {
+                complete_led_data.resize(
+                    complete_led_data.len() + strip.len * strip.led_type.bytes_per_led(),
+                    0,
+                );
+                continue;
+            }
+
             // 处理每个LED
             for i in 0..strip.len {
                 if i < strip_colors.len() {
                     let color = &strip_colors[i];
-                    let rgb = color.get_rgb();
-
                     // 应用颜色校准
-                    let calibrated_r = (rgb[0] as f32 * color_calibration.r) as u8;
-                    let calibrated_g = (rgb[1] as f32 * color_calibration.g) as u8;
-                    let calibrated_b = (rgb[2] as f32 * color_calibration.b) as u8;
-
-                    match strip.led_type {
-                        LedType::WS2812B => {
-                            // GRB格式
-                            complete_led_data.extend_from_slice(&[
-                                calibrated_g, // G (Green)
-                                calibrated_r, // R (Red)
-                                calibrated_b, // B (Blue)
-                            ]);
-                        }
+                    let calibrated = Self::calibrate_rgb(color.get_rgb(), color_calibration);
+                    // SK6812的W通道单独校准
+                    let calibrated_w = match strip.led_type {
+                        LedType::WS2812B | LedType::WS2811 => None,
                         LedType::SK6812 => {
-                            // GRBW格式，W通道单独校准
-                            let w_channel = Self::calculate_white_channel(
-                                calibrated_r,
-                                calibrated_g,
-                                calibrated_b,
-                            );
-                            let calibrated_w = (w_channel as f32 * color_calibration.w) as u8;
-                            complete_led_data.extend_from_slice(&[
-                                calibrated_g, // G (Green)
-                                calibrated_r, // R (Red)
-                                calibrated_b, // B (Blue)
-                                calibrated_w, // W (White)
-                            ]);
+                            Some(Self::calibrate_white(calibrated, color_calibration))
                         }
-                    }
+                    };
+
+                    // 按灯带配置的颜色顺序写入（默认GRB/GRBW，WS2811 固定RGB）
+                    strip
+                        .led_type
+                        .effective_color_order(strip.color_order)
+                        .encode_into(calibrated, calibrated_w, &mut complete_led_data);
                 } else {
                     warn!(
                         "LED索引 {} 超出V2灯带颜色数组范围 ({})",
@@ -488,7 +696,7 @@ impl LedDataProcessor {
                     );
                     // 填充黑色
                     match strip.led_type {
-                        LedType::WS2812B => {
+                        LedType::WS2812B | LedType::WS2811 => {
                             complete_led_data.extend_from_slice(&[0, 0, 0]);
                         }
                         LedType::SK6812 => {
@@ -508,13 +716,470 @@ impl LedDataProcessor {
         Ok(complete_led_data)
     }
 
+    /// 对单个LED颜色应用校准（感知亮度归一化、通道系数、高光压缩），返回发送到硬件的RGB
+    fn calibrate_rgb(rgb: [u8; 3], calibration: &ColorCalibration) -> [u8; 3] {
+        let [r, g, b] = Self::apply_perceptual_normalization(rgb, calibration);
+        Self::apply_highlight_rolloff(
+            [
+                (r as f32 * calibration.r) as u8,
+                (g as f32 * calibration.g) as u8,
+                (b as f32 * calibration.b) as u8,
+            ],
+            calibration,
+        )
+    }
+
+    /// 高光压缩：最亮通道超过拐点时按同一比例缩放三个通道（保持色相）
+    ///
+    /// 拐点以上使用 `t - strength * t² / 2` 曲线（t 为超出拐点的比例），
+    /// 在拐点处斜率为 1，过渡平滑且单调；拐点以下的颜色保持不变
+    fn apply_highlight_rolloff(rgb: [u8; 3], calibration: &ColorCalibration) -> [u8; 3] {
+        let strength = calibration.highlight_rolloff.clamp(0.0, 1.0);
+        let knee = calibration.highlight_knee as f32;
+        let max = rgb.into_iter().max().unwrap_or(0) as f32;
+        if strength <= 0.0 || max <= knee {
+            return rgb;
+        }
+
+        let range = 255.0 - knee;
+        let t = (max - knee) / range;
+        let compressed = knee + range * (t - strength * t * t / 2.0);
+        let scale = compressed / max;
+        rgb.map(|channel| (channel as f32 * scale).round() as u8)
+    }
+
+    /// 根据校准后的RGB计算发送到硬件的白色通道值（SK6812）
+    fn calibrate_white(calibrated_rgb: [u8; 3], calibration: &ColorCalibration) -> u8 {
+        let [r, g, b] = calibrated_rgb;
+        let w_channel = Self::calculate_white_channel(r, g, b, calibration);
+        (w_channel as f32 * calibration.w) as u8
+    }
+
+    /// 计算给定校准下每个通道的输入→输出传递曲线
+    ///
+    /// 与硬件编码使用相同的校准步骤：R/G/B 曲线为仅该通道有输入时的输出，
+    /// W 曲线为灰色输入 `(v, v, v)` 时白色通道的输出
+    pub fn calibration_curve(calibration: &ColorCalibration) -> CalibrationCurve {
+        let channel_curve = |channel: usize| -> Vec<u8> {
+            (0..=255u8)
+                .map(|value| {
+                    let mut rgb = [0; 3];
+                    rgb[channel] = value;
+                    Self::calibrate_rgb(rgb, calibration)[channel]
+                })
+                .collect()
+        };
+
+        CalibrationCurve {
+            r: channel_curve(0),
+            g: channel_curve(1),
+            b: channel_curve(2),
+            w: (0..=255u8)
+                .map(|value| {
+                    Self::calibrate_white(Self::calibrate_rgb([value; 3], calibration), calibration)
+                })
+                .collect(),
+        }
+    }
+
+    /// 计算一帧采样颜色的平均 Rec.709 亮度（0-1），没有LED时为0
+    pub fn frame_luminance(led_colors: &[Vec<LedColor>]) -> f32 {
+        let (sum, count) =
+            led_colors
+                .iter()
+                .flatten()
+                .fold((0.0f32, 0usize), |(sum, count), color| {
+                    let [r, g, b] = color.get_rgb().map(|c| c as f32 / 255.0);
+                    (sum + 0.2126 * r + 0.7152 * g + 0.0722 * b, count + 1)
+                });
+
+        if count == 0 {
+            0.0
+        } else {
+            sum / count as f32
+        }
+    }
+
+    /// 根据画面平均亮度计算自动亮度的目标输出比例
+    ///
+    /// `strength` 为0时不调整；为1时反向模式下全白画面降到最低比例，
+    /// 正向模式下全黑画面降到最低比例
+    pub fn auto_brightness_scale(luminance: f32, mode: AutoBrightnessMode, strength: f32) -> f32 {
+        let reduction = match mode {
+            AutoBrightnessMode::Inverse => luminance,
+            AutoBrightnessMode::Proportional => 1.0 - luminance,
+        };
+        (1.0 - strength * reduction).clamp(AUTO_BRIGHTNESS_MIN_SCALE, 1.0)
+    }
+
+    /// 按距上一帧的时间从上一帧的比例向目标比例过渡，避免亮度随画面频繁跳动
+    ///
+    /// 过渡速度只取决于经过的时间，不同帧率的显示器过渡快慢一致
+    fn smooth_auto_brightness_scale(previous: Option<f32>, target: f32, elapsed: Duration) -> f32 {
+        match previous {
+            Some(previous) => {
+                let factor = 1.0
+                    - (-elapsed.as_secs_f32() / AUTO_BRIGHTNESS_TIME_CONSTANT.as_secs_f32()).exp();
+                previous + (target - previous) * factor
+            }
+            None => target,
+        }
+    }
+
+    /// 按用户设置对一个显示器的整帧颜色应用自动亮度，各显示器分别平滑
+    async fn apply_auto_brightness(
+        mut led_colors: Vec<Vec<LedColor>>,
+        display_id: Option<u32>,
+    ) -> Vec<Vec<LedColor>> {
+        let preferences = crate::user_preferences::UserPreferencesManager::global()
+            .await
+            .get_auto_brightness()
+            .await;
+
+        let mut scales = AUTO_BRIGHTNESS_SCALES.lock().await;
+        if !preferences.enabled {
+            scales.clear();
+            return led_colors;
+        }
+
+        let target = Self::auto_brightness_scale(
+            Self::frame_luminance(&led_colors),
+            preferences.mode,
+            preferences.strength,
+        );
+        let now = Instant::now();
+        let previous = scales.get(&display_id).copied();
+        let scale = Self::smooth_auto_brightness_scale(
+            previous.map(|(scale, _)| scale),
+            target,
+            previous.map_or(Duration::ZERO, |(_, updated_at)| now - updated_at),
+        );
+        scales.insert(display_id, (scale, now));
+        drop(scales);
+
+        for color in led_colors.iter_mut().flatten() {
+            let [r, g, b] = color
+                .get_rgb()
+                .map(|c| (c as f32 * scale).round().clamp(0.0, 255.0) as u8);
+            color.set_rgb(r, g, b);
+        }
+        led_colors
+    }
+
+    /// 光标跟随模式下显示器的目标输出比例
+    ///
+    /// 光标所在显示器按强度提升亮度，其他显示器按强度调暗；`strength` 为0时不调整
+    pub fn cursor_focus_scale(focused: bool, strength: f32) -> f32 {
+        if focused {
+            1.0 + strength * CURSOR_FOCUS_MAX_BOOST
+        } else {
+            (1.0 - strength).max(0.0)
+        }
+    }
+
+    /// 按光标所在的显示器调整该显示器灯带的输出，光标位置未知时恢复原始亮度
+    async fn apply_cursor_focus(
+        mut led_colors: Vec<Vec<LedColor>>,
+        display_id: Option<u32>,
+    ) -> Vec<Vec<LedColor>> {
+        let preferences = crate::user_preferences::UserPreferencesManager::global()
+            .await
+            .get_cursor_focus()
+            .await;
+
+        let mut scales = CURSOR_FOCUS_SCALES.lock().await;
+        if !preferences.enabled {
+            scales.clear();
+            return led_colors;
+        }
+        let Some(display_id) = display_id else {
+            return led_colors;
+        };
+
+        let cursor_display = crate::screenshot_manager::ScreenshotManager::global()
+            .await
+            .get_cursor_display()
+            .await;
+        let target = match cursor_display {
+            Some(cursor_display) => {
+                Self::cursor_focus_scale(cursor_display == display_id, preferences.strength)
+            }
+            None => 1.0,
+        };
+        let previous = scales.get(&display_id).copied().unwrap_or(1.0);
+        let scale = previous + (target - previous) * CURSOR_FOCUS_SMOOTHING;
+        scales.insert(display_id, scale);
+        drop(scales);
+
+        for color in led_colors.iter_mut().flatten() {
+            let [r, g, b] = color
+                .get_rgb()
+                .map(|c| (c as f32 * scale).round().clamp(0.0, 255.0) as u8);
+            color.set_rgb(r, g, b);
+        }
+        led_colors
+    }
+
+    /// 一天中某一时刻（午夜起的分钟数）的目标色温（K）
+    ///
+    /// 从日出开始在 `transition_minutes` 内由夜间色温过渡到日间色温，
+    /// 从日落开始同样时长内过渡回夜间色温，支持跨越午夜的时间段。
+    pub fn scheduled_kelvin(
+        minute_of_day: f32,
+        settings: &ColorTemperatureShiftPreferences,
+    ) -> f32 {
+        let day = MINUTES_PER_DAY as f32;
+        let since_sunrise = (minute_of_day - settings.sunrise_minute as f32).rem_euclid(day);
+        let since_sunset = (minute_of_day - settings.sunset_minute as f32).rem_euclid(day);
+        let day_length =
+            (settings.sunset_minute as f32 - settings.sunrise_minute as f32).rem_euclid(day);
+        let progress = |elapsed: f32| {
+            if settings.transition_minutes == 0 {
+                1.0
+            } else {
+                (elapsed / settings.transition_minutes as f32).min(1.0)
+            }
+        };
+
+        // 0 为夜间色温，1 为日间色温
+        let daylight = if since_sunrise < day_length {
+            progress(since_sunrise)
+        } else {
+            1.0 - progress(since_sunset)
+        };
+        let (day_kelvin, night_kelvin) = (settings.day_kelvin as f32, settings.night_kelvin as f32);
+        night_kelvin + (day_kelvin - night_kelvin) * daylight
+    }
+
+    /// 色温对应的各通道增益（相对参考色温 6500K 归一化，最大通道为 1）
+    ///
+    /// 使用 Tanner Helland 的黑体辐射颜色近似公式。
+    pub fn kelvin_channel_gains(kelvin: f32) -> [f32; 3] {
+        let blackbody = |kelvin: f32| -> [f32; 3] {
+            let t = kelvin.clamp(MIN_SHIFT_KELVIN as f32, 40000.0) / 100.0;
+            let r = if t <= 66.0 {
+                255.0
+            } else {
+                329.69873 * (t - 60.0).powf(-0.13320476)
+            };
+            let g = if t <= 66.0 {
+                99.4708 * t.ln() - 161.11957
+            } else {
+                288.12217 * (t - 60.0).powf(-0.07551485)
+            };
+            let b = if t >= 66.0 {
+                255.0
+            } else if t <= 19.0 {
+                0.0
+            } else {
+                138.51773 * (t - 10.0).ln() - 305.0448
+            };
+            [r, g, b].map(|c| c.clamp(0.0, 255.0))
+        };
+
+        let target = blackbody(kelvin);
+        let reference = blackbody(REFERENCE_KELVIN);
+        let gains: [f32; 3] = std::array::from_fn(|i| target[i] / reference[i]);
+        let max = gains.iter().copied().fold(f32::MIN, f32::max);
+        gains.map(|gain| gain / max)
+    }
+
+    /// 按用户设置和当前本地时间对整帧颜色应用色温偏移（叠加在静态白平衡之上）
+    async fn apply_color_temperature_shift(
+        mut led_colors: Vec<Vec<LedColor>>,
+    ) -> Vec<Vec<LedColor>> {
+        let settings = crate::user_preferences::UserPreferencesManager::global()
+            .await
+            .get_color_temperature_shift()
+            .await;
+        if !settings.enabled {
+            return led_colors;
+        }
+
+        use chrono::Timelike;
+        let now = chrono::Local::now();
+        let minute_of_day = (now.hour() * 60 + now.minute()) as f32 + now.second() as f32 / 60.0;
+        let gains = Self::kelvin_channel_gains(Self::scheduled_kelvin(minute_of_day, &settings));
+
+        for color in led_colors.iter_mut().flatten() {
+            let rgb = color.get_rgb();
+            let [r, g, b] =
+                std::array::from_fn(|i| (rgb[i] as f32 * gains[i]).round().clamp(0.0, 255.0) as u8);
+            color.set_rgb(r, g, b);
+        }
+        led_colors
+    }
+
+    /// 在调色板中查找与 `rgb` 欧氏距离最近的颜色，调色板为空时原样返回
+    pub fn nearest_palette_color(rgb: [u8; 3], palette: &[[u8; 3]]) -> [u8; 3] {
+        palette
+            .iter()
+            .min_by_key(|entry| {
+                entry
+                    .iter()
+                    .zip(rgb)
+                    .map(|(&a, b)| (a as i32 - b as i32).pow(2))
+                    .sum::<i32>()
+            })
+            .copied()
+            .unwrap_or(rgb)
+    }
+
+    /// 按用户设置将整帧颜色映射到调色板中最近的颜色
+    async fn apply_palette_quantization(mut led_colors: Vec<Vec<LedColor>>) -> Vec<Vec<LedColor>> {
+        let preferences = crate::user_preferences::UserPreferencesManager::global()
+            .await
+            .get_palette_quantization()
+            .await;
+        if !preferences.enabled {
+            return led_colors;
+        }
+
+        for color in led_colors.iter_mut().flatten() {
+            let [r, g, b] = Self::nearest_palette_color(color.get_rgb(), &preferences.palette);
+            color.set_rgb(r, g, b);
+        }
+        led_colors
+    }
+
+    /// 颜色死区：与上次输出相比，变化小于 `deadband` 的通道保持上次的值
+    ///
+    /// 与时间平滑不同，这是一种量化迟滞：静态画面的 ±1 采样抖动被完全吸收，
+    /// 而超过死区的变化立即生效。`held` 为上次输出，LED数量变化时重新开始。
+    fn apply_deadband(held: &mut Vec<[u8; 3]>, led_colors: &mut [Vec<LedColor>], deadband: u8) {
+        let led_count: usize = led_colors.iter().map(Vec::len).sum();
+        if held.len() != led_count {
+            *held = led_colors.iter().flatten().map(LedColor::get_rgb).collect();
+            return;
+        }
+
+        for (color, held_rgb) in led_colors.iter_mut().flatten().zip(held.iter_mut()) {
+            for (held_value, value) in held_rgb.iter_mut().zip(color.get_rgb()) {
+                if value.abs_diff(*held_value) >= deadband {
+                    *held_value = value;
+                }
+            }
+            color.set_rgb(held_rgb[0], held_rgb[1], held_rgb[2]);
+        }
+    }
+
+    /// 按LED偏移量保持的上次输出应用颜色死区，死区为 0 时清除保持的状态
+    async fn apply_deadband_for_offset(
+        mut led_colors: Vec<Vec<LedColor>>,
+        start_led_offset: usize,
+        deadband: u8,
+    ) -> Vec<Vec<LedColor>> {
+        let mut held_colors = DEADBAND_HELD_COLORS.lock().await;
+        if deadband == 0 {
+            held_colors.remove(&start_led_offset);
+            return led_colors;
+        }
+
+        let held = held_colors.entry(start_led_offset).or_default();
+        Self::apply_deadband(held, &mut led_colors, deadband);
+        led_colors
+    }
+
+    /// 感知亮度归一化（需在校准中启用）
+    ///
+    /// 相同RGB幅值的颜色亮度观感不同（绿色明显比蓝色亮）。按 Rec.709 亮度
+    /// `0.2126R + 0.7152G + 0.0722B` 将颜色缩放到目标亮度：目标亮度随饱和度
+    /// 从颜色本身的明度（灰色，不变）过渡到三原色的平均亮度（纯色）。
+    /// 只缩放整体幅值，不改变色相；无法提升已达到最大值的通道。
+    fn apply_perceptual_normalization(rgb: [u8; 3], calibration: &ColorCalibration) -> [u8; 3] {
+        if !calibration.perceptual_normalization {
+            return rgb;
+        }
+
+        let [r, g, b] = rgb.map(|c| c as f32 / 255.0);
+        let max = r.max(g).max(b);
+        if max == 0.0 {
+            return rgb;
+        }
+
+        let luminance = 0.2126 * r + 0.7152 * g + 0.0722 * b;
+        let saturation = (max - r.min(g).min(b)) / max;
+        let target = max * (saturation * PERCEPTUAL_REFERENCE_LUMINANCE + (1.0 - saturation));
+
+        let scale = (target / luminance).min(1.0 / max);
+        rgb.map(|c| (c as f32 * scale).round().clamp(0.0, 255.0) as u8)
+    }
+
     /// 计算SK6812的白色通道值
     ///
-    /// 基于RGB值计算合适的白色通道值
-    fn calculate_white_channel(r: u8, g: u8, b: u8) -> u8 {
-        // 使用RGB的最小值作为白色通道的基础
-        // 这样可以减少RGB通道的负担，提高亮度效率
-        std::cmp::min(std::cmp::min(r, g), b)
+    /// 基于RGB值和校准中选择的白色合成策略计算合适的白色通道值
+    fn calculate_white_channel(r: u8, g: u8, b: u8, calibration: &ColorCalibration) -> u8 {
+        let min = r.min(g).min(b);
+
+        match calibration.white_synthesis {
+            WhiteSynthesis::None => 0,
+            // 使用RGB的最小值作为白色通道的基础
+            // 这样可以减少RGB通道的负担，提高亮度效率
+            WhiteSynthesis::Min => min,
+            WhiteSynthesis::Luminance => {
+                let max = r.max(g).max(b);
+                if max == 0 {
+                    return 0;
+                }
+
+                // 线性化后按 Rec.709 权重计算相对亮度，再转换为感知亮度 L*
+                let to_linear = |c: u8| {
+                    let c = c as f32 / 255.0;
+                    if c <= 0.04045 {
+                        c / 12.92
+                    } else {
+                        ((c + 0.055) / 1.055).powf(2.4)
+                    }
+                };
+                let y = 0.2126 * to_linear(r) + 0.7152 * to_linear(g) + 0.0722 * to_linear(b);
+                let lightness = if y > 0.008856 {
+                    1.16 * y.cbrt() - 0.16
+                } else {
+                    y * 9.033
+                };
+
+                // 按饱和度衰减，避免给高饱和颜色叠加白光
+                let whiteness = min as f32 / max as f32;
+                (lightness * whiteness * 255.0).round().clamp(0.0, 255.0) as u8
+            }
+            WhiteSynthesis::ColorTemperatureMatched => {
+                // 计算颜色中可由白色LED（白点）提供的最大分量：
+                // 与白点越接近，可提取的白光越多
+                // 全零白点已在校验时拒绝，这里仍按无白光处理以防万一
+                let [wr, wg, wb] = calibration.white_point;
+                if calibration.white_point == [0, 0, 0] {
+                    return 0;
+                }
+                [(r, wr), (g, wg), (b, wb)]
+                    .iter()
+                    .filter(|(_, white)| *white > 0)
+                    .map(|(c, white)| *c as f32 * 255.0 / *white as f32)
+                    .fold(255.0_f32, f32::min)
+                    .round() as u8
+            }
+            WhiteSynthesis::Accurate => {
+                // 以白点为基准归一化各通道，中性色（与白点同色）归一化后三通道相等
+                let [wr, wg, wb] = calibration.white_point;
+                let normalized: Vec<f32> = [(r, wr), (g, wg), (b, wb)]
+                    .iter()
+                    .filter(|(_, white)| *white > 0)
+                    .map(|(c, white)| (*c as f32 * 255.0 / *white as f32).min(255.0))
+                    .collect();
+                let max = normalized.iter().copied().fold(0.0_f32, f32::max);
+                if max <= 0.0 {
+                    return 0;
+                }
+                let min = normalized.iter().copied().fold(255.0_f32, f32::min);
+
+                // 白度低于阈值的饱和颜色不提取白光，阈值以上平滑过渡到完全提取
+                let whiteness = min / max;
+                let t = ((whiteness - ACCURATE_WHITENESS_THRESHOLD)
+                    / (1.0 - ACCURATE_WHITENESS_THRESHOLD))
+                    .clamp(0.0, 1.0);
+                let weight = t * t * (3.0 - 2.0 * t);
+                (min * weight).round().clamp(0.0, 255.0) as u8
+            }
+        }
     }
 
     /// 获取当前颜色校准配置
@@ -534,8 +1199,13 @@ impl LedDataProcessor {
         strips: &[LedStripConfig],
         websocket_publisher: &WebSocketEventPublisher,
     ) {
+        let preview_caps = websocket_publisher.led_strip_preview_caps().await;
         for (strip, colors) in strips.iter().zip(led_colors.iter()) {
+            if !strip.enabled {
+                continue;
+            }
             let rgb_bytes: Vec<u8> = colors.iter().flat_map(|color| color.get_rgb()).collect();
+            let rgb_bytes = Self::merge_spanning_strip_preview(strip, rgb_bytes).await;
 
             let border_str = match strip.border {
                 Border::Top => "Top",
@@ -546,15 +1216,88 @@ impl LedDataProcessor {
 
             websocket_publisher
                 .publish_led_strip_colors_changed(
-                    strip.display_id,
+                    strip.reported_display_id(),
                     border_str,
-                    strip.index,
+                    strip.reported_index(),
                     &rgb_bytes,
                 )
                 .await;
+
+            Self::publish_strip_preview_downsampled(
+                strip.reported_display_id(),
+                border_str,
+                strip.reported_index(),
+                &rgb_bytes,
+                &preview_caps,
+                websocket_publisher,
+            )
+            .await;
         }
     }
 
+    /// 跨屏灯带的分段与其他分段最近一帧的颜色按串联顺序拼接为原灯带的颜色，
+    /// 普通灯带原样返回
+    ///
+    /// 分段序列号按串联顺序分配，因此按序列号排列即为原灯带的物理顺序
+    async fn merge_spanning_strip_preview(strip: &LedStripConfig, rgb_bytes: Vec<u8>) -> Vec<u8> {
+        let Some(source_index) = strip.source_index else {
+            return rgb_bytes;
+        };
+
+        let mut preview = SPANNING_STRIP_PREVIEW.lock().await;
+        let segments = preview.entry(source_index).or_default();
+        segments.insert(strip.index, rgb_bytes);
+        segments.values().flatten().copied().collect()
+    }
+
+    /// 按客户端订阅的每个分辨率上限，发布该灯带降采样后的低分辨率预览
+    async fn publish_strip_preview_downsampled(
+        display_id: u32,
+        border: &str,
+        strip_index: usize,
+        rgb_bytes: &[u8],
+        preview_caps: &BTreeSet<usize>,
+        websocket_publisher: &WebSocketEventPublisher,
+    ) {
+        for &max_colors in preview_caps {
+            let preview = Self::downsample_rgb_bytes(rgb_bytes, max_colors);
+            websocket_publisher
+                .publish_led_strip_preview_colors_changed(
+                    display_id,
+                    border,
+                    strip_index,
+                    rgb_bytes.len() / 3,
+                    max_colors,
+                    &preview,
+                )
+                .await;
+        }
+    }
+
+    /// 将RGB字节数组降采样到最多 `max_colors` 个颜色，每个颜色为连续一段LED的平均值；
+    /// LED数量不超过上限时原样返回
+    pub fn downsample_rgb_bytes(rgb_bytes: &[u8], max_colors: usize) -> Vec<u8> {
+        let led_count = rgb_bytes.len() / 3;
+        if max_colors == 0 || led_count <= max_colors {
+            return rgb_bytes[..led_count * 3].to_vec();
+        }
+
+        (0..max_colors)
+            .flat_map(|bucket| {
+                let start = bucket * led_count / max_colors;
+                let end = (bucket + 1) * led_count / max_colors;
+                let mut sum = [0u32; 3];
+                for pixel in rgb_bytes[start * 3..end * 3].chunks_exact(3) {
+                    for (channel, &value) in sum.iter_mut().zip(pixel) {
+                        *channel += value as u32;
+                    }
+                }
+                let count = (end - start) as u32;
+                sum.map(|channel| ((channel + count / 2) / count) as u8)
+            })
+            .collect()
+    }
+
     /// V2版本：按灯带分组发布LED颜色数据
     ///
     /// 为每个V2灯带单独发布颜色数据，解决多显示器LED预览闪烁问题
@@ -564,7 +1307,11 @@ impl LedDataProcessor {
         display_registry: &DisplayRegistry,
         websocket_publisher: &WebSocketEventPublisher,
     ) {
+        let preview_caps = websocket_publisher.led_strip_preview_caps().await;
         for (strip, colors) in strips.iter().zip(led_colors.iter()) {
+            if !strip.enabled {
+                continue;
+            }
             let rgb_bytes: Vec<u8> = colors.iter().flat_map(|color| color.get_rgb()).collect();
 
             let border_str = match strip.border {
@@ -598,6 +1345,576 @@ impl LedDataProcessor {
             websocket_publisher
                 .publish_led_strip_colors_changed(display_id, border_str, strip.index, &rgb_bytes)
                 .await;
+
+            Self::publish_strip_preview_downsampled(
+                display_id,
+                border_str,
+                strip.index,
+                &rgb_bytes,
+                &preview_caps,
+                websocket_publisher,
+            )
+            .await;
         }
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn calibration_with(white_synthesis: WhiteSynthesis) -> ColorCalibration {
+        ColorCalibration {
+            white_synthesis,
+            ..ColorCalibration::new()
+        }
+    }
+
+    #[test]
+    fn test_downsample_rgb_bytes_averages_segments() {
+        // 5 颗灯珠降到 2 个颜色：前 2 颗、后 3 颗分别取平均
+        let rgb: Vec<u8> = [0u8, 100, 10, 20, 30]
+            .iter()
+            .flat_map(|&value| [value, 255 - value, 0])
+            .collect();
+        assert_eq!(
+            LedDataProcessor::downsample_rgb_bytes(&rgb, 2),
+            vec![50, 205, 0, 20, 235, 0]
+        );
+
+        // 灯珠数量不超过上限时原样返回
+        assert_eq!(LedDataProcessor::downsample_rgb_bytes(&rgb, 5), rgb);
+        assert_eq!(LedDataProcessor::downsample_rgb_bytes(&rgb, 64), rgb);
+    }
+
+    #[tokio::test]
+    async fn test_merge_spanning_strip_preview_joins_segments_in_chain_order() {
+        let segment = |index: usize, display_id: u32| LedStripConfig {
+            source_index: Some(1000),
+            source_display_id: Some(1),
+            ..LedStripConfig::default_for_display(display_id, index)
+        };
+
+        // 后一个分段先到达时，拼接结果仍按分段序列号排列
+        let merged =
+            LedDataProcessor::merge_spanning_strip_preview(&segment(1002, 2), vec![4, 5, 6]).await;
+        assert_eq!(merged, vec![4, 5, 6]);
+        let merged =
+            LedDataProcessor::merge_spanning_strip_preview(&segment(1001, 1), vec![1, 2, 3]).await;
+        assert_eq!(merged, vec![1, 2, 3, 4, 5, 6]);
+
+        // 普通灯带原样返回
+        let plain = LedStripConfig::default_for_display(1, 1003);
+        assert_eq!(
+            LedDataProcessor::merge_spanning_strip_preview(&plain, vec![7, 8, 9]).await,
+            vec![7, 8, 9]
+        );
+
+        SPANNING_STRIP_PREVIEW.lock().await.remove(&1000);
+    }
+
+    #[test]
+    fn test_min_strategy_is_default() {
+        let calibration = ColorCalibration::new();
+        assert_eq!(calibration.white_synthesis, WhiteSynthesis::Min);
+        assert_eq!(
+            LedDataProcessor::calculate_white_channel(200, 120, 80, &calibration),
+            80
+        );
+    }
+
+    #[test]
+    fn test_luminance_strategy_drives_gray_stronger_than_min() {
+        let min = calibration_with(WhiteSynthesis::Min);
+        let luminance = calibration_with(WhiteSynthesis::Luminance);
+
+        for gray in [64u8, 128, 200] {
+            let w_min = LedDataProcessor::calculate_white_channel(gray, gray, gray, &min);
+            let w_luminance =
+                LedDataProcessor::calculate_white_channel(gray, gray, gray, &luminance);
+            assert!(
+                w_luminance > w_min,
+                "gray {gray}: luminance W {w_luminance} should exceed min W {w_min}"
+            );
+        }
+
+        // 纯色不应叠加白光
+        assert_eq!(
+            LedDataProcessor::calculate_white_channel(255, 0, 0, &luminance),
+            0
+        );
+        assert_eq!(
+            LedDataProcessor::calculate_white_channel(0, 0, 0, &luminance),
+            0
+        );
+    }
+
+    #[test]
+    fn test_color_temperature_matched_strategy() {
+        let calibration = ColorCalibration {
+            white_synthesis: WhiteSynthesis::ColorTemperatureMatched,
+            white_point: [255, 180, 110],
+            ..ColorCalibration::new()
+        };
+
+        // 与白点完全一致的颜色可完全由白色LED提供
+        assert_eq!(
+            LedDataProcessor::calculate_white_channel(255, 180, 110, &calibration),
+            255
+        );
+        // 偏冷的颜色受蓝色分量限制，白光更少
+        let cool = LedDataProcessor::calculate_white_channel(110, 180, 255, &calibration);
+        assert!(cool < 255);
+        assert_eq!(cool, 110);
+
+        // 全零白点不提取白光
+        let zero = ColorCalibration {
+            white_point: [0, 0, 0],
+            ..calibration
+        };
+        assert_eq!(
+            LedDataProcessor::calculate_white_channel(255, 255, 255, &zero),
+            0
+        );
+    }
+
+    #[test]
+    fn test_accurate_strategy_keeps_saturated_colors_on_rgb() {
+        let min = calibration_with(WhiteSynthesis::Min);
+        let accurate = calibration_with(WhiteSynthesis::Accurate);
+
+        // 饱和红色：Min、ColorTemperatureMatched、Luminance 都会提取白光，
+        // Accurate 保持在RGB通道上
+        assert!(LedDataProcessor::calculate_white_channel(255, 40, 40, &min) > 0);
+        let matched = calibration_with(WhiteSynthesis::ColorTemperatureMatched);
+        assert_eq!(
+            LedDataProcessor::calculate_white_channel(255, 40, 40, &matched),
+            40
+        );
+        let luminance = calibration_with(WhiteSynthesis::Luminance);
+        assert!(LedDataProcessor::calculate_white_channel(255, 40, 40, &luminance) > 0);
+        assert_eq!(
+            LedDataProcessor::calculate_white_channel(255, 40, 40, &accurate),
+            0
+        );
+
+        // 中性色仍然完全由白色LED提供
+        assert_eq!(
+            LedDataProcessor::calculate_white_channel(200, 200, 200, &accurate),
+            200
+        );
+
+        // 以白点为基准判断中性色：与暖白点同色的颜色可完全提取白光
+        let warm = ColorCalibration {
+            white_synthesis: WhiteSynthesis::Accurate,
+            white_point: [255, 180, 110],
+            ..ColorCalibration::new()
+        };
+        assert_eq!(
+            LedDataProcessor::calculate_white_channel(255, 180, 110, &warm),
+            255
+        );
+
+        let none = calibration_with(WhiteSynthesis::None);
+        assert_eq!(
+            LedDataProcessor::calculate_white_channel(200, 200, 200, &none),
+            0
+        );
+    }
+
+    #[test]
+    fn test_perceptual_normalization_balances_green_and_blue() {
+        let luminance =
+            |[r, g, b]: [u8; 3]| 0.2126 * r as f32 + 0.7152 * g as f32 + 0.0722 * b as f32;
+        let green = [0, 255, 0];
+        let blue = [0, 0, 255];
+
+        let disabled = ColorCalibration::new();
+        assert_eq!(
+            LedDataProcessor::apply_perceptual_normalization(green, &disabled),
+            green
+        );
+
+        let enabled = ColorCalibration {
+            perceptual_normalization: true,
+            ..ColorCalibration::new()
+        };
+        let normalized_green = LedDataProcessor::apply_perceptual_normalization(green, &enabled);
+        let normalized_blue = LedDataProcessor::apply_perceptual_normalization(blue, &enabled);
+
+        let gap_before = luminance(green) - luminance(blue);
+        let gap_after = (luminance(normalized_green) - luminance(normalized_blue)).abs();
+        assert!(
+            gap_after < gap_before,
+            "luminance gap {gap_after} should shrink from {gap_before}"
+        );
+        // 仅缩放幅值，不改变色相
+        assert_eq!(normalized_green[0], 0);
+        assert_eq!(normalized_green[2], 0);
+
+        // 灰色保持不变
+        assert_eq!(
+            LedDataProcessor::apply_perceptual_normalization([128, 128, 128], &enabled),
+            [128, 128, 128]
+        );
+    }
+
+    #[test]
+    fn test_highlight_rolloff_compresses_near_white_only() {
+        let disabled = ColorCalibration::new();
+        assert_eq!(
+            LedDataProcessor::calibrate_rgb([255, 255, 255], &disabled),
+            [255, 255, 255]
+        );
+
+        let calibration = ColorCalibration {
+            highlight_knee: 205,
+            highlight_rolloff: 1.0,
+            ..ColorCalibration::new()
+        };
+
+        // 接近满白的输入被压缩，满亮度压到拐点与255的中点
+        let white = LedDataProcessor::calibrate_rgb([255, 255, 255], &calibration);
+        assert_eq!(white, [230, 230, 230]);
+        let near_white = LedDataProcessor::calibrate_rgb([240, 240, 240], &calibration);
+        assert!(near_white[0] < 240 && near_white[0] > 205);
+        assert!(near_white[0] < white[0]);
+
+        // 中间调保持不变
+        for value in [0u8, 64, 128, 200] {
+            assert_eq!(
+                LedDataProcessor::calibrate_rgb([value; 3], &calibration),
+                [value; 3]
+            );
+        }
+
+        // 按同一比例缩放，保持色相
+        let warm = LedDataProcessor::calibrate_rgb([255, 180, 100], &calibration);
+        assert_eq!(warm[0], 230);
+        assert!((warm[1] as f32 / warm[0] as f32 - 180.0 / 255.0).abs() < 0.01);
+    }
+
+    #[test]
+    fn test_calibration_curve_follows_encoding() {
+        let identity: Vec<u8> = (0..=255).collect();
+        let curve = LedDataProcessor::calibration_curve(&ColorCalibration::new());
+        assert_eq!(curve.r, identity);
+        assert_eq!(curve.g, identity);
+        assert_eq!(curve.b, identity);
+        assert_eq!(curve.w, identity);
+
+        let calibration = ColorCalibration {
+            r: 0.5,
+            w: 0.0,
+            ..ColorCalibration::new()
+        };
+        let curve = LedDataProcessor::calibration_curve(&calibration);
+        assert_eq!(curve.r[255], 127);
+        assert_eq!(curve.g, identity);
+        assert!(curve.w.iter().all(|&w| w == 0));
+
+        // 曲线与硬件编码结果一致
+        for value in [0u8, 64, 200, 255] {
+            let encoded = LedDataProcessor::calibrate_rgb([value, 0, 0], &calibration);
+            assert_eq!(curve.r[value as usize], encoded[0]);
+        }
+    }
+
+    #[test]
+    fn test_inverse_auto_brightness_dims_bright_frames() {
+        let frame = |value: u8| vec![vec![LedColor::new(value, value, value); 4]; 2];
+        let bright = LedDataProcessor::frame_luminance(&frame(240));
+        let dark = LedDataProcessor::frame_luminance(&frame(20));
+        assert!(bright > dark);
+
+        let bright_scale =
+            LedDataProcessor::auto_brightness_scale(bright, AutoBrightnessMode::Inverse, 0.8);
+        let dark_scale =
+            LedDataProcessor::auto_brightness_scale(dark, AutoBrightnessMode::Inverse, 0.8);
+        assert!(bright_scale < dark_scale);
+
+        // 正向模式方向相反，强度为0时不调整
+        assert!(
+            LedDataProcessor::auto_brightness_scale(bright, AutoBrightnessMode::Proportional, 0.8)
+                > LedDataProcessor::auto_brightness_scale(
+                    dark,
+                    AutoBrightnessMode::Proportional,
+                    0.8
+                )
+        );
+        assert_eq!(
+            LedDataProcessor::auto_brightness_scale(bright, AutoBrightnessMode::Inverse, 0.0),
+            1.0
+        );
+
+        // 平滑只向目标靠近一部分，避免亮度跳变
+        let frame_interval = Duration::from_millis(33);
+        let smoothed =
+            LedDataProcessor::smooth_auto_brightness_scale(Some(1.0), bright_scale, frame_interval);
+        assert!(smoothed < 1.0 && smoothed > bright_scale);
+    }
+
+    #[test]
+    fn test_auto_brightness_smoothing_is_independent_of_frame_rate() {
+        let target = 0.4;
+        let smooth_for = |frame_interval: Duration, frames: u32| {
+            (0..frames).fold(1.0, |scale, _| {
+                LedDataProcessor::smooth_auto_brightness_scale(Some(scale), target, frame_interval)
+            })
+        };
+
+        // 30fps 与 120fps 经过相同时间后到达的比例一致
+        let at_30fps = smooth_for(Duration::from_secs(1) / 30, 30);
+        let at_120fps = smooth_for(Duration::from_secs(1) / 120, 120);
+        assert!((at_30fps - at_120fps).abs() < 1e-3);
+
+        // 没有经过时间时保持不变
+        assert_eq!(
+            LedDataProcessor::smooth_auto_brightness_scale(Some(1.0), target, Duration::ZERO),
+            1.0
+        );
+    }
+
+    #[test]
+    fn test_disabled_strip_outputs_black_without_shifting_neighbors() {
+        let strip = |index: usize, enabled: bool| LedStripConfig {
+            index,
+            len: 2,
+            enabled,
+            ..LedStripConfig::default_for_display(1, index)
+        };
+        let colors = vec![vec![LedColor::new(10, 20, 30); 2]; 3];
+        let encode = |strips: &[LedStripConfig]| {
+            LedDataProcessor::encode_for_hardware(
+                colors.clone(),
+                strips,
+                &ColorCalibration::new(),
+                0,
+                DisplayWinding::Clockwise,
+            )
+            .unwrap()
+        };
+
+        let all_enabled = encode(&[strip(0, true), strip(1, true), strip(2, true)]);
+        let middle_disabled = encode(&[strip(0, true), strip(1, false), strip(2, true)]);
+
+        assert_eq!(middle_disabled.len(), all_enabled.len());
+        assert_eq!(middle_disabled[..6], all_enabled[..6]);
+        assert!(middle_disabled[6..12].iter().all(|&byte| byte == 0));
+        assert_eq!(middle_disabled[12..], all_enabled[12..]);
+        assert!(all_enabled[6..12].iter().any(|&byte| byte != 0));
+    }
+
+    #[test]
+    fn test_zero_length_strips_contribute_no_bytes() {
+        let strip = |index: usize, len: usize, led_type: LedType| LedStripConfig {
+            index,
+            len,
+            led_type,
+            ..LedStripConfig::default_for_display(1, index)
+        };
+        let encode = |colors: Vec<Vec<LedColor>>, strips: &[LedStripConfig]| {
+            LedDataProcessor::encode_for_hardware(
+                colors,
+                strips,
+                &ColorCalibration::new(),
+                0,
+                DisplayWinding::Clockwise,
+            )
+            .unwrap()
+        };
+        let red = vec![LedColor::new(255, 0, 0); 2];
+        let green = vec![LedColor::new(0, 255, 0); 3];
+
+        let expected = encode(
+            vec![red.clone(), green.clone()],
+            &[strip(0, 2, LedType::WS2812B), strip(1, 3, LedType::SK6812)],
+        );
+        // 零长度灯带位于开头、中间和末尾，且颜色数组可能缺少对应项
+        let interleaved = encode(
+            vec![Vec::new(), red, Vec::new(), green],
+            &[
+                strip(0, 0, LedType::SK6812),
+                strip(1, 2, LedType::WS2812B),
+                strip(2, 0, LedType::WS2812B),
+                strip(3, 3, LedType::SK6812),
+                strip(4, 0, LedType::SK6812),
+            ],
+        );
+
+        assert_eq!(expected.len(), 2 * 3 + 3 * 4);
+        assert_eq!(interleaved, expected);
+    }
+
+    #[test]
+    fn test_ws2811_encodes_rgb_regardless_of_color_order() {
+        let strip = |index: usize, led_type: LedType| LedStripConfig {
+            index,
+            len: 1,
+            led_type,
+            ..LedStripConfig::default_for_display(1, index)
+        };
+        let colors = vec![vec![LedColor::new(10, 20, 30)]; 2];
+
+        let data = LedDataProcessor::encode_for_hardware(
+            colors,
+            &[strip(0, LedType::WS2811), strip(1, LedType::WS2812B)],
+            &ColorCalibration::new(),
+            0,
+            DisplayWinding::Clockwise,
+        )
+        .unwrap();
+
+        // WS2811 为RGB，默认GRB顺序的WS2812B为GRB，都是3字节
+        assert_eq!(data, vec![10, 20, 30, 20, 10, 30]);
+
+        let test_data = LedDataProcessor::encode_for_test_mode(
+            vec![LedColor::new(10, 20, 30)],
+            LedType::WS2811,
+            2,
+            ColorOrder::default(),
+        )
+        .unwrap();
+        assert_eq!(test_data, vec![10, 20, 30, 0, 0, 0]);
+    }
+
+    #[test]
+    fn test_counterclockwise_winding_reverses_display_order() {
+        let strip = |index: usize, len: usize| LedStripConfig {
+            index,
+            len,
+            led_type: LedType::WS2811,
+            ..LedStripConfig::default_for_display(1, index)
+        };
+        // 灯带数组不按序列号排列，颜色数组与灯带数组一一对应
+        let strips = [strip(1, 3), strip(0, 2)];
+        let red = |values: &[u8]| values.iter().map(|&r| LedColor::new(r, 0, 0)).collect();
+        let colors: Vec<Vec<LedColor>> = vec![red(&[3, 4, 5]), red(&[1, 2])];
+        let encode = |winding: DisplayWinding| -> Vec<u8> {
+            LedDataProcessor::encode_for_hardware(
+                colors.clone(),
+                &strips,
+                &ColorCalibration::new(),
+                0,
+                winding,
+            )
+            .unwrap()
+            .chunks(3)
+            .map(|led| led[0])
+            .collect()
+        };
+
+        assert_eq!(encode(DisplayWinding::Clockwise), vec![1, 2, 3, 4, 5]);
+        // 逆时针：串联顺序和灯带内部顺序同时翻转，各灯带长度保持不变
+        assert_eq!(
+            encode(DisplayWinding::CounterClockwise),
+            vec![5, 4, 3, 2, 1]
+        );
+    }
+
+    #[test]
+    fn test_deadband_holds_small_changes() {
+        let frame = |r: u8, g: u8, b: u8| vec![vec![LedColor::new(r, g, b); 2]];
+        let rgb = |frame: &[Vec<LedColor>]| -> Vec<[u8; 3]> {
+            frame.iter().flatten().map(LedColor::get_rgb).collect()
+        };
+        let mut held = Vec::new();
+
+        let mut first = frame(100, 100, 100);
+        LedDataProcessor::apply_deadband(&mut held, &mut first, 2);
+        assert_eq!(rgb(&first), vec![[100, 100, 100]; 2]);
+
+        // 1 级的输入变化被死区吸收，输出保持不变
+        let mut jitter = frame(101, 99, 100);
+        LedDataProcessor::apply_deadband(&mut held, &mut jitter, 2);
+        assert_eq!(rgb(&jitter), vec![[100, 100, 100]; 2]);
+
+        // 达到死区的通道立即更新，其余通道保持
+        let mut change = frame(102, 101, 100);
+        LedDataProcessor::apply_deadband(&mut held, &mut change, 2);
+        assert_eq!(rgb(&change), vec![[102, 100, 100]; 2]);
+
+        // LED数量变化时重新开始
+        let mut resized = vec![vec![LedColor::new(1, 1, 1); 3]];
+        LedDataProcessor::apply_deadband(&mut held, &mut resized, 2);
+        assert_eq!(rgb(&resized), vec![[1, 1, 1]; 3]);
+        assert_eq!(held.len(), 3);
+    }
+
+    #[test]
+    fn test_color_temperature_shift_is_warmer_at_midnight() {
+        let settings = ColorTemperatureShiftPreferences {
+            enabled: true,
+            ..Default::default()
+        };
+        assert!(validate_color_temperature_shift(&settings).is_ok());
+
+        let midnight = LedDataProcessor::scheduled_kelvin(0.0, &settings);
+        let noon = LedDataProcessor::scheduled_kelvin(12.0 * 60.0, &settings);
+        assert_eq!(midnight, settings.night_kelvin as f32);
+        assert_eq!(noon, settings.day_kelvin as f32);
+
+        // 较暖的色温：蓝色通道相对红色通道衰减更多
+        let [midnight_r, _, midnight_b] = LedDataProcessor::kelvin_channel_gains(midnight);
+        let [noon_r, _, noon_b] = LedDataProcessor::kelvin_channel_gains(noon);
+        assert!(midnight_b / midnight_r < noon_b / noon_r);
+
+        // 日出过渡期间色温介于两者之间
+        let sunrise = settings.sunrise_minute as f32 + settings.transition_minutes as f32 / 2.0;
+        let dawn = LedDataProcessor::scheduled_kelvin(sunrise, &settings);
+        assert!(midnight < dawn && dawn < noon);
+
+        // 参考色温不改变颜色
+        for gain in LedDataProcessor::kelvin_channel_gains(6500.0) {
+            assert!((gain - 1.0).abs() < 1e-6);
+        }
+    }
+
+    #[test]
+    fn test_palette_quantization_maps_to_nearest_color() {
+        // 8 色调色板：黑、红、绿、蓝、黄、青、品红、白
+        let palette = [
+            [0, 0, 0],
+            [255, 0, 0],
+            [0, 255, 0],
+            [0, 0, 255],
+            [255, 255, 0],
+            [0, 255, 255],
+            [255, 0, 255],
+            [255, 255, 255],
+        ];
+        let cases = [
+            ([10, 20, 30], [0, 0, 0]),
+            ([200, 40, 30], [255, 0, 0]),
+            ([30, 180, 60], [0, 255, 0]),
+            ([20, 60, 220], [0, 0, 255]),
+            ([230, 210, 50], [255, 255, 0]),
+            ([40, 200, 210], [0, 255, 255]),
+            ([190, 30, 170], [255, 0, 255]),
+            ([220, 230, 240], [255, 255, 255]),
+        ];
+        for (input, expected) in cases {
+            assert_eq!(
+                LedDataProcessor::nearest_palette_color(input, &palette),
+                expected,
+                "{input:?}"
+            );
+        }
+
+        // 调色板中已有的颜色保持不变，空调色板不做处理
+        for entry in palette {
+            assert_eq!(
+                LedDataProcessor::nearest_palette_color(entry, &palette),
+                entry
+            );
+        }
+        assert_eq!(
+            LedDataProcessor::nearest_palette_color([1, 2, 3], &[]),
+            [1, 2, 3]
+        );
+
+        assert!(validate_palette(&palette).is_ok());
+        assert!(validate_palette(&[]).is_err());
+        assert!(validate_palette(&[[0, 0, 0]; MAX_PALETTE_SIZE + 1]).is_err());
+    }
+}
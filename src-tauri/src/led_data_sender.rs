@@ -1,15 +1,22 @@
 use dirs::config_dir;
 use log::{error, info, warn};
 use serde::{Deserialize, Serialize};
+use std::collections::{HashMap, HashSet};
+use std::future::Future;
 use std::net::SocketAddr;
 use std::path::PathBuf;
 use std::str::FromStr;
 use std::sync::Arc;
+use std::time::{Duration, Instant};
 use tokio::fs::OpenOptions;
 use tokio::io::AsyncWriteExt;
-use tokio::sync::{OnceCell, RwLock};
+use tokio::sync::{Mutex, OnceCell, RwLock};
 
-use crate::{led_status_manager::LedStatusManager, rpc::UdpRpc};
+use crate::{
+    led_status_manager::LedStatusManager,
+    output_sink::{OutputSink, OutputSinkConfig},
+    rpc::UdpRpc,
+};
 
 /// LED数据发送模式
 #[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize, Default)]
@@ -25,6 +32,8 @@ pub enum DataSendMode {
     TestEffect,
     /// 颜色校准数据
     ColorCalibration,
+    /// 手动设置的纯色数据（持续发送，直到清除）
+    ManualColor,
 }
 
 impl std::fmt::Display for DataSendMode {
@@ -35,10 +44,44 @@ impl std::fmt::Display for DataSendMode {
             DataSendMode::StripConfig => write!(f, "StripConfig"),
             DataSendMode::TestEffect => write!(f, "TestEffect"),
             DataSendMode::ColorCalibration => write!(f, "ColorCalibration"),
+            DataSendMode::ManualColor => write!(f, "ManualColor"),
         }
     }
 }
 
+/// LED数据的输出方式
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize, Default, utoipa::ToSchema)]
+pub enum OutputTransport {
+    /// 本应用的UDP协议，发送到通过mDNS发现的设备
+    #[default]
+    Native,
+    /// DDP协议，发送到指定的目标地址（适用于WLED等通用控制器）
+    Ddp,
+    /// Art-Net（ArtDmx），从指定universe开始依次发送到目标地址
+    ArtNet,
+}
+
+/// 默认的LED数据包协议版本（0x02帧格式）
+pub const DEFAULT_PROTOCOL_VERSION: u8 = 2;
+
+/// 支持的LED数据包协议版本
+///
+/// 目前固件只定义了一种帧格式（见 docs/hardware-protocol.md）：
+///
+/// * `2` - `[0x02, offset_hi, offset_lo, data...]`
+pub const SUPPORTED_PROTOCOL_VERSIONS: [u8; 1] = [DEFAULT_PROTOCOL_VERSION];
+
+/// LED数据包头的命令字节与通道字节覆盖，用于兼容社区固件变体
+///
+/// 数据包布局为 `[command, channel?, offset_hi, offset_lo, ...]`：
+/// * `command` - 为空时使用协议版本的默认命令字节（版本2为 `0x02`）
+/// * `channel` - 通道/灯带选择字节，为空时不插入，保持原有布局
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub struct PacketHeader {
+    pub command: Option<u8>,
+    pub channel: Option<u8>,
+}
+
 /// LED数据包信息
 #[derive(Debug, Clone)]
 pub struct LedDataPacket {
@@ -67,6 +110,137 @@ impl LedDataPacket {
         packet.extend_from_slice(&self.data); // Color data
         packet
     }
+
+    /// 构建0x02协议数据包，并应用设备配置的命令字节与通道字节
+    pub fn build_packet_with_header(&self, header: PacketHeader) -> Vec<u8> {
+        let mut packet = self.build_packet();
+        if let Some(command) = header.command {
+            packet[0] = command;
+        }
+        if let Some(channel) = header.channel {
+            packet.insert(1, channel);
+        }
+        packet
+    }
+}
+
+/// 单个UDP包的最大数据大小（硬件限制：不超过400字节）
+const MAX_PACKET_DATA_SIZE: usize = 400;
+
+/// 发送全黑帧，覆盖从0开始的 `total_bytes` 字节
+///
+/// 发送器以闭包形式传入，便于测试时替换为模拟发送器
+///
+/// # 参数
+/// * `total_bytes` - 全部灯带的硬件数据字节数
+/// * `send` - 实际发送单个数据包的函数
+///
+/// # 返回值
+/// 返回发送的数据包数量
+pub async fn send_blackout_frame<F, Fut>(total_bytes: usize, mut send: F) -> anyhow::Result<usize>
+where
+    F: FnMut(LedDataPacket) -> Fut,
+    Fut: Future<Output = anyhow::Result<()>>,
+{
+    let mut packet_count = 0;
+    let mut offset = 0;
+
+    while offset < total_bytes {
+        let chunk_size = std::cmp::min(MAX_PACKET_DATA_SIZE, total_bytes - offset);
+        let packet =
+            LedDataPacket::new(offset as u16, vec![0u8; chunk_size], "Blackout".to_string());
+        send(packet).await?;
+
+        packet_count += 1;
+        offset += chunk_size;
+    }
+
+    Ok(packet_count)
+}
+
+/// 按设备限制发送频率
+///
+/// 数据按显示器分段发送，同一设备每帧会收到多段数据，因此按（设备地址、起始偏移）
+/// 分别计时，避免同一帧的不同分段互相挤占。间隔内到达的帧直接丢弃：
+/// 颜色数据只关心最新一帧，下一帧很快会带来更新的内容。
+#[derive(Debug, Default)]
+pub struct SendThrottle {
+    last_sent: HashMap<(SocketAddr, u16), Instant>,
+}
+
+impl SendThrottle {
+    /// 判断是否允许在 `now` 发送，允许时记录发送时间
+    pub fn admit(&mut self, key: (SocketAddr, u16), interval: Duration, now: Instant) -> bool {
+        if interval.is_zero() {
+            return true;
+        }
+        if let Some(last) = self.last_sent.get(&key) {
+            if now.saturating_duration_since(*last) < interval {
+                return false;
+            }
+        }
+        self.last_sent.insert(key, now);
+        true
+    }
+}
+
+/// 默认的设备重新上线渐亮时长（毫秒）
+pub const DEFAULT_RECONNECT_FADE_MS: u64 = 500;
+
+/// 允许的最大渐亮时长（毫秒）
+pub const MAX_RECONNECT_FADE_MS: u64 = 10_000;
+
+/// 渐亮进度到亮度系数的 gamma 值，使人眼感知的亮度大致均匀增长
+const RECONNECT_FADE_GAMMA: f32 = 2.2;
+
+/// 校验设备重新上线渐亮时长，0 表示不渐亮
+pub fn validate_reconnect_fade_ms(fade_ms: u64) -> anyhow::Result<()> {
+    if fade_ms > MAX_RECONNECT_FADE_MS {
+        anyhow::bail!("reconnect fade must be at most {MAX_RECONNECT_FADE_MS}ms, got {fade_ms}ms");
+    }
+    Ok(())
+}
+
+/// 设备从离线恢复后的渐亮状态
+///
+/// 设备重新上线时记录恢复时间，之后发往该设备的帧按经过的时间调暗，
+/// 在渐亮时长内从全黑过渡到目标亮度，避免亮度突变
+#[derive(Debug, Default)]
+pub struct ReconnectFade {
+    recovered_at: HashMap<SocketAddr, Instant>,
+}
+
+impl ReconnectFade {
+    /// 记录设备在 `now` 重新上线
+    pub fn mark_recovered(&mut self, addr: SocketAddr, now: Instant) {
+        self.recovered_at.insert(addr, now);
+    }
+
+    /// 获取 `now` 时仍在渐亮的设备及其亮度系数（0.0~1.0），渐亮结束的设备不再记录
+    pub fn active_scales(&mut self, duration: Duration, now: Instant) -> HashMap<SocketAddr, f32> {
+        if duration.is_zero() {
+            self.recovered_at.clear();
+            return HashMap::new();
+        }
+        self.recovered_at
+            .retain(|_, at| now.saturating_duration_since(*at) < duration);
+        self.recovered_at
+            .iter()
+            .map(|(addr, at)| {
+                let progress =
+                    now.saturating_duration_since(*at).as_secs_f32() / duration.as_secs_f32();
+                (*addr, progress.powf(RECONNECT_FADE_GAMMA))
+            })
+            .collect()
+    }
+}
+
+/// 按亮度系数缩放一段LED数据
+pub fn scale_led_data(data: &[u8], scale: f32) -> Vec<u8> {
+    let scale = scale.clamp(0.0, 1.0);
+    data.iter()
+        .map(|&value| (value as f32 * scale).round() as u8)
+        .collect()
 }
 
 /// 统一的LED数据发送管理器
@@ -75,6 +249,16 @@ pub struct LedDataSender {
     current_mode: Arc<RwLock<DataSendMode>>,
     /// 测试模式下的目标地址
     test_target_address: Arc<RwLock<Option<SocketAddr>>>,
+    /// 模拟发送：完成编码和预览发布，但不写入UDP套接字
+    dry_run: Arc<RwLock<bool>>,
+    /// 输出端列表，每帧数据同时发送到所有输出端
+    sinks: Arc<RwLock<Vec<Arc<OutputSink>>>>,
+    /// 按设备的最小发送间隔限制
+    throttle: Arc<Mutex<SendThrottle>>,
+    /// 重新上线设备的渐亮状态
+    reconnect_fade: Arc<Mutex<ReconnectFade>>,
+    /// 设备重新上线后的渐亮时长，为0时立即恢复全亮
+    reconnect_fade_duration: Arc<RwLock<Duration>>,
 }
 
 impl LedDataSender {
@@ -87,6 +271,13 @@ impl LedDataSender {
                 LedDataSender {
                     current_mode: Arc::new(RwLock::new(DataSendMode::default())),
                     test_target_address: Arc::new(RwLock::new(None)),
+                    dry_run: Arc::new(RwLock::new(false)),
+                    sinks: Arc::new(RwLock::new(vec![Arc::new(OutputSink::native())])),
+                    throttle: Arc::new(Mutex::new(SendThrottle::default())),
+                    reconnect_fade: Arc::new(Mutex::new(ReconnectFade::default())),
+                    reconnect_fade_duration: Arc::new(RwLock::new(Duration::from_millis(
+                        DEFAULT_RECONNECT_FADE_MS,
+                    ))),
                 }
             })
             .await
@@ -164,6 +355,80 @@ impl LedDataSender {
         };
     }
 
+    /// 设置模拟发送（无硬件开发时使用）
+    pub async fn set_dry_run(&self, dry_run: bool) {
+        *self.dry_run.write().await = dry_run;
+        info!(
+            "LED data dry run {}",
+            if dry_run { "enabled" } else { "disabled" }
+        );
+    }
+
+    /// 是否处于模拟发送
+    pub async fn is_dry_run(&self) -> bool {
+        *self.dry_run.read().await
+    }
+
+    /// 按偏好设置重建输出端列表
+    ///
+    /// 无法创建的输出端会被跳过并返回错误，其余输出端照常生效
+    pub async fn configure_output(
+        &self,
+        output: &crate::user_preferences::OutputPreferences,
+    ) -> anyhow::Result<()> {
+        let mut sinks = Vec::with_capacity(output.sinks.len());
+        let mut failures = Vec::new();
+
+        // 先设置本地绑定地址，之后创建的输出端套接字从指定网卡发出
+        if let Err(e) = crate::output_sink::apply_local_bind_address(output.local_bind_address) {
+            error!("Invalid local bind address, falling back to the default interface: {e}");
+            failures.push(e.to_string());
+        }
+
+        for config in &output.sinks {
+            match OutputSink::connect(config.clone()).await {
+                Ok(sink) => {
+                    info!("LED output sink: {}", sink.describe());
+                    sinks.push(Arc::new(sink));
+                }
+                Err(e) => {
+                    error!("Failed to create LED output sink '{}': {e}", config.id);
+                    failures.push(format!("{}: {e}", config.id));
+                }
+            }
+        }
+
+        if sinks.is_empty() {
+            warn!("No LED output sinks configured, LED data will not be sent");
+        }
+        *self.sinks.write().await = sinks;
+        *self.reconnect_fade_duration.write().await =
+            Duration::from_millis(output.reconnect_fade_ms.min(MAX_RECONNECT_FADE_MS));
+
+        if failures.is_empty() {
+            Ok(())
+        } else {
+            Err(anyhow::anyhow!(
+                "Failed to create output sinks: {}",
+                failures.join("; ")
+            ))
+        }
+    }
+
+    async fn get_sinks(&self) -> Vec<Arc<OutputSink>> {
+        self.sinks.read().await.clone()
+    }
+
+    /// 获取当前生效的输出端配置
+    pub async fn get_sink_configs(&self) -> Vec<OutputSinkConfig> {
+        self.sinks
+            .read()
+            .await
+            .iter()
+            .map(|sink| sink.config().clone())
+            .collect()
+    }
+
     /// 获取当前发送模式
     pub async fn get_mode(&self) -> DataSendMode {
         *self.current_mode.read().await
@@ -171,6 +436,19 @@ impl LedDataSender {
 
     /// 设置发送模式
     pub async fn set_mode(&self, mode: DataSendMode) {
+        self.set_mode_with_events(
+            mode,
+            crate::websocket_events::WebSocketEventPublisher::global().await,
+        )
+        .await;
+    }
+
+    /// 设置发送模式，模式变化事件通过指定的发布器发送
+    async fn set_mode_with_events(
+        &self,
+        mode: DataSendMode,
+        events: &crate::websocket_events::WebSocketEventPublisher,
+    ) {
         let old_mode = {
             let mut current_mode = self.current_mode.write().await;
             let old_mode = *current_mode;
@@ -180,6 +458,17 @@ impl LedDataSender {
 
         info!("LED data send mode changed: {old_mode} -> {mode}");
 
+        // 仅在模式实际变化时通知前端
+        if old_mode != mode {
+            events.publish_data_send_mode_changed(old_mode, mode).await;
+            crate::webhook::notify(
+                crate::webhook::WebhookEvent::DataSendModeChanged,
+                &old_mode,
+                &mode,
+            )
+            .await;
+        }
+
         // 通过状态管理器更新状态
         let status_manager = LedStatusManager::global().await;
 
@@ -204,6 +493,17 @@ impl LedDataSender {
         &self,
         packet: LedDataPacket,
         expected_mode: DataSendMode,
+    ) -> anyhow::Result<()> {
+        self.send_packet_skipping(packet, expected_mode, &HashSet::new())
+            .await
+    }
+
+    /// 发送LED数据包，跳过 `skipped` 中的设备
+    async fn send_packet_skipping(
+        &self,
+        packet: LedDataPacket,
+        expected_mode: DataSendMode,
+        skipped: &HashSet<SocketAddr>,
     ) -> anyhow::Result<()> {
         // 检查当前模式是否允许发送
         if !self.can_send(expected_mode).await {
@@ -215,6 +515,11 @@ impl LedDataSender {
             ));
         }
 
+        // 模拟发送：跳过UDP，由 send_complete_led_data 记录摘要
+        if self.is_dry_run().await {
+            return Ok(());
+        }
+
         // 获取UDP RPC实例
         let udp_rpc = UdpRpc::global().await;
         if let Err(err) = udp_rpc {
@@ -245,13 +550,16 @@ impl LedDataSender {
             let target_addr_option = *self.test_target_address.read().await;
 
             if let Some(target_addr) = target_addr_option {
+                if skipped.contains(&target_addr) {
+                    return Ok(());
+                }
                 // 首先尝试发送到已知设备
-                match udp_rpc.send_to(&packet_data, target_addr).await {
+                match udp_rpc.send_to(&packet, target_addr).await {
                     Ok(()) => Ok(()),
                     Err(e) => {
                         log::warn!("⚠️ Failed to send to known device: {e}, trying direct send...");
                         // 如果失败，尝试直接发送（用于调试设备）
-                        udp_rpc.send_to_direct(&packet_data, target_addr).await
+                        udp_rpc.send_packet_direct(&packet, target_addr).await
                     }
                 }
             } else {
@@ -259,10 +567,10 @@ impl LedDataSender {
                     "⚠️ {} mode is active, but no target address is set. Using broadcast mode.",
                     packet.source
                 );
-                udp_rpc.send_to_all(&packet_data).await
+                udp_rpc.send_to_all_except(&packet, skipped).await
             }
         } else {
-            udp_rpc.send_to_all(&packet_data).await
+            udp_rpc.send_to_all_except(&packet, skipped).await
         };
 
         match send_result {
@@ -300,53 +608,218 @@ impl LedDataSender {
             "StripConfig" => DataSendMode::StripConfig,
             "TestEffect" => DataSendMode::TestEffect,
             "ColorCalibration" => DataSendMode::ColorCalibration,
+            "ManualColor" => DataSendMode::ManualColor,
             _ => DataSendMode::AmbientLight,
         };
 
         // 注意：LED颜色预览数据由 ambient_light/publisher.rs 负责发布
         // 这里不再重复发布，避免数据混乱和重复事件
 
-        // 拆分数据为UDP包
-        let max_data_size = 400; // 每个UDP包的最大数据大小（硬件限制：不超过400字节）
-        let mut current_offset = start_offset;
-        let mut remaining_data = complete_data.as_slice();
-
-        let mut packet_count = 0;
-        while !remaining_data.is_empty() {
-            let chunk_size = std::cmp::min(max_data_size, remaining_data.len());
-            let chunk = remaining_data[..chunk_size].to_vec();
-            remaining_data = &remaining_data[chunk_size..];
+        if !self.can_send(mode).await {
+            let current_mode = self.get_mode().await;
+            return Err(anyhow::anyhow!(
+                "Cannot send {} data in {} mode",
+                mode,
+                current_mode
+            ));
+        }
 
-            packet_count += 1;
+        // 同时发送到所有输出端，单个输出端失败不影响其他输出端
+        let sinks = self.get_sinks().await;
+        let results =
+            futures::future::join_all(sinks.iter().map(|sink| {
+                self.send_frame_to_sink(sink, start_offset, &complete_data, source, mode)
+            }))
+            .await;
 
-            let packet = LedDataPacket::new(current_offset, chunk, source.to_string());
-            self.send_packet(packet, mode).await?;
+        let mut packet_count = 0;
+        let mut failures = Vec::new();
+        for (sink, result) in sinks.iter().zip(results) {
+            match result {
+                Ok(count) => packet_count += count,
+                Err(e) => failures.push(format!("{}: {e}", sink.describe())),
+            }
+        }
 
-            current_offset += chunk_size as u16;
+        if self.is_dry_run().await {
+            log::debug!(
+                "🧪 [DryRun] {} frame: offset={}, {} bytes in {} packets (not sent)",
+                source,
+                start_offset,
+                complete_data.len(),
+                packet_count
+            );
         }
 
         // 记录发送统计信息到状态管理器
         let status_manager = LedStatusManager::global().await;
         if let Err(e) = status_manager
-            .record_send_stats(packet_count as u64, complete_data.len() as u64, true)
+            .record_send_stats(
+                packet_count as u64,
+                complete_data.len() as u64,
+                failures.is_empty(),
+            )
             .await
         {
             warn!("Failed to record send stats: {e}");
         }
 
+        if !sinks.is_empty() && failures.len() == sinks.len() {
+            return Err(anyhow::anyhow!(
+                "All output sinks failed: {}",
+                failures.join("; ")
+            ));
+        }
+        if !failures.is_empty() {
+            warn!(
+                "Failed to send {} frame to some output sinks: {}",
+                source,
+                failures.join("; ")
+            );
+        }
+
         Ok(())
     }
 
+    /// 向单个输出端发送一帧，返回发送的数据包数量
+    async fn send_frame_to_sink(
+        &self,
+        sink: &OutputSink,
+        start_offset: u16,
+        complete_data: &[u8],
+        source: &str,
+        mode: DataSendMode,
+    ) -> anyhow::Result<usize> {
+        if sink.is_native() {
+            return self
+                .send_native_frame(start_offset, complete_data, source, mode)
+                .await;
+        }
+        if self.is_dry_run().await {
+            return Ok(0);
+        }
+        let layout = crate::ambient_light::ConfigManagerV2::global()
+            .await
+            .get_sink_frame_layout()
+            .await;
+        sink.send_frame(start_offset as u32, complete_data, &layout)
+            .await
+    }
+
+    /// 按本应用协议拆分为UDP包发送，返回发送的数据包数量
+    async fn send_native_frame(
+        &self,
+        start_offset: u16,
+        complete_data: &[u8],
+        source: &str,
+        mode: DataSendMode,
+    ) -> anyhow::Result<usize> {
+        let mut skipped = self.throttled_boards(start_offset).await;
+        // 正在渐亮的设备不接收原始数据，改为单独发送调暗后的数据
+        let fading = self.fading_boards(mode, &skipped).await;
+        skipped.extend(fading.keys().copied());
+        let mut current_offset = start_offset;
+        let mut packet_count = 0;
+
+        for chunk in complete_data.chunks(MAX_PACKET_DATA_SIZE) {
+            let packet = LedDataPacket::new(current_offset, chunk.to_vec(), source.to_string());
+            self.send_packet_skipping(packet, mode, &skipped).await?;
+            self.send_faded_chunk(current_offset, chunk, source, &fading)
+                .await;
+
+            packet_count += 1;
+            current_offset += chunk.len() as u16;
+        }
+
+        Ok(packet_count)
+    }
+
+    /// 获取本帧因最小发送间隔限制需要跳过的设备
+    async fn throttled_boards(&self, start_offset: u16) -> HashSet<SocketAddr> {
+        let Ok(udp_rpc) = UdpRpc::global().await.as_ref() else {
+            return HashSet::new();
+        };
+        let intervals = udp_rpc.get_board_send_intervals().await;
+        if intervals.is_empty() {
+            return HashSet::new();
+        }
+
+        let now = Instant::now();
+        let mut throttle = self.throttle.lock().await;
+        intervals
+            .into_iter()
+            .filter(|(addr, interval)| !throttle.admit((*addr, start_offset), *interval, now))
+            .map(|(addr, _)| addr)
+            .collect()
+    }
+
+    /// 标记设备重新上线，之后发往该设备的帧从全黑逐渐亮起
+    pub async fn mark_board_recovered(&self, addr: SocketAddr) {
+        if self.reconnect_fade_duration.read().await.is_zero() {
+            return;
+        }
+        info!("Board {addr} recovered, fading in");
+        self.reconnect_fade
+            .lock()
+            .await
+            .mark_recovered(addr, Instant::now());
+    }
+
+    /// 获取本帧仍在渐亮的设备及其亮度系数
+    ///
+    /// 渐亮只作用于广播发送；测试和单灯条配置模式下指定了目标设备时立即显示原始数据
+    async fn fading_boards(
+        &self,
+        mode: DataSendMode,
+        skipped: &HashSet<SocketAddr>,
+    ) -> HashMap<SocketAddr, f32> {
+        if matches!(mode, DataSendMode::TestEffect | DataSendMode::StripConfig)
+            && self.test_target_address.read().await.is_some()
+        {
+            return HashMap::new();
+        }
+        let duration = *self.reconnect_fade_duration.read().await;
+        let mut fading = self
+            .reconnect_fade
+            .lock()
+            .await
+            .active_scales(duration, Instant::now());
+        fading.retain(|addr, _| !skipped.contains(addr));
+        fading
+    }
+
+    /// 向正在渐亮的设备发送按亮度系数调暗的一段数据
+    async fn send_faded_chunk(
+        &self,
+        offset: u16,
+        chunk: &[u8],
+        source: &str,
+        fading: &HashMap<SocketAddr, f32>,
+    ) {
+        if fading.is_empty() || self.is_dry_run().await {
+            return;
+        }
+        let Ok(udp_rpc) = UdpRpc::global().await.as_ref() else {
+            return;
+        };
+        for (addr, scale) in fading {
+            let packet =
+                LedDataPacket::new(offset, scale_led_data(chunk, *scale), source.to_string());
+            udp_rpc.send_to_if_present(&packet, *addr).await;
+        }
+    }
+
     /// 强制发送数据包（忽略模式检查，用于特殊情况如关闭LED）
     pub async fn force_send_packet(&self, packet: LedDataPacket) -> anyhow::Result<()> {
-        let udp_rpc = UdpRpc::global().await;
-        if let Err(err) = udp_rpc {
-            warn!("UDP RPC not available: {err}");
-            return Err(anyhow::anyhow!("UDP RPC not available: {}", err));
+        if self.is_dry_run().await {
+            log::info!(
+                "🧪 [DryRun] Skipping forced LED packet: source={}, offset={}, data_len={}",
+                packet.source,
+                packet.offset,
+                packet.data.len()
+            );
+            return Ok(());
         }
-        let udp_rpc = udp_rpc.as_ref().unwrap();
-
-        let packet_data = packet.build_packet();
 
         log::info!(
             "Force sending LED packet: source={}, offset={}, data_len={}",
@@ -355,7 +828,123 @@ impl LedDataSender {
             packet.data.len()
         );
 
-        udp_rpc.send_to_all(&packet_data).await
+        let sinks = self.get_sinks().await;
+        let packet = &packet;
+        let layout = crate::ambient_light::ConfigManagerV2::global()
+            .await
+            .get_sink_frame_layout()
+            .await;
+        let layout = &layout;
+        let results = futures::future::join_all(sinks.iter().map(|sink| async move {
+            if sink.is_native() {
+                let udp_rpc = UdpRpc::global()
+                    .await
+                    .as_ref()
+                    .map_err(|err| anyhow::anyhow!("UDP RPC not available: {}", err))?;
+                udp_rpc.send_to_all(&packet).await
+            } else {
+                sink.send_frame(packet.offset as u32, &packet.data, layout)
+                    .await
+                    .map(|_| ())
+            }
+        }))
+        .await;
+
+        let failures: Vec<String> = sinks
+            .iter()
+            .zip(results)
+            .filter_map(|(sink, result)| result.err().map(|e| format!("{}: {e}", sink.describe())))
+            .collect();
+        if !sinks.is_empty() && failures.len() == sinks.len() {
+            return Err(anyhow::anyhow!(
+                "All output sinks failed: {}",
+                failures.join("; ")
+            ));
+        }
+        if !failures.is_empty() {
+            warn!(
+                "Failed to force send packet to some output sinks: {}",
+                failures.join("; ")
+            );
+        }
+        Ok(())
+    }
+
+    /// 向单个设备发送完整LED数据（忽略模式检查，不改变测试目标地址）
+    ///
+    /// 用于设备识别等只针对一个设备的短暂操作，其他设备不受影响
+    pub async fn send_to_board(
+        &self,
+        target_addr: SocketAddr,
+        start_offset: u16,
+        complete_data: &[u8],
+        source: &str,
+    ) -> anyhow::Result<()> {
+        if self.is_dry_run().await {
+            log::debug!(
+                "🧪 [DryRun] {} frame to {}: {} bytes (not sent)",
+                source,
+                target_addr,
+                complete_data.len()
+            );
+            return Ok(());
+        }
+
+        let udp_rpc = UdpRpc::global()
+            .await
+            .as_ref()
+            .map_err(|err| anyhow::anyhow!("UDP RPC not available: {}", err))?;
+
+        let mut offset = start_offset;
+        for chunk in complete_data.chunks(MAX_PACKET_DATA_SIZE) {
+            let packet = LedDataPacket::new(offset, chunk.to_vec(), source.to_string());
+            if let Err(e) = udp_rpc.send_to(&packet, target_addr).await {
+                log::warn!("⚠️ Failed to send to known device: {e}, trying direct send...");
+                udp_rpc.send_packet_direct(&packet, target_addr).await?;
+            }
+            offset += chunk.len() as u16;
+        }
+
+        Ok(())
+    }
+
+    /// 向所有设备发送全黑帧并等待发送完成（用于退出应用前关闭LED）
+    ///
+    /// 先切换到 `None` 模式阻止其他任务继续发送，然后在超时时间内同步发送全黑数据
+    pub async fn flush_blackout(&self, timeout: Duration) -> anyhow::Result<()> {
+        self.set_mode(DataSendMode::None).await;
+
+        let config_manager = crate::ambient_light::ConfigManagerV2::global().await;
+        let config = config_manager.get_config().await;
+        // 覆盖到最远的灯带末尾，包括配置了显式硬件偏移量的灯带
+        let mut sorted_strips: Vec<_> = config.strips.iter().collect();
+        sorted_strips.sort_by_key(|strip| strip.chain_order());
+        let mut next_byte_offset = 0;
+        let mut total_bytes = 0;
+        for strip in sorted_strips {
+            let bytes_per_led = strip.led_type.bytes_per_led();
+            let start = strip
+                .hardware_offset
+                .map(|offset| offset * bytes_per_led)
+                .unwrap_or(next_byte_offset);
+            next_byte_offset = start + strip.len * bytes_per_led;
+            total_bytes = total_bytes.max(next_byte_offset);
+        }
+
+        info!("Sending blackout frame before shutdown: {total_bytes} bytes");
+
+        let send = send_blackout_frame(total_bytes, |packet| self.force_send_packet(packet));
+        match tokio::time::timeout(timeout, send).await {
+            Ok(Ok(packet_count)) => {
+                info!("✅ Blackout frame sent in {packet_count} packets");
+                Ok(())
+            }
+            Ok(Err(e)) => Err(e),
+            Err(_) => Err(anyhow::anyhow!(
+                "Timed out sending blackout frame after {}ms",
+                timeout.as_millis()
+            )),
+        }
     }
 
     /// Get statistics about the current state (for testing/debugging)
@@ -364,3 +953,282 @@ impl LedDataSender {
         format!("Current mode: {mode}")
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use tokio::sync::Mutex;
+
+    #[tokio::test]
+    async fn test_blackout_frame_covers_all_bytes_with_zeros() {
+        let sent = Arc::new(Mutex::new(Vec::<LedDataPacket>::new()));
+
+        let packet_count = send_blackout_frame(1000, |packet| {
+            let sent = sent.clone();
+            async move {
+                sent.lock().await.push(packet);
+                Ok(())
+            }
+        })
+        .await
+        .unwrap();
+
+        let sent = sent.lock().await;
+        assert_eq!(packet_count, 3);
+        assert_eq!(sent.len(), 3);
+
+        // 数据包按偏移连续覆盖全部字节，且全部为0
+        assert_eq!(
+            sent.iter().map(|p| p.offset).collect::<Vec<_>>(),
+            vec![0, 400, 800]
+        );
+        assert_eq!(sent.iter().map(|p| p.data.len()).sum::<usize>(), 1000);
+        assert!(sent.iter().all(|p| p.data.iter().all(|b| *b == 0)));
+
+        // 协议头保持0x02
+        assert_eq!(sent[1].build_packet()[..3], [0x02, 0x01, 0x90]);
+    }
+
+    #[test]
+    fn test_build_packet_with_header() {
+        let packet = LedDataPacket::new(0x0102, vec![7, 8, 9], "Test".to_string());
+
+        // 默认头部与0x02格式一致
+        assert_eq!(
+            packet.build_packet_with_header(PacketHeader::default()),
+            vec![0x02, 0x01, 0x02, 7, 8, 9]
+        );
+
+        let header = PacketHeader {
+            command: Some(0x12),
+            channel: Some(3),
+        };
+        assert_eq!(
+            packet.build_packet_with_header(header),
+            vec![0x12, 3, 0x01, 0x02, 7, 8, 9]
+        );
+
+        // 只指定通道时保留默认命令字节
+        let header = PacketHeader {
+            command: None,
+            channel: Some(1),
+        };
+        assert_eq!(
+            packet.build_packet_with_header(header),
+            vec![0x02, 1, 0x01, 0x02, 7, 8, 9]
+        );
+    }
+
+    #[tokio::test]
+    async fn test_blackout_frame_propagates_send_error() {
+        let result = send_blackout_frame(10, |_packet| async {
+            Err(anyhow::anyhow!("socket closed"))
+        })
+        .await;
+
+        assert!(result.is_err());
+    }
+
+    #[test]
+    fn test_send_throttle_thins_bursts_per_board_segment() {
+        let mut throttle = SendThrottle::default();
+        let board_a: SocketAddr = "192.168.1.10:23042".parse().unwrap();
+        let board_b: SocketAddr = "192.168.1.11:23042".parse().unwrap();
+        let interval = Duration::from_millis(10);
+        let start = Instant::now();
+
+        // 1ms一帧的突发，10ms间隔下只保留约十分之一
+        let admitted = (0..100)
+            .filter(|i| throttle.admit((board_a, 0), interval, start + Duration::from_millis(*i)))
+            .count();
+        assert_eq!(admitted, 10);
+
+        // 同一设备的其他分段和其他设备分别计时
+        assert!(throttle.admit((board_a, 300), interval, start));
+        assert!(throttle.admit((board_b, 0), interval, start));
+        // 间隔为0时不限制
+        assert!(throttle.admit((board_a, 0), Duration::ZERO, start));
+    }
+
+    #[test]
+    fn test_reconnect_fade_scales_first_frames_after_recovery() {
+        let mut fade = ReconnectFade::default();
+        let board_a: SocketAddr = "192.168.1.10:23042".parse().unwrap();
+        let board_b: SocketAddr = "192.168.1.11:23042".parse().unwrap();
+        let duration = Duration::from_millis(500);
+        let start = Instant::now();
+        let frame = [200u8, 100, 50];
+
+        fade.mark_recovered(board_a, start);
+
+        // 恢复瞬间全黑，随后逐渐变亮但仍低于目标亮度
+        let scales = fade.active_scales(duration, start);
+        assert_eq!(scale_led_data(&frame, scales[&board_a]), vec![0, 0, 0]);
+        assert!(!scales.contains_key(&board_b));
+
+        let mut previous = 0u8;
+        for ms in [50, 150, 300, 450] {
+            let scales = fade.active_scales(duration, start + Duration::from_millis(ms));
+            let scaled = scale_led_data(&frame, scales[&board_a]);
+            assert!(scaled[0] >= previous && scaled[0] < frame[0]);
+            previous = scaled[0];
+        }
+
+        // 渐亮结束后不再调暗
+        assert!(fade.active_scales(duration, start + duration).is_empty());
+
+        // 渐亮时长为0时立即恢复全亮
+        fade.mark_recovered(board_a, start);
+        assert!(fade.active_scales(Duration::ZERO, start).is_empty());
+    }
+
+    #[tokio::test]
+    async fn test_dry_run_skips_socket_but_respects_mode() {
+        let sender = LedDataSender {
+            current_mode: Arc::new(RwLock::new(DataSendMode::AmbientLight)),
+            test_target_address: Arc::new(RwLock::new(None)),
+            dry_run: Arc::new(RwLock::new(false)),
+            sinks: Arc::new(RwLock::new(vec![Arc::new(OutputSink::native())])),
+            throttle: Arc::new(Mutex::new(SendThrottle::default())),
+            reconnect_fade: Arc::new(Mutex::new(ReconnectFade::default())),
+            reconnect_fade_duration: Arc::new(RwLock::new(Duration::ZERO)),
+        };
+        sender.set_dry_run(true).await;
+        assert!(sender.is_dry_run().await);
+
+        let packet = LedDataPacket::new(0, vec![1, 2, 3], "AmbientLight".to_string());
+        assert!(sender
+            .send_packet(packet.clone(), DataSendMode::AmbientLight)
+            .await
+            .is_ok());
+
+        // 模式检查仍然生效
+        assert!(sender
+            .send_packet(packet, DataSendMode::TestEffect)
+            .await
+            .is_err());
+    }
+
+    #[tokio::test]
+    async fn test_manual_color_source_maps_to_manual_color_mode() {
+        let sender = LedDataSender {
+            current_mode: Arc::new(RwLock::new(DataSendMode::ManualColor)),
+            test_target_address: Arc::new(RwLock::new(None)),
+            dry_run: Arc::new(RwLock::new(true)),
+            sinks: Arc::new(RwLock::new(vec![Arc::new(OutputSink::native())])),
+            throttle: Arc::new(Mutex::new(SendThrottle::default())),
+            reconnect_fade: Arc::new(Mutex::new(ReconnectFade::default())),
+            reconnect_fade_duration: Arc::new(RwLock::new(Duration::ZERO)),
+        };
+
+        let source = DataSendMode::ManualColor.to_string();
+        assert!(sender
+            .send_complete_led_data(0, vec![255, 0, 0], &source)
+            .await
+            .is_ok());
+
+        // 手动纯色模式下不接受校准数据
+        assert!(sender
+            .send_complete_led_data(0, vec![255, 0, 0], "ColorCalibration")
+            .await
+            .is_err());
+    }
+
+    #[tokio::test]
+    async fn test_failing_sink_does_not_block_other_sinks() {
+        let receiver = tokio::net::UdpSocket::bind("127.0.0.1:0").await.unwrap();
+        let receiver_addr = receiver.local_addr().unwrap();
+
+        // 未开启广播的套接字发往广播地址会被系统拒绝
+        let failing = OutputSinkConfig {
+            id: "broadcast".to_string(),
+            transport: OutputTransport::Ddp,
+            target: "255.255.255.255".to_string(),
+            ..OutputSinkConfig::native()
+        };
+        let working = OutputSinkConfig {
+            id: "local".to_string(),
+            transport: OutputTransport::Ddp,
+            target: receiver_addr.to_string(),
+            ..OutputSinkConfig::native()
+        };
+        let sender = LedDataSender {
+            current_mode: Arc::new(RwLock::new(DataSendMode::AmbientLight)),
+            test_target_address: Arc::new(RwLock::new(None)),
+            dry_run: Arc::new(RwLock::new(false)),
+            sinks: Arc::new(RwLock::new(Vec::new())),
+            throttle: Arc::new(Mutex::new(SendThrottle::default())),
+            reconnect_fade: Arc::new(Mutex::new(ReconnectFade::default())),
+            reconnect_fade_duration: Arc::new(RwLock::new(Duration::ZERO)),
+        };
+        sender
+            .configure_output(&crate::user_preferences::OutputPreferences {
+                sinks: vec![failing, working],
+                local_bind_address: None,
+                reconnect_fade_ms: 0,
+            })
+            .await
+            .unwrap();
+
+        assert!(sender
+            .send_complete_led_data(0, vec![1, 2, 3], "AmbientLight")
+            .await
+            .is_ok());
+
+        let mut buf = [0u8; 64];
+        let len = tokio::time::timeout(Duration::from_secs(1), receiver.recv(&mut buf))
+            .await
+            .unwrap()
+            .unwrap();
+        assert_eq!(&buf[crate::ddp_sender::DDP_HEADER_LEN..len], &[1, 2, 3]);
+    }
+
+    #[tokio::test]
+    async fn test_set_mode_publishes_event_only_on_change() {
+        // 使用独立的发布器，避免并行测试切换全局发送器模式时混入事件
+        let events = crate::websocket_events::WebSocketEventPublisher::new();
+        let manager = events.get_websocket_manager();
+        let connection_id = manager.generate_connection_id();
+        manager.add_connection(connection_id).await;
+        manager
+            .subscribe_events(connection_id, vec!["DataSendModeChanged".to_string()])
+            .await;
+        let mut receiver = manager.subscribe();
+
+        let sender = LedDataSender {
+            current_mode: Arc::new(RwLock::new(DataSendMode::None)),
+            test_target_address: Arc::new(RwLock::new(None)),
+            dry_run: Arc::new(RwLock::new(false)),
+            sinks: Arc::new(RwLock::new(vec![Arc::new(OutputSink::native())])),
+            throttle: Arc::new(Mutex::new(SendThrottle::default())),
+            reconnect_fade: Arc::new(Mutex::new(ReconnectFade::default())),
+            reconnect_fade_duration: Arc::new(RwLock::new(Duration::ZERO)),
+        };
+        sender
+            .set_mode_with_events(DataSendMode::StripConfig, &events)
+            .await;
+        sender
+            .set_mode_with_events(DataSendMode::StripConfig, &events)
+            .await;
+        sender
+            .set_mode_with_events(DataSendMode::ColorCalibration, &events)
+            .await;
+        manager.remove_connection(connection_id).await;
+
+        let mut transitions = Vec::new();
+        while let Ok(message) = receiver.try_recv() {
+            if let crate::http_server::websocket::WsMessage::DataSendModeChanged { data } = message
+            {
+                transitions.push((data.previous_mode, data.mode));
+            }
+        }
+
+        assert_eq!(
+            transitions,
+            vec![
+                (DataSendMode::None, DataSendMode::StripConfig),
+                (DataSendMode::StripConfig, DataSendMode::ColorCalibration),
+            ]
+        );
+    }
+}
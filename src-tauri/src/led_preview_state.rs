@@ -6,17 +6,23 @@ use std::path::PathBuf;
 use std::sync::Arc;
 use tokio::sync::{OnceCell, RwLock};
 
+use crate::color_vision::ColorVisionSimulation;
+
 const CONFIG_FILE_NAME: &str = "cc.ivanli.ambient_light/led_preview_state.toml";
 
 #[derive(Debug, Clone, Serialize, Deserialize)]
 pub struct LedPreviewState {
     pub enabled: bool,
+    /// Color vision deficiency simulated in the preview only
+    #[serde(default)]
+    pub color_vision: ColorVisionSimulation,
 }
 
 impl Default for LedPreviewState {
     fn default() -> Self {
         Self {
             enabled: true, // Default to enabled
+            color_vision: ColorVisionSimulation::None,
         }
     }
 }
@@ -121,6 +127,32 @@ impl LedPreviewStateManager {
         Ok(())
     }
 
+    /// Get the color vision deficiency simulated in the preview
+    pub async fn get_color_vision(&self) -> ColorVisionSimulation {
+        self.state.read().await.color_vision
+    }
+
+    /// Set the color vision deficiency simulated in the preview
+    pub async fn set_color_vision(
+        &self,
+        color_vision: ColorVisionSimulation,
+    ) -> anyhow::Result<()> {
+        {
+            let mut state = self.state.write().await;
+            state.color_vision = color_vision;
+        }
+
+        // Save to file
+        let current_state = self.get_state().await;
+        current_state.write_config().await?;
+
+        info!("LED preview color vision simulation changed to: {color_vision:?}");
+
+        crate::websocket_events::publish_led_preview_state_changed(&current_state).await;
+
+        Ok(())
+    }
+
     /// Toggle LED preview state
     pub async fn toggle(&self) -> anyhow::Result<bool> {
         let current_enabled = self.is_enabled().await;
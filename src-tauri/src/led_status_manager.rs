@@ -1,11 +1,13 @@
 use log::{debug, info, warn};
 use serde::{Deserialize, Serialize};
+use std::collections::{BTreeMap, HashMap};
 use std::sync::Arc;
+use std::time::{Duration, Instant};
 use tokio::sync::{watch, OnceCell, RwLock};
 
 use crate::{
-    ambient_light::{BorderColors, LedStripConfig},
-    frequency_calculator::FrequencyCalculator,
+    ambient_light::{AdaptiveQualityState, BorderColors, LedStripConfig},
+    frequency_calculator::{FrequencyCalculator, RollingFps},
     led_data_sender::DataSendMode,
     websocket_events::WebSocketEventPublisher,
 };
@@ -27,6 +29,74 @@ pub struct LedStatusStats {
     pub last_updated: chrono::DateTime<chrono::Utc>,
     /// 数据发送统计
     pub send_stats: LedSendStats,
+    /// 发送帧率（获取状态时实时计算）
+    #[serde(default)]
+    pub fps: LedFpsStats,
+    /// 按显示器ID统计的自适应采样质量
+    #[serde(default)]
+    pub adaptive_quality: BTreeMap<u32, AdaptiveQualityState>,
+}
+
+/// 帧率平滑窗口
+const FPS_SMOOTHING_WINDOW: Duration = Duration::from_secs(2);
+
+/// 瞬时帧率和滑动窗口平均帧率
+#[derive(Debug, Clone, Copy, Serialize, Deserialize, Default, PartialEq)]
+pub struct FpsSnapshot {
+    /// 最近两帧间隔计算的瞬时帧率，最近 2 秒内少于两帧时为 0
+    pub instantaneous: f64,
+    /// 最近 2 秒内的平均帧率
+    pub smoothed: f64,
+}
+
+/// LED数据发送帧率统计
+#[derive(Debug, Clone, Serialize, Deserialize, Default, PartialEq)]
+pub struct LedFpsStats {
+    /// 所有发送事件的速率：每次发送计一次，多显示器时为各显示器速率之和，并非画面帧率
+    pub overall: FpsSnapshot,
+    /// 按显示器ID统计的帧率，各显示器的采集速率可能不同
+    pub displays: BTreeMap<u32, FpsSnapshot>,
+}
+
+/// 整体和各显示器的滑动窗口帧率统计
+#[derive(Debug)]
+struct FpsTracker {
+    overall: RollingFps,
+    displays: HashMap<u32, RollingFps>,
+}
+
+impl FpsTracker {
+    fn new() -> Self {
+        Self {
+            overall: RollingFps::new(FPS_SMOOTHING_WINDOW),
+            displays: HashMap::new(),
+        }
+    }
+
+    fn record(&mut self, display_id: Option<u32>, now: Instant) {
+        self.overall.record(now);
+        if let Some(display_id) = display_id {
+            self.displays
+                .entry(display_id)
+                .or_insert_with(|| RollingFps::new(FPS_SMOOTHING_WINDOW))
+                .record(now);
+        }
+    }
+
+    fn snapshot(&mut self, now: Instant) -> LedFpsStats {
+        let snapshot = |fps: &mut RollingFps| FpsSnapshot {
+            instantaneous: fps.instantaneous(now),
+            smoothed: fps.smoothed(now),
+        };
+        LedFpsStats {
+            overall: snapshot(&mut self.overall),
+            displays: self
+                .displays
+                .iter_mut()
+                .map(|(display_id, fps)| (*display_id, snapshot(fps)))
+                .collect(),
+        }
+    }
 }
 
 /// LED数据发送统计
@@ -52,6 +122,8 @@ impl Default for LedStatusStats {
             sorted_colors_bytes: 0,
             last_updated: chrono::Utc::now(),
             send_stats: LedSendStats::default(),
+            fps: LedFpsStats::default(),
+            adaptive_quality: BTreeMap::new(),
         }
     }
 }
@@ -74,6 +146,10 @@ pub struct LedStatusManager {
     status_change_rx: Arc<RwLock<watch::Receiver<LedStatusStats>>>,
     /// 频率计算器
     frequency_calculator: Arc<RwLock<FrequencyCalculator>>,
+    /// 滑动窗口帧率统计（整体和各显示器）
+    fps_tracker: Arc<RwLock<FpsTracker>>,
+    /// 各显示器的自适应采样质量
+    adaptive_quality: Arc<RwLock<BTreeMap<u32, AdaptiveQualityState>>>,
 }
 
 impl LedStatusManager {
@@ -96,6 +172,8 @@ impl LedStatusManager {
                     status_change_tx,
                     status_change_rx: Arc::new(RwLock::new(status_change_rx)),
                     frequency_calculator: Arc::new(RwLock::new(FrequencyCalculator::new())),
+                    fps_tracker: Arc::new(RwLock::new(FpsTracker::new())),
+                    adaptive_quality: Arc::new(RwLock::new(BTreeMap::new())),
                 }
             })
             .await
@@ -103,7 +181,23 @@ impl LedStatusManager {
 
     /// 获取当前LED状态
     pub async fn get_status(&self) -> LedStatusStats {
-        self.status.read().await.clone()
+        let mut status = self.status.read().await.clone();
+        status.fps = self.get_fps().await;
+        status.adaptive_quality = self.adaptive_quality.read().await.clone();
+        status
+    }
+
+    /// 记录显示器当前的自适应采样质量
+    pub async fn update_adaptive_quality(&self, display_id: u32, state: AdaptiveQualityState) {
+        self.adaptive_quality
+            .write()
+            .await
+            .insert(display_id, state);
+    }
+
+    /// 获取整体和各显示器的瞬时及平滑帧率
+    pub async fn get_fps(&self) -> LedFpsStats {
+        self.fps_tracker.write().await.snapshot(Instant::now())
     }
 
     /// 获取当前LED颜色数据
@@ -239,8 +333,8 @@ impl LedStatusManager {
             status.last_updated = chrono::Utc::now();
         }
 
-        // 记录到频率计算器
-        self.record_data_send_event().await?;
+        // 记录到频率计算器（帧率统计按帧记录，不计入单个数据包）
+        self.record_frequency_event().await?;
 
         self.notify_status_changed().await?;
         debug!(
@@ -249,8 +343,18 @@ impl LedStatusManager {
         Ok(())
     }
 
-    /// 记录数据发送事件到频率计算器
-    pub async fn record_data_send_event(&self) -> anyhow::Result<()> {
+    /// 记录数据发送事件到频率计算器，`display_id` 为发送数据所属的显示器
+    pub async fn record_data_send_event(&self, display_id: Option<u32>) -> anyhow::Result<()> {
+        self.fps_tracker
+            .write()
+            .await
+            .record(display_id, Instant::now());
+
+        self.record_frequency_event().await
+    }
+
+    /// 记录事件到频率计算器，按需广播频率更新
+    async fn record_frequency_event(&self) -> anyhow::Result<()> {
         {
             let mut freq_calc = self.frequency_calculator.write().await;
             freq_calc.add_event();
@@ -307,6 +411,7 @@ impl LedStatusManager {
 
         // 获取实际计算的频率
         let calculated_frequency = self.get_current_frequency().await;
+        let fps = self.get_fps().await;
 
         // 标记频率更新已发送
         {
@@ -317,7 +422,7 @@ impl LedStatusManager {
         // 通过WebSocket广播状态变更（使用实际频率）
         let websocket_publisher = WebSocketEventPublisher::global().await;
         websocket_publisher
-            .publish_led_status_changed_with_calculated_frequency(calculated_frequency)
+            .publish_led_status_changed_with_calculated_frequency(calculated_frequency, &fps)
             .await;
 
         // 移除频繁的状态变更通知日志
@@ -411,6 +516,34 @@ mod tests {
         manager.update_colors(vec![], vec![]).await.unwrap();
     }
 
+    #[test]
+    fn test_fps_tracker_reports_per_display_rates() {
+        let start = Instant::now();
+        let mut tracker = FpsTracker::new();
+
+        // 显示器 1 每 20ms 一帧，显示器 2 每 50ms 一帧
+        for i in 1..=100u64 {
+            let now = start + Duration::from_millis(i * 10);
+            if i % 2 == 0 {
+                tracker.record(Some(1), now);
+            }
+            if i % 5 == 0 {
+                tracker.record(Some(2), now);
+            }
+        }
+
+        let fps = tracker.snapshot(start + Duration::from_millis(1000));
+        assert!((fps.displays[&1].smoothed - 50.0).abs() < 0.01);
+        assert!((fps.displays[&1].instantaneous - 50.0).abs() < 0.01);
+        assert!((fps.displays[&2].smoothed - 20.0).abs() < 0.01);
+        assert!(fps.overall.smoothed > fps.displays[&1].smoothed);
+
+        // 停止发送超过平滑窗口后，瞬时帧率不再沿用最后两帧的间隔
+        let fps = tracker.snapshot(start + Duration::from_millis(4000));
+        assert_eq!(fps.overall, FpsSnapshot::default());
+        assert_eq!(fps.displays[&1], FpsSnapshot::default());
+    }
+
     #[tokio::test]
     async fn test_set_data_send_mode() {
         use crate::led_data_sender::LedDataSender;
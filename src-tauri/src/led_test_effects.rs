@@ -1,11 +1,24 @@
-use crate::ambient_light::LedType; // 使用统一的LedType
+use crate::ambient_light::{ColorOrder, LedStripConfigV2, LedType}; // 使用统一的LedType
 use serde::{Deserialize, Serialize};
 use std::collections::HashMap;
 use std::f64::consts::PI;
+use std::net::SocketAddr;
 use std::sync::Arc;
 use tokio::sync::{OnceCell, RwLock};
 use tokio::time::{Duration, Instant};
 use tokio_util::sync::CancellationToken;
+use utoipa::ToSchema;
+
+/// 设备识别闪烁的持续时间
+const IDENTIFY_DURATION: Duration = Duration::from_secs(3);
+/// 设备识别闪烁的亮/灭切换间隔
+const IDENTIFY_BLINK_INTERVAL: Duration = Duration::from_millis(250);
+/// 未配置灯带时识别图案点亮的LED数量
+const IDENTIFY_FALLBACK_LED_COUNT: usize = 60;
+/// 识别图案的颜色（青色，与常见的测试颜色区分开）
+const IDENTIFY_COLOR: [u8; 3] = [0, 255, 255];
+/// 所有测试效果合计每秒最多发送的帧数，超出时按比例放慢各设备的更新，避免多设备时占满网络
+pub const MAX_COMBINED_UPDATES_PER_SECOND: f64 = 240.0;
 
 #[derive(Debug, Clone, Serialize, Deserialize)]
 pub enum TestEffectType {
@@ -13,6 +26,7 @@ pub enum TestEffectType {
     GroupCounting,
     SingleScan,
     Breathing,
+    IndexMarker,
 }
 
 #[derive(Debug, Clone, Serialize, Deserialize)]
@@ -22,6 +36,52 @@ pub struct TestEffectConfig {
     pub led_type: LedType,
     pub speed: f64,  // Speed multiplier
     pub offset: u32, // Byte offset
+    /// 硬件颜色字节顺序，默认GRB/GRBW
+    #[serde(default)]
+    pub color_order: ColorOrder,
+    /// 多设备连续效果中本设备第一个LED的全局序号
+    #[serde(default)]
+    pub led_index_offset: u32,
+    /// 多设备连续效果的LED总数，0 表示仅使用本设备的 `led_count`
+    #[serde(default)]
+    pub total_led_count: u32,
+    /// 各效果的可选参数
+    #[serde(default)]
+    pub params: TestEffectParams,
+}
+
+/// 测试效果的可选参数，未设置的字段使用效果的默认值，与当前效果无关的字段被忽略
+#[derive(Debug, Clone, Default, PartialEq, Serialize, Deserialize)]
+#[serde(default)]
+pub struct TestEffectParams {
+    /// 分组计数：每组的LED数量（默认 10）
+    pub group_size: Option<u32>,
+    /// 分组计数：各组颜色，按顺序循环使用（默认10种颜色）
+    pub group_colors: Option<Vec<[u8; 3]>>,
+    /// 呼吸：颜色（默认白色）
+    pub breathing_color: Option<[u8; 3]>,
+    /// 呼吸：最低亮度（0-1，默认 0）
+    pub min_brightness: Option<f64>,
+    /// 呼吸：最高亮度（0-1，默认 1）
+    pub max_brightness: Option<f64>,
+}
+
+/// 校验测试效果参数
+pub fn validate_test_effect_params(params: &TestEffectParams) -> anyhow::Result<()> {
+    if params.group_size == Some(0) {
+        anyhow::bail!("group size must be greater than 0");
+    }
+    if params.group_colors.as_ref().is_some_and(Vec::is_empty) {
+        anyhow::bail!("group colors must not be empty");
+    }
+    let min = params.min_brightness.unwrap_or(0.0);
+    let max = params.max_brightness.unwrap_or(1.0);
+    if !(0.0..=1.0).contains(&min) || !(0.0..=1.0).contains(&max) || min > max {
+        anyhow::bail!(
+            "breathing brightness must satisfy 0 <= min <= max <= 1, got min {min}, max {max}"
+        );
+    }
+    Ok(())
 }
 
 /// LED测试效果任务信息
@@ -29,15 +89,117 @@ pub struct TestEffectConfig {
 pub struct TestEffectTask {
     pub board_address: String,
     pub config: TestEffectConfig,
+    /// 该设备自己的更新间隔（毫秒），实际间隔还受合计更新速率上限约束
     pub update_interval_ms: u32,
     pub start_time: Instant,
     pub cancellation_token: CancellationToken,
+    /// 是否属于全部设备同时运行的测试效果，此时数据直接发往该设备，避免与其他设备争用测试目标地址
+    pub all_boards: bool,
+}
+
+/// 灯带长度检测状态
+#[derive(Debug, Clone, Serialize, ToSchema)]
+pub struct LengthDetectionStatus {
+    /// 当前点亮的LED位置（从0开始）
+    pub position: u32,
+    /// 检测的最大LED数量
+    pub led_count: u32,
+    /// 是否按更新间隔自动前进
+    pub auto_advance: bool,
+    /// 已标记的各段灯带长度，按串联顺序排列
+    pub segment_lengths: Vec<usize>,
+}
+
+/// 灯带长度检测：单个亮灯沿灯带逐个前进，用户在亮灯到达拐角时标记，
+/// 由标记位置推算每条边的LED数量
+#[derive(Debug, Clone)]
+pub struct LengthDetector {
+    led_count: u32,
+    position: u32,
+    auto_advance: bool,
+    /// 是否已经显示过当前位置，首帧不前进
+    rendered: bool,
+    /// 每段灯带的结束位置（不含），即下一段第一个LED的位置
+    corners: Vec<u32>,
+}
+
+impl LengthDetector {
+    pub fn new(led_count: u32, auto_advance: bool) -> Self {
+        Self {
+            led_count,
+            position: 0,
+            auto_advance,
+            rendered: false,
+            corners: Vec::new(),
+        }
+    }
+
+    pub fn position(&self) -> u32 {
+        self.position
+    }
+
+    pub fn set_auto_advance(&mut self, auto_advance: bool) {
+        self.auto_advance = auto_advance;
+    }
+
+    /// 前进（或以负数后退）若干个LED，位置限制在灯带范围内
+    pub fn step(&mut self, steps: i32) -> u32 {
+        let last = self.led_count.saturating_sub(1) as i64;
+        self.position = (self.position as i64 + steps as i64).clamp(0, last) as u32;
+        self.position
+    }
+
+    /// 每个更新周期调用一次，返回本帧应点亮的LED位置
+    fn tick(&mut self) -> u32 {
+        if self.auto_advance && self.rendered {
+            self.step(1);
+        }
+        self.rendered = true;
+        self.position
+    }
+
+    /// 将当前点亮的LED标记为一段灯带的最后一个LED，返回该段的长度
+    pub fn mark_corner(&mut self) -> anyhow::Result<usize> {
+        let start = self.corners.last().copied().unwrap_or(0);
+        let end = self.position + 1;
+        if end <= start {
+            anyhow::bail!("Corner must be after the previous mark (LED {start})");
+        }
+        self.corners.push(end);
+        Ok((end - start) as usize)
+    }
+
+    /// 已标记的各段灯带长度
+    pub fn segment_lengths(&self) -> Vec<usize> {
+        let mut start = 0;
+        self.corners
+            .iter()
+            .map(|&end| {
+                let len = (end - start) as usize;
+                start = end;
+                len
+            })
+            .collect()
+    }
+
+    pub fn status(&self) -> LengthDetectionStatus {
+        LengthDetectionStatus {
+            position: self.position,
+            led_count: self.led_count,
+            auto_advance: self.auto_advance,
+            segment_lengths: self.segment_lengths(),
+        }
+    }
 }
 
 /// LED测试效果管理器
 pub struct LedTestEffectManager {
     /// 活跃的测试效果任务
     active_tasks: Arc<RwLock<HashMap<String, TestEffectTask>>>,
+    /// 进行中的灯带长度检测，按设备地址索引
+    length_detections: Arc<RwLock<HashMap<String, LengthDetector>>>,
+    /// 进行中的设备识别闪烁，按设备地址索引
+    identify_tasks: Arc<RwLock<HashMap<SocketAddr, CancellationToken>>>,
 }
 
 impl LedTestEffectManager {
@@ -45,6 +207,8 @@ impl LedTestEffectManager {
     pub fn new() -> Self {
         Self {
             active_tasks: Arc::new(RwLock::new(HashMap::new())),
+            length_detections: Arc::new(RwLock::new(HashMap::new())),
+            identify_tasks: Arc::new(RwLock::new(HashMap::new())),
         }
     }
 
@@ -66,6 +230,353 @@ impl LedTestEffectManager {
         // 如果已有相同设备的任务在运行，先停止它
         self.stop_test_effect(&board_address).await?;
 
+        self.spawn_test_effect(board_address, config, update_interval_ms, false)
+            .await;
+        Ok(())
+    }
+
+    /// 在所有已发现的设备上同时启动测试效果
+    ///
+    /// 各设备的起始偏移量和LED数量由其所接灯带（设备配置的 `strips`，为空表示全部灯带）
+    /// 在灯带配置中的串联位置推导；尚未配置灯带时设备按地址排序首尾相接，各使用
+    /// `config.led_count` 个LED。流动彩虹等效果按全局LED序号计算，从而连续地跨越整个安装。
+    /// `board_update_intervals_ms` 按设备地址覆盖更新间隔，未指定的设备使用 `update_interval_ms`。
+    /// 返回按串联顺序排列的设备地址。
+    pub async fn start_test_effect_on_all_boards(
+        &self,
+        config: TestEffectConfig,
+        update_interval_ms: u32,
+        board_update_intervals_ms: &HashMap<String, u32>,
+    ) -> anyhow::Result<Vec<String>> {
+        let udp_rpc = crate::rpc::UdpRpc::global()
+            .await
+            .as_ref()
+            .map_err(|err| anyhow::anyhow!("UDP RPC not available: {}", err))?;
+
+        let board_strips: Vec<(String, Vec<usize>)> = udp_rpc
+            .get_board_strips()
+            .await
+            .into_iter()
+            .map(|(addr, strips)| (addr.to_string(), strips))
+            .collect();
+        if board_strips.is_empty() {
+            anyhow::bail!("No boards available");
+        }
+
+        let strips = crate::ambient_light::ConfigManagerV2::global()
+            .await
+            .get_config()
+            .await
+            .strips;
+        let board_configs = if strips.is_empty() {
+            let configs = Self::chain_configs(&config, board_strips.len());
+            board_strips
+                .into_iter()
+                .map(|(addr, _)| addr)
+                .zip(configs)
+                .collect()
+        } else {
+            Self::board_configs(&config, &strips, &board_strips)
+        };
+
+        self.stop_all_boards_test_effect().await?;
+
+        let mut board_addresses = Vec::with_capacity(board_configs.len());
+        for (board_address, board_config) in board_configs {
+            self.stop_test_effect(&board_address).await?;
+            let interval_ms = board_update_intervals_ms
+                .get(&board_address)
+                .copied()
+                .unwrap_or(update_interval_ms);
+            self.spawn_test_effect(board_address.clone(), board_config, interval_ms, true)
+                .await;
+            board_addresses.push(board_address);
+        }
+
+        log::info!(
+            "✅ LED test effect started on {} boards",
+            board_addresses.len()
+        );
+        Ok(board_addresses)
+    }
+
+    /// 停止由 `start_test_effect_on_all_boards` 启动的所有测试效果，单独启动的效果不受影响
+    pub async fn stop_all_boards_test_effect(&self) -> anyhow::Result<Vec<String>> {
+        let board_addresses: Vec<String> = {
+            let tasks = self.active_tasks.read().await;
+            tasks
+                .values()
+                .filter(|task| task.all_boards)
+                .map(|task| task.board_address.clone())
+                .collect()
+        };
+
+        for board_address in &board_addresses {
+            self.stop_test_effect(board_address).await?;
+        }
+
+        Ok(board_addresses)
+    }
+
+    /// 为串联的各设备生成效果配置，设置各自的全局LED起始序号
+    fn chain_configs(config: &TestEffectConfig, board_count: usize) -> Vec<TestEffectConfig> {
+        let total_led_count = config.led_count * board_count as u32;
+        (0..board_count as u32)
+            .map(|index| TestEffectConfig {
+                led_index_offset: index * config.led_count,
+                total_led_count,
+                ..config.clone()
+            })
+            .collect()
+    }
+
+    /// 按灯带配置为各设备生成效果配置，返回按串联顺序排列的 (设备地址, 配置)
+    ///
+    /// 灯带按串联顺序（考虑 `hardware_offset`）排布，设备的字节偏移量和全局LED起始序号
+    /// 取其第一条灯带，LED数量为其所接灯带的长度之和；`board_strips` 中为空的设备驱动全部灯带
+    fn board_configs(
+        config: &TestEffectConfig,
+        strips: &[LedStripConfigV2],
+        board_strips: &[(String, Vec<usize>)],
+    ) -> Vec<(String, TestEffectConfig)> {
+        let mut sorted_strips: Vec<_> = strips.iter().collect();
+        sorted_strips.sort_by_key(|strip| strip.chain_order());
+
+        // 每条灯带的 (序列号, 字节起始位置, 全局LED起始序号, 长度)
+        let mut layout = Vec::with_capacity(sorted_strips.len());
+        let mut next_byte_offset = 0;
+        let mut next_led_index = 0;
+        for strip in sorted_strips {
+            let bytes_per_led = strip.led_type.bytes_per_led();
+            let byte_start = strip
+                .hardware_offset
+                .map(|offset| offset * bytes_per_led)
+                .unwrap_or(next_byte_offset);
+            layout.push((strip.index, byte_start, next_led_index, strip.len));
+            next_byte_offset = byte_start + strip.len * bytes_per_led;
+            next_led_index += strip.len;
+        }
+        let total_led_count = next_led_index as u32;
+
+        let mut configs: Vec<(usize, String, TestEffectConfig)> = board_strips
+            .iter()
+            .filter_map(|(board_address, board_strips)| {
+                let owned: Vec<_> = layout
+                    .iter()
+                    .filter(|(index, ..)| board_strips.is_empty() || board_strips.contains(index))
+                    .collect();
+                let &(_, byte_start, led_start, _) = *owned.first()?;
+                let led_count: usize = owned.iter().map(|&&(.., len)| len).sum();
+                Some((
+                    led_start,
+                    board_address.clone(),
+                    TestEffectConfig {
+                        led_count: led_count as u32,
+                        offset: byte_start as u32,
+                        led_index_offset: led_start as u32,
+                        total_led_count,
+                        ..config.clone()
+                    },
+                ))
+            })
+            .collect();
+        configs.sort_by(|a, b| (a.0, &a.1).cmp(&(b.0, &b.1)));
+        configs
+            .into_iter()
+            .map(|(_, board_address, config)| (board_address, config))
+            .collect()
+    }
+
+    /// 启动灯带长度检测
+    ///
+    /// 复用单灯扫描效果，亮灯每个更新周期前进一个LED（或由用户手动步进），
+    /// `config.led_count` 为检测的最大LED数量
+    pub async fn start_length_detection(
+        &self,
+        board_address: String,
+        mut config: TestEffectConfig,
+        step_interval_ms: u32,
+        auto_advance: bool,
+    ) -> anyhow::Result<LengthDetectionStatus> {
+        if config.led_count == 0 {
+            anyhow::bail!("LED count must be greater than 0");
+        }
+        if step_interval_ms == 0 {
+            anyhow::bail!("Step interval must be greater than 0 ms");
+        }
+
+        self.stop_test_effect(&board_address).await?;
+
+        config.effect_type = TestEffectType::SingleScan;
+        let detector = LengthDetector::new(config.led_count, auto_advance);
+        let status = detector.status();
+        self.length_detections
+            .write()
+            .await
+            .insert(board_address.clone(), detector);
+
+        self.spawn_test_effect(board_address.clone(), config, step_interval_ms, false)
+            .await;
+
+        log::info!("📏 LED length detection started for board: {board_address}");
+        Ok(status)
+    }
+
+    /// 手动步进长度检测的亮灯位置，可同时切换自动前进
+    pub async fn advance_length_detection(
+        &self,
+        board_address: &str,
+        steps: i32,
+        auto_advance: Option<bool>,
+    ) -> anyhow::Result<LengthDetectionStatus> {
+        let mut detections = self.length_detections.write().await;
+        let detector = detections.get_mut(board_address).ok_or_else(|| {
+            anyhow::anyhow!("No length detection running for board: {board_address}")
+        })?;
+
+        if let Some(auto_advance) = auto_advance {
+            detector.set_auto_advance(auto_advance);
+        }
+        detector.step(steps);
+        Ok(detector.status())
+    }
+
+    /// 标记亮灯已到达拐角（当前亮灯为该段最后一个LED）
+    pub async fn mark_length_detection_corner(
+        &self,
+        board_address: &str,
+    ) -> anyhow::Result<LengthDetectionStatus> {
+        let mut detections = self.length_detections.write().await;
+        let detector = detections.get_mut(board_address).ok_or_else(|| {
+            anyhow::anyhow!("No length detection running for board: {board_address}")
+        })?;
+
+        let len = detector.mark_corner()?;
+        log::info!(
+            "📏 Corner marked at LED {} for board {board_address}, segment length: {len}",
+            detector.position()
+        );
+        Ok(detector.status())
+    }
+
+    /// 获取长度检测状态
+    pub async fn get_length_detection_status(
+        &self,
+        board_address: &str,
+    ) -> Option<LengthDetectionStatus> {
+        self.length_detections
+            .read()
+            .await
+            .get(board_address)
+            .map(LengthDetector::status)
+    }
+
+    /// 结束长度检测并返回已标记的各段长度
+    pub async fn finish_length_detection(&self, board_address: &str) -> anyhow::Result<Vec<usize>> {
+        let segment_lengths = self
+            .length_detections
+            .read()
+            .await
+            .get(board_address)
+            .map(LengthDetector::segment_lengths)
+            .ok_or_else(|| {
+                anyhow::anyhow!("No length detection running for board: {board_address}")
+            })?;
+
+        self.stop_test_effect(board_address).await?;
+        Ok(segment_lengths)
+    }
+
+    /// 让指定设备的灯带以识别图案闪烁，`IDENTIFY_DURATION` 后自动熄灭
+    ///
+    /// 数据只发送到该设备，不切换全局发送模式；闪烁期间该设备暂不接收广播数据，
+    /// 结束后恢复，其他设备不受影响。对同一设备重复识别时会取代上一次识别。
+    pub async fn identify_board(&self, target_addr: SocketAddr) -> anyhow::Result<()> {
+        let udp_rpc = crate::rpc::UdpRpc::global()
+            .await
+            .as_ref()
+            .map_err(|err| anyhow::anyhow!("UDP RPC not available: {}", err))?;
+
+        let strips = crate::ambient_light::ConfigManagerV2::global()
+            .await
+            .get_config()
+            .await
+            .strips;
+        let board_strips = udp_rpc
+            .get_board_strips()
+            .await
+            .into_iter()
+            .find(|(addr, _)| *addr == target_addr)
+            .map(|(_, board_strips)| board_strips)
+            .unwrap_or_default();
+        let on_frame = LedTestEffects::identify_frame(&strips, &board_strips);
+        let off_frame = vec![0u8; on_frame.len()];
+
+        let token = CancellationToken::new();
+        if let Some(previous) = self
+            .identify_tasks
+            .write()
+            .await
+            .insert(target_addr, token.clone())
+        {
+            previous.cancel();
+        }
+        udp_rpc.hold_broadcast(target_addr).await;
+
+        log::info!("🔦 Identifying board {target_addr} for {IDENTIFY_DURATION:?}");
+
+        let identify_tasks = self.identify_tasks.clone();
+        tokio::spawn(async move {
+            let sender = crate::led_data_sender::LedDataSender::global().await;
+            let deadline = Instant::now() + IDENTIFY_DURATION;
+            let mut lit = true;
+
+            while Instant::now() < deadline && !token.is_cancelled() {
+                let frame = if lit { &on_frame } else { &off_frame };
+                if let Err(e) = sender
+                    .send_to_board(target_addr, 0, frame, "Identify")
+                    .await
+                {
+                    log::warn!("⚠️ Failed to send identify frame to {target_addr}: {e}");
+                }
+                lit = !lit;
+
+                tokio::select! {
+                    _ = tokio::time::sleep(IDENTIFY_BLINK_INTERVAL) => {}
+                    _ = token.cancelled() => {}
+                }
+            }
+
+            // 被新的识别取代时由新任务负责后续清理
+            let mut tasks = identify_tasks.write().await;
+            if token.is_cancelled() {
+                return;
+            }
+            tasks.remove(&target_addr);
+            drop(tasks);
+
+            // 熄灭灯带后再恢复广播，避免停留在点亮状态
+            if let Err(e) = sender
+                .send_to_board(target_addr, 0, &off_frame, "Identify")
+                .await
+            {
+                log::warn!("⚠️ Failed to clear identify pattern on {target_addr}: {e}");
+            }
+            udp_rpc.release_broadcast(target_addr).await;
+            log::info!("✅ Board identify finished: {target_addr}");
+        });
+
+        Ok(())
+    }
+
+    /// 启动测试效果后台任务
+    async fn spawn_test_effect(
+        &self,
+        board_address: String,
+        config: TestEffectConfig,
+        update_interval_ms: u32,
+        all_boards: bool,
+    ) {
         // 创建新任务
         let cancellation_token = CancellationToken::new();
         let task = TestEffectTask {
@@ -74,6 +585,7 @@ impl LedTestEffectManager {
             update_interval_ms,
             start_time: Instant::now(),
             cancellation_token: cancellation_token.clone(),
+            all_boards,
         };
 
         // 添加到活跃任务列表
@@ -92,13 +604,15 @@ impl LedTestEffectManager {
         });
 
         log::info!("✅ LED test effect started for board: {board_address}");
-        Ok(())
     }
 
     /// 停止LED测试效果
     pub async fn stop_test_effect(&self, board_address: &str) -> anyhow::Result<()> {
         log::info!("🛑 Stopping LED test effect for board: {board_address}");
 
+        // 测试效果停止时长度检测随之结束
+        self.length_detections.write().await.remove(board_address);
+
         let mut tasks = self.active_tasks.write().await;
         if let Some(task) = tasks.remove(board_address) {
             // 立即取消任务，唤醒睡眠中的循环
@@ -106,7 +620,8 @@ impl LedTestEffectManager {
             log::info!("✅ LED test effect stopped and cancelled for board: {board_address}");
 
             // 发送全黑数据来清除LED
-            self.send_clear_data(board_address, &task.config).await?;
+            self.send_clear_data(board_address, &task.config, task.all_boards)
+                .await?;
         } else {
             log::warn!("⚠️ No active test effect found for board: {board_address}");
         }
@@ -159,8 +674,17 @@ impl LedTestEffectManager {
             // 计算当前时间
             let elapsed_ms = task.start_time.elapsed().as_millis() as u64;
 
-            // 生成LED颜色数据
-            let colors = LedTestEffects::generate_colors(&task.config, elapsed_ms);
+            // 生成LED颜色数据，长度检测进行中时按检测位置点亮单个LED
+            let detection_position = self
+                .length_detections
+                .write()
+                .await
+                .get_mut(&board_address)
+                .map(LengthDetector::tick);
+            let colors = match detection_position {
+                Some(position) => LedTestEffects::chase_frame(&task.config, position),
+                None => LedTestEffects::generate_colors(&task.config, elapsed_ms),
+            };
 
             // 使用配置中的字节偏移量
             let byte_offset = task.config.offset as u16;
@@ -175,30 +699,69 @@ impl LedTestEffectManager {
             }
 
             // 等待下一次更新，或者被取消
-            // 使用较小的时间片来提高取消响应速度
-            let sleep_duration = Duration::from_millis(task.update_interval_ms as u64);
-            let chunk_size = Duration::from_millis(10); // 10ms时间片
-
-            let mut remaining = sleep_duration;
-            while remaining > Duration::ZERO {
-                let current_sleep = if remaining > chunk_size {
-                    chunk_size
-                } else {
-                    remaining
-                };
+            if !self.wait_for_next_update(&task).await {
+                return Ok(()); // 立即返回，不继续循环
+            }
+        }
 
-                tokio::select! {
-                    _ = tokio::time::sleep(current_sleep) => {
-                        remaining = remaining.saturating_sub(current_sleep);
-                    }
-                    _ = task.cancellation_token.cancelled() => {
-                        return Ok(()); // 立即返回，不继续循环
-                    }
+        Ok(())
+    }
+
+    /// 等待到设备的下一次更新，任务被取消时返回 `false`
+    ///
+    /// 各设备按自己的间隔更新，合计速率超过上限时统一放慢
+    async fn wait_for_next_update(&self, task: &TestEffectTask) -> bool {
+        let sleep_duration = {
+            let tasks = self.active_tasks.read().await;
+            let intervals: Vec<u32> = tasks.values().map(|task| task.update_interval_ms).collect();
+            Self::throttled_interval(
+                task.update_interval_ms,
+                &intervals,
+                MAX_COMBINED_UPDATES_PER_SECOND,
+            )
+        };
+        // 使用较小的时间片来提高取消响应速度
+        let chunk_size = Duration::from_millis(10); // 10ms时间片
+
+        let mut remaining = sleep_duration;
+        while remaining > Duration::ZERO {
+            let current_sleep = if remaining > chunk_size {
+                chunk_size
+            } else {
+                remaining
+            };
+
+            tokio::select! {
+                _ = tokio::time::sleep(current_sleep) => {
+                    remaining = remaining.saturating_sub(current_sleep);
+                }
+                _ = task.cancellation_token.cancelled() => {
+                    return false;
                 }
             }
         }
+        true
+    }
 
-        Ok(())
+    /// 计算设备实际的更新间隔
+    ///
+    /// `intervals_ms` 为所有活跃任务（含自身）的间隔，合计每秒帧数不超过 `max_updates_per_second`
+    /// 时保持自身间隔，否则所有间隔按同一比例放大，使合计速率恰好等于上限
+    fn throttled_interval(
+        interval_ms: u32,
+        intervals_ms: &[u32],
+        max_updates_per_second: f64,
+    ) -> Duration {
+        let interval = Duration::from_millis(interval_ms.max(1) as u64);
+        let combined_rate: f64 = intervals_ms
+            .iter()
+            .map(|&interval_ms| 1000.0 / interval_ms.max(1) as f64)
+            .sum();
+        if max_updates_per_second <= 0.0 || combined_rate <= max_updates_per_second {
+            return interval;
+        }
+
+        interval.mul_f64(combined_rate / max_updates_per_second)
     }
 
     /// 发送测试数据到硬件
@@ -211,11 +774,13 @@ impl LedTestEffectManager {
         // 获取任务配置以确定LED类型和数量
         let task_config = {
             let tasks = self.active_tasks.read().await;
-            tasks.get(board_address).map(|task| task.config.clone())
+            tasks
+                .get(board_address)
+                .map(|task| (task.config.clone(), task.all_boards))
         };
 
-        let config = match task_config {
-            Some(config) => config,
+        let (config, all_boards) = match task_config {
+            Some(task_config) => task_config,
             None => {
                 log::warn!("⚠️ No active task found for board: {board_address}");
                 return Ok(());
@@ -223,13 +788,18 @@ impl LedTestEffectManager {
         };
 
         // 将硬件数据转换为RGB颜色数组用于预览
-        let rgb_colors = LedTestEffects::hardware_data_to_rgb_colors(&data, &config.led_type);
+        let rgb_colors = LedTestEffects::hardware_data_to_rgb_colors(
+            &data,
+            &config.led_type,
+            config.color_order,
+        );
 
         // 使用LED数据处理器来发布预览数据并编码硬件数据
         let hardware_data = crate::led_data_processor::LedDataProcessor::process_test_mode(
             rgb_colors,
             config.led_type,
             config.led_count as usize,
+            config.color_order,
             crate::led_data_sender::DataSendMode::TestEffect,
         )
         .await?;
@@ -242,6 +812,14 @@ impl LedTestEffectManager {
             .set_mode(crate::led_data_sender::DataSendMode::TestEffect)
             .await;
 
+        // 多设备同时运行时直接发往该设备，不改动全局测试目标
+        if all_boards {
+            let target_addr: SocketAddr = board_address.parse()?;
+            return sender
+                .send_to_board(target_addr, offset, &hardware_data, "TestEffect")
+                .await;
+        }
+
         // 设置目标设备
         sender
             .set_test_target(Some(board_address.to_string()))
@@ -260,6 +838,7 @@ impl LedTestEffectManager {
         &self,
         board_address: &str,
         config: &TestEffectConfig,
+        all_boards: bool,
     ) -> anyhow::Result<()> {
         log::info!("🧹 Sending clear data to {board_address} without changing mode");
 
@@ -274,6 +853,15 @@ impl LedTestEffectManager {
         // 直接发送清除数据，不通过send_test_data避免模式冲突
         let sender = crate::led_data_sender::LedDataSender::global().await;
 
+        if all_boards {
+            let target_addr: SocketAddr = board_address.parse()?;
+            sender
+                .send_to_board(target_addr, byte_offset, &clear_data, "ClearData")
+                .await?;
+            log::info!("✅ Clear data sent to {board_address}");
+            return Ok(());
+        }
+
         // 设置目标设备
         sender
             .set_test_target(Some(board_address.to_string()))
@@ -293,6 +881,8 @@ impl Clone for LedTestEffectManager {
     fn clone(&self) -> Self {
         Self {
             active_tasks: self.active_tasks.clone(),
+            length_detections: self.length_detections.clone(),
+            identify_tasks: self.identify_tasks.clone(),
         }
     }
 }
@@ -305,77 +895,52 @@ impl LedTestEffects {
         matches!(led_type, LedType::SK6812)
     }
 
-    /// Convert RGB buffer to GRB for WS2812B
-    fn convert_rgb_to_grb(buffer: &mut [u8]) {
-        let bytes_per_led = 3; // RGB only
-        for i in (0..buffer.len()).step_by(bytes_per_led) {
-            if i + 2 < buffer.len() {
-                // Swap R and G: [R, G, B] -> [G, R, B]
-                buffer.swap(i, i + 1);
-            }
-        }
-    }
-
-    /// Convert RGBW buffer to GRBW for SK6812-RGBW
-    fn convert_rgbw_to_grbw(buffer: &mut [u8]) {
-        let bytes_per_led = 4; // RGBW
-        for i in (0..buffer.len()).step_by(bytes_per_led) {
-            if i + 3 < buffer.len() {
-                // Swap R and G: [R, G, B, W] -> [G, R, B, W]
-                buffer.swap(i, i + 1);
-            }
+    /// Convert RGB/RGBW buffer to the strip's hardware color order
+    fn convert_to_color_order(
+        buffer: &[u8],
+        led_type: LedType,
+        color_order: ColorOrder,
+    ) -> Vec<u8> {
+        let bytes_per_led = led_type.bytes_per_led();
+        let color_order = led_type.effective_color_order(color_order);
+        let mut converted = Vec::with_capacity(buffer.len());
+        for led in buffer.chunks_exact(bytes_per_led) {
+            let rgb = [led[0], led[1], led[2]];
+            let white = led.get(3).copied();
+            color_order.encode_into(rgb, white, &mut converted);
         }
+        converted
     }
 
     /// 将硬件数据转换为RGB颜色数组（用于预览）
     fn hardware_data_to_rgb_colors(
         data: &[u8],
         led_type: &LedType,
+        color_order: ColorOrder,
     ) -> Vec<crate::led_color::LedColor> {
-        let mut rgb_colors = Vec::new();
-
-        let bytes_per_led = match led_type {
-            LedType::WS2812B => 3, // RGB
-            LedType::SK6812 => 4,  // RGBW
-        };
-
-        let mut i = 0;
-        while i + bytes_per_led <= data.len() {
-            match led_type {
-                LedType::WS2812B => {
-                    // GRB格式 -> RGB
-                    let g = data[i];
-                    let r = data[i + 1];
-                    let b = data[i + 2];
-                    rgb_colors.push(crate::led_color::LedColor::new(r, g, b));
-                }
-                LedType::SK6812 => {
-                    // GRBW格式 -> RGB（忽略W通道）
-                    let g = data[i];
-                    let r = data[i + 1];
-                    let b = data[i + 2];
-                    // 忽略W通道 data[i + 3]
-                    rgb_colors.push(crate::led_color::LedColor::new(r, g, b));
-                }
-            }
-            i += bytes_per_led;
-        }
-
-        rgb_colors
+        data.chunks_exact(led_type.bytes_per_led())
+            .map(|led| {
+                // 按颜色顺序还原RGB（忽略W通道）
+                let ([r, g, b], _) = color_order.decode(led, *led_type);
+                crate::led_color::LedColor::new(r, g, b)
+            })
+            .collect()
     }
     /// Generate LED colors for a specific test effect at a given time
     pub fn generate_colors(config: &TestEffectConfig, time_ms: u64) -> Vec<u8> {
         let time_seconds = time_ms as f64 / 1000.0;
 
-        let mut buffer = match config.effect_type {
+        let buffer = match config.effect_type {
             TestEffectType::FlowingRainbow => Self::flowing_rainbow(
                 config.led_count,
                 config.led_type,
                 time_seconds,
                 config.speed,
+                config.led_index_offset,
+                config.total_led_count.max(config.led_count),
             ),
             TestEffectType::GroupCounting => {
-                Self::group_counting(config.led_count, config.led_type)
+                Self::group_counting(config.led_count, config.led_type, &config.params)
             }
             TestEffectType::SingleScan => Self::single_scan(
                 config.led_count,
@@ -388,30 +953,34 @@ impl LedTestEffects {
                 config.led_type,
                 time_seconds,
                 config.speed,
+                &config.params,
             ),
+            TestEffectType::IndexMarker => Self::index_marker(config.led_count, config.led_type),
         };
 
         // Convert RGB to correct color order based on LED type
-        match config.led_type {
-            LedType::WS2812B => {
-                Self::convert_rgb_to_grb(&mut buffer);
-            }
-            LedType::SK6812 => {
-                Self::convert_rgbw_to_grbw(&mut buffer);
-            }
-        }
-
-        buffer
+        Self::convert_to_color_order(&buffer, config.led_type, config.color_order)
     }
 
     /// Flowing rainbow effect - smooth rainbow colors flowing along the strip
-    fn flowing_rainbow(led_count: u32, led_type: LedType, time: f64, speed: f64) -> Vec<u8> {
+    ///
+    /// `index_offset` and `total_led_count` place this strip inside a longer chain
+    /// so the rainbow continues across boards.
+    fn flowing_rainbow(
+        led_count: u32,
+        led_type: LedType,
+        time: f64,
+        speed: f64,
+        index_offset: u32,
+        total_led_count: u32,
+    ) -> Vec<u8> {
         let mut buffer = Vec::new();
         let time_offset = (time * speed * 60.0) % 360.0; // 60 degrees per second at speed 1.0
 
         for i in 0..led_count {
             // Create longer wavelength for smoother color transitions
-            let hue = ((i as f64 * 720.0 / led_count as f64) + time_offset) % 360.0;
+            let global_index = (index_offset + i) as f64;
+            let hue = ((global_index * 720.0 / total_led_count as f64) + time_offset) % 360.0;
             let rgb = Self::hsv_to_rgb(hue, 1.0, 1.0);
 
             buffer.push(rgb.0);
@@ -427,26 +996,71 @@ impl LedTestEffects {
     }
 
     /// Group counting effect - every 10 LEDs have different colors
-    fn group_counting(led_count: u32, led_type: LedType) -> Vec<u8> {
+    ///
+    /// `params.group_size` and `params.group_colors` override the group length and colors.
+    fn group_counting(led_count: u32, led_type: LedType, params: &TestEffectParams) -> Vec<u8> {
         let mut buffer = Vec::new();
 
-        let group_colors = [
-            (255, 0, 0),     // Red (1-10)
-            (0, 255, 0),     // Green (11-20)
-            (0, 0, 255),     // Blue (21-30)
-            (255, 255, 0),   // Yellow (31-40)
-            (255, 0, 255),   // Magenta (41-50)
-            (0, 255, 255),   // Cyan (51-60)
-            (255, 128, 0),   // Orange (61-70)
-            (128, 255, 0),   // Lime (71-80)
-            (255, 255, 255), // White (81-90)
-            (128, 128, 128), // Gray (91-100)
+        const DEFAULT_GROUP_COLORS: [[u8; 3]; 10] = [
+            [255, 0, 0],     // Red (1-10)
+            [0, 255, 0],     // Green (11-20)
+            [0, 0, 255],     // Blue (21-30)
+            [255, 255, 0],   // Yellow (31-40)
+            [255, 0, 255],   // Magenta (41-50)
+            [0, 255, 255],   // Cyan (51-60)
+            [255, 128, 0],   // Orange (61-70)
+            [128, 255, 0],   // Lime (71-80)
+            [255, 255, 255], // White (81-90)
+            [128, 128, 128], // Gray (91-100)
         ];
+        let group_colors = params
+            .group_colors
+            .as_deref()
+            .filter(|colors| !colors.is_empty())
+            .unwrap_or(&DEFAULT_GROUP_COLORS);
+        let group_size = params.group_size.unwrap_or(10).max(1);
 
         for i in 0..led_count {
-            let group_index = (i / 10) % group_colors.len() as u32;
+            let group_index = (i / group_size) % group_colors.len() as u32;
             let color = group_colors[group_index as usize];
 
+            buffer.push(color[0]);
+            buffer.push(color[1]);
+            buffer.push(color[2]);
+
+            if Self::is_rgbw_type(&led_type) {
+                buffer.push(0); // White channel - 不点亮白色通道
+            }
+        }
+
+        buffer
+    }
+
+    /// Index marker pattern - shows where a strip starts and which way it runs
+    ///
+    /// LED #0 is green and every 10th LED after it is white, on top of a dim
+    /// blue-to-red gradient from the first LED to the last, so a reversed or
+    /// misindexed strip is obvious at a glance.
+    fn index_marker(led_count: u32, led_type: LedType) -> Vec<u8> {
+        const GRADIENT_BRIGHTNESS: f64 = 64.0;
+
+        let mut buffer = Vec::new();
+        let last_index = led_count.saturating_sub(1).max(1) as f64;
+
+        for i in 0..led_count {
+            let color = if i == 0 {
+                (0, 255, 0) // Green: first LED
+            } else if i % 10 == 0 {
+                (255, 255, 255) // White: every 10th LED
+            } else {
+                let t = i as f64 / last_index;
+                (
+                    (t * GRADIENT_BRIGHTNESS).round() as u8,
+                    0,
+                    ((1.0 - t) * GRADIENT_BRIGHTNESS).round() as u8,
+                )
+            };
+
             buffer.push(color.0);
             buffer.push(color.1);
             buffer.push(color.2);
@@ -461,10 +1075,65 @@ impl LedTestEffects {
 
     /// Single LED scan effect - one LED moves along the strip
     fn single_scan(led_count: u32, led_type: LedType, time: f64, speed: f64) -> Vec<u8> {
-        let mut buffer = Vec::new();
         let scan_period = 2.0 / speed; // 2 seconds per full scan at speed 1.0
         let active_index = ((time / scan_period * led_count as f64) as u32) % led_count;
 
+        Self::single_led(led_count, led_type, active_index)
+    }
+
+    /// Identify pattern: every other LED lit in the identify color, encoded per strip
+    ///
+    /// Strips are laid out in chain order (honouring `hardware_offset`) so the pattern
+    /// lines up with the ambient data. Only the strips wired to the board (`board_strips`,
+    /// empty meaning all) are lit, the rest stay black. Falls back to a plain WS2812B strip
+    /// when nothing is configured.
+    pub fn identify_frame(strips: &[LedStripConfigV2], board_strips: &[usize]) -> Vec<u8> {
+        let mut buffer = Vec::new();
+
+        if strips.is_empty() {
+            for i in 0..IDENTIFY_FALLBACK_LED_COUNT {
+                let color = if i % 2 == 0 { IDENTIFY_COLOR } else { [0; 3] };
+                ColorOrder::default().encode_into(color, None, &mut buffer);
+            }
+            return buffer;
+        }
+
+        let mut sorted_strips: Vec<_> = strips.iter().collect();
+        sorted_strips.sort_by_key(|strip| strip.chain_order());
+        for strip in sorted_strips {
+            let bytes_per_led = strip.led_type.bytes_per_led();
+            if let Some(offset) = strip.hardware_offset {
+                // 显式偏移量只用于跳过空隙，不能截断已写入的灯带
+                buffer.resize(buffer.len().max(offset * bytes_per_led), 0);
+            }
+            let lit = board_strips.is_empty() || board_strips.contains(&strip.index);
+            let white = match strip.led_type {
+                LedType::WS2812B | LedType::WS2811 => None,
+                LedType::SK6812 => Some(0),
+            };
+            let color_order = strip.led_type.effective_color_order(strip.color_order);
+            for i in 0..strip.len {
+                let color = if lit && i % 2 == 0 {
+                    IDENTIFY_COLOR
+                } else {
+                    [0; 3]
+                };
+                color_order.encode_into(color, white, &mut buffer);
+            }
+        }
+
+        buffer
+    }
+
+    /// Light a single LED at the given position (used by the length detection chase)
+    pub fn chase_frame(config: &TestEffectConfig, position: u32) -> Vec<u8> {
+        let buffer = Self::single_led(config.led_count, config.led_type, position);
+        Self::convert_to_color_order(&buffer, config.led_type, config.color_order)
+    }
+
+    fn single_led(led_count: u32, led_type: LedType, active_index: u32) -> Vec<u8> {
+        let mut buffer = Vec::new();
+
         for i in 0..led_count {
             if i == active_index {
                 // Bright white LED
@@ -491,19 +1160,35 @@ impl LedTestEffects {
     }
 
     /// Breathing effect - entire strip breathes with white light
-    fn breathing(led_count: u32, led_type: LedType, time: f64, speed: f64) -> Vec<u8> {
+    ///
+    /// `params.breathing_color` sets the color and `params.min_brightness` /
+    /// `params.max_brightness` bound the breathing range.
+    fn breathing(
+        led_count: u32,
+        led_type: LedType,
+        time: f64,
+        speed: f64,
+        params: &TestEffectParams,
+    ) -> Vec<u8> {
         let mut buffer = Vec::new();
         let breathing_period = 4.0 / speed; // 4 seconds per breath at speed 1.0
-        let brightness = ((time / breathing_period * 2.0 * PI).sin() * 0.5 + 0.5) * 255.0;
-        let brightness = brightness as u8;
+        let wave = (time / breathing_period * 2.0 * PI).sin() * 0.5 + 0.5;
+        let min_brightness = params.min_brightness.unwrap_or(0.0);
+        let max_brightness = params.max_brightness.unwrap_or(1.0);
+        let level = min_brightness + (max_brightness - min_brightness) * wave;
+
+        let color = params.breathing_color.unwrap_or([255, 255, 255]);
+        let [r, g, b] = color.map(|c| (c as f64 * level) as u8);
+        // White channel carries the white part of the color
+        let w = (*color.iter().min().unwrap_or(&0) as f64 * level) as u8;
 
         for _i in 0..led_count {
-            buffer.push(brightness);
-            buffer.push(brightness);
-            buffer.push(brightness);
+            buffer.push(r);
+            buffer.push(g);
+            buffer.push(b);
 
             if Self::is_rgbw_type(&led_type) {
-                buffer.push(brightness); // White channel
+                buffer.push(w); // White channel
             }
         }
 
@@ -567,14 +1252,21 @@ mod tests {
             led_type: LedType::WS2812B,
             speed: 1.0,
             offset: 0,
+            led_index_offset: 0,
+            total_led_count: 0,
+            params: TestEffectParams::default(),
+            color_order: ColorOrder::default(),
         };
 
         let colors_data = LedTestEffects::generate_colors(&config, 0);
         assert_eq!(colors_data.len(), 30); // 10 LEDs * 3 bytes per LED = 30 bytes
 
         // Convert hardware data back to RGB colors for verification
-        let rgb_colors =
-            LedTestEffects::hardware_data_to_rgb_colors(&colors_data, &config.led_type);
+        let rgb_colors = LedTestEffects::hardware_data_to_rgb_colors(
+            &colors_data,
+            &config.led_type,
+            config.color_order,
+        );
         assert_eq!(rgb_colors.len(), 10); // 10 LEDs
     }
 
@@ -586,14 +1278,21 @@ mod tests {
             led_type: LedType::WS2812B,
             speed: 1.0,
             offset: 0,
+            led_index_offset: 0,
+            total_led_count: 0,
+            params: TestEffectParams::default(),
+            color_order: ColorOrder::default(),
         };
 
         let colors_data = LedTestEffects::generate_colors(&config, 0);
         assert_eq!(colors_data.len(), 60); // 20 LEDs * 3 bytes per LED = 60 bytes
 
         // Convert hardware data back to RGB colors for testing
-        let rgb_colors =
-            LedTestEffects::hardware_data_to_rgb_colors(&colors_data, &config.led_type);
+        let rgb_colors = LedTestEffects::hardware_data_to_rgb_colors(
+            &colors_data,
+            &config.led_type,
+            config.color_order,
+        );
         assert_eq!(rgb_colors.len(), 20); // 20 LEDs
 
         // First 10 should be red
@@ -604,4 +1303,444 @@ mod tests {
         let tenth_color = rgb_colors[10].get_rgb();
         assert_eq!(tenth_color, [0, 255, 0]); // RGB: Green
     }
+
+    #[test]
+    fn test_index_marker_marks_start_tens_and_direction() {
+        let config = TestEffectConfig {
+            effect_type: TestEffectType::IndexMarker,
+            led_count: 25,
+            led_type: LedType::WS2812B,
+            speed: 1.0,
+            offset: 0,
+            led_index_offset: 0,
+            total_led_count: 0,
+            params: TestEffectParams::default(),
+            color_order: ColorOrder::default(),
+        };
+
+        let colors_data = LedTestEffects::generate_colors(&config, 0);
+        let rgb_colors = LedTestEffects::hardware_data_to_rgb_colors(
+            &colors_data,
+            &config.led_type,
+            config.color_order,
+        );
+        assert_eq!(rgb_colors.len(), 25);
+
+        assert_eq!(rgb_colors[0].get_rgb(), [0, 255, 0]);
+        assert_eq!(rgb_colors[10].get_rgb(), [255, 255, 255]);
+        assert_eq!(rgb_colors[20].get_rgb(), [255, 255, 255]);
+
+        // 渐变从蓝色过渡到红色，能看出灯带方向
+        let [r_start, _, b_start] = rgb_colors[1].get_rgb();
+        let [r_end, _, b_end] = rgb_colors[24].get_rgb();
+        assert!(b_start > r_start);
+        assert!(r_end > b_end);
+        assert!(rgb_colors[1..10]
+            .windows(2)
+            .all(|pair| pair[0].get_rgb()[0] <= pair[1].get_rgb()[0]));
+    }
+
+    #[test]
+    fn test_group_counting_wrgb_byte_positions() {
+        let config = TestEffectConfig {
+            effect_type: TestEffectType::GroupCounting,
+            led_count: 20,
+            led_type: LedType::SK6812,
+            speed: 1.0,
+            offset: 0,
+            led_index_offset: 0,
+            total_led_count: 0,
+            params: TestEffectParams::default(),
+            color_order: ColorOrder {
+                rgb: crate::ambient_light::RgbOrder::Rgb,
+                white: crate::ambient_light::WhitePosition::Leading,
+            },
+        };
+
+        let colors_data = LedTestEffects::generate_colors(&config, 0);
+        assert_eq!(colors_data.len(), 80); // 20 LEDs * 4 bytes per LED
+
+        // WRGB: W在第一个字节，随后为R、G、B
+        assert_eq!(&colors_data[0..4], &[0, 255, 0, 0]); // Red
+        assert_eq!(&colors_data[40..44], &[0, 0, 255, 0]); // Green
+
+        let rgb_colors = LedTestEffects::hardware_data_to_rgb_colors(
+            &colors_data,
+            &config.led_type,
+            config.color_order,
+        );
+        assert_eq!(rgb_colors[0].get_rgb(), [255, 0, 0]);
+        assert_eq!(rgb_colors[10].get_rgb(), [0, 255, 0]);
+    }
+
+    #[test]
+    fn test_length_detector_segments() {
+        let mut detector = LengthDetector::new(100, true);
+
+        // 首帧显示第一个LED，之后每帧前进一个
+        assert_eq!(detector.tick(), 0);
+        assert_eq!(detector.tick(), 1);
+
+        detector.step(28);
+        assert_eq!(detector.position(), 29);
+        assert_eq!(detector.mark_corner().unwrap(), 30);
+
+        // 不能在上一个拐角之前标记
+        detector.step(-5);
+        assert!(detector.mark_corner().is_err());
+
+        detector.step(25);
+        assert_eq!(detector.mark_corner().unwrap(), 20);
+
+        // 步进不会超出灯带范围
+        detector.step(1000);
+        assert_eq!(detector.position(), 99);
+        detector.set_auto_advance(false);
+        assert_eq!(detector.tick(), 99);
+
+        assert_eq!(detector.segment_lengths(), vec![30, 20]);
+    }
+
+    #[tokio::test]
+    async fn test_length_detection_rejects_zero_step_interval() {
+        let manager = LedTestEffectManager::new();
+        let config = TestEffectConfig {
+            effect_type: TestEffectType::SingleScan,
+            led_count: 10,
+            led_type: LedType::WS2812B,
+            speed: 1.0,
+            offset: 0,
+            color_order: ColorOrder::default(),
+            led_index_offset: 0,
+            total_led_count: 0,
+            params: TestEffectParams::default(),
+        };
+
+        assert!(manager
+            .start_length_detection("127.0.0.1:23042".to_string(), config, 0, true)
+            .await
+            .is_err());
+        assert!(manager.length_detections.read().await.is_empty());
+    }
+
+    #[test]
+    fn test_board_configs_follow_strip_layout() {
+        let config = TestEffectConfig {
+            effect_type: TestEffectType::FlowingRainbow,
+            led_count: 0,
+            led_type: LedType::WS2812B,
+            speed: 1.0,
+            offset: 0,
+            color_order: ColorOrder::default(),
+            led_index_offset: 0,
+            total_led_count: 0,
+            params: TestEffectParams::default(),
+        };
+        let strip = |index, len, chain_position| LedStripConfigV2 {
+            len,
+            chain_position,
+            ..LedStripConfigV2::default_for_display("display".to_string(), index)
+        };
+        // 串联顺序为 strip 1 (10), strip 0 (20), strip 2 (5)
+        let strips = [
+            strip(0, 20, Some(1)),
+            strip(1, 10, Some(0)),
+            strip(2, 5, Some(2)),
+        ];
+        let board_strips = [
+            ("10.0.0.1:23042".to_string(), vec![0, 2]),
+            ("10.0.0.2:23042".to_string(), vec![1]),
+            ("10.0.0.3:23042".to_string(), vec![7]),
+        ];
+
+        let configs = LedTestEffectManager::board_configs(&config, &strips, &board_strips);
+
+        // 没有所接灯带的设备被跳过，其余按串联顺序排列
+        let summary: Vec<_> = configs
+            .iter()
+            .map(|(addr, config)| {
+                (
+                    addr.as_str(),
+                    config.offset,
+                    config.led_count,
+                    config.led_index_offset,
+                    config.total_led_count,
+                )
+            })
+            .collect();
+        assert_eq!(
+            summary,
+            vec![
+                ("10.0.0.2:23042", 0, 10, 0, 35),
+                ("10.0.0.1:23042", 30, 25, 10, 35),
+            ]
+        );
+    }
+
+    #[test]
+    fn test_chained_rainbow_is_continuous_across_boards() {
+        let config = |led_count| TestEffectConfig {
+            effect_type: TestEffectType::FlowingRainbow,
+            led_count,
+            led_type: LedType::WS2812B,
+            speed: 1.0,
+            offset: 0,
+            color_order: ColorOrder::default(),
+            led_index_offset: 0,
+            total_led_count: 0,
+            params: TestEffectParams::default(),
+        };
+
+        let chained = LedTestEffectManager::chain_configs(&config(10), 3);
+        assert_eq!(
+            chained
+                .iter()
+                .map(|c| (c.led_index_offset, c.total_led_count))
+                .collect::<Vec<_>>(),
+            vec![(0, 30), (10, 30), (20, 30)]
+        );
+
+        // 三个设备各10个LED拼接后与一条30个LED的灯带完全一致
+        let joined: Vec<u8> = chained
+            .iter()
+            .flat_map(|c| LedTestEffects::generate_colors(c, 1500))
+            .collect();
+        assert_eq!(joined, LedTestEffects::generate_colors(&config(30), 1500));
+    }
+
+    /// 在暂停的时钟下同时运行各设备的更新循环，返回 `duration` 内每个设备的更新次数
+    async fn count_board_ticks(intervals_ms: &[u32], duration: Duration) -> Vec<u32> {
+        let manager = LedTestEffectManager::new();
+        let mut tasks = Vec::new();
+        for (index, &update_interval_ms) in intervals_ms.iter().enumerate() {
+            let board_address = format!("10.0.0.{}:23042", index + 1);
+            let task = TestEffectTask {
+                board_address: board_address.clone(),
+                config: TestEffectConfig {
+                    effect_type: TestEffectType::FlowingRainbow,
+                    led_count: 10,
+                    led_type: LedType::WS2812B,
+                    speed: 1.0,
+                    offset: 0,
+                    color_order: ColorOrder::default(),
+                    led_index_offset: 0,
+                    total_led_count: 0,
+                    params: TestEffectParams::default(),
+                },
+                update_interval_ms,
+                start_time: Instant::now(),
+                cancellation_token: CancellationToken::new(),
+                all_boards: true,
+            };
+            manager
+                .active_tasks
+                .write()
+                .await
+                .insert(board_address, task.clone());
+            tasks.push(task);
+        }
+
+        let handles: Vec<_> = tasks
+            .iter()
+            .cloned()
+            .map(|task| {
+                let manager = manager.clone();
+                tokio::spawn(async move {
+                    let mut ticks = 0;
+                    while manager.wait_for_next_update(&task).await {
+                        ticks += 1;
+                    }
+                    ticks
+                })
+            })
+            .collect();
+
+        tokio::time::sleep(duration).await;
+        for task in &tasks {
+            task.cancellation_token.cancel();
+        }
+
+        let mut ticks = Vec::new();
+        for handle in handles {
+            ticks.push(handle.await.unwrap());
+        }
+        ticks
+    }
+
+    #[tokio::test(start_paused = true)]
+    async fn test_boards_with_different_intervals_tick_independently() {
+        // 短灯带60fps，长灯带10fps，两个设备同时运行时各按自己的间隔更新
+        let ticks = count_board_ticks(&[16, 100], Duration::from_millis(1050)).await;
+        assert_eq!(ticks, vec![65, 10]);
+
+        // 合计速率超过上限时按同一比例放慢：6个设备各50fps合计300fps，
+        // 放慢到合计240fps，即每个设备25ms一次
+        let ticks = count_board_ticks(&[20; 6], Duration::from_millis(1010)).await;
+        assert_eq!(ticks, vec![40; 6]);
+    }
+
+    #[test]
+    fn test_chase_frame_lights_single_led() {
+        let config = TestEffectConfig {
+            effect_type: TestEffectType::SingleScan,
+            led_count: 4,
+            led_type: LedType::WS2812B,
+            speed: 1.0,
+            offset: 0,
+            led_index_offset: 0,
+            total_led_count: 0,
+            params: TestEffectParams::default(),
+            color_order: ColorOrder::default(),
+        };
+
+        let frame = LedTestEffects::chase_frame(&config, 2);
+        assert_eq!(frame, vec![0, 0, 0, 0, 0, 0, 255, 255, 255, 0, 0, 0]);
+    }
+
+    #[test]
+    fn test_identify_frame_alternates_leds() {
+        let strip = |index, len, led_type| LedStripConfigV2 {
+            led_type,
+            len,
+            ..LedStripConfigV2::default_for_display("display".to_string(), index)
+        };
+
+        // 按序列号排列，SK6812 灯带带白色通道
+        let frame = LedTestEffects::identify_frame(
+            &[strip(1, 2, LedType::SK6812), strip(0, 3, LedType::WS2812B)],
+            &[],
+        );
+        assert_eq!(
+            frame,
+            vec![
+                255, 0, 255, 0, 0, 0, 255, 0, 255, // WS2812B, GRB
+                255, 0, 255, 0, 0, 0, 0, 0, // SK6812, GRBW
+            ]
+        );
+
+        // 只点亮该设备所接的灯带
+        let frame = LedTestEffects::identify_frame(
+            &[strip(0, 3, LedType::WS2812B), strip(1, 2, LedType::WS2812B)],
+            &[1],
+        );
+        assert_eq!(
+            frame,
+            vec![
+                0, 0, 0, 0, 0, 0, 0, 0, 0, // strip 0 is on another board
+                255, 0, 255, 0, 0, 0, // strip 1
+            ]
+        );
+
+        assert_eq!(
+            LedTestEffects::identify_frame(&[], &[]).len(),
+            IDENTIFY_FALLBACK_LED_COUNT * 3
+        );
+    }
+
+    #[test]
+    fn test_identify_frame_hardware_offset_never_truncates() {
+        let strip = |index, len, hardware_offset| LedStripConfigV2 {
+            len,
+            hardware_offset,
+            ..LedStripConfigV2::default_for_display("display".to_string(), index)
+        };
+
+        // 第二条灯带的显式偏移量落在第一条灯带内部，不应截断已写入的数据
+        let frame = LedTestEffects::identify_frame(&[strip(0, 4, None), strip(1, 2, Some(1))], &[]);
+        assert_eq!(frame.len(), (4 + 2) * 3);
+    }
+
+    fn effect_with_params(
+        effect_type: TestEffectType,
+        params: TestEffectParams,
+    ) -> TestEffectConfig {
+        TestEffectConfig {
+            effect_type,
+            led_count: 8,
+            led_type: LedType::WS2812B,
+            speed: 1.0,
+            offset: 0,
+            led_index_offset: 0,
+            total_led_count: 0,
+            params,
+            color_order: ColorOrder::default(),
+        }
+    }
+
+    fn generate_rgb(config: &TestEffectConfig, time_ms: u64) -> Vec<[u8; 3]> {
+        let data = LedTestEffects::generate_colors(config, time_ms);
+        LedTestEffects::hardware_data_to_rgb_colors(&data, &config.led_type, config.color_order)
+            .iter()
+            .map(|color| color.get_rgb())
+            .collect()
+    }
+
+    #[test]
+    fn test_group_counting_honors_group_size_and_colors() {
+        let config = effect_with_params(
+            TestEffectType::GroupCounting,
+            TestEffectParams {
+                group_size: Some(3),
+                group_colors: Some(vec![[10, 20, 30], [40, 50, 60]]),
+                ..Default::default()
+            },
+        );
+
+        let colors = generate_rgb(&config, 0);
+        let a = [10, 20, 30];
+        let b = [40, 50, 60];
+        assert_eq!(colors, vec![a, a, a, b, b, b, a, a]);
+    }
+
+    #[test]
+    fn test_breathing_honors_color_and_brightness_range() {
+        let config = effect_with_params(
+            TestEffectType::Breathing,
+            TestEffectParams {
+                breathing_color: Some([255, 0, 0]),
+                min_brightness: Some(0.2),
+                max_brightness: Some(0.8),
+                ..Default::default()
+            },
+        );
+
+        // 速度为 1 时呼吸周期为 4 秒：1 秒时最亮，3 秒时最暗
+        assert!(generate_rgb(&config, 1000)
+            .iter()
+            .all(|&rgb| rgb == [204, 0, 0]));
+        assert!(generate_rgb(&config, 3000)
+            .iter()
+            .all(|&rgb| rgb == [51, 0, 0]));
+
+        // 默认参数保持白色、0-1 的亮度范围
+        let default = effect_with_params(TestEffectType::Breathing, TestEffectParams::default());
+        assert_eq!(generate_rgb(&default, 1000)[0], [255, 255, 255]);
+        assert_eq!(generate_rgb(&default, 3000)[0], [0, 0, 0]);
+    }
+
+    #[test]
+    fn test_validate_test_effect_params() {
+        assert!(validate_test_effect_params(&TestEffectParams::default()).is_ok());
+        for invalid in [
+            TestEffectParams {
+                group_size: Some(0),
+                ..Default::default()
+            },
+            TestEffectParams {
+                group_colors: Some(Vec::new()),
+                ..Default::default()
+            },
+            TestEffectParams {
+                min_brightness: Some(0.9),
+                max_brightness: Some(0.1),
+                ..Default::default()
+            },
+            TestEffectParams {
+                max_brightness: Some(1.5),
+                ..Default::default()
+            },
+        ] {
+            assert!(validate_test_effect_params(&invalid).is_err());
+        }
+    }
 }
@@ -0,0 +1,218 @@
+use std::collections::VecDeque;
+use std::str::FromStr;
+use std::sync::{
+    atomic::{AtomicUsize, Ordering},
+    Mutex, OnceLock,
+};
+
+use log::{LevelFilter, Log, Metadata, Record};
+use serde::Serialize;
+use utoipa::ToSchema;
+
+/// 内存日志缓冲区保留的最大日志条数
+pub const LOG_BUFFER_CAPACITY: usize = 2000;
+/// 默认捕获的日志级别（未设置 RUST_LOG 时终端只输出错误，缓冲区仍记录 info 以便导出）
+const DEFAULT_CAPTURE_LEVEL: LevelFilter = LevelFilter::Info;
+
+/// 捕获的一条日志
+#[derive(Debug, Clone, Serialize, ToSchema)]
+pub struct LogEntry {
+    /// 本地时间（RFC 3339）
+    pub timestamp: String,
+    /// 日志级别（ERROR/WARN/INFO/DEBUG/TRACE）
+    pub level: String,
+    /// 日志来源模块
+    pub target: String,
+    pub message: String,
+}
+
+/// 有界的日志环形缓冲区，写满后丢弃最早的日志
+pub struct LogBuffer {
+    capacity: usize,
+    entries: Mutex<VecDeque<LogEntry>>,
+}
+
+impl LogBuffer {
+    pub fn new(capacity: usize) -> Self {
+        Self {
+            capacity,
+            entries: Mutex::new(VecDeque::with_capacity(capacity)),
+        }
+    }
+
+    pub fn push(&self, entry: LogEntry) {
+        if self.capacity == 0 {
+            return;
+        }
+        let mut entries = self.entries.lock().unwrap_or_else(|e| e.into_inner());
+        if entries.len() >= self.capacity {
+            entries.pop_front();
+        }
+        entries.push_back(entry);
+    }
+
+    /// 获取最近的 `limit` 条日志，按时间先后排列
+    pub fn recent(&self, limit: usize) -> Vec<LogEntry> {
+        let entries = self.entries.lock().unwrap_or_else(|e| e.into_inner());
+        let skip = entries.len().saturating_sub(limit);
+        entries.iter().skip(skip).cloned().collect()
+    }
+}
+
+/// 同时输出到 env_logger 和内存缓冲区的日志记录器
+struct CaptureLogger {
+    env_logger: env_logger::Logger,
+    buffer: LogBuffer,
+    /// 运行时可调整的捕获级别（`LevelFilter as usize`）
+    capture_level: AtomicUsize,
+}
+
+impl CaptureLogger {
+    fn capture_level(&self) -> LevelFilter {
+        level_filter_from_usize(self.capture_level.load(Ordering::Relaxed))
+    }
+}
+
+impl Log for CaptureLogger {
+    fn enabled(&self, metadata: &Metadata) -> bool {
+        metadata.level() <= self.capture_level() || self.env_logger.enabled(metadata)
+    }
+
+    fn log(&self, record: &Record) {
+        if record.level() <= self.capture_level() {
+            self.buffer.push(LogEntry {
+                timestamp: chrono::Local::now().to_rfc3339(),
+                level: record.level().to_string(),
+                target: record.target().to_string(),
+                message: record.args().to_string(),
+            });
+        }
+        if self.env_logger.matches(record) {
+            self.env_logger.log(record);
+        }
+    }
+
+    fn flush(&self) {
+        self.env_logger.flush();
+    }
+}
+
+static LOGGER: OnceLock<CaptureLogger> = OnceLock::new();
+
+fn level_filter_from_usize(value: usize) -> LevelFilter {
+    LevelFilter::iter()
+        .find(|filter| *filter as usize == value)
+        .unwrap_or(DEFAULT_CAPTURE_LEVEL)
+}
+
+/// 初始化日志：终端输出仍由 RUST_LOG 控制，同时将日志捕获到内存缓冲区
+pub fn init() {
+    let env_logger = env_logger::Builder::from_default_env().build();
+    let env_filter = env_logger.filter();
+    let logger = LOGGER.get_or_init(|| CaptureLogger {
+        env_logger,
+        buffer: LogBuffer::new(LOG_BUFFER_CAPACITY),
+        capture_level: AtomicUsize::new(DEFAULT_CAPTURE_LEVEL as usize),
+    });
+
+    if log::set_logger(logger).is_ok() {
+        log::set_max_level(env_filter.max(DEFAULT_CAPTURE_LEVEL));
+    }
+}
+
+/// 获取最近的 `limit` 条捕获日志
+pub fn recent_logs(limit: usize) -> Vec<LogEntry> {
+    LOGGER
+        .get()
+        .map(|logger| logger.buffer.recent(limit))
+        .unwrap_or_default()
+}
+
+/// 获取当前的日志捕获级别
+pub fn get_level() -> LevelFilter {
+    LOGGER
+        .get()
+        .map(CaptureLogger::capture_level)
+        .unwrap_or(DEFAULT_CAPTURE_LEVEL)
+}
+
+/// 在运行时设置日志捕获级别（如 "debug"、"info"、"off"）
+pub fn set_level(level: &str) -> anyhow::Result<LevelFilter> {
+    let filter =
+        LevelFilter::from_str(level).map_err(|_| anyhow::anyhow!("Invalid log level: {level}"))?;
+    let logger = LOGGER
+        .get()
+        .ok_or_else(|| anyhow::anyhow!("Logger is not initialized"))?;
+
+    logger
+        .capture_level
+        .store(filter as usize, Ordering::Relaxed);
+    log::set_max_level(logger.env_logger.filter().max(filter));
+    log::info!("Log capture level set to {filter}");
+    Ok(filter)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use log::Level;
+
+    fn entry(message: &str) -> LogEntry {
+        LogEntry {
+            timestamp: String::new(),
+            level: Level::Info.to_string(),
+            target: "test".to_string(),
+            message: message.to_string(),
+        }
+    }
+
+    #[test]
+    fn test_log_buffer_is_bounded() {
+        let buffer = LogBuffer::new(3);
+        for i in 0..5 {
+            buffer.push(entry(&i.to_string()));
+        }
+
+        let messages: Vec<_> = buffer
+            .recent(10)
+            .into_iter()
+            .map(|entry| entry.message)
+            .collect();
+        assert_eq!(messages, vec!["2", "3", "4"]);
+
+        // 只取最近的两条，保持时间顺序
+        let messages: Vec<_> = buffer
+            .recent(2)
+            .into_iter()
+            .map(|entry| entry.message)
+            .collect();
+        assert_eq!(messages, vec!["3", "4"]);
+    }
+
+    #[test]
+    fn test_log_buffer_is_thread_safe() {
+        let buffer = std::sync::Arc::new(LogBuffer::new(100));
+        let handles: Vec<_> = (0..4)
+            .map(|thread| {
+                let buffer = buffer.clone();
+                std::thread::spawn(move || {
+                    for i in 0..50 {
+                        buffer.push(entry(&format!("{thread}-{i}")));
+                    }
+                })
+            })
+            .collect();
+        for handle in handles {
+            handle.join().unwrap();
+        }
+
+        assert_eq!(buffer.recent(usize::MAX).len(), 100);
+    }
+
+    #[test]
+    fn test_level_filter_round_trip() {
+        for filter in LevelFilter::iter() {
+            assert_eq!(level_filter_from_usize(filter as usize), filter);
+        }
+    }
+}
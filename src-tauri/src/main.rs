@@ -3,7 +3,10 @@
 
 mod ambient_light;
 mod ambient_light_state;
+mod artnet_sender;
 mod auto_start;
+mod color_vision;
+mod ddp_sender;
 mod display;
 mod frequency_calculator;
 mod http_server;
@@ -14,12 +17,18 @@ mod led_data_sender;
 mod led_preview_state;
 mod led_status_manager;
 mod led_test_effects;
+mod logger;
+mod osc_server;
+mod output_sink;
+mod preview_scaling;
+mod profile_manager;
 mod rpc;
 mod screen_stream;
 mod screenshot;
 mod screenshot_manager;
 mod user_preferences;
 mod volume;
+mod webhook;
 mod websocket_events;
 
 #[cfg(test)]
@@ -296,12 +305,29 @@ async fn handle_menu_event<R: Runtime>(app: &tauri::AppHandle<R>, event: tauri::
             }
         }
         "quit" => {
-            app.exit(0);
+            quit_app(app).await;
         }
         _ => {}
     }
 }
 
+/// 关闭所有LED后退出应用，避免灯带停留在最后一帧
+async fn quit_app<R: Runtime>(app: &tauri::AppHandle<R>) {
+    blackout_leds().await;
+    app.exit(0);
+}
+
+/// 向所有设备同步发送全黑帧（带超时）
+async fn blackout_leds() {
+    let sender = led_data_sender::LedDataSender::global().await;
+    if let Err(e) = sender
+        .flush_blackout(std::time::Duration::from_millis(500))
+        .await
+    {
+        warn!("Failed to turn off LEDs before exit: {}", e);
+    }
+}
+
 async fn handle_tray_event<R: Runtime>(app: &tauri::AppHandle<R>, event: TrayIconEvent) {
     if let TrayIconEvent::Click {
         button: MouseButton::Left,
@@ -432,20 +458,22 @@ fn handle_ambient_light_protocol<R: Runtime>(
             tokio::runtime::Handle::current().block_on(async {
                 let screenshot_manager = ScreenshotManager::global().await;
                 let channels = screenshot_manager.channels.read().await;
+                let scaling_preferences = UserPreferencesManager::global()
+                    .await
+                    .get_preview_scaling()
+                    .await;
 
                 if let Some(rx) = channels.get(&display_id) {
                     let rx = rx.read().await;
                     let screenshot = rx.borrow().clone();
                     let bytes = screenshot.bytes.clone();
 
-                    // Use much smaller intermediate resolution for performance
-                    let intermediate_width = 800; // Much smaller than original 5120
-                    let intermediate_height = 450; // Much smaller than original 2880
-
-                    // Convert BGRA to RGBA format
+                    // Convert BGRA to RGBA format (RGBA captures are used as-is)
                     let mut rgba_bytes = bytes.as_ref().clone();
-                    for chunk in rgba_bytes.chunks_exact_mut(4) {
-                        chunk.swap(0, 2); // Swap B and R channels
+                    if screenshot.capture_format == ambient_light::CaptureFormat::Bgra {
+                        for chunk in rgba_bytes.chunks_exact_mut(4) {
+                            chunk.swap(0, 2); // Swap B and R channels
+                        }
                     }
 
                     let image_result = image::RgbaImage::from_raw(
@@ -455,27 +483,14 @@ fn handle_ambient_light_protocol<R: Runtime>(
                     );
 
                     if let Some(img) = image_result {
-                        // Step 1: Fast downscale to intermediate size
-                        let intermediate_image = image::imageops::resize(
-                            &img,
-                            intermediate_width,
-                            intermediate_height,
-                            image::imageops::FilterType::Nearest, // Fastest possible
+                        // 中间尺寸与滤波器的画质/性能取舍见 `plan_preview_resize`
+                        let final_image = preview_scaling::resize_preview(
+                            img,
+                            width,
+                            height,
+                            &scaling_preferences,
                         );
 
-                        // Step 2: Scale to final target size
-                        let final_image =
-                            if width == intermediate_width && height == intermediate_height {
-                                intermediate_image
-                            } else {
-                                image::imageops::resize(
-                                    &intermediate_image,
-                                    width,
-                                    height,
-                                    image::imageops::FilterType::Triangle,
-                                )
-                            };
-
                         let raw_data = final_image.into_raw();
                         // info!("Efficient resize completed: {}x{}, {} bytes", width, height, raw_data.len());
                         Ok(raw_data)
@@ -522,10 +537,11 @@ fn handle_ambient_light_protocol<R: Runtime>(
 
 #[tokio::main]
 async fn main() {
-    env_logger::init();
+    logger::init();
 
     // 初始化新的稳定显示器ID系统
-    let _config_manager_v2 = ambient_light::ConfigManagerV2::global().await;
+    let config_manager_v2 = ambient_light::ConfigManagerV2::global().await;
+    config_manager_v2.watch_config_file();
 
     // Parse command line arguments
     let args: Vec<String> = std::env::args().collect();
@@ -533,8 +549,13 @@ async fn main() {
     let mut display_id: Option<String> = None;
     let mut headless_mode = false;
     let mut browser_mode = false;
+    let mut http_host: Option<String> = None;
+    let mut http_port: Option<u16> = None;
+    let mut ws_host: Option<String> = None;
+    let mut ws_port: Option<u16> = None;
+    let mut dump_config = false;
 
-    // Look for --page, --display, --headless, --browser, and --test-single-display-config arguments
+    // Look for --page, --display, --headless, --browser, --dump-config, server address and --test-single-display-config arguments
     let mut _test_single_display_config = false;
     for i in 0..args.len() {
         if args[i] == "--page" && i + 1 < args.len() {
@@ -549,11 +570,35 @@ async fn main() {
         } else if args[i] == "--browser" {
             browser_mode = true;
             info!("Command line argument detected: --browser");
+        } else if args[i] == "--http-host" && i + 1 < args.len() {
+            http_host = Some(args[i + 1].clone());
+        } else if args[i] == "--http-port" && i + 1 < args.len() {
+            http_port = Some(parse_port_arg("--http-port", &args[i + 1]));
+        } else if args[i] == "--ws-host" && i + 1 < args.len() {
+            ws_host = Some(args[i + 1].clone());
+        } else if args[i] == "--ws-port" && i + 1 < args.len() {
+            ws_port = Some(parse_port_arg("--ws-port", &args[i + 1]));
+        } else if args[i] == "--dump-config" {
+            dump_config = true;
         } else if args[i] == "--test-single-display-config" {
             _test_single_display_config = true;
         }
     }
 
+    // 只输出生效的配置后退出，不启动服务器和GUI
+    if dump_config {
+        match dump_effective_config().await {
+            Ok(json) => {
+                println!("{json}");
+                return;
+            }
+            Err(e) => {
+                error!("❌ Failed to dump config: {}", e);
+                std::process::exit(1);
+            }
+        }
+    }
+
     // Check environment variables
     if !headless_mode && std::env::var("AMBIENT_LIGHT_HEADLESS").is_ok() {
         headless_mode = true;
@@ -590,14 +635,46 @@ async fn main() {
         }
     }
 
-    // 启动HTTP服务器
+    // 服务器监听地址：命令行参数优先于偏好设置
+    let server_preferences = UserPreferencesManager::global().await.get_server().await;
+    let auth_config = http_server::auth::AuthConfig {
+        token: server_preferences.auth_token.clone(),
+        exempt_loopback: server_preferences.auth_exempt_loopback,
+    };
     let http_config = http_server::ServerConfig {
-        host: "127.0.0.1".to_string(),
-        port: 24101,
+        host: http_host.unwrap_or(server_preferences.http_host),
+        port: http_port.unwrap_or(server_preferences.http_port),
         enable_cors: true,
         serve_static_files: false,
         static_files_path: None,
+        auth: auth_config.clone(),
     };
+    let websocket_config = http_server::WebSocketServerConfig {
+        host: ws_host.unwrap_or(server_preferences.websocket_host),
+        port: ws_port.unwrap_or(server_preferences.websocket_port),
+        auth: auth_config,
+    };
+
+    // 启动前校验监听地址，配置错误时直接退出而不是在后台任务中失败
+    let http_addr = http_config.bind_addr().unwrap_or_else(|e| {
+        error!("❌ Invalid HTTP server address: {}", e);
+        std::process::exit(1);
+    });
+    let websocket_addr = websocket_config.bind_addr().unwrap_or_else(|e| {
+        error!("❌ Invalid WebSocket server address: {}", e);
+        std::process::exit(1);
+    });
+
+    // 发送LED数据的本地网卡地址需在发现设备之前设置，无效时回退为由系统选择
+    let output_preferences = UserPreferencesManager::global().await.get_output().await;
+    if let Err(e) = output_sink::apply_local_bind_address(output_preferences.local_bind_address) {
+        error!(
+            "❌ Invalid local bind address, using the default interface: {}",
+            e
+        );
+    }
+
+    // 启动HTTP服务器
 
     // 在后台启动HTTP服务器
     let _http_server_handle = {
@@ -673,23 +750,62 @@ async fn main() {
                 error!("💡 This indicates a blocking issue in the start() method");
             }
         }
+
+        // 应用输出端（本应用协议、DDP、Art-Net）
+        let output = UserPreferencesManager::global().await.get_output().await;
+        if let Err(e) = led_data_sender::LedDataSender::global()
+            .await
+            .configure_output(&output)
+            .await
+        {
+            warn!("Failed to configure LED output sinks: {}", e);
+        }
+
+        // 恢复上次退出前的发送模式（如手动纯色）
+        if let Err(e) = led_color_publisher.restore_persisted_send_mode().await {
+            warn!("Failed to restore persisted send mode: {}", e);
+        }
+
+        // 发布器就绪后异步执行设备板连通性自检，不阻塞启动
+        tokio::spawn(async move {
+            match UdpRpc::global().await {
+                Ok(udp_rpc) => {
+                    udp_rpc
+                        .run_startup_self_check(std::time::Duration::from_secs(1))
+                        .await;
+                }
+                Err(e) => warn!("Skipping board self-check, UDP RPC unavailable: {}", e),
+            }
+        });
+
+        // 发布器就绪后再开始按定时规则激活情景模式
+        profile_manager::ProfileManager::global()
+            .await
+            .start_scheduler();
     });
 
     // WebSocket server will be started in the Tauri setup hook
 
     let _volume = VolumeManager::global().await;
 
+    // 启动OSC服务器（需在偏好设置中启用）
+    tokio::spawn(async move {
+        if let Err(e) = osc_server::start_osc_server().await {
+            error!("Failed to start OSC server: {}", e);
+        }
+    });
+
     // 如果是无头模式，只运行后端服务，不启动GUI
     if headless_mode {
         info!("🚀 Running in headless mode - HTTP API only");
-        info!("📡 HTTP API server: http://127.0.0.1:24101");
-        info!("🔌 WebSocket server: ws://127.0.0.1:24102");
-        info!("📖 API documentation: http://127.0.0.1:24101/swagger-ui/");
+        info!("📡 HTTP API server: http://{}", http_addr);
+        info!("🔌 WebSocket server: ws://{}", websocket_addr);
+        info!("📖 API documentation: http://{}/swagger-ui/", http_addr);
         info!("💡 Press Ctrl+C to stop the server");
 
         // 启动WebSocket服务器
         tokio::spawn(async move {
-            if let Err(e) = start_websocket_server().await {
+            if let Err(e) = start_websocket_server(websocket_addr, websocket_config.auth).await {
                 error!("Failed to start WebSocket server: {}", e);
             }
         });
@@ -703,15 +819,15 @@ async fn main() {
     // 如果是浏览器模式，启动后端服务（不启动GUI）
     if browser_mode {
         info!("🌐 Running in browser mode - Backend only");
-        info!("� HTTP API server: http://127.0.0.1:24101");
-        info!("🔌 WebSocket server: ws://127.0.0.1:24102");
+        info!("� HTTP API server: http://{}", http_addr);
+        info!("🔌 WebSocket server: ws://{}", websocket_addr);
         info!("🌐 Web interface: Start frontend dev server with 'npm run dev'");
         info!("� Then access http://localhost:24100 in your browser");
         info!("💡 Press Ctrl+C to stop the server");
 
         // 启动WebSocket服务器
         tokio::spawn(async move {
-            if let Err(e) = start_websocket_server().await {
+            if let Err(e) = start_websocket_server(websocket_addr, websocket_config.auth).await {
                 error!("Failed to start WebSocket server: {}", e);
             }
         });
@@ -776,17 +892,19 @@ async fn main() {
                 if let Some(main_window) = app_handle.get_webview_window("main") {
                     let preferences_manager = UserPreferencesManager::global().await;
                     let preferences = preferences_manager.get_preferences().await;
+                    // Clamp hand-edited or corrupted values so the window stays usable
+                    let window_prefs = preferences.window.clamped();
 
                     // Restore window size (using logical pixels to avoid DPI scaling issues)
                     if let Err(e) = main_window.set_size(tauri::Size::Logical(tauri::LogicalSize {
-                        width: preferences.window.width,
-                        height: preferences.window.height,
+                        width: window_prefs.width,
+                        height: window_prefs.height,
                     })) {
                         warn!("Failed to restore window size: {}", e);
                     }
 
                     // Restore window position if available (using logical pixels)
-                    if let (Some(x), Some(y)) = (preferences.window.x, preferences.window.y) {
+                    if let (Some(x), Some(y)) = (window_prefs.x, window_prefs.y) {
                         if let Err(e) = main_window
                             .set_position(tauri::Position::Logical(tauri::LogicalPosition { x, y }))
                         {
@@ -795,7 +913,7 @@ async fn main() {
                     }
 
                     // Restore maximized state
-                    if preferences.window.maximized {
+                    if window_prefs.maximized {
                         if let Err(e) = main_window.maximize() {
                             warn!("Failed to maximize window: {}", e);
                         }
@@ -854,6 +972,16 @@ async fn main() {
                                     }
                                 });
                             }
+                            tauri::WindowEvent::CloseRequested { api, .. } => {
+                                // 先关闭LED再销毁窗口，确保数据包在进程退出前发出
+                                api.prevent_close();
+                                tauri::async_runtime::spawn(async move {
+                                    blackout_leds().await;
+                                    if let Err(e) = window.destroy() {
+                                        warn!("Failed to close window: {}", e);
+                                    }
+                                });
+                            }
                             _ => {}
                         }
                     });
@@ -997,7 +1125,8 @@ async fn main() {
 
             // Start WebSocket server for screen streaming
             tokio::spawn(async move {
-                if let Err(e) = start_websocket_server().await {
+                if let Err(e) = start_websocket_server(websocket_addr, websocket_config.auth).await
+                {
                     error!("Failed to start WebSocket server: {}", e);
                 }
             });
@@ -1036,19 +1165,62 @@ async fn main() {
         .expect("error while running tauri application");
 }
 
+/// 解析命令行端口参数，无效时直接退出
+fn parse_port_arg(flag: &str, value: &str) -> u16 {
+    value.parse().unwrap_or_else(|_| {
+        error!(
+            "❌ Invalid value for {}: '{}' is not a valid port",
+            flag, value
+        );
+        std::process::exit(1);
+    })
+}
+
+/// 将生效的灯带配置、用户偏好和检测到的显示器序列化为 JSON，便于附在问题反馈中
+async fn dump_effective_config() -> anyhow::Result<String> {
+    let config = ambient_light::ConfigManagerV2::global()
+        .await
+        .get_config()
+        .await;
+    let preferences = UserPreferencesManager::global()
+        .await
+        .get_preferences()
+        .await;
+    // 没有连接显示器或检测失败时输出空列表，其余配置照常输出
+    let displays = DisplayInfo::all().unwrap_or_else(|e| {
+        log::warn!("Failed to detect displays: {e}");
+        Vec::new()
+    });
+    let displays: Vec<DisplayInfoWrapper> = displays.iter().map(DisplayInfoWrapper).collect();
+
+    let dump = serde_json::json!({
+        "config": config,
+        "preferences": preferences,
+        "displays": displays,
+    });
+    Ok(serde_json::to_string_pretty(&dump)?)
+}
+
 // WebSocket server for screen streaming
-async fn start_websocket_server() -> anyhow::Result<()> {
+async fn start_websocket_server(
+    addr: std::net::SocketAddr,
+    auth: http_server::auth::AuthConfig,
+) -> anyhow::Result<()> {
     use tokio::net::TcpListener;
 
-    let listener = TcpListener::bind("127.0.0.1:24102").await?;
-    info!("WebSocket server listening on ws://127.0.0.1:24102");
+    http_server::warn_if_exposed("WebSocket server", addr, &auth);
+    let listener = TcpListener::bind(addr)
+        .await
+        .map_err(|e| anyhow::anyhow!("Failed to bind WebSocket server to {}: {}", addr, e))?;
+    info!("WebSocket server listening on ws://{}", addr);
 
     while let Ok((stream, addr)) = listener.accept().await {
         info!("New WebSocket connection from: {}", addr);
 
+        let auth = auth.clone();
         tokio::spawn(async move {
             info!("Starting WebSocket handler for connection from: {}", addr);
-            match screen_stream::handle_websocket_connection(stream).await {
+            match screen_stream::handle_websocket_connection(stream, addr, &auth).await {
                 Ok(_) => {
                     info!("WebSocket connection from {} completed successfully", addr);
                 }
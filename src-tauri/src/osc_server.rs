@@ -0,0 +1,354 @@
+use std::{
+    net::{Ipv4Addr, SocketAddr},
+    time::Duration,
+};
+
+use paris::{error, info, warn};
+use tokio::net::UdpSocket;
+
+use crate::{
+    ambient_light_state::AmbientLightStateManager,
+    display::DisplayManager,
+    rpc::{BoardMessageChannels, DisplaySetting, DisplaySettingRequest},
+    user_preferences::UserPreferencesManager,
+};
+
+const ENABLE_ADDRESS: &str = "/ambient/enable";
+const TOGGLE_ADDRESS: &str = "/ambient/toggle";
+const BRIGHTNESS_ADDRESS: &str = "/ambient/brightness";
+
+/// 接收失败后的初始退避时间
+const RECV_ERROR_BACKOFF_MIN: Duration = Duration::from_millis(100);
+/// 接收失败后的最大退避时间
+const RECV_ERROR_BACKOFF_MAX: Duration = Duration::from_secs(5);
+
+/// 连续第 `consecutive_errors` 次接收失败后的退避时间，按2倍递增直到上限
+fn recv_error_backoff(consecutive_errors: u32) -> Duration {
+    RECV_ERROR_BACKOFF_MIN
+        .saturating_mul(2u32.saturating_pow(consecutive_errors.saturating_sub(1)))
+        .min(RECV_ERROR_BACKOFF_MAX)
+}
+
+/// OSC 消息参数
+#[derive(Debug, Clone, PartialEq)]
+pub enum OscArg {
+    Int(i32),
+    Float(f32),
+    Bool(bool),
+    Str(String),
+}
+
+impl OscArg {
+    fn as_f32(&self) -> Option<f32> {
+        match self {
+            OscArg::Int(value) => Some(*value as f32),
+            OscArg::Float(value) => Some(*value),
+            OscArg::Bool(value) => Some(if *value { 1.0 } else { 0.0 }),
+            OscArg::Str(value) => value.parse().ok(),
+        }
+    }
+}
+
+/// 解析后的 OSC 消息
+#[derive(Debug, Clone, PartialEq)]
+pub struct OscMessage {
+    pub address: String,
+    pub args: Vec<OscArg>,
+}
+
+/// OSC 消息对应的控制命令
+#[derive(Debug, Clone, PartialEq)]
+pub enum OscCommand {
+    SetEnabled(bool),
+    Toggle,
+    /// 设置显示器亮度（百分比），`display_index` 为 None 时作用于所有显示器
+    Brightness {
+        display_index: Option<usize>,
+        value: u8,
+    },
+}
+
+/// 读取以 NUL 结尾并按 4 字节对齐的 OSC 字符串
+fn read_osc_string(buf: &[u8], offset: &mut usize) -> anyhow::Result<String> {
+    let rest = buf
+        .get(*offset..)
+        .ok_or_else(|| anyhow::anyhow!("OSC string offset out of range"))?;
+    let len = rest
+        .iter()
+        .position(|b| *b == 0)
+        .ok_or_else(|| anyhow::anyhow!("OSC string is not NUL terminated"))?;
+    let value = std::str::from_utf8(&rest[..len])?.to_string();
+
+    *offset += (len + 4) & !3;
+    if *offset > buf.len() {
+        return Err(anyhow::anyhow!("OSC string padding out of range"));
+    }
+
+    Ok(value)
+}
+
+fn read_osc_u32(buf: &[u8], offset: &mut usize) -> anyhow::Result<u32> {
+    let bytes = buf
+        .get(*offset..*offset + 4)
+        .ok_or_else(|| anyhow::anyhow!("OSC argument truncated"))?;
+    *offset += 4;
+    Ok(u32::from_be_bytes([bytes[0], bytes[1], bytes[2], bytes[3]]))
+}
+
+/// 解析单条 OSC 1.0 消息（不支持 bundle）
+pub fn parse_osc_message(buf: &[u8]) -> anyhow::Result<OscMessage> {
+    let mut offset = 0;
+    let address = read_osc_string(buf, &mut offset)?;
+    if !address.starts_with('/') {
+        return Err(anyhow::anyhow!("Invalid OSC address: {:?}", address));
+    }
+
+    // 旧版实现可能省略类型标签，视为无参数
+    if offset >= buf.len() {
+        return Ok(OscMessage {
+            address,
+            args: vec![],
+        });
+    }
+
+    let type_tags = read_osc_string(buf, &mut offset)?;
+    let type_tags = type_tags
+        .strip_prefix(',')
+        .ok_or_else(|| anyhow::anyhow!("Invalid OSC type tag string: {:?}", type_tags))?;
+
+    let mut args = Vec::with_capacity(type_tags.len());
+    for tag in type_tags.chars() {
+        let arg = match tag {
+            'i' => OscArg::Int(read_osc_u32(buf, &mut offset)? as i32),
+            'f' => OscArg::Float(f32::from_bits(read_osc_u32(buf, &mut offset)?)),
+            's' => OscArg::Str(read_osc_string(buf, &mut offset)?),
+            'T' => OscArg::Bool(true),
+            'F' => OscArg::Bool(false),
+            _ => return Err(anyhow::anyhow!("Unsupported OSC type tag: {}", tag)),
+        };
+        args.push(arg);
+    }
+
+    Ok(OscMessage { address, args })
+}
+
+impl OscCommand {
+    /// 将 OSC 消息映射为控制命令，无法识别的消息返回 None
+    pub fn from_message(message: &OscMessage) -> Option<Self> {
+        let first_arg = message.args.first();
+
+        match message.address.as_str() {
+            ENABLE_ADDRESS => {
+                let value = first_arg?.as_f32()?;
+                Some(OscCommand::SetEnabled(value >= 0.5))
+            }
+            // 按钮按下（1）触发切换，松开（0）忽略；无参数时直接切换
+            TOGGLE_ADDRESS => match first_arg.and_then(OscArg::as_f32) {
+                Some(value) if value < 0.5 => None,
+                _ => Some(OscCommand::Toggle),
+            },
+            address => {
+                let display_index = match address.strip_prefix(BRIGHTNESS_ADDRESS)? {
+                    "" => None,
+                    suffix => Some(suffix.strip_prefix('/')?.parse::<usize>().ok()?),
+                };
+
+                // 浮点数按 0.0-1.0 归一化值处理（推子控件），整数按百分比处理
+                let value = match first_arg? {
+                    OscArg::Float(value) if value.is_finite() => {
+                        (value.clamp(0.0, 1.0) * 100.0).round() as u8
+                    }
+                    OscArg::Int(value) => (*value).clamp(0, 100) as u8,
+                    _ => return None,
+                };
+
+                Some(OscCommand::Brightness {
+                    display_index,
+                    value,
+                })
+            }
+        }
+    }
+
+    async fn execute(self) -> anyhow::Result<()> {
+        match self {
+            OscCommand::SetEnabled(enabled) => {
+                AmbientLightStateManager::global()
+                    .await
+                    .set_enabled(enabled)
+                    .await
+            }
+            OscCommand::Toggle => AmbientLightStateManager::global()
+                .await
+                .toggle()
+                .await
+                .map(|_| ()),
+            OscCommand::Brightness {
+                display_index,
+                value,
+            } => {
+                let display_indices: Vec<usize> = match display_index {
+                    Some(index) => vec![index],
+                    None => {
+                        let count = DisplayManager::global().await.get_displays().await.len();
+                        (0..count).collect()
+                    }
+                };
+
+                let sender = &BoardMessageChannels::global()
+                    .await
+                    .display_setting_request_sender;
+                for display_index in display_indices {
+                    sender
+                        .send(DisplaySettingRequest {
+                            display_index,
+                            setting: DisplaySetting::Brightness(value),
+                        })
+                        .map_err(|e| anyhow::anyhow!("Failed to send brightness request: {}", e))?;
+                }
+
+                Ok(())
+            }
+        }
+    }
+}
+
+/// 根据用户偏好设置启动 OSC 服务器（默认关闭）
+pub async fn start_osc_server() -> anyhow::Result<()> {
+    let preferences = UserPreferencesManager::global()
+        .await
+        .get_preferences()
+        .await;
+    if !preferences.osc.enabled {
+        info!("OSC server disabled in preferences");
+        return Ok(());
+    }
+
+    let addr = SocketAddr::from((Ipv4Addr::UNSPECIFIED, preferences.osc.port));
+    let socket = UdpSocket::bind(addr).await?;
+    info!("🎛️ OSC server listening on udp://{}", addr);
+
+    let mut buf = [0u8; 1024];
+    let mut consecutive_errors = 0u32;
+    loop {
+        let (len, peer) = match socket.recv_from(&mut buf).await {
+            Ok(result) => {
+                consecutive_errors = 0;
+                result
+            }
+            Err(e) => {
+                // 套接字持续出错时避免空转占满CPU
+                consecutive_errors = consecutive_errors.saturating_add(1);
+                let backoff = recv_error_backoff(consecutive_errors);
+                error!(
+                    "OSC server receive failed: {}, retrying in {:?}",
+                    e, backoff
+                );
+                tokio::time::sleep(backoff).await;
+                continue;
+            }
+        };
+
+        let message = match parse_osc_message(&buf[..len]) {
+            Ok(message) => message,
+            Err(e) => {
+                warn!("Ignoring malformed OSC packet from {}: {}", peer, e);
+                continue;
+            }
+        };
+
+        let Some(command) = OscCommand::from_message(&message) else {
+            log::debug!("Ignoring unhandled OSC message: {:?}", message);
+            continue;
+        };
+
+        log::info!("OSC command from {}: {:?}", peer, command);
+        if let Err(e) = command.execute().await {
+            error!("Failed to execute OSC command: {}", e);
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_recv_error_backoff_grows_and_caps() {
+        assert_eq!(recv_error_backoff(1), RECV_ERROR_BACKOFF_MIN);
+        assert_eq!(recv_error_backoff(2), RECV_ERROR_BACKOFF_MIN * 2);
+        assert_eq!(recv_error_backoff(3), RECV_ERROR_BACKOFF_MIN * 4);
+        assert_eq!(recv_error_backoff(100), RECV_ERROR_BACKOFF_MAX);
+    }
+
+    fn pad(bytes: &mut Vec<u8>, value: &str) {
+        bytes.extend_from_slice(value.as_bytes());
+        bytes.push(0);
+        while bytes.len() % 4 != 0 {
+            bytes.push(0);
+        }
+    }
+
+    fn encode(address: &str, tags: &str, payload: &[u8]) -> Vec<u8> {
+        let mut bytes = Vec::new();
+        pad(&mut bytes, address);
+        pad(&mut bytes, tags);
+        bytes.extend_from_slice(payload);
+        bytes
+    }
+
+    #[test]
+    fn test_parse_float_message() {
+        let packet = encode(BRIGHTNESS_ADDRESS, ",f", &0.5f32.to_be_bytes());
+        let message = parse_osc_message(&packet).unwrap();
+
+        assert_eq!(message.address, BRIGHTNESS_ADDRESS);
+        assert_eq!(message.args, vec![OscArg::Float(0.5)]);
+        assert_eq!(
+            OscCommand::from_message(&message),
+            Some(OscCommand::Brightness {
+                display_index: None,
+                value: 50
+            })
+        );
+    }
+
+    #[test]
+    fn test_parse_display_brightness_and_enable() {
+        let packet = encode("/ambient/brightness/1", ",i", &150i32.to_be_bytes());
+        let message = parse_osc_message(&packet).unwrap();
+        assert_eq!(
+            OscCommand::from_message(&message),
+            Some(OscCommand::Brightness {
+                display_index: Some(1),
+                value: 100
+            })
+        );
+
+        let message = parse_osc_message(&encode(ENABLE_ADDRESS, ",F", &[])).unwrap();
+        assert_eq!(
+            OscCommand::from_message(&message),
+            Some(OscCommand::SetEnabled(false))
+        );
+
+        let message = parse_osc_message(&encode(TOGGLE_ADDRESS, ",f", &0f32.to_be_bytes()));
+        assert_eq!(OscCommand::from_message(&message.unwrap()), None);
+    }
+
+    #[test]
+    fn test_malformed_packets_are_rejected() {
+        // 缺少 NUL 结尾
+        assert!(parse_osc_message(b"/ambient/enable").is_err());
+        // 参数被截断
+        let packet = encode(ENABLE_ADDRESS, ",i", &[0, 0]);
+        assert!(parse_osc_message(&packet).is_err());
+        // 未知类型标签
+        let packet = encode(ENABLE_ADDRESS, ",x", &[]);
+        assert!(parse_osc_message(&packet).is_err());
+        // 非法地址
+        assert!(parse_osc_message(&encode("ambient", ",", &[])).is_err());
+        // 未知地址和错误子路径
+        let message = parse_osc_message(&encode("/ambient/brightnessx", ",i", &[0; 4])).unwrap();
+        assert_eq!(OscCommand::from_message(&message), None);
+    }
+}
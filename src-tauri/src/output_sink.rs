@@ -0,0 +1,452 @@
+use std::collections::HashSet;
+use std::net::{IpAddr, Ipv4Addr, Ipv6Addr, SocketAddr};
+use std::sync::RwLock;
+
+use serde::{Deserialize, Serialize};
+use tokio::net::UdpSocket;
+
+use crate::ambient_light::{ColorOrder, LedStripConfigV2, LedType};
+use crate::artnet_sender::{parse_artnet_target, ArtNetSender, ARTNET_MAX_UNIVERSE};
+use crate::ddp_sender::{parse_ddp_target, DdpSender};
+use crate::led_data_sender::OutputTransport;
+
+/// 硬件数据中一条灯带的字节位置和编码
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct SinkStripLayout {
+    pub byte_start: usize,
+    pub len: usize,
+    pub led_type: LedType,
+    pub color_order: ColorOrder,
+}
+
+impl SinkStripLayout {
+    fn byte_end(&self) -> usize {
+        self.byte_start + self.len * self.led_type.bytes_per_led()
+    }
+}
+
+/// 整条串联的硬件数据布局，供网络输出端按灯带转换颜色顺序和判断整帧结束
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub struct SinkFrameLayout {
+    strips: Vec<SinkStripLayout>,
+}
+
+impl SinkFrameLayout {
+    /// 按串联顺序（考虑 `hardware_offset`）排布灯带，与硬件编码一致
+    pub fn from_strips(strips: &[LedStripConfigV2]) -> Self {
+        let mut sorted_strips: Vec<_> = strips.iter().collect();
+        sorted_strips.sort_by_key(|strip| strip.chain_order());
+
+        let mut next_byte_offset = 0;
+        let strips = sorted_strips
+            .into_iter()
+            .map(|strip| {
+                let layout = SinkStripLayout {
+                    byte_start: strip
+                        .hardware_offset
+                        .map(|offset| offset * strip.led_type.bytes_per_led())
+                        .unwrap_or(next_byte_offset),
+                    len: strip.len,
+                    led_type: strip.led_type,
+                    color_order: strip.color_order,
+                };
+                next_byte_offset = layout.byte_end();
+                layout
+            })
+            .collect();
+        Self { strips }
+    }
+
+    /// 整帧硬件数据的字节数，未配置灯带时为0
+    pub fn frame_len(&self) -> usize {
+        self.strips
+            .iter()
+            .map(SinkStripLayout::byte_end)
+            .max()
+            .unwrap_or(0)
+    }
+}
+
+/// 输出端的颜色通道顺序
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Serialize, Deserialize, utoipa::ToSchema)]
+pub enum SinkColorOrder {
+    /// 与本应用硬件编码一致（各灯带配置的颜色顺序），原样发送
+    #[default]
+    Grb,
+    /// 按各灯带的LED类型和颜色顺序重新编码为RGB/RGBW（WLED等通用控制器通常使用此顺序）
+    Rgb,
+}
+
+impl SinkColorOrder {
+    /// 将从 `start_offset` 开始的硬件编码数据转换为输出端的通道顺序
+    ///
+    /// 按 `layout` 中各灯带的编码逐个LED转换，不在任何灯带内的字节原样保留；
+    /// 未配置灯带时按 `rgbw` 的固定步长交换红绿通道
+    pub fn apply<'a>(
+        &self,
+        data: &'a [u8],
+        start_offset: usize,
+        layout: &SinkFrameLayout,
+        rgbw: bool,
+    ) -> std::borrow::Cow<'a, [u8]> {
+        match self {
+            SinkColorOrder::Grb => std::borrow::Cow::Borrowed(data),
+            SinkColorOrder::Rgb if layout.strips.is_empty() => {
+                let stride = if rgbw { 4 } else { 3 };
+                let mut data = data.to_vec();
+                for pixel in data.chunks_exact_mut(stride) {
+                    pixel.swap(0, 1);
+                }
+                std::borrow::Cow::Owned(data)
+            }
+            SinkColorOrder::Rgb => {
+                let mut data = data.to_vec();
+                let end = start_offset + data.len();
+                let rgb_order = ColorOrder {
+                    rgb: crate::ambient_light::RgbOrder::Rgb,
+                    white: crate::ambient_light::WhitePosition::Trailing,
+                };
+                for strip in &layout.strips {
+                    let bytes_per_led = strip.led_type.bytes_per_led();
+                    for led in 0..strip.len {
+                        let led_start = strip.byte_start + led * bytes_per_led;
+                        if led_start < start_offset || led_start + bytes_per_led > end {
+                            continue;
+                        }
+                        let bytes = &mut data[led_start - start_offset..][..bytes_per_led];
+                        let (rgb, white) = strip.color_order.decode(bytes, strip.led_type);
+                        let mut encoded = Vec::with_capacity(bytes_per_led);
+                        rgb_order.encode_into(rgb, white, &mut encoded);
+                        bytes.copy_from_slice(&encoded);
+                    }
+                }
+                std::borrow::Cow::Owned(data)
+            }
+        }
+    }
+}
+
+/// 单个输出端配置
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize, utoipa::ToSchema)]
+pub struct OutputSinkConfig {
+    /// 输出端ID，添加时自动生成
+    #[serde(default)]
+    pub id: String,
+    pub transport: OutputTransport,
+    /// 目标地址（`IP` 或 `IP:端口`），本应用协议不需要
+    #[serde(default)]
+    pub target: String,
+    /// 数据按RGBW（每像素4字节）处理
+    #[serde(default)]
+    pub rgbw: bool,
+    /// 颜色通道顺序，本应用协议始终使用硬件编码
+    #[serde(default)]
+    pub color_order: SinkColorOrder,
+    /// Art-Net起始universe
+    #[serde(default)]
+    pub universe: u16,
+}
+
+impl OutputSinkConfig {
+    /// 本应用协议输出端，发送到通过mDNS发现的设备
+    pub fn native() -> Self {
+        Self {
+            id: "native".to_string(),
+            transport: OutputTransport::Native,
+            target: String::new(),
+            rgbw: false,
+            color_order: SinkColorOrder::Grb,
+            universe: 0,
+        }
+    }
+}
+
+/// 校验输出端配置：ID唯一、目标地址有效、本应用协议最多一个
+pub fn validate_output_sinks(sinks: &[OutputSinkConfig]) -> anyhow::Result<()> {
+    let mut ids = HashSet::new();
+    let mut has_native = false;
+
+    for sink in sinks {
+        if sink.id.is_empty() {
+            anyhow::bail!("Output sink id must not be empty");
+        }
+        if !ids.insert(sink.id.as_str()) {
+            anyhow::bail!("Duplicate output sink id '{}'", sink.id);
+        }
+        match sink.transport {
+            OutputTransport::Native => {
+                if has_native {
+                    anyhow::bail!("Only one native output sink is allowed");
+                }
+                has_native = true;
+            }
+            OutputTransport::Ddp => {
+                parse_ddp_target(&sink.target)?;
+            }
+            OutputTransport::ArtNet => {
+                parse_artnet_target(&sink.target)?;
+                if sink.universe > ARTNET_MAX_UNIVERSE {
+                    anyhow::bail!(
+                        "Art-Net universe {} exceeds the maximum of {ARTNET_MAX_UNIVERSE}",
+                        sink.universe
+                    );
+                }
+            }
+        }
+    }
+    Ok(())
+}
+
+/// 发送LED数据的UDP套接字绑定的本地地址，为空时由系统选择出口网卡
+static LOCAL_BIND_ADDRESS: RwLock<Option<IpAddr>> = RwLock::new(None);
+
+/// 校验本地绑定地址：必须是本机某个网卡上的地址（尝试绑定一个临时端口）
+pub fn validate_local_bind_address(address: Option<IpAddr>) -> anyhow::Result<()> {
+    if let Some(address) = address {
+        std::net::UdpSocket::bind((address, 0)).map_err(|e| {
+            anyhow::anyhow!("Local bind address {address} is not usable on this machine: {e}")
+        })?;
+    }
+    Ok(())
+}
+
+/// 校验并设置发送套接字的本地绑定地址，对之后创建的套接字生效
+///
+/// 地址无效时回退为由系统选择并返回错误
+pub fn apply_local_bind_address(address: Option<IpAddr>) -> anyhow::Result<()> {
+    let result = validate_local_bind_address(address);
+    let address = if result.is_ok() { address } else { None };
+    *LOCAL_BIND_ADDRESS
+        .write()
+        .unwrap_or_else(|e| e.into_inner()) = address;
+    result
+}
+
+/// 发往 `target` 的套接字应绑定的本地地址（端口由系统分配）
+///
+/// 配置的地址与目标地址族不一致时使用对应地址族的未指定地址
+fn local_bind_addr_for(target: SocketAddr, local: Option<IpAddr>) -> SocketAddr {
+    let ip = match (local, target) {
+        (Some(local), target) if local.is_ipv4() == target.is_ipv4() => local,
+        (_, SocketAddr::V4(_)) => IpAddr::V4(Ipv4Addr::UNSPECIFIED),
+        (_, SocketAddr::V6(_)) => IpAddr::V6(Ipv6Addr::UNSPECIFIED),
+    };
+    SocketAddr::new(ip, 0)
+}
+
+/// 创建发往 `target` 的UDP套接字，按配置绑定本地网卡
+pub async fn bind_udp_socket(target: SocketAddr) -> std::io::Result<UdpSocket> {
+    let local = *LOCAL_BIND_ADDRESS.read().unwrap_or_else(|e| e.into_inner());
+    UdpSocket::bind(local_bind_addr_for(target, local)).await
+}
+
+#[derive(Debug)]
+enum SinkTransport {
+    Native,
+    Ddp(DdpSender),
+    ArtNet(ArtNetSender),
+}
+
+/// 已连接的输出端
+#[derive(Debug)]
+pub struct OutputSink {
+    config: OutputSinkConfig,
+    transport: SinkTransport,
+}
+
+impl OutputSink {
+    /// 本应用协议输出端（默认输出端）
+    pub fn native() -> Self {
+        Self {
+            config: OutputSinkConfig::native(),
+            transport: SinkTransport::Native,
+        }
+    }
+
+    /// 按配置创建输出端（网络输出会绑定本地UDP套接字）
+    pub async fn connect(config: OutputSinkConfig) -> anyhow::Result<Self> {
+        let transport = match config.transport {
+            OutputTransport::Native => SinkTransport::Native,
+            OutputTransport::Ddp => {
+                let target = parse_ddp_target(&config.target)?;
+                SinkTransport::Ddp(DdpSender::new(target, config.rgbw).await?)
+            }
+            OutputTransport::ArtNet => {
+                let target = parse_artnet_target(&config.target)?;
+                SinkTransport::ArtNet(
+                    ArtNetSender::new(target, config.universe, config.rgbw).await?,
+                )
+            }
+        };
+        Ok(Self { config, transport })
+    }
+
+    pub fn config(&self) -> &OutputSinkConfig {
+        &self.config
+    }
+
+    /// 是否为本应用协议（由 `LedDataSender` 负责拆包和按设备发送）
+    pub fn is_native(&self) -> bool {
+        matches!(self.transport, SinkTransport::Native)
+    }
+
+    /// 用于日志的描述
+    pub fn describe(&self) -> String {
+        match &self.transport {
+            SinkTransport::Native => format!("{} (native)", self.config.id),
+            SinkTransport::Ddp(sender) => {
+                format!("{} (DDP -> {})", self.config.id, sender.target())
+            }
+            SinkTransport::ArtNet(sender) => {
+                format!("{} (Art-Net -> {})", self.config.id, sender.target())
+            }
+        }
+    }
+
+    /// 向网络输出端发送一帧（或一个显示器的一段）硬件编码数据，返回发送的数据包数量
+    pub async fn send_frame(
+        &self,
+        start_offset: u32,
+        data: &[u8],
+        layout: &SinkFrameLayout,
+    ) -> anyhow::Result<usize> {
+        let data =
+            self.config
+                .color_order
+                .apply(data, start_offset as usize, layout, self.config.rgbw);
+        match &self.transport {
+            SinkTransport::Native => Err(anyhow::anyhow!(
+                "Native output sink frames are sent by LedDataSender"
+            )),
+            SinkTransport::Ddp(sender) => {
+                sender
+                    .send_frame(start_offset, &data, layout.frame_len())
+                    .await
+            }
+            SinkTransport::ArtNet(sender) => sender.send_frame(start_offset as usize, &data).await,
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn sink(id: &str, transport: OutputTransport, target: &str) -> OutputSinkConfig {
+        OutputSinkConfig {
+            id: id.to_string(),
+            transport,
+            target: target.to_string(),
+            ..OutputSinkConfig::native()
+        }
+    }
+
+    #[test]
+    fn test_color_order_swaps_red_and_green_per_pixel_without_layout() {
+        let layout = SinkFrameLayout::default();
+        let grb = [1, 2, 3, 4, 5, 6];
+        assert_eq!(&*SinkColorOrder::Grb.apply(&grb, 0, &layout, false), &grb);
+        assert_eq!(
+            &*SinkColorOrder::Rgb.apply(&grb, 0, &layout, false),
+            &[2, 1, 3, 5, 4, 6]
+        );
+
+        let grbw = [1, 2, 3, 4, 5, 6, 7, 8];
+        assert_eq!(
+            &*SinkColorOrder::Rgb.apply(&grbw, 0, &layout, true),
+            &[2, 1, 3, 4, 6, 5, 7, 8]
+        );
+    }
+
+    #[test]
+    fn test_color_order_follows_each_strip_encoding() {
+        use crate::ambient_light::{RgbOrder, WhitePosition};
+
+        let strip = |index, len, led_type, color_order| LedStripConfigV2 {
+            len,
+            led_type,
+            color_order,
+            ..LedStripConfigV2::default_for_display("display".to_string(), index)
+        };
+        // 一条GRB的WS2812B灯带，后接一条白色通道在前、BGR顺序的SK6812灯带
+        let layout = SinkFrameLayout::from_strips(&[
+            strip(0, 1, LedType::WS2812B, ColorOrder::default()),
+            strip(
+                1,
+                1,
+                LedType::SK6812,
+                ColorOrder {
+                    rgb: RgbOrder::Bgr,
+                    white: WhitePosition::Leading,
+                },
+            ),
+        ]);
+        assert_eq!(layout.frame_len(), 7);
+
+        // R=10 G=20 B=30，白色通道 40
+        let hardware = [20, 10, 30, 40, 30, 20, 10];
+        assert_eq!(
+            &*SinkColorOrder::Rgb.apply(&hardware, 0, &layout, false),
+            &[10, 20, 30, 10, 20, 30, 40]
+        );
+
+        // 只发送第二条灯带时按其在整帧中的位置转换
+        assert_eq!(
+            &*SinkColorOrder::Rgb.apply(&hardware[3..], 3, &layout, false),
+            &[10, 20, 30, 40]
+        );
+    }
+
+    #[test]
+    fn test_validate_output_sinks() {
+        assert!(validate_output_sinks(&[
+            OutputSinkConfig::native(),
+            sink("wled", OutputTransport::Ddp, "192.168.1.50"),
+            sink("stage", OutputTransport::ArtNet, "10.0.0.2:6454"),
+        ])
+        .is_ok());
+        // 空列表表示不输出
+        assert!(validate_output_sinks(&[]).is_ok());
+
+        assert!(validate_output_sinks(&[
+            sink("a", OutputTransport::Ddp, "192.168.1.50"),
+            sink("a", OutputTransport::Ddp, "192.168.1.51"),
+        ])
+        .is_err());
+        assert!(validate_output_sinks(&[
+            OutputSinkConfig::native(),
+            sink("native-2", OutputTransport::Native, ""),
+        ])
+        .is_err());
+        assert!(
+            validate_output_sinks(&[sink("wled", OutputTransport::Ddp, "wled.local")]).is_err()
+        );
+    }
+
+    #[test]
+    fn test_local_bind_address() {
+        let v4_target: SocketAddr = "192.168.1.50:4048".parse().unwrap();
+        let v6_target: SocketAddr = "[fd00::50]:4048".parse().unwrap();
+        let lan: IpAddr = "192.168.1.10".parse().unwrap();
+
+        // 未配置时保持原来的未指定地址
+        assert_eq!(
+            local_bind_addr_for(v4_target, None),
+            "0.0.0.0:0".parse().unwrap()
+        );
+        assert_eq!(
+            local_bind_addr_for(v4_target, Some(lan)),
+            "192.168.1.10:0".parse().unwrap()
+        );
+        // 地址族不一致时不使用配置的地址
+        assert_eq!(
+            local_bind_addr_for(v6_target, Some(lan)),
+            "[::]:0".parse().unwrap()
+        );
+
+        assert!(validate_local_bind_address(None).is_ok());
+        assert!(validate_local_bind_address(Some(Ipv4Addr::LOCALHOST.into())).is_ok());
+        // 文档保留地址（TEST-NET-1）不属于本机网卡
+        assert!(validate_local_bind_address(Some("192.0.2.1".parse().unwrap())).is_err());
+    }
+}
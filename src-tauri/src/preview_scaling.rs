@@ -0,0 +1,207 @@
+use image::imageops::FilterType;
+use image::RgbaImage;
+
+use crate::user_preferences::PreviewScalingPreferences;
+
+/// 中间尺寸允许的最大边长
+pub const MAX_PREVIEW_INTERMEDIATE_SIZE: u32 = 7680;
+
+/// 预览缩放滤波器，从快到慢、从粗糙到平滑排列
+#[derive(
+    Debug, Clone, Copy, PartialEq, Eq, serde::Serialize, serde::Deserialize, utoipa::ToSchema,
+)]
+pub enum PreviewFilter {
+    /// 最近邻，最快，缩小倍数大时有锯齿和闪烁
+    Nearest,
+    /// 线性插值，开销适中
+    Triangle,
+    /// 三次插值
+    CatmullRom,
+    /// 高斯模糊，偏柔和
+    Gaussian,
+    /// Lanczos 窗口 3，最清晰也最慢
+    Lanczos3,
+}
+
+impl From<PreviewFilter> for FilterType {
+    fn from(filter: PreviewFilter) -> Self {
+        match filter {
+            PreviewFilter::Nearest => FilterType::Nearest,
+            PreviewFilter::Triangle => FilterType::Triangle,
+            PreviewFilter::CatmullRom => FilterType::CatmullRom,
+            PreviewFilter::Gaussian => FilterType::Gaussian,
+            PreviewFilter::Lanczos3 => FilterType::Lanczos3,
+        }
+    }
+}
+
+/// 预览图缩放步骤
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum PreviewResizePlan {
+    /// 原图即为目标尺寸，无需缩放
+    Unchanged,
+    /// 直接缩放到目标尺寸
+    Direct { filter: PreviewFilter },
+    /// 先用快速滤波器缩小到中间尺寸，再用平滑滤波器缩放到目标尺寸
+    TwoStep {
+        intermediate_width: u32,
+        intermediate_height: u32,
+        intermediate_filter: PreviewFilter,
+        final_filter: PreviewFilter,
+    },
+}
+
+/// 校验预览缩放配置
+pub fn validate_preview_scaling(preferences: &PreviewScalingPreferences) -> anyhow::Result<()> {
+    if preferences.intermediate_width > MAX_PREVIEW_INTERMEDIATE_SIZE
+        || preferences.intermediate_height > MAX_PREVIEW_INTERMEDIATE_SIZE
+    {
+        anyhow::bail!(
+            "Intermediate size {}x{} exceeds the maximum of {MAX_PREVIEW_INTERMEDIATE_SIZE}",
+            preferences.intermediate_width,
+            preferences.intermediate_height
+        );
+    }
+    Ok(())
+}
+
+/// 决定预览图的缩放步骤
+///
+/// 对大分辨率截图直接用平滑滤波器缩放很慢（滤波器核随缩小倍数变大），
+/// 两步缩放先用最近邻快速丢弃大部分像素，再在小图上做平滑缩放：
+/// 中间尺寸越大、中间滤波器越平滑，画质越好，开销也越大。
+///
+/// 只有目标尺寸小于中间尺寸且中间尺寸小于原图时两步缩放才有意义；
+/// 否则中间步骤要么丢失目标需要的细节（如 1080p 预览），要么是多余的
+/// （原图已经很小），此时直接用最终滤波器缩放一次。
+/// 中间宽或高为 0 时关闭两步缩放。
+pub fn plan_preview_resize(
+    source_width: u32,
+    source_height: u32,
+    width: u32,
+    height: u32,
+    preferences: &PreviewScalingPreferences,
+) -> PreviewResizePlan {
+    if source_width == width && source_height == height {
+        return PreviewResizePlan::Unchanged;
+    }
+
+    let intermediate_width = preferences.intermediate_width;
+    let intermediate_height = preferences.intermediate_height;
+    let use_intermediate = intermediate_width > 0
+        && intermediate_height > 0
+        && width < intermediate_width
+        && height < intermediate_height
+        && intermediate_width < source_width
+        && intermediate_height < source_height;
+
+    if use_intermediate {
+        PreviewResizePlan::TwoStep {
+            intermediate_width,
+            intermediate_height,
+            intermediate_filter: preferences.intermediate_filter,
+            final_filter: preferences.final_filter,
+        }
+    } else {
+        PreviewResizePlan::Direct {
+            filter: preferences.final_filter,
+        }
+    }
+}
+
+/// 按预览缩放配置将截图缩放到目标尺寸
+pub fn resize_preview(
+    image: RgbaImage,
+    width: u32,
+    height: u32,
+    preferences: &PreviewScalingPreferences,
+) -> RgbaImage {
+    match plan_preview_resize(image.width(), image.height(), width, height, preferences) {
+        PreviewResizePlan::Unchanged => image,
+        PreviewResizePlan::Direct { filter } => {
+            image::imageops::resize(&image, width, height, filter.into())
+        }
+        PreviewResizePlan::TwoStep {
+            intermediate_width,
+            intermediate_height,
+            intermediate_filter,
+            final_filter,
+        } => {
+            let intermediate = image::imageops::resize(
+                &image,
+                intermediate_width,
+                intermediate_height,
+                intermediate_filter.into(),
+            );
+            image::imageops::resize(&intermediate, width, height, final_filter.into())
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_default_plan_uses_two_steps_for_small_preview() {
+        let preferences = PreviewScalingPreferences::default();
+        assert_eq!(
+            plan_preview_resize(5120, 2880, 400, 225, &preferences),
+            PreviewResizePlan::TwoStep {
+                intermediate_width: 800,
+                intermediate_height: 450,
+                intermediate_filter: PreviewFilter::Nearest,
+                final_filter: PreviewFilter::Triangle,
+            }
+        );
+    }
+
+    #[test]
+    fn test_plan_skips_intermediate_step_when_not_useful() {
+        let preferences = PreviewScalingPreferences::default();
+        let direct = PreviewResizePlan::Direct {
+            filter: PreviewFilter::Triangle,
+        };
+
+        // 目标大于中间尺寸，中间步骤会丢失细节
+        assert_eq!(
+            plan_preview_resize(3840, 2160, 1920, 1080, &preferences),
+            direct
+        );
+        // 原图不大于中间尺寸
+        assert_eq!(
+            plan_preview_resize(800, 450, 400, 225, &preferences),
+            direct
+        );
+        // 关闭中间步骤
+        let disabled = PreviewScalingPreferences {
+            intermediate_width: 0,
+            ..preferences.clone()
+        };
+        assert_eq!(plan_preview_resize(5120, 2880, 400, 225, &disabled), direct);
+        // 已是目标尺寸
+        assert_eq!(
+            plan_preview_resize(400, 225, 400, 225, &preferences),
+            PreviewResizePlan::Unchanged
+        );
+    }
+
+    #[test]
+    fn test_resize_preview_output_size() {
+        let preferences = PreviewScalingPreferences::default();
+        let image = RgbaImage::from_pixel(1600, 900, image::Rgba([10, 20, 30, 255]));
+
+        let resized = resize_preview(image, 400, 225, &preferences);
+        assert_eq!(resized.dimensions(), (400, 225));
+        assert_eq!(resized.get_pixel(200, 100).0, [10, 20, 30, 255]);
+    }
+
+    #[test]
+    fn test_validate_preview_scaling() {
+        let mut preferences = PreviewScalingPreferences::default();
+        assert!(validate_preview_scaling(&preferences).is_ok());
+
+        preferences.intermediate_width = MAX_PREVIEW_INTERMEDIATE_SIZE + 1;
+        assert!(validate_preview_scaling(&preferences).is_err());
+    }
+}
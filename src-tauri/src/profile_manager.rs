@@ -0,0 +1,463 @@
+use std::collections::BTreeMap;
+use std::path::PathBuf;
+use std::time::Duration;
+
+use chrono::{Datelike, Timelike};
+use dirs::config_dir;
+use serde::{Deserialize, Serialize};
+use tokio::sync::{OnceCell, RwLock};
+use utoipa::ToSchema;
+
+use crate::ambient_light::{ColorCalibration, ConfigManagerV2, LedColorsPublisher};
+use crate::ambient_light_state::AmbientLightStateManager;
+
+const CONFIG_FILE_NAME: &str = "cc.ivanli.ambient_light/profiles.toml";
+
+/// 情景模式名称的最大字符数
+const MAX_PROFILE_NAME_LEN: usize = 64;
+
+/// 命名的情景模式：激活时恢复保存时的颜色校准和环境光开关状态
+#[derive(Debug, Clone, Copy, Serialize, Deserialize, PartialEq, ToSchema)]
+pub struct Profile {
+    /// 颜色校准
+    pub color_calibration: ColorCalibration,
+    /// 环境光是否开启
+    pub ambient_light_enabled: bool,
+}
+
+/// 定时激活情景模式的规则，在指定的星期和本地时间触发
+#[derive(Debug, Clone, Serialize, Deserialize, PartialEq, ToSchema)]
+pub struct ProfileSchedule {
+    /// 规则ID，由创建接口分配，创建和更新时请求中的值被忽略
+    #[serde(default)]
+    pub id: u32,
+    /// 要激活的情景模式名称
+    pub profile: String,
+    /// 触发时间：小时 (0-23，本地时间)
+    pub hour: u8,
+    /// 触发时间：分钟 (0-59)
+    pub minute: u8,
+    /// 生效的星期（1 为周一，7 为周日），为空时每天生效
+    #[serde(default)]
+    pub days: Vec<u8>,
+    /// 优先级，同一分钟内有多条规则触发时只执行优先级最高的一条
+    #[serde(default)]
+    pub priority: i32,
+    /// 是否启用
+    #[serde(default = "default_schedule_enabled")]
+    pub enabled: bool,
+}
+
+fn default_schedule_enabled() -> bool {
+    true
+}
+
+impl ProfileSchedule {
+    /// 校验触发时间和星期的取值范围
+    pub fn validate(&self) -> anyhow::Result<()> {
+        if self.hour > 23 || self.minute > 59 {
+            anyhow::bail!(
+                "schedule time must be between 00:00 and 23:59, got {:02}:{:02}",
+                self.hour,
+                self.minute
+            );
+        }
+        if let Some(day) = self.days.iter().find(|day| !(1..=7).contains(*day)) {
+            anyhow::bail!("schedule days must be between 1 (Monday) and 7 (Sunday), got {day}");
+        }
+        Ok(())
+    }
+
+    /// 规则是否在指定的星期（1 为周一）和时间触发
+    pub fn is_due(&self, weekday: u8, hour: u8, minute: u8) -> bool {
+        self.enabled
+            && self.hour == hour
+            && self.minute == minute
+            && (self.days.is_empty() || self.days.contains(&weekday))
+    }
+}
+
+/// 选出在指定时间触发的规则：多条规则同时触发时取优先级最高的一条，优先级相同时取ID最小的
+pub fn due_schedule(
+    schedules: &[ProfileSchedule],
+    weekday: u8,
+    hour: u8,
+    minute: u8,
+) -> Option<&ProfileSchedule> {
+    schedules
+        .iter()
+        .filter(|schedule| schedule.is_due(weekday, hour, minute))
+        .max_by(|a, b| a.priority.cmp(&b.priority).then(b.id.cmp(&a.id)))
+}
+
+/// 校验情景模式名称：去掉首尾空白后非空，且不超过 `MAX_PROFILE_NAME_LEN` 个字符
+pub fn validate_profile_name(name: &str) -> anyhow::Result<()> {
+    if name.trim().is_empty() || name.trim() != name {
+        anyhow::bail!(
+            "profile name must be non-empty without surrounding whitespace, got {name:?}"
+        );
+    }
+    if name.chars().count() > MAX_PROFILE_NAME_LEN {
+        anyhow::bail!("profile name must be at most {MAX_PROFILE_NAME_LEN} characters");
+    }
+    Ok(())
+}
+
+/// 情景模式激活事件，手动激活时 `schedule_id` 为空
+#[derive(Debug, Clone, Serialize, ToSchema)]
+pub struct ProfileActivation {
+    /// 情景模式名称
+    pub profile: String,
+    /// 触发激活的定时规则ID
+    pub schedule_id: Option<u32>,
+    /// 激活时应用的设置
+    pub settings: Profile,
+}
+
+/// 保存到文件的情景模式和定时规则
+#[derive(Debug, Clone, Default, Serialize, Deserialize, PartialEq)]
+pub struct ProfileStore {
+    #[serde(default)]
+    pub profiles: BTreeMap<String, Profile>,
+    #[serde(default)]
+    pub schedules: Vec<ProfileSchedule>,
+}
+
+impl ProfileStore {
+    fn get_config_path() -> anyhow::Result<PathBuf> {
+        let config_dir =
+            config_dir().ok_or_else(|| anyhow::anyhow!("Failed to get config directory"))?;
+        Ok(config_dir.join(CONFIG_FILE_NAME))
+    }
+
+    /// 读取配置文件，文件不存在时返回空的存储
+    pub async fn read_config() -> anyhow::Result<Self> {
+        let config_path = Self::get_config_path()?;
+        if !config_path.exists() {
+            return Ok(Self::default());
+        }
+
+        let content = tokio::fs::read_to_string(&config_path).await?;
+        Ok(toml::from_str(&content)?)
+    }
+
+    /// 写入配置文件
+    pub async fn write_config(&self) -> anyhow::Result<()> {
+        let config_path = Self::get_config_path()?;
+        if let Some(parent) = config_path.parent() {
+            tokio::fs::create_dir_all(parent).await?;
+        }
+
+        tokio::fs::write(&config_path, toml::to_string_pretty(self)?).await?;
+        Ok(())
+    }
+
+    /// 校验定时规则，引用的情景模式必须已存在
+    pub fn validate_schedule(&self, schedule: &ProfileSchedule) -> anyhow::Result<()> {
+        schedule.validate()?;
+        if !self.profiles.contains_key(&schedule.profile) {
+            anyhow::bail!("profile {:?} does not exist", schedule.profile);
+        }
+        Ok(())
+    }
+
+    /// 添加定时规则并分配ID
+    pub fn add_schedule(&mut self, mut schedule: ProfileSchedule) -> ProfileSchedule {
+        schedule.id = self
+            .schedules
+            .iter()
+            .map(|schedule| schedule.id)
+            .max()
+            .map_or(1, |id| id + 1);
+        self.schedules.push(schedule.clone());
+        schedule
+    }
+
+    /// 替换指定ID的定时规则，规则不存在时返回 None
+    pub fn replace_schedule(
+        &mut self,
+        id: u32,
+        mut schedule: ProfileSchedule,
+    ) -> Option<ProfileSchedule> {
+        let existing = self
+            .schedules
+            .iter_mut()
+            .find(|schedule| schedule.id == id)?;
+        schedule.id = id;
+        *existing = schedule.clone();
+        Some(schedule)
+    }
+
+    /// 删除指定ID的定时规则，返回规则是否存在
+    pub fn remove_schedule(&mut self, id: u32) -> bool {
+        let initial_len = self.schedules.len();
+        self.schedules.retain(|schedule| schedule.id != id);
+        self.schedules.len() < initial_len
+    }
+}
+
+/// 情景模式管理器：保存命名的情景模式，并按定时规则自动激活
+pub struct ProfileManager {
+    store: RwLock<ProfileStore>,
+}
+
+impl ProfileManager {
+    /// 获取全局实例
+    pub async fn global() -> &'static Self {
+        static PROFILE_MANAGER: OnceCell<ProfileManager> = OnceCell::const_new();
+
+        PROFILE_MANAGER
+            .get_or_init(|| async {
+                let store = match ProfileStore::read_config().await {
+                    Ok(store) => store,
+                    Err(e) => {
+                        log::warn!("Failed to read profiles config: {e}, using empty profiles");
+                        ProfileStore::default()
+                    }
+                };
+
+                Self {
+                    store: RwLock::new(store),
+                }
+            })
+            .await
+    }
+
+    /// 获取所有情景模式
+    pub async fn get_profiles(&self) -> BTreeMap<String, Profile> {
+        self.store.read().await.profiles.clone()
+    }
+
+    /// 将当前的颜色校准和环境光开关状态保存为命名的情景模式，同名情景模式被覆盖
+    pub async fn save_current_profile(&self, name: &str) -> anyhow::Result<Profile> {
+        validate_profile_name(name)?;
+        let profile = Profile {
+            color_calibration: ConfigManagerV2::global()
+                .await
+                .get_config()
+                .await
+                .color_calibration,
+            ambient_light_enabled: AmbientLightStateManager::global().await.is_enabled().await,
+        };
+
+        let mut store = self.store.write().await;
+        store.profiles.insert(name.to_string(), profile);
+        store.write_config().await?;
+        Ok(profile)
+    }
+
+    /// 删除情景模式，返回情景模式是否存在；引用它的定时规则保留，触发时跳过
+    pub async fn delete_profile(&self, name: &str) -> anyhow::Result<bool> {
+        let mut store = self.store.write().await;
+        if store.profiles.remove(name).is_none() {
+            return Ok(false);
+        }
+        store.write_config().await?;
+        Ok(true)
+    }
+
+    /// 激活情景模式：应用颜色校准和环境光开关状态，并广播激活事件；情景模式不存在时返回 None
+    pub async fn activate_profile(
+        &self,
+        name: &str,
+        schedule_id: Option<u32>,
+    ) -> anyhow::Result<Option<ProfileActivation>> {
+        let Some(profile) = self.store.read().await.profiles.get(name).copied() else {
+            return Ok(None);
+        };
+
+        log::info!("🎬 Activating profile {name:?} (schedule: {schedule_id:?})");
+        let config_manager = ConfigManagerV2::global().await;
+        config_manager
+            .update_color_calibration(profile.color_calibration)
+            .await?;
+        // 不等待防抖窗口，保证情景模式在下一帧生效
+        config_manager.flush_config_update().await;
+        LedColorsPublisher::global()
+            .await
+            .clear_preview_calibration()
+            .await;
+
+        let ambient_light = AmbientLightStateManager::global().await;
+        if ambient_light.is_enabled().await != profile.ambient_light_enabled {
+            ambient_light
+                .set_enabled(profile.ambient_light_enabled)
+                .await?;
+        }
+
+        let activation = ProfileActivation {
+            profile: name.to_string(),
+            schedule_id,
+            settings: profile,
+        };
+        crate::websocket_events::publish_profile_activated(&activation).await;
+        Ok(Some(activation))
+    }
+
+    /// 获取所有定时规则
+    pub async fn get_schedules(&self) -> Vec<ProfileSchedule> {
+        self.store.read().await.schedules.clone()
+    }
+
+    /// 校验定时规则，引用的情景模式必须已存在
+    pub async fn validate_schedule(&self, schedule: &ProfileSchedule) -> anyhow::Result<()> {
+        self.store.read().await.validate_schedule(schedule)
+    }
+
+    /// 创建定时规则，返回分配了ID的规则
+    pub async fn create_schedule(
+        &self,
+        schedule: ProfileSchedule,
+    ) -> anyhow::Result<ProfileSchedule> {
+        let mut store = self.store.write().await;
+        store.validate_schedule(&schedule)?;
+        let schedule = store.add_schedule(schedule);
+        store.write_config().await?;
+        Ok(schedule)
+    }
+
+    /// 更新定时规则，规则不存在时返回 None
+    pub async fn update_schedule(
+        &self,
+        id: u32,
+        schedule: ProfileSchedule,
+    ) -> anyhow::Result<Option<ProfileSchedule>> {
+        let mut store = self.store.write().await;
+        store.validate_schedule(&schedule)?;
+        let Some(schedule) = store.replace_schedule(id, schedule) else {
+            return Ok(None);
+        };
+        store.write_config().await?;
+        Ok(Some(schedule))
+    }
+
+    /// 删除定时规则，返回规则是否存在
+    pub async fn delete_schedule(&self, id: u32) -> anyhow::Result<bool> {
+        let mut store = self.store.write().await;
+        if !store.remove_schedule(id) {
+            return Ok(false);
+        }
+        store.write_config().await?;
+        Ok(true)
+    }
+
+    /// 启动定时任务：每分钟开始时检查一次规则，激活当前触发的情景模式
+    pub fn start_scheduler(&'static self) {
+        tokio::spawn(async move {
+            loop {
+                let now = chrono::Local::now();
+                let elapsed = Duration::new(now.second() as u64, now.nanosecond().min(999_999_999));
+                tokio::time::sleep(Duration::from_secs(60).saturating_sub(elapsed)).await;
+
+                let now = chrono::Local::now();
+                let schedule = due_schedule(
+                    &self.store.read().await.schedules,
+                    now.weekday().number_from_monday() as u8,
+                    now.hour() as u8,
+                    now.minute() as u8,
+                )
+                .cloned();
+                let Some(schedule) = schedule else {
+                    continue;
+                };
+
+                match self
+                    .activate_profile(&schedule.profile, Some(schedule.id))
+                    .await
+                {
+                    Ok(Some(_)) => {}
+                    Ok(None) => log::warn!(
+                        "Schedule {} refers to missing profile {:?}, skipped",
+                        schedule.id,
+                        schedule.profile
+                    ),
+                    Err(e) => log::error!(
+                        "Failed to activate profile {:?} for schedule {}: {e}",
+                        schedule.profile,
+                        schedule.id
+                    ),
+                }
+            }
+        });
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn schedule(id: u32, profile: &str, priority: i32) -> ProfileSchedule {
+        ProfileSchedule {
+            id,
+            profile: profile.to_string(),
+            hour: 20,
+            minute: 0,
+            days: Vec::new(),
+            priority,
+            enabled: true,
+        }
+    }
+
+    #[test]
+    fn test_due_schedule_resolves_overlaps_by_priority() {
+        let mut weekend_movie = schedule(1, "movie", 5);
+        weekend_movie.days = vec![6, 7];
+        let default = schedule(2, "default", 1);
+        let mut disabled = schedule(3, "disabled", 10);
+        disabled.enabled = false;
+        let schedules = vec![weekend_movie, default, disabled];
+
+        // 周六两条规则同时触发，优先级高的生效；禁用的规则不参与
+        assert_eq!(due_schedule(&schedules, 6, 20, 0).unwrap().profile, "movie");
+        // 周一只有每天生效的规则触发
+        assert_eq!(
+            due_schedule(&schedules, 1, 20, 0).unwrap().profile,
+            "default"
+        );
+        assert!(due_schedule(&schedules, 6, 20, 1).is_none());
+
+        // 优先级相同时取ID最小的规则
+        let tied = vec![schedule(4, "later", 0), schedule(2, "earlier", 0)];
+        assert_eq!(due_schedule(&tied, 1, 20, 0).unwrap().profile, "earlier");
+    }
+
+    #[test]
+    fn test_schedule_store_crud_and_validation() {
+        let mut store = ProfileStore::default();
+        let mut rule = schedule(0, "movie", 0);
+        assert!(store.validate_schedule(&rule).is_err());
+
+        store.profiles.insert(
+            "movie".to_string(),
+            Profile {
+                color_calibration: ColorCalibration::new(),
+                ambient_light_enabled: true,
+            },
+        );
+        store.validate_schedule(&rule).unwrap();
+        rule.minute = 60;
+        assert!(store.validate_schedule(&rule).is_err());
+        rule.minute = 0;
+        rule.days = vec![0];
+        assert!(store.validate_schedule(&rule).is_err());
+        rule.days = vec![5];
+
+        assert_eq!(store.add_schedule(rule.clone()).id, 1);
+        assert_eq!(store.add_schedule(rule.clone()).id, 2);
+
+        rule.hour = 8;
+        assert_eq!(store.replace_schedule(2, rule.clone()).unwrap().hour, 8);
+        assert!(store.replace_schedule(9, rule).is_none());
+        assert!(store.remove_schedule(1));
+        assert!(!store.remove_schedule(1));
+        assert_eq!(store.schedules.len(), 1);
+
+        // 定时规则随配置文件保存
+        let content = toml::to_string_pretty(&store).unwrap();
+        assert_eq!(toml::from_str::<ProfileStore>(&content).unwrap(), store);
+
+        assert!(validate_profile_name(" ").is_err());
+        assert!(validate_profile_name(&"x".repeat(65)).is_err());
+        validate_profile_name("movie").unwrap();
+    }
+}
@@ -1,19 +1,114 @@
 use std::{
     net::{IpAddr, SocketAddr},
     sync::Arc,
-    time::Duration,
+    time::{Duration, Instant},
 };
 
 use paris::{error, info, warn};
+use serde::Serialize;
 use tokio::{io, net::UdpSocket, sync::RwLock, task::yield_now, time::timeout};
 
-use crate::{ambient_light::ConfigManager, rpc::DisplaySettingRequest, volume::VolumeManager};
+use crate::{
+    ambient_light::ConfigManager,
+    led_data_sender::{LedDataPacket, PacketHeader},
+    output_sink::bind_udp_socket,
+    rpc::DisplaySettingRequest,
+    volume::VolumeManager,
+};
+
+use super::{BoardConfig, BoardConnectStatus, BoardInfo, BoardMessageChannels};
+
+/// 健康检查发送的测试数据：不含颜色数据的LED数据包，固件接受但不改变任何LED
+const HEALTH_PROBE_DATA: [u8; 0] = [];
+
+/// 设备板健康检查结果
+#[derive(Debug, Clone, PartialEq, Serialize)]
+pub struct BoardHealth {
+    /// 设备板地址（IP:端口）
+    pub address: String,
+    /// 可达性检测是否将设备标记为在线
+    pub online: bool,
+    /// 测试数据包是否成功写入套接字
+    pub data_sent: bool,
+    /// 设备是否回应了 ping（固件不确认LED数据，以 ping 回应作为收到数据的依据）
+    pub responded: bool,
+    /// 往返延迟（毫秒），未收到回应时为空
+    pub latency_ms: Option<f64>,
+    /// 数据写入成功且设备有回应
+    pub healthy: bool,
+}
+
+impl BoardHealth {
+    /// 未能完成探测的设备：未写入数据也没有回应
+    pub fn unreachable(address: String, online: bool) -> Self {
+        Self {
+            address,
+            online,
+            data_sent: false,
+            responded: false,
+            latency_ms: None,
+            healthy: false,
+        }
+    }
+}
+
+/// 执行健康检查所需的设备信息，可在释放设备列表锁后独立完成探测
+#[derive(Debug, Clone)]
+pub struct BoardProbe {
+    info: Arc<RwLock<BoardInfo>>,
+    port: Option<u16>,
+    header: PacketHeader,
+}
 
-use super::{BoardConnectStatus, BoardInfo, BoardMessageChannels};
+impl BoardProbe {
+    /// 健康检查：发送一个不改变LED的数据包，再发送 ping 并等待回应以测量往返延迟
+    ///
+    /// 与 `Board::check` 不同，不会修改设备的连接状态
+    pub async fn health_check(&self, wait: Duration) -> anyhow::Result<BoardHealth> {
+        let info = self.info.read().await;
+        let address = SocketAddr::new(IpAddr::V4(info.address), self.port.unwrap_or(info.port));
+        let online = info.connect_status == BoardConnectStatus::Connected;
+        drop(info);
+
+        let socket = bind_udp_socket(address).await?;
+        socket.connect(address).await?;
+
+        let packet = LedDataPacket::new(0, HEALTH_PROBE_DATA.to_vec(), "Health".to_string());
+        let data_sent = match socket
+            .send(&packet.build_packet_with_header(self.header))
+            .await
+        {
+            Ok(_) => true,
+            Err(err) => {
+                warn!("health probe send to {} failed: {}", address, err);
+                false
+            }
+        };
+
+        let instant = Instant::now();
+        socket.send(&[1]).await?;
+        let mut buf = [0u8; 1];
+        let responded =
+            matches!(timeout(wait, socket.recv(&mut buf)).await, Ok(Ok(_))) && buf == [1];
+        let latency_ms = responded.then(|| instant.elapsed().as_secs_f64() * 1000.0);
+
+        Ok(BoardHealth {
+            address: address.to_string(),
+            online,
+            data_sent,
+            responded,
+            latency_ms,
+            healthy: data_sent && responded,
+        })
+    }
+}
 
 #[derive(Debug)]
 pub struct Board {
     pub info: Arc<RwLock<BoardInfo>>,
+    config: BoardConfig,
+    /// 预热结束时间，在此之前丢弃LED数据（设备上线时根据 `warmup_ms` 设置）
+    warmup_until: Arc<RwLock<Option<Instant>>>,
     socket: Option<Arc<UdpSocket>>,
     listen_handler: Option<tokio::task::JoinHandle<()>>,
     volume_changed_subscriber_handler: Option<tokio::task::JoinHandle<()>>,
@@ -22,9 +117,11 @@ pub struct Board {
 }
 
 impl Board {
-    pub fn new(info: BoardInfo) -> Self {
+    pub fn new(info: BoardInfo, config: BoardConfig) -> Self {
         Self {
             info: Arc::new(RwLock::new(info)),
+            config,
+            warmup_until: Arc::new(RwLock::new(None)),
             socket: None,
             listen_handler: None,
             volume_changed_subscriber_handler: None,
@@ -33,17 +130,36 @@ impl Board {
         }
     }
 
+    /// 实际使用的UDP端口（优先使用覆盖配置）
+    fn target_port(&self, info: &BoardInfo) -> u16 {
+        self.config.port.unwrap_or(info.port)
+    }
+
+    /// 向该设备发送LED数据的最小间隔
+    pub fn min_send_interval(&self) -> Duration {
+        Duration::from_millis(self.config.min_send_interval_ms)
+    }
+
+    /// 接在该设备上的灯带序列号，为空时视为驱动全部灯带
+    pub fn strips(&self) -> &[usize] {
+        &self.config.strips
+    }
+
     pub fn get_socket_addr(&self) -> Option<SocketAddr> {
         let info = self.info.try_read().ok()?;
-        Some(SocketAddr::new(IpAddr::V4(info.address), info.port))
+        Some(SocketAddr::new(
+            IpAddr::V4(info.address),
+            self.target_port(&info),
+        ))
     }
 
     pub async fn init_socket(&mut self) -> anyhow::Result<()> {
         let info = self.info.clone();
         let info = info.read().await;
-        let socket = UdpSocket::bind("0.0.0.0:0").await?;
+        let address = SocketAddr::new(IpAddr::V4(info.address), self.target_port(&info));
+        let socket = bind_udp_socket(address).await?;
 
-        socket.connect((info.address, info.port)).await?;
+        socket.connect(address).await?;
         let socket = Arc::new(socket);
         self.socket = Some(socket.clone());
 
@@ -258,6 +374,11 @@ impl Board {
             return;
         }
 
+        if self.is_warming_up().await {
+            log::debug!("Board {}: warming up, skipping color send", info.host);
+            return;
+        }
+
         let socket = self.socket.as_ref().unwrap();
         log::debug!("Sending {} bytes to board {}", buf.len(), info.host);
 
@@ -275,11 +396,40 @@ impl Board {
         }
     }
 
+    /// 该设备配置的数据包头
+    pub fn packet_header(&self) -> PacketHeader {
+        self.config.packet_header()
+    }
+
+    /// 按该设备配置的包头编码并发送LED数据包
+    pub async fn send_packet(&self, packet: &LedDataPacket) {
+        let buf = packet.build_packet_with_header(self.packet_header());
+        self.send_colors(&buf).await;
+    }
+
+    /// 设备是否仍处于上线后的预热期
+    pub async fn is_warming_up(&self) -> bool {
+        self.warmup_until
+            .read()
+            .await
+            .is_some_and(|until| Instant::now() < until)
+    }
+
+    /// 健康检查所需的设备信息
+    pub fn probe(&self) -> BoardProbe {
+        BoardProbe {
+            info: self.info.clone(),
+            port: self.config.port,
+            header: self.packet_header(),
+        }
+    }
+
     pub async fn check(&self) -> anyhow::Result<()> {
         let info = self.info.read().await;
-        let socket = UdpSocket::bind("0.0.0.0:0").await?;
-        socket.connect((info.address, info.port)).await?;
+        let address = SocketAddr::new(IpAddr::V4(info.address), self.target_port(&info));
         drop(info);
+        let socket = bind_udp_socket(address).await?;
+        socket.connect(address).await?;
 
         let instant = std::time::Instant::now();
 
@@ -293,6 +443,14 @@ impl Board {
             Ok(_) => {
                 let ttl = instant.elapsed();
                 if buf == [1] {
+                    // 设备从离线变为在线时开始预热，避免冷启动时发送的数据被丢失或显示异常
+                    if info.connect_status != BoardConnectStatus::Connected
+                        && self.config.warmup_ms > 0
+                    {
+                        let warmup = Duration::from_millis(self.config.warmup_ms);
+                        *self.warmup_until.write().await = Some(Instant::now() + warmup);
+                        info!("board {} warming up for {:?}", info.host, warmup);
+                    }
                     info.connect_status = BoardConnectStatus::Connected;
                 } else if let BoardConnectStatus::Connecting(retry) = info.connect_status {
                     if retry < 10 {
@@ -351,3 +509,44 @@ impl Drop for Board {
         }
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use std::net::Ipv4Addr;
+
+    use super::*;
+
+    #[tokio::test]
+    async fn test_health_check_sends_probe_and_measures_round_trip() {
+        let device = UdpSocket::bind("127.0.0.1:0").await.unwrap();
+        let port = device.local_addr().unwrap().port();
+        let responder = tokio::spawn(async move {
+            let mut buf = [0u8; 64];
+            let (len, _) = device.recv_from(&mut buf).await.unwrap();
+            let probe = buf[..len].to_vec();
+            let (len, peer) = device.recv_from(&mut buf).await.unwrap();
+            assert_eq!(&buf[..len], &[1]);
+            device.send_to(&[1], peer).await.unwrap();
+            probe
+        });
+
+        let info = BoardInfo::new(
+            "test._ambient_light._udp.local.".to_string(),
+            "test".to_string(),
+            Ipv4Addr::LOCALHOST,
+            port,
+        );
+        let board = Board::new(info, BoardConfig::default());
+        let health = board
+            .probe()
+            .health_check(Duration::from_secs(1))
+            .await
+            .unwrap();
+
+        // 探测包不含颜色数据，不会改变任何LED
+        assert_eq!(responder.await.unwrap(), vec![0x02, 0, 0]);
+        assert!(health.data_sent && health.responded && health.healthy);
+        assert!(health.latency_ms.is_some());
+        assert!(!health.online);
+    }
+}
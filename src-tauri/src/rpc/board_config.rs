@@ -0,0 +1,273 @@
+use std::{collections::HashMap, path::PathBuf, sync::Arc};
+
+use dirs::config_dir;
+use paris::warn;
+use serde::{Deserialize, Serialize};
+use tokio::sync::{OnceCell, RwLock};
+
+use crate::led_data_sender::{PacketHeader, DEFAULT_PROTOCOL_VERSION, SUPPORTED_PROTOCOL_VERSIONS};
+
+const CONFIG_FILE_NAME: &str = "cc.ivanli.ambient_light/board_config.toml";
+
+/// 心跳（Ping/Pong）包的命令字节，数据包命令字节不能与之冲突
+const PING_COMMAND: u8 = 0x01;
+/// 预热时间上限（毫秒）
+pub const MAX_WARMUP_MS: u64 = 60_000;
+/// 最小发送间隔上限（毫秒）
+pub const MAX_MIN_SEND_INTERVAL_MS: u64 = 1_000;
+
+fn default_protocol_version() -> u8 {
+    DEFAULT_PROTOCOL_VERSION
+}
+
+/// 单个设备板的覆盖配置
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct BoardConfig {
+    /// UDP端口覆盖（None时使用mDNS公布的端口）
+    #[serde(default)]
+    pub port: Option<u16>,
+    /// LED数据包协议版本，目前只支持版本2（0x02帧格式）
+    #[serde(default = "default_protocol_version")]
+    pub protocol_version: u8,
+    /// 设备上线（首次响应心跳）后的预热时间（毫秒），期间丢弃LED数据，0表示不预热
+    #[serde(default)]
+    pub warmup_ms: u64,
+    /// 向该设备发送LED数据的最小间隔（毫秒），间隔内到达的帧被丢弃，0表示不限制
+    #[serde(default)]
+    pub min_send_interval_ms: u64,
+    /// 数据包命令字节覆盖（None时使用协议版本的默认值，版本2为0x02），用于社区固件变体
+    #[serde(default)]
+    pub command_byte: Option<u8>,
+    /// 紧跟命令字节的通道/灯带选择字节（None时不插入）
+    #[serde(default)]
+    pub channel: Option<u8>,
+    /// 接在该设备上的灯带序列号，为空时视为该设备驱动全部灯带
+    #[serde(default)]
+    pub strips: Vec<usize>,
+}
+
+impl Default for BoardConfig {
+    fn default() -> Self {
+        Self {
+            port: None,
+            protocol_version: DEFAULT_PROTOCOL_VERSION,
+            warmup_ms: 0,
+            min_send_interval_ms: 0,
+            command_byte: None,
+            channel: None,
+            strips: Vec::new(),
+        }
+    }
+}
+
+impl BoardConfig {
+    /// 校验配置是否有效
+    pub fn validate(&self) -> anyhow::Result<()> {
+        if !SUPPORTED_PROTOCOL_VERSIONS.contains(&self.protocol_version) {
+            return Err(anyhow::anyhow!(
+                "Unsupported protocol version {}, supported: {:?}",
+                self.protocol_version,
+                SUPPORTED_PROTOCOL_VERSIONS
+            ));
+        }
+
+        // 0x01 是心跳（Ping/Pong）命令，LED数据包不能使用
+        if self.command_byte == Some(PING_COMMAND) {
+            return Err(anyhow::anyhow!(
+                "Command byte 0x{PING_COMMAND:02x} is reserved for the ping packet"
+            ));
+        }
+
+        if self.port == Some(0) {
+            return Err(anyhow::anyhow!("Port override must not be 0"));
+        }
+
+        if self.warmup_ms > MAX_WARMUP_MS {
+            return Err(anyhow::anyhow!(
+                "Warmup {}ms exceeds the maximum of {}ms",
+                self.warmup_ms,
+                MAX_WARMUP_MS
+            ));
+        }
+
+        if self.min_send_interval_ms > MAX_MIN_SEND_INTERVAL_MS {
+            return Err(anyhow::anyhow!(
+                "Minimum send interval {}ms exceeds the maximum of {}ms",
+                self.min_send_interval_ms,
+                MAX_MIN_SEND_INTERVAL_MS
+            ));
+        }
+
+        Ok(())
+    }
+
+    /// 该设备数据包头的命令字节与通道字节
+    pub fn packet_header(&self) -> PacketHeader {
+        PacketHeader {
+            command: self.command_byte,
+            channel: self.channel,
+        }
+    }
+}
+
+/// 所有设备板的覆盖配置（按设备全名索引）
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+pub struct BoardConfigGroup {
+    #[serde(default)]
+    pub boards: HashMap<String, BoardConfig>,
+}
+
+impl BoardConfigGroup {
+    fn get_config_path() -> anyhow::Result<PathBuf> {
+        let config_dir =
+            config_dir().ok_or_else(|| anyhow::anyhow!("Failed to get config directory"))?;
+        Ok(config_dir.join(CONFIG_FILE_NAME))
+    }
+
+    pub async fn read_config() -> anyhow::Result<Self> {
+        let config_path = Self::get_config_path()?;
+
+        if !config_path.exists() {
+            return Ok(Self::default());
+        }
+
+        let content = tokio::fs::read_to_string(&config_path).await?;
+        let config: Self = toml::from_str(&content)?;
+        Ok(config)
+    }
+
+    pub async fn write_config(&self) -> anyhow::Result<()> {
+        let config_path = Self::get_config_path()?;
+
+        if let Some(parent) = config_path.parent() {
+            tokio::fs::create_dir_all(parent).await?;
+        }
+
+        let content = toml::to_string_pretty(self)?;
+        tokio::fs::write(&config_path, content).await?;
+        Ok(())
+    }
+}
+
+pub struct BoardConfigManager {
+    config: Arc<RwLock<BoardConfigGroup>>,
+}
+
+impl BoardConfigManager {
+    pub async fn global() -> &'static Self {
+        static BOARD_CONFIG_MANAGER: OnceCell<BoardConfigManager> = OnceCell::const_new();
+
+        BOARD_CONFIG_MANAGER
+            .get_or_init(|| async {
+                let config = match BoardConfigGroup::read_config().await {
+                    Ok(config) => config,
+                    Err(e) => {
+                        warn!("Failed to read board config: {}, using default", e);
+                        BoardConfigGroup::default()
+                    }
+                };
+
+                Self {
+                    config: Arc::new(RwLock::new(config)),
+                }
+            })
+            .await
+    }
+
+    /// 获取所有设备板的覆盖配置
+    pub async fn get_config(&self) -> BoardConfigGroup {
+        self.config.read().await.clone()
+    }
+
+    /// 获取指定设备板的配置（未配置时返回默认值）
+    pub async fn get_board_config(&self, fullname: &str) -> BoardConfig {
+        self.config
+            .read()
+            .await
+            .boards
+            .get(fullname)
+            .cloned()
+            .unwrap_or_default()
+    }
+
+    /// 设置指定设备板的配置并持久化
+    pub async fn set_board_config(
+        &self,
+        fullname: &str,
+        config: BoardConfig,
+    ) -> anyhow::Result<()> {
+        config.validate()?;
+
+        let current = {
+            let mut group = self.config.write().await;
+            if config == BoardConfig::default() {
+                group.boards.remove(fullname);
+            } else {
+                group.boards.insert(fullname.to_string(), config);
+            }
+            group.clone()
+        };
+
+        current.write_config().await
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_board_config_defaults_when_fields_missing() {
+        let group: BoardConfigGroup = toml::from_str(
+            r#"
+            [boards."board-a._ambient_light._udp.local."]
+            port = 23042
+            "#,
+        )
+        .unwrap();
+
+        let config = &group.boards["board-a._ambient_light._udp.local."];
+        assert_eq!(config.port, Some(23042));
+        assert_eq!(config.protocol_version, DEFAULT_PROTOCOL_VERSION);
+        assert_eq!(config.warmup_ms, 0);
+        assert_eq!(config.min_send_interval_ms, 0);
+        assert_eq!(config.packet_header(), PacketHeader::default());
+        assert!(config.strips.is_empty());
+    }
+
+    #[test]
+    fn test_board_config_rejects_unknown_protocol() {
+        let config = BoardConfig {
+            protocol_version: 99,
+            ..BoardConfig::default()
+        };
+        assert!(config.validate().is_err());
+        assert!(BoardConfig::default().validate().is_ok());
+    }
+
+    #[test]
+    fn test_board_config_rejects_ping_command_byte() {
+        let config = |command_byte| BoardConfig {
+            command_byte,
+            ..BoardConfig::default()
+        };
+        assert!(config(Some(0x01)).validate().is_err());
+        assert!(config(Some(0x03)).validate().is_ok());
+        assert!(config(None).validate().is_ok());
+    }
+
+    #[test]
+    fn test_board_config_limits_warmup() {
+        let config = BoardConfig {
+            warmup_ms: MAX_WARMUP_MS,
+            ..BoardConfig::default()
+        };
+        assert!(config.validate().is_ok());
+
+        let config = BoardConfig {
+            warmup_ms: MAX_WARMUP_MS + 1,
+            ..BoardConfig::default()
+        };
+        assert!(config.validate().is_err());
+    }
+}
@@ -1,7 +1,22 @@
-use std::net::Ipv4Addr;
+use std::{collections::BTreeMap, net::Ipv4Addr};
 
 use serde::{Deserialize, Serialize};
 
+/// 温度变化超过该值（摄氏度）才视为需要通知的变化，避免读数抖动频繁推送设备列表
+pub const TEMPERATURE_CHANGE_THRESHOLD: f32 = 1.0;
+
+const FIRMWARE_VERSION_KEYS: [&str; 3] = ["fw", "firmware", "version"];
+const TEMPERATURE_KEYS: [&str; 2] = ["temp", "temperature"];
+
+/// 设备广播的 mDNS 服务类型
+pub const BOARD_SERVICE_TYPE: &str = "_ambient_light._udp.local.";
+/// 服务实例名和主机名的最大长度（DNS 名称上限）
+const MAX_ANNOUNCEMENT_NAME_LEN: usize = 255;
+/// 单个 TXT 键值对（`key=value`）的最大长度（DNS TXT 字符串上限）
+const MAX_TXT_ENTRY_LEN: usize = 255;
+/// 可选的校验字段：设备提供时，其值须为其余字段的 CRC-32（8位十六进制）
+const CHECKSUM_KEY: &str = "crc";
+
 #[derive(Debug, Clone, Copy, Eq, PartialEq, Serialize, Deserialize)]
 pub enum BoardConnectStatus {
     Connected,
@@ -10,7 +25,72 @@ pub enum BoardConnectStatus {
     Unknown,
 }
 
-#[derive(Debug, Clone, Eq, PartialEq, Serialize, Deserialize)]
+/// 设备在广播（mDNS TXT 记录）中附带的遥测信息，旧固件不携带时各字段为空
+#[derive(Debug, Clone, Default, PartialEq, Serialize, Deserialize)]
+pub struct BoardTelemetry {
+    /// 固件版本（`fw`/`firmware`/`version` 字段）
+    pub firmware_version: Option<String>,
+    /// 板载温度，单位摄氏度（`temp`/`temperature` 字段）
+    pub temperature: Option<f32>,
+    /// 广播中的全部原始字段
+    pub raw: BTreeMap<String, String>,
+}
+
+impl BoardTelemetry {
+    /// 从广播的键值对中解析遥测信息，键名不区分大小写
+    pub fn from_properties<K, V>(properties: impl IntoIterator<Item = (K, V)>) -> Self
+    where
+        K: AsRef<str>,
+        V: AsRef<str>,
+    {
+        let raw: BTreeMap<String, String> = properties
+            .into_iter()
+            .map(|(key, value)| {
+                (
+                    key.as_ref().trim().to_ascii_lowercase(),
+                    value.as_ref().trim().to_string(),
+                )
+            })
+            .collect();
+
+        let find = |keys: &[&str]| {
+            keys.iter()
+                .find_map(|key| raw.get(*key))
+                .filter(|value| !value.is_empty())
+        };
+
+        let firmware_version = find(&FIRMWARE_VERSION_KEYS).cloned();
+        let temperature = find(&TEMPERATURE_KEYS)
+            .and_then(|value| value.trim_end_matches(['C', 'c', '°']).parse::<f32>().ok())
+            .filter(|value| value.is_finite());
+
+        Self {
+            firmware_version,
+            temperature,
+            raw,
+        }
+    }
+
+    /// 是否发生了需要通知前端的变化：温度小幅波动不算，其余字段任何变化都算
+    pub fn differs_materially(&self, other: &Self) -> bool {
+        let temperature_changed = match (self.temperature, other.temperature) {
+            (Some(a), Some(b)) => (a - b).abs() >= TEMPERATURE_CHANGE_THRESHOLD,
+            (a, b) => a.is_some() != b.is_some(),
+        };
+        let without_temperature = |raw: &BTreeMap<String, String>| {
+            raw.iter()
+                .filter(|(key, _)| !TEMPERATURE_KEYS.contains(&key.as_str()))
+                .map(|(key, value)| (key.clone(), value.clone()))
+                .collect::<BTreeMap<_, _>>()
+        };
+
+        temperature_changed
+            || self.firmware_version != other.firmware_version
+            || without_temperature(&self.raw) != without_temperature(&other.raw)
+    }
+}
+
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
 pub struct BoardInfo {
     pub fullname: String,
     pub host: String,
@@ -19,6 +99,8 @@ pub struct BoardInfo {
     pub connect_status: BoardConnectStatus,
     pub checked_at: Option<std::time::SystemTime>,
     pub ttl: Option<u128>,
+    #[serde(default)]
+    pub telemetry: BoardTelemetry,
 }
 
 impl BoardInfo {
@@ -31,6 +113,289 @@ impl BoardInfo {
             connect_status: BoardConnectStatus::Unknown,
             checked_at: None,
             ttl: None,
+            telemetry: BoardTelemetry::default(),
+        }
+    }
+
+    pub fn with_telemetry(mut self, telemetry: BoardTelemetry) -> Self {
+        self.telemetry = telemetry;
+        self
+    }
+
+    /// 校验并解析设备广播（mDNS 解析结果），广播无效时返回原因
+    ///
+    /// 检查服务名、主机名和 TXT 字段的长度与字符，要求端口非0且至少有一个可用的单播地址
+    /// （不含链路本地和回环地址）；
+    /// TXT 记录中带有 `crc` 字段时校验其余字段的 CRC-32，不带时跳过校验以兼容旧固件
+    pub fn from_announcement<K, V>(
+        fullname: &str,
+        host: &str,
+        addresses: impl IntoIterator<Item = Ipv4Addr>,
+        port: u16,
+        properties: impl IntoIterator<Item = (K, V)>,
+    ) -> anyhow::Result<Self>
+    where
+        K: AsRef<str>,
+        V: AsRef<str>,
+    {
+        let instance = fullname
+            .strip_suffix(BOARD_SERVICE_TYPE)
+            .and_then(|instance| instance.strip_suffix('.'))
+            .ok_or_else(|| anyhow::anyhow!("unexpected service name '{fullname}'"))?;
+        if instance.is_empty()
+            || fullname.len() > MAX_ANNOUNCEMENT_NAME_LEN
+            || fullname.chars().any(char::is_control)
+        {
+            anyhow::bail!("invalid service instance name '{fullname}'");
+        }
+
+        let host_is_valid = !host.is_empty()
+            && host.len() <= MAX_ANNOUNCEMENT_NAME_LEN
+            && host
+                .chars()
+                .all(|c| c.is_ascii_alphanumeric() || c == '-' || c == '.');
+        if !host_is_valid {
+            anyhow::bail!("invalid host name '{host}'");
+        }
+
+        if port == 0 {
+            anyhow::bail!("invalid port 0");
+        }
+
+        let address = addresses
+            .into_iter()
+            .filter(|address| {
+                // 设备 DHCP 失败时自分配的链路本地地址和回环地址不能用于连接
+                !address.is_unspecified()
+                    && !address.is_broadcast()
+                    && !address.is_multicast()
+                    && !address.is_link_local()
+                    && !address.is_loopback()
+            })
+            .min()
+            .ok_or_else(|| anyhow::anyhow!("no usable IPv4 address"))?;
+
+        let properties: Vec<(String, String)> = properties
+            .into_iter()
+            .map(|(key, value)| (key.as_ref().to_string(), value.as_ref().to_string()))
+            .collect();
+        for (key, value) in &properties {
+            if key.trim().is_empty()
+                || key.len() + value.len() + 1 > MAX_TXT_ENTRY_LEN
+                || key.chars().chain(value.chars()).any(char::is_control)
+            {
+                anyhow::bail!("invalid TXT property '{key}'");
+            }
+        }
+
+        let telemetry = BoardTelemetry::from_properties(properties);
+        if let Some(checksum) = telemetry.raw.get(CHECKSUM_KEY) {
+            let expected = announcement_checksum(&telemetry.raw);
+            if u32::from_str_radix(checksum, 16).ok() != Some(expected) {
+                anyhow::bail!("checksum mismatch: got '{checksum}', expected {expected:08x}");
+            }
+        }
+
+        Ok(
+            Self::new(fullname.to_string(), host.to_string(), address, port)
+                .with_telemetry(telemetry),
+        )
+    }
+}
+
+/// 广播 TXT 字段的校验值：除 `crc` 以外的字段按键名排序后以 `key=value` 逐行拼接，取 CRC-32
+pub fn announcement_checksum(raw: &BTreeMap<String, String>) -> u32 {
+    let payload = raw
+        .iter()
+        .filter(|(key, _)| key.as_str() != CHECKSUM_KEY)
+        .map(|(key, value)| format!("{key}={value}"))
+        .collect::<Vec<_>>()
+        .join("\n");
+    crc32(payload.as_bytes())
+}
+
+/// CRC-32（IEEE 802.3，反射多项式 0xEDB88320）
+fn crc32(data: &[u8]) -> u32 {
+    let mut crc = !0u32;
+    for &byte in data {
+        crc ^= byte as u32;
+        for _ in 0..8 {
+            crc = if crc & 1 != 0 {
+                (crc >> 1) ^ 0xEDB8_8320
+            } else {
+                crc >> 1
+            };
         }
     }
+    !crc
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_parse_telemetry_properties() {
+        let telemetry =
+            BoardTelemetry::from_properties([("FW", "1.4.2"), ("temp", "41.5C"), ("leds", "120")]);
+
+        assert_eq!(telemetry.firmware_version.as_deref(), Some("1.4.2"));
+        assert_eq!(telemetry.temperature, Some(41.5));
+        assert_eq!(telemetry.raw.get("leds").map(String::as_str), Some("120"));
+    }
+
+    #[test]
+    fn test_parse_announcement_without_telemetry() {
+        let telemetry = BoardTelemetry::from_properties(Vec::<(String, String)>::new());
+        assert_eq!(telemetry, BoardTelemetry::default());
+
+        let telemetry = BoardTelemetry::from_properties([("temp", "n/a"), ("fw", "")]);
+        assert_eq!(telemetry.temperature, None);
+        assert_eq!(telemetry.firmware_version, None);
+
+        // 旧版本保存/推送的设备信息没有 telemetry 字段
+        let info: BoardInfo = serde_json::from_value(serde_json::json!({
+            "fullname": "board._ambient_light._udp.local.",
+            "host": "board.local.",
+            "address": "192.168.1.10",
+            "port": 23042,
+            "connect_status": "Unknown",
+            "checked_at": null,
+            "ttl": null,
+        }))
+        .unwrap();
+        assert_eq!(info.telemetry, BoardTelemetry::default());
+    }
+
+    const FULLNAME: &str = "board._ambient_light._udp.local.";
+    const HOST: &str = "board.local.";
+
+    fn announce(
+        fullname: &str,
+        host: &str,
+        addresses: &[Ipv4Addr],
+        port: u16,
+        properties: &[(&str, &str)],
+    ) -> anyhow::Result<BoardInfo> {
+        BoardInfo::from_announcement(
+            fullname,
+            host,
+            addresses.iter().copied(),
+            port,
+            properties.iter().copied(),
+        )
+    }
+
+    #[test]
+    fn test_valid_announcement_is_parsed() {
+        let address = Ipv4Addr::new(192, 168, 1, 10);
+        let info = announce(
+            FULLNAME,
+            HOST,
+            &[
+                Ipv4Addr::UNSPECIFIED,
+                Ipv4Addr::new(169, 254, 12, 7),
+                Ipv4Addr::LOCALHOST,
+                address,
+            ],
+            23042,
+            &[("fw", "1.4.2")],
+        )
+        .unwrap();
+        assert_eq!(info.address, address);
+        assert_eq!(info.port, 23042);
+        assert_eq!(info.telemetry.firmware_version.as_deref(), Some("1.4.2"));
+
+        // 带有正确校验值的广播
+        let raw = BTreeMap::from([("fw".to_string(), "1.4.2".to_string())]);
+        let checksum = format!("{:08x}", announcement_checksum(&raw));
+        assert_eq!(checksum, format!("{:08x}", crc32(b"fw=1.4.2")));
+        assert!(announce(
+            FULLNAME,
+            HOST,
+            &[address],
+            23042,
+            &[("fw", "1.4.2"), ("crc", &checksum)]
+        )
+        .is_ok());
+    }
+
+    #[test]
+    fn test_malformed_announcements_are_rejected() {
+        let address = [Ipv4Addr::new(192, 168, 1, 10)];
+        let long_value = "x".repeat(MAX_TXT_ENTRY_LEN);
+
+        let malformed: Vec<anyhow::Result<BoardInfo>> = vec![
+            // 服务类型不符或实例名为空
+            announce("board._http._tcp.local.", HOST, &address, 23042, &[]),
+            announce(BOARD_SERVICE_TYPE, HOST, &address, 23042, &[]),
+            announce(
+                "bad\u{0}name._ambient_light._udp.local.",
+                HOST,
+                &address,
+                23042,
+                &[],
+            ),
+            // 主机名为空或含非法字符
+            announce(FULLNAME, "", &address, 23042, &[]),
+            announce(FULLNAME, "board local", &address, 23042, &[]),
+            // 端口为0
+            announce(FULLNAME, HOST, &address, 0, &[]),
+            // 没有可用的单播地址
+            announce(FULLNAME, HOST, &[], 23042, &[]),
+            announce(
+                FULLNAME,
+                HOST,
+                &[
+                    Ipv4Addr::UNSPECIFIED,
+                    Ipv4Addr::BROADCAST,
+                    Ipv4Addr::new(224, 0, 0, 251),
+                    Ipv4Addr::new(169, 254, 1, 1),
+                    Ipv4Addr::LOCALHOST,
+                ],
+                23042,
+                &[],
+            ),
+            // TXT 字段超长、键为空或含控制字符
+            announce(FULLNAME, HOST, &address, 23042, &[("fw", &long_value)]),
+            announce(FULLNAME, HOST, &address, 23042, &[("", "1.0.0")]),
+            announce(FULLNAME, HOST, &address, 23042, &[("fw", "1.0\r\n")]),
+            // 校验值不符或无法解析
+            announce(
+                FULLNAME,
+                HOST,
+                &address,
+                23042,
+                &[("fw", "1.4.2"), ("crc", "00000000")],
+            ),
+            announce(
+                FULLNAME,
+                HOST,
+                &address,
+                23042,
+                &[("fw", "1.4.2"), ("crc", "garbage")],
+            ),
+        ];
+
+        for (i, result) in malformed.into_iter().enumerate() {
+            assert!(result.is_err(), "malformed announcement #{i} was accepted");
+        }
+    }
+
+    #[test]
+    fn test_material_telemetry_changes() {
+        let base = BoardTelemetry::from_properties([("fw", "1.0.0"), ("temp", "40.0")]);
+
+        let jitter = BoardTelemetry::from_properties([("fw", "1.0.0"), ("temp", "40.4")]);
+        assert!(!base.differs_materially(&jitter));
+
+        let warmer = BoardTelemetry::from_properties([("fw", "1.0.0"), ("temp", "41.2")]);
+        assert!(base.differs_materially(&warmer));
+
+        let upgraded = BoardTelemetry::from_properties([("fw", "1.1.0"), ("temp", "40.0")]);
+        assert!(base.differs_materially(&upgraded));
+
+        let no_temperature = BoardTelemetry::from_properties([("fw", "1.0.0")]);
+        assert!(base.differs_materially(&no_temperature));
+    }
 }
@@ -1,10 +1,12 @@
 mod board;
+mod board_config;
 mod board_info;
 mod channels;
 mod display_setting_request;
 mod udp;
 
 pub use board::*;
+pub use board_config::*;
 pub use board_info::*;
 pub use channels::*;
 pub use display_setting_request::*;
@@ -1,16 +1,67 @@
-use std::{collections::HashMap, net::SocketAddr, sync::Arc, time::Duration};
+use std::{
+    collections::{HashMap, HashSet},
+    net::SocketAddr,
+    sync::Arc,
+    time::Duration,
+};
 
 use futures::future::join_all;
 use mdns_sd::{ServiceDaemon, ServiceEvent};
 use paris::{error, info, warn};
+use serde::Serialize;
 use tokio::sync::{watch, OnceCell, RwLock};
 
-use super::{Board, BoardInfo};
+use crate::led_data_sender::{LedDataPacket, PacketHeader};
+
+use super::{
+    Board, BoardConfig, BoardConfigManager, BoardConnectStatus, BoardHealth, BoardInfo,
+    BOARD_SERVICE_TYPE,
+};
+
+/// 启动自检前等待 mDNS 发现设备的时间
+const SELF_CHECK_DISCOVERY_DELAY: Duration = Duration::from_secs(5);
+
+/// 启动自检结果：逐个设备板的连通性检查汇总
+#[derive(Debug, Clone, PartialEq, Serialize)]
+pub struct BoardSelfCheckReport {
+    /// 自检完成时间
+    pub checked_at: chrono::DateTime<chrono::Utc>,
+    /// 已知设备板总数
+    pub total: usize,
+    /// 通过健康检查（数据写入成功且有回应）的设备数
+    pub reachable: usize,
+    /// 可达性检测已标记为断开、因此未发送探测包的设备数
+    pub skipped: usize,
+    /// 至少发现一个设备且全部设备都可达
+    pub healthy: bool,
+    /// 每个设备板的检查结果
+    pub boards: Vec<BoardHealth>,
+}
+
+impl BoardSelfCheckReport {
+    /// 根据各设备板的检查结果生成汇总，`skipped` 为未探测的断开设备数
+    pub fn from_results(boards: Vec<BoardHealth>, skipped: usize) -> Self {
+        let total = boards.len();
+        let reachable = boards.iter().filter(|health| health.healthy).count();
+        Self {
+            checked_at: chrono::Utc::now(),
+            total,
+            reachable,
+            skipped,
+            healthy: total > 0 && reachable == total,
+            boards,
+        }
+    }
+}
 
 #[derive(Debug, Clone)]
 pub struct UdpRpc {
     boards: Arc<RwLock<HashMap<String, Board>>>,
     boards_change_sender: Arc<watch::Sender<Vec<BoardInfo>>>,
+    /// 暂不接收广播数据的设备（例如正在执行识别闪烁），定向发送不受影响
+    held_boards: Arc<RwLock<HashSet<SocketAddr>>>,
+    /// 最近一次启动自检的结果，自检完成前为空
+    self_check_report: Arc<RwLock<Option<BoardSelfCheckReport>>>,
 }
 
 impl UdpRpc {
@@ -34,6 +85,8 @@ impl UdpRpc {
         Ok(Self {
             boards,
             boards_change_sender,
+            held_boards: Arc::new(RwLock::new(HashSet::new())),
+            self_check_report: Arc::new(RwLock::new(None)),
         })
     }
 
@@ -79,7 +132,7 @@ impl UdpRpc {
     }
 
     async fn search_boards(&self) -> anyhow::Result<()> {
-        let service_type = "_ambient_light._udp.local.";
+        let service_type = BOARD_SERVICE_TYPE;
         let mdns = ServiceDaemon::new()?;
         let receiver = mdns.browse(service_type).map_err(|e| {
             warn!("Failed to browse for {:?}: {:?}", service_type, e);
@@ -99,16 +152,61 @@ impl UdpRpc {
                         info.get_properties(),
                     );
 
+                    // 无效或伪造的广播不加入设备列表
+                    let board_info = match BoardInfo::from_announcement(
+                        info.get_fullname(),
+                        info.get_hostname(),
+                        info.get_addresses().iter().copied(),
+                        info.get_port(),
+                        info.get_properties()
+                            .iter()
+                            .map(|property| (property.key(), property.val_str())),
+                    ) {
+                        Ok(board_info) => board_info,
+                        Err(err) => {
+                            warn!(
+                                "Ignoring invalid board announcement {}: {}",
+                                info.get_fullname(),
+                                err
+                            );
+                            continue;
+                        }
+                    };
+
                     let mut boards = self.boards.write().await;
 
-                    let board_info = BoardInfo::new(
-                        info.get_fullname().to_string(),
-                        info.get_hostname().to_string(),
-                        *info.get_addresses().iter().next().unwrap(),
-                        info.get_port(),
-                    );
+                    // 已连接设备重新广播（例如遥测更新）时只更新遥测信息，不重建连接
+                    if let Some(existing) = boards.get(&board_info.fullname) {
+                        let mut existing_info = existing.info.write().await;
+                        if existing_info.address == board_info.address
+                            && existing_info.port == board_info.port
+                        {
+                            let changed = existing_info
+                                .telemetry
+                                .differs_materially(&board_info.telemetry);
+                            existing_info.telemetry = board_info.telemetry;
+                            drop(existing_info);
+
+                            if changed {
+                                let tx_boards = boards
+                                    .values()
+                                    .map(|it| async move { it.info.read().await.clone() });
+                                let tx_boards = join_all(tx_boards).await;
+                                drop(boards);
+
+                                if let Err(err) = sender.send(tx_boards) {
+                                    warn!("failed to send board change: {:?}", err);
+                                }
+                            }
+                            continue;
+                        }
+                    }
 
-                    let mut board = Board::new(board_info.clone());
+                    let board_config = BoardConfigManager::global()
+                        .await
+                        .get_board_config(&board_info.fullname)
+                        .await;
+                    let mut board = Board::new(board_info.clone(), board_config);
 
                     if let Err(err) = board.init_socket().await {
                         error!("failed to init socket: {:?}", err);
@@ -162,7 +260,38 @@ impl UdpRpc {
         self.boards_change_sender.borrow().clone()
     }
 
-    pub async fn send_to_all(&self, buff: &[u8]) -> anyhow::Result<()> {
+    /// 获取各设备实际使用的发送地址（含端口覆盖）及其驱动的灯带序列号（为空表示驱动全部灯带），按地址排序
+    pub async fn get_board_strips(&self) -> Vec<(SocketAddr, Vec<usize>)> {
+        let boards = self.boards.read().await;
+        let mut board_strips: Vec<(SocketAddr, Vec<usize>)> = boards
+            .values()
+            .filter_map(|board| Some((board.get_socket_addr()?, board.strips().to_vec())))
+            .collect();
+        board_strips.sort();
+        board_strips
+    }
+
+    /// 获取配置了最小发送间隔的设备地址及其间隔
+    pub async fn get_board_send_intervals(&self) -> Vec<(SocketAddr, Duration)> {
+        let boards = self.boards.read().await;
+        boards
+            .values()
+            .filter(|board| !board.min_send_interval().is_zero())
+            .filter_map(|board| Some((board.get_socket_addr()?, board.min_send_interval())))
+            .collect()
+    }
+
+    /// 向所有设备发送LED数据包，每个设备按自身协议版本编码
+    pub async fn send_to_all(&self, packet: &LedDataPacket) -> anyhow::Result<()> {
+        self.send_to_all_except(packet, &HashSet::new()).await
+    }
+
+    /// 向除 `skipped` 以外的所有设备发送LED数据包
+    pub async fn send_to_all_except(
+        &self,
+        packet: &LedDataPacket,
+        skipped: &HashSet<SocketAddr>,
+    ) -> anyhow::Result<()> {
         let boards = self.boards.read().await;
 
         if boards.is_empty() {
@@ -170,16 +299,68 @@ impl UdpRpc {
             return Ok(());
         }
 
-        log::debug!("Sending {} bytes to {} boards", buff.len(), boards.len());
+        log::debug!(
+            "Sending {} bytes to {} boards",
+            packet.data.len(),
+            boards.len()
+        );
 
+        let held_boards = self.held_boards.read().await;
         for board in boards.values() {
-            board.send_colors(buff).await;
+            if board
+                .get_socket_addr()
+                .is_some_and(|addr| held_boards.contains(&addr) || skipped.contains(&addr))
+            {
+                continue;
+            }
+            board.send_packet(packet).await;
         }
 
         Ok(())
     }
 
-    pub async fn send_to(&self, buff: &[u8], target_addr: SocketAddr) -> anyhow::Result<()> {
+    /// 暂停向指定设备发送广播数据，直到调用 `release_broadcast`
+    pub async fn hold_broadcast(&self, target_addr: SocketAddr) {
+        self.held_boards.write().await.insert(target_addr);
+    }
+
+    /// 恢复向指定设备发送广播数据
+    pub async fn release_broadcast(&self, target_addr: SocketAddr) {
+        self.held_boards.write().await.remove(&target_addr);
+    }
+
+    /// 更新设备覆盖配置并重新建立连接，使端口和协议版本立即生效
+    pub async fn apply_board_config(
+        &self,
+        fullname: &str,
+        config: BoardConfig,
+    ) -> anyhow::Result<()> {
+        BoardConfigManager::global()
+            .await
+            .set_board_config(fullname, config)
+            .await?;
+
+        let mut boards = self.boards.write().await;
+        let Some(existing) = boards.get(fullname) else {
+            // 设备当前不在线，配置将在下次发现时生效
+            return Ok(());
+        };
+
+        let board_info = existing.info.read().await.clone();
+        info!("board {} reconnecting with config {:?}", fullname, config);
+
+        let mut board = Board::new(board_info, config);
+        board.init_socket().await?;
+        boards.insert(fullname.to_string(), board);
+        Ok(())
+    }
+
+    /// 向指定地址的设备发送LED数据包，按该设备协议版本编码
+    pub async fn send_to(
+        &self,
+        packet: &LedDataPacket,
+        target_addr: SocketAddr,
+    ) -> anyhow::Result<()> {
         let boards = self.boards.read().await;
 
         if boards.is_empty() {
@@ -213,10 +394,10 @@ impl UdpRpc {
         if let Some(board) = target_board {
             log::info!(
                 "✅ Found target board! Sending {} bytes to: {}",
-                buff.len(),
+                packet.data.len(),
                 target_addr
             );
-            board.send_colors(buff).await;
+            board.send_packet(packet).await;
             Ok(())
         } else {
             warn!("❌ Target board with address {} not found", target_addr);
@@ -224,6 +405,125 @@ impl UdpRpc {
         }
     }
 
+    /// 指定地址设备配置的数据包头，设备不在列表中时使用默认包头
+    pub async fn packet_header_for(&self, target_addr: SocketAddr) -> PacketHeader {
+        self.boards
+            .read()
+            .await
+            .values()
+            .find(|board| board.get_socket_addr() == Some(target_addr))
+            .map(Board::packet_header)
+            .unwrap_or_default()
+    }
+
+    /// 直接向指定地址发送LED数据包，按该地址设备配置的包头编码，不要求设备在列表中
+    pub async fn send_packet_direct(
+        &self,
+        packet: &LedDataPacket,
+        target_addr: SocketAddr,
+    ) -> anyhow::Result<()> {
+        let header = self.packet_header_for(target_addr).await;
+        self.send_to_direct(&packet.build_packet_with_header(header), target_addr)
+            .await
+    }
+
+    /// 向指定地址的设备发送LED数据包，设备不存在时忽略（用于逐帧发送，不输出日志）
+    pub async fn send_to_if_present(&self, packet: &LedDataPacket, target_addr: SocketAddr) {
+        let boards = self.boards.read().await;
+        if let Some(board) = boards
+            .values()
+            .find(|board| board.get_socket_addr() == Some(target_addr))
+        {
+            board.send_packet(packet).await;
+        }
+    }
+
+    /// 对指定地址的设备执行健康检查，设备不在列表中时返回 None
+    pub async fn check_board_health(
+        &self,
+        target_addr: SocketAddr,
+        wait: Duration,
+    ) -> anyhow::Result<Option<BoardHealth>> {
+        // 先复制探测所需信息再释放锁，避免探测等待期间阻塞设备列表的更新
+        let probe = self
+            .boards
+            .read()
+            .await
+            .values()
+            .find(|board| board.get_socket_addr() == Some(target_addr))
+            .map(Board::probe);
+        let Some(probe) = probe else {
+            return Ok(None);
+        };
+
+        probe.health_check(wait).await.map(Some)
+    }
+
+    /// 启动自检：等待设备发现后对所有已知设备板执行健康检查，记录并推送汇总结果
+    ///
+    /// 可达性检测已标记为断开的设备不再发送探测包，直接记为不可达
+    pub async fn run_startup_self_check(&self, wait: Duration) -> BoardSelfCheckReport {
+        tokio::time::sleep(SELF_CHECK_DISCOVERY_DELAY).await;
+
+        let mut targets = Vec::new();
+        for board in self.boards.read().await.values() {
+            let address = board
+                .get_socket_addr()
+                .map(|addr| addr.to_string())
+                .unwrap_or_default();
+            let connect_status = board.info.read().await.connect_status;
+            targets.push((address, connect_status, board.probe()));
+        }
+
+        let mut skipped = 0;
+        let mut checks = Vec::with_capacity(targets.len());
+        for (address, connect_status, probe) in targets {
+            let online = connect_status == BoardConnectStatus::Connected;
+            let disconnected = connect_status == BoardConnectStatus::Disconnected;
+
+            if disconnected {
+                skipped += 1;
+            }
+            checks.push(async move {
+                if disconnected {
+                    return BoardHealth::unreachable(address, online);
+                }
+                match probe.health_check(wait).await {
+                    Ok(health) => health,
+                    Err(err) => {
+                        warn!("self-check of board {} failed: {}", address, err);
+                        BoardHealth::unreachable(address, online)
+                    }
+                }
+            });
+        }
+        let results = join_all(checks).await;
+
+        let report = BoardSelfCheckReport::from_results(results, skipped);
+        if report.total == 0 {
+            warn!("Board self-check: no boards discovered");
+        } else if report.healthy {
+            info!("Board self-check: all {} boards reachable", report.total);
+        } else {
+            warn!(
+                "Board self-check: {}/{} boards reachable ({} skipped as disconnected)",
+                report.reachable, report.total, report.skipped
+            );
+            for health in report.boards.iter().filter(|health| !health.healthy) {
+                warn!("  unreachable board: {}", health.address);
+            }
+        }
+
+        *self.self_check_report.write().await = Some(report.clone());
+        crate::websocket_events::publish_board_self_check_completed(&report).await;
+        report
+    }
+
+    /// 获取最近一次启动自检的结果
+    pub async fn get_self_check_report(&self) -> Option<BoardSelfCheckReport> {
+        self.self_check_report.read().await.clone()
+    }
+
     /// 直接发送数据到指定地址，不检查设备列表（用于调试和测试）
     pub async fn send_to_direct(&self, buff: &[u8], target_addr: SocketAddr) -> anyhow::Result<()> {
         log::info!(
@@ -233,7 +533,7 @@ impl UdpRpc {
         );
 
         // 创建临时UDP socket直接发送
-        let socket = tokio::net::UdpSocket::bind("0.0.0.0:0").await?;
+        let socket = crate::output_sink::bind_udp_socket(target_addr).await?;
 
         match socket.send_to(buff, target_addr).await {
             Ok(bytes_sent) => {
@@ -278,8 +578,28 @@ impl UdpRpc {
                 .map(|it| async move { it.info.read().await.clone() });
             let current_boards = join_all(current_boards).await;
 
+            // 从离线（重连中或已断开）恢复的设备
+            let recovered: Vec<SocketAddr> = boards
+                .values()
+                .zip(prev_boards.iter().zip(current_boards.iter()))
+                .filter(|(_, (prev, current))| {
+                    matches!(
+                        prev.connect_status,
+                        BoardConnectStatus::Connecting(_) | BoardConnectStatus::Disconnected
+                    ) && current.connect_status == BoardConnectStatus::Connected
+                })
+                .filter_map(|(board, _)| board.get_socket_addr())
+                .collect();
+
             drop(boards);
 
+            if !recovered.is_empty() {
+                let sender = crate::led_data_sender::LedDataSender::global().await;
+                for addr in recovered {
+                    sender.mark_board_recovered(addr).await;
+                }
+            }
+
             // Only send update if there are actual changes
             let has_changes = prev_boards.len() != current_boards.len()
                 || prev_boards
@@ -301,3 +621,31 @@ impl UdpRpc {
         }
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_self_check_report_summary() {
+        let reachable = BoardHealth {
+            address: "192.168.1.10:23042".to_string(),
+            online: true,
+            data_sent: true,
+            responded: true,
+            latency_ms: Some(3.0),
+            healthy: true,
+        };
+        let skipped = BoardHealth::unreachable("192.168.1.11:23042".to_string(), false);
+
+        let report = BoardSelfCheckReport::from_results(vec![reachable.clone(), skipped], 1);
+        assert_eq!((report.total, report.reachable, report.skipped), (2, 1, 1));
+        assert!(!report.healthy);
+
+        let report = BoardSelfCheckReport::from_results(vec![reachable], 0);
+        assert!(report.healthy);
+
+        let report = BoardSelfCheckReport::from_results(Vec::new(), 0);
+        assert!(!report.healthy);
+    }
+}
@@ -8,8 +8,17 @@ use futures_util::{SinkExt, StreamExt};
 use image::{ImageFormat, RgbaImage};
 use tokio::sync::{broadcast, RwLock};
 use tokio::time::sleep;
-use tokio_tungstenite::{accept_async, tungstenite::Message};
-
+use tokio_tungstenite::{
+    accept_hdr_async,
+    tungstenite::{
+        handshake::server::{ErrorResponse, Request, Response},
+        http::{header, StatusCode},
+        Message,
+    },
+};
+
+use crate::ambient_light::CaptureFormat;
+use crate::http_server::auth::AuthConfig;
 use crate::screenshot::Screenshot;
 use crate::screenshot_manager::ScreenshotManager;
 
@@ -254,54 +263,61 @@ impl ScreenStreamManager {
         let bytes = &screenshot.bytes;
 
         // Convert BGRA to RGBA using unsafe with optimized batch processing for maximum performance
+        // RGBA captures are already in the target order
         let mut rgba_bytes = bytes.as_ref().clone();
-        unsafe {
-            let ptr = rgba_bytes.as_mut_ptr() as *mut u32;
-            let len = rgba_bytes.len() / 4;
-
-            // Process in larger chunks of 64 for better cache efficiency and loop unrolling
-            let chunk_size = 64;
-            let full_chunks = len / chunk_size;
-            let remainder = len % chunk_size;
-
-            // Process full chunks with manual loop unrolling
-            for chunk_idx in 0..full_chunks {
-                let base_ptr = ptr.add(chunk_idx * chunk_size);
-
-                // Unroll the inner loop for better performance
-                for i in (0..chunk_size).step_by(4) {
-                    // Process 4 pixels at once
-                    let p0 = base_ptr.add(i).read();
-                    let p1 = base_ptr.add(i + 1).read();
-                    let p2 = base_ptr.add(i + 2).read();
-                    let p3 = base_ptr.add(i + 3).read();
-
-                    // BGRA (0xAABBGGRR) -> RGBA (0xAAGGBBRR)
-                    let s0 =
-                        (p0 & 0xFF00FF00) | ((p0 & 0x00FF0000) >> 16) | ((p0 & 0x000000FF) << 16);
-                    let s1 =
-                        (p1 & 0xFF00FF00) | ((p1 & 0x00FF0000) >> 16) | ((p1 & 0x000000FF) << 16);
-                    let s2 =
-                        (p2 & 0xFF00FF00) | ((p2 & 0x00FF0000) >> 16) | ((p2 & 0x000000FF) << 16);
-                    let s3 =
-                        (p3 & 0xFF00FF00) | ((p3 & 0x00FF0000) >> 16) | ((p3 & 0x000000FF) << 16);
-
-                    base_ptr.add(i).write(s0);
-                    base_ptr.add(i + 1).write(s1);
-                    base_ptr.add(i + 2).write(s2);
-                    base_ptr.add(i + 3).write(s3);
+        if screenshot.capture_format == CaptureFormat::Bgra {
+            unsafe {
+                let ptr = rgba_bytes.as_mut_ptr() as *mut u32;
+                let len = rgba_bytes.len() / 4;
+
+                // Process in larger chunks of 64 for better cache efficiency and loop unrolling
+                let chunk_size = 64;
+                let full_chunks = len / chunk_size;
+                let remainder = len % chunk_size;
+
+                // Process full chunks with manual loop unrolling
+                for chunk_idx in 0..full_chunks {
+                    let base_ptr = ptr.add(chunk_idx * chunk_size);
+
+                    // Unroll the inner loop for better performance
+                    for i in (0..chunk_size).step_by(4) {
+                        // Process 4 pixels at once
+                        let p0 = base_ptr.add(i).read();
+                        let p1 = base_ptr.add(i + 1).read();
+                        let p2 = base_ptr.add(i + 2).read();
+                        let p3 = base_ptr.add(i + 3).read();
+
+                        // BGRA (0xAABBGGRR) -> RGBA (0xAAGGBBRR)
+                        let s0 = (p0 & 0xFF00FF00)
+                            | ((p0 & 0x00FF0000) >> 16)
+                            | ((p0 & 0x000000FF) << 16);
+                        let s1 = (p1 & 0xFF00FF00)
+                            | ((p1 & 0x00FF0000) >> 16)
+                            | ((p1 & 0x000000FF) << 16);
+                        let s2 = (p2 & 0xFF00FF00)
+                            | ((p2 & 0x00FF0000) >> 16)
+                            | ((p2 & 0x000000FF) << 16);
+                        let s3 = (p3 & 0xFF00FF00)
+                            | ((p3 & 0x00FF0000) >> 16)
+                            | ((p3 & 0x000000FF) << 16);
+
+                        base_ptr.add(i).write(s0);
+                        base_ptr.add(i + 1).write(s1);
+                        base_ptr.add(i + 2).write(s2);
+                        base_ptr.add(i + 3).write(s3);
+                    }
                 }
-            }
 
-            // Process remaining pixels
-            let remainder_start = full_chunks * chunk_size;
-            for i in 0..remainder {
-                let idx = remainder_start + i;
-                let pixel = ptr.add(idx).read();
-                let swapped = (pixel & 0xFF00FF00)
-                    | ((pixel & 0x00FF0000) >> 16)
-                    | ((pixel & 0x000000FF) << 16);
-                ptr.add(idx).write(swapped);
+                // Process remaining pixels
+                let remainder_start = full_chunks * chunk_size;
+                for i in 0..remainder {
+                    let idx = remainder_start + i;
+                    let pixel = ptr.add(idx).read();
+                    let swapped = (pixel & 0xFF00FF00)
+                        | ((pixel & 0x00FF0000) >> 16)
+                        | ((pixel & 0x000000FF) << 16);
+                    ptr.add(idx).write(swapped);
+                }
             }
         }
 
@@ -391,10 +407,30 @@ impl ScreenStreamManager {
 }
 
 // WebSocket handler for screen streaming
-pub async fn handle_websocket_connection(stream: tokio::net::TcpStream) -> Result<()> {
+pub async fn handle_websocket_connection(
+    stream: tokio::net::TcpStream,
+    peer: std::net::SocketAddr,
+    auth: &AuthConfig,
+) -> Result<()> {
     log::info!("Accepting WebSocket connection...");
 
-    let ws_stream = match accept_async(stream).await {
+    // 握手阶段校验访问令牌，未授权时返回 401 并拒绝升级
+    let check_auth = |request: &Request, response: Response| {
+        let authorization = request
+            .headers()
+            .get(header::AUTHORIZATION)
+            .and_then(|value| value.to_str().ok());
+        if auth.is_authorized(Some(peer), authorization, request.uri().query()) {
+            Ok(response)
+        } else {
+            log::warn!("🔒 Rejected unauthorized screen stream connection from {peer}");
+            let mut error = ErrorResponse::new(Some("Unauthorized".to_string()));
+            *error.status_mut() = StatusCode::UNAUTHORIZED;
+            Err(error)
+        }
+    };
+
+    let ws_stream = match accept_hdr_async(stream, check_auth).await {
         Ok(ws) => {
             log::info!("WebSocket handshake completed successfully");
             ws
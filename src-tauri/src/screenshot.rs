@@ -1,10 +1,15 @@
+use std::collections::HashMap;
 use std::fmt::Debug;
 use std::fmt::Formatter;
 use std::sync::Arc;
 
 use serde::{Deserialize, Serialize};
+use utoipa::ToSchema;
 
-use crate::{ambient_light::LedStripConfig, led_color::LedColor};
+use crate::{
+    ambient_light::{Border, CaptureFormat, LedStripConfig, SampleShape, ZoneReduction},
+    led_color::LedColor,
+};
 
 /// 类型别名：图像数据加载结果 (数据, 宽度, 高度, 每行字节数)
 type ImageLoadResult = Result<(Vec<u8>, u32, u32, usize), Box<dyn std::error::Error>>;
@@ -18,6 +23,28 @@ pub struct Screenshot {
     pub bytes: Arc<Vec<u8>>,
     pub scale_factor: f32,
     pub bound_scale_factor: f32,
+    /// 显示器旋转角度（度），来自系统显示器信息
+    pub rotation: f32,
+    /// 每个LED的采样点数量（必须为完全平方数）
+    pub sample_points_per_led: usize,
+    /// 黑电平阈值：感知亮度低于该值的像素不参与颜色平均
+    pub black_level: u8,
+    /// 按灯带序列号配置的LED归一化位置，用于LED间距不均匀的灯带
+    pub led_positions: Arc<HashMap<usize, Vec<f32>>>,
+    /// 采样区域裁剪，为空时使用整幅画面
+    pub crop: Option<SampleCrop>,
+    /// 相邻LED之间的颜色插值强度（0-1），0 表示关闭
+    pub interpolation_strength: f32,
+    /// 手动设置的缩放比例，用于自动检测值与实际帧缓冲区不符的显示器
+    pub scale_factor_override: Option<f32>,
+    /// 采样点取平均时使用的颜色空间
+    pub averaging_color_space: AveragingColorSpace,
+    /// 按采样点的子像素位置双线性插值读取像素（更平滑但开销更大）
+    pub bilinear_sampling: bool,
+    /// 左右边的LED采样覆盖相邻LED的纵向窗口，使灯带沿长度方向呈现平滑渐变
+    pub vertical_gradient_sampling: bool,
+    /// 位图的像素字节顺序
+    pub capture_format: CaptureFormat,
 }
 
 impl Debug for Screenshot {
@@ -29,11 +56,44 @@ impl Debug for Screenshot {
             .field("bytes_per_row", &self.bytes_per_row)
             .field("scale_factor", &self.scale_factor)
             .field("bound_scale_factor", &self.bound_scale_factor)
+            .field("rotation", &self.rotation)
+            .field("sample_points_per_led", &self.sample_points_per_led)
+            .field("black_level", &self.black_level)
+            .field("led_positions", &self.led_positions.len())
+            .field("crop", &self.crop)
+            .field("interpolation_strength", &self.interpolation_strength)
+            .field("scale_factor_override", &self.scale_factor_override)
+            .field("averaging_color_space", &self.averaging_color_space)
+            .field("bilinear_sampling", &self.bilinear_sampling)
+            .field(
+                "vertical_gradient_sampling",
+                &self.vertical_gradient_sampling,
+            )
+            .field("capture_format", &self.capture_format)
             .finish()
     }
 }
 
-static SINGLE_AXIS_POINTS: usize = 5;
+/// 每个LED的默认采样点数量（2x2 网格）
+pub const DEFAULT_SAMPLE_POINTS_PER_LED: usize = 4;
+/// 每个LED允许的最大采样点数量（8x8 网格）
+pub const MAX_SAMPLE_POINTS_PER_LED: usize = 64;
+
+/// 校验每个LED的采样点数量
+///
+/// 采样点在LED区域内按正方形网格排布，因此数量必须是 1 到
+/// [`MAX_SAMPLE_POINTS_PER_LED`] 之间的完全平方数。
+pub fn validate_sample_points_per_led(points: usize) -> anyhow::Result<()> {
+    let samples_per_axis = (points as f64).sqrt() as usize;
+    if points == 0 || points > MAX_SAMPLE_POINTS_PER_LED || samples_per_axis.pow(2) != points {
+        return Err(anyhow::anyhow!(
+            "Sample points per LED must be a perfect square between 1 and {}, got {}",
+            MAX_SAMPLE_POINTS_PER_LED,
+            points
+        ));
+    }
+    Ok(())
+}
 
 impl Screenshot {
     pub fn new(
@@ -53,121 +113,150 @@ impl Screenshot {
             bytes,
             scale_factor,
             bound_scale_factor,
+            rotation: 0.0,
+            sample_points_per_led: DEFAULT_SAMPLE_POINTS_PER_LED,
+            black_level: 0,
+            led_positions: Arc::new(HashMap::new()),
+            crop: None,
+            interpolation_strength: 0.0,
+            scale_factor_override: None,
+            averaging_color_space: AveragingColorSpace::default(),
+            bilinear_sampling: false,
+            vertical_gradient_sampling: false,
+            capture_format: CaptureFormat::default(),
         }
     }
 
-    pub fn get_sample_points(&self, config: &LedStripConfig) -> Vec<LedSamplePoints> {
-        let height = self.height as usize;
-        let width = self.width as usize;
+    /// 设置显示器旋转角度，用于将灯带边框映射到截图中的实际边缘
+    pub fn with_rotation(mut self, rotation: f32) -> Self {
+        self.rotation = rotation;
+        self
+    }
 
-        // Debug: Print scale factors and dimensions (uncomment for debugging)
-        // log::debug!(
-        //     "Display {}: Screenshot dimensions {}x{}, scale_factor={}, bound_scale_factor={}",
-        //     self.display_id, width, height, self.scale_factor, self.bound_scale_factor
-        // );
+    /// 设置每个LED的采样点数量
+    pub fn with_sample_points_per_led(mut self, sample_points_per_led: usize) -> Self {
+        self.sample_points_per_led = sample_points_per_led;
+        self
+    }
 
-        // let height = CGDisplay::new(self.display_id).bounds().size.height as usize;
-        // let width = CGDisplay::new(self.display_id).bounds().size.width as usize;
+    /// 设置黑电平阈值
+    pub fn with_black_level(mut self, black_level: u8) -> Self {
+        self.black_level = black_level;
+        self
+    }
 
-        let result = match config.border {
-            crate::ambient_light::Border::Top => {
-                Self::get_one_edge_sample_points(height / 20, width, config.len, SINGLE_AXIS_POINTS)
-            }
-            crate::ambient_light::Border::Bottom => {
-                let points = Self::get_one_edge_sample_points(
-                    height / 20,
-                    width,
-                    config.len,
-                    SINGLE_AXIS_POINTS,
-                );
-                let result: Vec<LedSamplePoints> = points
-                    .into_iter()
-                    .map(|groups| -> Vec<Point> {
-                        groups
-                            .into_iter()
-                            .map(|(x, y)| (x, height - 1 - y))
-                            .collect()
-                    })
-                    .collect();
+    /// 设置按灯带序列号配置的LED位置
+    pub fn with_led_positions(mut self, led_positions: Arc<HashMap<usize, Vec<f32>>>) -> Self {
+        self.led_positions = led_positions;
+        self
+    }
 
-                // 调试：分析Bottom边框采样
-                log::debug!("🔍 Bottom border analysis:");
-                log::debug!("  Screen dimensions: {width}x{height}");
-                log::debug!("  LED count: {}", config.len);
-                log::debug!("  Generated {} LED groups", result.len());
-
-                if !result.is_empty() && !result[0].is_empty() {
-                    let first_led = &result[0];
-                    let min_y = first_led.iter().map(|p| p.1).min().unwrap_or(0);
-                    let max_y = first_led.iter().map(|p| p.1).max().unwrap_or(0);
-                    let min_x = first_led.iter().map(|p| p.0).min().unwrap_or(0);
-                    let max_x = first_led.iter().map(|p| p.0).max().unwrap_or(0);
-
-                    log::debug!("  First LED sample points: {} points", first_led.len());
-                    log::debug!(
-                        "  Y range: {} - {} (expected near {})",
-                        min_y,
-                        max_y,
-                        height - 1
-                    );
-                    log::debug!("  X range: {min_x} - {max_x}");
-                    log::debug!(
-                        "  Sample points: {:?}",
-                        &first_led[0..first_led.len().min(5)]
-                    );
-                }
+    /// 设置采样区域裁剪，边框将映射到裁剪区域的边缘
+    pub fn with_crop(mut self, crop: Option<SampleCrop>) -> Self {
+        self.crop = crop;
+        self
+    }
 
-                result
-            }
-            crate::ambient_light::Border::Left => {
-                let points = Self::get_one_edge_sample_points(
-                    width / 20,
-                    height,
-                    config.len,
-                    SINGLE_AXIS_POINTS,
-                );
-                points
-                    .into_iter()
-                    .map(|groups| -> Vec<Point> {
-                        groups.into_iter().map(|(x, y)| (y, x)).collect()
-                    })
-                    .collect()
-            }
-            crate::ambient_light::Border::Right => {
-                let points = Self::get_one_edge_sample_points(
-                    width / 20,
-                    height,
-                    config.len,
-                    SINGLE_AXIS_POINTS,
-                );
-                points
-                    .into_iter()
-                    .map(|groups| -> Vec<Point> {
-                        groups
-                            .into_iter()
-                            .map(|(x, y)| (width - 1 - y, x))
-                            .collect()
-                    })
-                    .collect()
+    /// 设置相邻LED之间的颜色插值强度
+    pub fn with_interpolation_strength(mut self, interpolation_strength: f32) -> Self {
+        self.interpolation_strength = interpolation_strength;
+        self
+    }
+
+    /// 设置采样点取平均时使用的颜色空间
+    pub fn with_averaging_color_space(mut self, color_space: AveragingColorSpace) -> Self {
+        self.averaging_color_space = color_space;
+        self
+    }
+
+    /// 设置是否按子像素位置双线性插值读取像素
+    pub fn with_bilinear_sampling(mut self, bilinear_sampling: bool) -> Self {
+        self.bilinear_sampling = bilinear_sampling;
+        self
+    }
+
+    /// 设置左右边是否按纵向渐变窗口采样
+    pub fn with_vertical_gradient_sampling(mut self, vertical_gradient_sampling: bool) -> Self {
+        self.vertical_gradient_sampling = vertical_gradient_sampling;
+        self
+    }
+
+    /// 设置位图的像素字节顺序
+    pub fn with_capture_format(mut self, capture_format: CaptureFormat) -> Self {
+        self.capture_format = capture_format;
+        self
+    }
+
+    /// 设置手动缩放比例，为空时使用自动检测的缩放比例
+    pub fn with_scale_factor_override(mut self, scale_factor_override: Option<f32>) -> Self {
+        self.scale_factor_override = scale_factor_override;
+        self
+    }
+
+    /// 实际生效的缩放比例
+    pub fn effective_scale_factor(&self) -> f32 {
+        self.scale_factor_override.unwrap_or(self.scale_factor)
+    }
+
+    /// 采样坐标空间的尺寸
+    ///
+    /// 设置了缩放比例覆盖时，画面占据帧缓冲区左上角 `覆盖值 / 检测值` 比例的区域
+    /// （逻辑尺寸乘以实际缩放比例），结果不超过帧缓冲区，避免采样点越界
+    fn sampling_size(&self) -> (usize, usize) {
+        let (width, height) = (self.width as usize, self.height as usize);
+        match self.scale_factor_override {
+            Some(scale_factor) if self.scale_factor > 0.0 => {
+                let ratio = scale_factor / self.scale_factor;
+                let scale =
+                    |size: usize| ((size as f32 * ratio).round() as usize).clamp(1, size.max(1));
+                (scale(width), scale(height))
             }
-        };
+            _ => (width, height),
+        }
+    }
 
-        // Debug: Print sample points for the first LED (uncomment for debugging)
-        // if !result.is_empty() && !result[0].is_empty() {
-        //     log::debug!(
-        //         "Display {} {:?} border: First LED sample points: {:?}",
-        //         self.display_id, config.border, &result[0][0..result[0].len().min(3)]
-        //     );
-        // }
+    /// 灯带每个LED当前使用的采样区域，顺序与 [`Self::get_sample_points`] 一致
+    pub fn get_sample_regions(&self, config: &LedStripConfig) -> Vec<LedSampleRegion> {
+        self.get_sample_points(config)
+            .iter()
+            .map(|points| LedSampleRegion::from_points(points))
+            .collect()
+    }
 
-        result
+    /// 灯带每个LED的采样点，与实时采样 [`Self::get_colors_by_led_configs`] 使用同一套生成逻辑
+    pub fn get_sample_points(&self, config: &LedStripConfig) -> Vec<LedSamplePoints> {
+        let (sampling_width, sampling_height) = self.sampling_size();
+        get_sample_points_for_config(
+            sampling_width,
+            sampling_height,
+            config,
+            self.rotation,
+            self.sample_points_per_led,
+            self.led_positions.get(&config.index).map(Vec::as_slice),
+            self.crop,
+            self.vertical_gradient_sampling,
+        )
     }
 
+    /// 为一条边生成每个LED的采样点
+    ///
+    /// `single_axis_points` 为每个LED的采样点总数。`Grid` 形状按 sqrt 向下取整后排成正方形网格，
+    /// 非完全平方数会被截断（例如 5 实际得到 2x2=4 个点）；`Line` 在厚度中线上沿边缘排成一行；
+    /// `LShape` 的首尾LED把一半的点沿厚度方向排在靠近拐角的一端，其余LED与 `Line` 相同。
+    ///
+    /// `led_positions` 为每个LED中心沿边缘的归一化位置，LED的采样区域延伸到与相邻LED的中点；
+    /// 为空时沿边缘均匀划分。
+    ///
+    /// `trim` 为边缘首尾不被灯带覆盖的比例（0-1），LED只分布在剩余的区间内，
+    /// `led_positions` 也相对于该区间。
     fn get_one_edge_sample_points(
         width: usize,
         length: usize,
         leds: usize,
         single_axis_points: usize,
+        led_positions: Option<&[f32]>,
+        trim: (f64, f64),
+        shape: SampleShape,
     ) -> Vec<LedSamplePoints> {
         if leds == 0 {
             return vec![];
@@ -175,8 +264,13 @@ impl Screenshot {
 
         let mut led_sample_points = Vec::new();
 
+        // 裁剪后灯带实际覆盖的边缘区间
+        let (start_trim, end_trim) = trim;
+        let edge_start = length as f64 * start_trim;
+        let edge_length = length as f64 * (1.0 - start_trim - end_trim).max(0.0);
+
         // 计算每个LED沿边缘方向的长度
-        let led_width = length as f64 / leds as f64;
+        let led_width = edge_length / leds as f64;
 
         // 计算采样网格：假设是正方形网格
         let samples_per_axis = (single_axis_points as f64).sqrt() as usize;
@@ -185,20 +279,71 @@ impl Screenshot {
             let mut led_points = Vec::new();
 
             // 计算当前LED的起始和结束位置（沿边缘方向）
-            let led_start = led_index as f64 * led_width;
-            let led_end = (led_index + 1) as f64 * led_width;
-
-            // 在LED区域内生成采样点网格
-            for row in 0..samples_per_axis {
-                for col in 0..samples_per_axis {
-                    // 在边缘厚度方向的采样位置
-                    let y_offset = (row as f64 + 0.5) * width as f64 / samples_per_axis as f64;
-
-                    // 在LED宽度方向的采样位置
-                    let x_offset = led_start
-                        + (col as f64 + 0.5) * (led_end - led_start) / samples_per_axis as f64;
+            let (led_start, led_end) = match led_positions {
+                Some(positions) => {
+                    let position = |i: usize| positions[i] as f64;
+                    let start = if led_index == 0 {
+                        0.0
+                    } else {
+                        (position(led_index - 1) + position(led_index)) / 2.0
+                    };
+                    let end = if led_index + 1 == leds {
+                        1.0
+                    } else {
+                        (position(led_index) + position(led_index + 1)) / 2.0
+                    };
+                    (
+                        edge_start + start * edge_length,
+                        edge_start + end * edge_length,
+                    )
+                }
+                None => (
+                    edge_start + led_index as f64 * led_width,
+                    edge_start + (led_index + 1) as f64 * led_width,
+                ),
+            };
+
+            // 沿边缘方向第 col 个（共 count 个）采样点的位置
+            let along = |col: usize, count: usize| {
+                led_start + (col as f64 + 0.5) * (led_end - led_start) / count as f64
+            };
+            // 厚度方向第 row 个（共 count 个）采样点的位置
+            let across =
+                |row: usize, count: usize| (row as f64 + 0.5) * width as f64 / count as f64;
+            let is_corner_led = led_index == 0 || led_index + 1 == leds;
+
+            match shape {
+                SampleShape::Grid => {
+                    // 在LED区域内生成采样点网格
+                    for row in 0..samples_per_axis {
+                        for col in 0..samples_per_axis {
+                            let x_offset = along(col, samples_per_axis);
+                            let y_offset = across(row, samples_per_axis);
+                            led_points.push((x_offset as usize, y_offset as usize));
+                        }
+                    }
+                }
+                SampleShape::LShape if is_corner_led => {
+                    // 沿边缘的一行 + 拐角一端沿厚度方向的一列
+                    let vertical = single_axis_points / 2;
+                    let horizontal = single_axis_points - vertical;
+                    let y_middle = width as f64 / 2.0;
+                    for col in 0..horizontal {
+                        led_points.push((along(col, horizontal) as usize, y_middle as usize));
+                    }
 
-                    led_points.push((x_offset as usize, y_offset as usize));
+                    let corner_col = if led_index == 0 { 0 } else { horizontal - 1 };
+                    let x_corner = along(corner_col, horizontal);
+                    for row in 0..vertical {
+                        led_points.push((x_corner as usize, across(row, vertical) as usize));
+                    }
+                }
+                SampleShape::Line | SampleShape::LShape => {
+                    let y_middle = width as f64 / 2.0;
+                    for col in 0..single_axis_points {
+                        led_points
+                            .push((along(col, single_axis_points) as usize, y_middle as usize));
+                    }
                 }
             }
 
@@ -208,74 +353,731 @@ impl Screenshot {
         led_sample_points
     }
 
+    /// 为左右边生成纵向渐变采样点
+    ///
+    /// 每个LED的采样窗口从上一个LED的中心延伸到下一个LED的中心（首尾LED延伸到边缘端点），
+    /// 沿边缘按三角分布的分位数排布 [`GRADIENT_SAMPLE_ROWS_PER_LED`] 行采样点，越靠近LED中心越密集，
+    /// 因此对采样点直接取平均即为以LED中心为峰值的三角加权平均。相邻LED的窗口互相重叠，
+    /// 灯带颜色沿长度方向按画面线性插值。厚度方向的采样点数量与 `Grid` 网格每行相同，
+    /// 其他形状只取厚度中线。`led_positions` 与 `trim` 的含义与 [`Self::get_one_edge_sample_points`] 相同。
+    fn get_one_edge_gradient_sample_points(
+        width: usize,
+        length: usize,
+        leds: usize,
+        single_axis_points: usize,
+        led_positions: Option<&[f32]>,
+        trim: (f64, f64),
+        shape: SampleShape,
+    ) -> Vec<LedSamplePoints> {
+        if leds == 0 {
+            return vec![];
+        }
+
+        let (start_trim, end_trim) = trim;
+        let edge_start = length as f64 * start_trim;
+        let edge_length = length as f64 * (1.0 - start_trim - end_trim).max(0.0);
+        let edge_end = edge_start + edge_length;
+        let center = |led_index: usize| {
+            let position = match led_positions {
+                Some(positions) => positions[led_index] as f64,
+                None => (led_index as f64 + 0.5) / leds as f64,
+            };
+            edge_start + position * edge_length
+        };
+
+        let columns = match shape {
+            SampleShape::Grid => ((single_axis_points as f64).sqrt() as usize).max(1),
+            SampleShape::Line | SampleShape::LShape => 1,
+        };
+        let max_along = length.saturating_sub(1) as f64;
+
+        (0..leds)
+            .map(|led_index| {
+                let low = if led_index == 0 {
+                    edge_start
+                } else {
+                    center(led_index - 1)
+                };
+                let high = if led_index + 1 == leds {
+                    edge_end
+                } else {
+                    center(led_index + 1)
+                };
+                let peak = center(led_index);
+
+                (0..GRADIENT_SAMPLE_ROWS_PER_LED)
+                    .flat_map(|row| {
+                        let quantile = (row as f64 + 0.5) / GRADIENT_SAMPLE_ROWS_PER_LED as f64;
+                        let along =
+                            triangular_quantile(quantile, low, peak, high).clamp(0.0, max_along);
+                        (0..columns).map(move |col| {
+                            let across = (col as f64 + 0.5) * width as f64 / columns as f64;
+                            (along as usize, across as usize)
+                        })
+                    })
+                    .collect()
+            })
+            .collect()
+    }
+
+    /// 计算每个LED采样点的颜色
+    ///
+    /// 感知亮度（`0.2126R + 0.7152G + 0.0722B`）低于 `black_level` 的像素不参与合并，
+    /// 避免深色界面把强调色拉向灰色；若所有像素都低于阈值则使用全部像素。
+    /// `black_level` 为 0 时不排除任何像素。多个采样点按 `reduction` 合并为一个颜色，
+    /// 取平均时按 `color_space` 决定是否先转换到线性空间，像素按 `format` 的字节顺序读取。
     pub fn get_one_edge_colors(
         sample_points_of_leds: &[LedSamplePoints],
         bitmap: &[u8],
         bytes_per_row: usize,
+        black_level: u8,
+        reduction: ZoneReduction,
+        color_space: AveragingColorSpace,
+        format: CaptureFormat,
     ) -> Vec<LedColor> {
-        let mut colors = vec![];
-        for led_points in sample_points_of_leds {
-            let mut r = 0.0;
-            let mut g = 0.0;
-            let mut b = 0.0;
-            let len = led_points.len() as f64;
-            for (x, y) in led_points {
-                // log::debug!("Sampling pixel at x: {}, y: {}, bytes_per_row: {}", x, y, bytes_per_row);
-                let position = y * bytes_per_row + x * 4;
-
-                // Add bounds checking to prevent index out of bounds
-                if position + 2 < bitmap.len() {
-                    b += bitmap[position] as f64;
-                    g += bitmap[position + 1] as f64;
-                    r += bitmap[position + 2] as f64;
-                } else {
-                    // Skip invalid positions or use default values
-                    log::warn!(
-                        "Invalid pixel position: x={}, y={}, position={}, bitmap_len={}",
-                        x,
-                        y,
-                        position,
-                        bitmap.len()
-                    );
-                }
-            }
-            let color = LedColor::new((r / len) as u8, (g / len) as u8, (b / len) as u8);
-
-            // Debug: Log sampled colors for troubleshooting
-            if colors.len() < 5 {
-                log::debug!(
-                    "🎨 Sampled color for LED {}: RGB({}, {}, {}) from {} sample points",
-                    colors.len(),
-                    (r / len) as u8,
-                    (g / len) as u8,
-                    (b / len) as u8,
-                    led_points.len()
+        let read_pixel = |(x, y): Point| {
+            let pixel = read_bitmap_pixel(bitmap, y * bytes_per_row + x * 4, format);
+            if pixel.is_none() {
+                // Skip invalid positions or use default values
+                log::warn!(
+                    "Invalid pixel position: x={}, y={}, position={}, bitmap_len={}",
+                    x,
+                    y,
+                    y * bytes_per_row + x * 4,
+                    bitmap.len()
                 );
             }
+            pixel
+        };
 
-            colors.push(color);
-        }
-        colors
+        reduce_edge_colors(
+            sample_points_of_leds,
+            read_pixel,
+            black_level,
+            reduction,
+            color_space,
+        )
+    }
+
+    /// 与 [`Screenshot::get_one_edge_colors`] 相同，但采样点坐标以 `subpixel_scale` 倍的
+    /// 子像素为单位，按小数位置对周围四个像素做双线性插值，避免画面移动时采样结果跳变
+    #[allow(clippy::too_many_arguments)]
+    pub fn get_one_edge_colors_bilinear(
+        sample_points_of_leds: &[LedSamplePoints],
+        bitmap: &[u8],
+        bytes_per_row: usize,
+        width: usize,
+        height: usize,
+        subpixel_scale: usize,
+        black_level: u8,
+        reduction: ZoneReduction,
+        color_space: AveragingColorSpace,
+        format: CaptureFormat,
+    ) -> Vec<LedColor> {
+        let read_pixel = |point: Point| {
+            bilinear_pixel(
+                bitmap,
+                bytes_per_row,
+                width,
+                height,
+                point,
+                subpixel_scale,
+                format,
+            )
+        };
+
+        reduce_edge_colors(
+            sample_points_of_leds,
+            read_pixel,
+            black_level,
+            reduction,
+            color_space,
+        )
     }
 
     /// 使用新的采样函数获取LED灯带颜色数据
     /// 这个方法使用改进的颜色采样算法，解决了之前的颜色错误问题
+    ///
+    /// 启用插值时，每个LED划分为 [`INTERPOLATION_ZONES_PER_LED`] 个采样区域，
+    /// 再沿灯带与相邻LED的区域混合，使LED稀疏时颜色过渡更平滑。
+    /// 配置了自定义LED位置的灯带不参与插值。
+    /// 采样点在 [`Self::sampling_size`] 的坐标空间中生成，与缩放比例覆盖保持一致。
     pub async fn get_colors_by_led_configs(
         &self,
         led_configs: &[LedStripConfig],
     ) -> Vec<Vec<LedColor>> {
-        sample_edge_colors_from_image(
+        let interpolate = |config: &LedStripConfig| {
+            self.interpolation_strength > 0.0 && !self.led_positions.contains_key(&config.index)
+        };
+
+        let sampling_configs: Vec<LedStripConfig> = led_configs
+            .iter()
+            .map(|config| {
+                if interpolate(config) {
+                    LedStripConfig {
+                        len: config.len * INTERPOLATION_ZONES_PER_LED,
+                        ..*config
+                    }
+                } else {
+                    *config
+                }
+            })
+            .collect();
+
+        // 设置了缩放比例覆盖时只在帧缓冲区左上角的实际画面内生成采样点，像素仍按原行宽读取
+        let (sampling_width, sampling_height) = self.sampling_size();
+        let colors = sample_edge_colors_from_rotated_image(
             &self.bytes,
-            self.width,
-            self.height,
+            sampling_width as u32,
+            sampling_height as u32,
             self.bytes_per_row,
-            led_configs,
-        )
+            &sampling_configs,
+            self.rotation,
+            self.sample_points_per_led,
+            self.black_level,
+            &self.led_positions,
+            self.crop,
+            self.averaging_color_space,
+            self.bilinear_sampling,
+            self.vertical_gradient_sampling,
+            self.capture_format,
+        );
+
+        colors
+            .into_iter()
+            .zip(led_configs)
+            .map(|(zones, config)| {
+                if interpolate(config) {
+                    interpolate_strip_colors(&zones, config.len, self.interpolation_strength)
+                } else {
+                    zones
+                }
+            })
+            .collect()
+    }
+
+    /// 计算整幅画面（设置了裁剪时为裁剪区域）的平均色，用于整条灯带单色填充
+    ///
+    /// 每个方向最多取 [`SCREEN_AVERAGE_GRID`] 个均匀分布的采样点，按 `averaging_color_space` 取平均
+    pub fn get_average_color(&self) -> LedColor {
+        let (sampling_width, sampling_height) = self.sampling_size();
+        let (crop_x, crop_y, width, height) = crop_rect(self.crop, sampling_width, sampling_height);
+        let columns = width.min(SCREEN_AVERAGE_GRID);
+        let rows = height.min(SCREEN_AVERAGE_GRID);
+        if columns == 0 || rows == 0 {
+            return LedColor::new(0, 0, 0);
+        }
+
+        let pixels: Vec<[f64; 3]> = (0..rows)
+            .flat_map(|row| {
+                let y = crop_y + (row * 2 + 1) * height / (rows * 2);
+                (0..columns).map(move |column| {
+                    let x = crop_x + (column * 2 + 1) * width / (columns * 2);
+                    (x, y)
+                })
+            })
+            .filter_map(|(x, y)| {
+                read_bitmap_pixel(
+                    &self.bytes,
+                    y * self.bytes_per_row + x * 4,
+                    self.capture_format,
+                )
+            })
+            .collect();
+
+        let [r, g, b] = self.averaging_color_space.mean_color(&pixels, pixels.len());
+        LedColor::new(r as u8, g as u8, b as u8)
+    }
+}
+
+/// 整屏平均色采样时每个方向的最大采样点数量
+pub const SCREEN_AVERAGE_GRID: usize = 64;
+
+/// 启用插值时每个LED划分的采样区域数量
+pub const INTERPOLATION_ZONES_PER_LED: usize = 3;
+
+/// 纵向渐变采样时每个LED沿边缘方向的采样行数
+pub const GRADIENT_SAMPLE_ROWS_PER_LED: usize = 8;
+
+/// 左右边的采样点：启用纵向渐变时使用重叠的渐变窗口，否则与上下边相同
+fn side_edge_sample_points(
+    width: usize,
+    length: usize,
+    config: &LedStripConfig,
+    sample_points_per_led: usize,
+    led_positions: Option<&[f32]>,
+    trim: (f64, f64),
+    vertical_gradient: bool,
+) -> Vec<LedSamplePoints> {
+    let sample_points = if vertical_gradient {
+        Screenshot::get_one_edge_gradient_sample_points
+    } else {
+        Screenshot::get_one_edge_sample_points
+    };
+    sample_points(
+        width,
+        length,
+        config.len,
+        sample_points_per_led,
+        led_positions,
+        trim,
+        config.sample_shape,
+    )
+}
+
+/// 三角分布（下限 `low`、峰值 `peak`、上限 `high`）的 `quantile` 分位数
+fn triangular_quantile(quantile: f64, low: f64, peak: f64, high: f64) -> f64 {
+    let span = high - low;
+    if span <= 0.0 {
+        return peak;
+    }
+    if quantile < (peak - low) / span {
+        low + (quantile * span * (peak - low)).sqrt()
+    } else {
+        high - ((1.0 - quantile) * span * (high - peak)).sqrt()
+    }
+}
+
+/// 采样点取平均时使用的颜色空间
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq, Serialize, Deserialize, ToSchema)]
+#[serde(rename_all = "snake_case")]
+pub enum AveragingColorSpace {
+    /// 直接平均 sRGB 编码值，混合色的边缘偏暗，保持原有效果
+    #[default]
+    Gamma,
+    /// 先转换为线性光再平均，结果再编码回 sRGB，物理上更准确
+    Linear,
+}
+
+impl AveragingColorSpace {
+    /// 在该颜色空间中计算平均色，`count` 大于像素数量时不足部分按黑色计入
+    fn mean_color(self, pixels: &[[f64; 3]], count: usize) -> [f64; 3] {
+        match self {
+            AveragingColorSpace::Gamma => mean_color(pixels, count),
+            AveragingColorSpace::Linear => {
+                let linear: Vec<[f64; 3]> = pixels
+                    .iter()
+                    .map(|pixel| pixel.map(srgb_to_linear))
+                    .collect();
+                mean_color(&linear, count).map(linear_to_srgb)
+            }
+        }
+    }
+}
+
+/// sRGB 编码值（0-255）转换为线性光（0-1）
+fn srgb_to_linear(value: f64) -> f64 {
+    let v = value / 255.0;
+    if v <= 0.04045 {
+        v / 12.92
+    } else {
+        ((v + 0.055) / 1.055).powf(2.4)
+    }
+}
+
+/// 线性光（0-1）编码为 sRGB 值（0-255），四舍五入到整数
+fn linear_to_srgb(value: f64) -> f64 {
+    let v = value.clamp(0.0, 1.0);
+    let v = if v <= 0.003_130_8 {
+        v * 12.92
+    } else {
+        1.055 * v.powf(1.0 / 2.4) - 0.055
+    };
+    (v * 255.0).round()
+}
+
+/// 双线性采样时采样点坐标的子像素精度（每像素划分的份数）
+pub const BILINEAR_SUBPIXEL_SCALE: usize = 16;
+
+/// 校验相邻LED颜色插值强度（0-1）
+pub fn validate_interpolation_strength(strength: f32) -> anyhow::Result<()> {
+    if !(0.0..=1.0).contains(&strength) {
+        anyhow::bail!("interpolation strength must be between 0 and 1, got {strength}");
+    }
+    Ok(())
+}
+
+/// 由每个LED的多个采样区域计算插值后的LED颜色
+///
+/// LED自身区域的平均色与一个三角形窗口的加权平均色按 `strength` 混合；
+/// 窗口从上一个LED的中心延伸到下一个LED的中心，越靠近本LED中心权重越大。
+fn interpolate_strip_colors(zones: &[LedColor], leds: usize, strength: f32) -> Vec<LedColor> {
+    const ZONES: usize = INTERPOLATION_ZONES_PER_LED;
+    let zone = |i: usize| {
+        zones
+            .get(i)
+            .map_or([0.0; 3], |c| c.get_rgb().map(f32::from))
+    };
+
+    (0..leds)
+        .map(|led| {
+            let own = (led * ZONES..(led + 1) * ZONES).fold([0.0; 3], |sum, i| {
+                let rgb = zone(i);
+                std::array::from_fn(|c| sum[c] + rgb[c] / ZONES as f32)
+            });
+
+            let center = led * ZONES + ZONES / 2;
+            let mut weighted = [0.0; 3];
+            let mut total_weight = 0.0;
+            for i in center.saturating_sub(ZONES - 1)..(center + ZONES).min(zones.len()) {
+                let weight = (ZONES - center.abs_diff(i)) as f32;
+                let rgb = zone(i);
+                for c in 0..3 {
+                    weighted[c] += rgb[c] * weight;
+                }
+                total_weight += weight;
+            }
+
+            let [r, g, b]: [f32; 3] = std::array::from_fn(|c| {
+                let blended = if total_weight > 0.0 {
+                    weighted[c] / total_weight
+                } else {
+                    own[c]
+                };
+                (own[c] * (1.0 - strength) + blended * strength).round()
+            });
+            LedColor::new(r as u8, g as u8, b as u8)
+        })
+        .collect()
+}
+
+/// 读取每个LED采样点的像素并按 `reduction` 合并为LED颜色
+///
+/// `read_pixel` 返回 None 的采样点不参与合并（平均时按黑色计入）
+fn reduce_edge_colors(
+    sample_points_of_leds: &[LedSamplePoints],
+    read_pixel: impl Fn(Point) -> Option<[f64; 3]>,
+    black_level: u8,
+    reduction: ZoneReduction,
+    color_space: AveragingColorSpace,
+) -> Vec<LedColor> {
+    let mut colors = vec![];
+    let mut pixels: Vec<[f64; 3]> = Vec::new();
+    for led_points in sample_points_of_leds {
+        pixels.clear();
+        pixels.extend(led_points.iter().filter_map(|point| read_pixel(*point)));
+
+        let bright: Vec<[f64; 3]> = if black_level > 0 {
+            pixels
+                .iter()
+                .copied()
+                .filter(|&pixel| perceived_luminance(pixel) >= black_level as f64)
+                .collect()
+        } else {
+            Vec::new()
+        };
+
+        let [r, g, b] = match reduction {
+            ZoneReduction::Mean if !bright.is_empty() => {
+                color_space.mean_color(&bright, bright.len())
+            }
+            // 无效的采样点按黑色计入平均
+            ZoneReduction::Mean => color_space.mean_color(&pixels, led_points.len()),
+            ZoneReduction::Max => {
+                // 设置了黑电平时只在亮于黑电平的采样点中取最亮者，全部低于黑电平时为黑色
+                let candidates = if black_level > 0 { &bright } else { &pixels };
+                candidates
+                    .iter()
+                    .copied()
+                    .max_by(|a, b| perceived_luminance(*a).total_cmp(&perceived_luminance(*b)))
+                    .unwrap_or([0.0; 3])
+            }
+            ZoneReduction::Dominant if !bright.is_empty() => dominant_color(&bright),
+            ZoneReduction::Dominant => dominant_color(&pixels),
+        };
+        let color = LedColor::new(r as u8, g as u8, b as u8);
+
+        // Debug: Log sampled colors for troubleshooting
+        if colors.len() < 5 {
+            log::debug!(
+                "🎨 Sampled color for LED {}: RGB({}, {}, {}) from {} sample points",
+                colors.len(),
+                r as u8,
+                g as u8,
+                b as u8,
+                led_points.len()
+            );
+        }
+
+        colors.push(color);
+    }
+    colors
+}
+
+/// 按 `format` 读取位图中指定字节位置的像素，返回 `[r, g, b]`，越界时返回 None
+fn read_bitmap_pixel(bitmap: &[u8], position: usize, format: CaptureFormat) -> Option<[f64; 3]> {
+    let pixel = bitmap.get(position..position + 3)?;
+    Some(format.rgb(pixel).map(f64::from))
+}
+
+/// 按子像素坐标对周围四个像素做双线性插值
+///
+/// `point` 以 `subpixel_scale` 倍的子像素为单位，像素中心位于 `(i + 0.5)` 处，
+/// 靠近图像边缘时使用边缘像素补齐。
+fn bilinear_pixel(
+    bitmap: &[u8],
+    bytes_per_row: usize,
+    width: usize,
+    height: usize,
+    (x, y): Point,
+    subpixel_scale: usize,
+    format: CaptureFormat,
+) -> Option<[f64; 3]> {
+    if width == 0 || height == 0 {
+        return None;
+    }
+    let scale = subpixel_scale.max(1) as f64;
+    let axis = |value: usize, size: usize| {
+        let position = ((value as f64 + 0.5) / scale - 0.5).clamp(0.0, (size - 1) as f64);
+        let start = position.floor() as usize;
+        (start, (start + 1).min(size - 1), position - start as f64)
+    };
+    let (x0, x1, fx) = axis(x, width);
+    let (y0, y1, fy) = axis(y, height);
+
+    let pixel =
+        |px: usize, py: usize| read_bitmap_pixel(bitmap, py * bytes_per_row + px * 4, format);
+    let (top_left, top_right) = (pixel(x0, y0)?, pixel(x1, y0)?);
+    let (bottom_left, bottom_right) = (pixel(x0, y1)?, pixel(x1, y1)?);
+
+    Some(std::array::from_fn(|c| {
+        let top = top_left[c] * (1.0 - fx) + top_right[c] * fx;
+        let bottom = bottom_left[c] * (1.0 - fx) + bottom_right[c] * fx;
+        top * (1.0 - fy) + bottom * fy
+    }))
+}
+
+/// 像素的感知亮度（`0.2126R + 0.7152G + 0.0722B`）
+fn perceived_luminance([r, g, b]: [f64; 3]) -> f64 {
+    0.2126 * r + 0.7152 * g + 0.0722 * b
+}
+
+/// 像素颜色之和除以 `count`，`count` 为 0 时返回黑色
+fn mean_color(pixels: &[[f64; 3]], count: usize) -> [f64; 3] {
+    if count == 0 {
+        return [0.0; 3];
+    }
+    let mut sum = [0.0; 3];
+    for pixel in pixels {
+        for (total, value) in sum.iter_mut().zip(pixel) {
+            *total += value;
+        }
+    }
+    sum.map(|value| value / count as f64)
+}
+
+/// 每个通道量化为 8 级后统计出现次数最多的颜色，返回该颜色所有像素的平均值
+///
+/// 用 512 个桶的直方图一次遍历完成统计；次数相同时取先出现的颜色
+fn dominant_color(pixels: &[[f64; 3]]) -> [f64; 3] {
+    const BUCKETS: usize = 8 * 8 * 8;
+    let bucket_of = |pixel: [f64; 3]| {
+        let [r, g, b] = pixel.map(|value| ((value as u8) >> 5) as usize);
+        (r << 6) | (g << 3) | b
+    };
+
+    let mut counts = [0usize; BUCKETS];
+    let mut sums = [[0.0f64; 3]; BUCKETS];
+    let mut first_seen = [usize::MAX; BUCKETS];
+    for (index, &pixel) in pixels.iter().enumerate() {
+        let bucket = bucket_of(pixel);
+        counts[bucket] += 1;
+        for (total, value) in sums[bucket].iter_mut().zip(pixel) {
+            *total += value;
+        }
+        first_seen[bucket] = first_seen[bucket].min(index);
+    }
+
+    let best = (0..BUCKETS)
+        .filter(|&bucket| counts[bucket] > 0)
+        .min_by_key(|&bucket| (std::cmp::Reverse(counts[bucket]), first_seen[bucket]));
+
+    match best {
+        Some(bucket) => sums[bucket].map(|value| value / counts[bucket] as f64),
+        None => [0.0; 3],
+    }
+}
+
+/// 根据显示器旋转角度，将灯带配置的边框映射到截图中的实际边缘
+///
+/// 灯带安装在面板上，其边框以面板未旋转时的方向描述；
+/// 截图则是系统旋转后的画面。例如旋转90°时，面板的"上"边对应截图的左边缘。
+///
+/// # 返回值
+/// 返回 (截图中的实际边缘, 是否需要反转LED顺序)
+pub fn physical_border_for_rotation(border: Border, rotation: f32) -> (Border, bool) {
+    // 归一化到 0/90/180/270
+    let quarter_turns = ((rotation / 90.0).round() as i32).rem_euclid(4);
+
+    match (quarter_turns, border) {
+        (1, Border::Top) => (Border::Left, true),
+        (1, Border::Left) => (Border::Bottom, false),
+        (1, Border::Bottom) => (Border::Right, true),
+        (1, Border::Right) => (Border::Top, false),
+        (2, Border::Top) => (Border::Bottom, true),
+        (2, Border::Bottom) => (Border::Top, true),
+        (2, Border::Left) => (Border::Right, true),
+        (2, Border::Right) => (Border::Left, true),
+        (3, Border::Top) => (Border::Right, false),
+        (3, Border::Right) => (Border::Bottom, true),
+        (3, Border::Bottom) => (Border::Left, false),
+        (3, Border::Left) => (Border::Top, true),
+        _ => (border, false),
     }
 }
+
+/// 采样区域裁剪（归一化坐标，0-1）
+///
+/// 用于可用画面只占输出画面一部分的显示器（例如投影仪），
+/// 边缘采样以裁剪区域的边缘为准，而不是整个帧缓冲区。
+#[derive(Clone, Copy, Debug, PartialEq, Serialize, Deserialize, ToSchema)]
+pub struct SampleCrop {
+    /// 左上角横坐标
+    pub x: f32,
+    /// 左上角纵坐标
+    pub y: f32,
+    /// 宽度
+    pub width: f32,
+    /// 高度
+    pub height: f32,
+}
+
+impl SampleCrop {
+    /// 校验裁剪区域：宽高必须大于 0，且区域完全位于画面内
+    pub fn validate(&self) -> anyhow::Result<()> {
+        let values = [self.x, self.y, self.width, self.height];
+        if values.iter().any(|v| !v.is_finite())
+            || self.x < 0.0
+            || self.y < 0.0
+            || self.width <= 0.0
+            || self.height <= 0.0
+            || self.x + self.width > 1.0 + f32::EPSILON
+            || self.y + self.height > 1.0 + f32::EPSILON
+        {
+            return Err(anyhow::anyhow!(
+                "Crop must lie within 0-1 with positive size, got x={}, y={}, width={}, height={}",
+                self.x,
+                self.y,
+                self.width,
+                self.height
+            ));
+        }
+        Ok(())
+    }
+}
+
+/// 将裁剪区域换算为像素矩形 (x, y, 宽, 高)，未裁剪时为整幅画面
+fn crop_rect(
+    crop: Option<SampleCrop>,
+    width: usize,
+    height: usize,
+) -> (usize, usize, usize, usize) {
+    let Some(crop) = crop else {
+        return (0, 0, width, height);
+    };
+
+    let x = ((crop.x.clamp(0.0, 1.0) * width as f32) as usize).min(width.saturating_sub(1));
+    let y = ((crop.y.clamp(0.0, 1.0) * height as f32) as usize).min(height.saturating_sub(1));
+    let crop_width = ((crop.width.clamp(0.0, 1.0) * width as f32).round() as usize)
+        .clamp(1, width.saturating_sub(x).max(1));
+    let crop_height = ((crop.height.clamp(0.0, 1.0) * height as f32).round() as usize)
+        .clamp(1, height.saturating_sub(y).max(1));
+    (x, y, crop_width, crop_height)
+}
+
+/// 将边缘采样带从物理边缘向内平移 `inset` 像素
+///
+/// 平移量被限制在采样带仍完整落在 `width`x`height` 区域内
+fn inset_sample_points(
+    points: &mut [LedSamplePoints],
+    border: Border,
+    inset: usize,
+    width: usize,
+    height: usize,
+) {
+    if inset == 0 {
+        return;
+    }
+    let (dx, dy): (isize, isize) = match border {
+        Border::Top => {
+            let inset = inset.min(height - height / 20) as isize;
+            (0, inset)
+        }
+        Border::Bottom => {
+            let inset = inset.min(height - height / 20) as isize;
+            (0, -inset)
+        }
+        Border::Left => {
+            let inset = inset.min(width - width / 20) as isize;
+            (inset, 0)
+        }
+        Border::Right => {
+            let inset = inset.min(width - width / 20) as isize;
+            (-inset, 0)
+        }
+    };
+    for (px, py) in points.iter_mut().flatten() {
+        *px = px.saturating_add_signed(dx);
+        *py = py.saturating_add_signed(dy);
+    }
+}
+
+/// 将裁剪区域内的采样点平移到整幅截图坐标
+fn offset_sample_points(points: &mut [LedSamplePoints], x: usize, y: usize) {
+    if x == 0 && y == 0 {
+        return;
+    }
+    for (px, py) in points.iter_mut().flatten() {
+        *px += x;
+        *py += y;
+    }
+}
+
 type Point = (usize, usize);
 pub type LedSamplePoints = Vec<Point>;
 
+/// 单个LED的采样区域（截图像素坐标），用于在预览上叠加显示
+#[derive(Clone, Debug, PartialEq, Serialize, ToSchema)]
+pub struct LedSampleRegion {
+    /// 包含全部采样点的矩形左上角横坐标
+    pub x: usize,
+    /// 包含全部采样点的矩形左上角纵坐标
+    pub y: usize,
+    /// 矩形宽度（没有采样点时为 0）
+    pub width: usize,
+    /// 矩形高度（没有采样点时为 0）
+    pub height: usize,
+    /// 采样点坐标 `[x, y]`
+    pub points: Vec<[usize; 2]>,
+}
+
+impl LedSampleRegion {
+    pub fn from_points(points: &[Point]) -> Self {
+        let (Some(min_x), Some(max_x)) = (
+            points.iter().map(|p| p.0).min(),
+            points.iter().map(|p| p.0).max(),
+        ) else {
+            return Self {
+                x: 0,
+                y: 0,
+                width: 0,
+                height: 0,
+                points: Vec::new(),
+            };
+        };
+        let min_y = points.iter().map(|p| p.1).min().unwrap_or(0);
+        let max_y = points.iter().map(|p| p.1).max().unwrap_or(0);
+
+        Self {
+            x: min_x,
+            y: min_y,
+            width: max_x - min_x + 1,
+            height: max_y - min_y + 1,
+            points: points.iter().map(|&(x, y)| [x, y]).collect(),
+        }
+    }
+}
+
 #[derive(Clone, Serialize, Deserialize, Debug)]
 pub struct ScreenSamplePoints {
     pub top: Vec<LedSamplePoints>,
@@ -291,42 +1093,591 @@ pub struct ScreenshotPayload {
     pub width: u32,
 }
 
-#[cfg(test)]
-mod tests {
-    use super::*;
-    use crate::ambient_light::Border;
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::ambient_light::Border;
+
+    // Helper function to create a mock LedStripConfig
+    fn mock_led_strip_config(border: Border, len: usize) -> LedStripConfig {
+        LedStripConfig {
+            index: 0,
+            border,
+            display_id: 1,
+            len,
+            led_type: crate::ambient_light::LedType::WS2812B,
+            reversed: false,
+            hardware_offset: None,
+            zone_reduction: crate::ambient_light::ZoneReduction::Mean,
+            color_order: crate::ambient_light::ColorOrder::default(),
+            sample_shape: crate::ambient_light::SampleShape::Grid,
+            sample_inset: 0,
+            start_trim_percent: 0.0,
+            end_trim_percent: 0.0,
+            enabled: true,
+            chain_position: None,
+            source_index: None,
+            source_display_id: None,
+        }
+    }
+
+    #[test]
+    fn test_get_one_edge_sample_points_logic() {
+        let leds = 10;
+        let length = 1000;
+        let width = 100;
+        let single_axis_points = 5;
+
+        let points = Screenshot::get_one_edge_sample_points(
+            width,
+            length,
+            leds,
+            single_axis_points,
+            None,
+            (0.0, 0.0),
+            SampleShape::Grid,
+        );
+
+        // Expect one group of points for each LED
+        assert_eq!(points.len(), leds);
+
+        // The actual logic: samples_per_axis = sqrt(single_axis_points) as usize
+        // For single_axis_points = 5: sqrt(5) = 2.236... = 2 (as usize)
+        // So each LED should have 2 * 2 = 4 points
+        let expected_samples_per_axis = (single_axis_points as f64).sqrt() as usize;
+        let expected_points_per_led = expected_samples_per_axis * expected_samples_per_axis;
+        assert_eq!(points[0].len(), expected_points_per_led);
+    }
+
+    #[test]
+    fn test_sample_shape_layouts() {
+        // 厚度 20、长度 100 的边缘，4 个LED，每个LED 4 个采样点
+        let points_for =
+            |shape| Screenshot::get_one_edge_sample_points(20, 100, 4, 4, None, (0.0, 0.0), shape);
+
+        let grid = points_for(SampleShape::Grid);
+        assert_eq!(grid[0], vec![(6, 5), (18, 5), (6, 15), (18, 15)]);
+        assert_eq!(grid[1], vec![(31, 5), (43, 5), (31, 15), (43, 15)]);
+
+        let line = points_for(SampleShape::Line);
+        assert_eq!(line[0], vec![(3, 10), (9, 10), (15, 10), (21, 10)]);
+        assert_eq!(line[1], vec![(28, 10), (34, 10), (40, 10), (46, 10)]);
+
+        // 首尾LED在靠近拐角的一端沿厚度方向采样，中间LED与 Line 相同
+        let l_shape = points_for(SampleShape::LShape);
+        assert_eq!(l_shape[0], vec![(6, 10), (18, 10), (6, 5), (6, 15)]);
+        assert_eq!(l_shape[1], line[1]);
+        assert_eq!(l_shape[2], line[2]);
+        assert_eq!(l_shape[3], vec![(81, 10), (93, 10), (93, 5), (93, 15)]);
+
+        for shape in [SampleShape::Grid, SampleShape::Line, SampleShape::LShape] {
+            assert!(points_for(shape).iter().all(|led| led.len() == 4));
+        }
+    }
+
+    /// 从左到右的红色渐变（BGRA），返回 (位图, 每行字节数)
+    fn red_gradient_bitmap(width: usize, height: usize) -> (Vec<u8>, usize) {
+        let bytes_per_row = width * 4;
+        let mut bitmap = vec![0; height * bytes_per_row];
+        for y in 0..height {
+            for x in 0..width {
+                let pos = y * bytes_per_row + x * 4;
+                bitmap[pos + 2] = (x * 255 / (width - 1)) as u8;
+                bitmap[pos + 3] = 255;
+            }
+        }
+        (bitmap, bytes_per_row)
+    }
+
+    #[test]
+    fn test_custom_led_positions_on_gradient_edge() {
+        // 顶边为从左到右的红色渐变
+        let width = 100;
+        let height = 100;
+        let (bitmap, bytes_per_row) = red_gradient_bitmap(width, height);
+
+        let config = mock_led_strip_config(Border::Top, 4);
+        let sample = |positions: Option<Vec<f32>>| -> Vec<u8> {
+            let mut led_positions = HashMap::new();
+            if let Some(positions) = positions {
+                led_positions.insert(config.index, positions);
+            }
+            sample_edge_colors_from_rotated_image(
+                &bitmap,
+                width as u32,
+                height as u32,
+                bytes_per_row,
+                &[config],
+                0.0,
+                DEFAULT_SAMPLE_POINTS_PER_LED,
+                0,
+                &led_positions,
+                None,
+                AveragingColorSpace::Gamma,
+                false,
+                false,
+                CaptureFormat::Bgra,
+            )[0]
+            .iter()
+            .map(|color| color.get_rgb()[0])
+            .collect()
+        };
+
+        let uniform = sample(None);
+        // 等间距的位置与均匀划分一致
+        assert_eq!(sample(Some(vec![0.125, 0.375, 0.625, 0.875])), uniform);
+
+        // LED集中在左侧时，前两个LED采样到更靠左（更暗）的颜色
+        let custom = sample(Some(vec![0.05, 0.1, 0.15, 0.95]));
+        assert!(custom[0] < uniform[0]);
+        assert!(custom[1] < uniform[1]);
+        assert!(custom.windows(2).all(|pair| pair[0] < pair[1]));
+
+        // 数量与灯带长度不一致时回退为均匀分布
+        assert_eq!(sample(Some(vec![0.5])), uniform);
+    }
+
+    #[test]
+    fn test_zone_reduction_on_gradient_edge() {
+        let width = 100;
+        let height = 100;
+        let (mut bitmap, bytes_per_row) = red_gradient_bitmap(width, height);
+        // 左侧四分之三为暗红色，右侧保留渐变
+        for y in 0..height {
+            for x in 0..75 {
+                bitmap[y * bytes_per_row + x * 4 + 2] = 40;
+            }
+        }
+
+        let sample = |zone_reduction: ZoneReduction| -> Vec<u8> {
+            let config = LedStripConfig {
+                zone_reduction,
+                color_order: crate::ambient_light::ColorOrder::default(),
+                sample_shape: crate::ambient_light::SampleShape::Grid,
+                sample_inset: 0,
+                start_trim_percent: 0.0,
+                end_trim_percent: 0.0,
+                enabled: true,
+                chain_position: None,
+                ..mock_led_strip_config(Border::Top, 2)
+            };
+            sample_edge_colors_from_rotated_image(
+                &bitmap,
+                width as u32,
+                height as u32,
+                bytes_per_row,
+                &[config],
+                0.0,
+                16,
+                0,
+                &HashMap::new(),
+                None,
+                AveragingColorSpace::Gamma,
+                false,
+                false,
+                CaptureFormat::Bgra,
+            )[0]
+            .iter()
+            .map(|color| color.get_rgb()[0])
+            .collect()
+        };
+
+        // 第二个LED的采样列位于 x = 56, 68, 81, 93
+        let bright = |x: usize| (x * 255 / (width - 1)) as u16;
+        let mean = ((40 * 2 + bright(81) + bright(93)) / 4) as u8;
+
+        // 纯色区域内三种方式结果一致
+        assert_eq!(sample(ZoneReduction::Mean), vec![40, mean]);
+        // 最亮的采样区域决定LED颜色
+        assert_eq!(sample(ZoneReduction::Max), vec![40, bright(93) as u8]);
+        // 一半采样点为暗红色，占多数
+        assert_eq!(sample(ZoneReduction::Dominant), vec![40, 40]);
+    }
+
+    #[test]
+    fn test_dominant_color_prefers_most_frequent_bucket() {
+        let pixels = [
+            [250.0, 10.0, 10.0],
+            [20.0, 200.0, 20.0],
+            [24.0, 210.0, 16.0],
+            [20.0, 220.0, 24.0],
+        ];
+        assert_eq!(dominant_color(&pixels), [64.0 / 3.0, 210.0, 20.0]);
+        assert_eq!(dominant_color(&[]), [0.0; 3]);
+
+        // 次数相同时取先出现的颜色
+        let tied = [
+            [20.0, 20.0, 200.0],
+            [250.0, 10.0, 10.0],
+            [250.0, 12.0, 10.0],
+            [22.0, 20.0, 200.0],
+        ];
+        assert_eq!(dominant_color(&tied), [21.0, 20.0, 200.0]);
+    }
+
+    #[test]
+    fn test_black_level_ignores_dark_pixels() {
+        let width = 8;
+        let bytes_per_row = width * 4;
+        let mut bitmap = vec![0; bytes_per_row];
+
+        // 一行中大部分为接近黑色的像素，只有两个亮橙色像素
+        for x in 0..width {
+            let pos = x * 4;
+            let (r, g, b) = if x == 2 || x == 5 {
+                (255, 128, 0)
+            } else {
+                (10, 10, 12)
+            };
+            bitmap[pos] = b;
+            bitmap[pos + 1] = g;
+            bitmap[pos + 2] = r;
+            bitmap[pos + 3] = 255;
+        }
+        let sample_points: Vec<LedSamplePoints> = vec![(0..width).map(|x| (x, 0)).collect()];
+
+        // 不设阈值时亮色被黑色像素稀释
+        let colors = Screenshot::get_one_edge_colors(
+            &sample_points,
+            &bitmap,
+            bytes_per_row,
+            0,
+            ZoneReduction::Mean,
+            AveragingColorSpace::Gamma,
+            CaptureFormat::Bgra,
+        );
+        assert!(colors[0].get_rgb()[0] < 100);
+
+        // 设置阈值后只平均亮像素
+        let colors = Screenshot::get_one_edge_colors(
+            &sample_points,
+            &bitmap,
+            bytes_per_row,
+            24,
+            ZoneReduction::Mean,
+            AveragingColorSpace::Gamma,
+            CaptureFormat::Bgra,
+        );
+        assert_eq!(colors[0].get_rgb(), [255, 128, 0]);
+
+        // 所有像素都低于阈值时回退为平均全部像素
+        let dark_points: Vec<LedSamplePoints> = vec![vec![(0, 0), (1, 0)]];
+        let colors = Screenshot::get_one_edge_colors(
+            &dark_points,
+            &bitmap,
+            bytes_per_row,
+            24,
+            ZoneReduction::Mean,
+            AveragingColorSpace::Gamma,
+            CaptureFormat::Bgra,
+        );
+        assert_eq!(colors[0].get_rgb(), [10, 10, 12]);
+
+        // 最亮采样点同样受黑电平限制：全部低于阈值时为黑色
+        let colors = Screenshot::get_one_edge_colors(
+            &dark_points,
+            &bitmap,
+            bytes_per_row,
+            24,
+            ZoneReduction::Max,
+            AveragingColorSpace::Gamma,
+            CaptureFormat::Bgra,
+        );
+        assert_eq!(colors[0].get_rgb(), [0, 0, 0]);
+        let colors = Screenshot::get_one_edge_colors(
+            &sample_points,
+            &bitmap,
+            bytes_per_row,
+            24,
+            ZoneReduction::Max,
+            AveragingColorSpace::Gamma,
+            CaptureFormat::Bgra,
+        );
+        assert_eq!(colors[0].get_rgb(), [255, 128, 0]);
+    }
+
+    #[test]
+    fn test_linear_averaging_of_black_and_white_edge() {
+        // 一半黑一半白的边缘
+        let bitmap = [0, 0, 0, 255, 255, 255, 255, 255];
+        let sample_points: Vec<LedSamplePoints> = vec![vec![(0, 0), (1, 0)]];
+        let average = |color_space: AveragingColorSpace| {
+            Screenshot::get_one_edge_colors(
+                &sample_points,
+                &bitmap,
+                bitmap.len(),
+                0,
+                ZoneReduction::Mean,
+                color_space,
+                CaptureFormat::Bgra,
+            )[0]
+            .get_rgb()
+        };
+
+        assert_eq!(average(AveragingColorSpace::Gamma), [127, 127, 127]);
+        assert_eq!(average(AveragingColorSpace::Linear), [188, 188, 188]);
+    }
+
+    #[test]
+    fn test_bilinear_sampling_smooths_coarse_gradient() {
+        // 只有 4 个像素宽的粗糙渐变，16 个LED 会落在同一像素上产生台阶
+        let width = 4;
+        let height = 4;
+        let (bitmap, bytes_per_row) = red_gradient_bitmap(width, height);
+
+        let config = mock_led_strip_config(Border::Top, 16);
+        let sample = |bilinear: bool| -> Vec<i32> {
+            sample_edge_colors_from_rotated_image(
+                &bitmap,
+                width as u32,
+                height as u32,
+                bytes_per_row,
+                &[config],
+                0.0,
+                1,
+                0,
+                &HashMap::new(),
+                None,
+                AveragingColorSpace::Gamma,
+                bilinear,
+                false,
+                CaptureFormat::Bgra,
+            )[0]
+            .iter()
+            .map(|color| color.get_rgb()[0] as i32)
+            .collect()
+        };
+        let max_step = |values: &[i32]| {
+            values
+                .windows(2)
+                .map(|pair| (pair[1] - pair[0]).abs())
+                .max()
+                .unwrap()
+        };
+
+        let nearest = sample(false);
+        let bilinear = sample(true);
+
+        assert!(bilinear.windows(2).all(|pair| pair[0] <= pair[1]));
+        assert!(max_step(&bilinear) < max_step(&nearest));
+    }
+
+    #[test]
+    fn test_half_red_half_blue_screen_averages_to_purple() {
+        let width = 40;
+        let height = 30;
+        let bytes_per_row = width * 4;
+        let mut bitmap = vec![0u8; height * bytes_per_row];
+        for y in 0..height {
+            for x in 0..width {
+                // BGRA：左半红色，右半蓝色
+                let pixel = if x < width / 2 {
+                    [0, 0, 255, 255]
+                } else {
+                    [255, 0, 0, 255]
+                };
+                let position = y * bytes_per_row + x * 4;
+                bitmap[position..position + 4].copy_from_slice(&pixel);
+            }
+        }
+
+        let screenshot = Screenshot::new(
+            1,
+            height as u32,
+            width as u32,
+            bytes_per_row,
+            Arc::new(bitmap),
+            1.0,
+            1.0,
+        );
+        assert_eq!(screenshot.get_average_color().get_rgb(), [127, 0, 127]);
+
+        // 裁剪到左半部分时只剩红色
+        let cropped = screenshot.with_crop(Some(SampleCrop {
+            x: 0.0,
+            y: 0.0,
+            width: 0.5,
+            height: 1.0,
+        }));
+        assert_eq!(cropped.get_average_color().get_rgb(), [255, 0, 0]);
+    }
+
+    #[test]
+    fn test_capture_formats_sample_matching_colors() {
+        let width = 40;
+        let height = 40;
+        let bytes_per_row = width * 4;
+        // 上半部分为偏红色、下半部分为偏蓝色，分别以 BGRA 和 RGBA 字节顺序编码同一画面
+        let encode = |format: CaptureFormat| -> Vec<u8> {
+            let mut bitmap = vec![0; height * bytes_per_row];
+            for y in 0..height {
+                let [r, g, b] = if y < height / 2 {
+                    [220, 40, 10]
+                } else {
+                    [10, 60, 200]
+                };
+                for x in 0..width {
+                    let pos = y * bytes_per_row + x * 4;
+                    let pixel = match format {
+                        CaptureFormat::Bgra => [b, g, r, 255],
+                        CaptureFormat::Rgba => [r, g, b, 255],
+                    };
+                    bitmap[pos..pos + 4].copy_from_slice(&pixel);
+                }
+            }
+            bitmap
+        };
+
+        let configs = [
+            mock_led_strip_config(Border::Top, 4),
+            mock_led_strip_config(Border::Bottom, 4),
+        ];
+        let sample = |bitmap_format: CaptureFormat, format: CaptureFormat| -> Vec<Vec<[u8; 3]>> {
+            sample_edge_colors_from_image(
+                &encode(bitmap_format),
+                width as u32,
+                height as u32,
+                bytes_per_row,
+                &configs,
+                format,
+            )
+            .iter()
+            .map(|strip| strip.iter().map(|color| color.get_rgb()).collect())
+            .collect()
+        };
+
+        let bgra = sample(CaptureFormat::Bgra, CaptureFormat::Bgra);
+        let rgba = sample(CaptureFormat::Rgba, CaptureFormat::Rgba);
+        assert_eq!(bgra, rgba);
+        assert_eq!(bgra[0], vec![[220, 40, 10]; 4]);
+        assert_eq!(bgra[1], vec![[10, 60, 200]; 4]);
+
+        // 格式设置错误时红蓝通道互换
+        let swapped = sample(CaptureFormat::Rgba, CaptureFormat::Bgra);
+        assert_eq!(swapped[0][0], [10, 40, 220]);
+    }
+
+    #[test]
+    fn test_vertical_gradient_sampling_captures_vertical_variation() {
+        let width = 80;
+        let height = 80;
+        let bytes_per_row = width * 4;
+        // 上半部分黑、下半部分白的纵向分级画面
+        let mut bitmap = vec![0; height * bytes_per_row];
+        for y in height / 2..height {
+            for x in 0..width {
+                let pos = y * bytes_per_row + x * 4;
+                bitmap[pos..pos + 4].copy_from_slice(&[255, 255, 255, 255]);
+            }
+        }
+
+        let config = mock_led_strip_config(Border::Left, 4);
+        let sample = |vertical_gradient: bool| -> Vec<u8> {
+            sample_edge_colors_from_rotated_image(
+                &bitmap,
+                width as u32,
+                height as u32,
+                bytes_per_row,
+                &[config],
+                0.0,
+                DEFAULT_SAMPLE_POINTS_PER_LED,
+                0,
+                &HashMap::new(),
+                None,
+                AveragingColorSpace::Gamma,
+                false,
+                vertical_gradient,
+                CaptureFormat::Bgra,
+            )[0]
+            .iter()
+            .map(|color| color.get_rgb()[0])
+            .collect()
+        };
+
+        // 默认每个LED只覆盖自己的区间，分界处是硬台阶
+        assert_eq!(sample(false), vec![0, 0, 255, 255]);
 
-    // Helper function to create a mock LedStripConfig
-    fn mock_led_strip_config(border: Border, len: usize) -> LedStripConfig {
-        LedStripConfig {
-            index: 0,
-            border,
-            display_id: 1,
-            len,
-            led_type: crate::ambient_light::LedType::WS2812B,
-            reversed: false,
-        }
+        // 渐变窗口覆盖相邻LED，分界两侧的LED得到过渡色
+        let gradient = sample(true);
+        assert_eq!(gradient.len(), 4);
+        assert!(gradient.windows(2).all(|pair| pair[0] <= pair[1]));
+        assert_eq!(gradient[0], 0);
+        assert_eq!(gradient[3], 255);
+        assert!(gradient[1] > 0 && gradient[1] < 128);
+        assert!(gradient[2] > 128 && gradient[2] < 255);
+
+        // 左右边之外的边框不受影响
+        let screenshot = Screenshot::new(1, 1080, 1920, 1920 * 4, Arc::new(vec![]), 1.0, 1.0);
+        let top = mock_led_strip_config(Border::Top, 8);
+        assert_eq!(
+            screenshot.get_sample_points(&top),
+            screenshot
+                .clone()
+                .with_vertical_gradient_sampling(true)
+                .get_sample_points(&top)
+        );
+        assert!(screenshot
+            .with_vertical_gradient_sampling(true)
+            .get_sample_points(&mock_led_strip_config(Border::Right, 8))
+            .iter()
+            .all(|led| led.len() == GRADIENT_SAMPLE_ROWS_PER_LED * 2));
     }
 
     #[test]
-    fn test_get_one_edge_sample_points_logic() {
-        let leds = 10;
-        let length = 1000;
-        let width = 100;
-        let single_axis_points = 5;
+    fn test_sample_regions_bound_sample_points() {
+        let screenshot = Screenshot::new(1, 1080, 1920, 1920 * 4, Arc::new(vec![]), 1.0, 1.0);
+        let config = mock_led_strip_config(Border::Right, 10);
 
-        let points =
-            Screenshot::get_one_edge_sample_points(width, length, leds, single_axis_points);
+        let points = screenshot.get_sample_points(&config);
+        let regions = screenshot.get_sample_regions(&config);
+        assert_eq!(regions.len(), points.len());
+
+        for (region, points) in regions.iter().zip(points.iter()) {
+            assert_eq!(region.points.len(), points.len());
+            for &(x, y) in points {
+                assert!((region.x..region.x + region.width).contains(&x));
+                assert!((region.y..region.y + region.height).contains(&y));
+            }
+        }
 
-        // Expect one group of points for each LED
-        assert_eq!(points.len(), leds);
+        let empty = LedSampleRegion::from_points(&[]);
+        assert_eq!((empty.width, empty.height), (0, 0));
+    }
 
-        // The actual logic: samples_per_axis = sqrt(single_axis_points) as usize
-        // For single_axis_points = 5: sqrt(5) = 2.236... = 2 (as usize)
-        // So each LED should have 2 * 2 = 4 points
-        let expected_samples_per_axis = (single_axis_points as f64).sqrt() as usize;
-        let expected_points_per_led = expected_samples_per_axis * expected_samples_per_axis;
-        assert_eq!(points[0].len(), expected_points_per_led);
+    #[test]
+    fn test_sample_points_per_led_setting() {
+        let screenshot = Screenshot::new(1, 1080, 1920, 1920 * 4, Arc::new(vec![]), 1.0, 1.0);
+
+        for points_per_led in [1, 4, 9, 16] {
+            assert!(validate_sample_points_per_led(points_per_led).is_ok());
+
+            let screenshot = screenshot
+                .clone()
+                .with_sample_points_per_led(points_per_led);
+            for border in [Border::Top, Border::Bottom, Border::Left, Border::Right] {
+                let config = mock_led_strip_config(border, 8);
+
+                let points = screenshot.get_sample_points(&config);
+                assert_eq!(points.len(), 8);
+                assert!(points.iter().all(|led| led.len() == points_per_led));
+
+                let points = get_sample_points_for_config(
+                    1920,
+                    1080,
+                    &config,
+                    0.0,
+                    points_per_led,
+                    None,
+                    None,
+                    false,
+                );
+                assert!(points.iter().all(|led| led.len() == points_per_led));
+            }
+        }
+
+        for invalid in [0, 5, 10, 81] {
+            assert!(validate_sample_points_per_led(invalid).is_err());
+        }
     }
 
     #[test]
@@ -500,7 +1851,15 @@ mod tests {
 
         // Test sampling from top-left (should be red)
         let sample_points = vec![vec![(10, 10), (15, 15), (20, 20)]];
-        let colors = Screenshot::get_one_edge_colors(&sample_points, &bitmap, bytes_per_row);
+        let colors = Screenshot::get_one_edge_colors(
+            &sample_points,
+            &bitmap,
+            bytes_per_row,
+            0,
+            ZoneReduction::Mean,
+            AveragingColorSpace::Gamma,
+            CaptureFormat::Bgra,
+        );
         assert_eq!(colors.len(), 1);
         println!("Top-left color (should be red): {:?}", colors[0]);
         let rgb = colors[0].get_rgb();
@@ -510,7 +1869,15 @@ mod tests {
 
         // Test sampling from top-right (should be green)
         let sample_points = vec![vec![(60, 10), (65, 15), (70, 20)]];
-        let colors = Screenshot::get_one_edge_colors(&sample_points, &bitmap, bytes_per_row);
+        let colors = Screenshot::get_one_edge_colors(
+            &sample_points,
+            &bitmap,
+            bytes_per_row,
+            0,
+            ZoneReduction::Mean,
+            AveragingColorSpace::Gamma,
+            CaptureFormat::Bgra,
+        );
         assert_eq!(colors.len(), 1);
         println!("Top-right color (should be green): {:?}", colors[0]);
         let rgb = colors[0].get_rgb();
@@ -543,7 +1910,15 @@ mod tests {
             vec![(5, 5), (6, 6)], // Points for LED 2
         ];
 
-        let colors = Screenshot::get_one_edge_colors(&sample_points, &bitmap, bytes_per_row);
+        let colors = Screenshot::get_one_edge_colors(
+            &sample_points,
+            &bitmap,
+            bytes_per_row,
+            0,
+            ZoneReduction::Mean,
+            AveragingColorSpace::Gamma,
+            CaptureFormat::Bgra,
+        );
 
         assert_eq!(colors.len(), 2);
         // Both LEDs should be solid red
@@ -564,6 +1939,17 @@ mod tests {
             len: 4, // 4 LEDs
             led_type: crate::ambient_light::LedType::WS2812B,
             reversed: false,
+            hardware_offset: None,
+            zone_reduction: crate::ambient_light::ZoneReduction::Mean,
+            color_order: crate::ambient_light::ColorOrder::default(),
+            sample_shape: crate::ambient_light::SampleShape::Grid,
+            sample_inset: 0,
+            start_trim_percent: 0.0,
+            end_trim_percent: 0.0,
+            enabled: true,
+            chain_position: None,
+            source_index: None,
+            source_display_id: None,
         };
 
         let screenshot = Screenshot::new(
@@ -643,6 +2029,9 @@ mod tests {
             edge_length,
             leds,
             single_axis_points,
+            None,
+            (0.0, 0.0),
+            SampleShape::Grid,
         );
 
         // 只在需要详细调试时输出
@@ -712,6 +2101,17 @@ mod tests {
             len: 4,
             led_type: crate::ambient_light::LedType::WS2812B,
             reversed: false,
+            hardware_offset: None,
+            zone_reduction: crate::ambient_light::ZoneReduction::Mean,
+            color_order: crate::ambient_light::ColorOrder::default(),
+            sample_shape: crate::ambient_light::SampleShape::Grid,
+            sample_inset: 0,
+            start_trim_percent: 0.0,
+            end_trim_percent: 0.0,
+            enabled: true,
+            chain_position: None,
+            source_index: None,
+            source_display_id: None,
         };
 
         let bitmap_arc = Arc::new(bitmap.clone());
@@ -727,7 +2127,15 @@ mod tests {
         let sample_points = screenshot.get_sample_points(&config);
 
         // Sample colors using the generated points directly from bitmap
-        let colors = Screenshot::get_one_edge_colors(&sample_points, &bitmap, bytes_per_row);
+        let colors = Screenshot::get_one_edge_colors(
+            &sample_points,
+            &bitmap,
+            bytes_per_row,
+            0,
+            ZoneReduction::Mean,
+            AveragingColorSpace::Gamma,
+            CaptureFormat::Bgra,
+        );
 
         #[cfg(debug_assertions)]
         if std::env::var("RUST_LOG")
@@ -780,6 +2188,17 @@ mod tests {
             len: 4,
             led_type: crate::ambient_light::LedType::WS2812B,
             reversed: false,
+            hardware_offset: None,
+            zone_reduction: crate::ambient_light::ZoneReduction::Mean,
+            color_order: crate::ambient_light::ColorOrder::default(),
+            sample_shape: crate::ambient_light::SampleShape::Grid,
+            sample_inset: 0,
+            start_trim_percent: 0.0,
+            end_trim_percent: 0.0,
+            enabled: true,
+            chain_position: None,
+            source_index: None,
+            source_display_id: None,
         };
 
         // 这个测试需要真实的屏幕截图数据，在CI环境中会跳过
@@ -787,16 +2206,463 @@ mod tests {
         // 注意：这个测试在CI环境中会失败，因为没有显示器
         // 但在开发环境中可以用来验证真实的采样逻辑
     }
+
+    /// 创建四条边缘颜色各不相同的模拟截图（BGRA）
+    /// 上：红，下：白，左：蓝，右：绿
+    fn mock_edge_screenshot(width: usize, height: usize, rotation: f32) -> Screenshot {
+        let bytes_per_row = width * 4;
+        let mut bitmap = vec![0u8; height * bytes_per_row];
+
+        for y in 0..height {
+            for x in 0..width {
+                let bgr = if x < width / 20 {
+                    [255, 0, 0] // 蓝
+                } else if x >= width - width / 20 {
+                    [0, 255, 0] // 绿
+                } else if y < height / 20 {
+                    [0, 0, 255] // 红
+                } else if y >= height - height / 20 {
+                    [255, 255, 255] // 白
+                } else {
+                    [0, 0, 0]
+                };
+                let position = y * bytes_per_row + x * 4;
+                bitmap[position..position + 3].copy_from_slice(&bgr);
+                bitmap[position + 3] = 255;
+            }
+        }
+
+        Screenshot::new(
+            1,
+            height as u32,
+            width as u32,
+            bytes_per_row,
+            Arc::new(bitmap),
+            1.0,
+            1.0,
+        )
+        .with_rotation(rotation)
+    }
+
+    async fn sample_single_led(screenshot: &Screenshot, border: Border) -> [u8; 3] {
+        let colors = screenshot
+            .get_colors_by_led_configs(&[mock_led_strip_config(border, 1)])
+            .await;
+        colors[0][0].get_rgb()
+    }
+
+    #[tokio::test]
+    async fn test_rotation_90_maps_top_strip_to_left_edge() {
+        let screenshot = mock_edge_screenshot(100, 100, 90.0);
+
+        for (border, expected) in [
+            (Border::Top, [0, 0, 255]),
+            (Border::Left, [255, 255, 255]),
+            (Border::Bottom, [0, 255, 0]),
+            (Border::Right, [255, 0, 0]),
+        ] {
+            assert_eq!(
+                sample_single_led(&screenshot, border).await,
+                expected,
+                "rotation 90.0, border {border:?}"
+            );
+        }
+    }
+
+    #[tokio::test]
+    async fn test_rotation_180_swaps_opposite_edges() {
+        let screenshot = mock_edge_screenshot(100, 100, 180.0);
+
+        for (border, expected) in [
+            (Border::Top, [255, 255, 255]),
+            (Border::Bottom, [255, 0, 0]),
+            (Border::Left, [0, 255, 0]),
+            (Border::Right, [0, 0, 255]),
+        ] {
+            assert_eq!(
+                sample_single_led(&screenshot, border).await,
+                expected,
+                "rotation 180.0, border {border:?}"
+            );
+        }
+    }
+
+    #[tokio::test]
+    async fn test_rotation_270_maps_top_strip_to_right_edge() {
+        let screenshot = mock_edge_screenshot(100, 100, 270.0);
+
+        for (border, expected) in [
+            (Border::Top, [0, 255, 0]),
+            (Border::Right, [255, 255, 255]),
+            (Border::Bottom, [0, 0, 255]),
+            (Border::Left, [255, 0, 0]),
+        ] {
+            assert_eq!(
+                sample_single_led(&screenshot, border).await,
+                expected,
+                "rotation 270.0, border {border:?}"
+            );
+        }
+    }
+
+    #[test]
+    fn test_rotation_keeps_led_order_following_panel_edge() {
+        let screenshot = mock_edge_screenshot(1920, 1080, 90.0);
+
+        // 旋转90°后，面板上边从左到右对应截图左边缘从下到上
+        let points = screenshot.get_sample_points(&mock_led_strip_config(Border::Top, 2));
+        assert_eq!(points.len(), 2);
+        assert!(points[0].iter().all(|(x, y)| *x < 96 && *y >= 540));
+        assert!(points[1].iter().all(|(x, y)| *x < 96 && *y < 540));
+
+        // 旋转0°时保持原有映射
+        let screenshot = mock_edge_screenshot(1920, 1080, 0.0);
+        let points = screenshot.get_sample_points(&mock_led_strip_config(Border::Top, 2));
+        assert!(points[0].iter().all(|(x, y)| *y < 54 && *x < 960));
+    }
+
+    /// 将四边着色的画面放入更大的灰色帧缓冲区中央（模拟投影仪的可用区域）
+    fn mock_letterboxed_screenshot(
+        width: usize,
+        height: usize,
+        (offset_x, offset_y): (usize, usize),
+        content: &Screenshot,
+    ) -> Screenshot {
+        let bytes_per_row = width * 4;
+        let mut bitmap = vec![128u8; height * bytes_per_row];
+        let content_width = content.width as usize;
+        for row in 0..content.height as usize {
+            let src = row * content.bytes_per_row;
+            let dst = (row + offset_y) * bytes_per_row + offset_x * 4;
+            bitmap[dst..dst + content_width * 4]
+                .copy_from_slice(&content.bytes[src..src + content_width * 4]);
+        }
+
+        Screenshot::new(
+            1,
+            height as u32,
+            width as u32,
+            bytes_per_row,
+            Arc::new(bitmap),
+            1.0,
+            1.0,
+        )
+    }
+
+    #[test]
+    fn test_crop_offsets_and_scales_sample_points() {
+        // 200x100 的帧缓冲区，可用区域为 (50, 50) 起的 100x50
+        let screenshot = mock_edge_screenshot(200, 100, 0.0).with_crop(Some(SampleCrop {
+            x: 0.25,
+            y: 0.5,
+            width: 0.5,
+            height: 0.5,
+        }));
+
+        let top = screenshot.get_sample_points(&mock_led_strip_config(Border::Top, 2));
+        assert!(top[0]
+            .iter()
+            .all(|(x, y)| (50..100).contains(x) && (50..52).contains(y)));
+        assert!(top[1]
+            .iter()
+            .all(|(x, y)| (100..150).contains(x) && (50..52).contains(y)));
+
+        let bottom = screenshot.get_sample_points(&mock_led_strip_config(Border::Bottom, 2));
+        assert!(bottom.iter().flatten().all(|(_, y)| (97..100).contains(y)));
+
+        let left = screenshot.get_sample_points(&mock_led_strip_config(Border::Left, 2));
+        assert!(left.iter().flatten().all(|(x, _)| (50..55).contains(x)));
+        assert!(left[0].iter().all(|(_, y)| (50..75).contains(y)));
+        assert!(left[1].iter().all(|(_, y)| (75..100).contains(y)));
+
+        let right = screenshot.get_sample_points(&mock_led_strip_config(Border::Right, 2));
+        assert!(right.iter().flatten().all(|(x, _)| (145..150).contains(x)));
+
+        // 未裁剪时仍使用整幅画面
+        let screenshot = screenshot.with_crop(None);
+        let top = screenshot.get_sample_points(&mock_led_strip_config(Border::Top, 2));
+        assert!(top[0].iter().all(|(x, y)| *x < 100 && *y < 5));
+    }
+
+    #[test]
+    fn test_scale_factor_override_scales_sample_points() {
+        // 检测到的缩放比例为 2，但帧缓冲区左上角 200x100 才是实际画面（实际缩放比例为 1）
+        let mut screenshot = mock_edge_screenshot(400, 200, 0.0);
+        screenshot.scale_factor = 2.0;
+        screenshot.bound_scale_factor = 2.0;
+
+        let detected = screenshot.get_sample_points(&mock_led_strip_config(Border::Right, 2));
+        let screenshot = screenshot.with_scale_factor_override(Some(1.0));
+        assert_eq!(screenshot.effective_scale_factor(), 1.0);
+        let overridden = screenshot.get_sample_points(&mock_led_strip_config(Border::Right, 2));
+
+        // 右边缘从 x=399 移到 x=199，沿边缘的坐标按同一比例缩小
+        assert!(detected
+            .iter()
+            .flatten()
+            .all(|(x, _)| (380..400).contains(x)));
+        assert!(overridden
+            .iter()
+            .flatten()
+            .all(|(x, _)| (190..200).contains(x)));
+        assert!(overridden[0].iter().all(|(_, y)| *y < 50));
+        assert!(overridden[1].iter().all(|(_, y)| (50..100).contains(y)));
+
+        // 覆盖值大于检测值时不超出帧缓冲区
+        let screenshot = screenshot.with_scale_factor_override(Some(4.0));
+        let points = screenshot.get_sample_points(&mock_led_strip_config(Border::Bottom, 2));
+        assert!(points
+            .iter()
+            .flatten()
+            .all(|(x, y)| *x < 400 && (190..200).contains(y)));
+    }
+
+    #[tokio::test]
+    async fn test_scale_factor_override_applies_to_sampled_colors() {
+        // 检测到的缩放比例为 2，实际画面只占 400x200 帧缓冲区左上角的 200x100
+        let content = mock_edge_screenshot(200, 100, 0.0);
+        let mut screenshot = mock_letterboxed_screenshot(400, 200, (0, 0), &content);
+        screenshot.scale_factor = 2.0;
+        screenshot.bound_scale_factor = 2.0;
+
+        // 未覆盖时右边缘和底边落在画面以外的灰色区域
+        assert_eq!(
+            sample_single_led(&screenshot, Border::Right).await,
+            [128, 128, 128]
+        );
+
+        let screenshot = screenshot.with_scale_factor_override(Some(1.0));
+        assert_eq!(
+            sample_single_led(&screenshot, Border::Right).await,
+            [0, 255, 0]
+        );
+        assert_eq!(
+            sample_single_led(&screenshot, Border::Bottom).await,
+            [255, 255, 255]
+        );
+
+        // 双线性采样同样只在实际画面内取点
+        let screenshot = screenshot.with_bilinear_sampling(true);
+        assert_eq!(
+            sample_single_led(&screenshot, Border::Right).await,
+            [0, 255, 0]
+        );
+    }
+
+    #[test]
+    fn test_sample_inset_shifts_edge_band() {
+        let screenshot = mock_edge_screenshot(400, 200, 0.0);
+        let rows = |points: &[LedSamplePoints]| {
+            points.iter().flatten().map(|(_, y)| *y).collect::<Vec<_>>()
+        };
+
+        let top = mock_led_strip_config(Border::Top, 4);
+        let inset_top = LedStripConfig {
+            sample_inset: 24,
+            ..top
+        };
+        let edge = rows(&screenshot.get_sample_points(&top));
+        let inset = rows(&screenshot.get_sample_points(&inset_top));
+        assert_eq!(
+            inset,
+            edge.iter().map(|y| y + 24).collect::<Vec<_>>(),
+            "sampled rows move away from the top edge by the inset"
+        );
+
+        let bottom = mock_led_strip_config(Border::Bottom, 4);
+        let inset_bottom = LedStripConfig {
+            sample_inset: 24,
+            ..bottom
+        };
+        let edge = rows(&screenshot.get_sample_points(&bottom));
+        let inset = rows(&screenshot.get_sample_points(&inset_bottom));
+        assert_eq!(inset, edge.iter().map(|y| y - 24).collect::<Vec<_>>());
+
+        // 超出范围的内缩被限制在截图内
+        let oversized = LedStripConfig {
+            sample_inset: 10_000,
+            ..bottom
+        };
+        assert!(rows(&screenshot.get_sample_points(&oversized))
+            .iter()
+            .all(|y| *y < 200));
+    }
+
+    #[test]
+    fn test_edge_trim_limits_leds_to_strip_extent() {
+        let screenshot = mock_edge_screenshot(1000, 200, 0.0);
+        let columns = |points: &[LedSamplePoints]| {
+            points.iter().flatten().map(|(x, _)| *x).collect::<Vec<_>>()
+        };
+
+        let top = mock_led_strip_config(Border::Top, 8);
+        let trimmed_top = LedStripConfig {
+            start_trim_percent: 10.0,
+            end_trim_percent: 10.0,
+            ..top
+        };
+        let points = screenshot.get_sample_points(&trimmed_top);
+        assert_eq!(points.len(), 8);
+        let xs = columns(&points);
+        assert!(
+            xs.iter().all(|x| (100..900).contains(x)),
+            "LEDs sample only the central 80% of the edge: {xs:?}"
+        );
+        // 首尾LED紧贴裁剪边界，8个LED均分 800 像素
+        assert!(columns(&points[..1]).iter().all(|x| *x < 200));
+        assert!(columns(&points[7..]).iter().all(|x| *x >= 800));
+
+        // 不裁剪时覆盖整条边
+        let xs = columns(&screenshot.get_sample_points(&top));
+        assert!(xs.iter().any(|x| *x < 100) && xs.iter().any(|x| *x >= 900));
+
+        // 只裁剪一端
+        let start_only = LedStripConfig {
+            start_trim_percent: 25.0,
+            ..top
+        };
+        let xs = columns(&screenshot.get_sample_points(&start_only));
+        assert!(xs.iter().all(|x| *x >= 250));
+        assert!(xs.iter().any(|x| *x >= 900));
+    }
+
+    #[tokio::test]
+    async fn test_crop_samples_content_edges() {
+        let content = mock_edge_screenshot(100, 100, 0.0);
+        let framebuffer = mock_letterboxed_screenshot(200, 200, (50, 50), &content);
+
+        // 未裁剪时采样到灰色边框
+        assert_eq!(
+            sample_single_led(&framebuffer, Border::Top).await,
+            [128, 128, 128]
+        );
+
+        let screenshot = framebuffer.with_crop(Some(SampleCrop {
+            x: 0.25,
+            y: 0.25,
+            width: 0.5,
+            height: 0.5,
+        }));
+        for (border, expected) in [
+            (Border::Top, [255, 0, 0]),
+            (Border::Bottom, [255, 255, 255]),
+            (Border::Left, [0, 0, 255]),
+            (Border::Right, [0, 255, 0]),
+        ] {
+            assert_eq!(
+                sample_single_led(&screenshot, border).await,
+                expected,
+                "crop border {border:?}"
+            );
+        }
+    }
+
+    #[test]
+    fn test_crop_validation() {
+        let crop = |x, y, width, height| SampleCrop {
+            x,
+            y,
+            width,
+            height,
+        };
+        assert!(crop(0.0, 0.0, 1.0, 1.0).validate().is_ok());
+        assert!(crop(0.1, 0.2, 0.8, 0.7).validate().is_ok());
+        assert!(crop(0.5, 0.0, 0.6, 1.0).validate().is_err());
+        assert!(crop(-0.1, 0.0, 0.5, 0.5).validate().is_err());
+        assert!(crop(0.0, 0.0, 0.0, 0.5).validate().is_err());
+        assert!(crop(0.0, 0.0, f32::NAN, 0.5).validate().is_err());
+    }
+
+    /// 水平阶梯渐变：红色分量按 0/80/160/240 分为等宽的四段
+    fn mock_staircase_screenshot(width: usize, height: usize) -> Screenshot {
+        let bytes_per_row = width * 4;
+        let mut bitmap = vec![0u8; height * bytes_per_row];
+        for y in 0..height {
+            for x in 0..width {
+                let red = (x * 4 / width) as u8 * 80;
+                let position = y * bytes_per_row + x * 4;
+                bitmap[position..position + 4].copy_from_slice(&[0, 0, red, 255]);
+            }
+        }
+
+        Screenshot::new(
+            1,
+            height as u32,
+            width as u32,
+            bytes_per_row,
+            Arc::new(bitmap),
+            1.0,
+            1.0,
+        )
+    }
+
+    fn max_adjacent_red_step(colors: &[LedColor]) -> u8 {
+        colors
+            .windows(2)
+            .map(|pair| pair[0].get_rgb()[0].abs_diff(pair[1].get_rgb()[0]))
+            .max()
+            .unwrap_or(0)
+    }
+
+    #[tokio::test]
+    async fn test_interpolation_smooths_staircase_gradient() {
+        let configs = [mock_led_strip_config(Border::Top, 8)];
+        let screenshot = mock_staircase_screenshot(120, 60);
+
+        let raw = screenshot.get_colors_by_led_configs(&configs).await;
+        assert_eq!(max_adjacent_red_step(&raw[0]), 80);
+
+        let disabled = screenshot
+            .clone()
+            .with_interpolation_strength(0.0)
+            .get_colors_by_led_configs(&configs)
+            .await;
+        assert_eq!(
+            disabled[0]
+                .iter()
+                .map(LedColor::get_rgb)
+                .collect::<Vec<_>>(),
+            raw[0].iter().map(LedColor::get_rgb).collect::<Vec<_>>()
+        );
+
+        let smoothed = screenshot
+            .with_interpolation_strength(1.0)
+            .get_colors_by_led_configs(&configs)
+            .await;
+        assert_eq!(smoothed[0].len(), 8);
+        assert!(
+            max_adjacent_red_step(&smoothed[0]) < 80,
+            "interpolated colors: {:?}",
+            smoothed[0]
+                .iter()
+                .map(LedColor::get_rgb)
+                .collect::<Vec<_>>()
+        );
+        // 渐变方向保持不变
+        assert!(smoothed[0]
+            .windows(2)
+            .all(|pair| pair[0].get_rgb()[0] <= pair[1].get_rgb()[0]));
+    }
+
+    #[test]
+    fn test_interpolation_strength_validation() {
+        assert!(validate_interpolation_strength(0.0).is_ok());
+        assert!(validate_interpolation_strength(1.0).is_ok());
+        assert!(validate_interpolation_strength(-0.1).is_err());
+        assert!(validate_interpolation_strength(1.5).is_err());
+        assert!(validate_interpolation_strength(f32::NAN).is_err());
+    }
 }
 
 /// 从图像数据中采样指定边缘指定范围的颜色数据
 ///
 /// # 参数
-/// * `image_data` - 图像的原始字节数据 (BGRA格式，每像素4字节)
+/// * `image_data` - 图像的原始字节数据 (每像素4字节，字节顺序由 `capture_format` 指定)
 /// * `width` - 图像宽度
 /// * `height` - 图像高度
 /// * `bytes_per_row` - 每行字节数
 /// * `led_configs` - LED灯带配置数组
+/// * `capture_format` - 像素字节顺序 (BGRA/RGBA)
 ///
 /// # 返回值
 /// 返回与LED灯带配置数组对应的颜色数据数组（有序、二维）
@@ -807,12 +2673,71 @@ pub fn sample_edge_colors_from_image(
     height: u32,
     bytes_per_row: usize,
     led_configs: &[LedStripConfig],
+    capture_format: CaptureFormat,
+) -> Vec<Vec<LedColor>> {
+    sample_edge_colors_from_rotated_image(
+        image_data,
+        width,
+        height,
+        bytes_per_row,
+        led_configs,
+        0.0,
+        DEFAULT_SAMPLE_POINTS_PER_LED,
+        0,
+        &HashMap::new(),
+        None,
+        AveragingColorSpace::Gamma,
+        false,
+        false,
+        capture_format,
+    )
+}
+
+/// 从旋转后的显示器图像中采样边缘颜色
+///
+/// 与 [`sample_edge_colors_from_image`] 相同，但会根据 `rotation`（度）
+/// 将灯带边框映射到截图中的实际边缘，并使用 `sample_points_per_led` 个采样点，
+/// 感知亮度低于 `black_level` 的像素不参与平均，`led_positions` 中配置了位置的灯带按位置采样，
+/// 设置了 `crop` 时边框映射到裁剪区域的边缘，取平均时使用 `color_space` 颜色空间，
+/// `bilinear` 为 true 时按子像素位置双线性插值读取像素，
+/// `vertical_gradient` 为 true 时左右边按纵向渐变窗口采样，像素按 `capture_format` 的字节顺序读取
+#[allow(clippy::too_many_arguments)]
+pub fn sample_edge_colors_from_rotated_image(
+    image_data: &[u8],
+    width: u32,
+    height: u32,
+    bytes_per_row: usize,
+    led_configs: &[LedStripConfig],
+    rotation: f32,
+    sample_points_per_led: usize,
+    black_level: u8,
+    led_positions: &HashMap<usize, Vec<f32>>,
+    crop: Option<SampleCrop>,
+    color_space: AveragingColorSpace,
+    bilinear: bool,
+    vertical_gradient: bool,
+    capture_format: CaptureFormat,
 ) -> Vec<Vec<LedColor>> {
     let mut result = Vec::new();
 
     // 为每个LED灯带配置生成颜色数据
     for config in led_configs {
-        let colors = sample_colors_for_led_strip(image_data, width, height, bytes_per_row, config);
+        let colors = sample_colors_for_led_strip(
+            image_data,
+            width,
+            height,
+            bytes_per_row,
+            config,
+            rotation,
+            sample_points_per_led,
+            black_level,
+            led_positions.get(&config.index).map(Vec::as_slice),
+            crop,
+            color_space,
+            bilinear,
+            vertical_gradient,
+            capture_format,
+        );
         result.push(colors);
     }
 
@@ -820,18 +2745,116 @@ pub fn sample_edge_colors_from_image(
 }
 
 /// 为单个LED灯带采样颜色数据
+#[allow(clippy::too_many_arguments)]
 fn sample_colors_for_led_strip(
     image_data: &[u8],
     width: u32,
     height: u32,
     bytes_per_row: usize,
     config: &LedStripConfig,
+    rotation: f32,
+    sample_points_per_led: usize,
+    black_level: u8,
+    led_positions: Option<&[f32]>,
+    crop: Option<SampleCrop>,
+    color_space: AveragingColorSpace,
+    bilinear: bool,
+    vertical_gradient: bool,
+    capture_format: CaptureFormat,
 ) -> Vec<LedColor> {
+    if bilinear {
+        // 在放大 BILINEAR_SUBPIXEL_SCALE 倍的坐标系中生成采样点以保留子像素位置
+        let scale = BILINEAR_SUBPIXEL_SCALE;
+        let scaled_config = LedStripConfig {
+            sample_inset: config.sample_inset * scale,
+            ..*config
+        };
+        let sample_points = get_sample_points_for_config(
+            width as usize * scale,
+            height as usize * scale,
+            &scaled_config,
+            rotation,
+            sample_points_per_led,
+            led_positions,
+            crop,
+            vertical_gradient,
+        );
+
+        return Screenshot::get_one_edge_colors_bilinear(
+            &sample_points,
+            image_data,
+            bytes_per_row,
+            width as usize,
+            height as usize,
+            scale,
+            black_level,
+            config.zone_reduction,
+            color_space,
+            capture_format,
+        );
+    }
+
     // 直接使用采样点生成逻辑，避免创建临时Screenshot对象和数据复制
-    let sample_points = get_sample_points_for_config(width as usize, height as usize, config);
+    let sample_points = get_sample_points_for_config(
+        width as usize,
+        height as usize,
+        config,
+        rotation,
+        sample_points_per_led,
+        led_positions,
+        crop,
+        vertical_gradient,
+    );
 
     // 使用现有的颜色采样逻辑
-    Screenshot::get_one_edge_colors(&sample_points, image_data, bytes_per_row)
+    Screenshot::get_one_edge_colors(
+        &sample_points,
+        image_data,
+        bytes_per_row,
+        black_level,
+        config.zone_reduction,
+        color_space,
+        capture_format,
+    )
+}
+
+/// 将配置的LED位置转换为采样方向上的位置
+///
+/// 数量与灯带长度不一致时回退为均匀分布；旋转导致采样方向反转时，
+/// 位置需要镜像并倒序，使反转后的结果仍与灯带LED顺序一致。
+fn sampling_led_positions(
+    led_positions: Option<&[f32]>,
+    leds: usize,
+    reverse_order: bool,
+) -> Option<Vec<f32>> {
+    let positions = led_positions?;
+    if positions.len() != leds {
+        log::warn!(
+            "LED positions count {} does not match strip length {}, using uniform spacing",
+            positions.len(),
+            leds
+        );
+        return None;
+    }
+
+    if reverse_order {
+        Some(positions.iter().rev().map(|p| 1.0 - p).collect())
+    } else {
+        Some(positions.to_vec())
+    }
+}
+
+/// 将灯带的首尾裁剪百分比转换为采样方向上的比例（0-1）
+///
+/// 旋转导致采样方向反转时首尾互换，使裁剪仍对应配置中描述的边缘两端。
+fn edge_trim(config: &LedStripConfig, reverse_order: bool) -> (f64, f64) {
+    let start = config.start_trim_percent.clamp(0.0, 100.0) as f64 / 100.0;
+    let end = config.end_trim_percent.clamp(0.0, 100.0) as f64 / 100.0;
+    if reverse_order {
+        (end, start)
+    } else {
+        (start, end)
+    }
 }
 
 /// 为指定配置生成采样点（独立函数，避免创建临时对象）
@@ -839,22 +2862,36 @@ fn get_sample_points_for_config(
     width: usize,
     height: usize,
     config: &LedStripConfig,
+    rotation: f32,
+    sample_points_per_led: usize,
+    led_positions: Option<&[f32]>,
+    crop: Option<SampleCrop>,
+    vertical_gradient: bool,
 ) -> Vec<LedSamplePoints> {
-    const SINGLE_AXIS_POINTS: usize = 5;
+    let (crop_x, crop_y, width, height) = crop_rect(crop, width, height);
+    let (border, reverse_order) = physical_border_for_rotation(config.border, rotation);
+    let positions = sampling_led_positions(led_positions, config.len, reverse_order);
+    let trim = edge_trim(config, reverse_order);
 
-    match config.border {
+    let mut points = match border {
         crate::ambient_light::Border::Top => Screenshot::get_one_edge_sample_points(
             height / 20,
             width,
             config.len,
-            SINGLE_AXIS_POINTS,
+            sample_points_per_led,
+            positions.as_deref(),
+            trim,
+            config.sample_shape,
         ),
         crate::ambient_light::Border::Bottom => {
             let points = Screenshot::get_one_edge_sample_points(
                 height / 20,
                 width,
                 config.len,
-                SINGLE_AXIS_POINTS,
+                sample_points_per_led,
+                positions.as_deref(),
+                trim,
+                config.sample_shape,
             );
             points
                 .into_iter()
@@ -867,11 +2904,14 @@ fn get_sample_points_for_config(
                 .collect()
         }
         crate::ambient_light::Border::Left => {
-            let points = Screenshot::get_one_edge_sample_points(
+            let points = side_edge_sample_points(
                 width / 20,
                 height,
-                config.len,
-                SINGLE_AXIS_POINTS,
+                config,
+                sample_points_per_led,
+                positions.as_deref(),
+                trim,
+                vertical_gradient,
             );
             points
                 .into_iter()
@@ -879,11 +2919,14 @@ fn get_sample_points_for_config(
                 .collect()
         }
         crate::ambient_light::Border::Right => {
-            let points = Screenshot::get_one_edge_sample_points(
+            let points = side_edge_sample_points(
                 width / 20,
                 height,
-                config.len,
-                SINGLE_AXIS_POINTS,
+                config,
+                sample_points_per_led,
+                positions.as_deref(),
+                trim,
+                vertical_gradient,
             );
             points
                 .into_iter()
@@ -895,7 +2938,15 @@ fn get_sample_points_for_config(
                 })
                 .collect()
         }
+    };
+
+    inset_sample_points(&mut points, border, config.sample_inset, width, height);
+    if reverse_order {
+        points.reverse();
     }
+    offset_sample_points(&mut points, crop_x, crop_y);
+
+    points
 }
 
 #[cfg(test)]
@@ -933,6 +2984,17 @@ mod color_sampling_tests {
                 len: 10, // 10个LED
                 led_type: LedType::WS2812B,
                 reversed: false,
+                hardware_offset: None,
+                zone_reduction: crate::ambient_light::ZoneReduction::Mean,
+                color_order: crate::ambient_light::ColorOrder::default(),
+                sample_shape: crate::ambient_light::SampleShape::Grid,
+                sample_inset: 0,
+                start_trim_percent: 0.0,
+                end_trim_percent: 0.0,
+                enabled: true,
+                chain_position: None,
+                source_index: None,
+                source_display_id: None,
             },
             // 底部灯带 - 应该采样到绿色
             LedStripConfig {
@@ -942,6 +3004,17 @@ mod color_sampling_tests {
                 len: 10, // 10个LED
                 led_type: LedType::WS2812B,
                 reversed: false,
+                hardware_offset: None,
+                zone_reduction: crate::ambient_light::ZoneReduction::Mean,
+                color_order: crate::ambient_light::ColorOrder::default(),
+                sample_shape: crate::ambient_light::SampleShape::Grid,
+                sample_inset: 0,
+                start_trim_percent: 0.0,
+                end_trim_percent: 0.0,
+                enabled: true,
+                chain_position: None,
+                source_index: None,
+                source_display_id: None,
             },
             // 左侧灯带 - 应该采样到蓝色
             LedStripConfig {
@@ -951,6 +3024,17 @@ mod color_sampling_tests {
                 len: 6, // 6个LED
                 led_type: LedType::WS2812B,
                 reversed: false,
+                hardware_offset: None,
+                zone_reduction: crate::ambient_light::ZoneReduction::Mean,
+                color_order: crate::ambient_light::ColorOrder::default(),
+                sample_shape: crate::ambient_light::SampleShape::Grid,
+                sample_inset: 0,
+                start_trim_percent: 0.0,
+                end_trim_percent: 0.0,
+                enabled: true,
+                chain_position: None,
+                source_index: None,
+                source_display_id: None,
             },
             // 右侧灯带 - 应该采样到黄色
             LedStripConfig {
@@ -960,6 +3044,17 @@ mod color_sampling_tests {
                 len: 6, // 6个LED
                 led_type: LedType::WS2812B,
                 reversed: false,
+                hardware_offset: None,
+                zone_reduction: crate::ambient_light::ZoneReduction::Mean,
+                color_order: crate::ambient_light::ColorOrder::default(),
+                sample_shape: crate::ambient_light::SampleShape::Grid,
+                sample_inset: 0,
+                start_trim_percent: 0.0,
+                end_trim_percent: 0.0,
+                enabled: true,
+                chain_position: None,
+                source_index: None,
+                source_display_id: None,
             },
         ]
     }
@@ -1010,8 +3105,14 @@ mod color_sampling_tests {
         let led_configs = create_test_led_configs();
 
         // 执行颜色采样
-        let sampled_colors =
-            sample_edge_colors_from_image(&image_data, width, height, bytes_per_row, &led_configs);
+        let sampled_colors = sample_edge_colors_from_image(
+            &image_data,
+            width,
+            height,
+            bytes_per_row,
+            &led_configs,
+            CaptureFormat::Bgra,
+        );
 
         // 验证结果
         assert_eq!(sampled_colors.len(), 4, "应该有4个LED灯带的颜色数据");
@@ -1169,10 +3270,27 @@ mod color_sampling_tests {
             len: 5,
             led_type: LedType::WS2812B,
             reversed: false,
+            hardware_offset: None,
+            zone_reduction: crate::ambient_light::ZoneReduction::Mean,
+            color_order: crate::ambient_light::ColorOrder::default(),
+            sample_shape: crate::ambient_light::SampleShape::Grid,
+            sample_inset: 0,
+            start_trim_percent: 0.0,
+            end_trim_percent: 0.0,
+            enabled: true,
+            chain_position: None,
+            source_index: None,
+            source_display_id: None,
         }];
 
-        let sampled_colors =
-            sample_edge_colors_from_image(&image_data, width, height, bytes_per_row, &top_config);
+        let sampled_colors = sample_edge_colors_from_image(
+            &image_data,
+            width,
+            height,
+            bytes_per_row,
+            &top_config,
+            CaptureFormat::Bgra,
+        );
 
         assert_eq!(sampled_colors.len(), 1);
         assert_eq!(sampled_colors[0].len(), 5);
@@ -1219,8 +3337,14 @@ mod color_sampling_tests {
         let led_configs = create_test_led_configs();
 
         // 测试新的采样函数
-        let colors_by_strips =
-            sample_edge_colors_from_image(&image_data, width, height, bytes_per_row, &led_configs);
+        let colors_by_strips = sample_edge_colors_from_image(
+            &image_data,
+            width,
+            height,
+            bytes_per_row,
+            &led_configs,
+            CaptureFormat::Bgra,
+        );
 
         // 验证返回的数据结构
         assert_eq!(colors_by_strips.len(), 4, "应该有4个LED灯带的颜色数据");
@@ -1258,6 +3382,17 @@ mod color_sampling_tests {
                 len: 5,
                 led_type: LedType::WS2812B,
                 reversed: false,
+                hardware_offset: None,
+                zone_reduction: crate::ambient_light::ZoneReduction::Mean,
+                color_order: crate::ambient_light::ColorOrder::default(),
+                sample_shape: crate::ambient_light::SampleShape::Grid,
+                sample_inset: 0,
+                start_trim_percent: 0.0,
+                end_trim_percent: 0.0,
+                enabled: true,
+                chain_position: None,
+                source_index: None,
+                source_display_id: None,
             },
             LedStripConfig {
                 index: 1,
@@ -1266,6 +3401,17 @@ mod color_sampling_tests {
                 len: 5,
                 led_type: LedType::WS2812B,
                 reversed: false,
+                hardware_offset: None,
+                zone_reduction: crate::ambient_light::ZoneReduction::Mean,
+                color_order: crate::ambient_light::ColorOrder::default(),
+                sample_shape: crate::ambient_light::SampleShape::Grid,
+                sample_inset: 0,
+                start_trim_percent: 0.0,
+                end_trim_percent: 0.0,
+                enabled: true,
+                chain_position: None,
+                source_index: None,
+                source_display_id: None,
             },
             // 显示器2的灯带
             LedStripConfig {
@@ -1275,6 +3421,17 @@ mod color_sampling_tests {
                 len: 5,
                 led_type: LedType::WS2812B,
                 reversed: false,
+                hardware_offset: None,
+                zone_reduction: crate::ambient_light::ZoneReduction::Mean,
+                color_order: crate::ambient_light::ColorOrder::default(),
+                sample_shape: crate::ambient_light::SampleShape::Grid,
+                sample_inset: 0,
+                start_trim_percent: 0.0,
+                end_trim_percent: 0.0,
+                enabled: true,
+                chain_position: None,
+                source_index: None,
+                source_display_id: None,
             },
             LedStripConfig {
                 index: 3,
@@ -1283,6 +3440,17 @@ mod color_sampling_tests {
                 len: 5,
                 led_type: LedType::WS2812B,
                 reversed: false,
+                hardware_offset: None,
+                zone_reduction: crate::ambient_light::ZoneReduction::Mean,
+                color_order: crate::ambient_light::ColorOrder::default(),
+                sample_shape: crate::ambient_light::SampleShape::Grid,
+                sample_inset: 0,
+                start_trim_percent: 0.0,
+                end_trim_percent: 0.0,
+                enabled: true,
+                chain_position: None,
+                source_index: None,
+                source_display_id: None,
             },
         ];
 
@@ -1306,6 +3474,7 @@ mod color_sampling_tests {
             height,
             bytes_per_row,
             &display_1_strips,
+            CaptureFormat::Bgra,
         );
 
         // 测试显示器2的采样
@@ -1315,6 +3484,7 @@ mod color_sampling_tests {
             height,
             bytes_per_row,
             &display_2_strips,
+            CaptureFormat::Bgra,
         );
 
         // 验证结果
@@ -1,16 +1,278 @@
 use std::time::Duration;
-use std::{collections::HashMap, sync::Arc};
+use std::{
+    collections::{HashMap, HashSet},
+    sync::Arc,
+};
 
 use core_graphics::display::{
     kCGNullWindowID, kCGWindowImageDefault, kCGWindowListOptionOnScreenOnly, CGDisplay,
 };
 use paris::warn;
+use serde::{Deserialize, Serialize};
 use tauri::async_runtime::RwLock;
 use tokio::sync::{broadcast, watch, OnceCell};
 use tokio::task::yield_now;
-use tokio::time::sleep;
+use tokio::time::{sleep, sleep_until};
+use utoipa::ToSchema;
+
+use crate::{
+    ambient_light::{SamplePointMapper, MAX_DISPLAY_FRAME_RATE, MIN_DISPLAY_FRAME_RATE},
+    screenshot::Screenshot,
+    user_preferences::FullscreenCapturePreferences,
+};
+
+/// 显示器未报告刷新率时的采集帧间隔（约 30 FPS）
+pub const NORMAL_FRAME_INTERVAL_MS: u64 = 33;
+/// 全屏应用运行时允许的最大帧间隔
+pub const MAX_FULLSCREEN_FRAME_INTERVAL_MS: u64 = 1000;
+/// 全屏窗口检测的轮询间隔
+const FULLSCREEN_POLL_INTERVAL: Duration = Duration::from_secs(2);
+/// 光标所在显示器检测的轮询间隔
+const CURSOR_POLL_INTERVAL: Duration = Duration::from_millis(100);
+
+/// 屏幕采集后端，不同后端可用于绕过特定后端的权限或HDR问题
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Hash, Serialize, Deserialize, ToSchema)]
+pub enum CaptureBackend {
+    /// CGWindowListCreateImage：合成屏幕上所有窗口的画面
+    #[default]
+    WindowList,
+    /// CGDisplayCreateImage：直接读取显示器画面
+    DisplayImage,
+}
+
+impl CaptureBackend {
+    /// 所有支持的采集后端
+    pub const ALL: [CaptureBackend; 2] = [CaptureBackend::WindowList, CaptureBackend::DisplayImage];
+
+    /// 后端说明
+    pub fn description(&self) -> &'static str {
+        match self {
+            CaptureBackend::WindowList => "CGWindowListCreateImage (composited on-screen windows)",
+            CaptureBackend::DisplayImage => "CGDisplayCreateImage (display framebuffer)",
+        }
+    }
+}
+
+/// 选择的采集后端失败后，在该时长内直接使用默认后端，之后再重试选择的后端
+const CAPTURE_BACKEND_RETRY_INTERVAL: Duration = Duration::from_secs(30);
+/// 采集后端可用性检查结果的缓存时长
+const CAPTURE_BACKEND_AVAILABILITY_TTL: Duration = Duration::from_secs(30);
+
+/// 采集时依次尝试的后端：先使用选择的后端，失败时回退到默认后端
+fn capture_backend_chain(selected: CaptureBackend) -> Vec<CaptureBackend> {
+    let mut chain = vec![selected];
+    if selected != CaptureBackend::default() {
+        chain.push(CaptureBackend::default());
+    }
+    chain
+}
+
+/// 单个显示器采集循环的后端回退状态
+///
+/// 选择的后端失败后不再每帧重试，而是在 [`CAPTURE_BACKEND_RETRY_INTERVAL`] 内
+/// 直接使用默认后端；更换选择的后端时立即重试
+#[derive(Debug, Default)]
+struct CaptureBackendFallback {
+    /// 失败的后端及失败时间
+    failed: Option<(CaptureBackend, tokio::time::Instant)>,
+}
+
+impl CaptureBackendFallback {
+    /// 本帧依次尝试的后端
+    fn chain(&self, selected: CaptureBackend, now: tokio::time::Instant) -> Vec<CaptureBackend> {
+        match self.failed {
+            Some((backend, failed_at))
+                if backend == selected
+                    && now.duration_since(failed_at) < CAPTURE_BACKEND_RETRY_INTERVAL =>
+            {
+                vec![CaptureBackend::default()]
+            }
+            _ => capture_backend_chain(selected),
+        }
+    }
+
+    /// 记录选择的后端采集失败，返回是否是新出现的失败（用于只警告一次）
+    fn record_failure(&mut self, selected: CaptureBackend, now: tokio::time::Instant) -> bool {
+        let newly_failed = self.failed.is_none_or(|(backend, _)| backend != selected);
+        self.failed = Some((selected, now));
+        newly_failed
+    }
+}
+
+/// 单个显示器采集循环缓存的帧间隔
+///
+/// 帧间隔依赖灯带配置中的帧率覆盖，只在收到配置更新后重新解析，
+/// 避免每帧获取配置管理器和显示器注册表的锁
+struct FrameIntervalCache<T> {
+    config_updates: watch::Receiver<T>,
+    interval: Option<Duration>,
+}
+
+impl<T> FrameIntervalCache<T> {
+    fn new(config_updates: watch::Receiver<T>) -> Self {
+        Self {
+            config_updates,
+            interval: None,
+        }
+    }
+
+    /// 获取缓存的帧间隔，首次调用或配置更新后通过 `resolve` 重新解析
+    async fn get<F, Fut>(&mut self, resolve: F) -> Duration
+    where
+        F: FnOnce() -> Fut,
+        Fut: std::future::Future<Output = Duration>,
+    {
+        let config_changed = self.config_updates.has_changed().unwrap_or(false);
+        match self.interval {
+            Some(interval) if !config_changed => interval,
+            _ => {
+                self.config_updates.borrow_and_update();
+                let interval = resolve().await;
+                self.interval = Some(interval);
+                interval
+            }
+        }
+    }
+}
+
+/// 等待显示器的下一帧：采集时按本帧开始时间计算，暂停采集时等待较长时间以降低CPU占用
+async fn wait_for_next_frame(tick_started: tokio::time::Instant, frame_interval: Option<Duration>) {
+    match frame_interval {
+        Some(frame_interval) => sleep_until(tick_started + frame_interval).await,
+        None => sleep(Duration::from_millis(1000)).await,
+    }
+}
+
+/// 校验全屏应用运行时的采集帧间隔
+pub fn validate_fullscreen_frame_interval(frame_interval_ms: u64) -> anyhow::Result<()> {
+    if !(NORMAL_FRAME_INTERVAL_MS..=MAX_FULLSCREEN_FRAME_INTERVAL_MS).contains(&frame_interval_ms) {
+        return Err(anyhow::anyhow!(
+            "Fullscreen frame interval must be between {} and {} ms, got {}",
+            NORMAL_FRAME_INTERVAL_MS,
+            MAX_FULLSCREEN_FRAME_INTERVAL_MS,
+            frame_interval_ms
+        ));
+    }
+    Ok(())
+}
+
+/// 计算显示器正常采集的帧间隔
+///
+/// 优先使用配置的帧率覆盖，未配置时按显示器刷新率采集；
+/// 显示器未报告刷新率（部分显示器报告为0）时使用约 30 FPS。
+pub fn display_frame_interval(frame_rate: Option<f32>, refresh_rate: f32) -> Duration {
+    let frame_rate = frame_rate.unwrap_or(refresh_rate);
+    if !frame_rate.is_finite() || frame_rate <= 0.0 {
+        return Duration::from_millis(NORMAL_FRAME_INTERVAL_MS);
+    }
+    let frame_rate = frame_rate.clamp(MIN_DISPLAY_FRAME_RATE, MAX_DISPLAY_FRAME_RATE);
+    Duration::from_secs_f64(1.0 / frame_rate as f64)
+}
+
+/// 计算显示器的采集帧间隔，返回 None 表示暂停该显示器的采集
+///
+/// 全屏应用运行且启用了全屏采集配置时，按配置降低帧率（不会快于显示器自身的帧间隔），或只采集主显示器。
+fn capture_frame_interval(
+    profile: &FullscreenCapturePreferences,
+    fullscreen_active: bool,
+    is_primary_display: bool,
+    display_interval: Duration,
+) -> Option<Duration> {
+    if !profile.enabled || !fullscreen_active {
+        return Some(display_interval);
+    }
+    if profile.primary_display_only && !is_primary_display {
+        return None;
+    }
+    Some(Duration::from_millis(profile.frame_interval_ms).max(display_interval))
+}
+
+/// 检测前台全屏窗口所在的显示器
+///
+/// 取屏幕上最前面的普通层级（layer 0）窗口，若其范围与某个显示器完全重合，
+/// 则认为有全屏应用在运行。本应用自身的窗口不参与判断。
+fn detect_fullscreen_display() -> Option<u32> {
+    use core_foundation::{
+        base::{CFType, TCFType},
+        dictionary::{CFDictionary, CFDictionaryRef},
+        number::CFNumber,
+        string::CFString,
+    };
+    use core_graphics::{
+        geometry::CGRect,
+        window::{
+            copy_window_info, kCGWindowBounds, kCGWindowLayer, kCGWindowListExcludeDesktopElements,
+            kCGWindowOwnerPID,
+        },
+    };
+
+    let windows = copy_window_info(
+        kCGWindowListOptionOnScreenOnly | kCGWindowListExcludeDesktopElements,
+        kCGNullWindowID,
+    )?;
+    let displays = CGDisplay::active_displays().ok()?;
+    let own_pid = std::process::id() as i64;
+
+    for window in windows.iter() {
+        let window: CFDictionary<CFString, CFType> =
+            unsafe { CFDictionary::wrap_under_get_rule(*window as CFDictionaryRef) };
+        let number = |key| {
+            window
+                .find(unsafe { CFString::wrap_under_get_rule(key) })
+                .and_then(|value| value.downcast::<CFNumber>())
+                .and_then(|number| number.to_i64())
+        };
+
+        // 菜单栏、Dock 等系统窗口位于更高的层级
+        if number(unsafe { kCGWindowLayer }) != Some(0)
+            || number(unsafe { kCGWindowOwnerPID }) == Some(own_pid)
+        {
+            continue;
+        }
+
+        // 只看最前面的普通窗口
+        let bounds = window
+            .find(unsafe { CFString::wrap_under_get_rule(kCGWindowBounds) })
+            .and_then(|value| value.downcast::<CFDictionary>())
+            .and_then(|dict| CGRect::from_dict_representation(&dict))?;
+
+        return displays.into_iter().find(|&display_id| {
+            let display_bounds = CGDisplay::new(display_id).bounds();
+            display_bounds.origin.x == bounds.origin.x
+                && display_bounds.origin.y == bounds.origin.y
+                && display_bounds.size.width == bounds.size.width
+                && display_bounds.size.height == bounds.size.height
+        });
+    }
+
+    None
+}
 
-use crate::{ambient_light::SamplePointMapper, screenshot::Screenshot};
+/// 查找包含桌面全局坐标 `(x, y)` 的显示器（坐标单位与 `DisplayInfo` 的范围一致）
+pub fn display_at_point(x: f64, y: f64, displays: &[display_info::DisplayInfo]) -> Option<u32> {
+    displays
+        .iter()
+        .find(|display| {
+            let (left, top) = (display.x as f64, display.y as f64);
+            x >= left
+                && x < left + display.width as f64
+                && y >= top
+                && y < top + display.height as f64
+        })
+        .map(|display| display.id)
+}
+
+/// 获取鼠标光标在桌面中的全局坐标
+fn cursor_location() -> Option<(f64, f64)> {
+    use core_graphics::{
+        event::CGEvent,
+        event_source::{CGEventSource, CGEventSourceStateID},
+    };
+
+    let source = CGEventSource::new(CGEventSourceStateID::CombinedSessionState).ok()?;
+    let location = CGEvent::new(source).ok()?.location();
+    Some((location.x, location.y))
+}
 
 /// 检查屏幕录制权限
 fn check_screen_recording_permission() -> bool {
@@ -65,6 +327,18 @@ pub struct ScreenshotManager {
     #[allow(clippy::type_complexity)]
     pub channels: Arc<RwLock<HashMap<u32, Arc<RwLock<watch::Sender<Screenshot>>>>>>,
     merged_screenshot_tx: Arc<RwLock<broadcast::Sender<Screenshot>>>,
+    /// 当前检测到的全屏应用所在显示器，采集循环订阅变化而不是每帧加锁查询
+    fullscreen_display: Arc<watch::Sender<Option<u32>>>,
+    /// 光标跟随模式下鼠标光标所在的显示器
+    cursor_display: Arc<RwLock<Option<u32>>>,
+    /// 被全屏采集配置（只采集主显示器）暂停采集的显示器
+    paused_displays: Arc<RwLock<HashSet<u32>>>,
+    /// 各显示器报告的刷新率（Hz），用于确定默认采集帧率
+    refresh_rates: Arc<RwLock<HashMap<u32, f32>>>,
+    /// 各显示器最近一帧实际使用的采集后端
+    active_backends: Arc<RwLock<HashMap<u32, CaptureBackend>>>,
+    /// 采集后端可用性检查结果及检查时间
+    backend_availability: Arc<RwLock<HashMap<CaptureBackend, (tokio::time::Instant, bool)>>>,
 }
 
 impl ScreenshotManager {
@@ -78,6 +352,12 @@ impl ScreenshotManager {
                 Self {
                     channels,
                     merged_screenshot_tx: Arc::new(RwLock::new(merged_screenshot_tx)),
+                    fullscreen_display: Arc::new(watch::Sender::new(None)),
+                    cursor_display: Arc::new(RwLock::new(None)),
+                    paused_displays: Arc::new(RwLock::new(HashSet::new())),
+                    refresh_rates: Arc::new(RwLock::new(HashMap::new())),
+                    active_backends: Arc::new(RwLock::new(HashMap::new())),
+                    backend_availability: Arc::new(RwLock::new(HashMap::new())),
                 }
             })
             .await
@@ -103,14 +383,20 @@ impl ScreenshotManager {
         );
         for display in &displays {
             log::info!(
-                "  Display ID: {}, Scale: {}",
+                "  Display ID: {}, Scale: {}, Rotation: {}, Refresh rate: {}Hz",
                 display.id,
-                display.scale_factor
+                display.scale_factor,
+                display.rotation,
+                display.frequency
             );
         }
+        *self.refresh_rates.write().await = displays
+            .iter()
+            .map(|display| (display.id, display.frequency))
+            .collect();
 
         let futures = displays.iter().map(|display| async {
-            self.start_one(display.id, display.scale_factor)
+            self.start_one(display.id, display.scale_factor, display.rotation)
                 .await
                 .unwrap_or_else(|err| {
                     warn!("start_one failed: display_id: {}, err: {}", display.id, err);
@@ -118,24 +404,129 @@ impl ScreenshotManager {
         });
 
         futures::future::join_all(futures).await;
+        self.start_fullscreen_detection();
+        self.start_cursor_display_tracking();
         log::info!("🎯 ScreenshotManager internal start completed successfully");
         Ok(())
     }
 
-    async fn start_one(&self, display_id: u32, scale_factor: f32) -> anyhow::Result<()> {
+    /// 获取显示器正常采集的帧间隔（配置的帧率覆盖或显示器刷新率）
+    pub async fn get_frame_interval(&self, display_id: u32) -> Duration {
+        let refresh_rate = self
+            .refresh_rates
+            .read()
+            .await
+            .get(&display_id)
+            .copied()
+            .unwrap_or_default();
+        let frame_rate = crate::ambient_light::ConfigManagerV2::global()
+            .await
+            .get_display_frame_rate(display_id)
+            .await;
+        display_frame_interval(frame_rate, refresh_rate)
+    }
+
+    /// 获取当前检测到的全屏应用所在显示器
+    pub async fn get_fullscreen_display(&self) -> Option<u32> {
+        *self.fullscreen_display.borrow()
+    }
+
+    /// 显示器的采集是否被全屏采集配置有意暂停（此时没有新画面不属于采集失败）
+    pub async fn is_capture_paused(&self, display_id: u32) -> bool {
+        self.paused_displays.read().await.contains(&display_id)
+    }
+
+    /// 获取光标跟随模式下鼠标光标所在的显示器，未启用或光标位置未知时为 None
+    pub async fn get_cursor_display(&self) -> Option<u32> {
+        *self.cursor_display.read().await
+    }
+
+    /// 启用光标跟随时，定期检测鼠标光标所在的显示器
+    fn start_cursor_display_tracking(&self) {
+        let cursor_display = self.cursor_display.clone();
+        tokio::spawn(async move {
+            loop {
+                let preferences = crate::user_preferences::UserPreferencesManager::global()
+                    .await
+                    .get_cursor_focus()
+                    .await;
+                let detected = if preferences.enabled {
+                    cursor_location().and_then(|(x, y)| {
+                        let displays = display_info::DisplayInfo::all().ok()?;
+                        display_at_point(x, y, &displays)
+                    })
+                } else {
+                    None
+                };
+
+                let mut current = cursor_display.write().await;
+                if *current != detected {
+                    log::debug!("Cursor display changed: {:?} -> {:?}", *current, detected);
+                    *current = detected;
+                }
+                drop(current);
+
+                sleep(CURSOR_POLL_INTERVAL).await;
+            }
+        });
+    }
+
+    /// 启用全屏采集配置时，定期检测前台是否有全屏应用
+    fn start_fullscreen_detection(&self) {
+        let fullscreen_display = self.fullscreen_display.clone();
+        tokio::spawn(async move {
+            let mut preferences = crate::user_preferences::UserPreferencesManager::global()
+                .await
+                .snapshot();
+            loop {
+                let detected = if preferences.current().fullscreen_capture.enabled {
+                    detect_fullscreen_display()
+                } else {
+                    None
+                };
+
+                // 只有检测结果变化时才通知采集循环
+                fullscreen_display.send_if_modified(|current| {
+                    if *current == detected {
+                        return false;
+                    }
+                    match detected {
+                        Some(display_id) => {
+                            log::info!("🎮 Fullscreen app detected on display {display_id}")
+                        }
+                        None => log::info!("🎮 Fullscreen app no longer active"),
+                    }
+                    *current = detected;
+                    true
+                });
+
+                sleep(FULLSCREEN_POLL_INTERVAL).await;
+            }
+        });
+    }
+
+    async fn start_one(
+        &self,
+        display_id: u32,
+        scale_factor: f32,
+        rotation: f32,
+    ) -> anyhow::Result<()> {
         log::info!("Starting screenshot capture for display_id: {display_id}");
 
         let merged_screenshot_tx = self.merged_screenshot_tx.clone();
 
-        let (tx, _) = watch::channel(Screenshot::new(
-            display_id,
-            0,
-            0,
-            0,
-            Arc::new(vec![]),
-            scale_factor,
-            scale_factor,
-        ));
+        let (tx, _) = watch::channel(
+            Screenshot::new(
+                display_id,
+                0,
+                0,
+                0,
+                Arc::new(vec![]),
+                scale_factor,
+                scale_factor,
+            )
+            .with_rotation(rotation),
+        );
         let tx = Arc::new(RwLock::new(tx));
 
         let mut channels = self.channels.write().await;
@@ -143,29 +534,125 @@ impl ScreenshotManager {
 
         drop(channels);
 
+        let fullscreen_display = self.fullscreen_display.subscribe();
+        let paused_displays = self.paused_displays.clone();
+        let config_updates = crate::ambient_light::ConfigManagerV2::global()
+            .await
+            .subscribe_config_updates();
+
         // Start background task for screen capture
         tokio::spawn(async move {
+            // 选择的采集后端失败后暂时回退到默认后端，到期后再重试
+            let mut backend_fallback = CaptureBackendFallback::default();
+            // 最近一帧实际使用的后端，只在变化时更新共享状态
+            let mut active_backend: Option<CaptureBackend> = None;
+            // 是否被全屏采集配置暂停，只在变化时更新共享状态
+            let mut capture_paused = false;
+            // 采样和全屏采集相关的偏好设置只在变化时重新读取
+            let mut preferences = crate::user_preferences::UserPreferencesManager::global()
+                .await
+                .snapshot();
+            // 显示器帧间隔只在灯带配置变化时重新解析
+            let mut display_interval = FrameIntervalCache::new(config_updates);
             // Implement screen capture using screen-capture-kit
             loop {
+                // 按本帧开始时间计算下一帧，采集耗时不拉低帧率
+                let tick_started = tokio::time::Instant::now();
                 // Check if ambient light is enabled and not in color calibration mode
                 let should_capture = {
                     let state_manager =
                         crate::ambient_light_state::AmbientLightStateManager::global().await;
                     let ambient_light_enabled = state_manager.is_enabled().await;
 
-                    // Also check LED data send mode - don't capture during color calibration
+                    // Also check LED data send mode - don't capture during color calibration or manual color
                     let led_sender = crate::led_data_sender::LedDataSender::global().await;
                     let current_mode = led_sender.get_mode().await;
                     let is_color_calibration = matches!(
                         current_mode,
                         crate::led_data_sender::DataSendMode::ColorCalibration
+                            | crate::led_data_sender::DataSendMode::ManualColor
                     );
 
                     ambient_light_enabled && !is_color_calibration
                 };
 
-                if should_capture {
-                    match Self::capture_display_screenshot(display_id, scale_factor).await {
+                // 每个显示器按各自的帧率采集；全屏应用运行时按采集配置降低帧率或只采集主显示器
+                let frame_interval = if should_capture {
+                    let display_interval = display_interval
+                        .get(|| async { Self::global().await.get_frame_interval(display_id).await })
+                        .await;
+                    let fullscreen_active = fullscreen_display.borrow().is_some();
+                    capture_frame_interval(
+                        &preferences.current().fullscreen_capture,
+                        fullscreen_active,
+                        display_id == CGDisplay::main().id,
+                        display_interval,
+                    )
+                } else {
+                    None
+                };
+
+                let paused_by_profile = should_capture && frame_interval.is_none();
+                if paused_by_profile != capture_paused {
+                    capture_paused = paused_by_profile;
+                    let mut paused_displays = paused_displays.write().await;
+                    if capture_paused {
+                        paused_displays.insert(display_id);
+                    } else {
+                        paused_displays.remove(&display_id);
+                    }
+                }
+
+                if frame_interval.is_some() {
+                    let sampling = &preferences.current().sampling;
+                    let sample_points_per_led = sampling.effective_points_per_led();
+                    let black_level = sampling.black_level;
+                    let interpolation_strength = sampling.effective_interpolation_strength();
+                    let averaging_color_space = sampling.averaging_color_space;
+                    let bilinear_sampling = sampling.bilinear_sampling;
+                    let vertical_gradient_sampling = sampling.vertical_gradient_sampling;
+                    let capture_backend = sampling.capture_backend;
+                    let config_manager = crate::ambient_light::ConfigManagerV2::global().await;
+                    let led_positions = config_manager.get_led_positions().await;
+                    let crop = config_manager.get_display_crop(display_id).await;
+                    let scale_factor_override =
+                        config_manager.get_display_scale_factor(display_id).await;
+                    let capture_format =
+                        config_manager.get_display_capture_format(display_id).await;
+
+                    let captured = Self::capture_with_fallback(
+                        display_id,
+                        scale_factor,
+                        capture_backend,
+                        &mut backend_fallback,
+                    )
+                    .await;
+                    if let Ok((_, backend)) = &captured {
+                        if active_backend != Some(*backend) {
+                            active_backend = Some(*backend);
+                            Self::global()
+                                .await
+                                .active_backends
+                                .write()
+                                .await
+                                .insert(display_id, *backend);
+                        }
+                    }
+
+                    match captured.map(|(screenshot, _)| {
+                        screenshot
+                            .with_rotation(rotation)
+                            .with_sample_points_per_led(sample_points_per_led)
+                            .with_black_level(black_level)
+                            .with_led_positions(led_positions.clone())
+                            .with_crop(crop)
+                            .with_interpolation_strength(interpolation_strength)
+                            .with_averaging_color_space(averaging_color_space)
+                            .with_bilinear_sampling(bilinear_sampling)
+                            .with_vertical_gradient_sampling(vertical_gradient_sampling)
+                            .with_scale_factor_override(scale_factor_override)
+                            .with_capture_format(capture_format)
+                    }) {
                         Ok(screenshot) => {
                             let tx_for_send = tx.read().await;
                             let merged_screenshot_tx = merged_screenshot_tx.write().await;
@@ -178,42 +665,18 @@ impl ScreenshotManager {
                             }
                         }
                         Err(err) => {
+                            // 不发布占位画面：没有新画面时由灯效发布器的采集失败策略处理
                             warn!(
                                 "Failed to capture screenshot for display {}: {}",
                                 display_id, err
                             );
-                            // Create a fallback empty screenshot to maintain the interface
-                            let screenshot = Screenshot::new(
-                                display_id,
-                                1080,
-                                1920,
-                                1920 * 4, // Assuming RGBA format
-                                Arc::new(vec![0u8; 1920 * 1080 * 4]),
-                                scale_factor,
-                                scale_factor,
-                            );
-
-                            let tx_for_send = tx.read().await;
-                            let merged_screenshot_tx = merged_screenshot_tx.write().await;
-
-                            if let Err(_err) = merged_screenshot_tx.send(screenshot.clone()) {
-                                // log::warn!("merged_screenshot_tx.send failed: {}", err);
-                            }
-                            if let Err(err) = tx_for_send.send(screenshot.clone()) {
-                                log::warn!("display {display_id} screenshot_tx.send failed: {err}");
-                            }
                         }
                     }
-                } else {
-                    // If ambient light is disabled or in color calibration mode, sleep longer to reduce CPU usage
-                    sleep(Duration::from_millis(1000)).await;
                 }
 
-                // Sleep for a frame duration when enabled
-                // Adjusted from 200ms (5 FPS) -> 33ms (~30 FPS) for smoother preview
-                if should_capture {
-                    sleep(Duration::from_millis(33)).await;
-                }
+                // If ambient light is disabled, in color calibration mode or paused for a
+                // fullscreen app, sleep longer to reduce CPU usage
+                wait_for_next_frame(tick_started, frame_interval).await;
                 yield_now().await;
             }
         });
@@ -221,22 +684,85 @@ impl ScreenshotManager {
         Ok(())
     }
 
+    /// 使用选择的后端采集，失败时回退到默认后端，返回画面及实际使用的后端
+    async fn capture_with_fallback(
+        display_id: u32,
+        scale_factor: f32,
+        selected: CaptureBackend,
+        fallback: &mut CaptureBackendFallback,
+    ) -> anyhow::Result<(Screenshot, CaptureBackend)> {
+        let mut last_err = None;
+        let now = tokio::time::Instant::now();
+        for backend in fallback.chain(selected, now) {
+            let result = Self::capture_display_screenshot(display_id, scale_factor, backend).await;
+            if backend == selected && selected != CaptureBackend::default() {
+                if let Err(err) = &result {
+                    if fallback.record_failure(selected, now) {
+                        log::warn!(
+                            "Display#{display_id}: capture backend {selected:?} failed ({err}), falling back to {:?} for {}s",
+                            CaptureBackend::default(),
+                            CAPTURE_BACKEND_RETRY_INTERVAL.as_secs()
+                        );
+                    }
+                } else if fallback.failed.take().is_some() {
+                    log::info!("Display#{display_id}: capture backend {selected:?} recovered");
+                }
+            }
+            match result {
+                Ok(screenshot) => return Ok((screenshot, backend)),
+                Err(err) => last_err = Some(err),
+            }
+        }
+        Err(last_err.unwrap_or_else(|| anyhow::anyhow!("No capture backend available")))
+    }
+
+    /// 各显示器最近一帧实际使用的采集后端（按显示器ID索引）
+    pub async fn get_active_capture_backends(&self) -> HashMap<u32, CaptureBackend> {
+        self.active_backends.read().await.clone()
+    }
+
+    /// 用主显示器试采集一帧，检查采集后端在当前系统上是否可用，结果缓存一段时间
+    pub async fn is_capture_backend_available(&self, backend: CaptureBackend) -> bool {
+        let now = tokio::time::Instant::now();
+        if let Some((checked_at, available)) = self
+            .backend_availability
+            .read()
+            .await
+            .get(&backend)
+            .copied()
+        {
+            if now.duration_since(checked_at) < CAPTURE_BACKEND_AVAILABILITY_TTL {
+                return available;
+            }
+        }
+
+        let available = Self::capture_display_screenshot(CGDisplay::main().id, 1.0, backend)
+            .await
+            .is_ok();
+        self.backend_availability
+            .write()
+            .await
+            .insert(backend, (now, available));
+        available
+    }
+
     async fn capture_display_screenshot(
         display_id: u32,
         scale_factor: f32,
+        backend: CaptureBackend,
     ) -> anyhow::Result<Screenshot> {
-        // For now, use the existing CGDisplay approach as a fallback
-        // TODO: Implement proper screen-capture-kit integration
-
         let cg_display = CGDisplay::new(display_id);
         let bounds = cg_display.bounds();
 
-        let cg_image = CGDisplay::screenshot(
-            bounds,
-            kCGWindowListOptionOnScreenOnly,
-            kCGNullWindowID,
-            kCGWindowImageDefault,
-        )
+        let cg_image = match backend {
+            CaptureBackend::WindowList => CGDisplay::screenshot(
+                bounds,
+                kCGWindowListOptionOnScreenOnly,
+                kCGNullWindowID,
+                kCGWindowImageDefault,
+            ),
+            CaptureBackend::DisplayImage => cg_display.image(),
+        }
         .ok_or_else(|| {
             anyhow::anyhow!(
                 "Display#{}: take screenshot failed - possibly no screen recording permission",
@@ -283,3 +809,221 @@ impl ScreenshotManager {
         }
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn display(id: u32, x: i32, y: i32, width: u32, height: u32) -> display_info::DisplayInfo {
+        display_info::DisplayInfo {
+            id,
+            x,
+            y,
+            width,
+            height,
+            rotation: 0.0,
+            scale_factor: 1.0,
+            is_primary: id == 1,
+            frequency: 60.0,
+            raw_handle: unsafe { std::mem::zeroed() },
+        }
+    }
+
+    #[test]
+    fn test_display_at_point_maps_cursor_to_display() {
+        // 主显示器在左，副显示器在右侧且向上偏移
+        let displays = vec![
+            display(1, 0, 0, 1920, 1080),
+            display(2, 1920, -200, 2560, 1440),
+        ];
+
+        assert_eq!(display_at_point(100.0, 500.0, &displays), Some(1));
+        assert_eq!(display_at_point(1919.5, 1079.0, &displays), Some(1));
+        // 右边界属于相邻显示器
+        assert_eq!(display_at_point(1920.0, 0.0, &displays), Some(2));
+        assert_eq!(display_at_point(3000.0, -150.0, &displays), Some(2));
+        // 主显示器上方、副显示器左侧的空白区域
+        assert_eq!(display_at_point(500.0, -100.0, &displays), None);
+        assert_eq!(display_at_point(4480.0, 100.0, &displays), None);
+    }
+
+    #[test]
+    fn test_display_frame_interval_ticks_independently() {
+        let primary = display_frame_interval(None, 144.0);
+        let secondary = display_frame_interval(None, 60.0);
+        let ticks_per_second = |interval: Duration| (1.0 / interval.as_secs_f64()).round() as u32;
+
+        // 144Hz 主显示器与 60Hz 副显示器各按自身刷新率采集
+        assert_eq!(ticks_per_second(primary), 144);
+        assert_eq!(ticks_per_second(secondary), 60);
+        // 配置的帧率覆盖刷新率
+        assert_eq!(
+            ticks_per_second(display_frame_interval(Some(30.0), 144.0)),
+            30
+        );
+        assert_eq!(
+            ticks_per_second(display_frame_interval(Some(1000.0), 60.0)),
+            240
+        );
+        // 未报告刷新率时使用默认帧间隔
+        assert_eq!(
+            display_frame_interval(None, 0.0),
+            Duration::from_millis(NORMAL_FRAME_INTERVAL_MS)
+        );
+
+        // 全屏降帧不会让帧率低于配置的显示器变快
+        let profile = FullscreenCapturePreferences {
+            enabled: true,
+            primary_display_only: false,
+            frame_interval_ms: 100,
+        };
+        let slow = display_frame_interval(Some(5.0), 60.0);
+        assert_eq!(
+            capture_frame_interval(&profile, true, false, primary),
+            Some(Duration::from_millis(100))
+        );
+        assert_eq!(
+            capture_frame_interval(&profile, true, false, slow),
+            Some(slow)
+        );
+    }
+
+    /// 按采集循环的方式运行一个显示器的帧循环，返回已采集的帧数
+    fn spawn_frame_loop(
+        display_id: u32,
+        refresh_rate: f32,
+        frame_rates: watch::Receiver<HashMap<u32, f32>>,
+        resolved: Arc<std::sync::atomic::AtomicU32>,
+    ) -> Arc<std::sync::atomic::AtomicU32> {
+        let ticks = Arc::new(std::sync::atomic::AtomicU32::new(0));
+        let counter = ticks.clone();
+        tokio::spawn(async move {
+            let mut display_interval = FrameIntervalCache::new(frame_rates.clone());
+            loop {
+                let tick_started = tokio::time::Instant::now();
+                let frame_interval = display_interval
+                    .get(|| async {
+                        resolved.fetch_add(1, std::sync::atomic::Ordering::Relaxed);
+                        let frame_rate = frame_rates.borrow().get(&display_id).copied();
+                        display_frame_interval(frame_rate, refresh_rate)
+                    })
+                    .await;
+                counter.fetch_add(1, std::sync::atomic::Ordering::Relaxed);
+                wait_for_next_frame(tick_started, Some(frame_interval)).await;
+            }
+        });
+        ticks
+    }
+
+    #[tokio::test(start_paused = true)]
+    async fn test_display_capture_loops_tick_independently() {
+        use std::sync::atomic::{AtomicU32, Ordering};
+
+        let (config_tx, frame_rates) = watch::channel(HashMap::new());
+        let resolved = Arc::new(AtomicU32::new(0));
+        // 125Hz 主显示器与 50Hz 副显示器
+        let primary = spawn_frame_loop(1, 125.0, frame_rates.clone(), resolved.clone());
+        let secondary = spawn_frame_loop(2, 50.0, frame_rates, resolved.clone());
+
+        // 每个循环按自身帧间隔采集，含 0 时刻的第一帧
+        sleep(Duration::from_millis(1005)).await;
+        assert_eq!(primary.load(Ordering::Relaxed), 126);
+        assert_eq!(secondary.load(Ordering::Relaxed), 51);
+        // 没有配置更新时帧间隔只解析一次
+        assert_eq!(resolved.load(Ordering::Relaxed), 2);
+
+        // 副显示器的帧率覆盖在下一帧生效，主显示器不受影响
+        config_tx.send(HashMap::from([(2, 25.0)])).unwrap();
+        sleep(Duration::from_millis(1005)).await;
+        assert_eq!(primary.load(Ordering::Relaxed), 126 + 126);
+        assert_eq!(secondary.load(Ordering::Relaxed), 51 + 25);
+        assert_eq!(resolved.load(Ordering::Relaxed), 4);
+    }
+
+    #[test]
+    fn test_capture_frame_interval_for_fullscreen_profile() {
+        let display_interval = Duration::from_millis(NORMAL_FRAME_INTERVAL_MS);
+        let normal = Some(display_interval);
+        let profile = FullscreenCapturePreferences {
+            enabled: true,
+            primary_display_only: true,
+            frame_interval_ms: 100,
+        };
+
+        // 没有全屏应用时保持正常帧率
+        assert_eq!(
+            capture_frame_interval(&profile, false, false, display_interval),
+            normal
+        );
+        // 全屏时只采集主显示器，并降低帧率
+        assert_eq!(
+            capture_frame_interval(&profile, true, false, display_interval),
+            None
+        );
+        assert_eq!(
+            capture_frame_interval(&profile, true, true, display_interval),
+            Some(Duration::from_millis(100))
+        );
+
+        let all_displays = FullscreenCapturePreferences {
+            primary_display_only: false,
+            ..profile.clone()
+        };
+        assert_eq!(
+            capture_frame_interval(&all_displays, true, false, display_interval),
+            Some(Duration::from_millis(100))
+        );
+
+        let disabled = FullscreenCapturePreferences {
+            enabled: false,
+            ..profile
+        };
+        assert_eq!(
+            capture_frame_interval(&disabled, true, false, display_interval),
+            normal
+        );
+
+        assert!(validate_fullscreen_frame_interval(100).is_ok());
+        assert!(validate_fullscreen_frame_interval(10).is_err());
+        assert!(validate_fullscreen_frame_interval(5000).is_err());
+    }
+
+    #[test]
+    fn test_capture_backend_falls_back_to_default() {
+        assert_eq!(
+            capture_backend_chain(CaptureBackend::WindowList),
+            vec![CaptureBackend::WindowList]
+        );
+        assert_eq!(
+            capture_backend_chain(CaptureBackend::DisplayImage),
+            vec![CaptureBackend::DisplayImage, CaptureBackend::WindowList]
+        );
+    }
+
+    #[test]
+    fn test_failed_capture_backend_is_retried_after_back_off() {
+        let start = tokio::time::Instant::now();
+        let mut fallback = CaptureBackendFallback::default();
+        let both = vec![CaptureBackend::DisplayImage, CaptureBackend::WindowList];
+        assert_eq!(fallback.chain(CaptureBackend::DisplayImage, start), both);
+
+        // 失败后只警告一次，回退期间不再尝试选择的后端
+        assert!(fallback.record_failure(CaptureBackend::DisplayImage, start));
+        let during = start + Duration::from_secs(1);
+        assert_eq!(
+            fallback.chain(CaptureBackend::DisplayImage, during),
+            vec![CaptureBackend::WindowList]
+        );
+
+        // 回退期满后重试，再次失败不重复警告
+        let retry_at = start + CAPTURE_BACKEND_RETRY_INTERVAL;
+        assert_eq!(fallback.chain(CaptureBackend::DisplayImage, retry_at), both);
+        assert!(!fallback.record_failure(CaptureBackend::DisplayImage, retry_at));
+
+        // 更换选择的后端时立即使用新后端
+        assert_eq!(
+            fallback.chain(CaptureBackend::WindowList, during),
+            vec![CaptureBackend::WindowList]
+        );
+    }
+}
@@ -1,6 +1,6 @@
 use crate::ambient_light::{
-    Border, ColorCalibration, ConfigManagerV2, LedStripConfigGroupV2, LedStripConfigV2, LedType,
-    PublisherAdapter,
+    Border, ColorCalibration, ColorOrder, ConfigManagerV2, LedStripConfigGroupV2, LedStripConfigV2,
+    LedType, PublisherAdapter, SampleShape, ZoneReduction,
 };
 use crate::display::{DisplayConfig, DisplayConfigGroup, DisplayRegistry};
 use std::time::SystemTime;
@@ -39,6 +39,16 @@ async fn test_complete_stable_display_id_workflow() {
             len: 30,
             led_type: LedType::WS2812B,
             reversed: false,
+            hardware_offset: None,
+            led_positions: None,
+            zone_reduction: ZoneReduction::Mean,
+            color_order: ColorOrder::default(),
+            sample_shape: SampleShape::Grid,
+            sample_inset: 0,
+            start_trim_percent: 0.0,
+            end_trim_percent: 0.0,
+            enabled: true,
+            chain_position: None,
         },
         LedStripConfigV2 {
             index: 1,
@@ -47,6 +57,16 @@ async fn test_complete_stable_display_id_workflow() {
             len: 20,
             led_type: LedType::WS2812B,
             reversed: false,
+            hardware_offset: None,
+            led_positions: None,
+            zone_reduction: ZoneReduction::Mean,
+            color_order: ColorOrder::default(),
+            sample_shape: SampleShape::Grid,
+            sample_inset: 0,
+            start_trim_percent: 0.0,
+            end_trim_percent: 0.0,
+            enabled: true,
+            chain_position: None,
         },
         LedStripConfigV2 {
             index: 2,
@@ -55,6 +75,16 @@ async fn test_complete_stable_display_id_workflow() {
             len: 40,
             led_type: LedType::SK6812,
             reversed: true,
+            hardware_offset: None,
+            led_positions: None,
+            zone_reduction: ZoneReduction::Mean,
+            color_order: ColorOrder::default(),
+            sample_shape: SampleShape::Grid,
+            sample_inset: 0,
+            start_trim_percent: 0.0,
+            end_trim_percent: 0.0,
+            enabled: true,
+            chain_position: None,
         },
     ];
 
@@ -68,6 +98,15 @@ async fn test_complete_stable_display_id_workflow() {
         strips,
         mappers: Vec::new(),
         color_calibration: ColorCalibration::new(),
+        mirror_sources: Default::default(),
+        display_crops: Default::default(),
+        display_scale_factors: Default::default(),
+        display_windings: Default::default(),
+        display_capture_formats: Default::default(),
+        display_frame_rates: Default::default(),
+        display_border_masks: Default::default(),
+        strip_spans: Default::default(),
+        calibration_presets: Default::default(),
         created_at: SystemTime::now(),
         updated_at: SystemTime::now(),
     };
@@ -260,6 +299,16 @@ async fn test_performance_with_many_displays() {
                     LedType::SK6812
                 },
                 reversed: border_idx % 2 == 1,
+                hardware_offset: None,
+                led_positions: None,
+                zone_reduction: ZoneReduction::Mean,
+                color_order: ColorOrder::default(),
+                sample_shape: SampleShape::Grid,
+                sample_inset: 0,
+                start_trim_percent: 0.0,
+                end_trim_percent: 0.0,
+                enabled: true,
+                chain_position: None,
             });
         }
     }
@@ -271,6 +320,15 @@ async fn test_performance_with_many_displays() {
         strips,
         mappers: Vec::new(),
         color_calibration: ColorCalibration::new(),
+        mirror_sources: Default::default(),
+        display_crops: Default::default(),
+        display_scale_factors: Default::default(),
+        display_windings: Default::default(),
+        display_capture_formats: Default::default(),
+        display_frame_rates: Default::default(),
+        display_border_masks: Default::default(),
+        strip_spans: Default::default(),
+        calibration_presets: Default::default(),
         created_at: SystemTime::now(),
         updated_at: SystemTime::now(),
     };
@@ -1,5 +1,6 @@
 use crate::ambient_light::{
-    Border, ColorCalibration, LedStripConfigGroupV2, LedStripConfigV2, LedType,
+    Border, ColorCalibration, ColorOrder, LedStripConfigGroupV2, LedStripConfigV2, LedType,
+    SampleShape, ZoneReduction,
 };
 use crate::display::{DisplayConfig, DisplayConfigGroup};
 use std::time::SystemTime;
@@ -28,6 +29,16 @@ async fn test_stable_display_id_basic_functionality() {
             len: 30,
             led_type: LedType::WS2812B,
             reversed: false,
+            hardware_offset: None,
+            led_positions: None,
+            zone_reduction: ZoneReduction::Mean,
+            color_order: ColorOrder::default(),
+            sample_shape: SampleShape::Grid,
+            sample_inset: 0,
+            start_trim_percent: 0.0,
+            end_trim_percent: 0.0,
+            enabled: true,
+            chain_position: None,
         },
         LedStripConfigV2 {
             index: 1,
@@ -36,6 +47,16 @@ async fn test_stable_display_id_basic_functionality() {
             len: 20,
             led_type: LedType::WS2812B,
             reversed: false,
+            hardware_offset: None,
+            led_positions: None,
+            zone_reduction: ZoneReduction::Mean,
+            color_order: ColorOrder::default(),
+            sample_shape: SampleShape::Grid,
+            sample_inset: 0,
+            start_trim_percent: 0.0,
+            end_trim_percent: 0.0,
+            enabled: true,
+            chain_position: None,
         },
         LedStripConfigV2 {
             index: 2,
@@ -44,6 +65,16 @@ async fn test_stable_display_id_basic_functionality() {
             len: 40,
             led_type: LedType::SK6812,
             reversed: true,
+            hardware_offset: None,
+            led_positions: None,
+            zone_reduction: ZoneReduction::Mean,
+            color_order: ColorOrder::default(),
+            sample_shape: SampleShape::Grid,
+            sample_inset: 0,
+            start_trim_percent: 0.0,
+            end_trim_percent: 0.0,
+            enabled: true,
+            chain_position: None,
         },
     ];
 
@@ -54,6 +85,15 @@ async fn test_stable_display_id_basic_functionality() {
         strips,
         mappers: Vec::new(),
         color_calibration: ColorCalibration::new(),
+        mirror_sources: Default::default(),
+        display_crops: Default::default(),
+        display_scale_factors: Default::default(),
+        display_windings: Default::default(),
+        display_capture_formats: Default::default(),
+        display_frame_rates: Default::default(),
+        display_border_masks: Default::default(),
+        strip_spans: Default::default(),
+        calibration_presets: Default::default(),
         created_at: SystemTime::now(),
         updated_at: SystemTime::now(),
     };
@@ -139,6 +179,16 @@ async fn test_config_serialization() {
         len: 30,
         led_type: LedType::WS2812B,
         reversed: false,
+        hardware_offset: None,
+        led_positions: None,
+        zone_reduction: ZoneReduction::Mean,
+        color_order: ColorOrder::default(),
+        sample_shape: SampleShape::Grid,
+        sample_inset: 0,
+        start_trim_percent: 0.0,
+        end_trim_percent: 0.0,
+        enabled: true,
+        chain_position: None,
     };
     config.strips.push(strip);
     config.generate_mappers();
@@ -177,6 +227,16 @@ async fn test_led_strip_start_position_calculation() {
             len: 10,
             led_type: LedType::WS2812B,
             reversed: false,
+            hardware_offset: None,
+            led_positions: None,
+            zone_reduction: ZoneReduction::Mean,
+            color_order: ColorOrder::default(),
+            sample_shape: SampleShape::Grid,
+            sample_inset: 0,
+            start_trim_percent: 0.0,
+            end_trim_percent: 0.0,
+            enabled: true,
+            chain_position: None,
         },
         LedStripConfigV2 {
             index: 1,
@@ -185,6 +245,16 @@ async fn test_led_strip_start_position_calculation() {
             len: 15,
             led_type: LedType::WS2812B,
             reversed: false,
+            hardware_offset: None,
+            led_positions: None,
+            zone_reduction: ZoneReduction::Mean,
+            color_order: ColorOrder::default(),
+            sample_shape: SampleShape::Grid,
+            sample_inset: 0,
+            start_trim_percent: 0.0,
+            end_trim_percent: 0.0,
+            enabled: true,
+            chain_position: None,
         },
         LedStripConfigV2 {
             index: 2,
@@ -193,6 +263,16 @@ async fn test_led_strip_start_position_calculation() {
             len: 20,
             led_type: LedType::WS2812B,
             reversed: false,
+            hardware_offset: None,
+            led_positions: None,
+            zone_reduction: ZoneReduction::Mean,
+            color_order: ColorOrder::default(),
+            sample_shape: SampleShape::Grid,
+            sample_inset: 0,
+            start_trim_percent: 0.0,
+            end_trim_percent: 0.0,
+            enabled: true,
+            chain_position: None,
         },
     ];
 
@@ -1,10 +1,20 @@
 use dirs::config_dir;
-use paris::{info, warn};
 use serde::{Deserialize, Serialize};
 use std::fs;
 use std::path::PathBuf;
 use std::sync::Arc;
-use tokio::sync::{OnceCell, RwLock};
+use tokio::sync::{watch, OnceCell, RwLock};
+
+use crate::ambient_light::{
+    CaptureFailurePolicy, ColorCountPolicy, DisplaySleepPolicy, FrameSyncMode, SamplingStrategy,
+};
+use crate::led_data_processor::AutoBrightnessMode;
+use crate::led_data_sender::DataSendMode;
+use crate::output_sink::OutputSinkConfig;
+use crate::preview_scaling::PreviewFilter;
+use crate::screenshot::AveragingColorSpace;
+use crate::screenshot_manager::CaptureBackend;
+use crate::webhook::WebhookEvent;
 
 const CONFIG_FILE_NAME: &str = "cc.ivanli.ambient_light/user_preferences.toml";
 
@@ -12,6 +22,34 @@ const CONFIG_FILE_NAME: &str = "cc.ivanli.ambient_light/user_preferences.toml";
 pub struct UserPreferences {
     pub window: WindowPreferences,
     pub ui: UIPreferences,
+    #[serde(default)]
+    pub osc: OscPreferences,
+    #[serde(default)]
+    pub sampling: SamplingPreferences,
+    #[serde(default)]
+    pub config_update: ConfigUpdatePreferences,
+    #[serde(default)]
+    pub send_mode: SendModePreferences,
+    #[serde(default)]
+    pub fullscreen_capture: FullscreenCapturePreferences,
+    #[serde(default)]
+    pub auto_brightness: AutoBrightnessPreferences,
+    #[serde(default)]
+    pub server: ServerPreferences,
+    #[serde(default)]
+    pub capture_failure: CaptureFailurePreferences,
+    #[serde(default)]
+    pub output: OutputPreferences,
+    #[serde(default)]
+    pub preview_scaling: PreviewScalingPreferences,
+    #[serde(default)]
+    pub webhook: WebhookPreferences,
+    #[serde(default)]
+    pub palette_quantization: PaletteQuantizationPreferences,
+    #[serde(default)]
+    pub color_temperature_shift: ColorTemperatureShiftPreferences,
+    #[serde(default)]
+    pub cursor_focus: CursorFocusPreferences,
 }
 
 #[derive(Debug, Clone, Serialize, Deserialize)]
@@ -32,6 +70,198 @@ pub struct UIPreferences {
     pub night_mode_theme: String,
 }
 
+/// OSC input server preferences (see docs/osc-control.md for the address schema)
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct OscPreferences {
+    pub enabled: bool,
+    pub port: u16,
+}
+
+/// Screen sampling preferences
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct SamplingPreferences {
+    /// Sample points per LED, must be a perfect square (see `validate_sample_points_per_led`)
+    pub points_per_led: usize,
+    /// Pixels with perceived luminance below this level are excluded from color averaging (0 disables)
+    #[serde(default)]
+    pub black_level: u8,
+    /// Blend each LED with its neighbors along the strip, 0 (off) to 1 (see `validate_interpolation_strength`)
+    #[serde(default)]
+    pub interpolation_strength: f32,
+    /// Color space sample points are averaged in; gamma space keeps the original look
+    #[serde(default)]
+    pub averaging_color_space: AveragingColorSpace,
+    /// Read pixels with bilinear interpolation at sub-pixel sample positions (higher quality, slower)
+    #[serde(default)]
+    pub bilinear_sampling: bool,
+    /// Sample overlapping vertical windows for left/right strips so they show a smooth gradient
+    #[serde(default)]
+    pub vertical_gradient_sampling: bool,
+    /// When multi-display colors are combined into a frame and sent
+    #[serde(default)]
+    pub frame_sync_mode: FrameSyncMode,
+    /// How to fit sampled colors to the configured LED count when they differ
+    #[serde(default)]
+    pub color_count_policy: ColorCountPolicy,
+    /// How strips behave while their display is asleep or turned off
+    #[serde(default)]
+    pub display_sleep_policy: DisplaySleepPolicy,
+    /// Also treat a display as asleep once its captured frames stay black for a few seconds.
+    /// Off by default: dark scenes would otherwise trigger the sleep policy.
+    #[serde(default)]
+    pub detect_sleep_from_black_frames: bool,
+    /// Screen capture backend, falls back to the default backend when it fails
+    #[serde(default)]
+    pub capture_backend: CaptureBackend,
+    /// Sample each LED from its edge, or fill every LED with the whole-screen average
+    #[serde(default)]
+    pub sampling_strategy: SamplingStrategy,
+}
+
+/// LED strip config update preferences
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct ConfigUpdatePreferences {
+    /// Successive config updates within this window (ms) are coalesced into one
+    /// `config_changed` notification; 0 notifies immediately
+    pub debounce_ms: u64,
+}
+
+/// Last persistent LED data send mode, restored on startup
+///
+/// Only `ManualColor` is restored from here; the ambient light enabled state is
+/// persisted separately by `AmbientLightStateManager`.
+#[derive(Debug, Clone, Default, PartialEq, Serialize, Deserialize)]
+pub struct SendModePreferences {
+    pub last_mode: DataSendMode,
+    /// Manual color payload for `DataSendMode::ManualColor`
+    #[serde(default)]
+    pub manual_color: Option<[u8; 3]>,
+}
+
+/// Capture profile applied while a fullscreen app is in the foreground
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct FullscreenCapturePreferences {
+    pub enabled: bool,
+    /// Only capture the main display while a fullscreen app is active
+    pub primary_display_only: bool,
+    /// Capture frame interval (ms) while a fullscreen app is active
+    pub frame_interval_ms: u64,
+}
+
+/// Scales ambient LED output by each display's average screen luminance, smoothed over time
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct AutoBrightnessPreferences {
+    pub enabled: bool,
+    pub mode: AutoBrightnessMode,
+    /// How strongly luminance affects output, 0 (no effect) to 1
+    pub strength: f32,
+}
+
+/// Snaps every LED color to the nearest entry of a fixed palette (preview and hardware alike)
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct PaletteQuantizationPreferences {
+    pub enabled: bool,
+    /// RGB palette entries, 1 to `MAX_PALETTE_SIZE` colors
+    pub palette: Vec<[u8; 3]>,
+}
+
+/// Shifts LED output between a daytime and nighttime color temperature by local time
+///
+/// Times are minutes since local midnight; each transition starts at sunrise/sunset and lasts
+/// `transition_minutes`. Applied on top of the static white balance, hardware output only.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct ColorTemperatureShiftPreferences {
+    pub enabled: bool,
+    pub day_kelvin: u32,
+    pub night_kelvin: u32,
+    pub sunrise_minute: u32,
+    pub sunset_minute: u32,
+    pub transition_minutes: u32,
+}
+
+/// Boosts the strips of the display under the mouse cursor and dims the others, smoothed
+/// across frames so moving between displays fades instead of jumping
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct CursorFocusPreferences {
+    pub enabled: bool,
+    /// How strongly the focused display is favored, 0 (no effect) to 1
+    pub strength: f32,
+}
+
+/// HTTP API and screen stream WebSocket bind addresses (see docs/remote-access.md)
+///
+/// Applied on startup; `--http-host`, `--http-port`, `--ws-host` and `--ws-port` override them.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+#[serde(default)]
+pub struct ServerPreferences {
+    pub http_host: String,
+    pub http_port: u16,
+    pub websocket_host: String,
+    pub websocket_port: u16,
+    /// Bearer token required by both servers; no auth when unset
+    pub auth_token: Option<String>,
+    /// Requests from loopback addresses skip the token check
+    pub auth_exempt_loopback: bool,
+}
+
+/// What the strips show when screen capture stops delivering frames
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+#[serde(default)]
+pub struct CaptureFailurePreferences {
+    pub policy: CaptureFailurePolicy,
+    /// No new frame within this time (ms) counts as a capture failure
+    pub stall_timeout_ms: u64,
+    /// Fade duration (ms) for `CaptureFailurePolicy::FadeToBlack`
+    pub fade_ms: u64,
+    /// RGB color shown by `CaptureFailurePolicy::DiagnosticColor`
+    pub diagnostic_color: [u8; 3],
+}
+
+/// Where LED data goes; every frame is sent to all sinks (see `validate_output_sinks`)
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+#[serde(default)]
+pub struct OutputPreferences {
+    /// Output sinks; an empty list sends nothing
+    pub sinks: Vec<OutputSinkConfig>,
+    /// Local address the sending UDP sockets bind to, picking the egress NIC on multi-homed
+    /// machines; `None` lets the OS choose (see `apply_local_bind_address`)
+    pub local_bind_address: Option<std::net::IpAddr>,
+    /// Fade-in duration in milliseconds when a board comes back online; 0 restores full
+    /// brightness instantly (see `ReconnectFade`)
+    pub reconnect_fade_ms: u64,
+}
+
+/// How display screenshots are downscaled for the `ambient-light://displays` preview
+///
+/// Larger intermediate sizes and smoother filters look better but cost more CPU
+/// (see `plan_preview_resize`)
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+#[serde(default)]
+pub struct PreviewScalingPreferences {
+    /// Intermediate width for the fast first pass; 0 disables the two-step resize
+    pub intermediate_width: u32,
+    /// Intermediate height for the fast first pass; 0 disables the two-step resize
+    pub intermediate_height: u32,
+    /// Filter for the first pass down to the intermediate size
+    pub intermediate_filter: PreviewFilter,
+    /// Filter for the final pass to the requested size
+    pub final_filter: PreviewFilter,
+}
+
+/// Outbound HTTP callback fired on ambient light state changes (see `webhook::notify`)
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+#[serde(default)]
+pub struct WebhookPreferences {
+    /// `http://` URL to POST to; empty disables the webhook
+    pub url: String,
+    /// Events that trigger a request
+    pub events: Vec<WebhookEvent>,
+    /// Timeout of a single request in milliseconds
+    pub timeout_ms: u64,
+    /// Retries after a failed request
+    pub max_retries: u32,
+}
+
 // DisplayPreferences removed - no implemented features
 
 impl Default for WindowPreferences {
@@ -47,6 +277,79 @@ impl Default for WindowPreferences {
     }
 }
 
+/// Smallest view scale accepted; the frontend fits wide multi-display layouts well below 1.0
+pub const MIN_VIEW_SCALE: f64 = 0.01;
+/// Largest view scale accepted
+pub const MAX_VIEW_SCALE: f64 = 4.0;
+/// Smallest window width restored or persisted
+pub const MIN_WINDOW_WIDTH: f64 = 400.0;
+/// Smallest window height restored or persisted
+pub const MIN_WINDOW_HEIGHT: f64 = 300.0;
+/// Largest window width/height restored or persisted
+pub const MAX_WINDOW_DIMENSION: f64 = 16384.0;
+
+/// Validate a view scale before it is persisted
+pub fn validate_view_scale(scale: f64) -> anyhow::Result<()> {
+    if !scale.is_finite() || !(MIN_VIEW_SCALE..=MAX_VIEW_SCALE).contains(&scale) {
+        anyhow::bail!(
+            "view scale must be between {MIN_VIEW_SCALE} and {MAX_VIEW_SCALE}, got {scale}"
+        );
+    }
+    Ok(())
+}
+
+fn validate_window_size(width: f64, height: f64) -> anyhow::Result<()> {
+    if !width.is_finite() || !(MIN_WINDOW_WIDTH..=MAX_WINDOW_DIMENSION).contains(&width) {
+        anyhow::bail!(
+            "window width must be between {MIN_WINDOW_WIDTH} and {MAX_WINDOW_DIMENSION}, got {width}"
+        );
+    }
+    if !height.is_finite() || !(MIN_WINDOW_HEIGHT..=MAX_WINDOW_DIMENSION).contains(&height) {
+        anyhow::bail!(
+            "window height must be between {MIN_WINDOW_HEIGHT} and {MAX_WINDOW_DIMENSION}, got {height}"
+        );
+    }
+    Ok(())
+}
+
+fn validate_window_position(x: f64, y: f64) -> anyhow::Result<()> {
+    if !x.is_finite() || !y.is_finite() {
+        anyhow::bail!("window position must be finite, got ({x}, {y})");
+    }
+    Ok(())
+}
+
+impl WindowPreferences {
+    /// Reject sizes outside the supported range and non-finite positions
+    pub fn validate(&self) -> anyhow::Result<()> {
+        validate_window_size(self.width, self.height)?;
+        validate_window_position(self.x.unwrap_or_default(), self.y.unwrap_or_default())
+    }
+
+    /// Copy with the size clamped into the supported range, used when restoring a
+    /// hand-edited or corrupted preferences file. Non-finite sizes fall back to the
+    /// defaults and a non-finite position lets the system place the window.
+    pub fn clamped(&self) -> Self {
+        let defaults = Self::default();
+        let clamp = |value: f64, default: f64, min: f64| {
+            if value.is_finite() {
+                value.clamp(min, MAX_WINDOW_DIMENSION)
+            } else {
+                default
+            }
+        };
+        let position_valid = self.x.is_none_or(f64::is_finite) && self.y.is_none_or(f64::is_finite);
+
+        Self {
+            width: clamp(self.width, defaults.width, MIN_WINDOW_WIDTH),
+            height: clamp(self.height, defaults.height, MIN_WINDOW_HEIGHT),
+            x: if position_valid { self.x } else { None },
+            y: if position_valid { self.y } else { None },
+            ..self.clone()
+        }
+    }
+}
+
 impl Default for UIPreferences {
     fn default() -> Self {
         Self {
@@ -58,9 +361,291 @@ impl Default for UIPreferences {
     }
 }
 
+impl Default for OscPreferences {
+    fn default() -> Self {
+        Self {
+            enabled: false,
+            port: 9000,
+        }
+    }
+}
+
+impl Default for ConfigUpdatePreferences {
+    fn default() -> Self {
+        Self { debounce_ms: 150 }
+    }
+}
+
+impl SamplingPreferences {
+    /// Sample points per LED, falling back to the default when the stored value is invalid
+    pub fn effective_points_per_led(&self) -> usize {
+        match crate::screenshot::validate_sample_points_per_led(self.points_per_led) {
+            Ok(_) => self.points_per_led,
+            Err(_) => crate::screenshot::DEFAULT_SAMPLE_POINTS_PER_LED,
+        }
+    }
+
+    /// Color interpolation strength, treating invalid stored values as disabled
+    pub fn effective_interpolation_strength(&self) -> f32 {
+        match crate::screenshot::validate_interpolation_strength(self.interpolation_strength) {
+            Ok(_) => self.interpolation_strength,
+            Err(_) => 0.0,
+        }
+    }
+}
+
+impl Default for SamplingPreferences {
+    fn default() -> Self {
+        Self {
+            points_per_led: crate::screenshot::DEFAULT_SAMPLE_POINTS_PER_LED,
+            black_level: 0,
+            interpolation_strength: 0.0,
+            averaging_color_space: AveragingColorSpace::default(),
+            bilinear_sampling: false,
+            vertical_gradient_sampling: false,
+            frame_sync_mode: FrameSyncMode::default(),
+            color_count_policy: ColorCountPolicy::default(),
+            display_sleep_policy: DisplaySleepPolicy::default(),
+            detect_sleep_from_black_frames: false,
+            capture_backend: CaptureBackend::default(),
+            sampling_strategy: SamplingStrategy::default(),
+        }
+    }
+}
+
+impl Default for FullscreenCapturePreferences {
+    fn default() -> Self {
+        Self {
+            enabled: false,
+            primary_display_only: true,
+            frame_interval_ms: 100,
+        }
+    }
+}
+
+impl Default for AutoBrightnessPreferences {
+    fn default() -> Self {
+        Self {
+            enabled: false,
+            mode: AutoBrightnessMode::Inverse,
+            strength: 0.5,
+        }
+    }
+}
+
+impl Default for PaletteQuantizationPreferences {
+    fn default() -> Self {
+        Self {
+            enabled: false,
+            palette: crate::led_data_processor::DEFAULT_QUANTIZATION_PALETTE.to_vec(),
+        }
+    }
+}
+
+impl Default for ColorTemperatureShiftPreferences {
+    fn default() -> Self {
+        Self {
+            enabled: false,
+            day_kelvin: 6500,
+            night_kelvin: 3400,
+            sunrise_minute: 7 * 60,
+            sunset_minute: 19 * 60,
+            transition_minutes: 60,
+        }
+    }
+}
+
+impl Default for CursorFocusPreferences {
+    fn default() -> Self {
+        Self {
+            enabled: false,
+            strength: 0.5,
+        }
+    }
+}
+
+impl Default for ServerPreferences {
+    fn default() -> Self {
+        Self {
+            http_host: "127.0.0.1".to_string(),
+            http_port: 24101,
+            websocket_host: "127.0.0.1".to_string(),
+            websocket_port: 24102,
+            auth_token: None,
+            auth_exempt_loopback: true,
+        }
+    }
+}
+
+impl Default for CaptureFailurePreferences {
+    fn default() -> Self {
+        Self {
+            policy: CaptureFailurePolicy::Hold,
+            stall_timeout_ms: 2000,
+            fade_ms: 1000,
+            diagnostic_color: [128, 0, 0],
+        }
+    }
+}
+
+impl Default for PreviewScalingPreferences {
+    fn default() -> Self {
+        Self {
+            intermediate_width: 800,
+            intermediate_height: 450,
+            intermediate_filter: PreviewFilter::Nearest,
+            final_filter: PreviewFilter::Triangle,
+        }
+    }
+}
+
+impl Default for WebhookPreferences {
+    fn default() -> Self {
+        Self {
+            url: String::new(),
+            events: vec![
+                WebhookEvent::AmbientLightToggled,
+                WebhookEvent::DataSendModeChanged,
+            ],
+            timeout_ms: 5000,
+            max_retries: 2,
+        }
+    }
+}
+
+impl Default for OutputPreferences {
+    fn default() -> Self {
+        Self {
+            sinks: vec![OutputSinkConfig::native()],
+            local_bind_address: None,
+            reconnect_fade_ms: crate::led_data_sender::DEFAULT_RECONNECT_FADE_MS,
+        }
+    }
+}
+
 // DisplayPreferences default implementation removed
 
+/// Keep a loaded section if it passes validation, otherwise fall back to its defaults
+fn valid_or_default<T: Default>(
+    section: T,
+    name: &str,
+    validate: impl FnOnce(&T) -> anyhow::Result<()>,
+) -> T {
+    match validate(&section) {
+        Ok(()) => section,
+        Err(e) => {
+            log::warn!("Invalid {name} preferences ({e}), using defaults");
+            T::default()
+        }
+    }
+}
+
 impl UserPreferences {
+    /// Run every section's validation, so a full-preferences update is held to the
+    /// same rules as the per-section endpoints
+    pub fn validate(&self) -> anyhow::Result<()> {
+        self.window.validate()?;
+        validate_view_scale(self.ui.view_scale)?;
+        crate::screenshot::validate_sample_points_per_led(self.sampling.points_per_led)?;
+        crate::screenshot::validate_interpolation_strength(self.sampling.interpolation_strength)?;
+        crate::screenshot_manager::validate_fullscreen_frame_interval(
+            self.fullscreen_capture.frame_interval_ms,
+        )?;
+        crate::led_data_processor::validate_auto_brightness_strength(
+            self.auto_brightness.strength,
+        )?;
+        crate::led_data_processor::validate_palette(&self.palette_quantization.palette)?;
+        crate::led_data_processor::validate_color_temperature_shift(&self.color_temperature_shift)?;
+        crate::led_data_processor::validate_cursor_focus_strength(self.cursor_focus.strength)?;
+        crate::ambient_light::validate_capture_failure(&self.capture_failure)?;
+        crate::output_sink::validate_output_sinks(&self.output.sinks)?;
+        crate::output_sink::validate_local_bind_address(self.output.local_bind_address)?;
+        crate::preview_scaling::validate_preview_scaling(&self.preview_scaling)?;
+        crate::webhook::validate_webhook(&self.webhook)
+    }
+
+    /// Copy that passes `validate`, used when loading a hand-edited or corrupted file:
+    /// the window size and view scale are clamped into range, other invalid values
+    /// fall back to their defaults.
+    pub fn clamped(&self) -> Self {
+        let view_scale = if self.ui.view_scale.is_finite() {
+            self.ui.view_scale.clamp(MIN_VIEW_SCALE, MAX_VIEW_SCALE)
+        } else {
+            UIPreferences::default().view_scale
+        };
+        let sampling = SamplingPreferences {
+            points_per_led: self.sampling.effective_points_per_led(),
+            interpolation_strength: self.sampling.effective_interpolation_strength(),
+            ..self.sampling.clone()
+        };
+
+        Self {
+            window: self.window.clamped(),
+            ui: UIPreferences {
+                view_scale,
+                ..self.ui.clone()
+            },
+            sampling,
+            fullscreen_capture: valid_or_default(
+                self.fullscreen_capture.clone(),
+                "fullscreen capture",
+                |section| {
+                    crate::screenshot_manager::validate_fullscreen_frame_interval(
+                        section.frame_interval_ms,
+                    )
+                },
+            ),
+            auto_brightness: valid_or_default(
+                self.auto_brightness.clone(),
+                "auto brightness",
+                |section| {
+                    crate::led_data_processor::validate_auto_brightness_strength(section.strength)
+                },
+            ),
+            palette_quantization: valid_or_default(
+                self.palette_quantization.clone(),
+                "palette quantization",
+                |section| crate::led_data_processor::validate_palette(&section.palette),
+            ),
+            color_temperature_shift: valid_or_default(
+                self.color_temperature_shift.clone(),
+                "color temperature shift",
+                crate::led_data_processor::validate_color_temperature_shift,
+            ),
+            cursor_focus: valid_or_default(self.cursor_focus.clone(), "cursor focus", |section| {
+                crate::led_data_processor::validate_cursor_focus_strength(section.strength)
+            }),
+            capture_failure: valid_or_default(
+                self.capture_failure.clone(),
+                "capture failure",
+                crate::ambient_light::validate_capture_failure,
+            ),
+            output: OutputPreferences {
+                // An address that is gone (e.g. after switching networks) only loses the
+                // bind override, the sinks are kept
+                local_bind_address: valid_or_default(
+                    self.output.local_bind_address,
+                    "local bind address",
+                    |address| crate::output_sink::validate_local_bind_address(*address),
+                ),
+                ..valid_or_default(self.output.clone(), "output", |section| {
+                    crate::output_sink::validate_output_sinks(&section.sinks)
+                })
+            },
+            preview_scaling: valid_or_default(
+                self.preview_scaling.clone(),
+                "preview scaling",
+                crate::preview_scaling::validate_preview_scaling,
+            ),
+            webhook: valid_or_default(
+                self.webhook.clone(),
+                "webhook",
+                crate::webhook::validate_webhook,
+            ),
+            ..self.clone()
+        }
+    }
+
     /// Get the configuration file path
     fn get_config_path() -> anyhow::Result<PathBuf> {
         let config_dir =
@@ -73,7 +658,7 @@ impl UserPreferences {
         let config_path = Self::get_config_path()?;
 
         if !config_path.exists() {
-            info!("User preferences config file not found, using defaults");
+            log::info!("User preferences config file not found, using defaults");
             return Ok(Self::default());
         }
 
@@ -82,6 +667,11 @@ impl UserPreferences {
         Ok(config)
     }
 
+    /// Copy the configuration file to a timestamped backup, returns None if there is no file yet
+    pub async fn backup_config() -> anyhow::Result<Option<PathBuf>> {
+        crate::ambient_light::backup_config_file(&Self::get_config_path()?).await
+    }
+
     /// Write configuration to file
     pub async fn write_config(&self) -> anyhow::Result<()> {
         let config_path = Self::get_config_path()?;
@@ -99,6 +689,23 @@ impl UserPreferences {
 
 pub struct UserPreferencesManager {
     preferences: Arc<RwLock<UserPreferences>>,
+    changes: watch::Sender<UserPreferences>,
+}
+
+/// Cached copy of the preferences for per-frame loops, refreshed only after preferences change
+pub struct PreferencesSnapshot {
+    receiver: watch::Receiver<UserPreferences>,
+    current: UserPreferences,
+}
+
+impl PreferencesSnapshot {
+    /// Get the latest preferences without taking the manager lock
+    pub fn current(&mut self) -> &UserPreferences {
+        if self.receiver.has_changed().unwrap_or(false) {
+            self.current = self.receiver.borrow_and_update().clone();
+        }
+        &self.current
+    }
 }
 
 impl UserPreferencesManager {
@@ -108,9 +715,9 @@ impl UserPreferencesManager {
         USER_PREFERENCES_MANAGER
             .get_or_init(|| async {
                 let preferences = match UserPreferences::read_config().await {
-                    Ok(prefs) => prefs,
+                    Ok(prefs) => prefs.clamped(),
                     Err(e) => {
-                        warn!(
+                        log::warn!(
                             "Failed to read user preferences config: {}, using defaults",
                             e
                         );
@@ -118,8 +725,10 @@ impl UserPreferencesManager {
                     }
                 };
 
+                let (changes, _) = watch::channel(preferences.clone());
                 Self {
                     preferences: Arc::new(RwLock::new(preferences)),
+                    changes,
                 }
             })
             .await
@@ -130,14 +739,33 @@ impl UserPreferencesManager {
         self.preferences.read().await.clone()
     }
 
-    /// Update user preferences
+    /// Subscribe to preference changes for loops that would otherwise read them every frame
+    pub fn snapshot(&self) -> PreferencesSnapshot {
+        let mut receiver = self.changes.subscribe();
+        let current = receiver.borrow_and_update().clone();
+        PreferencesSnapshot { receiver, current }
+    }
+
+    /// Restore default preferences after backing up the current file, returns the backup path
+    pub async fn reset_to_defaults(&self) -> anyhow::Result<Option<PathBuf>> {
+        let backup_path = UserPreferences::backup_config().await?;
+        self.update_preferences(UserPreferences::default()).await?;
+        log::info!("User preferences reset to defaults");
+        Ok(backup_path)
+    }
+
+    /// Update user preferences, rejecting values any section endpoint would reject
     pub async fn update_preferences(&self, preferences: UserPreferences) -> anyhow::Result<()> {
+        preferences.validate()?;
+
         // Write to file first
         preferences.write_config().await?;
 
         // Update in-memory state
         let mut current_prefs = self.preferences.write().await;
         *current_prefs = preferences.clone();
+        drop(current_prefs);
+        self.changes.send_replace(preferences.clone());
 
         // 通过WebSocket广播用户偏好设置变化
         crate::websocket_events::WebSocketEventPublisher::global()
@@ -153,6 +781,7 @@ impl UserPreferencesManager {
         &self,
         window_prefs: WindowPreferences,
     ) -> anyhow::Result<()> {
+        window_prefs.validate()?;
         let mut preferences = self.get_preferences().await;
         preferences.window = window_prefs;
         self.update_preferences(preferences).await
@@ -160,6 +789,7 @@ impl UserPreferencesManager {
 
     /// Update UI preferences
     pub async fn update_ui_preferences(&self, ui_prefs: UIPreferences) -> anyhow::Result<()> {
+        validate_view_scale(ui_prefs.view_scale)?;
         let mut preferences = self.get_preferences().await;
         preferences.ui = ui_prefs;
         self.update_preferences(preferences).await
@@ -167,6 +797,7 @@ impl UserPreferencesManager {
 
     /// Update specific window property
     pub async fn update_window_size(&self, width: f64, height: f64) -> anyhow::Result<()> {
+        validate_window_size(width, height)?;
         let mut preferences = self.get_preferences().await;
         preferences.window.width = width;
         preferences.window.height = height;
@@ -175,6 +806,7 @@ impl UserPreferencesManager {
 
     /// Update window position
     pub async fn update_window_position(&self, x: f64, y: f64) -> anyhow::Result<()> {
+        validate_window_position(x, y)?;
         let mut preferences = self.get_preferences().await;
         preferences.window.x = Some(x);
         preferences.window.y = Some(y);
@@ -183,6 +815,7 @@ impl UserPreferencesManager {
 
     /// Update view scale
     pub async fn update_view_scale(&self, scale: f64) -> anyhow::Result<()> {
+        validate_view_scale(scale)?;
         let mut preferences = self.get_preferences().await;
         preferences.ui.view_scale = scale;
         self.update_preferences(preferences).await
@@ -221,5 +854,488 @@ impl UserPreferencesManager {
         preferences.ui.night_mode_theme.clone()
     }
 
+    /// Update sample points per LED
+    pub async fn update_sample_points_per_led(&self, points: usize) -> anyhow::Result<()> {
+        crate::screenshot::validate_sample_points_per_led(points)?;
+        let mut preferences = self.get_preferences().await;
+        preferences.sampling.points_per_led = points;
+        self.update_preferences(preferences).await
+    }
+
+    /// Get sample points per LED, falling back to the default when the stored value is invalid
+    pub async fn get_sample_points_per_led(&self) -> usize {
+        self.preferences
+            .read()
+            .await
+            .sampling
+            .effective_points_per_led()
+    }
+
+    /// Update the sampling black level threshold
+    pub async fn update_black_level(&self, black_level: u8) -> anyhow::Result<()> {
+        let mut preferences = self.get_preferences().await;
+        preferences.sampling.black_level = black_level;
+        self.update_preferences(preferences).await
+    }
+
+    /// Get the sampling black level threshold
+    pub async fn get_black_level(&self) -> u8 {
+        self.preferences.read().await.sampling.black_level
+    }
+
+    /// Update the color interpolation strength between neighboring LEDs
+    pub async fn update_interpolation_strength(&self, strength: f32) -> anyhow::Result<()> {
+        crate::screenshot::validate_interpolation_strength(strength)?;
+        let mut preferences = self.get_preferences().await;
+        preferences.sampling.interpolation_strength = strength;
+        self.update_preferences(preferences).await
+    }
+
+    /// Get the color interpolation strength, treating invalid stored values as disabled
+    pub async fn get_interpolation_strength(&self) -> f32 {
+        self.preferences
+            .read()
+            .await
+            .sampling
+            .effective_interpolation_strength()
+    }
+
+    /// Update the color space used when averaging sample points
+    pub async fn update_averaging_color_space(
+        &self,
+        color_space: AveragingColorSpace,
+    ) -> anyhow::Result<()> {
+        let mut preferences = self.get_preferences().await;
+        preferences.sampling.averaging_color_space = color_space;
+        self.update_preferences(preferences).await
+    }
+
+    /// Get the color space used when averaging sample points
+    pub async fn get_averaging_color_space(&self) -> AveragingColorSpace {
+        self.preferences.read().await.sampling.averaging_color_space
+    }
+
+    /// Enable or disable bilinear sub-pixel sampling
+    pub async fn update_bilinear_sampling(&self, enabled: bool) -> anyhow::Result<()> {
+        let mut preferences = self.get_preferences().await;
+        preferences.sampling.bilinear_sampling = enabled;
+        self.update_preferences(preferences).await
+    }
+
+    /// Get whether bilinear sub-pixel sampling is enabled
+    pub async fn get_bilinear_sampling(&self) -> bool {
+        self.preferences.read().await.sampling.bilinear_sampling
+    }
+
+    /// Enable or disable vertical gradient sampling for left/right strips
+    pub async fn update_vertical_gradient_sampling(&self, enabled: bool) -> anyhow::Result<()> {
+        let mut preferences = self.get_preferences().await;
+        preferences.sampling.vertical_gradient_sampling = enabled;
+        self.update_preferences(preferences).await
+    }
+
+    /// Get whether vertical gradient sampling is enabled for left/right strips
+    pub async fn get_vertical_gradient_sampling(&self) -> bool {
+        self.preferences
+            .read()
+            .await
+            .sampling
+            .vertical_gradient_sampling
+    }
+
+    /// Update when multi-display colors are combined into a frame and sent
+    pub async fn update_frame_sync_mode(&self, mode: FrameSyncMode) -> anyhow::Result<()> {
+        let mut preferences = self.get_preferences().await;
+        preferences.sampling.frame_sync_mode = mode;
+        self.update_preferences(preferences).await
+    }
+
+    /// Get when multi-display colors are combined into a frame and sent
+    pub async fn get_frame_sync_mode(&self) -> FrameSyncMode {
+        self.preferences.read().await.sampling.frame_sync_mode
+    }
+
+    /// Update how sampled colors are fitted to the configured LED count
+    pub async fn update_color_count_policy(&self, policy: ColorCountPolicy) -> anyhow::Result<()> {
+        let mut preferences = self.get_preferences().await;
+        preferences.sampling.color_count_policy = policy;
+        self.update_preferences(preferences).await
+    }
+
+    /// Get how sampled colors are fitted to the configured LED count
+    pub async fn get_color_count_policy(&self) -> ColorCountPolicy {
+        self.preferences.read().await.sampling.color_count_policy
+    }
+
+    /// Update how strips behave while their display is asleep or turned off,
+    /// and optionally whether black frames alone count as sleep
+    pub async fn update_display_sleep_policy(
+        &self,
+        policy: DisplaySleepPolicy,
+        detect_sleep_from_black_frames: Option<bool>,
+    ) -> anyhow::Result<()> {
+        let mut preferences = self.get_preferences().await;
+        preferences.sampling.display_sleep_policy = policy;
+        if let Some(detect) = detect_sleep_from_black_frames {
+            preferences.sampling.detect_sleep_from_black_frames = detect;
+        }
+        self.update_preferences(preferences).await
+    }
+
+    /// Get how strips behave while their display is asleep or turned off
+    pub async fn get_display_sleep_policy(&self) -> DisplaySleepPolicy {
+        self.preferences.read().await.sampling.display_sleep_policy
+    }
+
+    /// Update the screen capture backend
+    pub async fn update_capture_backend(&self, backend: CaptureBackend) -> anyhow::Result<()> {
+        let mut preferences = self.get_preferences().await;
+        preferences.sampling.capture_backend = backend;
+        self.update_preferences(preferences).await
+    }
+
+    /// Get the screen capture backend
+    pub async fn get_capture_backend(&self) -> CaptureBackend {
+        self.preferences.read().await.sampling.capture_backend
+    }
+
+    /// Update how LED colors are sampled from the screen
+    pub async fn update_sampling_strategy(&self, strategy: SamplingStrategy) -> anyhow::Result<()> {
+        let mut preferences = self.get_preferences().await;
+        preferences.sampling.sampling_strategy = strategy;
+        self.update_preferences(preferences).await
+    }
+
+    /// Get how LED colors are sampled from the screen
+    pub async fn get_sampling_strategy(&self) -> SamplingStrategy {
+        self.preferences.read().await.sampling.sampling_strategy
+    }
+
+    /// Persist the last send mode and its payload, skipping the write when unchanged
+    pub async fn update_send_mode(
+        &self,
+        last_mode: DataSendMode,
+        manual_color: Option<[u8; 3]>,
+    ) -> anyhow::Result<()> {
+        let send_mode = SendModePreferences {
+            last_mode,
+            manual_color,
+        };
+        let mut preferences = self.get_preferences().await;
+        if preferences.send_mode == send_mode {
+            return Ok(());
+        }
+        preferences.send_mode = send_mode;
+        self.update_preferences(preferences).await
+    }
+
+    /// Get the persisted send mode
+    pub async fn get_send_mode(&self) -> SendModePreferences {
+        self.preferences.read().await.send_mode.clone()
+    }
+
+    /// Update the fullscreen capture profile
+    pub async fn update_fullscreen_capture(
+        &self,
+        fullscreen_capture: FullscreenCapturePreferences,
+    ) -> anyhow::Result<()> {
+        crate::screenshot_manager::validate_fullscreen_frame_interval(
+            fullscreen_capture.frame_interval_ms,
+        )?;
+        let mut preferences = self.get_preferences().await;
+        preferences.fullscreen_capture = fullscreen_capture;
+        self.update_preferences(preferences).await
+    }
+
+    /// Get the fullscreen capture profile
+    pub async fn get_fullscreen_capture(&self) -> FullscreenCapturePreferences {
+        self.preferences.read().await.fullscreen_capture.clone()
+    }
+
+    /// Update the auto brightness settings
+    pub async fn update_auto_brightness(
+        &self,
+        auto_brightness: AutoBrightnessPreferences,
+    ) -> anyhow::Result<()> {
+        crate::led_data_processor::validate_auto_brightness_strength(auto_brightness.strength)?;
+        let mut preferences = self.get_preferences().await;
+        preferences.auto_brightness = auto_brightness;
+        self.update_preferences(preferences).await
+    }
+
+    /// Get the auto brightness settings
+    pub async fn get_auto_brightness(&self) -> AutoBrightnessPreferences {
+        self.preferences.read().await.auto_brightness.clone()
+    }
+
+    /// Update the palette quantization settings
+    pub async fn update_palette_quantization(
+        &self,
+        palette_quantization: PaletteQuantizationPreferences,
+    ) -> anyhow::Result<()> {
+        crate::led_data_processor::validate_palette(&palette_quantization.palette)?;
+        let mut preferences = self.get_preferences().await;
+        preferences.palette_quantization = palette_quantization;
+        self.update_preferences(preferences).await
+    }
+
+    /// Update the time-based color temperature shift settings
+    pub async fn update_color_temperature_shift(
+        &self,
+        color_temperature_shift: ColorTemperatureShiftPreferences,
+    ) -> anyhow::Result<()> {
+        crate::led_data_processor::validate_color_temperature_shift(&color_temperature_shift)?;
+        let mut preferences = self.get_preferences().await;
+        preferences.color_temperature_shift = color_temperature_shift;
+        self.update_preferences(preferences).await
+    }
+
+    /// Get the time-based color temperature shift settings
+    pub async fn get_color_temperature_shift(&self) -> ColorTemperatureShiftPreferences {
+        self.preferences
+            .read()
+            .await
+            .color_temperature_shift
+            .clone()
+    }
+
+    /// Update the cursor-follow display focus settings
+    pub async fn update_cursor_focus(
+        &self,
+        cursor_focus: CursorFocusPreferences,
+    ) -> anyhow::Result<()> {
+        crate::led_data_processor::validate_cursor_focus_strength(cursor_focus.strength)?;
+        let mut preferences = self.get_preferences().await;
+        preferences.cursor_focus = cursor_focus;
+        self.update_preferences(preferences).await
+    }
+
+    /// Get the cursor-follow display focus settings
+    pub async fn get_cursor_focus(&self) -> CursorFocusPreferences {
+        self.preferences.read().await.cursor_focus.clone()
+    }
+
+    /// Get the palette quantization settings
+    pub async fn get_palette_quantization(&self) -> PaletteQuantizationPreferences {
+        self.preferences.read().await.palette_quantization.clone()
+    }
+
+    /// Get the server bind addresses
+    pub async fn get_server(&self) -> ServerPreferences {
+        self.preferences.read().await.server.clone()
+    }
+
+    /// Update the screen capture failure fallback
+    pub async fn update_capture_failure(
+        &self,
+        capture_failure: CaptureFailurePreferences,
+    ) -> anyhow::Result<()> {
+        crate::ambient_light::validate_capture_failure(&capture_failure)?;
+        let mut preferences = self.get_preferences().await;
+        preferences.capture_failure = capture_failure;
+        self.update_preferences(preferences).await
+    }
+
+    /// Get the screen capture failure fallback
+    pub async fn get_capture_failure(&self) -> CaptureFailurePreferences {
+        self.preferences.read().await.capture_failure.clone()
+    }
+
+    /// Update the LED output sinks
+    pub async fn update_output(&self, output: OutputPreferences) -> anyhow::Result<()> {
+        crate::output_sink::validate_output_sinks(&output.sinks)?;
+        crate::output_sink::validate_local_bind_address(output.local_bind_address)?;
+        let mut preferences = self.get_preferences().await;
+        preferences.output = output;
+        self.update_preferences(preferences).await
+    }
+
+    /// Get the LED output sinks
+    pub async fn get_output(&self) -> OutputPreferences {
+        self.preferences.read().await.output.clone()
+    }
+
+    /// Update the display preview scaling
+    pub async fn update_preview_scaling(
+        &self,
+        preview_scaling: PreviewScalingPreferences,
+    ) -> anyhow::Result<()> {
+        crate::preview_scaling::validate_preview_scaling(&preview_scaling)?;
+        let mut preferences = self.get_preferences().await;
+        preferences.preview_scaling = preview_scaling;
+        self.update_preferences(preferences).await
+    }
+
+    /// Get the display preview scaling
+    pub async fn get_preview_scaling(&self) -> PreviewScalingPreferences {
+        self.preferences.read().await.preview_scaling.clone()
+    }
+
+    /// Update the state change webhook
+    pub async fn update_webhook(&self, webhook: WebhookPreferences) -> anyhow::Result<()> {
+        crate::webhook::validate_webhook(&webhook)?;
+        let mut preferences = self.get_preferences().await;
+        preferences.webhook = webhook;
+        self.update_preferences(preferences).await
+    }
+
+    /// Get the state change webhook
+    pub async fn get_webhook(&self) -> WebhookPreferences {
+        self.preferences.read().await.webhook.clone()
+    }
+
     // Removed update_last_visited_page - feature not implemented
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_preferences_snapshot_refreshes_only_after_change() {
+        let (changes, _) = watch::channel(UserPreferences::default());
+        let mut receiver = changes.subscribe();
+        let current = receiver.borrow_and_update().clone();
+        let mut snapshot = PreferencesSnapshot { receiver, current };
+        assert_eq!(snapshot.current().sampling.black_level, 0);
+
+        let mut preferences = UserPreferences::default();
+        preferences.sampling.black_level = 12;
+        preferences.sampling.points_per_led = 3;
+        changes.send_replace(preferences);
+
+        let sampling = &snapshot.current().sampling;
+        assert_eq!(sampling.black_level, 12);
+        // Invalid stored values still fall back to the defaults
+        assert_eq!(
+            sampling.effective_points_per_led(),
+            crate::screenshot::DEFAULT_SAMPLE_POINTS_PER_LED
+        );
+    }
+
+    #[test]
+    fn test_send_mode_round_trip() {
+        let preferences = UserPreferences {
+            send_mode: SendModePreferences {
+                last_mode: DataSendMode::ManualColor,
+                manual_color: Some([255, 128, 0]),
+            },
+            ..Default::default()
+        };
+
+        let content = toml::to_string_pretty(&preferences).unwrap();
+        let restored: UserPreferences = toml::from_str(&content).unwrap();
+        assert_eq!(restored.send_mode, preferences.send_mode);
+
+        // Files written before the send mode was persisted fall back to the default
+        let mut legacy: toml::Table = toml::from_str(&content).unwrap();
+        legacy.remove("send_mode");
+        let restored: UserPreferences = toml::from_str(&legacy.to_string()).unwrap();
+        assert_eq!(restored.send_mode, SendModePreferences::default());
+    }
+
+    #[test]
+    fn test_full_preferences_validated_and_clamped_on_load() {
+        assert!(UserPreferences::default().validate().is_ok());
+
+        let mut corrupted = UserPreferences::default();
+        corrupted.ui.view_scale = 40.0;
+        corrupted.sampling.points_per_led = 3;
+        corrupted.auto_brightness.strength = 2.0;
+        corrupted.webhook.timeout_ms = 0;
+        corrupted.sampling.black_level = 12;
+        assert!(corrupted.validate().is_err());
+
+        let clamped = corrupted.clamped();
+        assert!(clamped.validate().is_ok());
+        assert_eq!(clamped.ui.view_scale, MAX_VIEW_SCALE);
+        assert_eq!(
+            clamped.sampling.points_per_led,
+            crate::screenshot::DEFAULT_SAMPLE_POINTS_PER_LED
+        );
+        assert_eq!(
+            clamped.auto_brightness.strength,
+            AutoBrightnessPreferences::default().strength
+        );
+        assert_eq!(
+            clamped.webhook.timeout_ms,
+            WebhookPreferences::default().timeout_ms
+        );
+        // Valid values are kept
+        assert_eq!(clamped.sampling.black_level, 12);
+
+        corrupted.ui.view_scale = f64::NAN;
+        assert_eq!(
+            corrupted.clamped().ui.view_scale,
+            UIPreferences::default().view_scale
+        );
+    }
+
+    #[test]
+    fn test_view_scale_out_of_range_rejected() {
+        assert!(validate_view_scale(UIPreferences::default().view_scale).is_ok());
+        assert!(validate_view_scale(MIN_VIEW_SCALE).is_ok());
+        assert!(validate_view_scale(MAX_VIEW_SCALE).is_ok());
+        assert!(validate_view_scale(0.0).is_err());
+        assert!(validate_view_scale(-1.0).is_err());
+        assert!(validate_view_scale(MAX_VIEW_SCALE + 0.1).is_err());
+        assert!(validate_view_scale(f64::NAN).is_err());
+        assert!(validate_view_scale(f64::INFINITY).is_err());
+    }
+
+    #[test]
+    fn test_window_preferences_validation() {
+        assert!(WindowPreferences::default().validate().is_ok());
+
+        let tiny = WindowPreferences {
+            width: 10.0,
+            height: 0.0,
+            ..Default::default()
+        };
+        assert!(tiny.validate().is_err());
+
+        let huge = WindowPreferences {
+            width: MAX_WINDOW_DIMENSION * 2.0,
+            ..Default::default()
+        };
+        assert!(huge.validate().is_err());
+
+        let nan_position = WindowPreferences {
+            x: Some(f64::NAN),
+            y: Some(100.0),
+            ..Default::default()
+        };
+        assert!(nan_position.validate().is_err());
+    }
+
+    #[test]
+    fn test_window_preferences_clamped_on_restore() {
+        let corrupted = WindowPreferences {
+            width: 10.0,
+            height: f64::NAN,
+            x: Some(f64::INFINITY),
+            y: Some(20.0),
+            maximized: true,
+            minimized_to_tray: false,
+        };
+        let clamped = corrupted.clamped();
+        assert_eq!(clamped.width, MIN_WINDOW_WIDTH);
+        assert_eq!(clamped.height, WindowPreferences::default().height);
+        assert_eq!(clamped.x, None);
+        assert_eq!(clamped.y, None);
+        assert!(clamped.maximized);
+        assert!(clamped.validate().is_ok());
+
+        let oversized = WindowPreferences {
+            width: 1e9,
+            height: 800.0,
+            x: Some(-200.0),
+            y: Some(50.0),
+            ..Default::default()
+        };
+        let clamped = oversized.clamped();
+        assert_eq!(clamped.width, MAX_WINDOW_DIMENSION);
+        assert_eq!(clamped.height, 800.0);
+        assert_eq!(clamped.x, Some(-200.0));
+    }
+}
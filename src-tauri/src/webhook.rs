@@ -0,0 +1,243 @@
+use std::time::Duration;
+
+use hyper::{header, Method, Request, Uri};
+use hyper_util::client::legacy::{connect::HttpConnector, Client};
+use hyper_util::rt::TokioExecutor;
+use log::{debug, warn};
+use serde::{Deserialize, Serialize};
+
+use crate::user_preferences::{UserPreferencesManager, WebhookPreferences};
+
+/// 单次请求超时上限（毫秒）
+pub const MAX_WEBHOOK_TIMEOUT_MS: u64 = 60_000;
+/// 重试次数上限
+pub const MAX_WEBHOOK_RETRIES: u32 = 10;
+
+/// 重试间隔基数，第 n 次重试前等待 n 倍
+const RETRY_BACKOFF: Duration = Duration::from_millis(500);
+
+/// 触发Webhook的事件
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize, utoipa::ToSchema)]
+pub enum WebhookEvent {
+    /// 氛围光开启/关闭
+    AmbientLightToggled,
+    /// LED数据发送模式变化
+    DataSendModeChanged,
+}
+
+/// Webhook请求体
+#[derive(Debug, Clone, Serialize)]
+pub struct WebhookPayload {
+    pub event: WebhookEvent,
+    pub old_state: serde_json::Value,
+    pub new_state: serde_json::Value,
+    pub timestamp: String,
+}
+
+/// 解析Webhook地址，仅支持 `http://`（局域网内的家庭自动化服务）
+pub fn parse_webhook_url(url: &str) -> anyhow::Result<Uri> {
+    let uri: Uri = url
+        .trim()
+        .parse()
+        .map_err(|e| anyhow::anyhow!("Invalid webhook URL '{url}': {e}"))?;
+    if uri.scheme_str() != Some("http") {
+        anyhow::bail!("Webhook URL '{url}' must use http://");
+    }
+    if uri.host().is_none() {
+        anyhow::bail!("Webhook URL '{url}' has no host");
+    }
+    Ok(uri)
+}
+
+/// 校验Webhook配置，地址为空表示关闭
+pub fn validate_webhook(preferences: &WebhookPreferences) -> anyhow::Result<()> {
+    if !preferences.url.is_empty() {
+        parse_webhook_url(&preferences.url)?;
+    }
+    if preferences.timeout_ms == 0 || preferences.timeout_ms > MAX_WEBHOOK_TIMEOUT_MS {
+        anyhow::bail!(
+            "Webhook timeout {}ms must be between 1 and {MAX_WEBHOOK_TIMEOUT_MS}ms",
+            preferences.timeout_ms
+        );
+    }
+    if preferences.max_retries > MAX_WEBHOOK_RETRIES {
+        anyhow::bail!(
+            "Webhook retries {} exceed the maximum of {MAX_WEBHOOK_RETRIES}",
+            preferences.max_retries
+        );
+    }
+    Ok(())
+}
+
+/// 按配置通知状态变化
+///
+/// 请求在后台任务中发送，失败只记录日志，不阻塞状态切换。
+pub async fn notify<T: Serialize>(event: WebhookEvent, old_state: &T, new_state: &T) {
+    let preferences = UserPreferencesManager::global().await.get_webhook().await;
+    if preferences.url.is_empty() || !preferences.events.contains(&event) {
+        return;
+    }
+
+    let payload = WebhookPayload {
+        event,
+        old_state: serde_json::to_value(old_state).unwrap_or_default(),
+        new_state: serde_json::to_value(new_state).unwrap_or_default(),
+        timestamp: chrono::Utc::now().to_rfc3339(),
+    };
+
+    tokio::spawn(async move {
+        match deliver_with_retries(&preferences, &payload).await {
+            Ok(()) => debug!("Webhook {event:?} delivered to {}", preferences.url),
+            Err(e) => warn!(
+                "Failed to deliver webhook {event:?} to {}: {e}",
+                preferences.url
+            ),
+        }
+    });
+}
+
+/// 发送Webhook，失败（连接错误、超时、非2xx响应）时按配置重试
+async fn deliver_with_retries(
+    preferences: &WebhookPreferences,
+    payload: &WebhookPayload,
+) -> anyhow::Result<()> {
+    let uri = parse_webhook_url(&preferences.url)?;
+    let body = serde_json::to_string(payload)?;
+    let timeout = Duration::from_millis(preferences.timeout_ms);
+    let client = Client::builder(TokioExecutor::new()).build_http::<String>();
+
+    let mut attempt = 0;
+    loop {
+        let result = match tokio::time::timeout(timeout, post(&client, &uri, body.clone())).await {
+            Ok(result) => result,
+            Err(_) => Err(anyhow::anyhow!(
+                "timed out after {}ms",
+                preferences.timeout_ms
+            )),
+        };
+
+        match result {
+            Ok(()) => return Ok(()),
+            Err(e) if attempt < preferences.max_retries => {
+                attempt += 1;
+                debug!("Webhook attempt {attempt} failed: {e}, retrying");
+                tokio::time::sleep(RETRY_BACKOFF * attempt).await;
+            }
+            Err(e) => {
+                return Err(anyhow::anyhow!("{e} (after {} attempts)", attempt + 1));
+            }
+        }
+    }
+}
+
+async fn post(
+    client: &Client<HttpConnector, String>,
+    uri: &Uri,
+    body: String,
+) -> anyhow::Result<()> {
+    let request = Request::builder()
+        .method(Method::POST)
+        .uri(uri.clone())
+        .header(header::CONTENT_TYPE, "application/json")
+        .body(body)?;
+    let response = client.request(request).await?;
+    if !response.status().is_success() {
+        anyhow::bail!("unexpected status {}", response.status());
+    }
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use tokio::io::{AsyncReadExt, AsyncWriteExt};
+    use tokio::net::TcpListener;
+
+    /// 读取一个完整的HTTP请求，返回请求体
+    async fn read_request(stream: &mut tokio::net::TcpStream) -> String {
+        let mut buf = Vec::new();
+        let mut chunk = [0u8; 1024];
+        loop {
+            let n = stream.read(&mut chunk).await.unwrap();
+            buf.extend_from_slice(&chunk[..n]);
+            let text = String::from_utf8_lossy(&buf).to_string();
+            if let Some(header_end) = text.find("\r\n\r\n") {
+                let content_length = text[..header_end]
+                    .lines()
+                    .find_map(|line| {
+                        let (name, value) = line.split_once(':')?;
+                        name.eq_ignore_ascii_case("content-length")
+                            .then(|| value.trim().parse::<usize>().unwrap())
+                    })
+                    .unwrap_or(0);
+                if buf.len() >= header_end + 4 + content_length {
+                    return text[header_end + 4..].to_string();
+                }
+            }
+            if n == 0 {
+                panic!("connection closed before request was complete");
+            }
+        }
+    }
+
+    #[tokio::test]
+    async fn test_webhook_retries_until_success() {
+        let listener = TcpListener::bind("127.0.0.1:0").await.unwrap();
+        let addr = listener.local_addr().unwrap();
+
+        let server = tokio::spawn(async move {
+            let mut bodies = Vec::new();
+            for status in ["500 Internal Server Error", "200 OK"] {
+                let (mut stream, _) = listener.accept().await.unwrap();
+                bodies.push(read_request(&mut stream).await);
+                stream
+                    .write_all(
+                        format!(
+                            "HTTP/1.1 {status}\r\ncontent-length: 0\r\nconnection: close\r\n\r\n"
+                        )
+                        .as_bytes(),
+                    )
+                    .await
+                    .unwrap();
+            }
+            bodies
+        });
+
+        let preferences = WebhookPreferences {
+            url: format!("http://{addr}/hooks/ambient-light"),
+            max_retries: 1,
+            ..WebhookPreferences::default()
+        };
+        let payload = WebhookPayload {
+            event: WebhookEvent::AmbientLightToggled,
+            old_state: serde_json::json!({ "enabled": false }),
+            new_state: serde_json::json!({ "enabled": true }),
+            timestamp: "2024-01-01T00:00:00Z".to_string(),
+        };
+
+        assert!(deliver_with_retries(&preferences, &payload).await.is_ok());
+
+        let bodies = server.await.unwrap();
+        assert_eq!(bodies.len(), 2);
+        let body: serde_json::Value = serde_json::from_str(&bodies[1]).unwrap();
+        assert_eq!(body["event"], "AmbientLightToggled");
+        assert_eq!(body["old_state"]["enabled"], false);
+        assert_eq!(body["new_state"]["enabled"], true);
+    }
+
+    #[test]
+    fn test_validate_webhook() {
+        let mut preferences = WebhookPreferences::default();
+        assert!(validate_webhook(&preferences).is_ok());
+
+        preferences.url = "http://homeassistant.local:8123/api/webhook/ambient".to_string();
+        assert!(validate_webhook(&preferences).is_ok());
+
+        preferences.url = "ftp://homeassistant.local/hook".to_string();
+        assert!(validate_webhook(&preferences).is_err());
+
+        preferences.url = String::new();
+        preferences.timeout_ms = 0;
+        assert!(validate_webhook(&preferences).is_err());
+    }
+}
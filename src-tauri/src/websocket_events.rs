@@ -5,12 +5,14 @@ use crate::{
     ambient_light_state::AmbientLightState,
     display::DisplayState,
     http_server::websocket::{
-        LedColorsChangedData, LedSortedColorsChangedData, LedStripColorsChangedData, NavigateData,
-        WebSocketManager, WsMessage,
+        DataSendModeChangedData, LedColorsChangedData, LedSortedColorsChangedData,
+        LedStripColorsChangedData, LedStripPreviewColorsChangedData, NavigateData,
+        WebSocketManager, WsMessage, LED_STRIP_PREVIEW_EVENT,
     },
     led_data_sender::DataSendMode,
     led_preview_state::LedPreviewState,
-    rpc::BoardInfo,
+    profile_manager::ProfileActivation,
+    rpc::{BoardInfo, BoardSelfCheckReport},
     user_preferences::UserPreferences,
 };
 
@@ -28,13 +30,18 @@ impl WebSocketEventPublisher {
         WEBSOCKET_EVENT_PUBLISHER_GLOBAL
             .get_or_init(|| async {
                 log::info!("🔌 Initializing WebSocket Event Publisher...");
-                Self {
-                    ws_manager: WebSocketManager::new(),
-                }
+                Self::new()
             })
             .await
     }
 
+    /// 创建独立的事件发布器（全局实例之外的发布器只在测试中使用）
+    pub fn new() -> Self {
+        Self {
+            ws_manager: WebSocketManager::new(),
+        }
+    }
+
     /// 获取WebSocket管理器的引用
     pub fn get_websocket_manager(&self) -> &WebSocketManager {
         &self.ws_manager
@@ -80,9 +87,16 @@ impl WebSocketEventPublisher {
         let status = status_manager.get_status().await;
         let timestamp = status.last_updated;
 
+        // 色觉模拟只作用于预览数据
+        let color_vision = crate::led_preview_state::LedPreviewStateManager::global()
+            .await
+            .get_color_vision()
+            .await;
+        let sorted_colors = color_vision.apply(sorted_colors);
+
         let message = WsMessage::LedSortedColorsChanged {
             data: LedSortedColorsChangedData {
-                sorted_colors: sorted_colors.to_vec(),
+                sorted_colors: sorted_colors.into_owned(),
                 mode: current_mode,
                 led_offset,
                 timestamp,
@@ -126,12 +140,18 @@ impl WebSocketEventPublisher {
             current_mode
         );
 
+        let color_vision = crate::led_preview_state::LedPreviewStateManager::global()
+            .await
+            .get_color_vision()
+            .await;
+        let colors = color_vision.apply(colors);
+
         let message = WsMessage::LedStripColorsChanged {
             data: LedStripColorsChangedData {
                 display_id,
                 border: border.to_string(),
                 strip_index,
-                colors: colors.to_vec(),
+                colors: colors.into_owned(),
                 mode: current_mode,
             },
         };
@@ -164,6 +184,52 @@ impl WebSocketEventPublisher {
         }
     }
 
+    /// 当前客户端订阅的低分辨率预览分辨率上限，没有订阅时为空
+    pub async fn led_strip_preview_caps(&self) -> std::collections::BTreeSet<usize> {
+        self.ws_manager.preview_resolution_caps().await
+    }
+
+    /// 发布按灯带分组的低分辨率预览颜色，只发送给订阅了对应分辨率上限的客户端
+    pub async fn publish_led_strip_preview_colors_changed(
+        &self,
+        display_id: u32,
+        border: &str,
+        strip_index: usize,
+        led_count: usize,
+        max_colors: usize,
+        colors: &[u8],
+    ) {
+        let event_type = format!("{LED_STRIP_PREVIEW_EVENT}:max_{max_colors}");
+
+        let sender = crate::led_data_sender::LedDataSender::global().await;
+        let mode = sender.get_mode().await;
+
+        let color_vision = crate::led_preview_state::LedPreviewStateManager::global()
+            .await
+            .get_color_vision()
+            .await;
+        let colors = color_vision.apply(colors);
+
+        let message = WsMessage::LedStripPreviewColorsChanged {
+            data: LedStripPreviewColorsChangedData {
+                display_id,
+                border: border.to_string(),
+                strip_index,
+                led_count,
+                max_colors,
+                colors: colors.into_owned(),
+                mode,
+            },
+        };
+        if let Err(e) = self
+            .ws_manager
+            .send_to_subscribers(&event_type, message)
+            .await
+        {
+            log::error!("❌ 发送LED灯带低分辨率预览事件失败: {e}");
+        }
+    }
+
     /// 发布LED状态变化事件
     pub async fn publish_led_status_changed(&self) {
         self.publish_led_status_changed_with_mode(None).await;
@@ -190,12 +256,7 @@ impl WebSocketEventPublisher {
         let data_length: u32 = configs
             .strips
             .iter()
-            .map(|strip| {
-                match strip.led_type {
-                    crate::ambient_light::LedType::WS2812B => strip.len as u32 * 3, // RGB
-                    crate::ambient_light::LedType::SK6812 => strip.len as u32 * 4,  // RGBW
-                }
-            })
+            .map(|strip| (strip.len * strip.led_type.bytes_per_led()) as u32)
             .sum();
 
         // 根据模式确定频率
@@ -204,6 +265,7 @@ impl WebSocketEventPublisher {
             DataSendMode::StripConfig => 30.0,     // 配置模式30Hz
             DataSendMode::TestEffect => 1.0,       // 测试效果1Hz
             DataSendMode::ColorCalibration => 1.0, // 颜色校准1Hz
+            DataSendMode::ManualColor => 5.0,      // 手动纯色5Hz
             DataSendMode::None => 0.0,             // 无发送
         };
 
@@ -234,6 +296,7 @@ impl WebSocketEventPublisher {
     pub async fn publish_led_status_changed_with_calculated_frequency(
         &self,
         calculated_frequency: f64,
+        fps: &crate::led_status_manager::LedFpsStats,
     ) {
         // 获取当前LED状态
         let sender = crate::led_data_sender::LedDataSender::global().await;
@@ -248,18 +311,16 @@ impl WebSocketEventPublisher {
         let data_length: u32 = configs
             .strips
             .iter()
-            .map(|strip| {
-                match strip.led_type {
-                    crate::ambient_light::LedType::WS2812B => strip.len as u32 * 3, // RGB
-                    crate::ambient_light::LedType::SK6812 => strip.len as u32 * 4,  // RGBW
-                }
-            })
+            .map(|strip| (strip.len * strip.led_type.bytes_per_led()) as u32)
             .sum();
 
         // 创建状态对象（使用实际计算的频率）
         let status = serde_json::json!({
             "mode": mode,
             "frequency": calculated_frequency,
+            "instantaneous_frequency": fps.overall.instantaneous,
+            "smoothed_frequency": fps.overall.smoothed,
+            "display_frequencies": fps.displays,
             "data_length": data_length,
             "total_led_count": total_led_count,
             "test_mode_active": mode == DataSendMode::TestEffect,
@@ -332,6 +393,29 @@ impl WebSocketEventPublisher {
         }
     }
 
+    /// 发布设备板启动自检完成事件
+    pub async fn publish_board_self_check_completed(&self, report: &BoardSelfCheckReport) {
+        if let Ok(report_json) = serde_json::to_value(report) {
+            let message = WsMessage::BoardSelfCheckCompleted { data: report_json };
+            match self
+                .ws_manager
+                .send_to_subscribers("BoardSelfCheckCompleted", message)
+                .await
+            {
+                Ok(subscriber_count) => {
+                    if subscriber_count > 0 {
+                        log::debug!("✅ 设备板自检结果已发送给 {subscriber_count} 个订阅者");
+                    }
+                }
+                Err(e) => {
+                    log::debug!("发送设备板自检结果失败: {e}");
+                }
+            }
+        } else {
+            log::error!("序列化设备板自检结果失败");
+        }
+    }
+
     /// 发布显示器状态变化事件
     pub async fn publish_displays_changed(&self, displays: &[DisplayState]) {
         if let Ok(displays_json) = serde_json::to_value(displays) {
@@ -380,6 +464,31 @@ impl WebSocketEventPublisher {
         }
     }
 
+    /// 发布情景模式激活事件
+    pub async fn publish_profile_activated(&self, activation: &ProfileActivation) {
+        if let Ok(activation_json) = serde_json::to_value(activation) {
+            let message = WsMessage::ProfileActivated {
+                data: activation_json,
+            };
+            match self
+                .ws_manager
+                .send_to_subscribers("ProfileActivated", message)
+                .await
+            {
+                Ok(subscriber_count) => {
+                    if subscriber_count > 0 {
+                        log::debug!("✅ 情景模式激活事件已发送给 {subscriber_count} 个订阅者");
+                    }
+                }
+                Err(e) => {
+                    log::debug!("发送情景模式激活事件失败: {e}");
+                }
+            }
+        } else {
+            log::error!("序列化情景模式激活事件失败");
+        }
+    }
+
     /// 发布LED预览状态变化事件
     pub async fn publish_led_preview_state_changed(&self, state: &LedPreviewState) {
         if let Ok(state_json) = serde_json::to_value(state) {
@@ -403,6 +512,34 @@ impl WebSocketEventPublisher {
         }
     }
 
+    /// 发布LED数据发送模式变化事件
+    pub async fn publish_data_send_mode_changed(
+        &self,
+        previous_mode: DataSendMode,
+        mode: DataSendMode,
+    ) {
+        let message = WsMessage::DataSendModeChanged {
+            data: DataSendModeChangedData {
+                previous_mode,
+                mode,
+            },
+        };
+        match self
+            .ws_manager
+            .send_to_subscribers("DataSendModeChanged", message)
+            .await
+        {
+            Ok(subscriber_count) => {
+                if subscriber_count > 0 {
+                    log::debug!("✅ 发送模式变化事件已发送给 {subscriber_count} 个订阅者");
+                }
+            }
+            Err(e) => {
+                log::debug!("发送模式变化事件失败: {e}");
+            }
+        }
+    }
+
     /// 发布用户偏好设置变化事件
     pub async fn publish_user_preferences_changed(&self, preferences: &UserPreferences) {
         if let Ok(preferences_json) = serde_json::to_value(preferences) {
@@ -476,6 +613,14 @@ pub async fn publish_boards_changed(boards: &[BoardInfo]) {
         .await;
 }
 
+/// 便捷函数：发布设备板启动自检结果
+pub async fn publish_board_self_check_completed(report: &BoardSelfCheckReport) {
+    get_websocket_publisher()
+        .await
+        .publish_board_self_check_completed(report)
+        .await;
+}
+
 /// 便捷函数：发布显示器状态变化
 pub async fn publish_displays_changed(displays: &[DisplayState]) {
     get_websocket_publisher()
@@ -492,6 +637,14 @@ pub async fn publish_ambient_light_state_changed(state: &AmbientLightState) {
         .await;
 }
 
+/// 便捷函数：发布情景模式激活事件
+pub async fn publish_profile_activated(activation: &ProfileActivation) {
+    get_websocket_publisher()
+        .await
+        .publish_profile_activated(activation)
+        .await;
+}
+
 /// 便捷函数：发布LED预览状态变化
 pub async fn publish_led_preview_state_changed(state: &LedPreviewState) {
     get_websocket_publisher()
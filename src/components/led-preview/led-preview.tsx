@@ -221,8 +221,8 @@ export function LedPreview(props: LedPreviewProps) {
               // 检查模式，只在特定模式下更新预览
               const mode = event.mode || 'AmbientLight'; // 默认为氛围光模式以保持向后兼容
 
-              // 只在氛围光模式、测试模式、灯带配置模式或颜色校准模式下更新LED预览
-              if (mode === 'AmbientLight' || mode === 'TestEffect' || mode === 'StripConfig' || mode === 'ColorCalibration') {
+              // 只在氛围光模式、测试模式、灯带配置模式、颜色校准模式或手动纯色模式下更新LED预览
+              if (mode === 'AmbientLight' || mode === 'TestEffect' || mode === 'StripConfig' || mode === 'ColorCalibration' || mode === 'ManualColor') {
                 const currentDataSize = event.sorted_colors.length;
 
                 // 节流渲染：统一通过 scheduleRender 以 ~30FPS 刷新
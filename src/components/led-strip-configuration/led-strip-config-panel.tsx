@@ -182,6 +182,7 @@ export const LedStripConfigPanel: Component<LedStripConfigPanelProps> = (props)
               >
                 <option value={LedType.WS2812B}>WS2812B (RGB)</option>
                 <option value={LedType.SK6812}>SK6812 (RGBW)</option>
+                <option value={LedType.WS2811}>WS2811 (RGB)</option>
               </select>
             </div>
 
@@ -12,7 +12,7 @@ interface LedStripConfig {
   border: 'Top' | 'Bottom' | 'Left' | 'Right';
   count: number;
   reverse: boolean;
-  ledType: 'WS2812B' | 'SK6812';
+  ledType: 'WS2812B' | 'SK6812' | 'WS2811';
   driver: string;
   sequence: number;
   startOffset: number; // 0-100%
@@ -496,10 +496,11 @@ const LedConfigPanel: Component<{
           <select
             class="select select-sm select-bordered"
             value={props.strip.ledType}
-            onChange={(e) => updateStrip({ ledType: e.currentTarget.value as 'WS2812B' | 'SK6812' })}
+            onChange={(e) => updateStrip({ ledType: e.currentTarget.value as 'WS2812B' | 'SK6812' | 'WS2811' })}
           >
             <option value="WS2812B">WS2812B (RGB)</option>
             <option value="SK6812">SK6812 (RGBW)</option>
+            <option value="WS2811">WS2811 (RGB)</option>
           </select>
         </div>
 
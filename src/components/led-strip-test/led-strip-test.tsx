@@ -18,12 +18,22 @@ interface TestPattern {
   effect_type: string;
 }
 
+// 各效果的可选参数，未设置时使用默认值
+interface TestEffectParams {
+  group_size?: number;
+  group_colors?: [number, number, number][];
+  breathing_color?: [number, number, number];
+  min_brightness?: number;
+  max_brightness?: number;
+}
+
 interface TestEffectConfig {
   effect_type: string;
   led_count: number;
   led_type: string;
   speed: number;
   offset: number;
+  params?: TestEffectParams;
 }
 
 export const LedStripTest = () => {
@@ -31,7 +41,7 @@ export const LedStripTest = () => {
   const [boards, setBoards] = createSignal<BoardInfo[]>([]);
   const [selectedBoard, setSelectedBoard] = createSignal<BoardInfo | null>(null);
   const [ledCount, setLedCount] = createSignal(60);
-  const [ledType, setLedType] = createSignal<'WS2812B' | 'SK6812'>('WS2812B');
+  const [ledType, setLedType] = createSignal<'WS2812B' | 'SK6812' | 'WS2811'>('WS2812B');
   const [ledOffset, setLedOffset] = createSignal(0);
   const [isRunning, setIsRunning] = createSignal(false);
   const [currentPattern, setCurrentPattern] = createSignal<TestPattern | null>(null);
@@ -269,6 +279,11 @@ export const LedStripTest = () => {
       description: t('ledTest.singleScanDesc'),
       effect_type: 'SingleScan'
     },
+    {
+      name: t('ledTest.indexMarker'),
+      description: t('ledTest.indexMarkerDesc'),
+      effect_type: 'IndexMarker'
+    },
     {
       name: t('ledTest.breathing'),
       description: t('ledTest.breathingDesc'),
@@ -552,10 +567,11 @@ export const LedStripTest = () => {
               <select
                 class="select select-bordered w-full"
                 value={ledType()}
-                onChange={(e) => setLedType(e.target.value as 'WS2812B' | 'SK6812')}
+                onChange={(e) => setLedType(e.target.value as 'WS2812B' | 'SK6812' | 'WS2811')}
               >
                 <option value="WS2812B">WS2812B</option>
                 <option value="SK6812">SK6812</option>
+                <option value="WS2811">WS2811</option>
               </select>
             </div>
 
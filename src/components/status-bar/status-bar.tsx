@@ -126,8 +126,8 @@ export function StatusBar(props: StatusBarProps) {
             try {
               const statusBarData = convertToStatusBarData(statusData, connected(), t);
 
-              // 使用后端计算的频率，并应用平滑动画
-              const backendFrequency = statusData.frequency || 0;
+              // 使用后端计算的频率（优先使用2秒滑动窗口平均值），并应用平滑动画
+              const backendFrequency = statusData.smoothed_frequency ?? statusData.frequency ?? 0;
               updateFrequencyDisplay(backendFrequency);
 
               // 更新状态数据（使用显示中的频率值以保持动画连续性）
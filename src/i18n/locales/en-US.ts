@@ -268,6 +268,8 @@ export const enUS: TranslationDict = {
     groupCountingDesc: 'Different colors for every ten LEDs to quickly count LED quantity',
     singleScan: 'Single Scan',
     singleScanDesc: 'Light up each LED individually for precise position testing',
+    indexMarker: 'Index Marker',
+    indexMarkerDesc: 'Green first LED and white every tenth LED over a blue-to-red gradient to check strip direction and order',
     breathingDesc: 'Breathing effect for the entire LED strip to test overall brightness',
     // LED test form labels
     ledCount: 'LED Count',
@@ -393,6 +395,7 @@ export const enUS: TranslationDict = {
       StripConfig: 'Configuration',
       TestEffect: 'Test Mode',
       ColorCalibration: 'Color Calibration',
+      ManualColor: 'Manual Color',
     },
   },
 
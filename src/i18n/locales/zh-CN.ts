@@ -268,6 +268,8 @@ export const zhCN: TranslationDict = {
     groupCountingDesc: '每十个LED一组不同颜色，用于快速计算灯珠数量',
     singleScan: '单色扫描',
     singleScanDesc: '单个LED依次点亮，用于精确测试每个LED位置',
+    indexMarker: '序号标记',
+    indexMarkerDesc: '首个LED为绿色、每第十个LED为白色，底色由蓝渐变到红，用于检查灯带方向和顺序',
     breathingDesc: '整条灯带呼吸效果，用于测试整体亮度',
     // LED test form labels
     ledCount: 'LED数量',
@@ -393,6 +395,7 @@ export const zhCN: TranslationDict = {
       StripConfig: '配置模式',
       TestEffect: '测试模式',
       ColorCalibration: '颜色校准',
+      ManualColor: '手动纯色',
     },
   },
 
@@ -402,6 +402,7 @@ export interface TranslationDict {
       StripConfig: string;
       TestEffect: string;
       ColorCalibration: string;
+      ManualColor: string;
     };
   };
 
@@ -1,3 +1,9 @@
+export type BoardTelemetry = {
+  firmware_version: string | null;
+  temperature: number | null;
+  raw: Record<string, string>;
+};
+
 export type BoardInfo = {
   fullname: string;
   host: string;
@@ -6,4 +12,23 @@ export type BoardInfo = {
   ttl: number;
   connect_status: 'Connected' | 'Disconnected' | 'Unknown' | { Connecting: number };
   checked_at: Date;
+  telemetry?: BoardTelemetry;
+};
+
+export type BoardHealth = {
+  address: string;
+  online: boolean;
+  data_sent: boolean;
+  responded: boolean;
+  latency_ms: number | null;
+  healthy: boolean;
+};
+
+export type BoardSelfCheckReport = {
+  checked_at: string;
+  total: number;
+  reachable: number;
+  skipped: number;
+  healthy: boolean;
+  boards: BoardHealth[];
 };
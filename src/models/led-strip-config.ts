@@ -3,8 +3,60 @@ import { Borders } from '../constants/border';
 export enum LedType {
   WS2812B = 'WS2812B',
   SK6812 = 'SK6812',
+  WS2811 = 'WS2811',
 }
 
+/**
+ * 单个LED覆盖的多个采样区域合并为一个颜色的方式
+ */
+export enum ZoneReduction {
+  Mean = 'Mean',
+  Max = 'Max',
+  Dominant = 'Dominant',
+}
+
+/**
+ * 每个LED采样区域内采样点的排列形状
+ */
+export enum SampleShape {
+  Grid = 'Grid',
+  Line = 'Line',
+  LShape = 'LShape',
+}
+
+/**
+ * RGB三个通道在硬件数据中的排列顺序
+ */
+export enum RgbOrder {
+  Rgb = 'Rgb',
+  Rbg = 'Rbg',
+  Grb = 'Grb',
+  Gbr = 'Gbr',
+  Brg = 'Brg',
+  Bgr = 'Bgr',
+}
+
+/**
+ * RGBW灯带中白色通道字节的位置
+ */
+export enum WhitePosition {
+  Leading = 'Leading',
+  Trailing = 'Trailing',
+}
+
+/**
+ * 灯带的颜色字节顺序，默认 GRB / GRBW
+ */
+export type ColorOrder = {
+  rgb: RgbOrder;
+  white: WhitePosition;
+};
+
+export const DEFAULT_COLOR_ORDER: ColorOrder = {
+  rgb: RgbOrder.Grb,
+  white: WhitePosition.Trailing,
+};
+
 
 
 export class ColorCalibration {
@@ -32,6 +84,38 @@ export class LedStripConfig {
      * 用于稳定的显示器标识，不受系统重启影响
      */
     public readonly display_internal_id?: string,
+    /**
+     * 硬件起始偏移量（以该灯带的LED为单位），为空时紧接上一条灯带
+     */
+    public readonly hardware_offset?: number | null,
+    /**
+     * 多个采样区域合并为LED颜色的方式，默认取平均值
+     */
+    public readonly zone_reduction: ZoneReduction = ZoneReduction.Mean,
+    /**
+     * 硬件数据的颜色字节顺序
+     */
+    public readonly color_order: ColorOrder = DEFAULT_COLOR_ORDER,
+    /**
+     * 采样点的排列形状，默认网格
+     */
+    public readonly sample_shape: SampleShape = SampleShape.Grid,
+    /**
+     * 采样区域距物理屏幕边缘的像素数，用于避开贴边的菜单栏、程序坞
+     */
+    public readonly sample_inset: number = 0,
+    /**
+     * 边缘起始端（上/下边为左端，左/右边为上端）不被灯带覆盖的百分比
+     */
+    public readonly start_trim_percent: number = 0,
+    /**
+     * 边缘末端（上/下边为右端，左/右边为下端）不被灯带覆盖的百分比
+     */
+    public readonly end_trim_percent: number = 0,
+    /**
+     * 物理串联顺序，为空时与序列号相同；序列号只决定界面中的排列顺序
+     */
+    public readonly chain_position?: number | null,
   ) {}
 
   /**
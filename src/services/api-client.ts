@@ -16,6 +16,18 @@ export interface ApiError {
   message: string;
 }
 
+// 携带后端错误码的请求错误，便于界面根据 code 给出可操作的提示
+export class ApiRequestError extends Error {
+  constructor(
+    public readonly status: number,
+    public readonly code: string,
+    message: string
+  ) {
+    super(message);
+    this.name = 'ApiRequestError';
+  }
+}
+
 // 配置类型
 export interface ApiClientConfig {
   baseUrl: string;
@@ -330,6 +342,10 @@ export class ApiClient {
       clearTimeout(timeoutId);
 
       if (!response.ok) {
+        const apiError: Partial<ApiError> | null = await response.json().catch(() => null);
+        if (apiError?.code) {
+          throw new ApiRequestError(response.status, apiError.code, apiError.message ?? apiError.code);
+        }
         throw new Error(`HTTP ${response.status}: ${response.statusText}`);
       }
 
@@ -175,6 +175,11 @@ export class ColorCalibrationService {
           buffer[bufferOffset + 1] = calibratedColor.r;
           buffer[bufferOffset + 2] = calibratedColor.b;
           buffer[bufferOffset + 3] = calibratedColor.w;
+        } else if (strip.led_type === LedType.WS2811) {
+          // WS2811: R,G,B 顺序
+          buffer[bufferOffset] = calibratedColor.r;
+          buffer[bufferOffset + 1] = calibratedColor.g;
+          buffer[bufferOffset + 2] = calibratedColor.b;
         } else {
           // WS2812B: G,R,B 顺序
           buffer[bufferOffset] = calibratedColor.g;
@@ -6,7 +6,7 @@
 import { api } from './api-client';
 import { DisplayInfo } from '../models/display-info.model';
 import { DisplayState } from '../models/display-state.model';
-import { BoardInfo } from '../models/board-info.model';
+import { BoardHealth, BoardInfo, BoardSelfCheckReport } from '../models/board-info.model';
 import { LedStripConfig } from '../models/led-strip-config';
 
 // LED颜色数据类型
@@ -17,6 +17,53 @@ export interface LedColor {
   w?: number;
 }
 
+// 已注册显示器及其绑定的灯带
+export interface DisplayBinding {
+  internal_id: string;
+  name: string;
+  manufacturer: string | null;
+  model: string | null;
+  connected: boolean;
+  system_id: number | null;
+  strip_indices: number[];
+}
+
+// 跨屏灯带：上/下边灯带沿多个并排显示器合并后的边缘采样
+export interface StripSpan {
+  strip_index: number;
+  display_internal_ids: string[];
+}
+
+// 显示器启用的边：禁用边上的灯带不采样，也不占用串联数据中的位置
+export interface BorderMask {
+  top: boolean;
+  bottom: boolean;
+  left: boolean;
+  right: boolean;
+}
+
+// 显示器采集帧率：未设置覆盖时按显示器刷新率采集
+export interface DisplayFrameRate {
+  frame_rate: number | null;
+  effective_frame_rate: number;
+}
+
+// 单个LED的采样区域（截图像素坐标）
+export interface LedSampleRegion {
+  x: number;
+  y: number;
+  width: number;
+  height: number;
+  points: [number, number][];
+}
+
+// 显示器当前使用的采样区域，strips 按灯带序列号索引
+export interface DisplaySampleRegions {
+  width: number;
+  height: number;
+  strips: Record<string, LedSampleRegion[]>;
+}
+
 // LED采样点类型
 export interface LedSamplePoints {
   border: string;
@@ -51,6 +98,87 @@ export class DisplayApiService {
     return api.get('/api/v1/display/configs');
   }
 
+  /**
+   * 获取所有已注册显示器（含未连接的）及绑定到各显示器的灯带
+   */
+  static async getDisplayBindings(): Promise<DisplayBinding[]> {
+    return api.get('/api/v1/display/bindings');
+  }
+
+  /**
+   * 将一组灯带绑定到指定显示器（内部ID），显示器断开后绑定保留
+   */
+  static async bindStripsToDisplay(internalId: string, stripIndices: number[]): Promise<string> {
+    return api.put(`/api/v1/display/bindings/${encodeURIComponent(internalId)}`, {
+      strip_indices: stripIndices,
+    });
+  }
+
+  /**
+   * 获取所有跨屏灯带设置
+   */
+  static async getStripSpans(): Promise<StripSpan[]> {
+    return api.get('/api/v1/display/spans');
+  }
+
+  /**
+   * 设置灯带跨越的显示器（内部ID，须包含灯带所属的显示器），为 null 时取消跨屏
+   */
+  static async setStripSpan(stripIndex: number, displayInternalIds: string[] | null): Promise<string> {
+    return api.put(`/api/v1/display/spans/${stripIndex}`, {
+      display_internal_ids: displayInternalIds,
+    });
+  }
+
+  /**
+   * 获取各显示器灯带当前使用的采样区域，按显示器ID索引
+   */
+  static async getSampleRegions(): Promise<Record<string, DisplaySampleRegions>> {
+    return api.get('/api/v1/display/sample-regions');
+  }
+
+  /**
+   * 获取显示器启用的边
+   */
+  static async getDisplayBorderMask(displayId: number): Promise<BorderMask> {
+    return api.get(`/api/v1/display/${displayId}/borders`);
+  }
+
+  /**
+   * 设置显示器启用的边（例如底边没有安装灯带时禁用底边）
+   */
+  static async setDisplayBorderMask(displayId: number, borderMask: BorderMask): Promise<string> {
+    return api.put(`/api/v1/display/${displayId}/borders`, { border_mask: borderMask });
+  }
+
+  /**
+   * 获取显示器的采集帧率
+   */
+  static async getDisplayFrameRate(displayId: number): Promise<DisplayFrameRate> {
+    return api.get(`/api/v1/display/${displayId}/frame-rate`);
+  }
+
+  /**
+   * 设置显示器的采集帧率（1-240 Hz），各显示器按各自的帧率独立采集
+   */
+  static async setDisplayFrameRate(displayId: number, frameRate: number): Promise<string> {
+    return api.put(`/api/v1/display/${displayId}/frame-rate`, { frame_rate: frameRate });
+  }
+
+  /**
+   * 清除显示器的采集帧率覆盖，恢复按显示器刷新率采集
+   */
+  static async clearDisplayFrameRate(displayId: number): Promise<string> {
+    return api.delete(`/api/v1/display/${displayId}/frame-rate`);
+  }
+
+  /**
+   * 用显示器最新一帧截图立即采样一次，返回按灯带序列号索引的颜色（不发送到硬件）
+   */
+  static async sampleDisplayNow(displayId: number): Promise<Record<string, LedColor[]>> {
+    return api.post(`/api/v1/display/${displayId}/sample-now`);
+  }
+
   /**
    * 获取指定显示器的颜色
    * 替代: invoke('get_display_colors', { displayId, ledConfigs })
@@ -115,6 +243,27 @@ export class DeviceApiService {
     return api.get('/api/v1/device/boards');
   }
 
+  /**
+   * 让指定设备板的灯带闪烁识别图案，数秒后自动熄灭
+   */
+  static async identifyBoard(address: string): Promise<string> {
+    return api.post(`/api/v1/device/boards/${encodeURIComponent(address)}/identify`);
+  }
+
+  /**
+   * 检查指定设备板能否收到数据并回应，返回往返延迟
+   */
+  static async getBoardHealth(address: string): Promise<BoardHealth> {
+    return api.get(`/api/v1/device/boards/${encodeURIComponent(address)}/health`);
+  }
+
+  /**
+   * 获取启动时设备板连通性自检结果，自检未完成时为 null
+   */
+  static async getBoardSelfCheck(): Promise<BoardSelfCheckReport | null> {
+    return api.get('/api/v1/device/boards/self-check');
+  }
+
   /**
    * 获取自动启动状态
    * 替代: invoke('get_auto_start_status')
@@ -4,10 +4,13 @@
  */
 
 import { api } from './api-client';
-import { LedStripConfig, LedType } from '../models/led-strip-config';
+import { ColorOrder, LedStripConfig, LedType } from '../models/led-strip-config';
 import { Borders } from '../constants/border';
 import { DataSendMode } from '../types/led-status';
 
+// 预览色觉模拟类型
+export type ColorVisionSimulation = 'None' | 'Protanopia' | 'Deuteranopia' | 'Tritanopia';
+
 // LED测试效果配置
 export interface TestEffectConfig {
   effect_type: string;
@@ -15,6 +18,23 @@ export interface TestEffectConfig {
   led_type: LedType;
   speed: number;
   offset: number;
+  color_order?: ColorOrder;
+}
+
+// 灯带长度检测状态
+export interface LengthDetectionStatus {
+  position: number;
+  led_count: number;
+  auto_advance: boolean;
+  segment_lengths: number[];
+}
+
+// 颜色校准曲线（每个通道 256 项映射）
+export interface CalibrationCurve {
+  r: number[];
+  g: number[];
+  b: number[];
+  w: number[];
 }
 
 // 边框颜色类型
@@ -35,6 +55,19 @@ export interface LedStatusStats {
   last_update: string;
 }
 
+// 单条灯带在硬件数据中的布局（诊断用）
+export interface StripLayoutEntry {
+  index: number;
+  chain_position: number;
+  display_id: number;
+  border: string;
+  led_type: LedType;
+  len: number;
+  byte_offset: number;
+  byte_len: number;
+  explicit_offset: boolean;
+}
+
 /**
  * LED API服务类
  */
@@ -82,9 +115,59 @@ export class LedApiService {
   /**
    * 设置LED数据发送模式
    * 替代: invoke('set_led_data_send_mode', { mode })
+   * @param dryRun 模拟发送：执行完整流程但不发送UDP数据，不传时保持不变
+   */
+  static async setDataSendMode(mode: DataSendMode, dryRun?: boolean): Promise<void> {
+    return api.put('/api/v1/led/mode', { mode, dry_run: dryRun });
+  }
+
+  /**
+   * 获取当前手动纯色，未设置时返回 null
    */
-  static async setDataSendMode(mode: DataSendMode): Promise<void> {
-    return api.put('/api/v1/led/mode', { mode });
+  static async getManualColor(): Promise<[number, number, number] | null> {
+    return api.get('/api/v1/led/manual-color');
+  }
+
+  /**
+   * 设置手动纯色，持续发送直到清除
+   */
+  static async setManualColor(r: number, g: number, b: number): Promise<void> {
+    return api.put('/api/v1/led/manual-color', { r, g, b });
+  }
+
+  /**
+   * 清除手动纯色，恢复氛围光或停止发送
+   */
+  static async clearManualColor(): Promise<void> {
+    return api.delete('/api/v1/led/manual-color');
+  }
+
+  /**
+   * 获取各灯带在硬件数据中的偏移量和长度（按串联顺序），用于排查颜色错位
+   */
+  static async getStripLayout(): Promise<StripLayoutEntry[]> {
+    return api.get('/api/v1/led/strip-layout');
+  }
+
+  /**
+   * 获取氛围光颜色是否已冻结
+   */
+  static async isFrozen(): Promise<boolean> {
+    return api.get('/api/v1/led/freeze');
+  }
+
+  /**
+   * 冻结氛围光颜色，持续发送当前颜色
+   */
+  static async freeze(): Promise<void> {
+    return api.post('/api/v1/led/freeze');
+  }
+
+  /**
+   * 解除冻结，恢复实时采样
+   */
+  static async unfreeze(): Promise<void> {
+    return api.delete('/api/v1/led/freeze');
   }
 
   /**
@@ -131,7 +214,7 @@ export class LedApiService {
   /**
    * 获取LED预览状态
    */
-  static async getLedPreviewState(): Promise<{ enabled: boolean }> {
+  static async getLedPreviewState(): Promise<{ enabled: boolean; color_vision: ColorVisionSimulation }> {
     return api.get('/api/v1/led/preview-state');
   }
 
@@ -142,6 +225,20 @@ export class LedApiService {
     return api.put('/api/v1/led/preview-state', { enabled });
   }
 
+  /**
+   * 获取预览色觉模拟类型
+   */
+  static async getPreviewColorVision(): Promise<ColorVisionSimulation> {
+    return api.get('/api/v1/led/preview-color-vision');
+  }
+
+  /**
+   * 设置预览色觉模拟类型（只影响预览，不影响硬件输出）
+   */
+  static async setPreviewColorVision(simulation: ColorVisionSimulation): Promise<void> {
+    return api.put('/api/v1/led/preview-color-vision', { simulation });
+  }
+
 
 
 
@@ -177,6 +274,55 @@ export class LedApiService {
     await api.post('/api/v1/led/stop-test-effect', params, { timeout: 1000 });
   }
 
+  /**
+   * 启动灯带长度检测（单个亮灯沿灯带逐个前进）
+   */
+  static async startLengthDetection(
+    boardAddress: string,
+    effectConfig: TestEffectConfig,
+    stepIntervalMs: number,
+    autoAdvance = true,
+  ): Promise<LengthDetectionStatus> {
+    return api.post('/api/v1/led/length-detection/start', {
+      board_address: boardAddress,
+      effect_config: effectConfig,
+      step_interval_ms: stepIntervalMs,
+      auto_advance: autoAdvance,
+    });
+  }
+
+  /**
+   * 步进长度检测的亮灯位置（负数后退），可同时切换自动前进
+   */
+  static async advanceLengthDetection(
+    boardAddress: string,
+    steps: number,
+    autoAdvance?: boolean,
+  ): Promise<LengthDetectionStatus> {
+    return api.post('/api/v1/led/length-detection/advance', {
+      board_address: boardAddress,
+      steps,
+      auto_advance: autoAdvance,
+    });
+  }
+
+  /**
+   * 标记亮灯已到达拐角
+   */
+  static async markLengthDetectionCorner(boardAddress: string): Promise<LengthDetectionStatus> {
+    return api.post('/api/v1/led/length-detection/mark', { board_address: boardAddress });
+  }
+
+  /**
+   * 结束长度检测并将各段长度写入灯带配置
+   */
+  static async applyLengthDetection(boardAddress: string, stripIndices: number[]): Promise<number[]> {
+    return api.post('/api/v1/led/length-detection/apply', {
+      board_address: boardAddress,
+      strip_indices: stripIndices,
+    });
+  }
+
   /**
    * 启动单屏配置发布器
    */
@@ -199,10 +345,18 @@ export class LedApiService {
   }
 
   /**
-   * 设置活跃灯带用于呼吸效果
+   * 设置活跃灯带用于呼吸效果，可选指定呼吸频率（Hz）与亮度范围（0-1）
    */
-  static async setActiveStripForBreathing(displayId: number, border: string | null): Promise<void> {
-    await api.post('/api/v1/led/set-active-strip-breathing', { display_id: displayId, border });
+  static async setActiveStripForBreathing(
+    displayId: number,
+    border: string | null,
+    breathing?: { frequency_hz?: number; min_brightness?: number; max_brightness?: number },
+  ): Promise<void> {
+    await api.post('/api/v1/led/set-active-strip-breathing', {
+      display_id: displayId,
+      border,
+      ...breathing,
+    });
   }
 }
 
@@ -293,6 +447,13 @@ export class ConfigApiService {
     return api.put('/api/v1/config/user-preferences', preferences);
   }
 
+  /**
+   * 恢复出厂设置（灯带配置、颜色校准和用户偏好设置），原配置会先备份
+   */
+  static async resetConfig(): Promise<{ config_backup: string | null; preferences_backup: string | null }> {
+    return api.post('/api/v1/config/reset', { confirm: true });
+  }
+
   /**
    * 更新窗口偏好设置
    * 替代: invoke('update_window_preferences', { windowPrefs })
@@ -341,6 +502,48 @@ export class ConfigApiService {
     return api.put('/api/v1/config/global-color-calibration', { calibration });
   }
 
+  /**
+   * 应用全局颜色校准，并等待第一帧使用新校准后返回耗时（毫秒）
+   */
+  static async confirmGlobalColorCalibration(calibration: any, timeoutMs?: number): Promise<{ latency_ms: number }> {
+    return api.put('/api/v1/config/global-color-calibration/confirm', { calibration, timeout_ms: timeoutMs });
+  }
+
+  /**
+   * 获取当前生效的颜色校准曲线（每个通道 256 项映射）
+   */
+  static async getCalibrationCurve(): Promise<CalibrationCurve> {
+    return api.get('/api/v1/config/calibration-curve');
+  }
+
+  /**
+   * 获取所有命名的颜色校准预设（按名称索引）
+   */
+  static async getCalibrationPresets(): Promise<Record<string, any>> {
+    return api.get('/api/v1/config/calibration-presets');
+  }
+
+  /**
+   * 将当前的颜色校准保存为命名预设，同名预设被覆盖
+   */
+  static async saveCalibrationPreset(name: string): Promise<string> {
+    return api.post('/api/v1/config/calibration-presets', { name });
+  }
+
+  /**
+   * 应用命名的颜色校准预设，返回应用的校准
+   */
+  static async applyCalibrationPreset(name: string): Promise<any> {
+    return api.post(`/api/v1/config/calibration-presets/${encodeURIComponent(name)}/apply`);
+  }
+
+  /**
+   * 删除命名的颜色校准预设
+   */
+  static async deleteCalibrationPreset(name: string): Promise<string> {
+    return api.delete(`/api/v1/config/calibration-presets/${encodeURIComponent(name)}`);
+  }
+
   /**
    * 获取用户偏好设置
    * 替代: invoke('get_user_preferences')
@@ -215,6 +215,11 @@ export class LedColorService {
         buffer[offset + 1] = color.r;
         buffer[offset + 2] = color.b;
         buffer[offset + 3] = color.w || 0;
+      } else if (ledType === LedType.WS2811) {
+        // WS2811: R,G,B 顺序
+        buffer[offset] = color.r;
+        buffer[offset + 1] = color.g;
+        buffer[offset + 2] = color.b;
       } else {
         // WS2812B: G,R,B 顺序
         buffer[offset] = color.g;
@@ -1,10 +1,25 @@
 import { createSignal } from 'solid-js';
 import { adaptiveApi } from '../services/api-adapter';
+import { DataSendMode } from '../types/led-status';
 
 // TypeScript interfaces matching Rust structs
 export interface UserPreferences {
   window: WindowPreferences;
   ui: UIPreferences;
+  osc: OscPreferences;
+  sampling: SamplingPreferences;
+  config_update: ConfigUpdatePreferences;
+  send_mode: SendModePreferences;
+  fullscreen_capture: FullscreenCapturePreferences;
+  auto_brightness: AutoBrightnessPreferences;
+  server: ServerPreferences;
+  capture_failure: CaptureFailurePreferences;
+  output: OutputPreferences;
+  preview_scaling: PreviewScalingPreferences;
+  webhook: WebhookPreferences;
+  palette_quantization: PaletteQuantizationPreferences;
+  color_temperature_shift: ColorTemperatureShiftPreferences;
+  cursor_focus: CursorFocusPreferences;
 }
 
 export interface WindowPreferences {
@@ -23,6 +38,125 @@ export interface UIPreferences {
   night_mode_theme: string;
 }
 
+export interface OscPreferences {
+  enabled: boolean;
+  port: number;
+}
+
+export interface SamplingPreferences {
+  points_per_led: number;
+  black_level: number;
+  interpolation_strength: number;
+  averaging_color_space: 'gamma' | 'linear';
+  bilinear_sampling: boolean;
+  vertical_gradient_sampling: boolean;
+  frame_sync_mode: 'Synchronized' | 'Immediate';
+  color_count_policy: 'Truncate' | 'Wrap' | 'Resample';
+  display_sleep_policy: 'Ignore' | 'Hold' | 'FadeToBlack';
+  detect_sleep_from_black_frames: boolean;
+  capture_backend: 'WindowList' | 'DisplayImage';
+  sampling_strategy: 'Edges' | 'ScreenAverage';
+}
+
+export interface ConfigUpdatePreferences {
+  debounce_ms: number;
+}
+
+export interface SendModePreferences {
+  last_mode: DataSendMode;
+  manual_color?: [number, number, number] | null;
+}
+
+export interface FullscreenCapturePreferences {
+  enabled: boolean;
+  primary_display_only: boolean;
+  frame_interval_ms: number;
+}
+
+export type AutoBrightnessMode = 'Inverse' | 'Proportional';
+
+export interface AutoBrightnessPreferences {
+  enabled: boolean;
+  mode: AutoBrightnessMode;
+  strength: number;
+}
+
+export interface ServerPreferences {
+  http_host: string;
+  http_port: number;
+  websocket_host: string;
+  websocket_port: number;
+  auth_token?: string | null;
+  auth_exempt_loopback: boolean;
+}
+
+export type CaptureFailurePolicy = 'Hold' | 'FadeToBlack' | 'DiagnosticColor';
+
+export interface CaptureFailurePreferences {
+  policy: CaptureFailurePolicy;
+  stall_timeout_ms: number;
+  fade_ms: number;
+  diagnostic_color: [number, number, number];
+}
+
+export type OutputTransport = 'Native' | 'Ddp' | 'ArtNet';
+
+export type SinkColorOrder = 'Grb' | 'Rgb';
+
+export interface OutputSinkConfig {
+  id: string;
+  transport: OutputTransport;
+  target: string;
+  rgbw: boolean;
+  color_order: SinkColorOrder;
+  universe: number;
+}
+
+export interface OutputPreferences {
+  sinks: OutputSinkConfig[];
+  local_bind_address: string | null;
+  reconnect_fade_ms: number;
+}
+
+export type PreviewFilter = 'Nearest' | 'Triangle' | 'CatmullRom' | 'Gaussian' | 'Lanczos3';
+
+export interface PreviewScalingPreferences {
+  intermediate_width: number;
+  intermediate_height: number;
+  intermediate_filter: PreviewFilter;
+  final_filter: PreviewFilter;
+}
+
+export type WebhookEvent = 'AmbientLightToggled' | 'DataSendModeChanged';
+
+export interface PaletteQuantizationPreferences {
+  enabled: boolean;
+  palette: [number, number, number][];
+}
+
+// 时间为本地午夜起的分钟数
+export interface ColorTemperatureShiftPreferences {
+  enabled: boolean;
+  day_kelvin: number;
+  night_kelvin: number;
+  sunrise_minute: number;
+  sunset_minute: number;
+  transition_minutes: number;
+}
+
+// 光标所在显示器的灯带提升亮度，其他显示器按强度调暗
+export interface CursorFocusPreferences {
+  enabled: boolean;
+  strength: number;
+}
+
+export interface WebhookPreferences {
+  url: string;
+  events: WebhookEvent[];
+  timeout_ms: number;
+  max_retries: number;
+}
+
 // Default preferences
 const defaultPreferences: UserPreferences = {
   window: {
@@ -37,6 +171,112 @@ const defaultPreferences: UserPreferences = {
     night_mode_theme_enabled: false,
     night_mode_theme: 'dark',
   },
+  osc: {
+    enabled: false,
+    port: 9000,
+  },
+  sampling: {
+    points_per_led: 4,
+    black_level: 0,
+    interpolation_strength: 0,
+    averaging_color_space: 'gamma',
+    bilinear_sampling: false,
+    vertical_gradient_sampling: false,
+    frame_sync_mode: 'Synchronized',
+    color_count_policy: 'Truncate',
+    display_sleep_policy: 'Hold',
+    detect_sleep_from_black_frames: false,
+    capture_backend: 'WindowList',
+    sampling_strategy: 'Edges',
+  },
+  config_update: {
+    debounce_ms: 150,
+  },
+  send_mode: {
+    last_mode: 'None',
+  },
+  fullscreen_capture: {
+    enabled: false,
+    primary_display_only: true,
+    frame_interval_ms: 100,
+  },
+  auto_brightness: {
+    enabled: false,
+    mode: 'Inverse',
+    strength: 0.5,
+  },
+  server: {
+    http_host: '127.0.0.1',
+    http_port: 24101,
+    websocket_host: '127.0.0.1',
+    websocket_port: 24102,
+    auth_exempt_loopback: true,
+  },
+  capture_failure: {
+    policy: 'Hold',
+    stall_timeout_ms: 2000,
+    fade_ms: 1000,
+    diagnostic_color: [128, 0, 0],
+  },
+  output: {
+    sinks: [
+      {
+        id: 'native',
+        transport: 'Native',
+        target: '',
+        rgbw: false,
+        color_order: 'Grb',
+        universe: 0,
+      },
+    ],
+    local_bind_address: null,
+    reconnect_fade_ms: 500,
+  },
+  preview_scaling: {
+    intermediate_width: 800,
+    intermediate_height: 450,
+    intermediate_filter: 'Nearest',
+    final_filter: 'Triangle',
+  },
+  webhook: {
+    url: '',
+    events: ['AmbientLightToggled', 'DataSendModeChanged'],
+    timeout_ms: 5000,
+    max_retries: 2,
+  },
+  palette_quantization: {
+    enabled: false,
+    palette: [
+      [0, 0, 0],
+      [128, 0, 0],
+      [0, 128, 0],
+      [128, 128, 0],
+      [0, 0, 128],
+      [128, 0, 128],
+      [0, 128, 128],
+      [192, 192, 192],
+      [128, 128, 128],
+      [255, 0, 0],
+      [0, 255, 0],
+      [255, 255, 0],
+      [0, 0, 255],
+      [255, 0, 255],
+      [0, 255, 255],
+      [255, 255, 255],
+    ],
+  },
+  color_temperature_shift: {
+    enabled: false,
+    day_kelvin: 6500,
+    night_kelvin: 3400,
+    sunrise_minute: 420,
+    sunset_minute: 1140,
+    transition_minutes: 60,
+  },
+  cursor_focus: {
+    enabled: false,
+    strength: 0.5,
+  },
 };
 
 // Reactive signals for user preferences
@@ -105,8 +345,8 @@ const updateUIPreferences = async (uiPrefs: UIPreferences) => {
 // Convenience functions for common updates
 const updateViewScale = async (scale: number) => {
   try {
-    // Validate scale value
-    if (!isFinite(scale) || scale <= 0 || scale > 10) {
+    // Validate scale value (same range the backend accepts)
+    if (!isFinite(scale) || scale < 0.01 || scale > 4) {
       console.warn('Invalid view scale value:', scale, 'skipping update');
       return;
     }
@@ -12,7 +12,8 @@ export type DataSendMode =
   | 'AmbientLight'   // 屏幕氛围光数据
   | 'StripConfig'    // 单灯条配置数据
   | 'TestEffect'     // 测试效果数据
-  | 'ColorCalibration'; // 颜色校准数据
+  | 'ColorCalibration' // 颜色校准数据
+  | 'ManualColor';   // 手动纯色数据
 
 /**
  * LED数据发送统计
@@ -29,6 +30,45 @@ export interface LedSendStats {
   send_errors: number;
 }
 
+/**
+ * 瞬时帧率和滑动窗口平均帧率
+ * 对应后端的 FpsSnapshot 结构
+ */
+export interface FpsSnapshot {
+  /** 最近两帧间隔计算的瞬时帧率，最近 2 秒内少于两帧时为 0 */
+  instantaneous: number;
+  /** 最近 2 秒内的平均帧率 */
+  smoothed: number;
+}
+
+/**
+ * LED数据发送帧率统计
+ * 对应后端的 LedFpsStats 结构
+ */
+export interface LedFpsStats {
+  /** 所有发送事件的速率：每次发送计一次，多显示器时为各显示器之和，并非画面帧率 */
+  overall: FpsSnapshot;
+  /** 按显示器ID统计的帧率 */
+  displays: Record<number, FpsSnapshot>;
+}
+
+/**
+ * 单个显示器当前的自适应采样质量
+ * 对应后端的 AdaptiveQualityState 结构
+ */
+export interface AdaptiveQualityState {
+  /** 质量等级，0 为配置的完整质量，越大采样越稀疏 */
+  level: number;
+  /** 当前实际使用的每个LED采样点数 */
+  sample_points_per_led: number;
+  /** 当前是否使用双线性采样 */
+  bilinear_sampling: boolean;
+  /** 单帧处理时间的滑动平均（毫秒） */
+  frame_time_ms: number;
+  /** 单帧处理时间预算（毫秒） */
+  budget_ms: number;
+}
+
 /**
  * LED状态统计信息
  * 对应后端的 LedStatusStats 结构
@@ -50,6 +90,10 @@ export interface LedStatusData {
   last_updated: string;
   /** 数据发送统计 */
   send_stats: LedSendStats;
+  /** 发送帧率统计 */
+  fps?: LedFpsStats;
+  /** 按显示器ID统计的自适应采样质量 */
+  adaptive_quality?: Record<number, AdaptiveQualityState>;
 }
 
 /**
@@ -93,7 +137,8 @@ export const MODE_DISPLAY_NAMES: Record<DataSendMode, string> = {
   'AmbientLight': 'Ambient Light',
   'StripConfig': 'Configuration',
   'TestEffect': 'Test Mode',
-  'ColorCalibration': 'Color Calibration'
+  'ColorCalibration': 'Color Calibration',
+  'ManualColor': 'Manual Color'
 };
 
 /**
@@ -112,7 +157,8 @@ export const MODE_BADGE_STYLES: Record<DataSendMode, string> = {
   'AmbientLight': 'badge-success', // 绿色 - 氛围光正常运行
   'StripConfig': 'badge-info',     // 蓝色 - 配置模式
   'TestEffect': 'badge-warning',   // 黄色 - 测试模式
-  'ColorCalibration': 'badge-secondary' // 紫色 - 颜色校准
+  'ColorCalibration': 'badge-secondary', // 紫色 - 颜色校准
+  'ManualColor': 'badge-accent'     // 强调色 - 手动纯色
 };
 
 /**
@@ -131,7 +177,8 @@ export const MODE_ICONS: Record<DataSendMode, string> = {
   'AmbientLight': '💡',   // 灯泡 - 氛围光
   'StripConfig': '⚙️',    // 齿轮 - 配置模式
   'TestEffect': '🧪',     // 试管 - 测试模式
-  'ColorCalibration': '🎨' // 调色板 - 颜色校准
+  'ColorCalibration': '🎨', // 调色板 - 颜色校准
+  'ManualColor': '🖌️'      // 画笔 - 手动纯色
 };
 
 /**
@@ -4,6 +4,7 @@
  */
 
 import { DataSendMode } from './led-status';
+import { BoardSelfCheckReport } from '../models/board-info.model';
 
 /**
  * LED颜色变化事件
@@ -40,6 +41,26 @@ export interface LedStripColorsChangedEvent {
   mode: DataSendMode;
 }
 
+/**
+ * LED灯带低分辨率预览颜色变化事件（订阅 `LedStripPreviewColorsChanged:max_<N>`）
+ */
+export interface LedStripPreviewColorsChangedEvent {
+  /** 显示器ID */
+  display_id: number;
+  /** 边框位置 ("Top", "Bottom", "Left", "Right") */
+  border: string;
+  /** 灯带索引 */
+  strip_index: number;
+  /** 灯带实际的LED数量 */
+  led_count: number;
+  /** 订阅的分辨率上限 */
+  max_colors: number;
+  /** 降采样后的颜色数据（RGB字节数组），每个颜色为连续一段LED的平均值 */
+  colors: number[];
+  /** 数据发送模式 */
+  mode: DataSendMode;
+}
+
 /**
  * LED状态变化事件
  */
@@ -61,6 +82,11 @@ export interface BoardsChangedEvent {
   boards: any;
 }
 
+/**
+ * 设备板启动自检完成事件
+ */
+export type BoardSelfCheckCompletedEvent = BoardSelfCheckReport;
+
 /**
  * 显示器状态变化事件
  */
@@ -75,6 +101,18 @@ export interface AmbientLightStateChangedEvent {
   state: any;
 }
 
+/**
+ * 情景模式激活事件（手动激活时 schedule_id 为 null）
+ */
+export interface ProfileActivatedEvent {
+  profile: string;
+  schedule_id: number | null;
+  settings: {
+    color_calibration: any;
+    ambient_light_enabled: boolean;
+  };
+}
+
 /**
  * LED预览状态变化事件
  */
@@ -82,6 +120,14 @@ export interface LedPreviewStateChangedEvent {
   state: any;
 }
 
+/**
+ * LED数据发送模式变化事件
+ */
+export interface DataSendModeChangedEvent {
+  previous_mode: string;
+  mode: string;
+}
+
 /**
  * 导航事件
  */
@@ -110,6 +156,27 @@ export interface SubscriptionConfirmedEvent {
   event_types: string[];
 }
 
+/**
+ * 客户端命令（与后端 WsCommand 保持一致）
+ */
+export type WebSocketCommand =
+  | { command: 'SetAmbientLightEnabled'; enabled: boolean }
+  | { command: 'SetDataSendMode'; mode: DataSendMode }
+  | { command: 'SetManualColor'; r: number; g: number; b: number }
+  | { command: 'ClearManualColor' }
+  | { command: 'SetDisplayBrightness'; display_index: number; brightness: number };
+
+/**
+ * 命令执行结果
+ */
+export interface CommandAckEvent {
+  /** 对应命令的请求ID */
+  id: string | null;
+  success: boolean;
+  /** 失败原因 */
+  error: string | null;
+}
+
 /**
  * WebSocket消息联合类型
  */
@@ -119,12 +186,17 @@ export type WebSocketMessage =
   | { type: 'LedStatusChanged'; data: LedStatusChangedEvent }
   | { type: 'ConfigChanged'; data: ConfigChangedEvent }
   | { type: 'BoardsChanged'; data: BoardsChangedEvent }
+  | { type: 'BoardSelfCheckCompleted'; data: BoardSelfCheckCompletedEvent }
   | { type: 'DisplaysChanged'; data: DisplaysChangedEvent }
   | { type: 'AmbientLightStateChanged'; data: AmbientLightStateChangedEvent }
+  | { type: 'ProfileActivated'; data: ProfileActivatedEvent }
   | { type: 'LedPreviewStateChanged'; data: LedPreviewStateChangedEvent }
+  | { type: 'DataSendModeChanged'; data: DataSendModeChangedEvent }
   | { type: 'Navigate'; data: NavigateEvent }
   | { type: 'Subscribe'; data: SubscribeEvent }
   | { type: 'Unsubscribe'; data: UnsubscribeEvent }
   | { type: 'SubscriptionConfirmed'; data: SubscriptionConfirmedEvent }
+  | { type: 'Command'; id?: string; data: WebSocketCommand }
+  | { type: 'CommandAck'; data: CommandAckEvent }
   | { type: 'Ping' }
   | { type: 'Pong' };